@@ -0,0 +1,156 @@
+//! Blocking facade for simple read and convert use cases.
+//!
+//! Everything in VersaTiles is async. Applications that are otherwise fully synchronous
+//! can use this module instead of setting up a tokio runtime themselves: each call
+//! blocks on a lazily created, process-wide current-thread tokio runtime.
+//!
+//! These functions must not be called from within an async context (e.g. inside a
+//! `#[tokio::main]` function). In that case they return an error instead of panicking;
+//! use the async API (e.g. [`TilesRuntime::get_reader_from_str`]) there.
+//!
+//! ```no_run
+//! use versatiles::{blocking, core::TileCoord};
+//!
+//! let reader = blocking::open_reader("../testdata/berlin.mbtiles").unwrap();
+//! let blob = reader.get_tile_data(&TileCoord::new(14, 8800, 5374).unwrap()).unwrap();
+//! println!("tile size: {:?}", blob.map(|b| b.len()));
+//! ```
+
+use crate::runtime::create_runtime;
+use anyhow::{Result, bail};
+use std::{future::Future, path::Path, sync::Arc, sync::OnceLock};
+use tokio::runtime::{Builder, Handle, Runtime};
+use versatiles_container::{
+	TileSource, TileSourceMetadata, TilesConverterParameters, TilesRuntime, convert_tiles_container,
+};
+use versatiles_core::{Blob, TileCoord, TileJSON};
+use versatiles_derive::context;
+
+/// Runs `future` to completion on the shared current-thread runtime.
+///
+/// Fails if called from within a tokio runtime, since blocking there would panic or deadlock.
+fn block_on<F: Future>(future: F) -> Result<F::Output> {
+	static EXECUTOR: OnceLock<Runtime> = OnceLock::new();
+
+	if Handle::try_current().is_ok() {
+		bail!(
+			"the blocking API cannot be used from within an async (tokio) runtime; use the async API instead, or call it from a separate thread (e.g. `std::thread::spawn`)"
+		);
+	}
+
+	let executor = match EXECUTOR.get() {
+		Some(executor) => executor,
+		None => {
+			let executor = Builder::new_current_thread().enable_all().build()?;
+			EXECUTOR.get_or_init(|| executor)
+		}
+	};
+	Ok(executor.block_on(future))
+}
+
+/// A tile source that can be read without an async runtime.
+#[derive(Clone, Debug)]
+pub struct BlockingReader {
+	reader: Arc<Box<dyn TileSource>>,
+}
+
+impl BlockingReader {
+	/// Fetches the encoded tile at `coord`, in the compression of the source.
+	///
+	/// Returns `Ok(None)` if the tile does not exist.
+	#[context("getting tile data: coord={coord:?}")]
+	pub fn get_tile_data(&self, coord: &TileCoord) -> Result<Option<Blob>> {
+		block_on(async {
+			let compression = self.reader.metadata().tile_compression;
+			self
+				.reader
+				.get_tile(coord)
+				.await?
+				.map(|tile| tile.into_blob(compression))
+				.transpose()
+		})?
+	}
+
+	/// Returns the TileJSON metadata of the source.
+	pub fn tilejson(&self) -> &TileJSON {
+		self.reader.tilejson()
+	}
+
+	/// Returns format, compression and bbox pyramid of the source.
+	pub fn metadata(&self) -> &TileSourceMetadata {
+		self.reader.metadata()
+	}
+
+	/// Returns the underlying async tile source.
+	pub fn into_inner(self) -> Arc<Box<dyn TileSource>> {
+		self.reader
+	}
+}
+
+/// Opens a tile source (path, URL, or data source expression) for blocking reads.
+#[context("opening blocking reader: '{filename}'")]
+pub fn open_reader(filename: &str) -> Result<BlockingReader> {
+	let runtime = create_runtime();
+	let reader = block_on(runtime.get_reader_from_str(filename))??;
+	Ok(BlockingReader { reader })
+}
+
+/// Converts the tile source `filename` into the container at `path_out`.
+///
+/// The output format is inferred from the extension of `path_out`.
+#[context("converting '{filename}' to {path_out:?}")]
+pub fn convert(filename: &str, path_out: &Path, parameters: TilesConverterParameters) -> Result<()> {
+	let runtime: TilesRuntime = create_runtime();
+	block_on(async {
+		let reader = runtime.get_reader_from_str(filename).await?;
+		convert_tiles_container(reader, parameters, path_out, runtime.clone()).await
+	})?
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use versatiles_core::{TileBBoxPyramid, TileCompression, TileFormat};
+
+	const FILENAME: &str = "../testdata/berlin.mbtiles";
+
+	#[test]
+	fn read_tiles_without_runtime() -> Result<()> {
+		let reader = open_reader(FILENAME)?;
+
+		let metadata = reader.metadata();
+		assert_eq!(metadata.tile_format, TileFormat::MVT);
+		assert_eq!(metadata.tile_compression, TileCompression::Gzip);
+		assert_eq!(reader.tilejson().as_object().get_number("maxzoom")?, Some(14.0));
+
+		let blob = reader.get_tile_data(&TileCoord::new(14, 8800, 5374)?)?.unwrap();
+		assert_eq!(&blob.as_slice()[0..2], &[31, 139]);
+
+		assert!(reader.get_tile_data(&TileCoord::new(14, 0, 0)?)?.is_none());
+		Ok(())
+	}
+
+	#[test]
+	fn convert_without_runtime() -> Result<()> {
+		let temp_dir = assert_fs::TempDir::new()?;
+		let path_out = temp_dir.path().join("berlin.versatiles");
+
+		let parameters = TilesConverterParameters {
+			bbox_pyramid: Some(TileBBoxPyramid::new_full(4)),
+			..Default::default()
+		};
+		convert(FILENAME, &path_out, parameters)?;
+
+		let reader = open_reader(path_out.to_str().unwrap())?;
+		assert_eq!(reader.metadata().bbox_pyramid.get_level_max(), Some(4));
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn friendly_error_inside_runtime() {
+		let error = open_reader(FILENAME).unwrap_err();
+		let messages = error.chain().map(|e| e.to_string()).collect::<Vec<_>>();
+		assert_eq!(messages[0], "opening blocking reader: '../testdata/berlin.mbtiles'");
+		assert!(messages[1].starts_with("the blocking API cannot be used from within an async (tokio) runtime"));
+	}
+}
@@ -13,6 +13,10 @@
 //!   port: 8080
 //!   minimal_recompression: false   # optional
 //!   disable_api: false             # optional
+//!   enable_metrics: false          # optional, serves /metrics
+//!   enable_preview: false          # optional, serves /tiles/{id}/preview
+//!   tls_cert: ./cert.pem           # optional, enables HTTPS + HTTP/2
+//!   tls_key: ./key.pem             # optional
 //!
 //! # Optional Cross-Origin Resource Sharing (CORS) settings
 //! cors:
@@ -35,6 +39,10 @@
 //! tiles:
 //!   - ["osm", "osm.versatiles"]
 //!   - ["berlin", "berlin.mbtiles"]
+//!   - name: berlin-hd
+//!     src: berlin-hd.versatiles
+//!     compression: br              # optional, serve tiles in this compression
+//!     fallback: ["berlin"]         # optional, sources asked for missing tiles
 //! ```
 //!
 //! ## Basic usage
@@ -136,12 +144,14 @@ impl Config {
 		Ok(cfg)
 	}
 
-	/// Resolve all relative paths in `static_sources` and `tile_sources` against `base`.
+	/// Resolve all relative paths in `server`, `static_sources` and `tile_sources` against `base`.
 	///
 	/// `base` should be the directory containing the YAML file (or an equivalent URL base).
 	/// Paths are left unchanged if they are already absolute; URLs are left unchanged.
 	#[context("resolving relative paths for {} static + {} tile sources", self.static_sources.len(), self.tile_sources.len())]
 	pub fn resolve_paths(&mut self, base: &DataLocation) -> Result<()> {
+		self.server.resolve_paths(base)?;
+
 		for static_source in &mut self.static_sources {
 			static_source.resolve_paths(base)?;
 		}
@@ -185,7 +195,13 @@ mod tests {
 					ip: Some("127.0.0.1".parse().unwrap()),
 					port: Some(51234),
 					minimal_recompression: Some(true),
-					disable_api: Some(true)
+					compression_passthrough: None,
+					missing_tiles_no_content: None,
+					disable_api: Some(true),
+					enable_metrics: None,
+					enable_preview: None,
+					tls_cert: None,
+					tls_key: None,
 				},
 				cors: CorsConfig {
 					allowed_origins: vec!["https://example.org".to_string(), "*.other-example.org".to_string()],
@@ -226,7 +242,7 @@ mod tests {
 			cfg.unwrap_err().chain().map(|e| e.to_string()).collect::<Vec<_>>(),
 			vec![
 				"parsing config from string (YAML)",
-				"server: unknown field `pi`, expected one of `ip`, `port`, `minimal_recompression`, `disable_api` at line 2 column 3"
+				"server: unknown field `pi`, expected one of `ip`, `port`, `minimal_recompression`, `compression_passthrough`, `missing_tiles_no_content`, `disable_api`, `enable_metrics`, `enable_preview`, `tls_cert`, `tls_key` at line 2 column 3"
 			]
 		);
 	}
@@ -242,7 +258,13 @@ mod tests {
 					ip: Some("0.0.0.0".to_string()),
 					port: Some(8080,),
 					minimal_recompression: Some(false,),
+					compression_passthrough: Some(false,),
+					missing_tiles_no_content: Some(false,),
 					disable_api: Some(false,),
+					enable_metrics: Some(false,),
+					enable_preview: Some(false,),
+					tls_cert: Some("./cert.pem".into()),
+					tls_key: Some("./key.pem".into()),
 				},
 				cors: CorsConfig {
 					allowed_origins: vec!["https://example.org".to_string(), "*.example.net".to_string()],
@@ -255,8 +277,18 @@ mod tests {
 				.iter()
 				.map(|(a, b)| (a.to_string(), b.to_string()))
 				.collect::<HashMap<String, String>>(),
-				static_sources: vec![StaticSourceConfig::from(("/", "./frontend.tar")),],
-				tile_sources: vec![TileSourceConfig::from(("osm", "osm.versatiles")),],
+				static_sources: vec![StaticSourceConfig {
+					directory_listing: Some(false),
+					follow_symlinks: Some(true),
+					serve_dotfiles: Some(false),
+					cache_responses: Some(false),
+					cache_ttl_seconds: Some(300),
+					..StaticSourceConfig::from(("/", "./frontend.tar"))
+				}],
+				tile_sources: vec![TileSourceConfig {
+					compression: Some(versatiles_core::TileCompression::Brotli),
+					..TileSourceConfig::from(("osm", "osm.versatiles"))
+				}],
 			}
 		)
 	}
@@ -10,14 +10,22 @@
 //!   ip: 0.0.0.0
 //!   port: 8080
 //!   minimal_recompression: false
+//!   compression_passthrough: false
+//!   missing_tiles_no_content: false
 //!   disable_api: false
+//!   enable_metrics: false
+//!   enable_preview: false
+//!   tls_cert: ./cert.pem
+//!   tls_key: ./key.pem
 //! ```
 //!
 //! All fields are optional. Defaults are applied when values are not specified.
 
+use anyhow::Result;
 use serde::Deserialize;
-use std::str;
-use versatiles_derive::ConfigDoc;
+use std::{path::PathBuf, str};
+use versatiles_container::DataLocation;
+use versatiles_derive::{ConfigDoc, context};
 
 /// Configuration for the VersaTiles HTTP server.
 ///
@@ -29,7 +37,12 @@ use versatiles_derive::ConfigDoc;
 /// * `ip` — Optional IP address to bind to (default `"0.0.0.0"`).
 /// * `port` — Optional port to listen on (default `8080`).
 /// * `minimal_recompression` — If `true`, prefer faster compression over smaller output.
+/// * `compression_passthrough` — If `true`, serve tiles in their stored compression without negotiation.
+/// * `missing_tiles_no_content` — If `true`, answer requests for missing tiles with `204 No Content`.
 /// * `disable_api` — If `true`, disable the `/api` endpoints entirely.
+/// * `enable_metrics` — If `true`, serve Prometheus metrics on `/metrics`.
+/// * `enable_preview` — If `true`, serve a MapLibre preview page on `/tiles/{id}/preview`.
+/// * `tls_cert` / `tls_key` — Optional PEM files; when both are set, the server uses HTTPS and HTTP/2.
 #[derive(Debug, Default, Clone, Deserialize, PartialEq, ConfigDoc)]
 #[serde(deny_unknown_fields)]
 pub struct ServerConfig {
@@ -51,11 +64,50 @@ pub struct ServerConfig {
 	#[config_demo("false")]
 	pub minimal_recompression: Option<bool>,
 
+	/// Optional flag to serve tiles exactly as stored, ignoring `Accept-Encoding`
+	/// Clients that don't accept the stored compression must decompress the tiles themselves
+	/// Defaults to false (tiles are recompressed if the client needs it)
+	#[serde()]
+	#[config_demo("false")]
+	pub compression_passthrough: Option<bool>,
+
+	/// Optional flag to answer requests for missing tiles with `204 No Content` instead of `404 Not Found`
+	/// MapLibre treats 204 as a legitimately empty tile, e.g. for elided empty tiles
+	/// Defaults to false (404)
+	#[serde()]
+	#[config_demo("false")]
+	pub missing_tiles_no_content: Option<bool>,
+
 	/// Optional flag to disable the `/api` endpoints
 	/// Defaults to false (enabling the API)
 	#[serde()]
 	#[config_demo("false")]
 	pub disable_api: Option<bool>,
+
+	/// Optional flag to serve Prometheus metrics on `/metrics`
+	/// The endpoint has no authentication, so only enable it if `/metrics` is not publicly reachable
+	/// Defaults to false (no metrics endpoint)
+	#[serde()]
+	#[config_demo("false")]
+	pub enable_metrics: Option<bool>,
+
+	/// Optional flag to serve a MapLibre map of every tile source on `/tiles/{id}/preview`
+	/// The page loads MapLibre GL JS from unpkg.com, so the browser needs internet access
+	/// Defaults to false (no preview pages)
+	#[serde()]
+	#[config_demo("false")]
+	pub enable_preview: Option<bool>,
+
+	/// Optional path to a PEM certificate chain, enables HTTPS together with `tls_key`
+	/// HTTP/2 is negotiated via ALPN
+	#[serde()]
+	#[config_demo("./cert.pem")]
+	pub tls_cert: Option<PathBuf>,
+
+	/// Optional path to the PEM private key belonging to `tls_cert`
+	#[serde()]
+	#[config_demo("./key.pem")]
+	pub tls_key: Option<PathBuf>,
 }
 
 /// Helper methods for merging partial `ServerConfig` values.
@@ -78,9 +130,50 @@ impl ServerConfig {
 			self.minimal_recompression = *minimal_recompression;
 		}
 	}
+	pub fn override_optional_compression_passthrough(&mut self, compression_passthrough: &Option<bool>) {
+		if compression_passthrough.is_some() {
+			self.compression_passthrough = *compression_passthrough;
+		}
+	}
+	pub fn override_optional_missing_tiles_no_content(&mut self, missing_tiles_no_content: &Option<bool>) {
+		if missing_tiles_no_content.is_some() {
+			self.missing_tiles_no_content = *missing_tiles_no_content;
+		}
+	}
 	pub fn override_optional_disable_api(&mut self, disable_api: &Option<bool>) {
 		if disable_api.is_some() {
 			self.disable_api = *disable_api;
 		}
 	}
+	pub fn override_optional_enable_metrics(&mut self, enable_metrics: &Option<bool>) {
+		if enable_metrics.is_some() {
+			self.enable_metrics = *enable_metrics;
+		}
+	}
+	pub fn override_optional_enable_preview(&mut self, enable_preview: &Option<bool>) {
+		if enable_preview.is_some() {
+			self.enable_preview = *enable_preview;
+		}
+	}
+	pub fn override_optional_tls_cert(&mut self, tls_cert: &Option<PathBuf>) {
+		if tls_cert.is_some() {
+			self.tls_cert = tls_cert.clone();
+		}
+	}
+	pub fn override_optional_tls_key(&mut self, tls_key: &Option<PathBuf>) {
+		if tls_key.is_some() {
+			self.tls_key = tls_key.clone();
+		}
+	}
+
+	/// Resolve relative `tls_cert` and `tls_key` paths against `base`.
+	#[context("resolving server paths relative to base path '{}'", base)]
+	pub fn resolve_paths(&mut self, base: &DataLocation) -> Result<()> {
+		for path in [&mut self.tls_cert, &mut self.tls_key].into_iter().flatten() {
+			if path.is_relative() {
+				*path = base.as_path()?.join(&*path);
+			}
+		}
+		Ok(())
+	}
 }
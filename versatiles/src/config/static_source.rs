@@ -28,8 +28,11 @@ use versatiles_derive::{ConfigDoc, context};
 /// This is used by the `StaticSources` subsystem to register handlers for
 /// static file serving.
 ///
-/// - `src` — Path to a directory or archive (`.tar`, `.tar.gz`, `.tar.zst`).
+/// - `src` — Path to a directory or archive (`.tar`, `.tar.gz`, `.tar.zst`), or the URL of an upstream HTTP server.
 /// - `prefix` — Optional base URL prefix (defaults to `/` if `None`).
+/// - `directory_listing` — Optional flag to list directories without an `index.html`.
+/// - `follow_symlinks`, `serve_dotfiles` — Optional flags controlling which files of a directory are served.
+/// - `cache_responses`, `cache_ttl_seconds` — Optional in-memory caching of upstream responses.
 ///
 /// Relative paths are resolved against the base path of the configuration file
 /// by [`StaticSourceConfig::resolve_paths`].
@@ -37,12 +40,38 @@ use versatiles_derive::{ConfigDoc, context};
 pub struct StaticSourceConfig {
 	#[config_demo("./frontend.tar")]
 	/// Path to static files, archive (e.g., .tar.gz) or directory containing assets
+	/// An `http://` or `https://` URL proxies the assets from that upstream server
 	pub src: DataLocation,
 
 	#[config_demo("/")]
 	/// Optional URL prefix where static files will be served
 	/// Defaults to root ("/")
 	pub prefix: Option<String>,
+
+	#[config_demo("false")]
+	/// Optional flag to serve an HTML directory listing for folders without an `index.html`
+	/// Only applies to directories, defaults to false
+	pub directory_listing: Option<bool>,
+
+	#[config_demo("true")]
+	/// Optional flag to follow symbolic links inside a directory
+	/// Their targets must still be inside the directory, defaults to true
+	pub follow_symlinks: Option<bool>,
+
+	#[config_demo("false")]
+	/// Optional flag to serve files and directories starting with `.`, like `.git` or `.env`
+	/// Only applies to directories, defaults to false
+	pub serve_dotfiles: Option<bool>,
+
+	#[config_demo("false")]
+	/// Optional flag to keep responses of an upstream HTTP server in memory
+	/// Only applies to URLs, defaults to false
+	pub cache_responses: Option<bool>,
+
+	#[config_demo("300")]
+	/// Optional time in seconds to keep cached upstream responses
+	/// Defaults to 300
+	pub cache_ttl_seconds: Option<u64>,
 }
 
 impl StaticSourceConfig {
@@ -78,12 +107,22 @@ impl<'de> Deserialize<'de> for StaticSourceConfig {
 		struct StaticSourceConfigHelper {
 			pub src: String,
 			pub prefix: Option<String>,
+			pub directory_listing: Option<bool>,
+			pub follow_symlinks: Option<bool>,
+			pub serve_dotfiles: Option<bool>,
+			pub cache_responses: Option<bool>,
+			pub cache_ttl_seconds: Option<u64>,
 		}
 
 		let helper = StaticSourceConfigHelper::deserialize(deserializer)?;
 		Ok(StaticSourceConfig {
 			src: DataLocation::from(helper.src),
 			prefix: helper.prefix,
+			directory_listing: helper.directory_listing,
+			follow_symlinks: helper.follow_symlinks,
+			serve_dotfiles: helper.serve_dotfiles,
+			cache_responses: helper.cache_responses,
+			cache_ttl_seconds: helper.cache_ttl_seconds,
 		})
 	}
 }
@@ -94,6 +133,11 @@ impl From<(&str, &str)> for StaticSourceConfig {
 		Self {
 			src: DataLocation::try_from(src).unwrap(),
 			prefix: Some(prefix.to_string()),
+			directory_listing: None,
+			follow_symlinks: None,
+			serve_dotfiles: None,
+			cache_responses: None,
+			cache_ttl_seconds: None,
 		}
 	}
 }
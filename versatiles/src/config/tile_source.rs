@@ -9,6 +9,10 @@
 //! tiles:
 //!   - ["osm", "osm.versatiles"]
 //!   - ["berlin", "https://example.org/tileset.mbtiles"]
+//!   - name: "berlin-hd"
+//!     src: "berlin-hd.versatiles"
+//!     compression: br
+//!     fallback: ["berlin"]
 //! ```
 //!
 //! The server will make these tiles available under:
@@ -18,6 +22,7 @@ use anyhow::Result;
 use serde::Deserialize;
 use std::fmt::Debug;
 use versatiles_container::{DataLocation, DataSource};
+use versatiles_core::TileCompression;
 use versatiles_derive::{ConfigDoc, context};
 
 /// Configuration entry for a single tile data source.
@@ -39,6 +44,16 @@ pub struct TileSourceConfig {
 	/// Can be a local file or remote URL.
 	#[config_demo("osm.versatiles")]
 	pub src: DataSource,
+
+	/// Optional compression to serve the tiles in (`br`, `gzip` or `none`)
+	/// Defaults to the compression the tiles are stored in
+	#[config_demo("br")]
+	pub compression: Option<TileCompression>,
+
+	/// Optional list of names of other tile sources
+	/// Tiles missing in this source are looked up in these sources, in the given order
+	#[config_demo("[]")]
+	pub fallback: Vec<String>,
 }
 
 impl TileSourceConfig {
@@ -74,12 +89,22 @@ impl<'de> Deserialize<'de> for TileSourceConfig {
 		struct TileSourceConfigHelper {
 			pub name: Option<String>,
 			pub src: String,
+			#[serde(default)]
+			pub compression: Option<String>,
+			#[serde(default)]
+			pub fallback: Vec<String>,
 		}
 
 		let helper = TileSourceConfigHelper::deserialize(deserializer)?;
 		Ok(TileSourceConfig {
 			name: helper.name,
 			src: DataSource::parse(&helper.src).map_err(|e| serde::de::Error::custom(e.to_string()))?,
+			compression: helper
+				.compression
+				.map(|c| TileCompression::try_from(c.as_str()))
+				.transpose()
+				.map_err(|e| serde::de::Error::custom(e.to_string()))?,
+			fallback: helper.fallback,
 		})
 	}
 }
@@ -90,6 +115,8 @@ impl From<(&str, &str)> for TileSourceConfig {
 		Self {
 			name: Some(name.to_string()),
 			src: DataSource::try_from(src).unwrap(),
+			compression: None,
+			fallback: Vec::new(),
 		}
 	}
 }
@@ -37,6 +37,8 @@
 //! }
 //! ```
 
+#[cfg(feature = "blocking")]
+pub mod blocking;
 pub mod config;
 pub mod runtime;
 #[cfg(feature = "server")]
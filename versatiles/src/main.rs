@@ -86,11 +86,17 @@ enum Commands {
 	/// Show information about a tile container
 	Probe(tools::probe::Subcommand),
 
+	/// Export a bitmap of the existing tiles of a zoom level
+	Coverage(tools::coverage::Subcommand),
+
 	#[cfg(feature = "server")]
 	#[clap(alias = "server")]
 	/// Serve tiles via HTTP
 	Serve(tools::serve::Subcommand),
 
+	/// List all pipeline operations
+	Operations(tools::operations::Subcommand),
+
 	/// Show detailed help
 	Help(tools::help::Subcommand),
 
@@ -138,7 +144,9 @@ fn run(cli: Cli, runtime: TilesRuntime) -> Result<()> {
 	match &cli.command {
 		Commands::Convert(arguments) => tools::convert::run(arguments, runtime),
 		Commands::Help(arguments) => tools::help::run(arguments),
+		Commands::Operations(arguments) => tools::operations::run(arguments),
 		Commands::Probe(arguments) => tools::probe::run(arguments, runtime),
+		Commands::Coverage(arguments) => tools::coverage::run(arguments, runtime),
 		#[cfg(feature = "server")]
 		Commands::Serve(arguments) => tools::serve::run(arguments, runtime),
 		Commands::Dev(arguments) => tools::dev::run(arguments, runtime),
@@ -195,6 +203,16 @@ mod tests {
 		);
 	}
 
+	/// Test for subcommand 'coverage'
+	#[test]
+	fn coverage_subcommand() {
+		let output = run_command(vec!["versatiles", "coverage"]).unwrap_err().to_string();
+		assert!(
+			output.starts_with("Export a bitmap of the existing tiles of a zoom level"),
+			"{output}"
+		);
+	}
+
 	/// Test for subcommand 'serve'
 	#[test]
 	fn serve_subcommand() {
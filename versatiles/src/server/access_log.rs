@@ -0,0 +1,148 @@
+//! Access log for the tile server.
+//!
+//! [`log_request`] is an Axum middleware that wraps every request and writes one line per
+//! response: method, path, the source that answered it, status, body size, elapsed time,
+//! the negotiated `Content-Encoding` and, for tiles, whether the tile came from a cache.
+//!
+//! Handlers attach an [`AccessLogInfo`] to their responses to tell the middleware which
+//! source answered. Failed requests (4xx/5xx) are logged at `info`, all others at `debug`,
+//! so the log level (e.g. `versatiles -v serve ...`) decides what is written.
+
+use axum::{
+	body::HttpBody,
+	extract::Request,
+	http::{Method, header},
+	middleware::Next,
+	response::Response,
+};
+use std::{
+	fmt,
+	time::{Duration, Instant},
+};
+
+/// Describes which source answered a request. Handlers insert it into the response extensions.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AccessLogInfo {
+	/// Id of the tile source, or type and prefix of the static source.
+	pub source: String,
+	/// Whether a tile was served from a cache. `None` for responses that are not tiles.
+	pub cache_hit: Option<bool>,
+}
+
+/// Middleware that logs every request, see the module docs.
+pub async fn log_request(request: Request, next: Next) -> Response {
+	// Skip the bookkeeping entirely if nothing would be written.
+	if !log::log_enabled!(log::Level::Info) {
+		return next.run(request).await;
+	}
+
+	let method = request.method().clone();
+	let path = request.uri().path().to_string();
+	let start = Instant::now();
+
+	let response = next.run(request).await;
+
+	let entry = AccessLogEntry::new(method, path, &response, start.elapsed());
+	if entry.status >= 400 {
+		log::info!("{entry}");
+	} else {
+		log::debug!("{entry}");
+	}
+	response
+}
+
+/// A single line of the access log.
+struct AccessLogEntry {
+	method: Method,
+	path: String,
+	source: String,
+	status: u16,
+	bytes: Option<u64>,
+	elapsed: Duration,
+	compression: String,
+	cache_hit: Option<bool>,
+}
+
+impl AccessLogEntry {
+	fn new(method: Method, path: String, response: &Response, elapsed: Duration) -> Self {
+		let info = response.extensions().get::<AccessLogInfo>();
+		AccessLogEntry {
+			method,
+			path,
+			source: info.map_or_else(|| String::from("-"), |info| info.source.clone()),
+			status: response.status().as_u16(),
+			bytes: response.body().size_hint().exact(),
+			elapsed,
+			compression: response
+				.headers()
+				.get(header::CONTENT_ENCODING)
+				.and_then(|value| value.to_str().ok())
+				.unwrap_or("identity")
+				.to_string(),
+			cache_hit: info.and_then(|info| info.cache_hit),
+		}
+	}
+}
+
+impl fmt::Display for AccessLogEntry {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(
+			f,
+			"{} {} source={} status={} bytes={} time={:.1}ms compression={}",
+			self.method,
+			self.path,
+			self.source,
+			self.status,
+			self.bytes.map_or_else(|| String::from("-"), |bytes| bytes.to_string()),
+			self.elapsed.as_secs_f64() * 1000.0,
+			self.compression,
+		)?;
+		match self.cache_hit {
+			Some(true) => write!(f, " cache=hit"),
+			Some(false) => write!(f, " cache=miss"),
+			None => Ok(()),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use axum::body::Body;
+
+	fn format(response: Response<Body>) -> String {
+		AccessLogEntry::new(
+			Method::GET,
+			String::from("/tiles/osm/3/2/1"),
+			&response,
+			Duration::from_micros(2500),
+		)
+		.to_string()
+	}
+
+	#[test]
+	fn tile_response() {
+		let mut response = Response::builder()
+			.status(200)
+			.header(header::CONTENT_ENCODING, "br")
+			.body(Body::from(vec![0u8; 123]))
+			.unwrap();
+		response.extensions_mut().insert(AccessLogInfo {
+			source: String::from("osm"),
+			cache_hit: Some(true),
+		});
+		assert_eq!(
+			format(response),
+			"GET /tiles/osm/3/2/1 source=osm status=200 bytes=123 time=2.5ms compression=br cache=hit"
+		);
+	}
+
+	#[test]
+	fn response_without_info() {
+		let response = Response::builder().status(404).body(Body::from("Not Found")).unwrap();
+		assert_eq!(
+			format(response),
+			"GET /tiles/osm/3/2/1 source=- status=404 bytes=9 time=2.5ms compression=identity"
+		);
+	}
+}
@@ -107,18 +107,14 @@ pub fn get_encoding(headers: &HeaderMap) -> TargetCompression {
 		if q <= 0.0 {
 			continue;
 		}
-		match name {
-			"gzip" => set.insert(Gzip),
-			"br" => set.insert(Brotli),
-			"*" => {
-				// Be conservative with wildcard: allow our common encodings.
-				set.insert(Gzip);
-				set.insert(Brotli);
-			}
-			_ => {
-				// Ignore unknown encodings.
-			}
+		if name == "*" {
+			// Be conservative with wildcard: allow our common encodings.
+			set.insert(Gzip);
+			set.insert(Brotli);
+		} else if let Some(compression) = TileCompression::from_content_encoding(name) {
+			set.insert(compression);
 		}
+		// Unknown encodings are ignored.
 	}
 
 	set
@@ -1,26 +1,33 @@
 //! HTTP handlers and small response helpers for the tile/static server.
 //!
-//! - `serve_tile` serves tiles from a single `ServerTileSource`.
-//! - `serve_static` serves files from a list of `StaticSource`s.
+//! - `serve_tile` serves tiles from a single `ServerTileSource`. With compression passthrough,
+//!   tiles are sent in their stored compression, whatever the client accepts.
+//! - `serve_static` serves files from a list of `StaticSource`s. Folders also answer single
+//!   byte-range requests, reading only the requested bytes.
 //! - `ok_json` is a tiny helper used by the API routes.
+//! - `ok_revalidated` serves generated documents with an `ETag`, so clients revalidate cheaply.
+//! - Tile and static responses carry an `AccessLogInfo`, so the access log knows which source
+//!   answered the request.
 //!
 //! Note: CORS headers are handled exclusively by the `CorsLayer`. Don’t set
 //! `Access-Control-Allow-Origin` here; that avoids header drift.
 
 use super::{
+	access_log::AccessLogInfo,
 	encoding::get_encoding,
-	sources::{ServerTileSource, SourceResponse, StaticSource},
-	utils::Url,
+	sources::{RangeResponse, ServerTileSource, SourceResponse, StaticSource},
+	utils::{HttpRange, Url},
 };
 use axum::{
 	body::Body,
 	extract::State,
-	http::{HeaderMap, Uri, header},
+	http::{HeaderMap, HeaderValue, Uri, header},
 	response::Response,
 };
+use enumset::EnumSet;
 use std::sync::Arc;
 use versatiles_core::{
-	Blob, TileCompression,
+	Blob, TileCompression, TileFormat,
 	utils::{TargetCompression, optimize_compression},
 };
 
@@ -33,16 +40,29 @@ pub struct StaticHandlerState {
 
 /// Core tile serving logic extracted for reuse in dynamic routing.
 /// Takes an Arc<ServerTileSource> to support both static and dynamic routing.
+///
+/// With `compression_passthrough`, `Accept-Encoding` is ignored: every compression counts as
+/// accepted, so the stored blob is neither decompressed nor recompressed.
+///
+/// With `missing_tiles_no_content`, requests for tiles that don't exist are answered with
+/// `204 No Content`. Other unknown paths still get a `404`.
 pub async fn serve_tile_from_source(
 	path: Url,
 	headers: HeaderMap,
 	tile_source: Arc<ServerTileSource>,
 	minimal_recompression: bool,
+	compression_passthrough: bool,
+	missing_tiles_no_content: bool,
 ) -> Response<Body> {
 	log::debug!("handle tile request: {path}");
 
-	let mut target = get_encoding(&headers);
-	if minimal_recompression {
+	let mut target = if compression_passthrough {
+		TargetCompression::from_set(EnumSet::all())
+	} else {
+		get_encoding(&headers)
+	};
+	if minimal_recompression || compression_passthrough {
+		// Fast compression keeps any accepted compression as it is.
 		target.set_fast_compression();
 	}
 
@@ -61,11 +81,17 @@ pub async fn serve_tile_from_source(
 
 	let response = tile_source.get_data(&stripped_path, &target).await;
 
-	match response {
+	let mut cache_hit = false;
+	let mut response = match response {
 		Ok(Some(result)) => {
 			log::debug!("send response for tile request: {path}");
+			cache_hit = result.cache_hit;
 			ok_data(result, target)
 		}
+		Ok(None) if missing_tiles_no_content && ServerTileSource::is_tile_url(&stripped_path) => {
+			log::debug!("send 204 for tile request: {path}");
+			no_content()
+		}
 		Ok(None) => {
 			log::debug!("send 404 for tile request: {path}");
 			error_404()
@@ -77,7 +103,13 @@ pub async fn serve_tile_from_source(
 			);
 			error_500()
 		}
-	}
+	};
+
+	response.extensions_mut().insert(AccessLogInfo {
+		source: tile_source.id.clone(),
+		cache_hit: ServerTileSource::is_tile_url(&stripped_path).then_some(cache_hit),
+	});
+	response
 }
 
 /// Static handler: tries each source in order until one returns data.
@@ -94,19 +126,47 @@ pub async fn serve_static(uri: Uri, headers: HeaderMap, State(state): State<Stat
 		target.set_fast_compression();
 	}
 
-	// Load sources (lock-free!)
-	let sources = state.sources.load();
+	// Load sources (lock-free!). Keep a full `Arc`, because sources may be awaited.
+	let sources = state.sources.load_full();
+
+	// Only single byte ranges are supported, other `Range` headers are ignored.
+	let range = headers
+		.get(header::RANGE)
+		.and_then(|value| value.to_str().ok())
+		.and_then(HttpRange::parse);
 
 	for source in sources.iter() {
-		if let Some(result) = source.get_data(&url, &target) {
+		if let Some(range) = &range
+			&& let Some(result) = source.get_range(&url, range).await
+		{
+			log::debug!("send range response to static request: {url}");
+			return with_static_source(ok_range(result), source);
+		}
+		if let Some(result) = source.get_data(&url, &target).await {
 			log::debug!("send response to static request: {url}");
-			return ok_data(result, target);
+			let accept_ranges = source.supports_ranges() && result.compression == TileCompression::Uncompressed;
+			let mut response = ok_data(result, target);
+			if accept_ranges && response.status() == 200 {
+				response
+					.headers_mut()
+					.insert(header::ACCEPT_RANGES, HeaderValue::from_static("bytes"));
+			}
+			return with_static_source(response, source);
 		}
 	}
 	log::debug!("send 404 to static request: {url}");
 	error_404()
 }
 
+/// Tells the access log which static source answered the request.
+fn with_static_source(mut response: Response<Body>, source: &StaticSource) -> Response<Body> {
+	response.extensions_mut().insert(AccessLogInfo {
+		source: format!("{}:{}", source.get_type(), source.get_prefix()),
+		cache_hit: None,
+	});
+	response
+}
+
 // --- small helpers -----------------------------------------------------------
 
 fn format_error_chain(err: &anyhow::Error) -> String {
@@ -132,6 +192,13 @@ fn error_with(status: u16, message: &str) -> Response<Body> {
 		.expect("failed to build error response")
 }
 
+fn no_content() -> Response<Body> {
+	Response::builder()
+		.status(204)
+		.body(Body::empty())
+		.expect("failed to build empty response")
+}
+
 pub fn error_404() -> Response<Body> {
 	error_with(404, "Not Found")
 }
@@ -142,10 +209,7 @@ pub fn error_500() -> Response<Body> {
 
 fn ok_data(result: SourceResponse, mut target: TargetCompression) -> Response<Body> {
 	// Binary images are effectively incompressible; avoid recompression.
-	if matches!(
-		result.mime.as_str(),
-		"image/png" | "image/jpeg" | "image/webp" | "image/avif"
-	) {
+	if TileFormat::try_from_mime(&result.mime).is_ok_and(|format| format.is_raster()) {
 		target.set_incompressible();
 	}
 
@@ -175,11 +239,8 @@ fn ok_data(result: SourceResponse, mut target: TargetCompression) -> Response<Bo
 		}
 	};
 
-	use TileCompression::*;
-	match compression {
-		Uncompressed => {}
-		Gzip => response = response.header(header::CONTENT_ENCODING, "gzip"),
-		Brotli => response = response.header(header::CONTENT_ENCODING, "br"),
+	if let Some(encoding) = compression.as_content_encoding() {
+		response = response.header(header::CONTENT_ENCODING, encoding);
 	}
 
 	log::trace!("send response with headers: {:?}", response.headers_ref());
@@ -189,6 +250,36 @@ fn ok_data(result: SourceResponse, mut target: TargetCompression) -> Response<Bo
 		.expect("failed to build OK response")
 }
 
+/// Answers a byte-range request with `206 Partial Content` or `416 Range Not Satisfiable`.
+///
+/// The bytes are sent as they are stored, so there is no compression negotiation.
+fn ok_range(result: RangeResponse) -> Response<Body> {
+	match result {
+		RangeResponse::Partial {
+			blob,
+			start,
+			total,
+			mime,
+		} => {
+			let end = start + blob.len() - 1;
+			Response::builder()
+				.status(206)
+				.header(header::CONTENT_TYPE, mime)
+				.header(header::CACHE_CONTROL, "public, max-age=2419200, no-transform")
+				.header(header::ACCEPT_RANGES, "bytes")
+				.header(header::CONTENT_RANGE, format!("bytes {start}-{end}/{total}"))
+				.body(Body::from(blob.into_vec()))
+				.expect("failed to build partial content response")
+		}
+		RangeResponse::Unsatisfiable { total } => Response::builder()
+			.status(416)
+			.header(header::CONTENT_TYPE, "text/plain; charset=utf-8")
+			.header(header::CONTENT_RANGE, format!("bytes */{total}"))
+			.body(Body::from("Range Not Satisfiable"))
+			.expect("failed to build range not satisfiable response"),
+	}
+}
+
 /// Tiny JSON helper used by API routes.
 pub fn ok_json(message: &str) -> Response<Body> {
 	ok_data(
@@ -196,16 +287,59 @@ pub fn ok_json(message: &str) -> Response<Body> {
 			blob: Blob::from(message),
 			compression: TileCompression::Uncompressed,
 			mime: String::from("application/json"),
+			cache_hit: false,
 		},
 		TargetCompression::from_none(),
 	)
 }
 
+/// Helper for documents that are generated from the current tile sources.
+///
+/// The response carries an `ETag` derived from the body and must be revalidated after a
+/// minute, so clients notice added or removed sources. Answers `304` if the client already
+/// has the current version.
+pub fn ok_revalidated(body: String, mime: &str, headers: &HeaderMap) -> Response<Body> {
+	let blob = Blob::from(body);
+	let etag = format!("\"{:016x}\"", blob.xxhash());
+
+	let response = Response::builder()
+		.header(header::ETAG, &etag)
+		.header(header::CACHE_CONTROL, "public, max-age=60, must-revalidate");
+
+	let is_fresh = headers
+		.get(header::IF_NONE_MATCH)
+		.and_then(|value| value.to_str().ok())
+		.is_some_and(|value| value.split(',').any(|tag| tag.trim() == etag || tag.trim() == "*"));
+	if is_fresh {
+		return response
+			.status(304)
+			.body(Body::empty())
+			.expect("failed to build not modified response");
+	}
+
+	response
+		.status(200)
+		.header(header::CONTENT_TYPE, mime)
+		.body(Body::from(blob.into_vec()))
+		.expect("failed to build OK response")
+}
+
+/// JSON helper for health probes; unlike `ok_json` the response must never be cached.
+pub fn health_json(status: u16, message: &str) -> Response<Body> {
+	Response::builder()
+		.status(status)
+		.header(header::CONTENT_TYPE, "application/json")
+		.header(header::CACHE_CONTROL, "no-store")
+		.body(Body::from(message.as_bytes().to_vec()))
+		.expect("failed to build health response")
+}
+
 // --- tests -------------------------------------------------------------------
 #[cfg(test)]
 mod tests {
 	use super::*;
 	use axum::http::header;
+	use versatiles_core::TileCompression;
 
 	#[test]
 	fn ok_json_sets_expected_headers() {
@@ -223,6 +357,122 @@ mod tests {
 		assert!(headers.get(header::CONTENT_ENCODING).is_none());
 	}
 
+	#[test]
+	fn ok_revalidated_answers_not_modified() {
+		let resp = ok_revalidated(String::from("<xml/>"), "application/xml", &HeaderMap::new());
+		assert_eq!(resp.status(), 200);
+		assert_eq!(resp.headers().get(header::CONTENT_TYPE).unwrap(), "application/xml");
+		assert_eq!(
+			resp.headers().get(header::CACHE_CONTROL).unwrap(),
+			"public, max-age=60, must-revalidate"
+		);
+		let etag = resp.headers().get(header::ETAG).unwrap().clone();
+
+		let mut headers = HeaderMap::new();
+		headers.insert(header::IF_NONE_MATCH, etag.clone());
+		let resp = ok_revalidated(String::from("<xml/>"), "application/xml", &headers);
+		assert_eq!(resp.status(), 304);
+		assert_eq!(resp.headers().get(header::ETAG).unwrap(), &etag);
+
+		// a changed document gets a new ETag
+		let resp = ok_revalidated(String::from("<xml></xml>"), "application/xml", &headers);
+		assert_eq!(resp.status(), 200);
+		assert_ne!(resp.headers().get(header::ETAG).unwrap(), &etag);
+	}
+
+	#[tokio::test]
+	async fn compression_passthrough_ignores_accept_encoding() {
+		use versatiles_container::{MockReader, MockReaderProfile, TileSource};
+		use versatiles_core::TileCoord;
+
+		let reader = MockReader::new_mock_profile(MockReaderProfile::Pbf).unwrap();
+		let stored = reader
+			.get_tile(&TileCoord::new(3, 2, 3).unwrap())
+			.await
+			.unwrap()
+			.unwrap()
+			.into_blob(TileCompression::Gzip)
+			.unwrap();
+		let source = Arc::new(ServerTileSource::from(Arc::new(reader.boxed()), "osm").unwrap());
+
+		let request = async |accept: &str, passthrough: bool| {
+			let mut headers = HeaderMap::new();
+			headers.insert(header::ACCEPT_ENCODING, accept.parse().unwrap());
+			let path = Url::from("/tiles/osm/3/2/3");
+			let resp = serve_tile_from_source(path, headers, Arc::clone(&source), false, passthrough, false).await;
+			assert_eq!(resp.status(), 200);
+			let encoding = resp
+				.headers()
+				.get(header::CONTENT_ENCODING)
+				.map(|v| v.to_str().unwrap().to_string());
+			let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+			(encoding, body.to_vec())
+		};
+
+		// Without passthrough the tile is recompressed for the client.
+		assert_eq!(request("br", false).await.0.as_deref(), Some("br"));
+		assert_eq!(request("identity", false).await.0, None);
+
+		// With passthrough the stored gzip blob is sent as it is.
+		for accept in ["br", "identity", "gzip"] {
+			let (encoding, body) = request(accept, true).await;
+			assert_eq!(encoding.as_deref(), Some("gzip"));
+			assert_eq!(body, stored.as_slice());
+		}
+	}
+
+	#[tokio::test]
+	async fn missing_tiles_no_content() {
+		use versatiles_container::{MockReader, MockReaderProfile, TileSource};
+
+		let reader = MockReader::new_mock_profile(MockReaderProfile::Pbf).unwrap();
+		let source = Arc::new(ServerTileSource::from(Arc::new(reader.boxed()), "osm").unwrap());
+
+		let status = async |path: &str, no_content: bool| {
+			let path = Url::from(path);
+			serve_tile_from_source(path, HeaderMap::new(), Arc::clone(&source), false, false, no_content)
+				.await
+				.status()
+		};
+
+		// the mock contains levels 2 to 6
+		assert_eq!(status("/tiles/osm/3/2/3", true).await, 200);
+		assert_eq!(status("/tiles/osm/7/2/3", false).await, 404);
+		assert_eq!(status("/tiles/osm/7/2/3", true).await, 204);
+		// unknown paths stay 404
+		assert_eq!(status("/tiles/osm/unknown.json", true).await, 404);
+	}
+
+	#[tokio::test]
+	async fn tile_responses_carry_access_log_info() {
+		use versatiles_container::{MockReader, MockReaderProfile, TileSource};
+
+		let reader = MockReader::new_mock_profile(MockReaderProfile::Pbf).unwrap();
+		let source = Arc::new(ServerTileSource::from(Arc::new(reader.boxed()), "osm").unwrap());
+
+		let info = async |path: &str| {
+			let response = serve_tile_from_source(
+				Url::from(path),
+				HeaderMap::new(),
+				Arc::clone(&source),
+				false,
+				false,
+				false,
+			)
+			.await;
+			response.extensions().get::<AccessLogInfo>().cloned().unwrap()
+		};
+
+		// without `Accept-Encoding` the tile is transcoded, so the second request hits the cache
+		let tile = AccessLogInfo {
+			source: String::from("osm"),
+			cache_hit: Some(false),
+		};
+		assert_eq!(info("/tiles/osm/3/2/3").await, tile);
+		assert_eq!(info("/tiles/osm/3/2/3").await.cache_hit, Some(true));
+		assert_eq!(info("/tiles/osm/tiles.json").await.cache_hit, None);
+	}
+
 	#[test]
 	fn ok_data_plain_text_gzip_when_allowed() {
 		// Source is uncompressed text; client allows gzip
@@ -230,6 +480,7 @@ mod tests {
 			blob: Blob::from("The quick brown fox jumps over the lazy dog"),
 			compression: TileCompression::Uncompressed,
 			mime: "text/plain".into(),
+			cache_hit: false,
 		};
 		let mut target = TargetCompression::from_none();
 		target.insert(TileCompression::Gzip);
@@ -290,6 +541,7 @@ mod tests {
 			blob: Blob::from(png_bytes),
 			compression: TileCompression::Uncompressed,
 			mime: "image/png".into(),
+			cache_hit: false,
 		};
 		let mut target = TargetCompression::from_none();
 		target.insert(TileCompression::Brotli);
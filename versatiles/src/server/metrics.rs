@@ -0,0 +1,316 @@
+//! Prometheus metrics for the tile server.
+//!
+//! Metrics are only collected and served if enabled with `enable_metrics` in the server config,
+//! because the endpoint has no authentication and would shadow a static `/metrics` file.
+//!
+//! [`ServerMetrics`] is a small registry of counters and histograms. It is shared by the
+//! [`record_request`] middleware, which updates it for every response, and the `/metrics`
+//! endpoint, which renders it in the Prometheus text exposition format:
+//! - `versatiles_requests_total{source, status}`: answered requests
+//! - `versatiles_tiles_served_total`, `versatiles_bytes_served_total`
+//! - `versatiles_request_duration_seconds`, `versatiles_tile_size_bytes`: histograms
+//! - `versatiles_open_readers`, `versatiles_transcode_cache_entries`: gauges, read on every scrape
+//! - `versatiles_transcode_cache_hits_total`
+//!
+//! Like the access log, the middleware learns from the [`AccessLogInfo`] that the handlers attach
+//! to their responses which source answered and whether the response is a tile.
+
+use super::{access_log::AccessLogInfo, sources::ServerTileSource};
+use axum::{
+	body::{Body, HttpBody},
+	extract::{Request, State},
+	http::header,
+	middleware::Next,
+	response::Response,
+};
+use dashmap::DashMap;
+use std::{
+	collections::BTreeMap,
+	fmt::Write,
+	sync::{
+		Arc, Mutex,
+		atomic::{AtomicU64, Ordering},
+	},
+	time::Instant,
+};
+
+/// Upper bounds of the request latency buckets, in seconds.
+const LATENCY_BUCKETS: &[f64] = &[0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+/// Upper bounds of the tile size buckets, in bytes.
+const TILE_SIZE_BUCKETS: &[f64] = &[1024.0, 4096.0, 16384.0, 65536.0, 262144.0, 1048576.0, 4194304.0];
+
+/// Counters and histograms of a running server, see the module docs.
+pub struct ServerMetrics {
+	/// Number of requests per source and status code.
+	requests: Mutex<BTreeMap<(String, u16), u64>>,
+	tiles_served: AtomicU64,
+	bytes_served: AtomicU64,
+	cache_hits: AtomicU64,
+	request_duration: Histogram,
+	tile_size: Histogram,
+}
+
+impl Default for ServerMetrics {
+	fn default() -> Self {
+		ServerMetrics {
+			requests: Mutex::default(),
+			tiles_served: AtomicU64::new(0),
+			bytes_served: AtomicU64::new(0),
+			cache_hits: AtomicU64::new(0),
+			request_duration: Histogram::new(LATENCY_BUCKETS),
+			tile_size: Histogram::new(TILE_SIZE_BUCKETS),
+		}
+	}
+}
+
+impl ServerMetrics {
+	/// Records one answered request.
+	fn record(&self, response: &Response, seconds: f64) {
+		let info = response.extensions().get::<AccessLogInfo>();
+		let source = info.map_or("-", |info| info.source.as_str());
+		let status = response.status().as_u16();
+		*self
+			.requests
+			.lock()
+			.unwrap()
+			.entry((source.to_string(), status))
+			.or_default() += 1;
+		self.request_duration.observe(seconds);
+
+		let bytes = response.body().size_hint().exact();
+		if let Some(bytes) = bytes {
+			self.bytes_served.fetch_add(bytes, Ordering::Relaxed);
+		}
+
+		// Only tile responses carry a cache flag.
+		let Some(cache_hit) = info.and_then(|info| info.cache_hit) else {
+			return;
+		};
+		if cache_hit {
+			self.cache_hits.fetch_add(1, Ordering::Relaxed);
+		}
+		if response.status().is_success() {
+			self.tiles_served.fetch_add(1, Ordering::Relaxed);
+			if let Some(bytes) = bytes {
+				self.tile_size.observe(bytes as f64);
+			}
+		}
+	}
+
+	/// Renders all metrics in the Prometheus text exposition format.
+	pub fn render(&self, tile_sources: &DashMap<String, Arc<ServerTileSource>>) -> String {
+		let mut out = String::new();
+
+		describe(
+			&mut out,
+			"versatiles_requests_total",
+			"counter",
+			"Answered HTTP requests.",
+		);
+		for ((source, status), count) in self.requests.lock().unwrap().iter() {
+			let source = escape_label(source);
+			writeln!(
+				out,
+				"versatiles_requests_total{{source=\"{source}\",status=\"{status}\"}} {count}"
+			)
+			.unwrap();
+		}
+
+		let counters = [
+			(
+				"versatiles_tiles_served_total",
+				"Tiles served successfully.",
+				&self.tiles_served,
+			),
+			(
+				"versatiles_bytes_served_total",
+				"Bytes of all response bodies.",
+				&self.bytes_served,
+			),
+			(
+				"versatiles_transcode_cache_hits_total",
+				"Tiles served from the cache of transcoded tiles.",
+				&self.cache_hits,
+			),
+		];
+		for (name, help, counter) in counters {
+			describe(&mut out, name, "counter", help);
+			writeln!(out, "{name} {}", counter.load(Ordering::Relaxed)).unwrap();
+		}
+
+		self.request_duration.render(
+			&mut out,
+			"versatiles_request_duration_seconds",
+			"Time to answer a request.",
+		);
+		self
+			.tile_size
+			.render(&mut out, "versatiles_tile_size_bytes", "Size of served tiles.");
+
+		// Gauges describe the current state, so they are read on every scrape.
+		let cache_entries: usize = tile_sources
+			.iter()
+			.map(|entry| entry.value().transcoded_cache_len())
+			.sum();
+		let gauges = [
+			("versatiles_open_readers", "Mounted tile sources.", tile_sources.len()),
+			(
+				"versatiles_transcode_cache_entries",
+				"Transcoded tiles in the caches of all tile sources.",
+				cache_entries,
+			),
+		];
+		for (name, help, value) in gauges {
+			describe(&mut out, name, "gauge", help);
+			writeln!(out, "{name} {value}").unwrap();
+		}
+
+		out
+	}
+}
+
+/// A histogram with fixed buckets.
+struct Histogram {
+	bounds: &'static [f64],
+	state: Mutex<HistogramState>,
+}
+
+#[derive(Default)]
+struct HistogramState {
+	/// Observations per bucket, not cumulative. The last entry counts values above all bounds.
+	buckets: Vec<u64>,
+	sum: f64,
+	count: u64,
+}
+
+impl Histogram {
+	fn new(bounds: &'static [f64]) -> Self {
+		Histogram {
+			bounds,
+			state: Mutex::new(HistogramState {
+				buckets: vec![0; bounds.len() + 1],
+				..Default::default()
+			}),
+		}
+	}
+
+	fn observe(&self, value: f64) {
+		let index = self.bounds.partition_point(|bound| *bound < value);
+		let mut state = self.state.lock().unwrap();
+		state.buckets[index] += 1;
+		state.sum += value;
+		state.count += 1;
+	}
+
+	fn render(&self, out: &mut String, name: &str, help: &str) {
+		describe(out, name, "histogram", help);
+		let state = self.state.lock().unwrap();
+		let mut cumulative = 0;
+		for (bound, count) in self.bounds.iter().zip(&state.buckets) {
+			cumulative += count;
+			writeln!(out, "{name}_bucket{{le=\"{bound}\"}} {cumulative}").unwrap();
+		}
+		writeln!(out, "{name}_bucket{{le=\"+Inf\"}} {}", state.count).unwrap();
+		writeln!(out, "{name}_sum {}", state.sum).unwrap();
+		writeln!(out, "{name}_count {}", state.count).unwrap();
+	}
+}
+
+fn describe(out: &mut String, name: &str, kind: &str, help: &str) {
+	writeln!(out, "# HELP {name} {help}").unwrap();
+	writeln!(out, "# TYPE {name} {kind}").unwrap();
+}
+
+/// Escapes a label value as required by the exposition format.
+fn escape_label(value: &str) -> String {
+	value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+/// Middleware that records every request in the [`ServerMetrics`].
+pub async fn record_request(State(metrics): State<Arc<ServerMetrics>>, request: Request, next: Next) -> Response {
+	let start = Instant::now();
+	let response = next.run(request).await;
+	metrics.record(&response, start.elapsed().as_secs_f64());
+	response
+}
+
+/// Answers a scrape with the rendered metrics; the response must never be cached.
+pub fn ok_metrics(body: String) -> Response<Body> {
+	Response::builder()
+		.status(200)
+		.header(header::CONTENT_TYPE, "text/plain; version=0.0.4; charset=utf-8")
+		.header(header::CACHE_CONTROL, "no-store")
+		.body(Body::from(body))
+		.expect("failed to build metrics response")
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use versatiles_container::{MockReader, MockReaderProfile, TileSource};
+
+	fn response(status: u16, size: usize, info: Option<AccessLogInfo>) -> Response {
+		let mut response = Response::builder()
+			.status(status)
+			.body(Body::from(vec![0u8; size]))
+			.unwrap();
+		if let Some(info) = info {
+			response.extensions_mut().insert(info);
+		}
+		response
+	}
+
+	fn tile(cache_hit: bool) -> Option<AccessLogInfo> {
+		Some(AccessLogInfo {
+			source: String::from("osm"),
+			cache_hit: Some(cache_hit),
+		})
+	}
+
+	#[test]
+	fn records_and_renders() {
+		let metrics = ServerMetrics::default();
+		metrics.record(&response(200, 2000, tile(false)), 0.002);
+		metrics.record(&response(200, 100, tile(true)), 0.0005);
+		metrics.record(&response(404, 9, tile(false)), 0.02);
+		metrics.record(&response(404, 9, None), 20.0);
+
+		let sources = DashMap::new();
+		let reader = Arc::new(MockReader::new_mock_profile(MockReaderProfile::Png).unwrap().boxed());
+		sources.insert(
+			"osm".to_string(),
+			Arc::new(ServerTileSource::from(reader, "osm").unwrap()),
+		);
+
+		let text = metrics.render(&sources);
+		for line in [
+			"# TYPE versatiles_requests_total counter",
+			"versatiles_requests_total{source=\"-\",status=\"404\"} 1",
+			"versatiles_requests_total{source=\"osm\",status=\"200\"} 2",
+			"versatiles_requests_total{source=\"osm\",status=\"404\"} 1",
+			"versatiles_tiles_served_total 2",
+			"versatiles_bytes_served_total 2118",
+			"versatiles_transcode_cache_hits_total 1",
+			"# TYPE versatiles_request_duration_seconds histogram",
+			"versatiles_request_duration_seconds_bucket{le=\"0.001\"} 1",
+			"versatiles_request_duration_seconds_bucket{le=\"0.005\"} 2",
+			"versatiles_request_duration_seconds_bucket{le=\"10\"} 3",
+			"versatiles_request_duration_seconds_bucket{le=\"+Inf\"} 4",
+			"versatiles_request_duration_seconds_count 4",
+			"versatiles_tile_size_bytes_bucket{le=\"1024\"} 1",
+			"versatiles_tile_size_bytes_bucket{le=\"4096\"} 2",
+			"versatiles_tile_size_bytes_sum 2100",
+			"# TYPE versatiles_open_readers gauge",
+			"versatiles_open_readers 1",
+			"versatiles_transcode_cache_entries 0",
+		] {
+			assert!(text.lines().any(|l| l == line), "missing '{line}' in:\n{text}");
+		}
+	}
+
+	#[test]
+	fn label_values_are_escaped() {
+		assert_eq!(escape_label("a\"b\\c\nd"), "a\\\"b\\\\c\\nd");
+	}
+}
@@ -1,12 +1,18 @@
 //! server implementation
 
+mod access_log;
 mod cors;
 pub mod encoding;
 mod handlers;
+mod metrics;
 mod routes;
 mod sources;
 mod tile_server;
+mod tls;
 mod utils;
+mod wmts;
 
+pub use sources::FolderOptions;
 pub use tile_server::*;
+pub use tls::TlsConfig;
 pub use utils::Url;
@@ -4,21 +4,32 @@
 //! lifecycle or CORS logic. It's intentionally tiny and declarative.
 
 use super::{
-	handlers::{StaticHandlerState, error_404, ok_json, serve_static, serve_tile_from_source},
+	handlers::{
+		StaticHandlerState, error_404, health_json, ok_json, ok_revalidated, serve_static, serve_tile_from_source,
+	},
+	metrics::{ServerMetrics, ok_metrics},
 	sources::{ServerTileSource, StaticSource},
 	utils::Url,
+	wmts,
 };
 use anyhow::Result;
 use axum::{
 	Router,
 	body::Body,
 	extract::State,
-	http::{HeaderMap, Uri},
+	http::{HeaderMap, Uri, header},
 	response::Response,
 	routing::get,
 };
 use dashmap::DashMap;
-use std::sync::Arc;
+use std::{
+	sync::{
+		Arc,
+		atomic::{AtomicBool, Ordering},
+	},
+	time::Instant,
+};
+use versatiles_core::json::{JsonArray, JsonObject, JsonValue};
 use versatiles_derive::context;
 
 /// State for dynamic tile routing - looks up sources at request time.
@@ -26,6 +37,10 @@ use versatiles_derive::context;
 pub struct DynamicTileHandlerState {
 	pub tile_sources: Arc<DashMap<String, Arc<ServerTileSource>>>,
 	pub minimal_recompression: bool,
+	pub compression_passthrough: bool,
+	pub missing_tiles_no_content: bool,
+	/// Serve the preview pages `/tiles/{id}/preview`.
+	pub enable_preview: bool,
 }
 
 /// Dynamic tile handler that extracts source_id from the path and looks it up.
@@ -56,8 +71,21 @@ pub async fn serve_dynamic_tile(
 		}
 	};
 
+	if !state.enable_preview && parts.get(2).is_some_and(|part| part == "preview") {
+		log::debug!("preview of tile source '{source_id}' is disabled");
+		return error_404();
+	}
+
 	// Delegate to core serving logic
-	serve_tile_from_source(path, headers, tile_source, state.minimal_recompression).await
+	serve_tile_from_source(
+		path,
+		headers,
+		tile_source,
+		state.minimal_recompression,
+		state.compression_passthrough,
+		state.missing_tiles_no_content,
+	)
+	.await
 }
 
 /// Attach dynamic tile routing with single catch-all route.
@@ -65,10 +93,16 @@ pub fn add_tile_sources_to_app(
 	app: Router,
 	sources: Arc<DashMap<String, Arc<ServerTileSource>>>,
 	minimal_recompression: bool,
+	compression_passthrough: bool,
+	missing_tiles_no_content: bool,
+	enable_preview: bool,
 ) -> Router {
 	let state = DynamicTileHandlerState {
 		tile_sources: sources,
 		minimal_recompression,
+		compression_passthrough,
+		missing_tiles_no_content,
+		enable_preview,
 	};
 
 	let tile_router = Router::new()
@@ -93,9 +127,19 @@ pub fn add_static_sources_to_app(
 	app.merge(static_app)
 }
 
-/// Attach small JSON API endpoints (currently `/tiles/index.json`).
+/// Attach small JSON API endpoints:
+/// - `/tiles/index.json`: sorted list of tile source ids
+/// - `/tiles/sources.json`: sorted list of mounted tile sources with their TileJSON url and a description of the source,
+///   plus the tile grid for sources that are not tiled in Web Mercator
+/// - `/wmts/1.0.0/WMTSCapabilities.xml`: WMTS capabilities document of the mounted tile sources
+///
+/// `tls` tells whether the server terminates TLS itself, so absolute urls default to `https`.
 #[context("adding API routes to app")]
-pub async fn add_api_to_app(app: Router, sources: Arc<DashMap<String, Arc<ServerTileSource>>>) -> Result<Router> {
+pub async fn add_api_to_app(
+	app: Router,
+	sources: Arc<DashMap<String, Arc<ServerTileSource>>>,
+	tls: bool,
+) -> Result<Router> {
 	let mut api_app = Router::new();
 
 	api_app = api_app.route(
@@ -114,9 +158,121 @@ pub async fn add_api_to_app(app: Router, sources: Arc<DashMap<String, Arc<Server
 		}),
 	);
 
+	api_app = api_app.route(
+		"/tiles/sources.json",
+		get({
+			let sources = Arc::clone(&sources);
+			move || async move {
+				// Collect first, so no DashMap guard is held across an await point.
+				let mut list: Vec<Arc<ServerTileSource>> = sources.iter().map(|entry| Arc::clone(entry.value())).collect();
+				list.sort_by(|a, b| a.id.cmp(&b.id));
+
+				let mut array = Vec::with_capacity(list.len());
+				for source in list {
+					let mut obj = JsonObject::new();
+					obj.set("id", source.id.clone());
+					obj.set("tilejson", source.prefix.join_as_string("tiles.json"));
+					obj.set("source", source.get_source_name().await);
+					if let Some(tile_grid) = &source.metadata().tile_grid {
+						obj.set("tile_grid", tile_grid.as_json_value());
+					}
+					array.push(JsonValue::from(obj));
+				}
+				ok_json(&JsonArray(array).stringify())
+			}
+		}),
+	);
+
+	api_app = api_app.route(
+		"/wmts/1.0.0/WMTSCapabilities.xml",
+		get({
+			let sources = Arc::clone(&sources);
+			move |headers: HeaderMap| async move {
+				// Generated per request, so sources added or removed at runtime show up immediately.
+				let mut list: Vec<Arc<ServerTileSource>> = sources.iter().map(|entry| Arc::clone(entry.value())).collect();
+				list.sort_by(|a, b| a.id.cmp(&b.id));
+
+				let xml = wmts::build_capabilities(&list, &get_base_url(&headers, tls));
+				ok_revalidated(xml, "application/xml", &headers)
+			}
+		}),
+	);
+
 	Ok(app.merge(api_app))
 }
 
+/// Absolute base url of the server as seen by the client, e.g. `https://tiles.example.org`.
+///
+/// A proxy in front of the server sets the scheme with `X-Forwarded-Proto`. Without it, the scheme
+/// is `https` if the server terminates TLS itself (`tls`) and `http` otherwise.
+fn get_base_url(headers: &HeaderMap, tls: bool) -> String {
+	let value = |name| headers.get(name).and_then(|value| value.to_str().ok());
+	let default_scheme = if tls { "https" } else { "http" };
+	let scheme = value(header::HeaderName::from_static("x-forwarded-proto")).unwrap_or(default_scheme);
+	let host = value(header::HOST).unwrap_or("localhost");
+	format!("{scheme}://{host}")
+}
+
+/// State for the health probes.
+#[derive(Clone)]
+pub struct HealthState {
+	pub tile_sources: Arc<DashMap<String, Arc<ServerTileSource>>>,
+	/// Set once all configured tile sources have been opened.
+	pub ready: Arc<AtomicBool>,
+	pub started: Instant,
+}
+
+impl HealthState {
+	fn as_json(&self, status: &str) -> String {
+		let mut obj = JsonObject::new();
+		obj.set("status", status);
+		obj.set("uptime_seconds", self.started.elapsed().as_secs());
+		obj.set("sources", self.tile_sources.len());
+		obj.set("version", env!("CARGO_PKG_VERSION"));
+		obj.stringify()
+	}
+}
+
+async fn serve_healthz(State(state): State<HealthState>) -> Response<Body> {
+	health_json(200, &state.as_json("ok"))
+}
+
+async fn serve_readyz(State(state): State<HealthState>) -> Response<Body> {
+	if state.ready.load(Ordering::Acquire) {
+		health_json(200, &state.as_json("ready"))
+	} else {
+		health_json(503, &state.as_json("starting"))
+	}
+}
+
+/// Attach health probes for load balancers:
+/// - `/healthz`: liveness, always `200` while the server is running
+/// - `/readyz`: readiness, `503` until all configured tile sources have been opened
+pub fn add_health_to_app(app: Router, state: HealthState) -> Router {
+	let health_app = Router::new()
+		.route("/healthz", get(serve_healthz))
+		.route("/readyz", get(serve_readyz))
+		.with_state(state);
+	app.merge(health_app)
+}
+
+/// State for the metrics endpoint.
+#[derive(Clone)]
+pub struct MetricsState {
+	pub metrics: Arc<ServerMetrics>,
+	pub tile_sources: Arc<DashMap<String, Arc<ServerTileSource>>>,
+}
+
+async fn serve_metrics(State(state): State<MetricsState>) -> Response<Body> {
+	ok_metrics(state.metrics.render(&state.tile_sources))
+}
+
+/// Attach `/metrics` for Prometheus.
+pub fn add_metrics_to_app(app: Router, state: MetricsState) -> Router {
+	let metrics_app = Router::new().route("/metrics", get(serve_metrics)).with_state(state);
+	app.merge(metrics_app)
+}
+
 // --- tests -------------------------------------------------------------------
 #[cfg(test)]
 mod tests {
@@ -136,23 +292,172 @@ mod tests {
 	async fn api_index_json_is_precomputed_and_empty_when_no_sources() {
 		let app = Router::new();
 		let sources = Arc::new(DashMap::new());
-		let app = add_api_to_app(app, sources).await.unwrap();
+		let app = add_api_to_app(app, sources, false).await.unwrap();
 
 		let (status, body) = get_body_text(app, "/tiles/index.json").await;
 		assert_eq!(status, StatusCode::OK);
 		assert_eq!(body, "[]");
 	}
 
+	#[tokio::test]
+	async fn api_sources_json_lists_mounted_sources() {
+		use versatiles_container::{MockReader, MockReaderProfile, TileSource};
+
+		let sources = Arc::new(DashMap::new());
+		for (id, profile) in [("satellite", MockReaderProfile::Png), ("osm", MockReaderProfile::Pbf)] {
+			let reader = Arc::new(MockReader::new_mock_profile(profile).unwrap().boxed());
+			sources.insert(id.to_string(), Arc::new(ServerTileSource::from(reader, id).unwrap()));
+		}
+		let app = add_api_to_app(Router::new(), sources, false).await.unwrap();
+
+		let (status, body) = get_body_text(app, "/tiles/sources.json").await;
+		assert_eq!(status, StatusCode::OK);
+		assert_eq!(
+			body,
+			"[{\"id\":\"osm\",\"source\":\"container 'dummy' ('dummy')\",\"tilejson\":\"/tiles/osm/tiles.json\"},{\"id\":\"satellite\",\"source\":\"container 'dummy' ('dummy')\",\"tilejson\":\"/tiles/satellite/tiles.json\"}]"
+		);
+	}
+
+	#[tokio::test]
+	async fn api_sources_json_includes_tile_grid() {
+		use versatiles_container::{MockReader, TileSource, TileSourceMetadata};
+		use versatiles_core::{TileCompression, TileFormat, TileGrid};
+
+		let metadata = TileSourceMetadata::new_builder()
+			.with_format(TileFormat::PNG)
+			.with_compression(TileCompression::Uncompressed)
+			.with_tile_grid(TileGrid::new(10.0, 70.0, 4.0, 2.0, vec![2.0, 1.0]).unwrap())
+			.build()
+			.unwrap();
+		let reader = Arc::new(MockReader::new_mock(metadata).unwrap().boxed());
+		let sources = Arc::new(DashMap::new());
+		sources.insert(
+			"sweden".to_string(),
+			Arc::new(ServerTileSource::from(reader, "sweden").unwrap()),
+		);
+		let app = add_api_to_app(Router::new(), sources, false).await.unwrap();
+
+		let (status, body) = get_body_text(app, "/tiles/sources.json").await;
+		assert_eq!(status, StatusCode::OK);
+		assert_eq!(
+			body,
+			"[{\"id\":\"sweden\",\"source\":\"container 'dummy' ('dummy')\",\"tile_grid\":{\"origin\":[10,70],\"resolutions\":[2,1],\"tile_height_deg\":2,\"tile_width_deg\":4},\"tilejson\":\"/tiles/sweden/tiles.json\"}]"
+		);
+	}
+
+	#[tokio::test]
+	async fn wmts_capabilities_are_revalidated() {
+		use versatiles_container::{MockReader, MockReaderProfile, TileSource};
+
+		let sources = Arc::new(DashMap::new());
+		let reader = Arc::new(MockReader::new_mock_profile(MockReaderProfile::Png).unwrap().boxed());
+		sources.insert(
+			"osm".to_string(),
+			Arc::new(ServerTileSource::from(reader, "osm").unwrap()),
+		);
+		let app = add_api_to_app(Router::new(), Arc::clone(&sources), false)
+			.await
+			.unwrap();
+
+		let req = axum::http::Request::builder()
+			.uri("/wmts/1.0.0/WMTSCapabilities.xml")
+			.header(header::HOST, "tiles.example.org")
+			.header("x-forwarded-proto", "https")
+			.body(Body::empty())
+			.unwrap();
+		let res = app.clone().oneshot(req).await.unwrap();
+		assert_eq!(res.status(), StatusCode::OK);
+		let etag = res.headers().get(header::ETAG).unwrap().clone();
+		let bytes = axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+		let body = String::from_utf8_lossy(&bytes);
+		assert!(body.contains("<ows:Identifier>osm</ows:Identifier>"));
+		assert!(body.contains(r#"template="https://tiles.example.org/tiles/osm/{TileMatrix}/{TileCol}/{TileRow}""#));
+
+		let req = axum::http::Request::builder()
+			.uri("/wmts/1.0.0/WMTSCapabilities.xml")
+			.header(header::HOST, "tiles.example.org")
+			.header("x-forwarded-proto", "https")
+			.header(header::IF_NONE_MATCH, etag.clone())
+			.body(Body::empty())
+			.unwrap();
+		let res = app.clone().oneshot(req).await.unwrap();
+		assert_eq!(res.status(), StatusCode::NOT_MODIFIED);
+
+		// removing a source changes the document
+		sources.remove("osm");
+		let (status, body) = get_body_text(app, "/wmts/1.0.0/WMTSCapabilities.xml").await;
+		assert_eq!(status, StatusCode::OK);
+		assert!(!body.contains("<Layer>"));
+	}
+
+	#[tokio::test]
+	async fn health_probes_report_readiness() {
+		use versatiles_container::{MockReader, MockReaderProfile, TileSource};
+
+		let sources = Arc::new(DashMap::new());
+		let reader = Arc::new(MockReader::new_mock_profile(MockReaderProfile::Png).unwrap().boxed());
+		sources.insert(
+			"osm".to_string(),
+			Arc::new(ServerTileSource::from(reader, "osm").unwrap()),
+		);
+
+		let ready = Arc::new(AtomicBool::new(false));
+		let state = HealthState {
+			tile_sources: sources,
+			ready: Arc::clone(&ready),
+			started: Instant::now(),
+		};
+		let app = add_health_to_app(Router::new(), state);
+		let version = env!("CARGO_PKG_VERSION");
+
+		let (status, body) = get_body_text(app.clone(), "/healthz").await;
+		assert_eq!(status, StatusCode::OK);
+		assert_eq!(
+			body,
+			format!("{{\"sources\":1,\"status\":\"ok\",\"uptime_seconds\":0,\"version\":\"{version}\"}}")
+		);
+
+		let (status, body) = get_body_text(app.clone(), "/readyz").await;
+		assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+		assert!(body.contains("\"status\":\"starting\""));
+
+		ready.store(true, Ordering::Release);
+		let (status, body) = get_body_text(app, "/readyz").await;
+		assert_eq!(status, StatusCode::OK);
+		assert!(body.contains("\"status\":\"ready\""));
+	}
+
 	#[tokio::test]
 	async fn no_tile_sources_yields_404() {
 		let app = Router::new();
 		let sources = Arc::new(DashMap::new());
-		let app = add_tile_sources_to_app(app, sources, false);
+		let app = add_tile_sources_to_app(app, sources, false, false, false, false);
 
 		let (status, _body) = get_body_text(app, "/tiles/any/1/2/3").await;
 		assert_eq!(status, StatusCode::NOT_FOUND);
 	}
 
+	#[rstest::rstest]
+	#[case::disabled(false, StatusCode::NOT_FOUND)]
+	#[case::enabled(true, StatusCode::OK)]
+	#[tokio::test]
+	async fn preview_is_opt_in(#[case] enable_preview: bool, #[case] expected: StatusCode) {
+		use versatiles_container::{MockReader, MockReaderProfile, TileSource};
+
+		let sources = Arc::new(DashMap::new());
+		let reader = Arc::new(MockReader::new_mock_profile(MockReaderProfile::Png).unwrap().boxed());
+		sources.insert(
+			"osm".to_string(),
+			Arc::new(ServerTileSource::from(reader, "osm").unwrap()),
+		);
+		let app = add_tile_sources_to_app(Router::new(), sources, false, false, false, enable_preview);
+
+		let (status, _body) = get_body_text(app.clone(), "/tiles/osm/preview").await;
+		assert_eq!(status, expected);
+		let (status, _body) = get_body_text(app, "/tiles/osm/tiles.json").await;
+		assert_eq!(status, StatusCode::OK);
+	}
+
 	#[tokio::test]
 	async fn no_static_sources_yields_404() {
 		let app = Router::new();
@@ -162,4 +467,15 @@ mod tests {
 		let (status, _body) = get_body_text(app, "/").await;
 		assert_eq!(status, StatusCode::NOT_FOUND);
 	}
+
+	#[test]
+	fn base_url_defaults_to_the_servers_scheme() {
+		let mut headers = HeaderMap::new();
+		headers.insert(header::HOST, "tiles.example.org".parse().unwrap());
+		assert_eq!(get_base_url(&headers, false), "http://tiles.example.org");
+		assert_eq!(get_base_url(&headers, true), "https://tiles.example.org");
+
+		headers.insert("x-forwarded-proto", "http".parse().unwrap());
+		assert_eq!(get_base_url(&headers, true), "http://tiles.example.org");
+	}
 }
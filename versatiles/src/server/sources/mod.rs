@@ -1,11 +1,18 @@
-//! implementation of different sources (tile containers, folders, tar files)
+//! implementation of different sources (tile containers, folders, tar files, versatiles attachments)
 
+mod request_coalescer;
 mod response;
 mod static_source;
 mod static_source_folder;
+mod static_source_http;
 mod static_source_tar;
+mod static_source_versatiles;
+mod tile_info;
 mod tile_source;
 
-pub use response::SourceResponse;
+pub use request_coalescer::RequestCoalescer;
+pub use response::{RangeResponse, SourceResponse};
 pub use static_source::StaticSource;
+pub use static_source_folder::FolderOptions;
+pub use tile_info::TileInfo;
 pub use tile_source::ServerTileSource;
@@ -0,0 +1,216 @@
+//! Deduplication of identical in-flight tile requests.
+//!
+//! Map clients often request the same tile several times in parallel (e.g. multiple
+//! layers sharing a source). Instead of reading the tile once per request, the first
+//! request performs the read and broadcasts the result to all requests that arrive
+//! while it is still in flight. Errors are shared as well, so every waiting request fails.
+
+use anyhow::{Result, anyhow};
+use dashmap::{DashMap, mapref::entry::Entry};
+use std::{future::Future, sync::Arc};
+use tokio::sync::broadcast;
+use versatiles_core::{Blob, TileCoord};
+
+/// Result of a fetch as it is broadcast to the waiting requests.
+type SharedResult = Result<Option<Blob>, Arc<anyhow::Error>>;
+
+/// Coalesces concurrent fetches of the same tile into a single fetch.
+#[derive(Default)]
+pub struct RequestCoalescer {
+	in_flight: DashMap<TileCoord, broadcast::Sender<SharedResult>>,
+}
+
+/// Removes the in-flight entry when the leading request completes or is cancelled.
+///
+/// Dropping the entry closes the channel, so waiters of a cancelled request are woken up
+/// and can fetch the tile themselves.
+struct InFlightGuard<'a> {
+	in_flight: &'a DashMap<TileCoord, broadcast::Sender<SharedResult>>,
+	coord: TileCoord,
+}
+
+impl Drop for InFlightGuard<'_> {
+	fn drop(&mut self) {
+		self.in_flight.remove(&self.coord);
+	}
+}
+
+impl RequestCoalescer {
+	/// Returns the tile at `coord`, calling `fetch` only if no fetch for `coord` is in flight.
+	///
+	/// Otherwise waits for the in-flight fetch and returns a copy of its result.
+	pub async fn get_or_fetch<F, Fut>(&self, coord: TileCoord, fetch: F) -> Result<Option<Blob>>
+	where
+		F: FnOnce() -> Fut,
+		Fut: Future<Output = Result<Option<Blob>>>,
+	{
+		let sender = match self.in_flight.entry(coord) {
+			Entry::Occupied(entry) => {
+				let mut receiver = entry.get().subscribe();
+				// Release the shard lock before awaiting.
+				drop(entry);
+				match receiver.recv().await {
+					Ok(result) => return result.map_err(unshare_error),
+					Err(_) => {
+						log::debug!("in-flight request for {coord:?} was cancelled, fetching again");
+						return fetch().await;
+					}
+				}
+			}
+			Entry::Vacant(entry) => {
+				let (sender, _) = broadcast::channel(1);
+				entry.insert(sender.clone());
+				sender
+			}
+		};
+
+		let guard = InFlightGuard {
+			in_flight: &self.in_flight,
+			coord,
+		};
+		let result = fetch().await.map_err(Arc::new);
+
+		// Remove the entry before broadcasting: everybody who subscribed until now gets
+		// this result, later requests start a new fetch.
+		drop(guard);
+		let _ = sender.send(result.clone());
+
+		result.map_err(unshare_error)
+	}
+}
+
+/// Returns the error itself if it is not shared anymore, otherwise a copy of its message chain.
+fn unshare_error(err: Arc<anyhow::Error>) -> anyhow::Error {
+	Arc::try_unwrap(err).unwrap_or_else(|err| anyhow!("{err:#}"))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::sync::{
+		Arc,
+		atomic::{AtomicUsize, Ordering},
+	};
+	use std::time::Duration;
+
+	impl RequestCoalescer {
+		fn in_flight_count(&self) -> usize {
+			self.in_flight.len()
+		}
+	}
+
+	async fn fetch_counted(calls: Arc<AtomicUsize>, delay_ms: u64) -> Result<Option<Blob>> {
+		calls.fetch_add(1, Ordering::SeqCst);
+		tokio::time::sleep(Duration::from_millis(delay_ms)).await;
+		Ok(Some(Blob::from("tile")))
+	}
+
+	#[tokio::test]
+	async fn simultaneous_requests_fetch_once() {
+		let coalescer = Arc::new(RequestCoalescer::default());
+		let calls = Arc::new(AtomicUsize::new(0));
+		let coord = TileCoord::new(3, 1, 2).unwrap();
+
+		let handles = (0..10)
+			.map(|_| {
+				let coalescer = Arc::clone(&coalescer);
+				let calls = Arc::clone(&calls);
+				tokio::spawn(async move { coalescer.get_or_fetch(coord, || fetch_counted(calls, 200)).await })
+			})
+			.collect::<Vec<_>>();
+
+		for handle in handles {
+			assert_eq!(handle.await.unwrap().unwrap(), Some(Blob::from("tile")));
+		}
+		assert_eq!(calls.load(Ordering::SeqCst), 1);
+		assert_eq!(coalescer.in_flight_count(), 0);
+	}
+
+	#[tokio::test]
+	async fn errors_are_shared() {
+		let coalescer = Arc::new(RequestCoalescer::default());
+		let coord = TileCoord::new(3, 1, 2).unwrap();
+
+		let handles = (0..3)
+			.map(|_| {
+				let coalescer = Arc::clone(&coalescer);
+				tokio::spawn(async move {
+					coalescer
+						.get_or_fetch(coord, || async {
+							tokio::time::sleep(Duration::from_millis(100)).await;
+							Err(anyhow!("broken tile").context("reading tile"))
+						})
+						.await
+				})
+			})
+			.collect::<Vec<_>>();
+
+		for handle in handles {
+			let err = handle.await.unwrap().unwrap_err();
+			assert_eq!(format!("{err:#}"), "reading tile: broken tile");
+		}
+		assert_eq!(coalescer.in_flight_count(), 0);
+	}
+
+	#[tokio::test]
+	async fn sequential_requests_fetch_again() {
+		let coalescer = RequestCoalescer::default();
+		let calls = Arc::new(AtomicUsize::new(0));
+		let coord = TileCoord::new(3, 1, 2).unwrap();
+
+		for _ in 0..3 {
+			let result = coalescer.get_or_fetch(coord, || fetch_counted(calls.clone(), 0)).await;
+			assert_eq!(result.unwrap(), Some(Blob::from("tile")));
+			assert_eq!(coalescer.in_flight_count(), 0);
+		}
+		assert_eq!(calls.load(Ordering::SeqCst), 3);
+	}
+
+	#[tokio::test]
+	async fn different_coords_are_not_coalesced() {
+		let coalescer = Arc::new(RequestCoalescer::default());
+		let calls = Arc::new(AtomicUsize::new(0));
+
+		let handles = (0..4)
+			.map(|x| {
+				let coalescer = Arc::clone(&coalescer);
+				let calls = Arc::clone(&calls);
+				let coord = TileCoord::new(3, x, 0).unwrap();
+				tokio::spawn(async move { coalescer.get_or_fetch(coord, || fetch_counted(calls, 50)).await })
+			})
+			.collect::<Vec<_>>();
+
+		for handle in handles {
+			handle.await.unwrap().unwrap();
+		}
+		assert_eq!(calls.load(Ordering::SeqCst), 4);
+	}
+
+	#[tokio::test]
+	async fn waiters_survive_cancelled_leader() {
+		let coalescer = Arc::new(RequestCoalescer::default());
+		let calls = Arc::new(AtomicUsize::new(0));
+		let coord = TileCoord::new(3, 1, 2).unwrap();
+
+		let leader = {
+			let coalescer = Arc::clone(&coalescer);
+			let calls = Arc::clone(&calls);
+			tokio::spawn(async move { coalescer.get_or_fetch(coord, || fetch_counted(calls, 10_000)).await })
+		};
+		while coalescer.in_flight_count() == 0 {
+			tokio::task::yield_now().await;
+		}
+
+		let waiter = {
+			let coalescer = Arc::clone(&coalescer);
+			let calls = Arc::clone(&calls);
+			tokio::spawn(async move { coalescer.get_or_fetch(coord, || fetch_counted(calls, 0)).await })
+		};
+		tokio::time::sleep(Duration::from_millis(50)).await;
+
+		leader.abort();
+		assert_eq!(waiter.await.unwrap().unwrap(), Some(Blob::from("tile")));
+		assert_eq!(calls.load(Ordering::SeqCst), 2);
+		assert_eq!(coalescer.in_flight_count(), 0);
+	}
+}
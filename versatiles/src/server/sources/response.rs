@@ -1,9 +1,12 @@
 use versatiles_core::{Blob, TileCompression};
 
+#[derive(Clone)]
 pub struct SourceResponse {
 	pub blob: Blob,
 	pub compression: TileCompression,
 	pub mime: String,
+	/// Whether the blob was served from a cache, e.g. a tile that had already been transcoded.
+	pub cache_hit: bool,
 }
 
 impl SourceResponse {
@@ -12,6 +15,21 @@ impl SourceResponse {
 			blob,
 			compression: compression.to_owned(),
 			mime: mime.to_owned(),
+			cache_hit: false,
 		})
 	}
 }
+
+/// Answer of a static source to a byte-range request.
+#[derive(Clone, Debug, PartialEq)]
+pub enum RangeResponse {
+	/// The requested bytes `start..start + blob.len()` of a file with `total` bytes.
+	Partial {
+		blob: Blob,
+		start: u64,
+		total: u64,
+		mime: String,
+	},
+	/// The range lies outside of a file with `total` bytes.
+	Unsatisfiable { total: u64 },
+}
@@ -1,17 +1,36 @@
-use super::{super::utils::Url, SourceResponse, static_source_folder::Folder, static_source_tar::TarFile};
+use super::{
+	super::utils::{HttpRange, Url},
+	RangeResponse, SourceResponse,
+	static_source_folder::{Folder, FolderOptions},
+	static_source_http::HttpStaticSource,
+	static_source_tar::TarFile,
+	static_source_versatiles::VersaTilesAttachments,
+};
 use anyhow::Result;
 use async_trait::async_trait;
-use std::{fmt::Debug, path::Path, sync::Arc};
+use std::{fmt::Debug, path::Path, sync::Arc, time::Duration};
 use versatiles_core::utils::TargetCompression;
 use versatiles_derive::context;
 
 #[async_trait]
 pub trait StaticSourceTrait: Send + Sync + Debug {
-	#[cfg(test)]
+	/// Short name of the kind of source, like "folder" or "tar", e.g. for the access log.
 	fn get_type(&self) -> &str;
 	#[cfg(test)]
 	fn get_name(&self) -> &str;
-	fn get_data(&self, url: &Url, accept: &TargetCompression) -> Option<SourceResponse>;
+	async fn get_data(&self, url: &Url, accept: &TargetCompression) -> Option<SourceResponse>;
+
+	/// Reads only the requested bytes of the uncompressed file at `url`.
+	///
+	/// Returns `None` if the source can't serve ranges for this file, so the full file is served.
+	async fn get_range(&self, _url: &Url, _range: &HttpRange) -> Option<RangeResponse> {
+		None
+	}
+
+	/// Whether full responses should advertise `Accept-Ranges: bytes`.
+	fn supports_ranges(&self) -> bool {
+		false
+	}
 }
 
 #[derive(Clone)]
@@ -21,13 +40,18 @@ pub struct StaticSource {
 }
 
 impl StaticSource {
+	/// Creates a static source for a folder, tar archive or `.versatiles` file.
+	///
+	/// `folder_options` only apply to folders.
 	#[context("creating static source: path={path:?}, prefix={prefix}")]
-	pub fn new(path: &Path, prefix: &str) -> Result<StaticSource> {
+	pub async fn new(path: &Path, prefix: &str, folder_options: FolderOptions) -> Result<StaticSource> {
 		let prefix = Url::from(prefix).to_dir();
 
 		Ok(StaticSource {
 			source: Arc::new(if std::fs::metadata(path)?.is_dir() {
-				Box::new(Folder::from(path)?)
+				Box::new(Folder::with_options(path, folder_options)?)
+			} else if path.extension().is_some_and(|extension| extension == "versatiles") {
+				Box::new(VersaTilesAttachments::open(path).await?)
 			} else {
 				Box::new(TarFile::from(path)?)
 			}),
@@ -35,7 +59,20 @@ impl StaticSource {
 		})
 	}
 
-	#[cfg(test)]
+	/// Creates a static source that proxies the files of an upstream HTTP server.
+	#[context("creating static source: url={base_url}, prefix={prefix}")]
+	pub fn new_http(
+		base_url: &reqwest::Url,
+		prefix: &str,
+		cache_responses: bool,
+		cache_ttl: Duration,
+	) -> Result<StaticSource> {
+		Ok(StaticSource {
+			source: Arc::new(Box::new(HttpStaticSource::new(base_url, cache_responses, cache_ttl)?)),
+			prefix: Url::from(prefix).to_dir(),
+		})
+	}
+
 	pub fn get_type(&self) -> &str {
 		self.source.get_type()
 	}
@@ -44,11 +81,28 @@ impl StaticSource {
 		&self.prefix
 	}
 
-	pub fn get_data(&self, url: &Url, accept: &TargetCompression) -> Option<SourceResponse> {
+	pub async fn get_data(&self, url: &Url, accept: &TargetCompression) -> Option<SourceResponse> {
 		if !url.starts_with(&self.prefix) {
 			return None;
 		}
-		self.source.get_data(&url.strip_prefix(&self.prefix).unwrap(), accept)
+		self
+			.source
+			.get_data(&url.strip_prefix(&self.prefix).unwrap(), accept)
+			.await
+	}
+
+	pub async fn get_range(&self, url: &Url, range: &HttpRange) -> Option<RangeResponse> {
+		if !url.starts_with(&self.prefix) {
+			return None;
+		}
+		self
+			.source
+			.get_range(&url.strip_prefix(&self.prefix).unwrap(), range)
+			.await
+	}
+
+	pub fn supports_ranges(&self) -> bool {
+		self.source.supports_ranges()
 	}
 }
 
@@ -72,7 +126,7 @@ mod tests {
 			"MockSource"
 		}
 
-		fn get_data(&self, path: &Url, _accept: &TargetCompression) -> Option<SourceResponse> {
+		async fn get_data(&self, path: &Url, _accept: &TargetCompression) -> Option<SourceResponse> {
 			if path.starts_with(&Url::from("exists")) {
 				SourceResponse::new_some(
 					Blob::from(vec![1, 2, 3, 4]),
@@ -85,17 +139,17 @@ mod tests {
 		}
 	}
 
-	#[test]
-	fn new_static_source() -> Result<()> {
+	#[tokio::test]
+	async fn new_static_source() -> Result<()> {
 		use TileCompression::*;
 
-		let check_type = |path: PathBuf, type_name: &str| {
-			let source = StaticSource::new(&path, "").unwrap();
+		let check_type = async |path: PathBuf, type_name: &str| {
+			let source = StaticSource::new(&path, "", FolderOptions::default()).await.unwrap();
 			assert_eq!(source.get_type(), type_name);
 		};
 
-		let check_error = |path: PathBuf, error_should: &str| {
-			let source = StaticSource::new(&path, "");
+		let check_error = async |path: PathBuf, error_should: &str| {
+			let source = StaticSource::new(&path, "", FolderOptions::default()).await;
 			let error = source
 				.err()
 				.iter()
@@ -119,32 +173,32 @@ mod tests {
 
 		// Test non existent file
 		let path = temp_dir.path().join("non_existent.tar");
-		check_error(path, "(os error 2)");
+		check_error(path, "(os error 2)").await;
 
 		// Test .tar file
 		let path = temp_dir.path().join("temp.tar");
 		create_file(&path, Uncompressed);
-		check_type(path, "tar");
+		check_type(path, "tar").await;
 
 		// Test gzip compressed .tar file
 		let path = temp_dir.path().join("temp.tar.gz");
 		create_file(&path, Gzip);
-		check_type(path, "tar");
+		check_type(path, "tar").await;
 
 		// Test brotli compressed .tar file
 		let path = temp_dir.path().join("temp.tar.br");
 		create_file(&path, Brotli);
-		check_type(path, "tar");
+		check_type(path, "tar").await;
 
 		// Test non .tar file
 		let path = temp_dir.path().join("data.tar.bmp");
 		create_file(&path, Uncompressed);
-		check_error(path, "\" must be a name of a tar file");
+		check_error(path, "\" must be a name of a tar file").await;
 
 		// Test initialization with a folder
 		let path = temp_dir.path().join("folder");
 		std::fs::create_dir(&path)?;
-		check_type(path, "folder");
+		check_type(path, "folder").await;
 
 		Ok(())
 	}
@@ -155,7 +209,9 @@ mod tests {
 			source: Arc::new(Box::new(MockStaticSource)),
 			prefix: Url::from(""),
 		};
-		let result = static_source.get_data(&Url::from("exists"), &TargetCompression::from_none());
+		let result = static_source
+			.get_data(&Url::from("exists"), &TargetCompression::from_none())
+			.await;
 		assert!(result.is_some());
 	}
 
@@ -165,7 +221,9 @@ mod tests {
 			source: Arc::new(Box::new(MockStaticSource)),
 			prefix: Url::from(""),
 		};
-		let result = static_source.get_data(&Url::from("does_not_exist"), &TargetCompression::from_none());
+		let result = static_source
+			.get_data(&Url::from("does_not_exist"), &TargetCompression::from_none())
+			.await;
 		assert!(result.is_none());
 	}
 
@@ -176,11 +234,15 @@ mod tests {
 			prefix: Url::from("path/to"),
 		};
 		// Should match and retrieve data
-		let result = static_source.get_data(&Url::from("path/to/exists"), &TargetCompression::from_none());
+		let result = static_source
+			.get_data(&Url::from("path/to/exists"), &TargetCompression::from_none())
+			.await;
 		assert!(result.is_some());
 
 		// Should fail due to path mismatch
-		let result = static_source.get_data(&Url::from("path/wrong/exists"), &TargetCompression::from_none());
+		let result = static_source
+			.get_data(&Url::from("path/wrong/exists"), &TargetCompression::from_none())
+			.await;
 		assert!(result.is_none());
 	}
 }
@@ -1,6 +1,9 @@
 use super::{
-	super::{Url, utils::guess_mime},
-	SourceResponse,
+	super::{
+		Url,
+		utils::{HttpRange, guess_mime},
+	},
+	RangeResponse, SourceResponse,
 	static_source::StaticSourceTrait,
 };
 use anyhow::{Result, ensure};
@@ -9,17 +12,44 @@ use std::{
 	env::current_dir,
 	fmt::Debug,
 	fs::File,
-	io::{BufReader, Read},
+	io::{BufReader, Read, Seek, SeekFrom},
 	path::{Path, PathBuf},
 };
+use time::{OffsetDateTime, format_description::parse_borrowed};
 use versatiles_core::{Blob, TileCompression, utils::TargetCompression};
 use versatiles_derive::context;
 
+/// Options for serving a static folder.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FolderOptions {
+	/// Serve an HTML directory listing for directories without an `index.html`.
+	pub directory_listing: bool,
+	/// Follow symbolic links. Their targets must still be inside the folder.
+	pub follow_symlinks: bool,
+	/// Serve files and directories whose name starts with `.`, like `.git` or `.env`.
+	pub serve_dotfiles: bool,
+}
+
+impl Default for FolderOptions {
+	fn default() -> Self {
+		FolderOptions {
+			directory_listing: false,
+			follow_symlinks: true,
+			serve_dotfiles: false,
+		}
+	}
+}
+
+/// Maximum number of bytes served for a single range request. Larger ranges, like the `bytes=0-`
+/// of media players, are answered with their first bytes; clients request the rest with further ranges.
+const MAX_RANGE_LENGTH: u64 = 16 * 1024 * 1024;
+
 // Folder struct definition
 #[derive(Clone)]
 pub struct Folder {
 	folder: PathBuf,
 	name: String,
+	options: FolderOptions,
 }
 
 impl Folder {
@@ -41,57 +71,256 @@ impl Folder {
 		Ok(Folder {
 			folder,
 			name: path.to_str().unwrap().to_owned(),
+			options: FolderOptions::default(),
 		})
 	}
-}
 
-#[async_trait]
-impl StaticSourceTrait for Folder {
+	// Constructor that additionally enables or disables HTML directory listings
+	// for directories without an `index.html`
 	#[cfg(test)]
-	fn get_type(&self) -> &str {
-		"folder"
+	#[context("loading static folder from path: {path:?}, directory_listing={enabled}")]
+	pub fn with_directory_listing(path: &Path, enabled: bool) -> Result<Folder> {
+		Folder::with_options(
+			path,
+			FolderOptions {
+				directory_listing: enabled,
+				..FolderOptions::default()
+			},
+		)
 	}
 
-	// Returns the name of the folder
-	#[cfg(test)]
-	fn get_name(&self) -> &str {
-		&self.name
+	// Constructor with all options
+	#[context("loading static folder from path: {path:?}, options={options:?}")]
+	pub fn with_options(path: &Path, options: FolderOptions) -> Result<Folder> {
+		let mut folder = Folder::from(path)?;
+		folder.options = options;
+		Ok(folder)
 	}
 
-	// Gets the data at the given path and responds with a compressed or uncompressed version
-	// based on the accept header
-	fn get_data(&self, url: &Url, _accept: &TargetCompression) -> Option<SourceResponse> {
+	// Maps the URL to a path inside the folder and tells whether a directory listing may be generated.
+	// Traversal attempts and (unless enabled) dotfiles are rejected before touching the filesystem.
+	fn resolve_path(&self, url: &Url) -> Option<(PathBuf, bool)> {
+		if !url.as_vec().iter().all(|segment| self.is_allowed_segment(segment)) {
+			return None;
+		}
+
 		let mut local_path = url.to_pathbug(&self.folder);
 
 		// If the path is a directory, append 'index.html'
+		// Listings are only generated for directory URLs ending with `/`, otherwise relative links would break.
+		let mut may_list_directory = self.options.directory_listing;
 		if local_path.is_dir() {
 			local_path.push("index.html");
+			may_list_directory = false;
+		}
+
+		Some((local_path, may_list_directory))
+	}
+
+	fn is_allowed_segment(&self, segment: &str) -> bool {
+		// `uri.path()` is not percent-decoded, so encoded dots, slashes, backslashes and NUL are rejected as well.
+		let lower = segment.to_ascii_lowercase();
+		if segment == "."
+			|| segment == ".."
+			|| segment.contains(['\\', '\0'])
+			|| ["%2e", "%2f", "%5c", "%00"].iter().any(|s| lower.contains(s))
+		{
+			return false;
 		}
+		self.options.serve_dotfiles || !segment.starts_with('.')
+	}
 
-		// If the local path is not a subpath of the folder, return not found
-		if !local_path.starts_with(&self.folder) {
+	// Checks that an existing path, with all symbolic links resolved, is inside the folder.
+	// Returns the canonical path.
+	fn check_path(&self, path: &Path) -> Option<PathBuf> {
+		let canonical = path.canonicalize().ok()?;
+		let relative = canonical.strip_prefix(&self.folder).ok()?;
+		if !self.options.follow_symlinks && canonical != path {
 			return None;
 		}
+		if !self.options.serve_dotfiles
+			&& relative
+				.components()
+				.any(|c| c.as_os_str().to_string_lossy().starts_with('.'))
+		{
+			return None;
+		}
+		Some(canonical)
+	}
 
-		let mime = guess_mime(&local_path);
+	fn open_file(&self, path: &Path) -> Option<File> {
+		File::open(self.check_path(path)?).ok()
+	}
 
-		// Check for compressed versions first (".br" and ".gz"), falling back to uncompressed if neither is found
+	// Blocking part of `get_data`
+	fn read_data(&self, url: &Url) -> Option<SourceResponse> {
+		let (local_path, may_list_directory) = self.resolve_path(url)?;
 
-		let (file, compression) = if let Ok(file) = File::open(&local_path) {
-			(file, TileCompression::Uncompressed)
-		} else if let Ok(file) = File::open(format!("{}.br", local_path.display())) {
-			(file, TileCompression::Brotli)
-		} else if let Ok(file) = File::open(format!("{}.gz", local_path.display())) {
-			(file, TileCompression::Gzip)
-		} else {
+		let mime = guess_mime(&local_path);
+
+		// Try the uncompressed file first, then the precompressed versions (".br" and ".gz")
+		let opened = [
+			TileCompression::Uncompressed,
+			TileCompression::Brotli,
+			TileCompression::Gzip,
+		]
+		.into_iter()
+		.find_map(|compression| {
+			let path = format!("{}{}", local_path.display(), compression.as_extension());
+			self.open_file(Path::new(&path)).map(|file| (file, compression))
+		});
+
+		let Some((file, compression)) = opened else {
+			// Fall back to a directory listing, if enabled
+			if may_list_directory
+				&& local_path.file_name().is_some_and(|name| name == "index.html")
+				&& let Some(dir) = local_path.parent().and_then(|dir| self.check_path(dir))
+				&& dir.is_dir()
+			{
+				return SourceResponse::new_some(
+					Blob::from(generate_directory_listing(&dir, self.options.serve_dotfiles)),
+					TileCompression::Uncompressed,
+					"text/html",
+				);
+			}
 			return None;
 		};
 
 		let mut buffer = Vec::new();
-		BufReader::new(file).read_to_end(&mut buffer).unwrap();
+		BufReader::new(file).read_to_end(&mut buffer).ok()?;
 
 		SourceResponse::new_some(Blob::from(buffer), compression, &mime)
 	}
+
+	// Blocking part of `get_range`
+	fn read_range(&self, url: &Url, range: &HttpRange) -> Option<RangeResponse> {
+		let (local_path, _) = self.resolve_path(url)?;
+		let mut file = self.open_file(&local_path)?;
+		let metadata = file.metadata().ok().filter(|metadata| metadata.is_file())?;
+		let total = metadata.len();
+
+		let Some(range) = range.resolve(total) else {
+			return Some(RangeResponse::Unsatisfiable { total });
+		};
+		let end = range.end.min(range.start + MAX_RANGE_LENGTH);
+
+		let mut buffer = vec![0; (end - range.start) as usize];
+		file.seek(SeekFrom::Start(range.start)).ok()?;
+		file.read_exact(&mut buffer).ok()?;
+
+		Some(RangeResponse::Partial {
+			blob: Blob::from(buffer),
+			start: range.start,
+			total,
+			mime: guess_mime(&local_path),
+		})
+	}
+}
+
+/// Generates an HTML5 page listing the entries of `dir`, similar to Apache's autoindex.
+///
+/// Directories are listed first, each group sorted by name. Links are relative,
+/// so the page must be served under a URL ending with `/`.
+/// Entries starting with `.` are only listed with `show_dotfiles`.
+pub fn generate_directory_listing(dir: &Path, show_dotfiles: bool) -> String {
+	let mut entries = std::fs::read_dir(dir)
+		.map(|iter| {
+			iter
+				.filter_map(|entry| entry.ok())
+				.filter(|entry| show_dotfiles || !entry.file_name().to_string_lossy().starts_with('.'))
+				.collect::<Vec<_>>()
+		})
+		.unwrap_or_default();
+	entries.sort_by_key(|entry| (!entry.path().is_dir(), entry.file_name()));
+
+	let title = escape_html(&dir.file_name().unwrap_or_default().to_string_lossy());
+	let date_format = parse_borrowed::<2>("[year]-[month]-[day] [hour]:[minute]").unwrap();
+
+	let mut html = String::new();
+	html.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+	html.push_str(&format!("<title>Index of {title}</title>\n</head>\n<body>\n"));
+	html.push_str(&format!("<h1>Index of {title}</h1>\n<table>\n"));
+	html.push_str("<tr><th>Name</th><th>Size</th><th>Last modified</th></tr>\n");
+	html.push_str("<tr><td><a href=\"../\">../</a></td><td></td><td></td></tr>\n");
+
+	for entry in entries {
+		let Ok(metadata) = entry.metadata() else {
+			continue;
+		};
+		let mut name = entry.file_name().to_string_lossy().to_string();
+		let size = if metadata.is_dir() {
+			name.push('/');
+			String::from("-")
+		} else {
+			metadata.len().to_string()
+		};
+		let modified = metadata
+			.modified()
+			.ok()
+			.and_then(|time| OffsetDateTime::from(time).format(&date_format).ok())
+			.unwrap_or_default();
+		html.push_str(&format!(
+			"<tr><td><a href=\"{}\">{}</a></td><td>{size}</td><td>{modified}</td></tr>\n",
+			escape_html(&encode_uri_component(&name)),
+			escape_html(&name),
+		));
+	}
+
+	html.push_str("</table>\n</body>\n</html>\n");
+	html
+}
+
+fn escape_html(text: &str) -> String {
+	text
+		.replace('&', "&amp;")
+		.replace('<', "&lt;")
+		.replace('>', "&gt;")
+		.replace('"', "&quot;")
+}
+
+fn encode_uri_component(text: &str) -> String {
+	let mut result = String::new();
+	for byte in text.bytes() {
+		if byte.is_ascii_alphanumeric() || b"-_.~/".contains(&byte) {
+			result.push(byte as char);
+		} else {
+			result.push_str(&format!("%{byte:02X}"));
+		}
+	}
+	result
+}
+
+#[async_trait]
+impl StaticSourceTrait for Folder {
+	fn get_type(&self) -> &str {
+		"folder"
+	}
+
+	// Returns the name of the folder
+	#[cfg(test)]
+	fn get_name(&self) -> &str {
+		&self.name
+	}
+
+	// Gets the data at the given path and responds with a compressed or uncompressed version
+	// based on the accept header. The filesystem is accessed on the blocking thread pool.
+	async fn get_data(&self, url: &Url, _accept: &TargetCompression) -> Option<SourceResponse> {
+		let (folder, url) = (self.clone(), url.clone());
+		tokio::task::spawn_blocking(move || folder.read_data(&url)).await.ok()?
+	}
+
+	// Reads the requested bytes of the uncompressed file, without loading the rest of it.
+	// At most `MAX_RANGE_LENGTH` bytes are read.
+	async fn get_range(&self, url: &Url, range: &HttpRange) -> Option<RangeResponse> {
+		let (folder, url, range) = (self.clone(), url.clone(), *range);
+		tokio::task::spawn_blocking(move || folder.read_range(&url, &range))
+			.await
+			.ok()?
+	}
+
+	fn supports_ranges(&self) -> bool {
+		true
+	}
 }
 
 impl Debug for Folder {
@@ -99,6 +328,7 @@ impl Debug for Folder {
 		f.debug_struct("Folder")
 			.field("folder", &self.folder)
 			.field("name", &self.name)
+			.field("options", &self.options)
 			.finish()
 	}
 }
@@ -114,17 +344,23 @@ mod tests {
 
 		let debug: String = format!("{folder:?}");
 		assert!(debug.starts_with("Folder { folder: \""));
-		assert!(debug.ends_with("testdata\", name: \"../testdata\" }"));
+		assert!(debug.ends_with(
+			"testdata\", name: \"../testdata\", options: FolderOptions { directory_listing: false, follow_symlinks: true, serve_dotfiles: false } }"
+		));
 
 		// Test get_name function
 		assert_eq!(folder.get_name(), "../testdata");
 
 		// Test get_data function with a non-existent file
-		let result = folder.get_data(&Url::from("recipes/Queijo.txt"), &TargetCompression::from_none());
+		let result = folder
+			.get_data(&Url::from("recipes/Queijo.txt"), &TargetCompression::from_none())
+			.await;
 		assert!(result.is_none());
 
 		// Test get_data function with an existing uncompressed file
-		let result = folder.get_data(&Url::from("berlin.mbtiles"), &TargetCompression::from_none());
+		let result = folder
+			.get_data(&Url::from("berlin.mbtiles"), &TargetCompression::from_none())
+			.await;
 		assert!(result.is_some());
 
 		let result = result.unwrap();
@@ -132,6 +368,42 @@ mod tests {
 		assert_eq!(result.compression, TileCompression::Uncompressed);
 	}
 
+	#[tokio::test]
+	async fn byte_ranges() {
+		let folder = Folder::from(Path::new("../testdata")).unwrap();
+		assert!(folder.supports_ranges());
+		let url = Url::from("berlin.mbtiles");
+		let expected = std::fs::read("../testdata/berlin.mbtiles").unwrap();
+		let total = expected.len();
+
+		let partial = |start: usize, end: usize| RangeResponse::Partial {
+			blob: Blob::from(&expected[start..end]),
+			start: start as u64,
+			total: total as u64,
+			mime: String::from("application/octet-stream"),
+		};
+
+		// a range in the middle of the file
+		let response = folder.get_range(&url, &HttpRange::FromTo(1000, 1999)).await;
+		assert_eq!(response, Some(partial(1000, 2000)));
+
+		// the last bytes of the file
+		let response = folder.get_range(&url, &HttpRange::Suffix(100)).await;
+		assert_eq!(response, Some(partial(total - 100, total)));
+
+		// open ended ranges are capped
+		let response = folder.get_range(&url, &HttpRange::From(0)).await;
+		assert_eq!(response, Some(partial(0, MAX_RANGE_LENGTH as usize)));
+
+		// a range beyond the end of the file
+		let response = folder.get_range(&url, &HttpRange::From(total as u64)).await;
+		assert_eq!(response, Some(RangeResponse::Unsatisfiable { total: total as u64 }));
+
+		// missing files are left to the other sources
+		let response = folder.get_range(&Url::from("missing.txt"), &HttpRange::From(0)).await;
+		assert_eq!(response, None);
+	}
+
 	#[tokio::test]
 	async fn directory_with_index_html() {
 		// Setup: Create a temporary directory and place an index.html file inside it
@@ -148,6 +420,7 @@ mod tests {
 		// Attempt to retrieve data from the directory, expecting to get the contents of index.html
 		let response = folder
 			.get_data(&Url::from("testdir"), &TargetCompression::from_none())
+			.await
 			.unwrap();
 
 		let result = response.blob.as_str();
@@ -175,6 +448,7 @@ mod tests {
 		// Test Brotli compression
 		let response_br = folder
 			.get_data(&Url::from("compressed.txt"), &TargetCompression::from_none())
+			.await
 			.unwrap();
 
 		assert_eq!(response_br.blob.as_str(), "Brotli compressed content");
@@ -186,6 +460,7 @@ mod tests {
 		// Test Gzip compression
 		let response_gz = folder
 			.get_data(&Url::from("compressed.txt"), &TargetCompression::from_none())
+			.await
 			.unwrap();
 
 		assert_eq!(response_gz.blob.as_str(), "Gzip compressed content");
@@ -194,4 +469,154 @@ mod tests {
 		// Cleanup
 		temp_dir.close().unwrap();
 	}
+
+	#[test]
+	fn directory_listing_of_testdata() {
+		let html = generate_directory_listing(Path::new("../testdata"), false);
+		assert!(html.starts_with("<!DOCTYPE html>\n<html lang=\"en\">"));
+		assert!(html.ends_with("</html>\n"));
+		assert!(html.contains("<title>Index of testdata</title>"));
+		for filename in ["berlin.mbtiles", "berlin.pmtiles", "berlin.vpl", "static.tar.gz"] {
+			assert!(
+				html.contains(&format!("<a href=\"{filename}\">{filename}</a>")),
+				"listing should contain {filename}"
+			);
+		}
+		let size = std::fs::metadata("../testdata/berlin.mbtiles").unwrap().len();
+		assert!(html.contains(&format!(
+			"<a href=\"berlin.mbtiles\">berlin.mbtiles</a></td><td>{size}</td>"
+		)));
+	}
+
+	#[test]
+	fn directory_listing_escapes_names() {
+		let temp_dir = assert_fs::TempDir::new().unwrap();
+		std::fs::create_dir(temp_dir.path().join("sub dir")).unwrap();
+		std::fs::write(temp_dir.path().join("a<b>.txt"), b"12345").unwrap();
+
+		let html = generate_directory_listing(temp_dir.path(), false);
+		assert!(html.contains("<a href=\"sub%20dir/\">sub dir/</a></td><td>-</td>"));
+		assert!(html.contains("<a href=\"a%3Cb%3E.txt\">a&lt;b&gt;.txt</a></td><td>5</td>"));
+		// directories are listed before files
+		assert!(html.find("sub dir/").unwrap() < html.find("a&lt;b&gt;.txt").unwrap());
+	}
+
+	#[tokio::test]
+	async fn directory_listing_only_when_enabled() {
+		let temp_dir = assert_fs::TempDir::new().unwrap();
+		std::fs::create_dir(temp_dir.path().join("dir")).unwrap();
+		std::fs::write(temp_dir.path().join("dir/file.txt"), b"content").unwrap();
+		let url = Url::from("dir/index.html");
+
+		let folder = Folder::with_directory_listing(temp_dir.path(), false).unwrap();
+		assert!(folder.get_data(&url, &TargetCompression::from_none()).await.is_none());
+
+		let folder = Folder::with_directory_listing(temp_dir.path(), true).unwrap();
+		let response = folder.get_data(&url, &TargetCompression::from_none()).await.unwrap();
+		assert_eq!(response.mime, "text/html");
+		assert!(response.blob.as_str().contains("<a href=\"file.txt\">file.txt</a>"));
+
+		// a directory URL without trailing slash gets no listing
+		assert!(
+			folder
+				.get_data(&Url::from("dir"), &TargetCompression::from_none())
+				.await
+				.is_none()
+		);
+
+		// a missing directory gets no listing
+		let url = Url::from("missing/index.html");
+		assert!(folder.get_data(&url, &TargetCompression::from_none()).await.is_none());
+	}
+
+	async fn get(folder: &Folder, url: &str) -> Option<String> {
+		let response = folder
+			.get_data(&Url::from(url), &TargetCompression::from_none())
+			.await?;
+		Some(response.blob.as_str().to_string())
+	}
+
+	#[tokio::test]
+	async fn traversal_is_rejected() {
+		let temp_dir = assert_fs::TempDir::new().unwrap();
+		std::fs::create_dir(temp_dir.path().join("public")).unwrap();
+		std::fs::write(temp_dir.path().join("secret.txt"), b"secret").unwrap();
+		std::fs::write(temp_dir.path().join("public/file.txt"), b"public").unwrap();
+		let folder = Folder::from(&temp_dir.path().join("public")).unwrap();
+
+		assert_eq!(get(&folder, "file.txt").await.as_deref(), Some("public"));
+		for url in [
+			"../secret.txt",
+			"sub/../../secret.txt",
+			"./../secret.txt",
+			"%2e%2e/secret.txt",
+			"%2E%2E%2Fsecret.txt",
+			"..%5csecret.txt",
+			"..\\secret.txt",
+		] {
+			assert_eq!(get(&folder, url).await, None, "{url}");
+			let range = folder.get_range(&Url::from(url), &HttpRange::From(0)).await;
+			assert_eq!(range, None, "{url}");
+		}
+	}
+
+	#[cfg(unix)]
+	#[tokio::test]
+	async fn symlinks() {
+		use std::os::unix::fs::symlink;
+
+		let temp_dir = assert_fs::TempDir::new().unwrap();
+		let root = temp_dir.path().join("public");
+		std::fs::create_dir_all(root.join("assets")).unwrap();
+		std::fs::create_dir(temp_dir.path().join("outside")).unwrap();
+		std::fs::write(root.join("assets/app.js"), b"app").unwrap();
+		std::fs::write(temp_dir.path().join("outside/secret.txt"), b"secret").unwrap();
+		symlink(root.join("assets"), root.join("linked")).unwrap();
+		symlink(temp_dir.path().join("outside"), root.join("escape")).unwrap();
+		symlink(temp_dir.path().join("outside/secret.txt"), root.join("escape.txt")).unwrap();
+
+		// symlinks are followed by default, as long as they stay inside the folder
+		let folder = Folder::from(&root).unwrap();
+		assert_eq!(get(&folder, "linked/app.js").await.as_deref(), Some("app"));
+		assert_eq!(get(&folder, "escape/secret.txt").await, None);
+		assert_eq!(get(&folder, "escape.txt").await, None);
+		let range = folder.get_range(&Url::from("escape.txt"), &HttpRange::From(0)).await;
+		assert_eq!(range, None);
+
+		let options = FolderOptions {
+			follow_symlinks: false,
+			..FolderOptions::default()
+		};
+		let folder = Folder::with_options(&root, options).unwrap();
+		assert_eq!(get(&folder, "assets/app.js").await.as_deref(), Some("app"));
+		assert_eq!(get(&folder, "linked/app.js").await, None);
+		assert_eq!(get(&folder, "escape/secret.txt").await, None);
+	}
+
+	#[tokio::test]
+	async fn dotfiles() {
+		let temp_dir = assert_fs::TempDir::new().unwrap();
+		std::fs::create_dir(temp_dir.path().join(".git")).unwrap();
+		std::fs::write(temp_dir.path().join(".git/config"), b"config").unwrap();
+		std::fs::write(temp_dir.path().join(".hidden"), b"hidden").unwrap();
+		std::fs::write(temp_dir.path().join("visible.txt"), b"visible").unwrap();
+
+		let folder = Folder::with_directory_listing(temp_dir.path(), true).unwrap();
+		assert_eq!(get(&folder, "visible.txt").await.as_deref(), Some("visible"));
+		assert_eq!(get(&folder, ".hidden").await, None);
+		assert_eq!(get(&folder, ".git/config").await, None);
+		let listing = get(&folder, "index.html").await.unwrap();
+		assert!(listing.contains("visible.txt"));
+		assert!(!listing.contains(".hidden") && !listing.contains(".git"));
+
+		let options = FolderOptions {
+			directory_listing: true,
+			serve_dotfiles: true,
+			..FolderOptions::default()
+		};
+		let folder = Folder::with_options(temp_dir.path(), options).unwrap();
+		assert_eq!(get(&folder, ".hidden").await.as_deref(), Some("hidden"));
+		assert_eq!(get(&folder, ".git/config").await.as_deref(), Some("config"));
+		assert!(get(&folder, "index.html").await.unwrap().contains(".hidden"));
+	}
 }
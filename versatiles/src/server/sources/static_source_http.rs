@@ -0,0 +1,241 @@
+//! Static source that forwards requests to an upstream HTTP server, e.g. to serve styles and fonts
+//! of another server next to local tiles.
+
+use super::{super::Url, SourceResponse, static_source::StaticSourceTrait};
+use anyhow::{Result, bail, ensure};
+use async_trait::async_trait;
+use reqwest::{
+	Client, StatusCode,
+	header::{CONTENT_ENCODING, CONTENT_TYPE},
+};
+use std::{
+	fmt::Debug,
+	sync::Mutex,
+	time::{Duration, Instant},
+};
+use versatiles_core::{Blob, LimitedCache, TileCompression, utils::TargetCompression};
+use versatiles_derive::context;
+
+type CacheEntry = (Instant, Option<SourceResponse>);
+
+/// Maximum number of upstream responses kept in the cache.
+const CACHE_ENTRIES: usize = 1024;
+/// Time allowed for connecting to the upstream server.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+/// Time allowed for a whole upstream request, including reading the body.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+pub struct HttpStaticSource {
+	base_url: reqwest::Url,
+	name: String,
+	client: Client,
+	cache_responses: bool,
+	cache_ttl: Duration,
+	cache: Mutex<LimitedCache<String, CacheEntry>>,
+}
+
+impl HttpStaticSource {
+	/// Creates a source that resolves request paths relative to `base_url`, so with
+	/// `https://example.org/assets` a request for `/style.json` fetches `https://example.org/assets/style.json`.
+	///
+	/// If `cache_responses` is set, upstream responses (including "not found") are kept in memory for `cache_ttl`.
+	/// At most [`CACHE_ENTRIES`] responses are cached; the least recently used ones are evicted first.
+	#[context("creating HTTP static source for '{base_url}'")]
+	pub fn new(base_url: &reqwest::Url, cache_responses: bool, cache_ttl: Duration) -> Result<HttpStaticSource> {
+		let mut base_url = base_url.clone();
+		ensure!(
+			matches!(base_url.scheme(), "http" | "https"),
+			"unsupported scheme '{}'",
+			base_url.scheme()
+		);
+		if !base_url.path().ends_with('/') {
+			base_url.set_path(&format!("{}/", base_url.path()));
+		}
+
+		Ok(HttpStaticSource {
+			name: base_url.to_string(),
+			base_url,
+			client: Client::builder()
+				.connect_timeout(CONNECT_TIMEOUT)
+				.timeout(REQUEST_TIMEOUT)
+				.build()?,
+			cache_responses,
+			cache_ttl,
+			cache: Mutex::new(LimitedCache::with_maximum_length(CACHE_ENTRIES)),
+		})
+	}
+
+	/// Returns `None` if the upstream server answers with `404 Not Found`.
+	#[context("fetching '{path}' from '{}'", self.base_url)]
+	async fn fetch(&self, path: &str) -> Result<Option<SourceResponse>> {
+		// "./" keeps the first segment from being parsed as a scheme
+		let url = self.base_url.join(&format!("./{path}"))?;
+		let response = self.client.get(url).send().await?;
+		if response.status() == StatusCode::NOT_FOUND {
+			return Ok(None);
+		}
+		let response = response.error_for_status()?;
+
+		let headers = response.headers();
+		let mime = headers
+			.get(CONTENT_TYPE)
+			.and_then(|value| value.to_str().ok())
+			.unwrap_or("application/octet-stream")
+			.to_string();
+		let compression = match headers.get(CONTENT_ENCODING).map(|value| value.to_str()) {
+			None | Some(Ok("identity")) => TileCompression::Uncompressed,
+			Some(Ok("gzip")) => TileCompression::Gzip,
+			Some(Ok("br")) => TileCompression::Brotli,
+			Some(encoding) => bail!("unsupported content encoding {encoding:?}"),
+		};
+
+		let blob = Blob::from(response.bytes().await?.to_vec());
+		Ok(SourceResponse::new_some(blob, compression, &mime))
+	}
+
+	fn get_cached(&self, path: &str) -> Option<Option<SourceResponse>> {
+		let (time, response) = self.cache.lock().unwrap().get(&path.to_string())?;
+		(time.elapsed() < self.cache_ttl).then_some(response)
+	}
+
+	fn set_cached(&self, path: &str, response: &Option<SourceResponse>) {
+		let mut cache = self.cache.lock().unwrap();
+		cache.add(path.to_string(), (Instant::now(), response.clone()));
+	}
+}
+
+#[async_trait]
+impl StaticSourceTrait for HttpStaticSource {
+	fn get_type(&self) -> &str {
+		"http"
+	}
+
+	#[cfg(test)]
+	fn get_name(&self) -> &str {
+		&self.name
+	}
+
+	async fn get_data(&self, url: &Url, _accept: &TargetCompression) -> Option<SourceResponse> {
+		let segments = url.as_vec();
+		if segments.iter().any(|segment| segment == "." || segment == "..") {
+			return None;
+		}
+		let mut path = segments.join("/");
+		if url.is_dir() && !path.is_empty() {
+			path.push('/');
+		}
+
+		if self.cache_responses
+			&& let Some(response) = self.get_cached(&path)
+		{
+			return response;
+		}
+
+		let response = match self.fetch(&path).await {
+			Ok(response) => response,
+			Err(err) => {
+				log::warn!("{err:#}");
+				return None;
+			}
+		};
+
+		if self.cache_responses {
+			self.set_cached(&path, &response);
+		}
+		response
+	}
+}
+
+impl Debug for HttpStaticSource {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("HttpStaticSource")
+			.field("base_url", &self.name)
+			.field("cache_responses", &self.cache_responses)
+			.field("cache_ttl", &self.cache_ttl)
+			.finish()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use axum::{Router, http::header, routing::get};
+	use std::sync::{
+		Arc,
+		atomic::{AtomicUsize, Ordering},
+	};
+	use tokio::net::TcpListener;
+
+	/// Starts an upstream server and returns its base URL and the number of requests to `/count`.
+	async fn upstream() -> Result<(reqwest::Url, Arc<AtomicUsize>)> {
+		let counter = Arc::new(AtomicUsize::new(0));
+		let count = Arc::clone(&counter);
+		let app = Router::new()
+			.route(
+				"/assets/style.json",
+				get(|| async { ([(header::CONTENT_TYPE, "application/json")], r#"{"version":8}"#) }),
+			)
+			.route("/assets/font.pbf", get(|| async { vec![1u8, 2, 3] }))
+			.route(
+				"/assets/count",
+				get(move || async move { count.fetch_add(1, Ordering::SeqCst).to_string() }),
+			);
+
+		let listener = TcpListener::bind("127.0.0.1:0").await?;
+		let addr = listener.local_addr()?;
+		tokio::spawn(async move { axum::serve(listener, app).await });
+		Ok((reqwest::Url::parse(&format!("http://{addr}/assets"))?, counter))
+	}
+
+	async fn request(source: &HttpStaticSource, path: &str) -> Option<SourceResponse> {
+		source.get_data(&Url::from(path), &TargetCompression::from_none()).await
+	}
+
+	#[tokio::test]
+	async fn forwards_requests() -> Result<()> {
+		let (base_url, _) = upstream().await?;
+		let source = HttpStaticSource::new(&base_url, false, Duration::from_secs(60))?;
+		assert_eq!(source.get_type(), "http");
+		assert!(source.get_name().ends_with("/assets/"));
+
+		let response = request(&source, "/style.json").await.unwrap();
+		assert_eq!(response.blob.as_str(), r#"{"version":8}"#);
+		assert_eq!(response.mime, "application/json");
+		assert_eq!(response.compression, TileCompression::Uncompressed);
+
+		let response = request(&source, "/font.pbf").await.unwrap();
+		assert_eq!(response.blob.as_slice(), &[1, 2, 3]);
+		assert_eq!(response.mime, "application/octet-stream");
+
+		assert!(request(&source, "/missing.json").await.is_none());
+		assert!(request(&source, "/../assets/style.json").await.is_none());
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn caches_responses() -> Result<()> {
+		let (base_url, counter) = upstream().await?;
+
+		let source = HttpStaticSource::new(&base_url, false, Duration::from_secs(60))?;
+		assert_eq!(request(&source, "/count").await.unwrap().blob.as_str(), "0");
+		assert_eq!(request(&source, "/count").await.unwrap().blob.as_str(), "1");
+
+		let source = HttpStaticSource::new(&base_url, true, Duration::from_secs(60))?;
+		assert_eq!(request(&source, "/count").await.unwrap().blob.as_str(), "2");
+		assert_eq!(request(&source, "/count").await.unwrap().blob.as_str(), "2");
+		assert_eq!(counter.load(Ordering::SeqCst), 3);
+		assert_eq!(source.cache.lock().unwrap().capacity(), CACHE_ENTRIES);
+
+		let source = HttpStaticSource::new(&base_url, true, Duration::from_millis(50))?;
+		assert_eq!(request(&source, "/count").await.unwrap().blob.as_str(), "3");
+		tokio::time::sleep(Duration::from_millis(100)).await;
+		assert_eq!(request(&source, "/count").await.unwrap().blob.as_str(), "4");
+		Ok(())
+	}
+
+	#[test]
+	fn rejects_other_schemes() {
+		let url = reqwest::Url::parse("ftp://example.org/").unwrap();
+		assert!(HttpStaticSource::new(&url, false, Duration::from_secs(1)).is_err());
+	}
+}
@@ -6,7 +6,7 @@ use std::{collections::HashMap, env::current_dir, ffi::OsStr, fmt::Debug, fs::Fi
 use tar::{Archive, EntryType};
 use versatiles_core::{
 	Blob, TileCompression,
-	utils::{TargetCompression, decompress_brotli, decompress_gzip},
+	utils::{TargetCompression, decompress},
 };
 use versatiles_derive::context;
 
@@ -52,10 +52,11 @@ impl TarFile {
 		drop(file);
 
 		for part in path.to_str().unwrap().rsplit('.') {
-			match part {
-				"tar" => break,
-				"gz" => buffer = decompress_gzip(&buffer)?,
-				"br" => buffer = decompress_brotli(&buffer)?,
+			if part == "tar" {
+				break;
+			}
+			match TileCompression::from_extension(part) {
+				Some(compression) if compression != Uncompressed => buffer = decompress(buffer, compression)?,
 				_ => bail!("{path:?} must be a name of a tar file"),
 			}
 		}
@@ -77,11 +78,7 @@ impl TarFile {
 			let compression = entry_path
 				.extension()
 				.and_then(OsStr::to_str)
-				.map(|ext| match ext {
-					"br" => Brotli,
-					"gz" => Gzip,
-					_ => Uncompressed,
-				})
+				.and_then(TileCompression::from_extension)
 				.unwrap_or(Uncompressed);
 
 			if compression != Uncompressed {
@@ -132,7 +129,6 @@ impl TarFile {
 
 #[async_trait]
 impl StaticSourceTrait for TarFile {
-	#[cfg(test)]
 	fn get_type(&self) -> &str {
 		"tar"
 	}
@@ -142,7 +138,7 @@ impl StaticSourceTrait for TarFile {
 		&self.name
 	}
 
-	fn get_data(&self, url: &Url, accept: &TargetCompression) -> Option<SourceResponse> {
+	async fn get_data(&self, url: &Url, accept: &TargetCompression) -> Option<SourceResponse> {
 		use TileCompression::*;
 
 		let file_entry = self.lookup.get(&url.str[1..])?.to_owned();
@@ -244,24 +240,24 @@ mod tests {
 		let file = make_test_tar(compression_tar).await;
 		let mut tar_file = TarFile::from(&file)?;
 
-		test2(&mut tar_file, compression_tar, TileCompression::Uncompressed)?;
-		test2(&mut tar_file, compression_tar, TileCompression::Gzip)?;
-		test2(&mut tar_file, compression_tar, TileCompression::Brotli)?;
+		test2(&mut tar_file, compression_tar, TileCompression::Uncompressed).await?;
+		test2(&mut tar_file, compression_tar, TileCompression::Gzip).await?;
+		test2(&mut tar_file, compression_tar, TileCompression::Brotli).await?;
 
 		return Ok(());
 
-		fn test2(
+		async fn test2(
 			tar_file: &mut TarFile,
 			compression_tar: TileCompression,
 			compression_accept: TileCompression,
 		) -> Result<()> {
 			let accept = TargetCompression::from(compression_accept);
 
-			let result = tar_file.get_data(&Url::from("non_existing_file"), &accept);
+			let result = tar_file.get_data(&Url::from("non_existing_file"), &accept).await;
 			assert!(result.is_none());
 
 			//let path = ["0", "0", "0"];
-			let result = tar_file.get_data(&Url::from("tiles.json"), &accept);
+			let result = tar_file.get_data(&Url::from("tiles.json"), &accept).await;
 			assert!(result.is_some());
 
 			let result = result.unwrap();
@@ -0,0 +1,136 @@
+use super::super::utils::{Url, guess_mime};
+use super::{SourceResponse, static_source::StaticSourceTrait};
+use anyhow::{Result, ensure};
+use async_trait::async_trait;
+use std::{collections::HashMap, fmt::Debug, path::Path};
+use versatiles_container::{TilesRuntime, VersaTilesReader};
+use versatiles_core::{Blob, TileCompression, utils::TargetCompression};
+use versatiles_derive::context;
+
+/// Serves the attachments (e.g. style sprites and glyphs) of a `.versatiles` container.
+pub struct VersaTilesAttachments {
+	lookup: HashMap<String, (Blob, TileCompression, String)>,
+	name: String,
+}
+
+impl VersaTilesAttachments {
+	/// Reads all attachments into memory.
+	#[context("loading attachments from versatiles file: {path:?}")]
+	pub async fn open(path: &Path) -> Result<Self> {
+		let reader = VersaTilesReader::open_path(path, TilesRuntime::default()).await?;
+
+		let mut lookup = HashMap::new();
+		for attachment_path in reader.attachment_paths() {
+			let (blob, compression) = reader.get_attachment(attachment_path).await?.unwrap();
+			let mime = guess_mime(Path::new(attachment_path));
+			log::trace!("Adding attachment: {attachment_path} ({compression:?})");
+			lookup.insert(attachment_path.to_string(), (blob, compression, mime));
+		}
+		ensure!(!lookup.is_empty(), "{path:?} doesn't contain attachments");
+
+		Ok(Self {
+			lookup,
+			name: path.to_string_lossy().to_string(),
+		})
+	}
+}
+
+#[async_trait]
+impl StaticSourceTrait for VersaTilesAttachments {
+	fn get_type(&self) -> &str {
+		"versatiles"
+	}
+
+	#[cfg(test)]
+	fn get_name(&self) -> &str {
+		&self.name
+	}
+
+	async fn get_data(&self, url: &Url, _accept: &TargetCompression) -> Option<SourceResponse> {
+		let (blob, compression, mime) = self.lookup.get(&url.str[1..])?;
+		SourceResponse::new_some(blob.clone(), *compression, mime)
+	}
+}
+
+impl Debug for VersaTilesAttachments {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("VersaTilesAttachments")
+			.field("name", &self.name)
+			.finish()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use assert_fs::NamedTempFile;
+	use versatiles_container::{AttachmentWriter, MockReader, MockReaderProfile, TilesWriter, VersaTilesWriter};
+
+	const SPRITE_JSON: &str = r#"{"icon":{"x":0,"y":0,"width":16,"height":16,"pixelRatio":1}}"#;
+	const GLYPHS: &[u8] = &[10, 4, 0x4e, 0x6f, 0x74, 0x6f];
+
+	/// Writes a small container with a sprite and one glyph range.
+	async fn make_test_versatiles() -> NamedTempFile {
+		let file = NamedTempFile::new("map.versatiles").unwrap();
+		let mut reader = MockReader::new_mock_profile(MockReaderProfile::Pbf).unwrap();
+		VersaTilesWriter::write_to_path(&mut reader, &file, TilesRuntime::default())
+			.await
+			.unwrap();
+
+		let mut attachments = AttachmentWriter::new();
+		attachments
+			.add_attachment("sprites/sprite.json", Blob::from(SPRITE_JSON), TileCompression::Gzip)
+			.unwrap();
+		attachments
+			.add_attachment(
+				"fonts/Noto-Sans-Regular/0-255.pbf",
+				Blob::from(GLYPHS),
+				TileCompression::Uncompressed,
+			)
+			.unwrap();
+		attachments.append_to_path(&file).unwrap();
+		file
+	}
+
+	#[tokio::test]
+	async fn get_data() -> Result<()> {
+		let file = make_test_versatiles().await;
+		let source = VersaTilesAttachments::open(&file).await?;
+		assert!(source.get_name().ends_with("map.versatiles"));
+		let accept = TargetCompression::from_none();
+
+		let result = source
+			.get_data(&Url::from("sprites/sprite.json"), &accept)
+			.await
+			.unwrap();
+		assert_eq!(result.mime, "application/json");
+		assert_eq!(result.compression, TileCompression::Gzip);
+
+		let result = source
+			.get_data(&Url::from("fonts/Noto-Sans-Regular/0-255.pbf"), &accept)
+			.await
+			.unwrap();
+		assert_eq!(result.blob.as_slice(), GLYPHS);
+		assert_eq!(result.compression, TileCompression::Uncompressed);
+
+		assert!(
+			source
+				.get_data(&Url::from("sprites/sprite.png"), &accept)
+				.await
+				.is_none()
+		);
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn without_attachments() {
+		let file = NamedTempFile::new("plain.versatiles").unwrap();
+		let mut reader = MockReader::new_mock_profile(MockReaderProfile::Pbf).unwrap();
+		VersaTilesWriter::write_to_path(&mut reader, &file, TilesRuntime::default())
+			.await
+			.unwrap();
+
+		let err = VersaTilesAttachments::open(&file).await.unwrap_err();
+		assert!(format!("{err:?}").contains("doesn't contain attachments"));
+	}
+}
@@ -0,0 +1,38 @@
+use versatiles_core::{TileCompression, TileFormat, json::JsonObject};
+
+/// Metadata about a single tile, served by the `/tiles/{id}/{z}/{x}/{y}/info` endpoint.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TileInfo {
+	pub format: TileFormat,
+	pub compression: TileCompression,
+	/// Size of the tile in bytes, as stored in the source.
+	pub size_bytes: u64,
+}
+
+impl TileInfo {
+	pub fn as_json(&self) -> String {
+		let mut obj = JsonObject::new();
+		obj.set("format", self.format.as_str());
+		obj.set("compression", self.compression.as_str());
+		obj.set("size_bytes", self.size_bytes);
+		obj.stringify()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn as_json() {
+		let info = TileInfo {
+			format: TileFormat::MVT,
+			compression: TileCompression::Gzip,
+			size_bytes: 1234,
+		};
+		assert_eq!(
+			info.as_json(),
+			"{\"compression\":\"gzip\",\"format\":\"mvt\",\"size_bytes\":1234}"
+		);
+	}
+}
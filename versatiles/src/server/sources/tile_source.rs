@@ -1,8 +1,15 @@
-use super::{super::utils::Url, SourceResponse};
-use anyhow::Result;
-use std::{fmt::Debug, sync::Arc};
-use versatiles_container::TileSource;
-use versatiles_core::{Blob, TileCompression, TileCoord, utils::TargetCompression};
+use super::{super::utils::Url, RequestCoalescer, SourceResponse, TileInfo};
+use anyhow::{Result, ensure};
+use std::{
+	fmt::Debug,
+	sync::{Arc, Mutex},
+};
+use versatiles_container::{TileSource, TileSourceMetadata};
+use versatiles_core::{
+	Blob, LimitedCache, TileCompression, TileCoord,
+	json::JsonValue,
+	utils::{CompressionGoal, TargetCompression, recompress},
+};
 use versatiles_derive::context;
 
 // TileSource struct definition
@@ -13,8 +20,17 @@ pub struct ServerTileSource {
 	reader: Arc<Box<dyn TileSource>>, // NO MORE MUTEX! 🚀
 	pub tile_mime: String,
 	pub compression: TileCompression,
+	/// Sources asked, in order, for tiles missing in `reader`.
+	fallback: Vec<Arc<Box<dyn TileSource>>>,
+	/// Shares the result of a tile read with identical requests that arrive while it is in flight.
+	coalescer: Arc<RequestCoalescer>,
+	/// Tiles transcoded for clients that don't accept the stored compression.
+	transcoded: Arc<Mutex<LimitedCache<(TileCoord, TileCompression), Blob>>>,
 }
 
+/// Maximum number of transcoded tiles kept per source.
+const TRANSCODED_CACHE_ENTRIES: usize = 4096;
+
 impl ServerTileSource {
 	// Constructor function for creating a TileSource instance
 	#[context("creating tile source: id='{id}'")]
@@ -29,49 +45,95 @@ impl ServerTileSource {
 			reader,
 			tile_mime,
 			compression,
+			fallback: Vec::new(),
+			coalescer: Arc::new(RequestCoalescer::default()),
+			transcoded: Arc::new(Mutex::new(LimitedCache::with_maximum_length(TRANSCODED_CACHE_ENTRIES))),
 		})
 	}
 
+	/// Serves the tiles in `compression` instead of the compression they are stored in.
+	#[must_use]
+	pub fn with_compression(mut self, compression: TileCompression) -> Self {
+		self.compression = compression;
+		self
+	}
+
+	/// Asks the `fallback` sources, in order, for tiles that are missing in this source.
+	///
+	/// The fallback sources must have the same tile format.
+	pub fn with_fallback(mut self, fallback: Vec<Arc<Box<dyn TileSource>>>) -> Result<Self> {
+		let format = self.metadata().tile_format;
+		for source in &fallback {
+			let fallback_format = source.metadata().tile_format;
+			ensure!(
+				fallback_format == format,
+				"a fallback of tile source '{}' has tile format {fallback_format}, but the source has {format}",
+				self.id
+			);
+		}
+		self.fallback = fallback;
+		Ok(self)
+	}
+
+	/// Metadata of the underlying tile source.
+	pub fn metadata(&self) -> &TileSourceMetadata {
+		self.reader.metadata()
+	}
+
+	/// Number of transcoded tiles currently cached.
+	pub fn transcoded_cache_len(&self) -> usize {
+		self.transcoded.lock().unwrap().len()
+	}
+
 	pub async fn get_source_name(&self) -> String {
 		self.reader.source_type().to_string() // Direct access!
 	}
 
+	/// Returns `true` if `url` (relative to the prefix) requests a tile, like `z/x/y`.
+	pub fn is_tile_url(url: &Url) -> bool {
+		let parts = url.as_vec();
+		parts.len() >= 3 && parts.get(3).is_none_or(|part| part != "info")
+	}
+
 	// Retrieve the tile data as an HTTP response
 	#[context("getting tile data: url={url}")]
-	pub async fn get_data(&self, url: &Url, _accept: &TargetCompression) -> Result<Option<SourceResponse>> {
+	pub async fn get_data(&self, url: &Url, accept: &TargetCompression) -> Result<Option<SourceResponse>> {
 		let parts: Vec<String> = url.as_vec();
 
-		if parts.len() >= 3 {
-			// Parse the tile coordinates
-			let level = parts[0].parse::<u8>().context("value for z is not a number")?;
-			let x = parts[1].parse::<u32>().context("value for x is not a number")?;
-
-			let y: String = parts[2].chars().take_while(|c| c.is_numeric()).collect();
-			let y = y.parse::<u32>().context("value for y is not a number")?;
-
-			// Create a TileCoord instance
-			let coord = TileCoord::new(level, x, y)?;
+		if parts.len() >= 4 && parts[3] == "info" {
+			// Get tile metadata
+			let coord = parse_coord(&parts)?;
+			return Ok(match self.get_tile_info(&coord).await? {
+				Some(info) => SourceResponse::new_some(
+					Blob::from(info.as_json()),
+					TileCompression::Uncompressed,
+					"application/json",
+				),
+				None => None,
+			});
+		} else if parts.len() >= 3 {
+			let coord = parse_coord(&parts)?;
 
 			log::debug!("get tile, prefix: {}, coord: {}", self.prefix, coord.as_json());
 
-			// Get tile data
-			let tile = self.reader.get_tile(&coord).await;
+			// Get tile data, sharing the read with identical in-flight requests
+			let blob = self
+				.coalescer
+				.get_or_fetch(coord, || self.fetch_tile_blob(coord))
+				.await?;
 
 			// If tile data is not found, return a not found response
-			if tile.is_err() {
+			let Some(blob) = blob else {
 				return Ok(None);
-			}
-
-			// If tile data is not found, return a not found response
-			return if let Some(tile) = tile? {
-				Ok(SourceResponse::new_some(
-					tile.into_blob(self.compression)?,
-					self.compression,
-					&self.tile_mime,
-				))
-			} else {
-				Ok(None)
 			};
+
+			let (blob, compression, cache_hit) = self.negotiate_compression(coord, blob, accept)?;
+			return Ok(Some(SourceResponse {
+				blob,
+				compression,
+				mime: self.tile_mime.clone(),
+				cache_hit,
+			}));
 		} else if (parts[0] == "meta.json") || (parts[0] == "tiles.json") {
 			// Get metadata
 			let tile_json = self.build_tile_json().await?;
@@ -81,12 +143,99 @@ impl ServerTileSource {
 				TileCompression::Uncompressed,
 				"application/json",
 			));
+		} else if parts[0] == "preview" {
+			// Get a map page for visual inspection
+			return Ok(SourceResponse::new_some(
+				self.build_preview_html(),
+				TileCompression::Uncompressed,
+				"text/html; charset=utf-8",
+			));
 		}
 
 		// If the request is unknown, return a not found response
 		Ok(None)
 	}
 
+	/// Reads a tile and encodes it in the compression of this source.
+	///
+	/// Missing tiles are looked up in the fallback sources. If no source has the tile, the
+	/// result is `None`, which is served as 404. Encoding errors, and read errors if no other
+	/// source has the tile, are returned, so they are served as 500.
+	#[context("fetching tile {coord:?}")]
+	async fn fetch_tile_blob(&self, coord: TileCoord) -> Result<Option<Blob>> {
+		let mut read_error = None;
+		for reader in std::iter::once(&self.reader).chain(&self.fallback) {
+			match reader.get_tile(&coord).await {
+				Ok(Some(tile)) => return Ok(Some(tile.into_blob(self.compression)?)),
+				Ok(None) => {}
+				Err(err) => {
+					log::debug!("failed to read tile {coord:?}: {err:?}");
+					read_error.get_or_insert(err);
+				}
+			}
+		}
+		read_error.map_or(Ok(None), Err)
+	}
+
+	/// Returns the tile in a compression accepted by the client.
+	///
+	/// If the stored compression is not accepted, the tile is transcoded to an accepted
+	/// compression: brotli before gzip, or gzip before brotli when fast compression is requested,
+	/// otherwise uncompressed. Transcoded tiles are cached per coordinate. The returned flag tells
+	/// whether the tile came from that cache.
+	///
+	/// Raster tiles are returned as stored, because images don't benefit from another compression.
+	/// The response only removes a compression the client doesn't accept.
+	#[context("negotiating compression of tile {coord:?}")]
+	fn negotiate_compression(
+		&self,
+		coord: TileCoord,
+		blob: Blob,
+		accept: &TargetCompression,
+	) -> Result<(Blob, TileCompression, bool)> {
+		if accept.contains(self.compression) || self.metadata().tile_format.is_raster() {
+			return Ok((blob, self.compression, false));
+		}
+
+		use TileCompression::{Brotli, Gzip};
+		let preference = if accept.compression_goal == CompressionGoal::UseBestCompression {
+			[Brotli, Gzip]
+		} else {
+			[Gzip, Brotli]
+		};
+		let target = preference
+			.into_iter()
+			.find(|compression| accept.contains(*compression))
+			.unwrap_or(TileCompression::Uncompressed);
+
+		let key = (coord, target);
+		if let Some(blob) = self.transcoded.lock().unwrap().get(&key) {
+			return Ok((blob, target, true));
+		}
+
+		// Transcode without holding the lock, so other requests are not blocked.
+		let blob = recompress(blob, self.compression, target)?;
+		self.transcoded.lock().unwrap().add(key, blob.clone());
+		Ok((blob, target, false))
+	}
+
+	/// Returns format, compression and stored size of a single tile.
+	///
+	/// Containers read the size from their index, see [`TileSource::get_tile_size`], so the
+	/// tile itself is not loaded.
+	#[context("getting tile info: coord={coord:?}")]
+	pub async fn get_tile_info(&self, coord: &TileCoord) -> Result<Option<TileInfo>> {
+		let Some(size_bytes) = self.reader.get_tile_size(coord).await? else {
+			return Ok(None);
+		};
+		let metadata = self.metadata();
+		Ok(Some(TileInfo {
+			format: metadata.tile_format,
+			compression: metadata.tile_compression,
+			size_bytes,
+		}))
+	}
+
 	#[context("building tilejson for tile source id='{}'", self.id)]
 	async fn build_tile_json(&self) -> Result<Blob> {
 		// Direct access - no lock!
@@ -96,10 +245,43 @@ impl ServerTileSource {
 		let tiles_url = self.prefix.join_as_string("{z}/{x}/{y}");
 		tilejson.set_list("tiles", vec![tiles_url])?;
 
-		Ok(tilejson.into())
+		// Map clients expect the tile size as "tileSize"
+		let mut object = tilejson.as_object();
+		if let Some(tile_size) = tilejson.tile_size {
+			object.set("tileSize", tile_size.size());
+		}
+
+		Ok(Blob::from(object.stringify()))
+	}
+
+	/// Builds a MapLibre page that shows this source, see `preview.html`.
+	fn build_preview_html(&self) -> Blob {
+		// Injected as JSON strings into a script; escaping "<" prevents closing the script tag.
+		let js_string = |text: &str| JsonValue::from(text).stringify().replace('<', "\\u003c");
+		let html = PREVIEW_HTML.replace("{{source_id}}", &js_string(&self.id)).replace(
+			"{{tilejson_url}}",
+			&js_string(&self.prefix.join_as_string("tiles.json")),
+		);
+		Blob::from(html)
 	}
 }
 
+const PREVIEW_HTML: &str = include_str!("preview.html");
+
+// Parse the tile coordinates from the first three url parts: z/x/y
+// The y part may carry a retina suffix and/or a file extension (e.g. "3@2x.png"),
+// both are ignored, so "@2x" URLs map to the same tile.
+#[context("parsing tile coordinates: {parts:?}")]
+fn parse_coord(parts: &[String]) -> Result<TileCoord> {
+	let level = parts[0].parse::<u8>().context("value for z is not a number")?;
+	let x = parts[1].parse::<u32>().context("value for x is not a number")?;
+
+	let y: String = parts[2].chars().take_while(|c| c.is_numeric()).collect();
+	let y = y.parse::<u32>().context("value for y is not a number")?;
+
+	TileCoord::new(level, x, y)
+}
+
 // Debug implementation for ServerTileSource
 impl Debug for ServerTileSource {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -107,6 +289,7 @@ impl Debug for ServerTileSource {
 			.field("reader", &self.reader)
 			.field("tile_mime", &self.tile_mime)
 			.field("compression", &self.compression)
+			.field("fallback", &self.fallback)
 			.finish()
 	}
 }
@@ -118,7 +301,7 @@ mod tests {
 	use anyhow::Result;
 	use rstest::rstest;
 	use versatiles_container::{MockReader, MockReaderProfile};
-	use versatiles_core::TileJSON;
+	use versatiles_core::{TileJSON, json::JsonValue, utils::decompress};
 
 	// Test the constructor function for TileSource
 	#[tokio::test]
@@ -135,6 +318,173 @@ mod tests {
 		Ok(())
 	}
 
+	#[tokio::test]
+	async fn retina_tiles() -> Result<()> {
+		let reader = Arc::new(MockReader::new_mock_profile(MockReaderProfile::Png512)?.boxed());
+		let container = ServerTileSource::from(reader, "prefix")?;
+
+		let tile_json = JsonValue::parse_blob(&container.build_tile_json().await?)?.into_object()?;
+		assert_eq!(tile_json.get_number("tileSize")?, Some(512.0));
+		assert_eq!(tile_json.get_number("tile_size")?, Some(512.0));
+
+		let get_blob = async |url: &str| {
+			container
+				.get_data(&Url::from(url), &TargetCompression::from_none())
+				.await
+				.unwrap()
+				.unwrap()
+				.blob
+		};
+		let blob = get_blob("4/5/6").await;
+		assert_eq!(get_blob("4/5/6@2x").await, blob);
+		assert_eq!(get_blob("4/5/6@2x.png").await, blob);
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn transcode_unaccepted_compression() -> Result<()> {
+		// the mock vector tiles are stored with gzip
+		let reader = Arc::new(MockReader::new_mock_profile(MockReaderProfile::Pbf)?.boxed());
+		let container = ServerTileSource::from(reader, "prefix")?;
+		let url = Url::from("3/1/2");
+
+		let get = async |compressions: &[TileCompression]| {
+			let mut accept = TargetCompression::from_none();
+			compressions.iter().for_each(|c| accept.insert(*c));
+			container.get_data(&url, &accept).await.unwrap().unwrap()
+		};
+
+		let gzip = get(&[TileCompression::Gzip]).await;
+		assert_eq!(gzip.compression, TileCompression::Gzip);
+		let raw = decompress(gzip.blob, TileCompression::Gzip)?;
+
+		let brotli = get(&[TileCompression::Brotli]).await;
+		assert_eq!(brotli.compression, TileCompression::Brotli);
+		assert!(!brotli.cache_hit);
+		assert_eq!(decompress(brotli.blob.clone(), TileCompression::Brotli)?, raw);
+
+		let uncompressed = get(&[]).await;
+		assert_eq!(uncompressed.compression, TileCompression::Uncompressed);
+		assert_eq!(uncompressed.blob, raw);
+
+		// transcoded tiles are cached, tiles in the stored compression are not
+		assert_eq!(container.transcoded.lock().unwrap().len(), 2);
+		let cached = get(&[TileCompression::Brotli]).await;
+		assert_eq!(cached.blob, brotli.blob);
+		assert!(cached.cache_hit);
+		assert!(!get(&[TileCompression::Gzip]).await.cache_hit);
+		assert_eq!(container.transcoded.lock().unwrap().len(), 2);
+
+		// fast compression prefers gzip
+		let mut accept = TargetCompression::from_set(TileCompression::Brotli | TileCompression::Gzip);
+		accept.set_fast_compression();
+		let container = container.with_compression(TileCompression::Uncompressed);
+		let fast = container.get_data(&url, &accept).await?.unwrap();
+		assert_eq!(fast.compression, TileCompression::Gzip);
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn raster_tiles_are_not_transcoded() -> Result<()> {
+		let mut metadata = versatiles_container::TileSourceMetadata::new_builder()
+			.with_format(versatiles_core::TileFormat::PNG)
+			.with_compression(TileCompression::Uncompressed)
+			.with_bbox_pyramid(versatiles_core::TileBBoxPyramid::new_full(4))
+			.build()?;
+		metadata.tile_compression = TileCompression::Gzip;
+		let reader = Arc::new(MockReader::new_mock(metadata)?.boxed());
+		let container = ServerTileSource::from(reader, "prefix")?;
+
+		let response = container
+			.get_data(&Url::from("3/1/2"), &TargetCompression::from(TileCompression::Brotli))
+			.await?
+			.unwrap();
+		assert_eq!(response.compression, TileCompression::Gzip);
+		assert_eq!(container.transcoded_cache_len(), 0);
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn fallback_and_compression_override() -> Result<()> {
+		use versatiles_container::TileSourceMetadata;
+		use versatiles_core::{TileBBoxPyramid, TileFormat};
+
+		let metadata = TileSourceMetadata::new_builder()
+			.with_format(TileFormat::MVT)
+			.with_compression(TileCompression::Gzip)
+			.with_bbox_pyramid(TileBBoxPyramid::new_full(2))
+			.build()?;
+		let partial = Arc::new(MockReader::new_mock(metadata)?.boxed());
+		let full = Arc::new(MockReader::new_mock_profile(MockReaderProfile::Pbf)?.boxed());
+		let container = ServerTileSource::from(partial, "prefix")?
+			.with_compression(TileCompression::Brotli)
+			.with_fallback(vec![full])?;
+
+		let get = async |url: &str| {
+			let mut accept = TargetCompression::from_none();
+			accept.insert(TileCompression::Brotli);
+			container.get_data(&Url::from(url), &accept).await.unwrap()
+		};
+
+		// level 2 is read from the source itself, level 5 from the fallback
+		for url in ["2/1/1", "5/3/4"] {
+			let response = get(url).await.unwrap();
+			assert_eq!(response.compression, TileCompression::Brotli, "{url}");
+			assert_eq!(
+				decompress(response.blob, TileCompression::Brotli)?.as_slice(),
+				versatiles_container::MOCK_BYTES_PBF
+			);
+		}
+		// level 7 is in neither source
+		assert!(get("7/3/4").await.is_none());
+
+		let png = Arc::new(MockReader::new_mock_profile(MockReaderProfile::Png)?.boxed());
+		let err = ServerTileSource::from(png, "png")?
+			.with_fallback(vec![Arc::new(
+				MockReader::new_mock_profile(MockReaderProfile::Pbf)?.boxed(),
+			)])
+			.unwrap_err();
+		assert!(
+			format!("{err:?}").contains("a fallback of tile source 'png' has tile format mvt, but the source has png")
+		);
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn preview_page() -> Result<()> {
+		let reader = Arc::new(MockReader::new_mock_profile(MockReaderProfile::Pbf)?.boxed());
+		let container = ServerTileSource::from(reader, "<osm>")?;
+
+		let response = container
+			.get_data(&Url::from("preview"), &TargetCompression::from_none())
+			.await?
+			.unwrap();
+		assert_eq!(response.mime, "text/html; charset=utf-8");
+
+		let html = response.blob.as_str();
+		assert!(html.contains("new maplibregl.Map("));
+		assert!(html.contains(r#"const sourceId = "\u003cosm>";"#));
+		assert!(html.contains(r#"const tileJsonUrl = "/tiles/\u003cosm>/tiles.json";"#));
+		assert!(!html.contains("{{"));
+
+		Ok(())
+	}
+
+	#[rstest]
+	#[case("3/4/5", (3, 4, 5))]
+	#[case("3/4/5.png", (3, 4, 5))]
+	#[case("3/4/5@2x", (3, 4, 5))]
+	#[case("3/4/5@2x.webp", (3, 4, 5))]
+	fn parse_coord_ignores_suffixes(#[case] url: &str, #[case] expected: (u8, u32, u32)) -> Result<()> {
+		let coord = parse_coord(&Url::from(url).as_vec())?;
+		assert_eq!((coord.level, coord.x, coord.y), expected);
+		Ok(())
+	}
+
 	// Test the debug function
 	#[test]
 	fn debug() -> Result<()> {
@@ -143,7 +493,7 @@ mod tests {
 		// Updated expected output - no more "Mutex { data: ... }"
 		assert_eq!(
 			format!("{container:?}"),
-			"ServerTileSource { reader: MockReader { parameters: TileSourceMetadata { bbox_pyramid: [2: [0,1,2,3] (3x3), 3: [0,2,4,6] (5x5), 4: [0,0,15,15] (16x16), 5: [0,0,31,31] (32x32), 6: [0,0,63,63] (64x64)], tile_compression: Uncompressed, tile_format: PNG, traversal: Traversal(AnyOrder,full) } }, tile_mime: \"image/png\", compression: Uncompressed }"
+			"ServerTileSource { reader: MockReader { parameters: TileSourceMetadata { bbox_pyramid: [2: [0,1,2,3] (3x3), 3: [0,2,4,6] (5x5), 4: [0,0,15,15] (16x16), 5: [0,0,31,31] (32x32), 6: [0,0,63,63] (64x64)], tile_compression: Uncompressed, tile_format: PNG, traversal: Traversal(AnyOrder,full), tile_size: None, tile_grid: None } }, tile_mime: \"image/png\", compression: Uncompressed, fallback: [] }"
 		);
 		Ok(())
 	}
@@ -208,10 +558,23 @@ mod tests {
 		let reader = runtime.get_reader_from_str(filename).await?;
 		let c = &mut ServerTileSource::from(reader, "prefix")?;
 
+		assert_eq!(&check_response(c, coord, Gzip, exp_mime).await?[0..4], exp_header);
+
+		let info = check_response(c, &format!("{coord}/info"), Uncompressed, "application/json").await?;
+		let info = JsonValue::parse_blob(&Blob::from(info))?.into_object()?;
+		let metadata = c.metadata();
+		let tile = c
+			.reader
+			.get_tile(&parse_coord(&Url::from(coord).as_vec())?)
+			.await?
+			.unwrap();
+		assert_eq!(info.get_string("format")?.unwrap(), metadata.tile_format.as_str());
 		assert_eq!(
-			&check_response(c, coord, Uncompressed, exp_mime).await?[0..4],
-			exp_header
+			info.get_string("compression")?.unwrap(),
+			metadata.tile_compression.as_str()
 		);
+		let size = tile.into_blob(metadata.tile_compression)?.len();
+		assert_eq!(info.get_number("size_bytes")?.unwrap() as u64, size);
 
 		let tile_json = check_response(c, "meta.json", Uncompressed, "application/json").await?;
 		let tile_json = TileJSON::try_from(tile_json)?.as_object();
@@ -224,6 +587,7 @@ mod tests {
 		assert_eq!(check_status(c, "-1/0/0.png").await, 400);
 		assert_eq!(check_status(c, "0/0/-1.png").await, 400);
 		assert_eq!(check_status(c, "16/0/0.png").await, 404);
+		assert_eq!(check_status(c, "16/0/0/info").await, 404);
 
 		Ok(())
 	}
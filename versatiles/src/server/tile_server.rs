@@ -6,15 +6,17 @@
 //! - `routes` composes handlers into an Axum `Router`.
 //! - `encoding` parses `Accept-Encoding` into our internal compression bitset.
 //! - `cors` builds a `CorsLayer` from user-configurable origin patterns.
+//! - `access_log` logs method, path, source, status, size and timing of every request.
+//! - `metrics` counts requests, tiles and bytes for the optional Prometheus endpoint `/metrics`.
 //!
 //! `tile_server.rs` owns *lifecycle* concerns only: configuration ingestion,
 //! building the router, applying cross-cutting middlewares (CORS, backpressure,
-//! timeouts, panic catching), listening on a socket, graceful shutdown, and
-//! a tiny `/status` probe for liveness checks.
+//! timeouts, panic catching), listening on a socket (optionally with TLS), graceful shutdown, and
+//! a tiny `/status` probe plus `/healthz` and `/readyz` probes for load balancers, and, if enabled, `/metrics`.
 
-use super::{cors, routes, sources};
+use super::{TlsConfig, access_log, cors, metrics, routes, sources, tls};
 use crate::config::{Config, TileSourceConfig};
-use anyhow::{Result, bail};
+use anyhow::{Result, anyhow, bail, ensure};
 use arc_swap::ArcSwap;
 use axum::error_handling::HandleErrorLayer;
 use axum::http::{StatusCode, header::HeaderName, header::HeaderValue};
@@ -23,13 +25,15 @@ use axum::{Router, routing::get};
 use dashmap::DashMap;
 use std::path::Path;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
 use tokio::{net::TcpListener, sync::oneshot};
 use tower::{
 	ServiceBuilder, buffer::BufferLayer, limit::ConcurrencyLimitLayer, load_shed::LoadShedLayer, timeout::TimeoutLayer,
 };
 use tower_http::catch_panic::CatchPanicLayer;
 use tower_http::set_header::SetResponseHeaderLayer;
-use versatiles_container::{TileSource, TilesRuntime};
+use versatiles_container::{DataLocation, TileSource, TilesRuntime};
 use versatiles_derive::context;
 
 /// Thin orchestration layer for the VersaTiles HTTP server.
@@ -65,6 +69,11 @@ pub struct TileServer {
 	join: Option<tokio::task::JoinHandle<()>>,
 	/// If true, prefer faster (lower ratio) recompression when negotiating encodings.
 	minimal_recompression: bool,
+	/// If true, serve tiles in their stored compression and ignore `Accept-Encoding`.
+	/// Clients that don't accept the stored compression must decompress tiles themselves.
+	compression_passthrough: bool,
+	/// If true, answer requests for missing tiles with `204 No Content` instead of `404`.
+	missing_tiles_no_content: bool,
 	/// Expose small helper endpoints like `/tiles/index.json` and `/status`.
 	disable_api: bool,
 	runtime: TilesRuntime,
@@ -73,6 +82,17 @@ pub struct TileServer {
 	cors_max_age_seconds: u64,
 	/// Extra response headers as configured.
 	extra_response_headers: Vec<(HeaderName, HeaderValue)>,
+	/// If set, serve HTTPS and negotiate HTTP/2 via ALPN.
+	tls: Option<TlsConfig>,
+	/// Reported by `/readyz`; true while the listener is serving, see [`TileServer::start`].
+	ready: Arc<AtomicBool>,
+	/// Serve `/metrics` and record requests for it. Off by default, since the endpoint has no authentication.
+	enable_metrics: bool,
+	/// Serve a MapLibre preview of every tile source. Off by default, since the page loads
+	/// MapLibre from an external CDN.
+	enable_preview: bool,
+	/// Served on `/metrics`; kept across restarts, so counters only ever increase.
+	metrics: Arc<metrics::ServerMetrics>,
 }
 
 impl TileServer {
@@ -87,11 +107,18 @@ impl TileServer {
 			exit_signal: None,
 			join: None,
 			minimal_recompression,
+			compression_passthrough: false,
+			missing_tiles_no_content: false,
 			disable_api,
 			runtime,
 			cors_allowed_origins: Vec::new(),
 			cors_max_age_seconds: 3600,
 			extra_response_headers: Vec::new(),
+			tls: None,
+			ready: Arc::new(AtomicBool::new(false)),
+			enable_metrics: false,
+			enable_preview: false,
+			metrics: Arc::default(),
 		}
 	}
 
@@ -110,6 +137,12 @@ impl TileServer {
 			parsed_headers.push((name, value));
 		}
 
+		let tls = match (config.server.tls_cert, config.server.tls_key) {
+			(Some(cert_pem), Some(key_pem)) => Some(TlsConfig { cert_pem, key_pem }),
+			(None, None) => None,
+			_ => bail!("TLS requires both a certificate (tls_cert) and a private key (tls_key)"),
+		};
+
 		let mut server = TileServer {
 			ip: config.server.ip.unwrap_or("0.0.0.0".into()),
 			port: config.server.port.unwrap_or(8080),
@@ -118,31 +151,87 @@ impl TileServer {
 			exit_signal: None,
 			join: None,
 			minimal_recompression: config.server.minimal_recompression.unwrap_or(false),
+			compression_passthrough: config.server.compression_passthrough.unwrap_or(false),
+			missing_tiles_no_content: config.server.missing_tiles_no_content.unwrap_or(false),
 			disable_api: config.server.disable_api.unwrap_or(false),
 			runtime,
 			cors_allowed_origins: config.cors.allowed_origins.clone(),
 			cors_max_age_seconds: config.cors.max_age_seconds.unwrap_or(3600),
 			extra_response_headers: parsed_headers,
+			tls,
+			ready: Arc::new(AtomicBool::new(false)),
+			enable_metrics: config.server.enable_metrics.unwrap_or(false),
+			enable_preview: config.server.enable_preview.unwrap_or(false),
+			metrics: Arc::default(),
 		};
 
+		// Open all tile sources first, so they can refer to each other as fallback.
+		let mut readers = Vec::new();
 		for tile_config in config.tile_sources.iter() {
-			server.add_tile_source_config(tile_config).await?;
+			readers.push(server.open_tile_source_config(tile_config).await?);
+		}
+		for (tile_config, (name, reader)) in config.tile_sources.iter().zip(&readers) {
+			let fallback = tile_config
+				.fallback
+				.iter()
+				.map(|fallback_name| {
+					ensure!(fallback_name != name, "tile source '{name}' can't be its own fallback");
+					readers
+						.iter()
+						.find(|(other_name, _)| other_name == fallback_name)
+						.map(|(_, reader)| reader.clone())
+						.ok_or_else(|| {
+							anyhow!("fallback '{fallback_name}' of tile source '{name}' is not a configured tile source")
+						})
+				})
+				.collect::<Result<Vec<_>>>()?;
+
+			let mut source = sources::ServerTileSource::from(reader.clone(), name)?.with_fallback(fallback)?;
+			if let Some(compression) = tile_config.compression {
+				source = source.with_compression(compression);
+			}
+			server.insert_tile_source(source)?;
 		}
 
 		for static_config in config.static_sources.iter() {
-			server
-				.add_static_source(
-					static_config.src.as_path()?,
-					static_config.prefix.as_deref().unwrap_or("/"),
-				)
-				.await?;
+			let url_prefix = static_config.prefix.as_deref().unwrap_or("/");
+			if let DataLocation::Url(url) = &static_config.src {
+				server.add_static_source_http(
+					url,
+					url_prefix,
+					static_config.cache_responses.unwrap_or(false),
+					Duration::from_secs(static_config.cache_ttl_seconds.unwrap_or(300)),
+				)?;
+			} else {
+				let defaults = sources::FolderOptions::default();
+				let options = sources::FolderOptions {
+					directory_listing: static_config.directory_listing.unwrap_or(defaults.directory_listing),
+					follow_symlinks: static_config.follow_symlinks.unwrap_or(defaults.follow_symlinks),
+					serve_dotfiles: static_config.serve_dotfiles.unwrap_or(defaults.serve_dotfiles),
+				};
+				server
+					.add_static_source_with_options(static_config.src.as_path()?, url_prefix, options)
+					.await?;
+			}
 		}
 
 		Ok(server)
 	}
 
-	#[context("adding tile source from config: {tile_config:?}")]
-	async fn add_tile_source_config(&mut self, tile_config: &TileSourceConfig) -> Result<()> {
+	/// Construct a server from a YAML config file, see [`Config::from_path`].
+	///
+	/// Relative paths in the file are resolved against the directory of the file.
+	#[context("building tile server from config file '{}'", path.display())]
+	pub async fn from_config_file(path: &Path, runtime: TilesRuntime) -> Result<TileServer> {
+		TileServer::from_config(Config::from_path(path)?, runtime).await
+	}
+
+	/// Opens the reader of a configured tile source and returns it with the name it is served under.
+	#[context("opening tile source from config: {tile_config:?}")]
+	async fn open_tile_source_config(
+		&self,
+		tile_config: &TileSourceConfig,
+	) -> Result<(String, Arc<Box<dyn TileSource>>)> {
 		let name = tile_config.name.clone().unwrap_or(tile_config.src.name()?.to_string());
 
 		log::debug!(
@@ -153,7 +242,7 @@ impl TileServer {
 
 		let reader = self.runtime.get_reader(tile_config.src.clone()).await?;
 
-		self.add_tile_source(name, reader).await
+		Ok((name, reader))
 	}
 
 	/// Add a tile source dynamically while server is running.
@@ -165,7 +254,12 @@ impl TileServer {
 		log::debug!("add source: id='{name}', source={reader:?}");
 
 		// Create ServerTileSource (validates and wraps reader)
-		let source = sources::ServerTileSource::from(reader, &name)?;
+		self.insert_tile_source(sources::ServerTileSource::from(reader, &name)?)
+	}
+
+	/// Registers a tile source under its id, checking for id and URL prefix collisions.
+	fn insert_tile_source(&mut self, source: sources::ServerTileSource) -> Result<()> {
+		let name = source.id.clone();
 		let source_arc = Arc::new(source);
 
 		// Check for ID collision
@@ -217,17 +311,71 @@ impl TileServer {
 	///
 	/// Uses read-copy-update (RCU) for lock-free hot-reload.
 	/// Can be called before or after `start()` - changes take effect immediately.
-	#[context("adding static source: path={path:?}, url_prefix='{url_prefix}'")]
 	pub async fn add_static_source(&mut self, path: &Path, url_prefix: &str) -> Result<()> {
+		self.add_static_source_with_listing(path, url_prefix, false).await
+	}
+
+	/// Register a static file source mounted at `url_prefix`, optionally serving
+	/// HTML directory listings for folders without an `index.html`.
+	///
+	/// `directory_listing` only applies to folders, tar archives never list their contents.
+	/// A `.versatiles` file serves its attachments, e.g. style sprites and glyphs.
+	#[context("adding static source: path={path:?}, url_prefix='{url_prefix}', directory_listing={directory_listing}")]
+	pub async fn add_static_source_with_listing(
+		&mut self,
+		path: &Path,
+		url_prefix: &str,
+		directory_listing: bool,
+	) -> Result<()> {
+		let options = sources::FolderOptions {
+			directory_listing,
+			..Default::default()
+		};
+		self.add_static_source_with_options(path, url_prefix, options).await
+	}
+
+	/// Register a static file source mounted at `url_prefix` with all [`FolderOptions`](super::FolderOptions).
+	///
+	/// The options only apply to folders: symbolic links are resolved and must stay inside the folder,
+	/// and files or directories starting with `.` are only served with `serve_dotfiles`.
+	#[context("adding static source: path={path:?}, url_prefix='{url_prefix}', options={options:?}")]
+	pub async fn add_static_source_with_options(
+		&mut self,
+		path: &Path,
+		url_prefix: &str,
+		options: sources::FolderOptions,
+	) -> Result<()> {
 		log::debug!("add static: {path:?}");
-		let source = sources::StaticSource::new(path, url_prefix)?;
+		let source = sources::StaticSource::new(path, url_prefix, options).await?;
+		self.push_static_source(source);
+		log::info!("added static source: path={:?}, url_prefix='{}'", path, url_prefix);
+		Ok(())
+	}
+
+	/// Register a static source mounted at `url_prefix` that proxies the files of an
+	/// upstream HTTP server at `base_url`.
+	///
+	/// With `cache_responses`, upstream responses are kept in memory for `cache_ttl`.
+	#[context("adding static source: url={base_url}, url_prefix='{url_prefix}'")]
+	pub fn add_static_source_http(
+		&mut self,
+		base_url: &reqwest::Url,
+		url_prefix: &str,
+		cache_responses: bool,
+		cache_ttl: Duration,
+	) -> Result<()> {
+		let source = sources::StaticSource::new_http(base_url, url_prefix, cache_responses, cache_ttl)?;
+		self.push_static_source(source);
+		log::info!("added static source: url={base_url}, url_prefix='{url_prefix}'");
+		Ok(())
+	}
+
+	fn push_static_source(&self, source: sources::StaticSource) {
 		self.static_sources.rcu(|old| {
 			let mut new = (**old).clone();
 			new.push(source.clone());
 			new
 		});
-		log::info!("added static source: path={:?}, url_prefix='{}'", path, url_prefix);
-		Ok(())
 	}
 
 	/// Remove a static source by URL prefix.
@@ -258,11 +406,36 @@ impl TileServer {
 		Ok(was_removed)
 	}
 
+	/// Enable or disable TLS. Takes effect on the next `start()`.
+	pub fn set_tls(&mut self, tls: Option<TlsConfig>) {
+		self.tls = tls;
+	}
+
+	/// Enable or disable the Prometheus endpoint `/metrics`. Takes effect on the next `start()`.
+	pub fn set_enable_metrics(&mut self, enable_metrics: bool) {
+		self.enable_metrics = enable_metrics;
+	}
+
+	/// Enable or disable the preview pages `/tiles/{id}/preview`. Takes effect on the next `start()`.
+	pub fn set_enable_preview(&mut self, enable_preview: bool) {
+		self.enable_preview = enable_preview;
+	}
+
+	/// Set whether `/readyz` reports the server as ready to receive traffic.
+	///
+	/// `start()` marks the server as ready once the listener is serving, `stop()` as not ready.
+	pub fn set_ready(&self, ready: bool) {
+		self.ready.store(ready, Ordering::Release);
+	}
+
 	/// Start listening and serving requests.
 	///
 	/// - Idempotent: if already running, the previous instance is stopped first.
 	/// - Builds the router (`routes`), applies CORS and global protection layers,
 	///   then spawns `axum::serve(...)` with graceful shutdown support.
+	/// - With TLS configured, connections are terminated by `tls::serve_tls` instead.
+	///   Both paths accept HTTP/1.1 and HTTP/2 (cleartext HTTP/2 requires prior knowledge).
+	/// - `/readyz` reports ready only after the serving task has been spawned on the bound listener.
 	#[context("starting tile server")]
 	pub async fn start(&mut self) -> Result<()> {
 		// If already running, stop first to avoid port conflicts and leaked tasks.
@@ -274,6 +447,10 @@ impl TileServer {
 
 		// Build the router
 		let mut router = Router::new().route("/status", get(|| async { "ready!" }));
+		router = self.add_health_to_app(router);
+		if self.enable_metrics {
+			router = self.add_metrics_to_app(router);
+		}
 		router = self.add_tile_sources_to_app(router);
 		if !self.disable_api {
 			router = self.add_api_to_app(router).await?;
@@ -320,6 +497,18 @@ impl TileServer {
 
 		router = router.layer(protection);
 
+		// Log every request outermost, so the elapsed time includes queueing and overload answers.
+		router = router.layer(axum::middleware::from_fn(access_log::log_request));
+		if self.enable_metrics {
+			router = router.layer(axum::middleware::from_fn_with_state(
+				Arc::clone(&self.metrics),
+				metrics::record_request,
+			));
+		}
+
+		// Load certificates before binding, so a broken TLS setup fails `start()` directly.
+		let acceptor = self.tls.as_ref().map(TlsConfig::build_acceptor).transpose()?;
+
 		let addr = format!("{}:{}", self.ip, self.port);
 		log::info!("server binding on {addr}");
 
@@ -332,7 +521,9 @@ impl TileServer {
 
 		// Spawn the server and keep a handle so we can await it on shutdown.
 		let handle = tokio::spawn(async move {
-			if let Err(err) = axum::serve(listener, router.into_make_service())
+			if let Some(acceptor) = acceptor {
+				tls::serve_tls(listener, acceptor, router, rx).await;
+			} else if let Err(err) = axum::serve(listener, router.into_make_service())
 				.with_graceful_shutdown(async {
 					rx.await.ok();
 				})
@@ -345,6 +536,7 @@ impl TileServer {
 
 		self.exit_signal = Some(tx);
 		self.join = Some(handle);
+		self.set_ready(true);
 
 		Ok(())
 	}
@@ -359,6 +551,7 @@ impl TileServer {
 		}
 
 		log::info!("stopping server");
+		self.set_ready(false);
 
 		// Signal graceful shutdown.
 		if let Some(tx) = self.exit_signal.take() {
@@ -380,6 +573,18 @@ impl TileServer {
 		}
 	}
 
+	/// Start the server, wait for `signal` to complete, then shut down gracefully.
+	///
+	/// In-flight requests are drained (see [`TileServer::stop`]) before this returns. Use e.g.
+	/// `tokio::signal::ctrl_c()` or a `oneshot::Receiver` as signal.
+	#[context("running tile server")]
+	pub async fn run_until(&mut self, signal: impl Future<Output = ()>) -> Result<()> {
+		self.start().await?;
+		signal.await;
+		self.stop().await;
+		Ok(())
+	}
+
 	/// Get the port the server is listening on (or will listen on).
 	///
 	/// If the server was started with port 0, this returns the actual ephemeral port
@@ -390,7 +595,33 @@ impl TileServer {
 
 	/// Helper: delegate to `routes::add_tile_sources_to_app` to attach tile endpoints.
 	fn add_tile_sources_to_app(&self, app: Router) -> Router {
-		routes::add_tile_sources_to_app(app, Arc::clone(&self.tile_sources), self.minimal_recompression)
+		routes::add_tile_sources_to_app(
+			app,
+			Arc::clone(&self.tile_sources),
+			self.minimal_recompression,
+			self.compression_passthrough,
+			self.missing_tiles_no_content,
+			self.enable_preview,
+		)
+	}
+
+	/// Helper: delegate to `routes::add_health_to_app` to attach the health probes.
+	fn add_health_to_app(&self, app: Router) -> Router {
+		let state = routes::HealthState {
+			tile_sources: Arc::clone(&self.tile_sources),
+			ready: Arc::clone(&self.ready),
+			started: Instant::now(),
+		};
+		routes::add_health_to_app(app, state)
+	}
+
+	/// Helper: delegate to `routes::add_metrics_to_app` to attach the Prometheus endpoint.
+	fn add_metrics_to_app(&self, app: Router) -> Router {
+		let state = routes::MetricsState {
+			metrics: Arc::clone(&self.metrics),
+			tile_sources: Arc::clone(&self.tile_sources),
+		};
+		routes::add_metrics_to_app(app, state)
 	}
 
 	/// Helper: delegate to `routes::add_static_sources_to_app` to attach static endpoints.
@@ -401,7 +632,7 @@ impl TileServer {
 	/// Helper: delegate to `routes::add_api_to_app` to attach small JSON API endpoints.
 	#[context("adding API routes to app")]
 	async fn add_api_to_app(&self, app: Router) -> Result<Router> {
-		routes::add_api_to_app(app, Arc::clone(&self.tile_sources)).await
+		routes::add_api_to_app(app, Arc::clone(&self.tile_sources), self.tls.is_some()).await
 	}
 
 	pub async fn get_url_mapping(&self) -> Vec<(super::Url, String)> {
@@ -425,8 +656,8 @@ mod tests {
 	use reqwest::Client;
 	use rstest::rstest;
 	use std::sync::Arc;
-	use versatiles_container::{MockReader, MockReaderProfile as MRP, TileSourceMetadata, Traversal};
-	use versatiles_core::{TileBBoxPyramid, TileCompression as TC, TileFormat as TF};
+	use versatiles_container::{MockReader, MockReaderProfile as MRP, TileSourceMetadata};
+	use versatiles_core::{Blob, TileBBoxPyramid, TileCompression as TC, TileFormat as TF};
 
 	const IP: &str = "127.0.0.1";
 
@@ -442,6 +673,7 @@ mod tests {
 		}
 
 		let mut server = TileServer::new_test(IP, 50001, true, false);
+		server.set_enable_metrics(true);
 
 		let reader = Arc::new(MockReader::new_mock_profile(MRP::Pbf)?.boxed());
 		server.add_tile_source("cheese".to_string(), reader).await?;
@@ -454,14 +686,240 @@ mod tests {
 		assert_eq!(get("tiles/cheese/meta.json").await, meta);
 		assert_eq!(get("tiles/cheese/tiles.json").await, meta);
 		assert_eq!(&get("tiles/cheese/3/4/5").await[0..9], "\u{1a}4\n\u{5}ocean");
+		assert!(
+			get("tiles/cheese/3/4/5/info")
+				.await
+				.starts_with("{\"compression\":\"gzip\",\"format\":\"mvt\",\"size_bytes\":")
+		);
 		assert_eq!(get("tiles/index.json").await, "[\"cheese\"]");
 		assert_eq!(get("status").await, "ready!");
+		assert!(get("healthz").await.starts_with("{\"sources\":1,\"status\":\"ok\","));
+		assert!(get("readyz").await.starts_with("{\"sources\":1,\"status\":\"ready\","));
+		let metrics = get("metrics").await;
+		assert!(metrics.contains("versatiles_requests_total{source=\"cheese\",status=\"200\"} 4\n"));
+		assert!(metrics.contains("versatiles_tiles_served_total 1\n"));
+		assert!(metrics.contains("versatiles_open_readers 1\n"));
+
+		server.set_ready(false);
+		assert!(
+			get("readyz")
+				.await
+				.starts_with("{\"sources\":1,\"status\":\"starting\",")
+		);
 
 		server.stop().await;
 
 		Ok(())
 	}
 
+	#[tokio::test]
+	async fn server_from_config_file() -> Result<()> {
+		use versatiles_container::{TilesWriter, VersaTilesWriter};
+
+		// all paths in the config are relative to the config file
+		let dir = assert_fs::TempDir::new()?;
+		let mut full = MockReader::new_mock_profile(MRP::Pbf)?;
+		VersaTilesWriter::write_to_path(&mut full, &dir.path().join("full.versatiles"), TilesRuntime::default()).await?;
+		let metadata = TileSourceMetadata::new_builder()
+			.with_format(TF::MVT)
+			.with_compression(TC::Gzip)
+			.with_bbox_pyramid(TileBBoxPyramid::new_full(2))
+			.build()?;
+		let mut low = MockReader::new_mock(metadata)?;
+		VersaTilesWriter::write_to_path(&mut low, &dir.path().join("low.versatiles"), TilesRuntime::default()).await?;
+		std::fs::create_dir(dir.path().join("public"))?;
+		std::fs::write(dir.path().join("public/index.html"), "<html>hello</html>")?;
+
+		let config_path = dir.path().join("server.yml");
+		std::fs::write(
+			&config_path,
+			[
+				"server:",
+				"  ip: 127.0.0.1",
+				"  port: 0",
+				"tiles:",
+				"  - name: full",
+				"    src: full.versatiles",
+				"  - name: low",
+				"    src: ./low.versatiles",
+				"    compression: br",
+				"    fallback: [full]",
+				"static:",
+				"  - src: public",
+				"    prefix: /",
+			]
+			.join("\n"),
+		)?;
+
+		let mut server = TileServer::from_config_file(&config_path, crate::runtime::create_test_runtime()).await?;
+		server.start().await?;
+
+		// keep the responses compressed, to check the served compression
+		let client = Client::builder().gzip(false).brotli(false).build()?;
+		let get = async |path: &str| {
+			let response = client
+				.get(format!("http://{IP}:{}/{path}", server.port))
+				.header(header::ACCEPT_ENCODING, "gzip, br")
+				.send()
+				.await
+				.unwrap();
+			let encoding = response
+				.headers()
+				.get(header::CONTENT_ENCODING)
+				.map(|value| value.to_str().unwrap().to_string());
+			(response.status().as_u16(), encoding, response.bytes().await.unwrap())
+		};
+
+		// the gzip tiles of "full" are recompressed, because the client also accepts brotli
+		assert_eq!(get("tiles/full/3/4/5").await.0, 200);
+		assert_eq!(get("tiles/full/3/4/5").await.1.as_deref(), Some("br"));
+
+		// served from "low" itself, from the fallback "full", and missing in both
+		assert_eq!(get("tiles/low/2/1/1").await.1.as_deref(), Some("br"));
+		assert_eq!(get("tiles/low/5/3/4").await.0, 200);
+		assert_eq!(get("tiles/low/5/3/4").await.1.as_deref(), Some("br"));
+		assert_eq!(get("tiles/low/8/3/4").await.0, 404);
+
+		// static files are compressed on the fly, too
+		let (status, encoding, body) = get("index.html").await;
+		assert_eq!((status, encoding.as_deref()), (200, Some("br")));
+		let body = versatiles_core::utils::decompress(Blob::from(body.to_vec()), TC::Brotli)?;
+		assert_eq!(body.as_slice(), b"<html>hello</html>");
+
+		server.stop().await;
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn server_from_config_rejects_unknown_fallback() -> Result<()> {
+		let config = Config::from_string("tiles:\n  - name: a\n    src: ../testdata/berlin.mbtiles\n    fallback: [b]")?;
+		let err = TileServer::from_config(config, crate::runtime::create_test_runtime())
+			.await
+			.err()
+			.unwrap();
+		assert!(
+			format!("{err:?}").contains("fallback 'b' of tile source 'a' is not a configured tile source"),
+			"{err:?}"
+		);
+		Ok(())
+	}
+
+	#[rstest]
+	#[case::cleartext(false)]
+	#[case::tls(true)]
+	#[tokio::test]
+	async fn serve_http2(#[case] use_tls: bool) -> Result<()> {
+		let mut server = TileServer::new_test(IP, 0, true, false);
+		let reader = Arc::new(MockReader::new_mock_profile(MRP::Pbf)?.boxed());
+		server.add_tile_source("cheese".to_string(), reader).await?;
+		if use_tls {
+			server.set_tls(Some(TlsConfig::new(
+				"../testdata/tls-cert.pem",
+				"../testdata/tls-key.pem",
+			)));
+		}
+		server.start().await?;
+
+		let scheme = if use_tls { "https" } else { "http" };
+		let client = Client::builder()
+			.http2_prior_knowledge()
+			.danger_accept_invalid_certs(true)
+			.build()
+			.unwrap();
+
+		let resp = client
+			.get(format!("{scheme}://{IP}:{}/tiles/cheese/3/4/5", server.get_port()))
+			.header(header::ACCEPT_ENCODING, "gzip")
+			.send()
+			.await?;
+		assert_eq!(resp.version(), reqwest::Version::HTTP_2);
+		assert_eq!(resp.status(), StatusCode::OK);
+		assert!(!resp.bytes().await?.is_empty());
+
+		let resp = client
+			.get(format!("{scheme}://{IP}:{}/status", server.get_port()))
+			.send()
+			.await?;
+		assert_eq!(resp.version(), reqwest::Version::HTTP_2);
+		assert_eq!(resp.text().await?, "ready!");
+
+		server.stop().await;
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn serve_tls_rejects_plain_http() -> Result<()> {
+		let mut server = TileServer::new_test(IP, 0, true, false);
+		server.set_tls(Some(TlsConfig::new(
+			"../testdata/tls-cert.pem",
+			"../testdata/tls-key.pem",
+		)));
+		server.start().await?;
+
+		assert!(
+			reqwest::get(format!("http://{IP}:{}/status", server.get_port()))
+				.await
+				.is_err()
+		);
+
+		server.stop().await;
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn run_until_stops_on_signal() -> Result<()> {
+		const PORT: u16 = 50005;
+		let mut server = TileServer::new_test(IP, PORT, true, false);
+		let url = format!("http://{IP}:{PORT}/status");
+
+		let (tx, rx) = oneshot::channel::<()>();
+		let task = tokio::spawn(async move {
+			server
+				.run_until(async move {
+					rx.await.ok();
+				})
+				.await
+		});
+
+		// wait until the server answers
+		let mut response = None;
+		for _ in 0..100 {
+			if let Ok(res) = reqwest::get(&url).await {
+				response = Some(res);
+				break;
+			}
+			tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+		}
+		assert_eq!(response.unwrap().text().await?, "ready!");
+
+		tx.send(()).unwrap();
+		tokio::time::timeout(std::time::Duration::from_secs(15), task).await???;
+		assert!(reqwest::get(&url).await.is_err());
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn start_fails_with_invalid_tls_config() {
+		let mut server = TileServer::new_test(IP, 0, true, false);
+		server.set_tls(Some(TlsConfig::new(
+			"../testdata/missing.pem",
+			"../testdata/tls-key.pem",
+		)));
+		assert_eq!(
+			server
+				.start()
+				.await
+				.unwrap_err()
+				.chain()
+				.map(|e| e.to_string())
+				.collect::<Vec<_>>()[0..2],
+			[
+				"starting tile server",
+				"loading TLS certificate \"../testdata/missing.pem\" and key \"../testdata/tls-key.pem\""
+			]
+		);
+	}
+
 	#[tokio::test]
 	#[should_panic]
 	async fn same_prefix_twice() {
@@ -548,7 +1006,14 @@ mod tests {
 
 		let mut server = TileServer::new_test(IP, 0, true, false);
 
-		let parameters = TileSourceMetadata::new(format, compression, TileBBoxPyramid::new_full(8), Traversal::ANY);
+		let mut parameters = TileSourceMetadata::new_builder()
+			.with_format(format)
+			.with_compression(TC::Uncompressed)
+			.with_bbox_pyramid(TileBBoxPyramid::new_full(8))
+			.build()
+			.unwrap();
+		// The builder rejects compressed raster formats, so the compression is set afterwards.
+		parameters.tile_compression = compression;
 		let reader = Arc::new(MockReader::new_mock(parameters).unwrap().boxed());
 		server.add_tile_source("cheese".to_string(), reader).await.unwrap();
 		server.start().await.unwrap();
@@ -633,6 +1098,119 @@ mod tests {
 		Ok(())
 	}
 
+	#[tokio::test]
+	async fn static_folder_serves_byte_ranges() -> Result<()> {
+		let mut server = TileServer::new_test(IP, 0, true, false);
+		server.add_static_source(Path::new("../testdata"), "/").await?;
+		server.start().await?;
+		let url = format!("http://{IP}:{}/berlin.mbtiles", server.port);
+		let expected = std::fs::read("../testdata/berlin.mbtiles")?;
+		let client = Client::new();
+
+		// ranges are served as stored, whatever the client accepts
+		let get_range = async |range: &str| {
+			let response = client
+				.get(&url)
+				.header(header::RANGE, range)
+				.header(header::ACCEPT_ENCODING, "gzip, br")
+				.send()
+				.await
+				.unwrap();
+			let get_header = |name: header::HeaderName| {
+				response
+					.headers()
+					.get(name)
+					.map(|value| value.to_str().unwrap().to_string())
+			};
+			let headers = (
+				get_header(header::CONTENT_RANGE),
+				get_header(header::ACCEPT_RANGES),
+				get_header(header::CONTENT_ENCODING),
+			);
+			let status = response.status().as_u16();
+			(status, headers, response.bytes().await.unwrap().to_vec())
+		};
+
+		// a range in the middle of the file
+		let (status, headers, body) = get_range("bytes=1000-1999").await;
+		assert_eq!(status, 206);
+		assert_eq!(
+			headers,
+			(Some("bytes 1000-1999/26533888".into()), Some("bytes".into()), None)
+		);
+		assert_eq!(body, expected[1000..2000]);
+
+		// a suffix range
+		let (status, headers, body) = get_range("bytes=-500").await;
+		assert_eq!(status, 206);
+		assert_eq!(headers.0.as_deref(), Some("bytes 26533388-26533887/26533888"));
+		assert_eq!(body, expected[26533388..]);
+
+		// a range beyond the end of the file
+		let (status, headers, _) = get_range("bytes=30000000-").await;
+		assert_eq!(status, 416);
+		assert_eq!(headers.0.as_deref(), Some("bytes */26533888"));
+
+		// full responses advertise range support
+		let url = format!("http://{IP}:{}/cities.csv", server.port);
+		let response = client.get(&url).send().await?;
+		assert_eq!(response.status().as_u16(), 200);
+		assert_eq!(response.headers().get(header::ACCEPT_RANGES).unwrap(), "bytes");
+
+		server.stop().await;
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn static_source_serves_versatiles_attachments() -> Result<()> {
+		use versatiles_container::{AttachmentWriter, TilesWriter, VersaTilesWriter};
+		use versatiles_core::Blob;
+
+		let sprite = r#"{"icon":{"x":0,"y":0,"width":16,"height":16,"pixelRatio":1}}"#;
+		let glyphs = vec![10u8, 4, 0x4e, 0x6f, 0x74, 0x6f];
+
+		let file = assert_fs::NamedTempFile::new("map.versatiles")?;
+		let mut reader = MockReader::new_mock_profile(MRP::Pbf)?;
+		VersaTilesWriter::write_to_path(&mut reader, &file, TilesRuntime::default()).await?;
+		let mut attachments = AttachmentWriter::new();
+		attachments.add_attachment("sprites/sprite.json", Blob::from(sprite), TC::Brotli)?;
+		attachments.add_attachment(
+			"fonts/Noto-Sans/0-255.pbf",
+			Blob::from(glyphs.clone()),
+			TC::Uncompressed,
+		)?;
+		attachments.append_to_path(&file)?;
+
+		let mut server = TileServer::new_test(IP, 0, true, false);
+		server.add_static_source(&file, "/assets").await?;
+		server.start().await?;
+		let port = server.port;
+
+		let client = Client::builder().build().unwrap();
+		let response = client
+			.get(format!("http://{IP}:{port}/assets/sprites/sprite.json"))
+			.send()
+			.await?;
+		assert_eq!(response.status(), 200);
+		assert_eq!(response.text().await?, sprite);
+
+		let response = client
+			.get(format!("http://{IP}:{port}/assets/fonts/Noto-Sans/0-255.pbf"))
+			.send()
+			.await?;
+		assert_eq!(response.status(), 200);
+		assert_eq!(response.bytes().await?.to_vec(), glyphs);
+
+		let response = client
+			.get(format!("http://{IP}:{port}/assets/sprites/sprite.png"))
+			.send()
+			.await?;
+		assert_eq!(response.status(), 404);
+
+		server.stop().await;
+		Ok(())
+	}
+
 	#[tokio::test]
 	async fn extra_response_headers_are_applied() -> Result<()> {
 		// Use ephemeral port to avoid conflicts on CI/Windows.
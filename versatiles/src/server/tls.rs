@@ -0,0 +1,173 @@
+//! TLS termination for the tile server.
+//!
+//! When a [`TlsConfig`] is set, the server accepts TLS connections and negotiates
+//! HTTP/2 (falling back to HTTP/1.1) via ALPN. Without TLS, the server still speaks
+//! cleartext HTTP/2 (h2c) to clients that use prior knowledge, which is handy for
+//! local testing.
+
+use anyhow::{Result, bail};
+use axum::Router;
+use hyper_util::{
+	rt::{TokioExecutor, TokioIo},
+	server::{conn::auto::Builder, graceful::GracefulShutdown},
+	service::TowerToHyperService,
+};
+use std::{path::PathBuf, sync::Arc, time::Duration};
+use tokio::{
+	net::TcpListener,
+	sync::{oneshot, watch},
+};
+use tokio_rustls::{
+	TlsAcceptor,
+	rustls::{
+		ServerConfig,
+		pki_types::{CertificateDer, PrivateKeyDer, pem::PemObject},
+	},
+};
+use versatiles_derive::context;
+
+/// Certificate chain and private key used to serve HTTPS.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TlsConfig {
+	/// PEM file containing the certificate chain, leaf certificate first.
+	pub cert_pem: PathBuf,
+	/// PEM file containing the private key (PKCS#8, PKCS#1 or SEC1).
+	pub key_pem: PathBuf,
+}
+
+impl TlsConfig {
+	pub fn new(cert_pem: impl Into<PathBuf>, key_pem: impl Into<PathBuf>) -> TlsConfig {
+		TlsConfig {
+			cert_pem: cert_pem.into(),
+			key_pem: key_pem.into(),
+		}
+	}
+
+	/// Load certificate and key and build an acceptor that advertises `h2` and `http/1.1`.
+	#[context("loading TLS certificate {:?} and key {:?}", self.cert_pem, self.key_pem)]
+	pub(super) fn build_acceptor(&self) -> Result<TlsAcceptor> {
+		let certs = CertificateDer::pem_file_iter(&self.cert_pem)?.collect::<Result<Vec<_>, _>>()?;
+		if certs.is_empty() {
+			bail!("no certificate found in {:?}", self.cert_pem);
+		}
+		let key = PrivateKeyDer::from_pem_file(&self.key_pem)?;
+
+		let mut config = ServerConfig::builder()
+			.with_no_client_auth()
+			.with_single_cert(certs, key)?;
+		config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+
+		Ok(TlsAcceptor::from(Arc::new(config)))
+	}
+}
+
+/// Time a client gets to complete the TLS handshake before the connection is dropped.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Pause after a failed `accept`, e.g. when the process ran out of file descriptors.
+const ACCEPT_ERROR_DELAY: Duration = Duration::from_millis(100);
+
+/// Accept TLS connections on `listener` and serve `router` until `shutdown` fires.
+///
+/// Mirrors `axum::serve(...).with_graceful_shutdown(...)`: after the signal, no new
+/// connections are accepted and open connections are drained. Connections that are
+/// still in the TLS handshake are dropped.
+pub(super) async fn serve_tls(
+	listener: TcpListener,
+	acceptor: TlsAcceptor,
+	router: Router,
+	mut shutdown: oneshot::Receiver<()>,
+) {
+	let builder = Builder::new(TokioExecutor::new());
+	let graceful = GracefulShutdown::new();
+	let (handshake_cancel, _) = watch::channel(());
+
+	loop {
+		let accepted = tokio::select! {
+			accepted = listener.accept() => accepted,
+			_ = &mut shutdown => break,
+		};
+		let stream = match accepted {
+			Ok((stream, _)) => stream,
+			Err(err) => {
+				log::warn!("failed to accept connection: {err}");
+				tokio::select! {
+					() = tokio::time::sleep(ACCEPT_ERROR_DELAY) => continue,
+					_ = &mut shutdown => break,
+				}
+			}
+		};
+
+		let acceptor = acceptor.clone();
+		let builder = builder.clone();
+		let service = TowerToHyperService::new(router.clone());
+		// Register the connection before the handshake, so shutdown waits for it.
+		let watcher = graceful.watcher();
+		let mut cancel = handshake_cancel.subscribe();
+
+		tokio::spawn(async move {
+			let handshake = tokio::time::timeout(HANDSHAKE_TIMEOUT, acceptor.accept(stream));
+			let stream = tokio::select! {
+				result = handshake => match result {
+					Ok(Ok(stream)) => stream,
+					Ok(Err(err)) => {
+						log::debug!("TLS handshake failed: {err}");
+						return;
+					}
+					Err(_) => {
+						log::debug!("TLS handshake timed out");
+						return;
+					}
+				},
+				_ = cancel.changed() => return,
+			};
+			let connection = builder.serve_connection_with_upgrades(TokioIo::new(stream), service);
+			if let Err(err) = watcher.watch(connection.into_owned()).await {
+				log::debug!("connection closed with error: {err}");
+			}
+		});
+	}
+
+	drop(listener);
+	handshake_cancel.send_replace(());
+	graceful.shutdown().await;
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn build_acceptor() -> Result<()> {
+		let acceptor = TlsConfig::new("../testdata/tls-cert.pem", "../testdata/tls-key.pem").build_acceptor()?;
+		assert_eq!(
+			acceptor.config().alpn_protocols,
+			vec![b"h2".to_vec(), b"http/1.1".to_vec()]
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn build_acceptor_with_missing_files() {
+		let error = TlsConfig::new("../testdata/missing.pem", "../testdata/tls-key.pem")
+			.build_acceptor()
+			.err()
+			.unwrap();
+		assert_eq!(
+			error.to_string(),
+			"loading TLS certificate \"../testdata/missing.pem\" and key \"../testdata/tls-key.pem\""
+		);
+	}
+
+	#[test]
+	fn build_acceptor_without_certificate() {
+		let error = TlsConfig::new("../testdata/tls-key.pem", "../testdata/tls-key.pem")
+			.build_acceptor()
+			.err()
+			.unwrap();
+		assert_eq!(
+			error.chain().last().unwrap().to_string(),
+			"no certificate found in \"../testdata/tls-key.pem\""
+		);
+	}
+}
@@ -1,7 +1,9 @@
-//! helper function for handling URLs and MIME
+//! helper functions for handling URLs, MIME types and byte ranges
 
 mod mime;
+mod range;
 mod url;
 
 pub use mime::*;
+pub use range::*;
 pub use url::*;
@@ -0,0 +1,83 @@
+//! Parsing of single byte ranges from the HTTP `Range` header (RFC 9110, section 14.1.2).
+
+use std::ops::Range;
+
+/// A single byte range as requested by a client.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HttpRange {
+	/// `bytes=first-last`, both inclusive.
+	FromTo(u64, u64),
+	/// `bytes=first-`, everything from `first` to the end.
+	From(u64),
+	/// `bytes=-length`, the last `length` bytes.
+	Suffix(u64),
+}
+
+impl HttpRange {
+	/// Parses the value of a `Range` header.
+	///
+	/// Returns `None` for invalid values, other units and multiple ranges. Servers may ignore
+	/// such headers, so these requests are answered with the full file.
+	pub fn parse(header: &str) -> Option<HttpRange> {
+		let spec = header.trim().strip_prefix("bytes=")?;
+		if spec.contains(',') {
+			return None;
+		}
+		let (first, last) = spec.split_once('-')?;
+		let (first, last) = (first.trim(), last.trim());
+
+		match (first.is_empty(), last.is_empty()) {
+			(true, false) => Some(HttpRange::Suffix(last.parse().ok()?)),
+			(false, true) => Some(HttpRange::From(first.parse().ok()?)),
+			(false, false) => {
+				let (first, last) = (first.parse().ok()?, last.parse().ok()?);
+				(first <= last).then_some(HttpRange::FromTo(first, last))
+			}
+			(true, true) => None,
+		}
+	}
+
+	/// Returns the requested bytes of a file with `length` bytes as a half-open range,
+	/// or `None` if the range is unsatisfiable.
+	pub fn resolve(&self, length: u64) -> Option<Range<u64>> {
+		let range = match *self {
+			HttpRange::FromTo(first, last) => first..last.saturating_add(1).min(length),
+			HttpRange::From(first) => first..length,
+			HttpRange::Suffix(suffix) => length.saturating_sub(suffix)..length,
+		};
+		(range.start < range.end).then_some(range)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use rstest::rstest;
+
+	#[rstest]
+	#[case("bytes=0-499", Some(HttpRange::FromTo(0, 499)))]
+	#[case("bytes=500-", Some(HttpRange::From(500)))]
+	#[case("bytes=-500", Some(HttpRange::Suffix(500)))]
+	#[case(" bytes=5 - 7 ", Some(HttpRange::FromTo(5, 7)))]
+	#[case("bytes=7-5", None)]
+	#[case("bytes=-", None)]
+	#[case("bytes=0-1,5-6", None)]
+	#[case("items=0-5", None)]
+	#[case("bytes=a-b", None)]
+	fn parse(#[case] header: &str, #[case] expected: Option<HttpRange>) {
+		assert_eq!(HttpRange::parse(header), expected);
+	}
+
+	#[rstest]
+	#[case(HttpRange::FromTo(10, 19), Some(10..20))]
+	#[case(HttpRange::FromTo(90, 200), Some(90..100))]
+	#[case(HttpRange::FromTo(100, 200), None)]
+	#[case(HttpRange::From(99), Some(99..100))]
+	#[case(HttpRange::From(100), None)]
+	#[case(HttpRange::Suffix(10), Some(90..100))]
+	#[case(HttpRange::Suffix(1000), Some(0..100))]
+	#[case(HttpRange::Suffix(0), None)]
+	fn resolve(#[case] range: HttpRange, #[case] expected: Option<Range<u64>>) {
+		assert_eq!(range.resolve(100), expected);
+	}
+}
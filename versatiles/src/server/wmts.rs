@@ -0,0 +1,228 @@
+//! WMTS capabilities document for the mounted tile sources.
+//!
+//! Every tile source is advertised as one `Layer` of a Web Mercator tile matrix set, limited to
+//! the zoom levels and tiles of its bbox pyramid. Sources with 256 px tiles (or an unknown tile
+//! size) use `GoogleMapsCompatible`, sources with 512 px tiles use `GoogleMapsCompatible512`,
+//! which has the same scales as `GoogleMapsCompatible` one level higher. Clients fetch tiles through
+//! `ResourceURL` templates that point at the regular `/tiles/{id}/{z}/{x}/{y}` routes.
+
+use super::sources::ServerTileSource;
+use std::{collections::BTreeMap, sync::Arc};
+use versatiles_core::TileFormat;
+
+/// Scale denominator of zoom level 0 in `GoogleMapsCompatible` (256 px tiles, 0.28 mm pixels).
+const SCALE_DENOMINATOR_0: f64 = 559_082_264.028_717_8;
+/// Half the width of the Web Mercator projection in metres.
+const MERCATOR_EXTENT: f64 = 20_037_508.342_789_2;
+
+/// Builds the capabilities document. `base_url` (e.g. `http://localhost:8080`) is prepended
+/// to the tile URL templates.
+pub fn build_capabilities(sources: &[Arc<ServerTileSource>], base_url: &str) -> String {
+	let mut lines = vec![
+		String::from(r#"<?xml version="1.0" encoding="UTF-8"?>"#),
+		String::from(
+			r#"<Capabilities xmlns="http://www.opengis.net/wmts/1.0" xmlns:ows="http://www.opengis.net/ows/1.1" xmlns:xlink="http://www.w3.org/1999/xlink" version="1.0.0">"#,
+		),
+		String::from("<ows:ServiceIdentification>"),
+		String::from("<ows:Title>VersaTiles</ows:Title>"),
+		String::from("<ows:ServiceType>OGC WMTS</ows:ServiceType>"),
+		String::from("<ows:ServiceTypeVersion>1.0.0</ows:ServiceTypeVersion>"),
+		String::from("</ows:ServiceIdentification>"),
+		String::from("<Contents>"),
+	];
+
+	// highest zoom level per tile size, to define only the tile matrix sets that are used
+	let mut level_max = BTreeMap::<u16, u8>::new();
+	for source in sources {
+		let metadata = source.metadata();
+		let pyramid = &metadata.bbox_pyramid;
+		let Some(geo_bbox) = pyramid.get_geo_bbox() else {
+			// Nothing to advertise for sources without tiles.
+			continue;
+		};
+		let tile_size = metadata.tile_size.map_or(256, |size| size.size());
+		let set_level_max = level_max.entry(tile_size).or_default();
+		*set_level_max = (*set_level_max).max(pyramid.get_level_max().unwrap_or(0));
+
+		let id = escape(&source.id);
+		let mime = match metadata.tile_format {
+			TileFormat::MVT => "application/vnd.mapbox-vector-tile",
+			_ => metadata.tile_format.as_mime_str(),
+		};
+		let [x_min, y_min, x_max, y_max] = geo_bbox.as_array();
+
+		lines.push(String::from("<Layer>"));
+		lines.push(format!("<ows:Title>{id}</ows:Title>"));
+		lines.push(String::from("<ows:WGS84BoundingBox>"));
+		lines.push(format!("<ows:LowerCorner>{x_min} {y_min}</ows:LowerCorner>"));
+		lines.push(format!("<ows:UpperCorner>{x_max} {y_max}</ows:UpperCorner>"));
+		lines.push(String::from("</ows:WGS84BoundingBox>"));
+		lines.push(format!("<ows:Identifier>{id}</ows:Identifier>"));
+		lines.push(String::from(
+			r#"<Style isDefault="true"><ows:Identifier>default</ows:Identifier></Style>"#,
+		));
+		lines.push(format!("<Format>{mime}</Format>"));
+		lines.push(String::from("<TileMatrixSetLink>"));
+		lines.push(format!("<TileMatrixSet>{}</TileMatrixSet>", tile_matrix_set(tile_size)));
+		lines.push(String::from("<TileMatrixSetLimits>"));
+		for bbox in pyramid.iter_levels() {
+			// `iter_levels` skips empty levels, so the bounds always exist.
+			let (Ok(col_min), Ok(col_max), Ok(row_min), Ok(row_max)) =
+				(bbox.x_min(), bbox.x_max(), bbox.y_min(), bbox.y_max())
+			else {
+				continue;
+			};
+			lines.push(format!(
+				"<TileMatrixLimits><TileMatrix>{}</TileMatrix><MinTileRow>{row_min}</MinTileRow><MaxTileRow>{row_max}</MaxTileRow><MinTileCol>{col_min}</MinTileCol><MaxTileCol>{col_max}</MaxTileCol></TileMatrixLimits>",
+				bbox.level
+			));
+		}
+		lines.push(String::from("</TileMatrixSetLimits>"));
+		lines.push(String::from("</TileMatrixSetLink>"));
+		lines.push(format!(
+			r#"<ResourceURL format="{mime}" resourceType="tile" template="{}"/>"#,
+			escape(&format!(
+				"{base_url}{}",
+				source.prefix.join_as_string("{TileMatrix}/{TileCol}/{TileRow}")
+			))
+		));
+		lines.push(String::from("</Layer>"));
+	}
+
+	for (tile_size, level_max) in level_max {
+		lines.push(String::from("<TileMatrixSet>"));
+		lines.push(format!(
+			"<ows:Identifier>{}</ows:Identifier>",
+			tile_matrix_set(tile_size)
+		));
+		lines.push(String::from(
+			"<ows:SupportedCRS>urn:ogc:def:crs:EPSG::3857</ows:SupportedCRS>",
+		));
+		if tile_size == 256 {
+			lines.push(String::from(
+				"<WellKnownScaleSet>urn:ogc:def:wkss:OGC:1.0:GoogleMapsCompatible</WellKnownScaleSet>",
+			));
+		}
+		// larger tiles cover the same area with more pixels, so the scale is finer
+		let scale_denominator_0 = SCALE_DENOMINATOR_0 * 256.0 / f64::from(tile_size);
+		for level in 0..=level_max {
+			let count = 1u64 << level;
+			lines.push(format!(
+				"<TileMatrix><ows:Identifier>{level}</ows:Identifier><ScaleDenominator>{}</ScaleDenominator><TopLeftCorner>-{MERCATOR_EXTENT} {MERCATOR_EXTENT}</TopLeftCorner><TileWidth>{tile_size}</TileWidth><TileHeight>{tile_size}</TileHeight><MatrixWidth>{count}</MatrixWidth><MatrixHeight>{count}</MatrixHeight></TileMatrix>",
+				scale_denominator_0 / count as f64
+			));
+		}
+		lines.push(String::from("</TileMatrixSet>"));
+	}
+	lines.push(String::from("</Contents>"));
+	lines.push(String::from("</Capabilities>"));
+
+	lines.join("\n")
+}
+
+/// Identifier of the tile matrix set for tiles of `tile_size` pixels.
+fn tile_matrix_set(tile_size: u16) -> String {
+	match tile_size {
+		256 => String::from("GoogleMapsCompatible"),
+		_ => format!("GoogleMapsCompatible{tile_size}"),
+	}
+}
+
+/// Escapes the characters that are not allowed in XML text and attribute values.
+fn escape(text: &str) -> String {
+	text
+		.replace('&', "&amp;")
+		.replace('<', "&lt;")
+		.replace('>', "&gt;")
+		.replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+	use versatiles_container::{MockReader, MockReaderProfile, TileSource};
+	use versatiles_core::TileSize;
+
+	fn get_source(id: &str, profile: MockReaderProfile) -> Result<Arc<ServerTileSource>> {
+		let reader = Arc::new(MockReader::new_mock_profile(profile)?.boxed());
+		Ok(Arc::new(ServerTileSource::from(reader, id)?))
+	}
+
+	#[test]
+	fn capabilities_list_all_sources() -> Result<()> {
+		let sources = [
+			get_source("osm", MockReaderProfile::Pbf)?,
+			get_source("satellite", MockReaderProfile::Png)?,
+		];
+		let xml = build_capabilities(&sources, "http://localhost:8080");
+		let lines = xml.lines().collect::<Vec<_>>();
+
+		assert!(xml.starts_with("<?xml"));
+		assert!(lines.contains(&"<ows:Identifier>osm</ows:Identifier>"));
+		assert!(lines.contains(&"<ows:Identifier>satellite</ows:Identifier>"));
+		assert!(lines.contains(&"<Format>application/vnd.mapbox-vector-tile</Format>"));
+		assert!(lines.contains(&"<Format>image/png</Format>"));
+		assert!(lines.contains(
+			&r#"<ResourceURL format="image/png" resourceType="tile" template="http://localhost:8080/tiles/satellite/{TileMatrix}/{TileCol}/{TileRow}"/>"#
+		));
+
+		// the mock pyramid covers zoom levels 2 to 6
+		assert!(lines.contains(&"<TileMatrixLimits><TileMatrix>2</TileMatrix><MinTileRow>1</MinTileRow><MaxTileRow>3</MaxTileRow><MinTileCol>0</MinTileCol><MaxTileCol>2</MaxTileCol></TileMatrixLimits>"));
+		assert!(lines.contains(&"<TileMatrixLimits><TileMatrix>6</TileMatrix><MinTileRow>0</MinTileRow><MaxTileRow>63</MaxTileRow><MinTileCol>0</MinTileCol><MaxTileCol>63</MaxTileCol></TileMatrixLimits>"));
+		assert!(!xml.contains("<TileMatrix>1</TileMatrix>"));
+		assert!(!xml.contains("<TileMatrix>7</TileMatrix>"));
+
+		// the tile matrix set is defined up to the highest level of all sources
+		assert_eq!(xml.matches("<TileMatrix><ows:Identifier>").count(), 7);
+		assert!(xml.contains("<ows:Identifier>0</ows:Identifier><ScaleDenominator>559082264.0287178</ScaleDenominator>"));
+
+		Ok(())
+	}
+
+	#[test]
+	fn capabilities_use_the_tile_size() -> Result<()> {
+		let mut metadata = MockReader::new_mock_profile(MockReaderProfile::Png)?.metadata().clone();
+		metadata.tile_size = Some(TileSize::Size512);
+		let reader = Arc::new(MockReader::new_mock(metadata)?.boxed());
+		let sources = [
+			get_source("osm", MockReaderProfile::Pbf)?,
+			Arc::new(ServerTileSource::from(reader, "retina")?),
+		];
+		let xml = build_capabilities(&sources, "http://localhost:8080");
+		let lines = xml.lines().collect::<Vec<_>>();
+
+		assert_eq!(
+			lines
+				.iter()
+				.filter(|line| line.starts_with("<TileMatrixSet>"))
+				.copied()
+				.collect::<Vec<_>>(),
+			[
+				"<TileMatrixSet>GoogleMapsCompatible</TileMatrixSet>",
+				"<TileMatrixSet>GoogleMapsCompatible512</TileMatrixSet>",
+				"<TileMatrixSet>",
+				"<TileMatrixSet>",
+			]
+		);
+		assert!(lines.contains(&"<ows:Identifier>GoogleMapsCompatible512</ows:Identifier>"));
+		assert_eq!(
+			xml.matches("<TileWidth>256</TileWidth><TileHeight>256</TileHeight>")
+				.count(),
+			7
+		);
+		assert_eq!(
+			xml.matches("<TileWidth>512</TileWidth><TileHeight>512</TileHeight>")
+				.count(),
+			7
+		);
+		assert!(xml.contains("<ows:Identifier>0</ows:Identifier><ScaleDenominator>279541132.0143589</ScaleDenominator>"));
+		assert_eq!(xml.matches("<WellKnownScaleSet>").count(), 1);
+		Ok(())
+	}
+
+	#[test]
+	fn escape_special_characters() {
+		assert_eq!(escape(r#"a&b<c>"d""#), "a&amp;b&lt;c&gt;&quot;d&quot;");
+	}
+}
@@ -1,7 +1,7 @@
 use anyhow::{Result, bail};
 use std::path::PathBuf;
 use versatiles_container::{TilesConverterParameters, TilesRuntime, convert_tiles_container};
-use versatiles_core::{GeoBBox, TileBBoxPyramid, TileCompression, TileFormat};
+use versatiles_core::{GeoBBox, TileBBoxPyramid, TileCompression, TileFormat, TileSize};
 use versatiles_derive::context;
 
 #[derive(clap::Args, Debug)]
@@ -43,6 +43,42 @@ pub struct Subcommand {
 	#[arg(long, short, value_enum, display_order = 2)]
 	compress: Option<TileCompression>,
 
+	/// recompress all tiles, even if the compression doesn't change.
+	/// By default, tiles are copied byte for byte if possible.
+	#[arg(long, display_order = 2)]
+	force_recompress: bool,
+
+	/// Brotli quality level (0-11) for tiles compressed to Brotli.
+	/// Higher levels produce smaller tiles but take longer. [default: 10]
+	#[arg(long, value_name = "LEVEL", value_parser = clap::value_parser!(u32).range(0..=11), display_order = 2)]
+	brotli_level: Option<u32>,
+
+	/// resize raster tiles to SIZE×SIZE pixels (256 or 512), using a Lanczos filter.
+	/// Tiles that already have this size are copied unchanged. Vector tiles are not affected.
+	#[arg(long, value_name = "SIZE", value_parser = parse_tile_size, display_order = 2)]
+	tile_size: Option<TileSize>,
+
+	/// losslessly optimize PNG tiles (palette and grey reduction, highest zlib effort).
+	/// Tiles get ~10-20% smaller, but the conversion takes much longer.
+	#[arg(long, display_order = 2)]
+	optimize: bool,
+
+	/// reduce PNG tiles to at most MAX_COLORS colors (2-256) and store them as palette images.
+	/// Lossy if a tile has more colors. Takes precedence over --optimize.
+	#[arg(long, value_name = "MAX_COLORS", value_parser = clap::value_parser!(u16).range(2..=256), display_order = 2)]
+	quantize: Option<u16>,
+
+	/// drop empty tiles (fully transparent images, vector tiles without features).
+	/// Only tiles up to MAX_BYTES (stored size) are checked. [default: 2048]
+	#[arg(long, value_name = "MAX_BYTES", num_args = 0..=1, require_equals = true, default_missing_value = "2048", display_order = 2)]
+	elide_empty: Option<u64>,
+
+	/// drop blank tiles: raster tiles with a single uniform color (e.g. plain ocean) and vector tiles
+	/// without features. Includes everything --elide-empty drops, but checks every tile whatever its
+	/// size. The colors of dropped tiles are logged.
+	#[arg(long, display_order = 2)]
+	drop_blank: bool,
+
 	/// swap rows and columns, e.g. z/x/y -> z/y/x
 	#[arg(long, display_order = 3)]
 	swap_xy: bool,
@@ -54,6 +90,21 @@ pub struct Subcommand {
 	/// set the output tile format
 	#[arg(long, value_name = "TILE_FORMAT", display_order = 3)]
 	tile_format: Option<TileFormat>,
+
+	/// write byte-identical output for identical input, e.g. for artifact caching.
+	/// Tiles are sorted per block, so this needs more memory.
+	#[arg(long, display_order = 3)]
+	reproducible: bool,
+
+	/// only print tile counts, compression and an estimated output size,
+	/// but don't write the output
+	#[arg(long, display_order = 4)]
+	dry_run: bool,
+
+	/// write a JSON report of the conversion (metadata, tiles per zoom level, bytes written,
+	/// warnings, wall time) to FILE, also if the conversion fails
+	#[arg(long, value_name = "FILE", display_order = 4)]
+	report: Option<PathBuf>,
 }
 
 #[tokio::main]
@@ -64,9 +115,20 @@ pub async fn run(arguments: &Subcommand, runtime: TilesRuntime) -> Result<()> {
 
 	let parameters = TilesConverterParameters {
 		bbox_pyramid: get_bbox_pyramid(arguments)?,
+		geo_bbox: None,
 		flip_y: arguments.flip_y,
 		swap_xy: arguments.swap_xy,
 		tile_compression: arguments.compress,
+		force_recompress: arguments.force_recompress,
+		brotli_level: arguments.brotli_level,
+		tile_size: arguments.tile_size,
+		optimize_png: arguments.optimize,
+		quantize_png: arguments.quantize,
+		elide_empty_tiles: arguments.elide_empty,
+		drop_blank_tiles: arguments.drop_blank,
+		reproducible: arguments.reproducible,
+		dry_run: arguments.dry_run,
+		report_path: arguments.report.clone(),
 	};
 
 	convert_tiles_container(reader, parameters, &arguments.output_file, runtime).await?;
@@ -76,6 +138,10 @@ pub async fn run(arguments: &Subcommand, runtime: TilesRuntime) -> Result<()> {
 	Ok(())
 }
 
+fn parse_tile_size(value: &str) -> Result<TileSize> {
+	TileSize::new(value.parse()?)
+}
+
 #[context("Failed to get bounding box pyramid")]
 fn get_bbox_pyramid(arguments: &Subcommand) -> Result<Option<TileBBoxPyramid>> {
 	if arguments.min_zoom.is_none() && arguments.max_zoom.is_none() && arguments.bbox.is_none() {
@@ -120,6 +186,15 @@ mod tests {
 	use anyhow::Result;
 	use assert_fs::TempDir;
 
+	#[test]
+	fn tile_size_argument() {
+		use versatiles_core::TileSize;
+		assert_eq!(super::parse_tile_size("512").unwrap(), TileSize::Size512);
+		for invalid in ["0", "300", "8192", "big"] {
+			assert!(super::parse_tile_size(invalid).is_err(), "{invalid}");
+		}
+	}
+
 	#[test]
 	fn test_local() -> Result<()> {
 		let temp_dir = TempDir::new()?;
@@ -138,6 +213,7 @@ mod tests {
 			"versatiles",
 			"convert",
 			"--bbox=13.38,52.46,13.43,52.49",
+			"--elide-empty",
 			&format!("{temp_path}/berlin1.versatiles"),
 			&format!("{temp_path}/berlin2.versatiles"),
 		])?;
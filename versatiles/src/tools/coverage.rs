@@ -0,0 +1,65 @@
+use anyhow::Result;
+use std::path::PathBuf;
+use versatiles_container::TilesRuntime;
+
+#[derive(clap::Args, Debug)]
+#[command(arg_required_else_help = true, disable_version_flag = true)]
+pub struct Subcommand {
+	/// Tile container (path, URL, or data source expression).
+	/// Run `versatiles help source` for syntax details.
+	#[arg(required = true, verbatim_doc_comment)]
+	filename: String,
+
+	/// zoom level
+	#[arg(required = true)]
+	zoom: u8,
+
+	/// write the bitmap to this file: one bit per tile of the level's bbox,
+	/// row by row, least significant bit first
+	#[arg(long, short, verbatim_doc_comment)]
+	output: Option<PathBuf>,
+}
+
+#[tokio::main]
+pub async fn run(arguments: &Subcommand, runtime: TilesRuntime) -> Result<()> {
+	log::info!("coverage of {:?} at zoom {}", arguments.filename, arguments.zoom);
+
+	let reader = runtime.get_reader_from_str(&arguments.filename).await?;
+	let coverage = reader.coverage(arguments.zoom).await?;
+
+	if let Some(output) = &arguments.output {
+		std::fs::write(output, &coverage.bitmap)?;
+	}
+	println!("{}", coverage.as_json_value().stringify());
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::tests::run_command;
+	use anyhow::Result;
+	use assert_fs::NamedTempFile;
+
+	#[test]
+	fn test_local() -> Result<()> {
+		let output = NamedTempFile::new("coverage.bin")?;
+		run_command(vec![
+			"versatiles",
+			"coverage",
+			"-q",
+			"../testdata/berlin.mbtiles",
+			"10",
+			"--output",
+			output.to_str().unwrap(),
+		])?;
+		assert!(!std::fs::read(&output)?.is_empty());
+		Ok(())
+	}
+
+	#[test]
+	fn test_invalid_zoom() {
+		let err = run_command(vec!["versatiles", "coverage", "-q", "../testdata/berlin.mbtiles", "32"]).unwrap_err();
+		assert!(format!("{err:?}").contains("level (32) must be <= 31"), "{err:?}");
+	}
+}
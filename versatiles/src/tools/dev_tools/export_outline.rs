@@ -38,7 +38,7 @@ pub async fn run(args: &ExportOutline, runtime: TilesRuntime) -> Result<()> {
 		bail!("Only GeoJSON output is supported for now");
 	}
 
-	let bbox = *bbox_pyramid.get_level_bbox(level);
+	let bbox = *bbox_pyramid.get_bounding_bbox(level);
 	let mut stream = reader
 		.get_tile_stream(bbox)
 		.await?
@@ -46,7 +46,7 @@ pub async fn run(args: &ExportOutline, runtime: TilesRuntime) -> Result<()> {
 
 	let progress = runtime.create_progress("Scanning tile sizes", bbox.count_tiles());
 	let mut outline = TileOutline::new();
-	while let Some(entry) = stream.next().await {
+	while let Some(entry) = stream.next().await? {
 		outline.add_coord(entry.0);
 		progress.inc(1);
 	}
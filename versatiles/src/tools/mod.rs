@@ -1,9 +1,11 @@
 //! cli tools
 
 pub mod convert;
+pub mod coverage;
 pub mod dev;
 mod dev_tools;
 pub mod help;
+pub mod operations;
 pub mod probe;
 #[cfg(feature = "server")]
 pub mod serve;
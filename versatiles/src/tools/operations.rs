@@ -0,0 +1,56 @@
+use anyhow::Result;
+use versatiles_pipeline::{OperationInfo, PipelineFactory};
+
+#[derive(clap::Args, Debug)]
+#[command(disable_version_flag = true)]
+pub struct Subcommand {}
+
+pub fn run(_arguments: &Subcommand) -> Result<()> {
+	println!("{}", format_table(&PipelineFactory::new_dummy().list_operations()));
+	Ok(())
+}
+
+/// One row per operation: kind, tag name and the first line of its documentation.
+fn format_table(operations: &[OperationInfo]) -> String {
+	let rows = operations
+		.iter()
+		.map(|o| {
+			(
+				o.kind.as_str(),
+				o.tag_name.as_str(),
+				o.docs.lines().next().unwrap_or_default(),
+			)
+		})
+		.collect::<Vec<_>>();
+	let kind_width = rows.iter().map(|r| r.0.len()).chain([4]).max().unwrap_or_default();
+	let name_width = rows.iter().map(|r| r.1.len()).chain([4]).max().unwrap_or_default();
+
+	let mut lines = vec![format!("{:kind_width$}  {:name_width$}  DESCRIPTION", "KIND", "NAME")];
+	for (kind, name, description) in rows {
+		lines.push(format!("{kind:kind_width$}  {name:name_width$}  {description}"));
+	}
+	lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::tests::run_command;
+
+	#[test]
+	fn test_run() -> Result<()> {
+		run_command(vec!["versatiles", "operations"])?;
+		Ok(())
+	}
+
+	#[test]
+	fn table() {
+		let table = format_table(&PipelineFactory::new_dummy().list_operations());
+		let lines = table.lines().collect::<Vec<_>>();
+		assert_eq!(lines[0], "KIND       NAME                      DESCRIPTION");
+		assert!(lines.contains(
+			&"read       from_container            Reads a tile container, such as a `*.versatiles`, `*.mbtiles`, `*.pmtiles` or `*.tar` file."
+		));
+		assert!(lines.contains(&"transform  filter                    Filter tiles by bounding box and/or zoom levels."));
+	}
+}
@@ -1,4 +1,4 @@
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, bail};
 use regex::Regex;
 use std::{mem::swap, path::PathBuf};
 use tokio::time::{Duration, sleep};
@@ -7,6 +7,7 @@ use versatiles::{
 	server::TileServer,
 };
 use versatiles_container::{DataLocation, DataSource, TilesRuntime};
+use versatiles_derive::context;
 
 #[derive(clap::Args, Debug)]
 #[command(arg_required_else_help = true, disable_version_flag = true, verbatim_doc_comment)]
@@ -23,7 +24,13 @@ pub struct Subcommand {
 	#[arg(verbatim_doc_comment)]
 	pub tile_sources: Vec<String>,
 
-	/// Path to a configuration file (TOML format) to configure the server, CORS, static and tile sources.
+	/// Mount a tile source under a given name, as "NAME=PATH". Can be repeated.
+	/// Each source is served at "/tiles/NAME/", e.g. "--mount osm=planet.versatiles"
+	/// serves "/tiles/osm/tiles.json". All mounted sources are listed at "/tiles/sources.json".
+	#[arg(long = "mount", value_name = "NAME=PATH", verbatim_doc_comment, display_order = 0)]
+	pub mounts: Vec<String>,
+
+	/// Path to a configuration file (YAML format) to configure the server, CORS, static and tile sources.
 	/// Command line arguments will override configuration file settings.
 	#[arg(short = 'c', long, value_name = "FILE", display_order = 0)]
 	pub config: Option<PathBuf>,
@@ -51,9 +58,36 @@ pub struct Subcommand {
 	#[arg(long, display_order = 2)]
 	pub minimal_recompression: Option<bool>,
 
+	/// serve tiles in their stored compression, ignoring the Accept-Encoding of the client.
+	/// Clients that don't support the stored compression have to decompress tiles themselves.
+	#[arg(long, verbatim_doc_comment, display_order = 2)]
+	pub compression_passthrough: Option<bool>,
+
+	/// answer requests for missing tiles with "204 No Content" instead of "404 Not Found".
+	/// MapLibre treats 204 as a legitimately empty tile.
+	#[arg(long, verbatim_doc_comment, display_order = 2)]
+	pub missing_tiles_no_content: Option<bool>,
+
 	/// disable API
 	#[arg(long, display_order = 4)]
 	pub disable_api: Option<bool>,
+
+	/// serve Prometheus metrics on /metrics. The endpoint has no authentication.
+	#[arg(long, display_order = 4)]
+	pub enable_metrics: Option<bool>,
+
+	/// serve a MapLibre map of every tile source on /tiles/{id}/preview.
+	/// The page loads MapLibre GL JS from unpkg.com.
+	#[arg(long, verbatim_doc_comment, display_order = 4)]
+	pub enable_preview: Option<bool>,
+
+	/// Serve via HTTPS (with HTTP/2) using this PEM certificate chain. Requires --tls-key.
+	#[arg(long, value_name = "FILE", requires = "tls_key", display_order = 3)]
+	pub tls_cert: Option<PathBuf>,
+
+	/// PEM private key for --tls-cert.
+	#[arg(long, value_name = "FILE", requires = "tls_cert", display_order = 3)]
+	pub tls_key: Option<PathBuf>,
 }
 
 #[tokio::main]
@@ -70,11 +104,34 @@ pub async fn run(arguments: &Subcommand, runtime: TilesRuntime) -> Result<()> {
 	config
 		.server
 		.override_optional_minimal_recompression(&arguments.minimal_recompression);
+	config
+		.server
+		.override_optional_compression_passthrough(&arguments.compression_passthrough);
+	config
+		.server
+		.override_optional_missing_tiles_no_content(&arguments.missing_tiles_no_content);
 	config.server.override_optional_disable_api(&arguments.disable_api);
+	config
+		.server
+		.override_optional_enable_metrics(&arguments.enable_metrics);
+	config
+		.server
+		.override_optional_enable_preview(&arguments.enable_preview);
+	config.server.override_optional_tls_cert(&arguments.tls_cert);
+	config.server.override_optional_tls_key(&arguments.tls_key);
 
 	for src in &arguments.tile_sources {
 		let src = DataSource::parse(src)?;
-		config.tile_sources.push(TileSourceConfig { name: None, src });
+		config.tile_sources.push(TileSourceConfig {
+			name: None,
+			src,
+			compression: None,
+			fallback: Vec::new(),
+		});
+	}
+
+	for mount in &arguments.mounts {
+		config.tile_sources.push(parse_mount(mount)?);
 	}
 
 	let static_patterns: Vec<Regex> = [
@@ -103,6 +160,11 @@ pub async fn run(arguments: &Subcommand, runtime: TilesRuntime) -> Result<()> {
 			Ok(StaticSourceConfig {
 				src: DataLocation::parse(filename)?,
 				prefix,
+				directory_listing: None,
+				follow_symlinks: None,
+				serve_dotfiles: None,
+				cache_responses: None,
+				cache_ttl_seconds: None,
 			})
 		})
 		.collect::<Result<Vec<StaticSourceConfig>>>()?;
@@ -117,24 +179,90 @@ pub async fn run(arguments: &Subcommand, runtime: TilesRuntime) -> Result<()> {
 		.iter()
 		.for_each(|(url, source)| log::info!("add tile source: {} <- {source}", url.join_as_string("*")));
 
-	server.start().await?;
+	let auto_shutdown = arguments.auto_shutdown;
+	server
+		.run_until(async move {
+			if let Some(milliseconds) = auto_shutdown {
+				sleep(Duration::from_millis(milliseconds)).await
+			} else if let Err(err) = tokio::signal::ctrl_c().await {
+				log::error!("failed to listen for ctrl-c: {err}");
+				std::future::pending::<()>().await
+			}
+		})
+		.await
+}
 
-	if let Some(milliseconds) = arguments.auto_shutdown {
-		sleep(Duration::from_millis(milliseconds)).await
-	} else {
-		loop {
-			sleep(Duration::from_secs(60)).await
-		}
+/// Parses a `NAME=PATH` mount argument into a named tile source.
+#[context("parsing mount argument '{argument}', expected NAME=PATH")]
+fn parse_mount(argument: &str) -> Result<TileSourceConfig> {
+	let Some((name, path)) = argument.split_once('=') else {
+		bail!("missing '='");
+	};
+	if name.is_empty() || name.contains('/') {
+		bail!("invalid name '{name}'");
 	}
-
-	Ok(())
+	Ok(TileSourceConfig {
+		name: Some(name.to_string()),
+		src: DataSource::parse(path)?,
+		compression: None,
+		fallback: Vec::new(),
+	})
 }
 
 #[cfg(test)]
 mod tests {
+	use super::parse_mount;
 	use crate::tests::run_command;
 	use anyhow::Result;
 
+	#[test]
+	fn test_parse_mount() -> Result<()> {
+		let config = parse_mount("berlin=../testdata/berlin.mbtiles")?;
+		assert_eq!(config.name.as_deref(), Some("berlin"));
+		assert_eq!(config.src.name()?, "berlin");
+
+		let error = |argument: &str| {
+			parse_mount(argument)
+				.unwrap_err()
+				.chain()
+				.map(|e| e.to_string())
+				.collect::<Vec<_>>()
+				.join(": ")
+		};
+		assert_eq!(
+			error("berlin.mbtiles"),
+			"parsing mount argument 'berlin.mbtiles', expected NAME=PATH: missing '='"
+		);
+		assert_eq!(
+			error("=berlin.mbtiles"),
+			"parsing mount argument '=berlin.mbtiles', expected NAME=PATH: invalid name ''"
+		);
+		assert_eq!(
+			error("a/b=berlin.mbtiles"),
+			"parsing mount argument 'a/b=berlin.mbtiles', expected NAME=PATH: invalid name 'a/b'"
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn test_mounts() -> Result<()> {
+		run_command(vec![
+			"versatiles",
+			"serve",
+			"-i",
+			"127.0.0.1",
+			"-p",
+			"65005",
+			"--auto-shutdown",
+			"500",
+			"--mount",
+			"berlin=../testdata/berlin.mbtiles",
+			"--mount",
+			"pmtiles=../testdata/berlin.pmtiles",
+		])?;
+		Ok(())
+	}
+
 	#[test]
 	fn test_local() -> Result<()> {
 		run_command(vec![
@@ -167,6 +295,26 @@ mod tests {
 		Ok(())
 	}
 
+	#[test]
+	fn test_tls() -> Result<()> {
+		run_command(vec![
+			"versatiles",
+			"serve",
+			"-i",
+			"127.0.0.1",
+			"-p",
+			"65004",
+			"--tls-cert",
+			"../testdata/tls-cert.pem",
+			"--tls-key",
+			"../testdata/tls-key.pem",
+			"--auto-shutdown",
+			"500",
+			"../testdata/berlin.mbtiles[test]",
+		])?;
+		Ok(())
+	}
+
 	#[test]
 	fn test_config() -> Result<()> {
 		run_command(vec![
@@ -10,6 +10,8 @@
 //! ```
 //! where `<z>`, `<x>`, and `<y>` are zoom level and tile coordinates, `<format>` is the tile format (e.g., `png`, `pbf`), and `<compression>` is optional (e.g., `br`, `gz`).
 //!
+//! Tile files without any extension (`<root>/<z>/<x>/<y>`) are also accepted. Their format and compression are detected from the magic bytes of the first such file, see [`detect_tile_format`](versatiles_core::utils::detect_tile_format).
+//!
 //! Examples:
 //! | Path               | Description                  |
 //! |--------------------|------------------------------|
@@ -38,7 +40,7 @@
 //! ## Errors
 //! Errors are returned if the directory is not absolute, does not exist, is not a directory, contains no tiles, or if tiles have inconsistent formats or compressions.
 
-use crate::{SourceType, Tile, TileSource, TileSourceMetadata, Traversal};
+use crate::{SourceType, Tile, TileSource, TileSourceMetadata};
 use anyhow::{Result, bail, ensure};
 use async_trait::async_trait;
 use itertools::Itertools;
@@ -103,6 +105,7 @@ impl DirectoryReader {
 		let mut tile_map = HashMap::new();
 		let mut container_form: Option<TileFormat> = None;
 		let mut container_comp: Option<TileCompression> = None;
+		let mut detected: Option<(TileFormat, TileCompression)> = None;
 		let mut bbox_pyramid = TileBBoxPyramid::new_empty();
 
 		for result1 in fs::read_dir(dir)? {
@@ -134,16 +137,24 @@ impl DirectoryReader {
 
 					for entry3 in files {
 						// y level
-						let mut filename = entry3.file_name().into_string().unwrap();
-						let file_comp = TileCompression::from_filename(&mut filename);
-						let this_form = TileFormat::from_filename(&mut filename);
-
-						if this_form.is_none() {
-							continue;
-						}
-						let file_form = this_form.unwrap();
+						let filename = entry3.file_name().into_string().unwrap();
+						let (stem, file_form, file_comp) = match split_tile_filename(&filename) {
+							Some(parts) => parts,
+							// files without extension: detect format and compression from the first one
+							None if !filename.contains('.') => {
+								if filename.parse::<u32>().is_err() {
+									continue;
+								}
+								let (form, comp) = match detected {
+									Some(detected) => detected,
+									None => *detected.insert(Self::detect_format(&entry3.path())?),
+								};
+								(filename.as_str(), form, comp)
+							}
+							None => continue,
+						};
 
-						let numeric3 = filename.parse::<u32>();
+						let numeric3 = stem.parse::<u32>();
 						if numeric3.is_err() {
 							continue;
 						}
@@ -175,24 +186,14 @@ impl DirectoryReader {
 					}
 				}
 			} else {
-				match name1.as_str() {
-					"meta.json" | "tiles.json" | "metadata.json" => {
-						tilejson.merge(&TileJSON::try_from_blob_or_default(&Self::read(&entry1.path())?))?;
-					}
-					"meta.json.gz" | "tiles.json.gz" | "metadata.json.gz" => {
-						tilejson.merge(&TileJSON::try_from_blob_or_default(&decompress(
-							Self::read(&entry1.path())?,
-							TileCompression::Gzip,
-						)?))?;
-					}
-					"meta.json.br" | "tiles.json.br" | "metadata.json.br" => {
-						tilejson.merge(&TileJSON::try_from_blob_or_default(&decompress(
-							Self::read(&entry1.path())?,
-							TileCompression::Brotli,
-						)?))?;
-					}
-					&_ => {}
-				};
+				let mut name = name1;
+				let compression = TileCompression::from_filename(&mut name);
+				if matches!(name.as_str(), "meta.json" | "tiles.json" | "metadata.json") {
+					tilejson.merge(&TileJSON::try_from_blob_or_default(&decompress(
+						Self::read(&entry1.path())?,
+						compression,
+					)?))?;
+				}
 			}
 		}
 
@@ -205,14 +206,32 @@ impl DirectoryReader {
 
 		tilejson.update_from_pyramid(&bbox_pyramid);
 
+		let mut metadata = TileSourceMetadata::new_builder()
+			.with_format(tile_format)
+			.with_compression(TileCompression::Uncompressed)
+			.with_bbox_pyramid(bbox_pyramid)
+			.build()?;
+		// set afterwards: the builder rejects compressed raster tiles, but existing directories may contain them
+		metadata.tile_compression = tile_compression;
+		metadata.tile_size = tilejson.tile_size;
+
 		Ok(DirectoryReader {
 			tilejson,
 			dir: dir.to_path_buf(),
 			tile_map,
-			metadata: TileSourceMetadata::new(tile_format, tile_compression, bbox_pyramid, Traversal::ANY),
+			metadata,
 		})
 	}
 
+	/// Detects format and compression of a tile file without extension from its content.
+	#[context("detecting tile format of '{}'", path.display())]
+	fn detect_format(path: &Path) -> Result<(TileFormat, TileCompression)> {
+		let blob = Self::read(path)?;
+		let compression = detect_compression(&blob);
+		let format = detect_tile_format(&decompress(blob, compression)?).context("unknown tile format")?;
+		Ok((format, compression))
+	}
+
 	/// Reads a file into a `Blob`.
 	#[context("reading file '{}'", path.display())]
 	fn read(path: &Path) -> Result<Blob> {
@@ -256,6 +275,15 @@ impl TileSource for DirectoryReader {
 		}
 	}
 
+	/// Reads the size of the tile file, without reading the tile.
+	#[context("getting the size of tile {:?}", coord)]
+	async fn get_tile_size(&self, coord: &TileCoord) -> Result<Option<u64>> {
+		match self.tile_map.get(coord) {
+			Some(path) => Ok(Some(fs::metadata(path)?.len())),
+			None => Ok(None),
+		}
+	}
+
 	async fn get_tile_stream(&self, bbox: TileBBox) -> Result<TileStream<Tile>> {
 		self.stream_individual_tiles(bbox).await
 	}
@@ -275,7 +303,7 @@ mod tests {
 	use super::*;
 	use assert_fs::{
 		TempDir,
-		fixture::{FileWriteStr, PathChild},
+		fixture::{FileWriteBin, FileWriteStr, PathChild},
 	};
 	use std::fs::{self};
 	use versatiles_core::{assert_wildcard, utils::compress};
@@ -305,6 +333,21 @@ mod tests {
 		Ok(())
 	}
 
+	#[tokio::test]
+	async fn get_tile_size_reads_the_file_size() -> Result<()> {
+		let dir = TempDir::new()?;
+		dir.child("3/2/1.png").write_str("test tile data")?;
+
+		let reader = DirectoryReader::open_path(&dir)?;
+		let coord = TileCoord::new(3, 2, 1)?;
+		let len = fs::metadata(dir.child("3/2/1.png").path())?.len();
+		assert_eq!(reader.get_tile_size(&coord).await?, Some(len));
+		assert_eq!(len, 14);
+		assert_eq!(reader.get_tile_size(&TileCoord::new(2, 2, 1)?).await?, None);
+
+		Ok(())
+	}
+
 	#[tokio::test]
 	async fn open_path_with_nonexistent_directory() -> Result<()> {
 		let dir = TempDir::new()?;
@@ -338,6 +381,29 @@ mod tests {
 		Ok(())
 	}
 
+	#[tokio::test]
+	async fn open_path_without_extensions() -> Result<()> {
+		let dir = TempDir::new()?;
+		let png = Blob::from(b"\x89PNG\r\n\x1a\n".to_vec());
+		dir.child("3/2/1")
+			.write_binary(compress(png.clone(), TileCompression::Gzip)?.as_slice())?;
+		dir.child("3/2/2")
+			.write_binary(compress(png.clone(), TileCompression::Gzip)?.as_slice())?;
+		dir.child("3/2/readme").write_str("no tile")?;
+
+		let reader = DirectoryReader::open_path(dir.path())?;
+		assert_eq!(reader.metadata().tile_format, TileFormat::PNG);
+		assert_eq!(reader.metadata().tile_compression, TileCompression::Gzip);
+		assert_eq!(reader.metadata().bbox_pyramid.count_tiles(), 2);
+
+		let tile = reader.get_tile(&TileCoord::new(3, 2, 2)?).await?.unwrap();
+		assert_eq!(tile.into_blob(TileCompression::Uncompressed)?, png);
+
+		dir.child("3/2/3.pbf").write_str("vector tile")?;
+		assert!(DirectoryReader::open_path(dir.path()).is_err());
+		Ok(())
+	}
+
 	#[tokio::test]
 	async fn read_compressed_meta_files() -> Result<()> {
 		let dir = TempDir::new().unwrap();
@@ -401,6 +467,26 @@ mod tests {
 		Ok(())
 	}
 
+	#[tokio::test]
+	async fn extension_aliases() -> Result<()> {
+		let dir = TempDir::new()?;
+		dir.child("3/2/1.jpg").write_str("tile 1")?;
+		dir.child("3/2/2.jpeg").write_str("tile 2")?;
+		let reader = DirectoryReader::open_path(&dir)?;
+		assert_eq!(reader.metadata().tile_format, TileFormat::JPG);
+		assert_eq!(reader.metadata().bbox_pyramid.count_tiles(), 2);
+
+		let dir = TempDir::new()?;
+		dir.child("3/2/1.mvt.gz").write_str("tile 1")?;
+		dir.child("3/2/2.pbf.gz").write_str("tile 2")?;
+		let reader = DirectoryReader::open_path(&dir)?;
+		assert_eq!(reader.metadata().tile_format, TileFormat::MVT);
+		assert_eq!(reader.metadata().tile_compression, TileCompression::Gzip);
+		assert_eq!(reader.metadata().bbox_pyramid.count_tiles(), 2);
+
+		Ok(())
+	}
+
 	#[tokio::test]
 	async fn error_different_tile_formats() -> Result<()> {
 		let dir = TempDir::new()?;
@@ -454,7 +540,7 @@ mod tests {
 
 		assert_wildcard!(
 			format!("{reader:?}"),
-			"DirectoryReader { source_type: Container { name: \"directory\", uri: \"*\" }, parameters: TileSourceMetadata { bbox_pyramid: [3: [2,1,2,1] (1x1)], tile_compression: Brotli, tile_format: PNG, traversal: Traversal(AnyOrder,full) } }"
+			"DirectoryReader { source_type: Container { name: \"directory\", uri: \"*\" }, parameters: TileSourceMetadata { bbox_pyramid: [3: [2,1,2,1] (1x1)], tile_compression: Brotli, tile_format: PNG, traversal: Traversal(AnyOrder,full), tile_size: None, tile_grid: None } }"
 		);
 
 		assert_eq!(
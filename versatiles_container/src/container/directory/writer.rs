@@ -43,14 +43,14 @@
 //! ### Errors
 //! Returns errors if the destination path is not absolute, if file I/O fails, or if compression/encoding fails.
 
-use crate::{TileSource, TileSourceTraverseExt, TilesRuntime, TilesWriter, Traversal};
+use crate::{TileSource, TileSourceTraverseExt, TilesRuntime, TilesWriter, Traversal, encode_tilejson_file};
 use anyhow::{Result, bail, ensure};
 use async_trait::async_trait;
 use std::{
 	fs,
 	path::{Path, PathBuf},
 };
-use versatiles_core::{io::DataWriterTrait, utils::compress, *};
+use versatiles_core::{io::DataWriterTrait, utils::extension_for, *};
 use versatiles_derive::context;
 
 /// Writes a directory-based tile pyramid along with a compressed TileJSON (`tiles.json[.<br|gz>]`).
@@ -79,7 +79,7 @@ impl TilesWriter for DirectoryWriter {
 	///
 	/// * Validates that `path` is absolute.
 	/// * Encodes tiles using `reader.parameters().tile_format` and `reader.parameters().tile_compression`.
-	/// * Writes `tiles.json[.<compression>]` containing the reader's TileJSON, compressed to the same transport layer.
+	/// * Writes `tiles.json[.<compression>]` via [`encode_tilejson_file`], compressed like the tiles.
 	/// * Creates the `{z}/{x}/{y}` directory structure on demand.
 	///
 	/// # Errors
@@ -93,30 +93,23 @@ impl TilesWriter for DirectoryWriter {
 		let parameters = reader.metadata();
 		let tile_format = parameters.tile_format;
 
-		let extension_format = tile_format.as_extension().to_string();
 		let tile_compression = reader.metadata().tile_compression;
-		let extension_compression = tile_compression.as_extension().to_string();
+		let extension = extension_for(tile_format, tile_compression);
 
-		let tilejson = reader.tilejson();
-		let meta_data = compress(tilejson.into(), tile_compression)?;
-		let filename = format!("tiles.json{extension_compression}");
+		let (filename, meta_data) = encode_tilejson_file(reader)?;
 		Self::write(path.join(filename), meta_data)?;
 
 		reader
 			.traverse_all_tiles(
 				&Traversal::ANY,
 				move |_bbox, mut stream| {
-					let extension_format = extension_format.clone();
-					let extension_compression = extension_compression.clone();
+					let extension = extension.clone();
 					let path = path.to_path_buf();
 					Box::pin(async move {
-						while let Some(entry) = stream.next().await {
+						while let Some(entry) = stream.next().await? {
 							let (coord, tile) = entry;
 
-							let filename = format!(
-								"{}/{}/{}{}{}",
-								coord.level, coord.x, coord.y, extension_format, extension_compression
-							);
+							let filename = format!("{}/{}/{}{}", coord.level, coord.x, coord.y, extension);
 
 							// Write blob to file
 							Self::write(path.join(filename), tile.into_blob(tile_compression)?)?;
@@ -158,12 +151,13 @@ mod tests {
 		let temp_dir = assert_fs::TempDir::new()?;
 		let temp_path = temp_dir.path();
 
-		let mut mock_reader = MockReader::new_mock(TileSourceMetadata::new(
-			TileFormat::MVT,
-			TileCompression::Gzip,
-			TileBBoxPyramid::new_full(2),
-			Traversal::ANY,
-		))?;
+		let mut mock_reader = MockReader::new_mock(
+			TileSourceMetadata::new_builder()
+				.with_format(TileFormat::MVT)
+				.with_compression(TileCompression::Gzip)
+				.with_bbox_pyramid(TileBBoxPyramid::new_full(2))
+				.build()?,
+		)?;
 
 		DirectoryWriter::write_to_path(&mut mock_reader, temp_path, TilesRuntime::default()).await?;
 
@@ -187,4 +181,24 @@ mod tests {
 
 		Ok(())
 	}
+
+	#[tokio::test]
+	async fn test_tilejson_extension_follows_tile_compression() -> Result<()> {
+		let temp_dir = assert_fs::TempDir::new()?;
+
+		let mut mock_reader = MockReader::new_mock(
+			TileSourceMetadata::new_builder()
+				.with_format(TileFormat::MVT)
+				.with_compression(TileCompression::Brotli)
+				.with_bbox_pyramid(TileBBoxPyramid::new_full(1))
+				.build()?,
+		)?;
+
+		DirectoryWriter::write_to_path(&mut mock_reader, temp_dir.path(), TilesRuntime::default()).await?;
+
+		assert!(temp_dir.path().join("tiles.json.br").exists());
+		assert!(!temp_dir.path().join("tiles.json.gz").exists());
+		assert!(temp_dir.path().join("1/1/1.pbf.br").exists());
+		Ok(())
+	}
 }
@@ -50,15 +50,25 @@
 //! - Returns errors if the database is unreadable, the `format` is missing/unknown,
 //!   or queries fail.
 
-use crate::{SourceType, Tile, TileSource, TileSourceMetadata, TilesRuntime, Traversal};
-use anyhow::{Result, anyhow, ensure};
+use crate::{ContainerError, SourceType, Tile, TileSource, TileSourceMetadata, TilesRuntime};
+use anyhow::{Result, anyhow, bail, ensure};
 use async_trait::async_trait;
+use futures::{StreamExt, stream};
 use r2d2::Pool;
-use r2d2_sqlite::SqliteConnectionManager;
-use std::{path::Path, sync::Arc};
-use versatiles_core::{TileCompression::*, TileFormat::*, json::parse_json_str, types::*};
+use r2d2_sqlite::{SqliteConnectionManager, rusqlite::OptionalExtension};
+use std::{
+	path::Path,
+	sync::{
+		Arc,
+		atomic::{AtomicU64, Ordering},
+	},
+};
+use versatiles_core::{TileCompression::*, TileFormat::*, json::parse_json_str, types::*, utils::detect_gzip_variant};
 use versatiles_derive::context;
 
+/// Upper limit of tiles fetched with one query when streaming tiles.
+const MAX_TILES_PER_QUERY: u32 = 4096;
+
 /// Reader for MBTiles (SQLite) containers.
 ///
 /// Opens a SQLite database with `metadata` and `tiles` tables, merges metadata into
@@ -70,6 +80,10 @@ pub struct MBTilesReader {
 	tilejson: TileJSON,
 	metadata: TileSourceMetadata,
 	runtime: TilesRuntime,
+	/// Maximum number of tiles per query in [`TileSource::get_tile_stream`].
+	tiles_per_query: u32,
+	/// Number of queries issued by [`TileSource::get_tile_stream`].
+	query_count: AtomicU64,
 }
 
 impl MBTilesReader {
@@ -103,7 +117,10 @@ impl MBTilesReader {
 
 		let manager = SqliteConnectionManager::file(path);
 		let pool = Pool::builder().max_size(10).build(manager)?;
-		let metadata = TileSourceMetadata::new(MVT, Uncompressed, TileBBoxPyramid::new_empty(), Traversal::ANY);
+		let metadata = TileSourceMetadata::new_builder()
+			.with_format(MVT)
+			.with_compression(Uncompressed)
+			.build()?;
 
 		let mut reader = MBTilesReader {
 			name: String::from(path.to_str().unwrap()),
@@ -111,6 +128,8 @@ impl MBTilesReader {
 			tilejson: TileJSON::default(),
 			metadata,
 			runtime,
+			tiles_per_query: MAX_TILES_PER_QUERY,
+			query_count: AtomicU64::new(0),
 		};
 
 		reader.load_meta_data()?;
@@ -166,14 +185,18 @@ impl MBTilesReader {
 						tile_format = Ok(WEBP);
 						compression = Ok(Uncompressed);
 					}
-					_ => panic!("unknown file format: {value}"),
+					_ => bail!(ContainerError::UnsupportedFormat(format!("mbtiles format '{value}'"))),
 				},
 				// https://github.com/mapbox/mbtiles-spec/blob/master/1.3/spec.md#content
 				"bounds" => {
 					let bounds = value
 						.split(',')
 						.map(|s| s.parse::<f64>())
-						.collect::<Result<Vec<f64>, _>>()?;
+						.collect::<Result<Vec<f64>, _>>()
+						.map_err(|err| ContainerError::Parse {
+							what: String::from("bounds"),
+							context: format!("'{value}': {err}"),
+						})?;
 					self.tilejson.limit_bbox(GeoBBox::try_from(bounds)?);
 				}
 				"name" | "attribution" | "author" | "description" | "license" | "type" | "version" => {
@@ -196,10 +219,62 @@ impl MBTilesReader {
 		self.metadata.tile_format = tile_format?;
 		self.metadata.tile_compression = compression?;
 		self.metadata.bbox_pyramid = pyramid;
+		self.metadata.tile_size = self.detect_tile_size().unwrap_or_else(|err| {
+			log::warn!("{err:?}");
+			None
+		});
+		if let Err(err) = self.log_gzip_variant() {
+			log::warn!("{err:?}");
+		}
 
 		Ok(())
 	}
 
+	/// Logs how the first tile of a Gzip-compressed tileset is stored.
+	///
+	/// Some old tools write raw deflate or zlib streams instead of gzip, which
+	/// [`decompress_gzip`](versatiles_core::utils::decompress_gzip) accepts as well.
+	#[context("detecting gzip variant of '{}'", self.name)]
+	fn log_gzip_variant(&self) -> Result<()> {
+		if self.metadata.tile_compression != Gzip {
+			return Ok(());
+		}
+
+		let conn = self.pool.get()?;
+		let mut stmt = conn.prepare("SELECT tile_data FROM tiles LIMIT 1")?;
+		if let Ok(data) = stmt.query_row([], |row| row.get::<_, Vec<u8>>(0)) {
+			log::debug!("tiles of '{}' are stored as {}", self.name, detect_gzip_variant(&data));
+		}
+		Ok(())
+	}
+
+	/// Decode the first raster tile to determine the tile size.
+	///
+	/// MBTiles has no metadata entry for the tile size, so it is read from the tile data.
+	/// Returns `None` for vector tiles and empty tilesets.
+	///
+	/// # Errors
+	/// Returns an error if the query fails or the tile can't be decoded.
+	#[context("detecting tile size of '{}'", self.name)]
+	fn detect_tile_size(&self) -> Result<Option<TileSize>> {
+		if !self.metadata.tile_format.is_raster() {
+			return Ok(None);
+		}
+
+		let conn = self.pool.get()?;
+		let mut stmt = conn.prepare("SELECT tile_data FROM tiles LIMIT 1")?;
+		let Ok(data) = stmt.query_row([], |row| row.get::<_, Vec<u8>>(0)) else {
+			return Ok(None);
+		};
+
+		Tile::from_blob(
+			Blob::from(data),
+			self.metadata.tile_compression,
+			self.metadata.tile_format,
+		)
+		.tile_size()
+	}
+
 	/// Execute a simple aggregate query against the `tiles` table.
 	///
 	/// * `sql_value` — the SELECT expression (e.g., `MIN(tile_column)`).
@@ -327,6 +402,7 @@ impl TileSource for MBTilesReader {
 	#[context("fetching tile {:?} from '{}'", coord, self.name)]
 	async fn get_tile(&self, coord: &TileCoord) -> Result<Option<Tile>> {
 		log::trace!("read tile from coord {coord:?}");
+		ContainerError::ensure_valid_coord(coord)?;
 
 		let conn = self.pool.get()?;
 		let mut stmt =
@@ -346,13 +422,33 @@ impl TileSource for MBTilesReader {
 		}
 	}
 
+	/// Queries the length of the tile data, without reading it.
+	#[context("getting the size of tile {:?}", coord)]
+	async fn get_tile_size(&self, coord: &TileCoord) -> Result<Option<u64>> {
+		ContainerError::ensure_valid_coord(coord)?;
+
+		let conn = self.pool.get()?;
+		let mut stmt = conn
+			.prepare("SELECT length(tile_data) FROM tiles WHERE tile_column = ? AND tile_row = ? AND zoom_level = ?")?;
+
+		let max_index = 2u32.pow(coord.level as u32) - 1;
+		let size = stmt
+			.query_row([coord.x, max_index - coord.y, coord.level as u32], |row| {
+				row.get::<_, i64>(0)
+			})
+			.optional()?;
+		Ok(size.map(|size| size as u64))
+	}
+
 	/// Stream tiles within a single-zoom bounding box.
 	///
 	/// The input bbox is XYZ; rows are flipped to TMS for the query and flipped back on output.
-	/// Empty bboxes yield an empty stream.
+	/// The bbox is split into row stripes of at most [`MAX_TILES_PER_QUERY`] tiles, which are
+	/// queried one after another while the stream is consumed. Empty bboxes yield an empty stream.
 	///
 	/// # Errors
-	/// Returns an error if the query fails.
+	/// Query errors surface while the stream is consumed. The stream stops at the first failing
+	/// stripe, and consuming it returns the error instead of silently missing tiles.
 	#[context("streaming tiles for bbox {:?}", bbox)]
 	async fn get_tile_stream(&self, mut bbox: TileBBox) -> Result<TileStream<Tile>> {
 		log::debug!("get_tile_stream {:?}", bbox);
@@ -365,40 +461,67 @@ impl TileSource for MBTilesReader {
 
 		log::trace!("corrected bbox {bbox:?}");
 
-		let conn = self.pool.get().unwrap();
-		let mut stmt = conn
-			 .prepare(
-					"SELECT tile_column, tile_row, zoom_level, tile_data FROM tiles WHERE tile_column >= ? AND tile_column <= ? AND tile_row >= ? AND tile_row <= ? AND zoom_level = ?",
-			 )
-			 .unwrap();
+		let max_rows = (self.tiles_per_query / bbox.width()).max(1);
+		let stripes = bbox.iter_bbox_row_slices(max_rows);
+
+		let tiles = stream::iter(stripes)
+			.flat_map(move |stripe| {
+				let items = match self.query_stripe(&stripe) {
+					Ok(tiles) => tiles.into_iter().map(|(coord, tile)| (coord, Ok(tile))).collect(),
+					Err(err) => {
+						let mut coord = stripe.min_corner().expect("stripes are not empty");
+						coord.flip_y();
+						vec![(coord, Err(err))]
+					}
+				};
+				stream::iter(items)
+			})
+			.boxed();
+		Ok(TileStream::from_stream(tiles).stop_at_error())
+	}
+}
+
+impl MBTilesReader {
+	/// Fetches all tiles of a stripe with a single query.
+	///
+	/// `bbox` uses TMS rows; the returned coordinates are flipped back to XYZ.
+	#[context("querying tiles of {bbox:?} from '{}'", self.name)]
+	fn query_stripe(&self, bbox: &TileBBox) -> Result<Vec<(TileCoord, Tile)>> {
+		self.query_count.fetch_add(1, Ordering::Relaxed);
+
+		let conn = self.pool.get()?;
+		let mut stmt = conn.prepare_cached(
+			"SELECT tile_column, tile_row, tile_data FROM tiles WHERE zoom_level = ? AND tile_row BETWEEN ? AND ? AND tile_column BETWEEN ? AND ?",
+		)?;
 
-		let vec: Vec<(TileCoord, Tile)> = stmt
+		let rows = stmt
 			.query_map(
 				[
-					bbox.x_min()?,
-					bbox.x_max()?,
+					u32::from(bbox.level),
 					bbox.y_min()?,
 					bbox.y_max()?,
-					bbox.level as u32,
+					bbox.x_min()?,
+					bbox.x_max()?,
 				],
-				move |row| {
-					let x = row.get::<_, u32>(0)?;
-					let y = row.get::<_, u32>(1)?;
-					let level = row.get::<_, u8>(2)?;
-					let mut coord = TileCoord::new(level, x, y).unwrap();
-					coord.flip_y();
-					let blob = Blob::from(row.get::<_, Vec<u8>>(3)?);
-					let tile = Tile::from_blob(blob, self.metadata.tile_compression, self.metadata.tile_format);
-					Ok((coord, tile))
-				},
-			)
-			.unwrap()
-			.filter_map(|r| r.ok())
-			.collect();
-
-		log::trace!("got {} tiles", vec.len());
-
-		Ok(TileStream::from_vec(vec))
+				|row| Ok((row.get::<_, u32>(0)?, row.get::<_, u32>(1)?, row.get::<_, Vec<u8>>(2)?)),
+			)?
+			.collect::<Result<Vec<_>, _>>()?;
+
+		log::trace!("got {} tiles for stripe {bbox:?}", rows.len());
+
+		rows
+			.into_iter()
+			.map(|(x, y, data)| {
+				let mut coord = TileCoord::new(bbox.level, x, y)?;
+				coord.flip_y();
+				let tile = Tile::from_blob(
+					Blob::from(data),
+					self.metadata.tile_compression,
+					self.metadata.tile_format,
+				);
+				Ok((coord, tile))
+			})
+			.collect()
 	}
 }
 
@@ -434,7 +557,7 @@ pub mod tests {
 
 		assert_eq!(
 			format!("{reader:?}"),
-			"MBTilesReader { parameters: TileSourceMetadata { bbox_pyramid: [0: [0,0,0,0] (1x1), 1: [1,0,1,0] (1x1), 2: [2,1,2,1] (1x1), 3: [4,2,4,2] (1x1), 4: [8,5,8,5] (1x1), 5: [17,10,17,10] (1x1), 6: [34,20,34,21] (1x2), 7: [68,41,68,42] (1x2), 8: [137,83,137,84] (1x2), 9: [274,167,275,168] (2x2), 10: [549,335,551,336] (3x2), 11: [1098,670,1102,673] (5x4), 12: [2196,1340,2204,1346] (9x7), 13: [4393,2680,4409,2693] (17x14), 14: [8787,5361,8818,5387] (32x27)], tile_compression: Gzip, tile_format: MVT, traversal: Traversal(AnyOrder,full) } }"
+			"MBTilesReader { parameters: TileSourceMetadata { bbox_pyramid: [0: [0,0,0,0] (1x1), 1: [1,0,1,0] (1x1), 2: [2,1,2,1] (1x1), 3: [4,2,4,2] (1x1), 4: [8,5,8,5] (1x1), 5: [17,10,17,10] (1x1), 6: [34,20,34,21] (1x2), 7: [68,41,68,42] (1x2), 8: [137,83,137,84] (1x2), 9: [274,167,275,168] (2x2), 10: [549,335,551,336] (3x2), 11: [1098,670,1102,673] (5x4), 12: [2196,1340,2204,1346] (9x7), 13: [4393,2680,4409,2693] (17x14), 14: [8787,5361,8818,5387] (32x27)], tile_compression: Gzip, tile_format: MVT, traversal: Traversal(AnyOrder,full), tile_size: None, tile_grid: None } }"
 		);
 		assert_eq!(
 			reader.source_type().to_string(),
@@ -446,7 +569,7 @@ pub mod tests {
 		);
 		assert_eq!(
 			format!("{:?}", reader.metadata()),
-			"TileSourceMetadata { bbox_pyramid: [0: [0,0,0,0] (1x1), 1: [1,0,1,0] (1x1), 2: [2,1,2,1] (1x1), 3: [4,2,4,2] (1x1), 4: [8,5,8,5] (1x1), 5: [17,10,17,10] (1x1), 6: [34,20,34,21] (1x2), 7: [68,41,68,42] (1x2), 8: [137,83,137,84] (1x2), 9: [274,167,275,168] (2x2), 10: [549,335,551,336] (3x2), 11: [1098,670,1102,673] (5x4), 12: [2196,1340,2204,1346] (9x7), 13: [4393,2680,4409,2693] (17x14), 14: [8787,5361,8818,5387] (32x27)], tile_compression: Gzip, tile_format: MVT, traversal: Traversal(AnyOrder,full) }"
+			"TileSourceMetadata { bbox_pyramid: [0: [0,0,0,0] (1x1), 1: [1,0,1,0] (1x1), 2: [2,1,2,1] (1x1), 3: [4,2,4,2] (1x1), 4: [8,5,8,5] (1x1), 5: [17,10,17,10] (1x1), 6: [34,20,34,21] (1x2), 7: [68,41,68,42] (1x2), 8: [137,83,137,84] (1x2), 9: [274,167,275,168] (2x2), 10: [549,335,551,336] (3x2), 11: [1098,670,1102,673] (5x4), 12: [2196,1340,2204,1346] (9x7), 13: [4393,2680,4409,2693] (17x14), 14: [8787,5361,8818,5387] (32x27)], tile_compression: Gzip, tile_format: MVT, traversal: Traversal(AnyOrder,full), tile_size: None, tile_grid: None }"
 		);
 		assert_eq!(reader.metadata().tile_compression, Gzip);
 		assert_eq!(reader.metadata().tile_format, MVT);
@@ -460,11 +583,114 @@ pub mod tests {
 		assert_eq!(tile.range(0..10), &[31, 139, 8, 0, 0, 0, 0, 0, 0, 3]);
 		assert_eq!(tile.range(172959..172969), &[255, 15, 172, 89, 205, 237, 7, 134, 5, 0]);
 
+		assert_eq!(
+			reader.get_tile_size(&TileCoord::new(14, 8803, 5376)?).await?,
+			Some(172969)
+		);
+		assert_eq!(reader.get_tile_size(&TileCoord::new(15, 0, 0)?).await?, None);
+
 		MockWriter::write(&mut reader).await?;
 
 		Ok(())
 	}
 
+	#[tokio::test]
+	async fn get_tile_with_invalid_coord() -> Result<()> {
+		let reader = MBTilesReader::open_path(&PATH, TilesRuntime::default())?;
+
+		// valid, but not in the file
+		assert!(reader.get_tile(&TileCoord::new(15, 0, 0)?).await?.is_none());
+
+		let coord = TileCoord { level: 3, x: 0, y: 9 };
+		let err = reader.get_tile(&coord).await.unwrap_err();
+		assert!(matches!(
+			ContainerError::find(&err),
+			Some(ContainerError::InvalidCoord(c)) if *c == coord
+		));
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn tile_stream_queries_row_stripes() -> Result<()> {
+		async fn coords(reader: &MBTilesReader, bbox: TileBBox) -> Result<(Vec<TileCoord>, u64)> {
+			reader.query_count.store(0, Ordering::Relaxed);
+			let mut coords = reader.get_tile_stream(bbox).await?.to_vec().await.unwrap();
+			coords.sort_by_key(|(coord, _)| (coord.y, coord.x));
+			let coords = coords.into_iter().map(|(coord, _)| coord).collect();
+			Ok((coords, reader.query_count.load(Ordering::Relaxed)))
+		}
+
+		let mut reader = MBTilesReader::open_path(&PATH, TilesRuntime::default())?;
+		// 32 × 27 tiles; reading them one by one would need 864 queries
+		let bbox = TileBBox::from_min_and_max(14, 8787, 5361, 8818, 5387)?;
+
+		let (all, count) = coords(&reader, bbox).await?;
+		assert_eq!(count, 1);
+		assert!(all.iter().all(|coord| bbox.contains(coord)));
+		assert!(!all.is_empty());
+
+		// 64 tiles per query → stripes of 2 rows → 14 queries
+		reader.tiles_per_query = 64;
+		let (striped, count) = coords(&reader, bbox).await?;
+		assert_eq!(count, 14);
+		assert_eq!(striped, all);
+
+		// a bbox wider than the limit still reads one row per query
+		reader.tiles_per_query = 8;
+		let (rows, count) = coords(&reader, bbox).await?;
+		assert_eq!(count, 27);
+		assert_eq!(rows, all);
+
+		assert_eq!(coords(&reader, TileBBox::new_empty(14)?).await?, (vec![], 0));
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn tile_stream_fails_on_query_errors() {
+		let file = assert_fs::NamedTempFile::new("broken.mbtiles").unwrap();
+		let conn = r2d2_sqlite::rusqlite::Connection::open(&file).unwrap();
+		conn
+			.execute_batch(
+				"CREATE TABLE metadata (name text, value text);
+			CREATE TABLE tiles (zoom_level integer, tile_column integer, tile_row integer, tile_data blob);
+			INSERT INTO metadata VALUES ('format', 'pbf');
+			INSERT INTO tiles VALUES (1, 0, 0, x'00');",
+			)
+			.unwrap();
+
+		let reader = MBTilesReader::open_path(&file, TilesRuntime::default()).unwrap();
+		conn.execute_batch("DROP TABLE tiles").unwrap();
+
+		// the missing tiles must not look like an empty stripe
+		let bbox = TileBBox::new_full(1).unwrap();
+		let stream = reader.get_tile_stream(bbox).await.unwrap();
+		let error = stream.to_vec().await.unwrap_err();
+		assert!(format!("{error:#}").contains("querying tiles of"), "{error:#}");
+	}
+
+	#[test]
+	fn open_unsupported_format() -> Result<()> {
+		let file = assert_fs::NamedTempFile::new("unsupported.mbtiles")?;
+		let conn = r2d2_sqlite::rusqlite::Connection::open(&file)?;
+		conn.execute_batch(
+			"CREATE TABLE metadata (name text, value text);
+			CREATE TABLE tiles (zoom_level integer, tile_column integer, tile_row integer, tile_data blob);
+			INSERT INTO metadata VALUES ('format', 'tiff');
+			INSERT INTO tiles VALUES (0, 0, 0, x'00');",
+		)?;
+		drop(conn);
+
+		let err = MBTilesReader::open_path(&file, TilesRuntime::default()).unwrap_err();
+		assert!(matches!(
+			ContainerError::find(&err),
+			Some(ContainerError::UnsupportedFormat(format)) if format == "mbtiles format 'tiff'"
+		));
+
+		Ok(())
+	}
+
 	// Test tile fetching
 	#[cfg(feature = "cli")]
 	#[tokio::test]
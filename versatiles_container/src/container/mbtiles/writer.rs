@@ -204,14 +204,18 @@ impl TilesWriter for MBTilesWriter {
 					let writer_mutex = Arc::clone(&writer_mutex);
 					Box::pin(async move {
 						let mut writer = writer_mutex.lock().await;
+						let mut buffer = Vec::with_capacity(4096);
 						stream
 							.map_item_parallel(move |tile| tile.into_blob(tile_compression))
-							.unwrap_results()
-							.for_each_buffered(4096, |v| {
-								writer.add_tiles(&v).unwrap();
+							.try_for_each(|coord, blob| {
+								buffer.push((coord, blob));
+								if buffer.len() >= 4096 {
+									writer.add_tiles(&std::mem::take(&mut buffer))?;
+								}
+								Ok(())
 							})
-							.await;
-						Ok(())
+							.await?;
+						writer.add_tiles(&buffer)
 					})
 				},
 				runtime.clone(),
@@ -239,7 +243,7 @@ impl TilesWriter for MBTilesWriter {
 #[cfg(test)]
 mod tests {
 	use super::*;
-	use crate::{MBTilesReader, MockReader, MockWriter, TileSourceMetadata};
+	use crate::{MBTilesReader, MockReader, MockReaderProfile, MockWriter, TileSourceMetadata};
 	use assert_fs::NamedTempFile;
 
 	#[tokio::test]
@@ -249,6 +253,8 @@ mod tests {
 			tile_compression: TileCompression::Gzip,
 			tile_format: TileFormat::MVT,
 			traversal: Traversal::ANY,
+			tile_size: None,
+			tile_grid: None,
 		})?;
 
 		let filename = NamedTempFile::new("temp.mbtiles")?;
@@ -260,4 +266,17 @@ mod tests {
 
 		Ok(())
 	}
+
+	#[tokio::test]
+	async fn read_write_keeps_tile_size() -> Result<()> {
+		let mut mock_reader = MockReader::new_mock_profile(MockReaderProfile::Png512)?;
+
+		let filename = NamedTempFile::new("temp.mbtiles")?;
+		MBTilesWriter::write_to_path(&mut mock_reader, &filename, TilesRuntime::default()).await?;
+
+		let reader = MBTilesReader::open_path(&filename, TilesRuntime::default())?;
+		assert_eq!(reader.metadata().tile_size, Some(TileSize::Size512));
+
+		Ok(())
+	}
 }
@@ -4,6 +4,8 @@
 //!
 //! ## MockReader
 //! The `MockReader` struct is the main component, which can be initialized with different profiles representing various tile formats and compressions.
+//! Tests that need specific tiles create it from a list of tiles with [`MockReader::from_tiles`], and
+//! simulate slow or broken sources with [`MockReader::with_delay`] and [`MockReader::with_failure`].
 //!
 //! ## Usage
 //! These mocks can be used to simulate tile reading operations in tests, allowing verification of code behavior under controlled conditions.
@@ -22,10 +24,10 @@
 //! }
 //! ```
 
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc, time::Duration};
 
-use crate::{SourceType, Tile, TileSource, TileSourceMetadata, Traversal};
-use anyhow::Result;
+use crate::{SourceType, Tile, TileSource, TileSourceMetadata};
+use anyhow::{Result, bail, ensure};
 use async_trait::async_trait;
 use versatiles_core::{utils::compress, *};
 use versatiles_derive::context;
@@ -37,6 +39,8 @@ pub enum MockReaderProfile {
 	Json,
 	/// Mock profile for PNG format.
 	Png,
+	/// Mock profile for PNG format with 512×512 pixel ("@2x") tiles.
+	Png512,
 	/// Mock profile for PBF format.
 	Pbf,
 }
@@ -44,12 +48,19 @@ pub enum MockReaderProfile {
 pub const MOCK_BYTES_JPG: &[u8; 671] = include_bytes!("./mock_tiles/mock.jpg");
 pub const MOCK_BYTES_PBF: &[u8; 54] = include_bytes!("./mock_tiles/mock.pbf");
 pub const MOCK_BYTES_PNG: &[u8; 103] = include_bytes!("./mock_tiles/mock.png");
+pub const MOCK_BYTES_PNG_512: &[u8; 126] = include_bytes!("./mock_tiles/mock_512.png");
 pub const MOCK_BYTES_WEBP: &[u8; 44] = include_bytes!("./mock_tiles/mock.webp");
 
 /// Mock implementation of a `TilesReader`.
 pub struct MockReader {
 	metadata: TileSourceMetadata,
 	tilejson: TileJSON,
+	/// Tiles served instead of the generated mock tiles, see [`MockReader::from_tiles`].
+	tiles: Option<HashMap<TileCoord, Tile>>,
+	/// Error message of every tile request, see [`MockReader::with_failure`].
+	failure: Option<String>,
+	/// Time every tile request takes, see [`MockReader::with_delay`].
+	delay: Option<Duration>,
 }
 
 impl MockReader {
@@ -64,21 +75,27 @@ impl MockReader {
 		bbox_pyramid.set_level_bbox(TileBBox::new_full(6)?);
 
 		MockReader::new_mock(match profile {
-			MockReaderProfile::Json => TileSourceMetadata::new(
-				TileFormat::JSON,
-				TileCompression::Uncompressed,
-				bbox_pyramid,
-				Traversal::ANY,
-			),
-			MockReaderProfile::Png => TileSourceMetadata::new(
-				TileFormat::PNG,
-				TileCompression::Uncompressed,
-				bbox_pyramid,
-				Traversal::ANY,
-			),
-			MockReaderProfile::Pbf => {
-				TileSourceMetadata::new(TileFormat::MVT, TileCompression::Gzip, bbox_pyramid, Traversal::ANY)
-			}
+			MockReaderProfile::Json => TileSourceMetadata::new_builder()
+				.with_format(TileFormat::JSON)
+				.with_compression(TileCompression::Uncompressed)
+				.with_bbox_pyramid(bbox_pyramid)
+				.build()?,
+			MockReaderProfile::Png => TileSourceMetadata::new_builder()
+				.with_format(TileFormat::PNG)
+				.with_compression(TileCompression::Uncompressed)
+				.with_bbox_pyramid(bbox_pyramid)
+				.build()?,
+			MockReaderProfile::Png512 => TileSourceMetadata::new_builder()
+				.with_format(TileFormat::PNG)
+				.with_compression(TileCompression::Uncompressed)
+				.with_bbox_pyramid(bbox_pyramid)
+				.with_tile_size(TileSize::Size512)
+				.build()?,
+			MockReaderProfile::Pbf => TileSourceMetadata::new_builder()
+				.with_format(TileFormat::MVT)
+				.with_compression(TileCompression::Gzip)
+				.with_bbox_pyramid(bbox_pyramid)
+				.build()?,
 		})
 	}
 
@@ -87,7 +104,67 @@ impl MockReader {
 	pub fn new_mock(metadata: TileSourceMetadata) -> Result<MockReader> {
 		let mut tilejson = TileJSON::default();
 		tilejson.set_string("type", "dummy")?;
-		Ok(MockReader { metadata, tilejson })
+		Ok(MockReader {
+			metadata,
+			tilejson,
+			tiles: None,
+			failure: None,
+			delay: None,
+		})
+	}
+
+	/// Creates a mock tiles reader that serves exactly the given tiles.
+	///
+	/// Format and compression are taken from the first tile, the bbox pyramid covers all tiles.
+	/// Use [`with_metadata`](Self::with_metadata) to declare other metadata.
+	#[context("creating mock reader from tiles")]
+	pub fn from_tiles(tiles: Vec<(TileCoord, Tile)>) -> Result<MockReader> {
+		ensure!(!tiles.is_empty(), "a mock reader needs at least one tile");
+		let mut bbox_pyramid = TileBBoxPyramid::new_empty();
+		for (coord, _) in &tiles {
+			bbox_pyramid.include_coord(coord);
+		}
+		let mut reader = MockReader::new_mock(
+			TileSourceMetadata::new_builder()
+				.with_format(tiles[0].1.format())
+				.with_compression(tiles[0].1.compression())
+				.with_bbox_pyramid(bbox_pyramid)
+				.build()?,
+		)?;
+		reader.tiles = Some(tiles.into_iter().collect());
+		Ok(reader)
+	}
+
+	/// Replaces the metadata, e.g. to declare a larger bbox pyramid than the tiles cover.
+	#[must_use]
+	pub fn with_metadata(mut self, metadata: TileSourceMetadata) -> MockReader {
+		self.metadata = metadata;
+		self
+	}
+
+	/// Lets every tile request fail with `message`.
+	#[must_use]
+	pub fn with_failure(mut self, message: &str) -> MockReader {
+		self.failure = Some(message.to_string());
+		self
+	}
+
+	/// Answers every tile request (including failures) only after `delay`.
+	#[must_use]
+	pub fn with_delay(mut self, delay: Duration) -> MockReader {
+		self.delay = Some(delay);
+		self
+	}
+
+	/// Waits for the delay and returns the failure, if there are any.
+	async fn before_request(&self) -> Result<()> {
+		if let Some(delay) = self.delay {
+			tokio::time::sleep(delay).await;
+		}
+		if let Some(failure) = &self.failure {
+			bail!("{failure}");
+		}
+		Ok(())
 	}
 }
 
@@ -107,29 +184,52 @@ impl TileSource for MockReader {
 
 	#[context("fetching mock tile {:?} (format={:?}, compression={:?})", coord, self.metadata.tile_format, self.metadata.tile_compression)]
 	async fn get_tile(&self, coord: &TileCoord) -> Result<Option<Tile>> {
-		use TileFormat::*;
-
-		if !self.metadata.bbox_pyramid.contains_coord(coord) {
-			return Ok(None);
+		self.before_request().await?;
+		match &self.tiles {
+			Some(tiles) => Ok(tiles.get(coord).cloned()),
+			None => mock_tile(&self.metadata, coord),
 		}
+	}
 
-		let format = self.metadata.tile_format;
-		let mut blob = match format {
-			JSON => Blob::from(coord.as_json()),
-			PNG => Blob::from(MOCK_BYTES_PNG.to_vec()),
-			MVT => Blob::from(MOCK_BYTES_PBF.to_vec()),
-			//AVIF => Blob::from(MOCK_BYTES_AVIF.to_vec()),
-			JPG => Blob::from(MOCK_BYTES_JPG.to_vec()),
-			WEBP => Blob::from(MOCK_BYTES_WEBP.to_vec()),
-			_ => panic!("tile format {format:?} is not implemented for MockReader"),
+	async fn get_tile_stream(&self, bbox: TileBBox) -> Result<TileStream<Tile>> {
+		self.before_request().await?;
+		let Some(tiles) = &self.tiles else {
+			let metadata = self.metadata.clone();
+			return Ok(TileStream::from_iter_coord(bbox.into_iter_coords(), move |coord| {
+				mock_tile(&metadata, &coord).ok().flatten()
+			}));
 		};
-		blob = compress(blob, self.metadata.tile_compression)?;
-		Ok(Some(Tile::from_blob(blob, self.metadata.tile_compression, format)))
+		let mut tiles: Vec<(TileCoord, Tile)> = tiles
+			.iter()
+			.filter(|(coord, _)| bbox.contains(coord))
+			.map(|(coord, tile)| (*coord, tile.clone()))
+			.collect();
+		tiles.sort_by_key(|(coord, _)| (coord.y, coord.x));
+		Ok(TileStream::from_vec(tiles))
 	}
+}
 
-	async fn get_tile_stream(&self, bbox: TileBBox) -> Result<TileStream<Tile>> {
-		self.stream_individual_tiles(bbox).await
+/// Generates the mock tile at `coord` for the format and compression of `metadata`.
+fn mock_tile(metadata: &TileSourceMetadata, coord: &TileCoord) -> Result<Option<Tile>> {
+	use TileFormat::*;
+
+	if !metadata.bbox_pyramid.contains_coord(coord) {
+		return Ok(None);
 	}
+
+	let format = metadata.tile_format;
+	let mut blob = match format {
+		JSON => Blob::from(coord.as_json()),
+		PNG if metadata.tile_size == Some(TileSize::Size512) => Blob::from(MOCK_BYTES_PNG_512.to_vec()),
+		PNG => Blob::from(MOCK_BYTES_PNG.to_vec()),
+		MVT => Blob::from(MOCK_BYTES_PBF.to_vec()),
+		//AVIF => Blob::from(MOCK_BYTES_AVIF.to_vec()),
+		JPG => Blob::from(MOCK_BYTES_JPG.to_vec()),
+		WEBP => Blob::from(MOCK_BYTES_WEBP.to_vec()),
+		_ => panic!("tile format {format:?} is not implemented for MockReader"),
+	};
+	blob = compress(blob, metadata.tile_compression)?;
+	Ok(Some(Tile::from_blob(blob, metadata.tile_compression, format)))
 }
 
 impl std::fmt::Debug for MockReader {
@@ -181,10 +281,62 @@ mod tests {
 		};
 
 		test(MockReaderProfile::Png, Blob::from(MOCK_BYTES_PNG.to_vec())).await;
+		test(MockReaderProfile::Png512, Blob::from(MOCK_BYTES_PNG_512.to_vec())).await;
 		test(MockReaderProfile::Pbf, Blob::from(MOCK_BYTES_PBF.to_vec())).await;
 		test(MockReaderProfile::Json, Blob::from("{\"z\":6,\"x\":23,\"y\":45}")).await;
 	}
 
+	#[tokio::test]
+	async fn tile_size_matches_tiles() -> Result<()> {
+		for (profile, tile_size) in [
+			(MockReaderProfile::Png, TileSize::Size256),
+			(MockReaderProfile::Png512, TileSize::Size512),
+		] {
+			let reader = MockReader::new_mock_profile(profile)?;
+			let mut tile = reader.get_tile(&TileCoord::new(4, 5, 6)?).await?.unwrap();
+			assert_eq!(tile.tile_size()?, Some(tile_size));
+		}
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn from_tiles() -> Result<()> {
+		let tile = |text: &str| Tile::from_blob(Blob::from(text), TileCompression::Uncompressed, TileFormat::JSON);
+		let reader = MockReader::from_tiles(vec![
+			(TileCoord::new(3, 2, 1)?, tile("b")),
+			(TileCoord::new(3, 1, 1)?, tile("a")),
+		])?;
+		assert_eq!(reader.metadata().tile_format, TileFormat::JSON);
+		assert_eq!(reader.metadata().bbox_pyramid.count_tiles(), 2);
+		assert!(reader.get_tile(&TileCoord::new(3, 0, 1)?).await?.is_none());
+
+		let tiles = reader
+			.get_tile_stream(TileBBox::new_full(3)?)
+			.await?
+			.to_vec()
+			.await
+			.unwrap();
+		let tiles = tiles
+			.into_iter()
+			.map(|(coord, tile)| Ok((coord.x, tile.into_blob(TileCompression::Uncompressed)?.into_string())))
+			.collect::<Result<Vec<_>>>()?;
+		assert_eq!(tiles, [(1, "a".to_string()), (2, "b".to_string())]);
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn with_failure_and_delay() -> Result<()> {
+		let reader = MockReader::new_mock_profile(MockReaderProfile::Png)?
+			.with_failure("source is broken")
+			.with_delay(Duration::from_millis(20));
+		let start = std::time::Instant::now();
+		let error = reader.get_tile(&TileCoord::new(4, 5, 6)?).await.unwrap_err();
+		assert_eq!(error.root_cause().to_string(), "source is broken");
+		assert!(start.elapsed() >= Duration::from_millis(20));
+		assert!(reader.get_tile_stream(TileBBox::new_full(4)?).await.is_err());
+		Ok(())
+	}
+
 	#[tokio::test]
 	async fn convert_from() -> Result<()> {
 		let mut reader = MockReader::new_mock_profile(MockReaderProfile::Png)?;
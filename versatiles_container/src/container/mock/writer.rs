@@ -50,7 +50,7 @@ impl MockWriter {
 				&Traversal::ANY,
 				|_bbox, mut stream| {
 					Box::pin(async move {
-						while stream.next().await.is_some() {}
+						while stream.next().await?.is_some() {}
 						Ok(())
 					})
 				},
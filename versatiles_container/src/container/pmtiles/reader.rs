@@ -146,15 +146,16 @@ impl PMTilesReader {
 		)?;
 		log::trace!("Bounding box pyramid: {:?}", bbox_pyramid);
 
-		let metadata = TileSourceMetadata::new(
-			header.tile_type.as_value()?,
-			header.tile_compression.as_value()?,
-			bbox_pyramid,
-			Traversal {
+		let mut metadata = TileSourceMetadata::new_builder()
+			.with_format(header.tile_type.as_value()?)
+			.with_compression(header.tile_compression.as_value()?)
+			.with_bbox_pyramid(bbox_pyramid)
+			.with_traversal(Traversal {
 				order: TraversalOrder::PMTiles,
 				size: TraversalSize::new_default(),
-			},
-		);
+			})
+			.build()?;
+		metadata.tile_size = tilejson.tile_size;
 		log::trace!("Reader parameters: {:?}", metadata);
 
 		let root_entries = Arc::new(EntriesV3::from_blob(&root_bytes_uncompressed)?);
@@ -172,6 +173,52 @@ impl PMTilesReader {
 		})
 	}
 
+	/// Returns the byte range of a tile in the data reader, or `None` if the tile does not exist.
+	///
+	/// Converts the coordinate to a **Hilbert tile ID**, then traverses up to three levels
+	/// of PMTiles directories to locate the tile. Leaf directories are cached to avoid
+	/// repeated decompression.
+	#[context("looking up tile {:?} in PMTiles directories", coord)]
+	async fn get_tile_range(&self, coord: &TileCoord) -> Result<Option<ByteRange>> {
+		// Convert the tile coordinates into a unique tile ID
+		let tile_id: u64 = coord.get_hilbert_index()?;
+		// Start with the root directory entries
+		let mut entries = self.root_entries.clone();
+
+		// Iterate through the directory depth (up to 3 levels)
+		for _depth in 0..3 {
+			// Find the entry corresponding to the requested tile ID
+			let Some(entry) = entries.find_tile(tile_id) else {
+				return Ok(None);
+			};
+
+			// An empty range means there is no tile
+			if entry.range.length == 0 {
+				return Ok(None);
+			}
+
+			// If the entry represents a run of tiles, it points to the tile data
+			if entry.run_length > 0 {
+				return Ok(Some(entry.range.shifted_forward(self.header.tile_data.offset)));
+			}
+
+			// Otherwise, fetch the directory bytes for the next level
+			let range = entry.range;
+			let mut cache = self.leaves_cache.lock().await;
+			// Use the cache to avoid redundant decompression and reading
+			entries = cache.get_or_set(&range, || {
+				let mut blob = self.leaves_bytes.read_range(&range)?;
+				// Decompress the directory bytes
+				blob = decompress(blob, self.internal_compression)?;
+				let entries = EntriesV3::from_blob(&blob)?;
+				Ok(Arc::new(entries))
+			})?;
+		}
+
+		// If the tile data is not found after traversing all levels, return an error
+		bail!("not found")
+	}
+
 	/// Decode and return the root directory entries (`EntriesV3`).
 	#[context("reading PMTiles root entries")]
 	pub fn get_tile_entries(&self) -> Result<EntriesV3> {
@@ -275,64 +322,26 @@ impl TileSource for PMTilesReader {
 
 	/// Fetch a tile by XYZ coordinate.
 	///
-	/// Converts the coordinate to a **Hilbert tile ID**, then traverses up to three levels
-	/// of PMTiles directories to locate the tile. Leaf directories are cached to avoid
-	/// repeated decompression. Returns `Ok(None)` if the tile does not exist.
+	/// Looks up the byte range of the tile in the directories, see
+	/// [`PMTilesReader::get_tile_range`], and reads it. Returns `Ok(None)` if the tile does not exist.
 	#[context("fetching tile {:?} from PMTiles", coord)]
 	async fn get_tile(&self, coord: &TileCoord) -> Result<Option<Tile>> {
-		// Log the requested tile coordinates for debugging purposes
 		log::trace!("get_tile {:?}", coord);
 
-		// Convert the tile coordinates into a unique tile ID
-		let tile_id: u64 = coord.get_hilbert_index()?;
-		// Start with the root directory entries
-		let mut entries = self.root_entries.clone();
-
-		// Iterate through the directory depth (up to 3 levels)
-		for _depth in 0..3 {
-			// Find the entry corresponding to the requested tile ID
-			let entry = entries.find_tile(tile_id);
-
-			// If the entry is not found, return None
-			let entry = if let Some(entry) = entry {
-				entry
-			} else {
-				return Ok(None);
-			};
-
-			// Check if the entry has a valid range
-			if entry.range.length > 0 {
-				// If the entry represents a run of tiles, directly fetch the tile data
-				if entry.run_length > 0 {
-					return Ok(Some(Tile::from_blob(
-						self
-							.data_reader
-							.read_range(&entry.range.shifted_forward(self.header.tile_data.offset))
-							.await?,
-						self.metadata.tile_compression,
-						self.metadata.tile_format,
-					)));
-				} else {
-					// Otherwise, fetch the directory bytes for the next level
-					let range = entry.range;
-					let mut cache = self.leaves_cache.lock().await;
-					// Use the cache to avoid redundant decompression and reading
-					entries = cache.get_or_set(&range, || {
-						let mut blob = self.leaves_bytes.read_range(&range)?;
-						// Decompress the directory bytes
-						blob = decompress(blob, self.internal_compression)?;
-						let entries = EntriesV3::from_blob(&blob)?;
-						Ok(Arc::new(entries))
-					})?;
-				}
-			} else {
-				// If the range is invalid, return None
-				return Ok(None);
-			}
-		}
+		let Some(range) = self.get_tile_range(coord).await? else {
+			return Ok(None);
+		};
+		Ok(Some(Tile::from_blob(
+			self.data_reader.read_range(&range).await?,
+			self.metadata.tile_compression,
+			self.metadata.tile_format,
+		)))
+	}
 
-		// If the tile data is not found after traversing all levels, return an error
-		bail!("not found")
+	/// Reads the size from the directories, without reading the tile.
+	#[context("getting the size of tile {:?} in PMTiles", coord)]
+	async fn get_tile_size(&self, coord: &TileCoord) -> Result<Option<u64>> {
+		Ok(self.get_tile_range(coord).await?.map(|range| range.length))
 	}
 
 	async fn get_tile_stream(&self, bbox: TileBBox) -> Result<TileStream<Tile>> {
@@ -383,7 +392,7 @@ mod tests {
 
 		assert_wildcard!(
 			format!("{:?}", reader.metadata()),
-			"TileSourceMetadata { bbox_pyramid: [0: [0,0,0,0] (1x1), 1: [1,0,1,0] (1x1), 2: [2,1,2,1] (1x1), 3: [4,2,4,2] (1x1), 4: [8,5,8,5] (1x1), 5: [17,10,17,10] (1x1), 6: [34,20,34,21] (1x2), 7: [68,41,68,42] (1x2), 8: [137,83,137,84] (1x2), 9: [274,167,275,168] (2x2), 10: [549,335,551,336] (3x2), 11: [1098,670,1102,673] (5x4), 12: [2196,1340,2204,1346] (9x7), 13: [4393,2680,4409,2693] (17x14), 14: [8787,5361,8818,5387] (32x27)], tile_compression: Gzip, tile_format: MVT, traversal: Traversal(PMTiles,full) }"
+			"TileSourceMetadata { bbox_pyramid: [0: [0,0,0,0] (1x1), 1: [1,0,1,0] (1x1), 2: [2,1,2,1] (1x1), 3: [4,2,4,2] (1x1), 4: [8,5,8,5] (1x1), 5: [17,10,17,10] (1x1), 6: [34,20,34,21] (1x2), 7: [68,41,68,42] (1x2), 8: [137,83,137,84] (1x2), 9: [274,167,275,168] (2x2), 10: [549,335,551,336] (3x2), 11: [1098,670,1101,670] (4x1), 11: [1098,671,1102,671] (5x1), 11: [1098,672,1101,673] (4x2), 11: [1102,672,1102,672] (1x1), 12: *, 13: *, 14: [8812,5386,8814,5387] (3x2)], tile_compression: Gzip, tile_format: MVT, traversal: Traversal(PMTiles,full), tile_size: None, tile_grid: None }"
 		);
 
 		assert_eq!(
@@ -395,6 +404,8 @@ mod tests {
 				.len(),
 			20
 		);
+		assert_eq!(reader.get_tile_size(&TileCoord::new(0, 0, 0)?).await?, Some(20));
+		assert_eq!(reader.get_tile_size(&TileCoord::new(15, 0, 0)?).await?, None);
 
 		assert_eq!(
 			reader
@@ -108,7 +108,7 @@ impl TilesWriter for PMTilesWriter {
 					Box::pin(async move {
 						let mut writer = writer_mutex.lock().await;
 						let mut entries = entries_mutex.lock().await;
-						let mut tiles = stream.to_vec().await;
+						let mut tiles = stream.to_vec().await?;
 						tiles.sort_by_key(|(coord, _)| coord.get_hilbert_index().unwrap());
 						for (coord, mut tile) in tiles {
 							let id = coord.get_hilbert_index()?;
@@ -170,6 +170,8 @@ mod tests {
 			tile_compression: TileCompression::Gzip,
 			tile_format: TileFormat::MVT,
 			traversal: Traversal::ANY,
+			tile_size: None,
+			tile_grid: None,
 		})?;
 
 		let runtime = TilesRuntime::default();
@@ -196,6 +198,8 @@ mod tests {
 			tile_compression: TileCompression::Uncompressed,
 			tile_format: TileFormat::MVT,
 			traversal: Traversal::ANY,
+			tile_size: None,
+			tile_grid: None,
 		})?;
 
 		let runtime = TilesRuntime::default();
@@ -31,12 +31,16 @@
 //! Returns errors when the tar cannot be opened or read, when no tiles are found,
 //! or when mixed formats/compressions are detected.
 
-use crate::{SourceType, Tile, TileSource, TileSourceMetadata, Traversal};
+use crate::{SourceType, Tile, TileSource, TileSourceMetadata};
 use anyhow::{Result, anyhow, ensure};
 use async_trait::async_trait;
 use std::{collections::HashMap, fmt::Debug, io::Read, path::Path, sync::Arc};
 use tar::{Archive, EntryType};
-use versatiles_core::{io::*, utils::decompress, *};
+use versatiles_core::{
+	io::*,
+	utils::{decompress, split_tile_filename},
+	*,
+};
 use versatiles_derive::context;
 
 /// Reader for tiles stored inside a tar archive.
@@ -98,16 +102,10 @@ impl TarTilesReader {
 				let level = path_vec[0].parse::<u8>()?;
 				let x = path_vec[1].parse::<u32>()?;
 
-				let mut filename: String = String::from(path_vec[2]);
-				let this_compression = TileCompression::from_filename(&mut filename);
-				let this_format = TileFormat::from_filename(&mut filename);
-
-				if this_format.is_none() {
+				let Some((y, this_format, this_compression)) = split_tile_filename(path_vec[2]) else {
 					continue;
-				}
-				let this_format = this_format.unwrap();
-
-				let y = filename.parse::<u32>()?;
+				};
+				let y = y.parse::<u32>()?;
 
 				if let Some(f) = &tile_format {
 					ensure!(
@@ -143,27 +141,15 @@ impl TarTilesReader {
 			};
 
 			if path_vec.len() == 1 {
-				match path_vec[0] {
-					"meta.json" | "tiles.json" | "metadata.json" => {
-						tilejson.merge(&TileJSON::try_from_blob_or_default(&read_to_end()))?;
-						continue;
-					}
-					"meta.json.gz" | "tiles.json.gz" | "metadata.json.gz" => {
-						tilejson.merge(&TileJSON::try_from_blob_or_default(&decompress(
-							read_to_end(),
-							TileCompression::Gzip,
-						)?))?;
-						continue;
-					}
-					"meta.json.br" | "tiles.json.br" | "metadata.json.br" => {
-						tilejson.merge(&TileJSON::try_from_blob_or_default(&decompress(
-							read_to_end(),
-							TileCompression::Brotli,
-						)?))?;
-						continue;
-					}
-					&_ => {}
-				};
+				let mut name = path_vec[0].to_string();
+				let compression = TileCompression::from_filename(&mut name);
+				if matches!(name.as_str(), "meta.json" | "tiles.json" | "metadata.json") {
+					tilejson.merge(&TileJSON::try_from_blob_or_default(&decompress(
+						read_to_end(),
+						compression,
+					)?))?;
+					continue;
+				}
 			}
 
 			log::warn!("unknown file in tar: {path_tmp_string:?}");
@@ -173,12 +159,12 @@ impl TarTilesReader {
 			return Err(anyhow!("no tiles found in tar"));
 		}
 
-		let metadata = TileSourceMetadata::new(
-			tile_format.ok_or(anyhow!("unknown tile format, can't detect format"))?,
-			tile_compression.ok_or(anyhow!("unknown tile compression, can't detect compression"))?,
-			bbox_pyramid.clone(),
-			Traversal::ANY,
-		);
+		let mut metadata = TileSourceMetadata::new_builder()
+			.with_format(tile_format.ok_or(anyhow!("unknown tile format, can't detect format"))?)
+			.with_compression(tile_compression.ok_or(anyhow!("unknown tile compression, can't detect compression"))?)
+			.with_bbox_pyramid(bbox_pyramid.clone())
+			.build()?;
+		metadata.tile_size = tilejson.tile_size;
 
 		Ok(TarTilesReader {
 			tilejson,
@@ -231,6 +217,14 @@ impl TileSource for TarTilesReader {
 		}
 	}
 
+	async fn has_tile(&self, coord: &TileCoord) -> Result<bool> {
+		Ok(self.tile_map.contains_key(coord))
+	}
+
+	async fn get_tile_size(&self, coord: &TileCoord) -> Result<Option<u64>> {
+		Ok(self.tile_map.get(coord).map(|range| range.length))
+	}
+
 	async fn get_tile_stream(&self, bbox: TileBBox) -> Result<TileStream<Tile>> {
 		self.stream_individual_tiles(bbox).await
 	}
@@ -261,7 +255,7 @@ pub mod tests {
 
 		assert_eq!(
 			format!("{reader:?}"),
-			"TarTilesReader { parameters: TileSourceMetadata { bbox_pyramid: [0: [0,0,0,0] (1x1), 1: [0,0,1,1] (2x2), 2: [0,0,3,3] (4x4), 3: [0,0,7,7] (8x8)], tile_compression: Gzip, tile_format: MVT, traversal: Traversal(AnyOrder,full) } }"
+			"TarTilesReader { parameters: TileSourceMetadata { bbox_pyramid: [0: [0,0,0,0] (1x1), 1: [0,0,1,1] (2x2), 2: [0,0,3,3] (4x4), 3: [0,0,7,7] (8x8)], tile_compression: Gzip, tile_format: MVT, traversal: Traversal(AnyOrder,full), tile_size: None, tile_grid: None } }"
 		);
 		assert_wildcard!(reader.source_type().to_string(), "container 'tar' ('*.tar')");
 		assert_eq!(
@@ -270,7 +264,7 @@ pub mod tests {
 		);
 		assert_eq!(
 			format!("{:?}", reader.metadata()),
-			"TileSourceMetadata { bbox_pyramid: [0: [0,0,0,0] (1x1), 1: [0,0,1,1] (2x2), 2: [0,0,3,3] (4x4), 3: [0,0,7,7] (8x8)], tile_compression: Gzip, tile_format: MVT, traversal: Traversal(AnyOrder,full) }"
+			"TileSourceMetadata { bbox_pyramid: [0: [0,0,0,0] (1x1), 1: [0,0,1,1] (2x2), 2: [0,0,3,3] (4x4), 3: [0,0,7,7] (8x8)], tile_compression: Gzip, tile_format: MVT, traversal: Traversal(AnyOrder,full), tile_size: None, tile_grid: None }"
 		);
 		assert_eq!(reader.metadata().tile_compression, TileCompression::Gzip);
 		assert_eq!(reader.metadata().tile_format, TileFormat::MVT);
@@ -362,6 +356,8 @@ pub mod tests {
 		assert_eq!(reader.metadata().tile_format, TileFormat::BIN);
 		assert_eq!(reader.metadata().tile_compression, TileCompression::Uncompressed);
 		assert_eq!(reader.metadata().bbox_pyramid.count_tiles(), 1);
+		assert!(reader.has_tile(&TileCoord::new(3, 1, 2)?).await?);
+		assert!(!reader.has_tile(&TileCoord::new(3, 2, 1)?).await?);
 		assert_eq!(
 			reader
 				.get_tile(&TileCoord::new(3, 1, 2)?)
@@ -373,4 +369,22 @@ pub mod tests {
 		);
 		Ok(())
 	}
+
+	#[tokio::test]
+	async fn extension_aliases() -> Result<()> {
+		let filename = assert_fs::NamedTempFile::new("extension_aliases.tar")?;
+		let mut a = tar::Builder::new(std::fs::File::create(&filename)?);
+		for path in ["3/1/2.mvt", "3/1/3.pbf", "3/1/4.MVT"] {
+			let mut header = tar::Header::new_gnu();
+			header.set_size(2);
+			header.set_cksum();
+			a.append_data(&mut header, path, [1, 2].as_ref())?;
+		}
+		a.finish()?;
+
+		let reader = TarTilesReader::open_path(&filename)?;
+		assert_eq!(reader.metadata().tile_format, TileFormat::MVT);
+		assert_eq!(reader.metadata().bbox_pyramid.count_tiles(), 3);
+		Ok(())
+	}
 }
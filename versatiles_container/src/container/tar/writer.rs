@@ -6,7 +6,8 @@
 //! [`TileSourceMetadata::tile_compression`].
 //!
 //! ## Behavior
-//! - Creates regular file entries with mode `0644`.
+//! - Creates regular file entries with mode `0644`, without timestamps and ownership, so identical
+//!   input gives an identical archive.
 //! - Uses the **same** tile `format` and `compression` for all files (as reported by the reader).
 //! - Writes TileJSON first, then streams all tiles from the reader (order is not significant).
 //! - The output path can be relative or absolute; parent directories must exist or be creatable.
@@ -15,7 +16,7 @@
 //! Returns errors if the archive file cannot be created, or if encoding/compression of
 //! tiles/TileJSON fails while streaming from the reader.
 
-use crate::{TileSource, TileSourceTraverseExt, TilesRuntime, TilesWriter, Traversal};
+use crate::{TileSource, TileSourceTraverseExt, TilesRuntime, TilesWriter, Traversal, encode_tilejson_file};
 use anyhow::{Result, bail};
 use async_trait::async_trait;
 use futures::lock::Mutex;
@@ -25,7 +26,7 @@ use std::{
 	sync::Arc,
 };
 use tar::{Builder, Header};
-use versatiles_core::{io::DataWriterTrait, utils::compress};
+use versatiles_core::{io::DataWriterTrait, utils::extension_for};
 use versatiles_derive::context;
 
 /// Writer for tiles packaged inside a tar archive.
@@ -41,7 +42,7 @@ pub struct TarTilesWriter {}
 impl TilesWriter for TarTilesWriter {
 	/// Write all tiles and TileJSON from `reader` into a tarball at `path`.
 	///
-	/// * Encodes TileJSON to a blob using `reader.parameters().tile_compression` and writes it as `tiles.json[.<compression>]`.
+	/// * Encodes TileJSON with [`encode_tilejson_file`], using the tile compression, and writes it as `tiles.json[.<compression>]`.
 	/// * Streams all tiles from the reader and writes them to `{z}/{x}/{y}.<format>[.<compression>]`.
 	/// * Creates entries with mode `0644` and writes them as regular files.
 	///
@@ -57,14 +58,10 @@ impl TilesWriter for TarTilesWriter {
 		let tile_format = &parameters.tile_format.clone();
 		let tile_compression = reader.metadata().tile_compression;
 
-		let extension_format = tile_format.as_extension();
-		let extension_compression = tile_compression.as_extension();
+		let extension = extension_for(*tile_format, tile_compression);
 
-		let meta_data = compress(reader.tilejson().into(), tile_compression)?;
-		let filename = format!("tiles.json{extension_compression}");
-		let mut header = Header::new_gnu();
-		header.set_size(meta_data.len() as u64);
-		header.set_mode(0o644);
+		let (filename, meta_data) = encode_tilejson_file(reader)?;
+		let mut header = new_header(meta_data.len());
 		builder.append_data(&mut header, Path::new(&filename), meta_data.as_slice())?;
 
 		let builder_mutex = Arc::new(Mutex::new(builder));
@@ -74,21 +71,17 @@ impl TilesWriter for TarTilesWriter {
 				&Traversal::ANY,
 				|_bbox, mut stream| {
 					let builder_mutex = Arc::clone(&builder_mutex);
+					let extension = extension.clone();
 					Box::pin(async move {
 						let mut builder = builder_mutex.lock().await;
-						while let Some((coord, tile)) = stream.next().await {
-							let filename = format!(
-								"./{}/{}/{}{}{}",
-								coord.level, coord.x, coord.y, extension_format, extension_compression
-							);
+						while let Some((coord, tile)) = stream.next().await? {
+							let filename = format!("./{}/{}/{}{}", coord.level, coord.x, coord.y, extension);
 							let path = PathBuf::from(&filename);
 
 							let blob = tile.into_blob(tile_compression)?;
 
 							// Build header
-							let mut header = Header::new_gnu();
-							header.set_size(blob.len());
-							header.set_mode(0o644);
+							let mut header = new_header(blob.len());
 
 							// Write blob to file
 							builder.append_data(&mut header, path, blob.as_slice())?;
@@ -120,6 +113,17 @@ impl TilesWriter for TarTilesWriter {
 	}
 }
 
+/// Returns the header of a regular file entry with mode `0644` and zeroed mtime, uid and gid.
+fn new_header(size: u64) -> Header {
+	let mut header = Header::new_gnu();
+	header.set_size(size);
+	header.set_mode(0o644);
+	header.set_mtime(0);
+	header.set_uid(0);
+	header.set_gid(0);
+	header
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -134,6 +138,8 @@ mod tests {
 			tile_compression: TileCompression::Gzip,
 			tile_format: TileFormat::MVT,
 			traversal: Traversal::ANY,
+			tile_size: None,
+			tile_grid: None,
 		})?;
 
 		let temp_path = NamedTempFile::new("test_output.tar")?;
@@ -152,6 +158,8 @@ mod tests {
 			tile_compression: TileCompression::Uncompressed,
 			tile_format: TileFormat::JSON,
 			traversal: Traversal::ANY,
+			tile_size: None,
+			tile_grid: None,
 		})?;
 
 		let temp_path = NamedTempFile::new("test_meta_output.tar")?;
@@ -173,6 +181,8 @@ mod tests {
 			tile_compression: TileCompression::Uncompressed,
 			tile_format: TileFormat::JSON,
 			traversal: Traversal::ANY,
+			tile_size: None,
+			tile_grid: None,
 		})?;
 
 		let temp_path = NamedTempFile::new("test_empty_tiles.tar")?;
@@ -198,6 +208,8 @@ mod tests {
 			tile_compression: TileCompression::Gzip,
 			tile_format: TileFormat::MVT,
 			traversal: Traversal::ANY,
+			tile_size: None,
+			tile_grid: None,
 		})?;
 
 		let invalid_path = Path::new("/invalid/path/output.tar");
@@ -214,6 +226,8 @@ mod tests {
 			tile_compression: TileCompression::Uncompressed,
 			tile_format: TileFormat::PNG,
 			traversal: Traversal::ANY,
+			tile_size: None,
+			tile_grid: None,
 		})?;
 
 		let temp_path = NamedTempFile::new("test_large_tiles.tar")?;
@@ -239,6 +253,8 @@ mod tests {
 				tile_compression,
 				tile_format: TileFormat::MVT,
 				traversal: Traversal::ANY,
+				tile_size: None,
+				tile_grid: None,
 			})?;
 
 			let temp_path = NamedTempFile::new(format!("test_compression_{tile_compression:?}.tar"))?;
@@ -260,6 +276,8 @@ mod tests {
 			tile_compression: TileCompression::Uncompressed,
 			tile_format: TileFormat::PNG,
 			traversal: Traversal::ANY,
+			tile_size: None,
+			tile_grid: None,
 		})?;
 
 		let temp_path = NamedTempFile::new("test_zxy_scheme.tar")?;
@@ -3,6 +3,7 @@
 //! see [specification](https://github.com/versatiles-org/versatiles-spec)
 //!
 //! This module provides functionality to read from and write to `*.versatiles` container files.
+//! Besides tiles, a file can carry attachments like style sprites and glyphs, see [`AttachmentWriter`].
 //!
 //! # Usage Example
 //!
@@ -48,7 +49,7 @@
 mod types;
 
 mod reader;
-pub use reader::VersaTilesReader;
+pub use reader::{IndexCacheMode, VersaTilesReader, VersaTilesReaderOptions};
 
 mod writer;
-pub use writer::VersaTilesWriter;
+pub use writer::{AttachmentWriter, VersaTilesWriter};
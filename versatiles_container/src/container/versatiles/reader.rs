@@ -6,20 +6,35 @@
 //! reads embedded TileJSON metadata, and exposes tiles via [`TileSource`]. The
 //! file format organizes data into fixed **256×256 tile blocks**; each block stores
 //! a Brotli-compressed tile index (byte ranges), followed by a contiguous region of
-//! tile blobs. This reader lazily caches decoded tile indices for fast random access.
+//! tile blobs. On open, the reader fetches the tile indices of all blocks (as long as they fit
+//! into `PREFETCH_INDEX_LIMIT`) and keeps them in memory, so single-tile requests only read
+//! the tile bytes. Larger indices are fetched per block on first access, and only the most
+//! recently used ones are kept, see `ON_DEMAND_CACHE_BLOCKS`.
+//!
+//! [`VersaTilesReaderOptions`] override this: `preload_index` always reads all tile indices on
+//! open, `index_cache_blocks` keeps only the most recently used tile indices in memory. The
+//! active [`IndexCacheMode`] is shown in the `Debug` output and when probing the container.
+//!
+//! For large containers on slow storage, `sidecar_index` names a local file with a copy of the
+//! block index and all tile indices (see [`VersaTilesReader::write_sidecar_index`]), so opening
+//! the container doesn't read any index from it. If the sidecar belongs to a different file, the
+//! reader logs a warning and uses the embedded index.
 //!
 //! ## Extracted artifacts
 //! - `tilejson`: parsed TileJSON from the `meta_range` (if present)
 //! - `parameters`: [`TileSourceMetadata`] with `tile_format`, `tile_compression`, and a
 //!   **bbox pyramid** computed from the block index
 //! - `block_index`: lightweight structure describing all block ranges
+//! - `tile_indexes`: decoded tile index of every accessed block, see
+//!   [`VersaTilesReader::index_memory_footprint`]
+//! - `attachments`: directory of the attachments at the end of the file, see
+//!   [`VersaTilesReader::get_attachment`]
 //!
 //! ## Usage
 //! ```rust,no_run
 //! use versatiles_container::*;
 //! use versatiles_core::*;
 //! use anyhow::Result;
-//! use futures::StreamExt;
 //! use std::path::Path;
 //!
 //! #[tokio::main]
@@ -40,9 +55,9 @@
 //!     }
 //!
 //!     // Stream a bbox (coalesces reads per block for fewer I/O calls)
-//!     let bbox = metadata.bbox_pyramid.get_level_bbox(4).clone();
+//!     let bbox = metadata.bbox_pyramid.get_bounding_bbox(4).clone();
 //!     let mut stream = reader.get_tile_stream(bbox).await?;
-//!     while let Some((coord, mut tile)) = stream.next().await {
+//!     while let Some((coord, mut tile)) = stream.next().await? {
 //!         let _size = tile.as_blob(metadata.tile_compression)?.len();
 //!         // use (coord, _size)
 //!     }
@@ -54,28 +69,113 @@
 //! Returns errors when the file cannot be read or decompressed, when metadata/index parsing fails,
 //! or when a requested tile is missing.
 
-use super::types::{BlockDefinition, BlockIndex, FileHeader, TileIndex};
-use crate::{SourceType, Tile, TileSource, TileSourceMetadata, TilesRuntime, Traversal, TraversalOrder, TraversalSize};
-use anyhow::Result;
+use super::types::{AttachmentIndex, BlockDefinition, BlockIndex, FileHeader, SidecarIndex, TileIndex};
+use crate::{
+	ContainerError, SourceType, Tile, TileCoverage, TileSource, TileSourceMetadata, TilesRuntime, Traversal,
+	TraversalOrder, TraversalSize,
+};
+use anyhow::{Result, anyhow, ensure};
 use async_trait::async_trait;
-use futures::{lock::Mutex, stream::StreamExt};
-use std::{fmt::Debug, ops::Shr, path::Path, sync::Arc};
+use futures::{TryStreamExt, lock::Mutex, stream::StreamExt};
+use std::{
+	collections::HashMap,
+	fmt::Debug,
+	mem::size_of,
+	ops::Shr,
+	path::{Path, PathBuf},
+	sync::Arc,
+};
 #[cfg(feature = "cli")]
 use versatiles_core::utils::PrettyPrint;
 use versatiles_core::{io::*, utils::decompress, *};
 use versatiles_derive::context;
 
+/// Tile indices of all blocks are read when opening a container, if their decoded size
+/// (16 bytes per tile) stays below this limit. Otherwise they are read on first access.
+const PREFETCH_INDEX_LIMIT: u64 = 64 * 1024 * 1024;
+
+/// Number of tile indices that are fetched concurrently while opening a container.
+const PREFETCH_CONCURRENCY: usize = 16;
+
+/// If the tile indices are read on demand, at most this many are kept in memory.
+/// A tile index of a full block takes 1 MiB, so the cache stays below 256 MiB.
+const ON_DEMAND_CACHE_BLOCKS: usize = 256;
+
+/// Options for opening a [`VersaTilesReader`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct VersaTilesReaderOptions {
+	/// Read the tile indices of all blocks when opening, even if they exceed
+	/// `PREFETCH_INDEX_LIMIT`. Afterwards, every tile fetch is a single read.
+	pub preload_index: bool,
+	/// Keep at most this many tile indices in memory, evicting the least recently used.
+	/// `0` (default) keeps every index that has been read.
+	pub index_cache_blocks: usize,
+	/// Read the block index and all tile indices from this sidecar file instead of the container,
+	/// see [`VersaTilesReader::write_sidecar_index`].
+	pub sidecar_index: Option<PathBuf>,
+}
+
+/// How a [`VersaTilesReader`] keeps the tile indices of its blocks in memory.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IndexCacheMode {
+	/// All tile indices were read on open.
+	Preloaded,
+	/// Tile indices are read on first access, the most recently used are kept.
+	OnDemand,
+	/// Tile indices are read on access, at most this many are kept.
+	Lru(usize),
+	/// All tile indices were read from a sidecar index file.
+	Sidecar,
+}
+
+/// Tile indices of the blocks that are kept in memory.
+enum TileIndexCache {
+	Unbounded(HashMap<TileCoord, Arc<TileIndex>>),
+	Lru(LimitedCache<TileCoord, Arc<TileIndex>>),
+}
+
+impl TileIndexCache {
+	fn get(&mut self, coord: &TileCoord) -> Option<Arc<TileIndex>> {
+		match self {
+			TileIndexCache::Unbounded(map) => map.get(coord).cloned(),
+			TileIndexCache::Lru(cache) => cache.get(coord),
+		}
+	}
+
+	fn insert(&mut self, coord: TileCoord, tile_index: Arc<TileIndex>) {
+		match self {
+			TileIndexCache::Unbounded(map) => {
+				map.insert(coord, tile_index);
+			}
+			TileIndexCache::Lru(cache) => {
+				cache.add(coord, tile_index);
+			}
+		}
+	}
+
+	/// Memory in bytes used by all cached tile indices.
+	fn memory_footprint(&self) -> usize {
+		let size = |tile_index: &Arc<TileIndex>| tile_index.len() * size_of::<ByteRange>();
+		match self {
+			TileIndexCache::Unbounded(map) => map.values().map(size).sum(),
+			TileIndexCache::Lru(cache) => cache.values().map(size).sum(),
+		}
+	}
+}
+
 /// Reader for `.versatiles` containers.
 ///
 /// Decompresses and parses the block index, merges embedded TileJSON, computes a
-/// per-zoom bounding-box pyramid, and serves tiles via lazy index lookups. Tile
-/// indices are cached (least-recently-used) to accelerate repeated random access.
+/// per-zoom bounding-box pyramid, and serves tiles via index lookups. Preloaded tile indices
+/// are kept for the lifetime of the reader, tile indices read on demand are kept in an LRU cache.
 pub struct VersaTilesReader {
+	attachments: AttachmentIndex,
 	block_index: BlockIndex,
 	header: FileHeader,
+	index_mode: IndexCacheMode,
 	metadata: TileSourceMetadata,
 	reader: DataReader,
-	tile_index_cache: Mutex<LimitedCache<TileCoord, Arc<TileIndex>>>,
+	tile_indexes: Mutex<TileIndexCache>,
 	tilejson: TileJSON,
 	runtime: TilesRuntime,
 }
@@ -93,16 +193,53 @@ impl VersaTilesReader {
 		VersaTilesReader::open_reader(DataReaderFile::open(path)?, runtime).await
 	}
 
+	/// Open a `.versatiles` container from a filesystem path with custom [`VersaTilesReaderOptions`].
+	///
+	/// # Errors
+	/// Returns an error if the file cannot be opened or the options are invalid.
+	#[context("Failed to open versatiles file at '{path:?}'")]
+	pub async fn open_path_with_options(
+		path: &Path,
+		runtime: TilesRuntime,
+		options: VersaTilesReaderOptions,
+	) -> Result<VersaTilesReader> {
+		VersaTilesReader::open_reader_with_options(DataReaderFile::open(path)?, runtime, options).await
+	}
+
 	/// Open a `.versatiles` container from an existing [`DataReader`].
 	///
 	/// Reads the header, loads and (if present) decompresses the TileJSON metadata, then
 	/// reads and decompresses the **block index** (Brotli). Finally, computes the bbox pyramid
-	/// from the block index and initializes the tile-index cache.
+	/// from the block index and prefetches the tile indices of all blocks.
 	///
 	/// # Errors
 	/// Returns an error if header/metadata/index reads or decompressions fail.
 	#[context("Failed to open versatiles reader")]
-	pub async fn open_reader(mut reader: DataReader, runtime: TilesRuntime) -> Result<VersaTilesReader> {
+	pub async fn open_reader(reader: DataReader, runtime: TilesRuntime) -> Result<VersaTilesReader> {
+		VersaTilesReader::open_reader_with_options(reader, runtime, VersaTilesReaderOptions::default()).await
+	}
+
+	/// Open a `.versatiles` container from an existing [`DataReader`] with custom
+	/// [`VersaTilesReaderOptions`].
+	///
+	/// # Errors
+	/// Returns an error if the options are invalid or header/metadata/index reads or
+	/// decompressions fail.
+	#[context("Failed to open versatiles reader")]
+	pub async fn open_reader_with_options(
+		mut reader: DataReader,
+		runtime: TilesRuntime,
+		options: VersaTilesReaderOptions,
+	) -> Result<VersaTilesReader> {
+		ensure!(
+			!(options.preload_index && options.index_cache_blocks > 0),
+			"preload_index and index_cache_blocks can't be combined"
+		);
+		ensure!(
+			!(options.sidecar_index.is_some() && options.index_cache_blocks > 0),
+			"sidecar_index and index_cache_blocks can't be combined"
+		);
+
 		let header = FileHeader::from_reader(&mut reader)
 			.await
 			.context("Failed reading the header")?;
@@ -118,40 +255,149 @@ impl VersaTilesReader {
 			TileJSON::default()
 		};
 
-		let block_index = BlockIndex::from_brotli_blob(
-			reader
-				.read_range(&header.blocks_range)
-				.await
-				.context("Failed reading the block index")?,
-		)
-		.context("Failed decompressing the block index")?;
+		let sidecar = match &options.sidecar_index {
+			Some(path) => {
+				let sidecar = SidecarIndex::from_path(path)?;
+				if sidecar.matches(&header, reader.get_size())? {
+					Some(sidecar)
+				} else {
+					log::warn!(
+						"sidecar index {path:?} doesn't match '{}', using the embedded index instead",
+						reader.get_name()
+					);
+					None
+				}
+			}
+			None => None,
+		};
+
+		let (block_index, sidecar_tile_indexes) = match sidecar {
+			Some(sidecar) => {
+				let (block_index, tile_indexes) = sidecar.into_parts();
+				(block_index, Some(tile_indexes))
+			}
+			None => {
+				let block_index = BlockIndex::from_brotli_blob(
+					reader
+						.read_range(&header.blocks_range)
+						.await
+						.context("Failed reading the block index")?,
+				)
+				.map_err(|err| ContainerError::Corrupt {
+					offset: header.blocks_range.offset,
+					detail: format!("block index can't be decoded: {err:#}"),
+				})?;
+				(block_index, None)
+			}
+		};
+
+		let attachments = AttachmentIndex::from_reader(&reader)
+			.await
+			.context("Failed reading the attachments")?;
 
 		let bbox_pyramid = block_index.get_bbox_pyramid();
-		let metadata = TileSourceMetadata::new(
-			header.tile_format,
-			header.compression,
-			bbox_pyramid,
-			Traversal {
+		let mut metadata = TileSourceMetadata::new_builder()
+			.with_format(header.tile_format)
+			.with_compression(header.compression)
+			.with_bbox_pyramid(bbox_pyramid)
+			.with_traversal(Traversal {
 				order: TraversalOrder::AnyOrder,
 				size: TraversalSize::new_max(256)?,
-			},
-		);
+			})
+			.build()?;
+		metadata.tile_size = tilejson.tile_size;
+
+		let index_size: u64 = block_index
+			.iter()
+			.map(|block| block.count_tiles() * size_of::<ByteRange>() as u64)
+			.sum();
+		let (index_mode, tile_indexes) = if let Some(sidecar_tile_indexes) = sidecar_tile_indexes {
+			(
+				IndexCacheMode::Sidecar,
+				TileIndexCache::Unbounded(sidecar_tile_indexes.into_iter().collect()),
+			)
+		} else if options.index_cache_blocks > 0 {
+			(
+				IndexCacheMode::Lru(options.index_cache_blocks),
+				TileIndexCache::Lru(LimitedCache::with_maximum_length(options.index_cache_blocks)),
+			)
+		} else if options.preload_index || index_size <= PREFETCH_INDEX_LIMIT {
+			(IndexCacheMode::Preloaded, TileIndexCache::Unbounded(HashMap::new()))
+		} else {
+			log::debug!("tile indices need {index_size} bytes, so they are read on demand");
+			(
+				IndexCacheMode::OnDemand,
+				TileIndexCache::Lru(LimitedCache::with_maximum_length(ON_DEMAND_CACHE_BLOCKS)),
+			)
+		};
 
-		Ok(VersaTilesReader {
+		let reader = VersaTilesReader {
+			attachments,
 			block_index,
 			header,
+			index_mode,
 			metadata,
 			reader,
-			tile_index_cache: Mutex::new(LimitedCache::with_maximum_size(100_000_000)),
+			tile_indexes: Mutex::new(tile_indexes),
 			tilejson,
 			runtime,
-		})
+		};
+
+		if index_mode == IndexCacheMode::Preloaded {
+			reader.preload_tile_indexes().await?;
+			log::debug!(
+				"preloaded tile indices of {} blocks, using {} bytes",
+				reader.block_index.len(),
+				reader.index_memory_footprint().await
+			);
+		}
+
+		Ok(reader)
 	}
 
-	/// Load (and cache) the tile index for a block.
-	///
-	/// Reads the block's index blob, decompresses it, adjusts offsets to the tiles segment,
-	/// and inserts the result into an in-memory LRU-like cache. Subsequent calls reuse the cache.
+	/// Reads the tile indices of all blocks.
+	#[context("Failed to preload tile indices")]
+	async fn preload_tile_indexes(&self) -> Result<()> {
+		// Owned blocks keep the stream `Send`.
+		let blocks: Vec<BlockDefinition> = self.block_index.iter().cloned().collect();
+		let tile_indexes: Vec<(TileCoord, TileIndex)> = futures::stream::iter(blocks)
+			.map(|block| async move { anyhow::Ok((*block.get_coord(), self.read_tile_index(&block).await?)) })
+			.buffer_unordered(PREFETCH_CONCURRENCY)
+			.try_collect()
+			.await?;
+
+		let mut cache = self.tile_indexes.lock().await;
+		for (coord, tile_index) in tile_indexes {
+			cache.insert(coord, Arc::new(tile_index));
+		}
+		Ok(())
+	}
+
+	/// Reads the block's index blob, decompresses it and adjusts offsets to the tiles segment.
+	#[context("Failed to read tile index for block {block:?}")]
+	async fn read_tile_index(&self, block: &BlockDefinition) -> Result<TileIndex> {
+		let blob = self.reader.read_range(block.get_index_range()).await?;
+		let mut tile_index = TileIndex::from_brotli_blob(blob)?;
+		tile_index.add_offset(block.get_tiles_range().offset);
+
+		if tile_index.len() != block.count_tiles() as usize {
+			return Err(
+				ContainerError::Corrupt {
+					offset: block.get_index_range().offset,
+					detail: format!(
+						"tile index has {} entries, but the block contains {} tiles",
+						tile_index.len(),
+						block.count_tiles()
+					),
+				}
+				.into(),
+			);
+		}
+
+		Ok(tile_index)
+	}
+
+	/// Returns the tile index for a block, reading it on first access.
 	///
 	/// # Errors
 	/// Returns an error if reading or decompression fails.
@@ -159,19 +405,116 @@ impl VersaTilesReader {
 	async fn get_block_tile_index(&self, block: &BlockDefinition) -> Result<Arc<TileIndex>> {
 		let block_coord = block.get_coord();
 
-		let mut cache = self.tile_index_cache.lock().await;
+		if let Some(value) = self.tile_indexes.lock().await.get(block_coord) {
+			return Ok(value);
+		}
+		// Don't hold the lock while reading, so other blocks can be served meanwhile.
+		// Concurrent requests for the same block may read its index twice.
+		let tile_index = Arc::new(self.read_tile_index(block).await?);
+		self.tile_indexes.lock().await.insert(*block_coord, tile_index.clone());
+		Ok(tile_index)
+	}
 
-		Ok(if let Some(value) = cache.get(block_coord) {
-			value
-		} else {
-			let blob = self.reader.read_range(block.get_index_range()).await?;
-			let mut tile_index = TileIndex::from_brotli_blob(blob)?;
-			tile_index.add_offset(block.get_tiles_range().offset);
+	/// Returns the byte range of a tile, or `None` if the container doesn't contain it.
+	///
+	/// Computes the corresponding **block coordinate** (z, x>>8, y>>8), verifies membership
+	/// within the block's bbox and looks up the tile's byte range from the cached index.
+	async fn get_tile_range(&self, coord: &TileCoord) -> Result<Option<ByteRange>> {
+		ContainerError::ensure_valid_coord(coord)?;
 
-			assert_eq!(tile_index.len(), block.count_tiles() as usize);
+		// Calculate block coordinate
+		let block_coord = TileCoord::new(coord.level, coord.x.shr(8), coord.y.shr(8))?;
+
+		// Get the block using the block coordinate
+		let block = self.block_index.get_block(&block_coord);
+
+		if block.is_none() {
+			return Ok(None);
+		}
+		let block = block.unwrap().clone();
+
+		// Get the block and its bounding box
+		let bbox = block.get_global_bbox();
+
+		// Check if the tile is within the block definition
+		if !bbox.contains(coord) {
+			log::trace!("tile {coord:?} outside block definition");
+			return Ok(None);
+		}
+
+		// Get the tile ID
+		let tile_id = bbox.index_of(coord).unwrap() as usize;
+
+		// Retrieve the tile index from cache or read from the reader
+		let tile_index: Arc<TileIndex> = self.get_block_tile_index(&block).await?;
+		let tile_range: ByteRange = *tile_index.get(tile_id);
+
+		//  None if the tile range has zero length
+		if tile_range.length == 0 {
+			return Ok(None);
+		}
+		Ok(Some(tile_range))
+	}
+
+	/// Memory in bytes used by the block index and all tile indices currently in memory.
+	pub async fn index_memory_footprint(&self) -> u64 {
+		let blocks = self.block_index.len() * size_of::<BlockDefinition>();
+		let tiles = self.tile_indexes.lock().await.memory_footprint();
+		(blocks + tiles) as u64
+	}
 
-			cache.add(*block_coord, Arc::new(tile_index))
-		})
+	/// How the tile indices are kept in memory.
+	#[must_use]
+	pub fn index_cache_mode(&self) -> IndexCacheMode {
+		self.index_mode
+	}
+
+	/// Writes the block index and the tile indices of all blocks to a sidecar index file, which
+	/// can be passed to [`VersaTilesReaderOptions::sidecar_index`] when opening this container again.
+	///
+	/// # Errors
+	/// Returns an error if the size of the container is unknown, or reading the tile indices or
+	/// writing the file fails.
+	#[context("Failed to write sidecar index to '{path:?}'")]
+	pub async fn write_sidecar_index(&self, path: &Path) -> Result<()> {
+		let archive_size = self
+			.reader
+			.get_size()
+			.ok_or(anyhow!("size of '{}' is unknown", self.reader.get_name()))?;
+		let mut blocks = Vec::with_capacity(self.block_index.len());
+		for block in self.block_index.iter() {
+			blocks.push((block.clone(), self.get_block_tile_index(block).await?));
+		}
+		SidecarIndex::new(archive_size, &self.header, blocks)?.write_to_path(path)
+	}
+
+	/// Paths of all attachments in alphabetical order.
+	///
+	/// Attachments are only found if the size of the underlying reader is known, e.g. for local files.
+	pub fn attachment_paths(&self) -> impl Iterator<Item = &str> {
+		self.attachments.paths()
+	}
+
+	/// Reads an attachment, returning the stored blob and its compression.
+	///
+	/// # Errors
+	/// Returns an error if reading fails.
+	#[context("Failed to read attachment '{path}'")]
+	pub async fn get_attachment(&self, path: &str) -> Result<Option<(Blob, TileCompression)>> {
+		let Some((range, compression)) = self.attachments.get(path) else {
+			return Ok(None);
+		};
+		Ok(Some((self.reader.read_range(range).await?, *compression)))
+	}
+
+	/// Block index of the container, used when patching the file.
+	pub(crate) fn block_index(&self) -> &BlockIndex {
+		&self.block_index
+	}
+
+	/// Reads raw bytes of the container, used to copy unchanged blocks when patching the file.
+	pub(crate) async fn read_range(&self, range: &ByteRange) -> Result<Blob> {
+		self.reader.read_range(range).await
 	}
 
 	/// Sum of all block index byte lengths.
@@ -324,42 +667,13 @@ impl TileSource for VersaTilesReader {
 
 	/// Fetch a single tile by XYZ coordinate.
 	///
-	/// Computes the corresponding **block coordinate** (z, x>>8, y>>8), verifies membership
-	/// within the block's bbox, looks up the tile's byte range from the cached index, and reads it.
+	/// Looks up the tile's byte range from the cached index and reads it.
 	/// Returns `Ok(None)` for empty ranges or missing blocks.
 	#[context("fetching tile {:?} from '{}'", coord, self.reader.get_name())]
 	async fn get_tile(&self, coord: &TileCoord) -> Result<Option<Tile>> {
-		// Calculate block coordinate
-		let block_coord = TileCoord::new(coord.level, coord.x.shr(8), coord.y.shr(8))?;
-
-		// Get the block using the block coordinate
-		let block = self.block_index.get_block(&block_coord);
-
-		if block.is_none() {
-			return Ok(None);
-		}
-		let block = block.unwrap().clone();
-
-		// Get the block and its bounding box
-		let bbox = block.get_global_bbox();
-
-		// Check if the tile is within the block definition
-		if !bbox.contains(coord) {
-			log::trace!("tile {coord:?} outside block definition");
+		let Some(tile_range) = self.get_tile_range(coord).await? else {
 			return Ok(None);
-		}
-
-		// Get the tile ID
-		let tile_id = bbox.index_of(coord).unwrap() as usize;
-
-		// Retrieve the tile index from cache or read from the reader
-		let tile_index: Arc<TileIndex> = self.get_block_tile_index(&block).await?;
-		let tile_range: ByteRange = *tile_index.get(tile_id);
-
-		//  None if the tile range has zero length
-		if tile_range.length == 0 {
-			return Ok(None);
-		}
+		};
 
 		// Read the tile data from the reader
 		let blob = self.reader.read_range(&tile_range).await?;
@@ -370,6 +684,34 @@ impl TileSource for VersaTilesReader {
 		)))
 	}
 
+	/// Checks the tile index only, without reading the tile.
+	#[context("checking tile {:?} in '{}'", coord, self.reader.get_name())]
+	async fn has_tile(&self, coord: &TileCoord) -> Result<bool> {
+		Ok(self.get_tile_range(coord).await?.is_some())
+	}
+
+	/// Reads the size from the tile index, without reading the tile.
+	#[context("getting the size of tile {:?} in '{}'", coord, self.reader.get_name())]
+	async fn get_tile_size(&self, coord: &TileCoord) -> Result<Option<u64>> {
+		Ok(self.get_tile_range(coord).await?.map(|range| range.length))
+	}
+
+	/// Reads the coverage from the block and tile indices, without checking every coordinate.
+	#[context("reading the coverage of level {level} in '{}'", self.reader.get_name())]
+	async fn coverage(&self, level: u8) -> Result<TileCoverage> {
+		let mut coverage = TileCoverage::new_empty(self, level)?;
+		for block in self.block_index.iter().filter(|block| block.get_z() == level) {
+			let bbox = block.get_global_bbox();
+			let tile_index = self.get_block_tile_index(block).await?;
+			for (index, range) in tile_index.iter().enumerate() {
+				if range.length > 0 {
+					coverage.set(&bbox.coord_at_index(index as u64)?);
+				}
+			}
+		}
+		Ok(coverage)
+	}
+
 	#[context("streaming tiles for bbox {:?}", bbox)]
 	async fn get_tile_stream(&self, bbox: TileBBox) -> Result<TileStream<Tile>> {
 		log::debug!("get_tile_stream {:?}", bbox);
@@ -409,6 +751,10 @@ impl TileSource for VersaTilesReader {
 	async fn probe_container(&self, print: &PrettyPrint) -> Result<()> {
 		print.add_key_value("meta size", &self.header.meta_range.length).await;
 		print.add_key_value("block count", &self.block_index.len()).await;
+		print.add_key_value("tile index mode", &self.index_mode).await;
+		print
+			.add_key_value("tile index memory", &self.index_memory_footprint().await)
+			.await;
 
 		print
 			.add_key_value("sum of block index sizes", &self.get_index_size())
@@ -464,7 +810,7 @@ impl TileSource for VersaTilesReader {
 					y: coord.y,
 					z: coord.level,
 				});
-				biggest_tiles.sort_by(|a, b| b.size.cmp(&a.size));
+				biggest_tiles.sort_by_key(|tile| std::cmp::Reverse(tile.size));
 				while biggest_tiles.len() > 10 {
 					biggest_tiles.pop();
 				}
@@ -493,6 +839,7 @@ impl Debug for VersaTilesReader {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
 		f.debug_struct("VersaTilesReader")
 			.field("parameters", &self.metadata())
+			.field("index_mode", &self.index_mode)
 			.finish()
 	}
 }
@@ -526,7 +873,7 @@ mod tests {
 
 		assert_eq!(
 			format!("{reader:?}"),
-			"VersaTilesReader { parameters: TileSourceMetadata { bbox_pyramid: [0: [0,0,0,0] (1x1), 1: [0,0,1,1] (2x2), 2: [0,0,3,3] (4x4), 3: [0,0,7,7] (8x8), 4: [0,0,15,15] (16x16)], tile_compression: Gzip, tile_format: MVT, traversal: Traversal(AnyOrder,1..256) } }"
+			"VersaTilesReader { parameters: TileSourceMetadata { bbox_pyramid: [0: [0,0,0,0] (1x1), 1: [0,0,1,1] (2x2), 2: [0,0,3,3] (4x4), 3: [0,0,7,7] (8x8), 4: [0,0,15,15] (16x16)], tile_compression: Gzip, tile_format: MVT, traversal: Traversal(AnyOrder,1..256), tile_size: None, tile_grid: None }, index_mode: Preloaded }"
 		);
 		assert_wildcard!(
 			reader.source_type().to_string(),
@@ -538,7 +885,7 @@ mod tests {
 		);
 		assert_eq!(
 			format!("{:?}", reader.metadata()),
-			"TileSourceMetadata { bbox_pyramid: [0: [0,0,0,0] (1x1), 1: [0,0,1,1] (2x2), 2: [0,0,3,3] (4x4), 3: [0,0,7,7] (8x8), 4: [0,0,15,15] (16x16)], tile_compression: Gzip, tile_format: MVT, traversal: Traversal(AnyOrder,1..256) }"
+			"TileSourceMetadata { bbox_pyramid: [0: [0,0,0,0] (1x1), 1: [0,0,1,1] (2x2), 2: [0,0,3,3] (4x4), 3: [0,0,7,7] (8x8), 4: [0,0,15,15] (16x16)], tile_compression: Gzip, tile_format: MVT, traversal: Traversal(AnyOrder,1..256), tile_size: None, tile_grid: None }"
 		);
 		assert_eq!(reader.metadata().tile_compression, TileCompression::Gzip);
 		assert_eq!(reader.metadata().tile_format, TileFormat::MVT);
@@ -555,7 +902,7 @@ mod tests {
 			.await?
 			.map_item_parallel(|mut tile| Ok(tile.as_blob(TileCompression::Gzip)?.len()))
 			.unwrap_results();
-		let sizes: Vec<(TileCoord, u64)> = sizes.to_vec().await;
+		let sizes: Vec<(TileCoord, u64)> = sizes.to_vec().await.unwrap();
 		assert_eq!(sizes.len(), 256);
 		for (_, size) in sizes {
 			assert_eq!(size, 77);
@@ -573,7 +920,8 @@ mod tests {
 			.map_item_parallel(|tile| tile.into_blob(TileCompression::Uncompressed))
 			.unwrap_results()
 			.to_vec()
-			.await;
+			.await
+			.unwrap();
 		all.sort_by_key(|(c, _)| (c.y, c.x));
 		assert_eq!(all.len(), bbox.count_tiles() as usize);
 
@@ -613,23 +961,62 @@ mod tests {
 		Ok(())
 	}
 
+	#[tokio::test]
+	async fn has_tile_matches_get_tile() -> Result<()> {
+		let (_, reader) = mk_reader().await?;
+		for coord in [
+			TileCoord::new(0, 0, 0)?,
+			TileCoord::new(4, 15, 1)?,
+			TileCoord::new(5, 0, 0)?,
+			TileCoord::new(12, 100, 100)?,
+		] {
+			assert_eq!(
+				reader.has_tile(&coord).await?,
+				reader.get_tile(&coord).await?.is_some(),
+				"{coord:?}"
+			);
+		}
+		assert!(reader.has_tile(&TileCoord::new(4, 15, 1)?).await?);
+		assert!(!reader.has_tile(&TileCoord::new(5, 0, 0)?).await?);
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn get_tile_size_matches_get_tile() -> Result<()> {
+		let (_, reader) = mk_reader().await?;
+		let compression = reader.metadata().tile_compression;
+		for coord in [
+			TileCoord::new(0, 0, 0)?,
+			TileCoord::new(4, 15, 1)?,
+			TileCoord::new(5, 0, 0)?,
+		] {
+			let len = match reader.get_tile(&coord).await? {
+				Some(tile) => Some(tile.into_blob(compression)?.len()),
+				None => None,
+			};
+			assert_eq!(reader.get_tile_size(&coord).await?, len, "{coord:?}");
+		}
+		Ok(())
+	}
+
 	#[tokio::test]
 	async fn single_tile_bbox_streams() -> Result<()> {
 		let (_, reader) = mk_reader().await?;
 		let one = TileBBox::from_min_and_max(4, 15, 1, 15, 1)?;
-		let blobs = reader.get_tile_stream(one).await?.to_vec().await;
+		let blobs = reader.get_tile_stream(one).await?.to_vec().await.unwrap();
 		assert_eq!(blobs.len(), 1);
 		Ok(())
 	}
 
 	#[tokio::test]
 	async fn read_your_own_dog_food() -> Result<()> {
-		let mut reader1 = MockReader::new_mock(TileSourceMetadata::new(
-			TileFormat::JSON,
-			TileCompression::Gzip,
-			TileBBoxPyramid::new_full(4),
-			Traversal::ANY,
-		))?;
+		let mut reader1 = MockReader::new_mock(
+			TileSourceMetadata::new_builder()
+				.with_format(TileFormat::JSON)
+				.with_compression(TileCompression::Gzip)
+				.with_bbox_pyramid(TileBBoxPyramid::new_full(4))
+				.build()?,
+		)?;
 
 		let runtime = TilesRuntime::default();
 
@@ -650,6 +1037,307 @@ mod tests {
 		Ok(())
 	}
 
+	#[tokio::test]
+	async fn coverage_is_read_from_the_index() -> Result<()> {
+		let mut bbox_pyramid = TileBBoxPyramid::new_empty();
+		bbox_pyramid.include_bbox(&TileBBox::from_min_and_max(5, 0, 0, 3, 3)?);
+		bbox_pyramid.include_bbox(&TileBBox::from_min_and_max(5, 10, 10, 12, 12)?);
+		let mut source = MockReader::new_mock(
+			TileSourceMetadata::new_builder()
+				.with_format(TileFormat::PNG)
+				.with_compression(TileCompression::Uncompressed)
+				.with_bbox_pyramid(bbox_pyramid)
+				.build()?,
+		)?;
+
+		let runtime = TilesRuntime::default();
+		let mut data_writer = DataWriterBlob::new()?;
+		VersaTilesWriter::write_to_writer(&mut source, &mut data_writer, runtime.clone()).await?;
+		let reader = VersaTilesReader::open_reader(Box::new(data_writer.to_reader()), runtime).await?;
+
+		let coverage = reader.coverage(5).await?;
+		assert_eq!((coverage.width, coverage.height, coverage.count()), (13, 13, 25));
+		assert!(coverage.contains(&TileCoord::new(5, 11, 11)?));
+		assert!(!coverage.contains(&TileCoord::new(5, 5, 5)?));
+		assert_eq!(coverage, crate::tile_coverage(&reader, 5).await?);
+		assert_eq!(reader.coverage(6).await?.count(), 0);
+		Ok(())
+	}
+
+	/// Wraps a [`DataReaderBlob`] and records every requested range.
+	#[derive(Debug)]
+	struct CountingReader {
+		inner: DataReaderBlob,
+		reads: Arc<std::sync::Mutex<Vec<ByteRange>>>,
+	}
+
+	#[async_trait]
+	impl DataReaderTrait for CountingReader {
+		async fn read_range(&self, range: &ByteRange) -> Result<Blob> {
+			self.reads.lock().unwrap().push(*range);
+			self.inner.read_range(range).await
+		}
+		fn get_name(&self) -> &str {
+			self.inner.get_name()
+		}
+	}
+
+	#[tokio::test]
+	async fn tile_indexes_are_read_once() -> Result<()> {
+		let runtime = TilesRuntime::default();
+		let mut mock = MockReader::new_mock(
+			TileSourceMetadata::new_builder()
+				.with_format(TileFormat::JSON)
+				.with_compression(TileCompression::Uncompressed)
+				.with_bbox_pyramid(TileBBoxPyramid::new_full(4))
+				.build()?,
+		)?;
+		let mut data_writer = DataWriterBlob::new()?;
+		VersaTilesWriter::write_to_writer(&mut mock, &mut data_writer, runtime.clone()).await?;
+
+		let reads = Arc::new(std::sync::Mutex::new(Vec::new()));
+		let data_reader = CountingReader {
+			inner: data_writer.to_reader(),
+			reads: reads.clone(),
+		};
+		let reader = VersaTilesReader::open_reader(Box::new(data_reader), runtime).await?;
+
+		// all tile indexes are fetched on open
+		let index_ranges: Vec<ByteRange> = reader.block_index.iter().map(|b| *b.get_index_range()).collect();
+		assert_eq!(index_ranges.len(), 5);
+		for range in &index_ranges {
+			assert_eq!(reads.lock().unwrap().iter().filter(|r| *r == range).count(), 1);
+		}
+		let reads_on_open = reads.lock().unwrap().len();
+
+		// afterwards only tile bytes are read
+		for _ in 0..2 {
+			for level in 0..=4 {
+				for coord in TileBBox::new_full(level)?.iter_coords() {
+					assert!(reader.get_tile(&coord).await?.is_some());
+				}
+			}
+			reader
+				.get_tile_stream(TileBBox::new_full(4)?)
+				.await?
+				.to_vec()
+				.await
+				.unwrap();
+		}
+		{
+			let reads = reads.lock().unwrap();
+			assert!(reads.len() > reads_on_open + 2 * 341);
+			for range in &index_ranges {
+				assert_eq!(reads.iter().filter(|r| *r == range).count(), 1);
+			}
+		}
+
+		assert_eq!(
+			reader.index_memory_footprint().await,
+			(5 * size_of::<BlockDefinition>() + 341 * size_of::<ByteRange>()) as u64
+		);
+
+		Ok(())
+	}
+
+	/// Writes a container with 5 blocks (levels 0-4) and opens it with a [`CountingReader`].
+	async fn open_counting(
+		options: VersaTilesReaderOptions,
+	) -> Result<(VersaTilesReader, Arc<std::sync::Mutex<Vec<ByteRange>>>)> {
+		let runtime = TilesRuntime::default();
+		let mut mock = MockReader::new_mock(
+			TileSourceMetadata::new_builder()
+				.with_format(TileFormat::JSON)
+				.with_compression(TileCompression::Uncompressed)
+				.with_bbox_pyramid(TileBBoxPyramid::new_full(4))
+				.build()?,
+		)?;
+		let mut data_writer = DataWriterBlob::new()?;
+		VersaTilesWriter::write_to_writer(&mut mock, &mut data_writer, runtime.clone()).await?;
+
+		let reads = Arc::new(std::sync::Mutex::new(Vec::new()));
+		let data_reader = CountingReader {
+			inner: data_writer.to_reader(),
+			reads: reads.clone(),
+		};
+		let reader = VersaTilesReader::open_reader_with_options(Box::new(data_reader), runtime, options).await?;
+		Ok((reader, reads))
+	}
+
+	#[tokio::test]
+	async fn preload_index_reads_once_per_tile() -> Result<()> {
+		let options = VersaTilesReaderOptions {
+			preload_index: true,
+			..Default::default()
+		};
+		let (reader, reads) = open_counting(options).await?;
+		assert_eq!(reader.index_cache_mode(), IndexCacheMode::Preloaded);
+		assert!(format!("{reader:?}").ends_with("index_mode: Preloaded }"));
+
+		for level in 0..=4 {
+			for coord in TileBBox::new_full(level)?.iter_coords() {
+				let before = reads.lock().unwrap().len();
+				assert!(reader.get_tile(&coord).await?.is_some());
+				assert_eq!(reads.lock().unwrap().len(), before + 1, "{coord:?}");
+			}
+		}
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn index_cache_blocks_limits_memory() -> Result<()> {
+		let options = VersaTilesReaderOptions {
+			index_cache_blocks: 2,
+			..Default::default()
+		};
+		let (reader, reads) = open_counting(options).await?;
+		assert_eq!(reader.index_cache_mode(), IndexCacheMode::Lru(2));
+		assert!(format!("{reader:?}").ends_with("index_mode: Lru(2) }"));
+		let index_ranges: Vec<ByteRange> = reader.block_index.iter().map(|b| *b.get_index_range()).collect();
+		let index_reads = |reads: &[ByteRange]| reads.iter().filter(|r| index_ranges.contains(r)).count();
+
+		// nothing is read on open
+		assert_eq!(index_reads(&reads.lock().unwrap()), 0);
+
+		// the first tile of a block reads its index, more tiles of the same block don't
+		for level in 0..=4 {
+			reader.get_tile(&TileCoord::new(level, 0, 0)?).await?;
+			reader.get_tile(&TileCoord::new(level, 0, 0)?).await?;
+		}
+		assert_eq!(index_reads(&reads.lock().unwrap()), 5);
+
+		// level 0 was evicted, level 4 is still cached
+		reader.get_tile(&TileCoord::new(4, 1, 1)?).await?;
+		assert_eq!(index_reads(&reads.lock().unwrap()), 5);
+		reader.get_tile(&TileCoord::new(0, 0, 0)?).await?;
+		assert_eq!(index_reads(&reads.lock().unwrap()), 6);
+
+		// levels 0 and 4 are cached: 1 + 256 tiles
+		assert_eq!(
+			reader.index_memory_footprint().await,
+			(5 * size_of::<BlockDefinition>() + 257 * size_of::<ByteRange>()) as u64
+		);
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn conflicting_index_options() {
+		let options = VersaTilesReaderOptions {
+			preload_index: true,
+			index_cache_blocks: 10,
+			..Default::default()
+		};
+		let err = open_counting(options).await.unwrap_err();
+		assert!(format!("{err:?}").contains("can't be combined"));
+	}
+
+	/// Writes a container with levels 0 to `max_level` and its sidecar index into `dir`.
+	async fn write_with_sidecar(dir: &Path, max_level: u8) -> Result<(PathBuf, PathBuf)> {
+		let mut mock = MockReader::new_mock(
+			TileSourceMetadata::new_builder()
+				.with_format(TileFormat::JSON)
+				.with_compression(TileCompression::Uncompressed)
+				.with_bbox_pyramid(TileBBoxPyramid::new_full(max_level))
+				.build()?,
+		)?;
+		let path = dir.join(format!("{max_level}.versatiles"));
+		let sidecar_path = dir.join(format!("{max_level}.sidecar"));
+		VersaTilesWriter::write_to_path_with_sidecar(&mut mock, &path, &sidecar_path, TilesRuntime::default()).await?;
+		Ok((path, sidecar_path))
+	}
+
+	#[tokio::test]
+	async fn sidecar_index_skips_embedded_index() -> Result<()> {
+		let dir = assert_fs::TempDir::new()?;
+		let (path, sidecar_path) = write_with_sidecar(dir.path(), 4).await?;
+		let embedded = VersaTilesReader::open_path(&path, TilesRuntime::default()).await?;
+
+		let reads = Arc::new(std::sync::Mutex::new(Vec::new()));
+		let data_reader = CountingReader {
+			inner: DataReaderBlob::from(std::fs::read(&path)?),
+			reads: reads.clone(),
+		};
+		let options = VersaTilesReaderOptions {
+			sidecar_index: Some(sidecar_path),
+			..Default::default()
+		};
+		let reader =
+			VersaTilesReader::open_reader_with_options(Box::new(data_reader), TilesRuntime::default(), options).await?;
+		assert_eq!(reader.index_cache_mode(), IndexCacheMode::Sidecar);
+
+		// neither the block index nor any tile index is read from the container
+		let mut index_ranges: Vec<ByteRange> = embedded.block_index.iter().map(|b| *b.get_index_range()).collect();
+		index_ranges.push(embedded.header.blocks_range);
+		assert!(reads.lock().unwrap().iter().all(|r| !index_ranges.contains(r)));
+
+		assert_eq!(reader.block_index, embedded.block_index);
+		assert_eq!(reader.metadata(), embedded.metadata());
+		for coord in TileBBox::new_full(4)?.iter_coords() {
+			assert_eq!(reader.get_tile(&coord).await?, embedded.get_tile(&coord).await?);
+		}
+		assert!(reads.lock().unwrap().iter().all(|r| !index_ranges.contains(r)));
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn sidecar_index_of_another_file_is_ignored() -> Result<()> {
+		let dir = assert_fs::TempDir::new()?;
+		let (path, _) = write_with_sidecar(dir.path(), 4).await?;
+		let (_, other_sidecar_path) = write_with_sidecar(dir.path(), 3).await?;
+
+		let options = VersaTilesReaderOptions {
+			sidecar_index: Some(other_sidecar_path),
+			..Default::default()
+		};
+		let reader = VersaTilesReader::open_path_with_options(&path, TilesRuntime::default(), options).await?;
+		assert_eq!(reader.index_cache_mode(), IndexCacheMode::Preloaded);
+		assert_eq!(reader.metadata().bbox_pyramid, TileBBoxPyramid::new_full(4));
+		assert!(reader.get_tile(&TileCoord::new(4, 15, 15)?).await?.is_some());
+
+		let options = VersaTilesReaderOptions {
+			sidecar_index: Some(dir.path().join("missing.sidecar")),
+			..Default::default()
+		};
+		assert!(
+			VersaTilesReader::open_path_with_options(&path, TilesRuntime::default(), options)
+				.await
+				.is_err()
+		);
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn open_corrupt_header() {
+		let mut blob = vec![0u8; 66];
+		blob[0..14].copy_from_slice(b"versatiles_v01");
+		let data_reader = Box::new(DataReaderBlob::from(blob));
+
+		let err = VersaTilesReader::open_reader(data_reader, TilesRuntime::default())
+			.await
+			.unwrap_err();
+		assert!(matches!(
+			ContainerError::find(&err),
+			Some(ContainerError::Corrupt { offset: 0, .. })
+		));
+	}
+
+	#[tokio::test]
+	async fn get_tile_with_invalid_coord() -> Result<()> {
+		let (_, reader) = mk_reader().await?;
+
+		// valid, but outside of the container
+		assert!(reader.get_tile(&TileCoord::new(12, 0, 0)?).await?.is_none());
+
+		let coord = TileCoord { level: 2, x: 4, y: 0 };
+		let err = reader.get_tile(&coord).await.unwrap_err();
+		assert!(matches!(
+			ContainerError::find(&err),
+			Some(ContainerError::InvalidCoord(c)) if *c == coord
+		));
+
+		Ok(())
+	}
+
 	#[tokio::test]
 	#[cfg(feature = "cli")]
 	async fn probe() -> Result<()> {
@@ -657,9 +1345,9 @@ mod tests {
 
 		let mut printer = PrettyPrint::new();
 		reader.probe_container(&printer.get_category("container").await).await?;
-		assert_eq!(
+		assert_wildcard!(
 			printer.as_string().await,
-			"container:\n  meta size: 58\n  block count: 5\n  sum of block index sizes: 70\n  sum of block tiles sizes: 385\n"
+			"container:\n  meta size: 58\n  block count: 5\n  tile index mode: Preloaded\n  tile index memory: *\n  sum of block index sizes: 70\n  sum of block tiles sizes: 385\n"
 		);
 
 		let mut printer = PrettyPrint::new();
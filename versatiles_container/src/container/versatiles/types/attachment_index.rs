@@ -0,0 +1,193 @@
+//! This module defines the `AttachmentIndex` struct, the directory of named blobs (e.g. sprites and
+//! glyphs) stored at the end of a versatiles file.
+//!
+//! The attachment section is appended after all tile blocks and is not referenced by the header,
+//! so readers that only follow the header ranges ignore it:
+//!
+//! ```notest
+//! [ attachment blobs... | directory | directory range (2 × u64) | "vs_attachments" ]
+//! ```
+//!
+//! Each directory entry consists of the path (varint length + UTF-8 bytes), the compression (u8)
+//! and the byte range of the blob. All integers are big endian.
+
+use crate::ContainerError;
+use anyhow::{Result, bail, ensure};
+use std::collections::BTreeMap;
+use versatiles_core::{io::*, *};
+use versatiles_derive::context;
+
+const MAGIC: &[u8; 14] = b"vs_attachments";
+const TRAILER_LENGTH: u64 = 16 + MAGIC.len() as u64;
+
+/// A struct representing the directory of attachments within a versatiles file.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AttachmentIndex {
+	entries: BTreeMap<String, (ByteRange, TileCompression)>,
+}
+
+impl AttachmentIndex {
+	/// Creates a new empty `AttachmentIndex`.
+	pub fn new_empty() -> Self {
+		Self::default()
+	}
+
+	/// Adds an attachment stored at `range`, replacing an existing one with the same path.
+	pub fn add(&mut self, path: &str, range: ByteRange, compression: TileCompression) {
+		self.entries.insert(path.to_string(), (range, compression));
+	}
+
+	/// Returns the byte range and compression of an attachment.
+	pub fn get(&self, path: &str) -> Option<&(ByteRange, TileCompression)> {
+		self.entries.get(path)
+	}
+
+	/// Returns the paths of all attachments in alphabetical order.
+	pub fn paths(&self) -> impl Iterator<Item = &str> {
+		self.entries.keys().map(String::as_str)
+	}
+
+	/// Converts the directory and the trailer pointing to it into a binary blob.
+	///
+	/// # Arguments
+	/// * `offset` - The position in the file where the blob will be written.
+	#[context("Failed to convert AttachmentIndex to blob")]
+	pub fn to_blob(&self, offset: u64) -> Result<Blob> {
+		let mut writer = ValueWriterBlob::new_be();
+		for (path, (range, compression)) in &self.entries {
+			writer.write_varint(path.len() as u64)?;
+			writer.write_string(path)?;
+			writer.write_u8(u8::from(*compression))?;
+			writer.write_range(range)?;
+		}
+		let directory_range = ByteRange::new(offset, writer.position()?);
+		writer.write_range(&directory_range)?;
+		writer.write_slice(MAGIC)?;
+		Ok(writer.into_blob())
+	}
+
+	/// Creates an `AttachmentIndex` from the binary directory.
+	#[context("Failed to create AttachmentIndex from blob")]
+	fn from_blob(blob: &Blob) -> Result<Self> {
+		let mut reader = ValueReaderSlice::new_be(blob.as_slice());
+		let mut index = Self::new_empty();
+		while reader.has_remaining() {
+			let length = reader.read_varint()?;
+			let path = reader.read_string(length)?;
+			let compression = TileCompression::try_from(reader.read_u8()?)?;
+			let range = reader.read_range()?;
+			index.add(&path, range, compression);
+		}
+		Ok(index)
+	}
+
+	/// Reads the attachment directory from the end of a file.
+	///
+	/// Returns an empty index if the file has no attachment section or its size is unknown.
+	///
+	/// # Errors
+	/// Returns an error if the file ends with an attachment trailer, but the directory is corrupt.
+	#[context("Failed to read AttachmentIndex from reader")]
+	pub async fn from_reader(reader: &DataReader) -> Result<Self> {
+		let Some(size) = reader.get_size() else {
+			return Ok(Self::new_empty());
+		};
+		if size < TRAILER_LENGTH {
+			return Ok(Self::new_empty());
+		}
+
+		let trailer = reader
+			.read_range(&ByteRange::new(size - TRAILER_LENGTH, TRAILER_LENGTH))
+			.await?;
+		if !trailer.as_slice().ends_with(MAGIC) {
+			return Ok(Self::new_empty());
+		}
+
+		let directory_range = ValueReaderSlice::new_be(trailer.as_slice()).read_range()?;
+		let directory_end = directory_range.offset.checked_add(directory_range.length);
+		if directory_end.is_none_or(|end| end > size - TRAILER_LENGTH) {
+			bail!(ContainerError::Corrupt {
+				offset: size - TRAILER_LENGTH,
+				detail: format!("attachment directory {directory_range:?} exceeds the file"),
+			});
+		}
+
+		let index = Self::from_blob(&reader.read_range(&directory_range).await?)?;
+		for (path, (range, _)) in &index.entries {
+			ensure!(
+				range
+					.offset
+					.checked_add(range.length)
+					.is_some_and(|end| end <= directory_range.offset),
+				"attachment '{path}' {range:?} overlaps the attachment directory"
+			);
+		}
+		Ok(index)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[tokio::test]
+	async fn round_trip() -> Result<()> {
+		let mut index = AttachmentIndex::new_empty();
+		index.add("sprites/sprite.json", ByteRange::new(100, 20), TileCompression::Gzip);
+		index.add(
+			"fonts/Noto Sans/0-255.pbf",
+			ByteRange::new(120, 300),
+			TileCompression::Uncompressed,
+		);
+
+		// 420 bytes of "file content" followed by the directory
+		let mut file = vec![0u8; 420];
+		file.extend_from_slice(index.to_blob(420)?.as_slice());
+		let reader: DataReader = Box::new(DataReaderBlob::from(file));
+
+		let read = AttachmentIndex::from_reader(&reader).await?;
+		assert_eq!(read, index);
+		assert_eq!(
+			read.paths().collect::<Vec<_>>(),
+			["fonts/Noto Sans/0-255.pbf", "sprites/sprite.json"]
+		);
+		assert_eq!(
+			read.get("sprites/sprite.json"),
+			Some(&(ByteRange::new(100, 20), TileCompression::Gzip))
+		);
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn overflowing_ranges_are_corrupt() -> Result<()> {
+		// an attachment whose end doesn't fit into a u64
+		let mut index = AttachmentIndex::new_empty();
+		index.add("a", ByteRange::new(u64::MAX, 2), TileCompression::Uncompressed);
+		let mut file = vec![0u8; 10];
+		file.extend_from_slice(index.to_blob(10)?.as_slice());
+		let reader: DataReader = Box::new(DataReaderBlob::from(file));
+		let err = AttachmentIndex::from_reader(&reader).await.unwrap_err();
+		assert!(format!("{err:#}").contains("overlaps the attachment directory"));
+
+		// a directory whose end doesn't fit into a u64
+		let mut writer = ValueWriterBlob::new_be();
+		writer.write_range(&ByteRange::new(u64::MAX, 2))?;
+		writer.write_slice(MAGIC)?;
+		let reader: DataReader = Box::new(DataReaderBlob::from(writer.into_blob().into_vec()));
+		let err = AttachmentIndex::from_reader(&reader).await.unwrap_err();
+		assert!(format!("{err:#}").contains("exceeds the file"));
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn files_without_attachments() -> Result<()> {
+		for file in [vec![], vec![1u8; 10], vec![2u8; 100]] {
+			let reader: DataReader = Box::new(DataReaderBlob::from(file));
+			assert_eq!(
+				AttachmentIndex::from_reader(&reader).await?,
+				AttachmentIndex::new_empty()
+			);
+		}
+		Ok(())
+	}
+}
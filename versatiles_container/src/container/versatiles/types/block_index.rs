@@ -94,8 +94,12 @@ impl BlockIndex {
 	/// Returns an error if the conversion fails.
 	#[context("Failed to create BlockIndex from blob")]
 	pub fn as_blob(&self) -> Result<Blob> {
+		// sorted, so the same blocks always give the same blob
+		let mut blocks = self.lookup.iter().collect::<Vec<_>>();
+		blocks.sort_by_key(|(coord, _)| coord.sort_index());
+
 		let mut writer = ValueWriterBlob::new_be();
-		for (_coord, block) in self.lookup.iter() {
+		for (_coord, block) in blocks {
 			writer.write_blob(&block.as_blob()?)?;
 		}
 
@@ -1,42 +1,43 @@
 use super::{BlockDefinition, TileIndex};
-use anyhow::Result;
+use anyhow::{Result, ensure};
 use std::collections::HashMap;
 use versatiles_core::{Blob, ByteRange, TileBBox, TileCoord, io::DataWriterTrait};
 use versatiles_derive::context;
 
+/// Writes the tiles of one block in the order they arrive.
+///
+/// Only the byte ranges of the tiles are kept, so a block of 65,536 tiles is never held in memory.
+/// The bbox of the block is the bbox of the written tiles and is known after [`Self::finalize`].
 pub struct BlockWriter<'a> {
-	pub bbox: TileBBox,
+	level: u8,
 	writer: &'a mut dyn DataWriterTrait,
 	initial_offset: u64,
-	tile_index: TileIndex,
+	entries: Vec<(TileCoord, ByteRange)>,
 	tile_hash_lookup: HashMap<Vec<u8>, ByteRange>,
 }
 
 impl<'a> BlockWriter<'a> {
-	pub fn new(block_definition: &BlockDefinition, writer: &'a mut dyn DataWriterTrait) -> Self {
-		let bbox = *block_definition.get_global_bbox();
+	pub fn new(level: u8, writer: &'a mut dyn DataWriterTrait) -> Self {
 		let initial_offset = writer.get_position().unwrap();
-		let tile_index = TileIndex::new_empty(bbox.count_tiles() as usize);
-		let tile_hash_lookup: HashMap<Vec<u8>, ByteRange> = HashMap::new();
 
 		Self {
-			bbox,
+			level,
 			writer,
 			initial_offset,
-			tile_index,
-			tile_hash_lookup,
+			entries: Vec::new(),
+			tile_hash_lookup: HashMap::new(),
 		}
 	}
 
 	/// Write a single tile to the writer.
 	#[context("writing tile at {coord:?}")]
 	pub fn write_tile(&mut self, coord: TileCoord, blob: Blob) -> Result<()> {
-		let index = self.bbox.index_of(&coord)? as usize;
+		ensure!(coord.level == self.level, "tile is not on level {}", self.level);
 
 		let mut save_hash = false;
 		if blob.len() < 1000 {
 			if let Some(range) = self.tile_hash_lookup.get(blob.as_slice()) {
-				self.tile_index.set(index, *range);
+				self.entries.push((coord, *range));
 				return Ok(());
 			}
 			save_hash = true;
@@ -45,7 +46,7 @@ impl<'a> BlockWriter<'a> {
 		let mut range = self.writer.append(&blob)?;
 		range.shift_backward(self.initial_offset);
 
-		self.tile_index.set(index, range);
+		self.entries.push((coord, range));
 
 		if save_hash {
 			self.tile_hash_lookup.insert(blob.into_vec(), range);
@@ -54,13 +55,29 @@ impl<'a> BlockWriter<'a> {
 		Ok(())
 	}
 
+	/// Write the tile index and return the block, or `None` if no tile was written.
 	#[context("finalizing block writer")]
-	pub fn finalize(self) -> Result<(ByteRange, ByteRange)> {
+	pub fn finalize(self) -> Result<Option<BlockDefinition>> {
+		if self.entries.is_empty() {
+			return Ok(None);
+		}
+
+		let mut bbox = TileBBox::new_empty(self.level)?;
+		for (coord, _) in &self.entries {
+			bbox.include_coord(coord)?;
+		}
+		let mut block = BlockDefinition::new(&bbox)?;
+
+		let mut tile_index = TileIndex::new_empty(bbox.count_tiles() as usize);
+		for (coord, range) in self.entries {
+			tile_index.set(bbox.index_of(&coord)? as usize, range);
+		}
+
 		// Get the final writer position
 		let offset1 = self.writer.get_position()?;
-		let tile_range = ByteRange::new(self.initial_offset, offset1 - self.initial_offset);
-		let index_range = self.writer.append(&self.tile_index.as_brotli_blob()?)?;
+		block.set_tiles_range(ByteRange::new(self.initial_offset, offset1 - self.initial_offset));
+		block.set_index_range(self.writer.append(&tile_index.as_brotli_blob()?)?);
 
-		Ok((tile_range, index_range))
+		Ok(Some(block))
 	}
 }
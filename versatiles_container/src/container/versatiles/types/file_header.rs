@@ -4,11 +4,12 @@
 //!
 //! The `FileHeader` struct contains metadata about the file, including its tile format, compression, zoom range, bounding box, and byte ranges for metadata and blocks.
 
+use crate::ContainerError;
 use anyhow::{Result, bail, ensure};
 use versatiles_core::{io::*, *};
 use versatiles_derive::context;
 
-const HEADER_LENGTH: u64 = 66;
+pub(super) const HEADER_LENGTH: u64 = 66;
 const BBOX_SCALE: f64 = 10000000.0;
 
 /// A struct representing the header of a versatiles file.
@@ -140,13 +141,21 @@ impl FileHeader {
 		use TileFormat::*;
 
 		if blob.len() != HEADER_LENGTH {
-			bail!("'{blob:?}' is not a valid versatiles header. A header should be {HEADER_LENGTH} bytes long.");
+			bail!(ContainerError::Corrupt {
+				offset: 0,
+				detail: format!(
+					"'{blob:?}' is not a valid versatiles header. A header should be {HEADER_LENGTH} bytes long."
+				)
+			});
 		}
 
 		let mut reader = ValueReaderSlice::new_be(blob.as_slice());
 		let magic_word = reader.read_string(14)?;
 		if &magic_word != "versatiles_v02" {
-			bail!("'{blob:?}' is not a valid versatiles header. A header should start with 'versatiles_v02'");
+			bail!(ContainerError::Corrupt {
+				offset: 0,
+				detail: format!("'{blob:?}' is not a valid versatiles header. A header should start with 'versatiles_v02'")
+			});
 		};
 
 		let tile_format = match reader.read_u8()? {
@@ -162,14 +171,18 @@ impl FileHeader {
 			0x21 => GEOJSON,
 			0x22 => TOPOJSON,
 			0x23 => JSON,
-			value => bail!("unknown tile_type value: {value}"),
+			value => bail!(ContainerError::UnsupportedFormat(format!(
+				"unknown tile_type value: {value}"
+			))),
 		};
 
 		let compression = match reader.read_u8()? {
 			0 => Uncompressed,
 			1 => Gzip,
 			2 => Brotli,
-			value => bail!("unknown compression value: {value}"),
+			value => bail!(ContainerError::UnsupportedFormat(format!(
+				"unknown compression value: {value}"
+			))),
 		};
 
 		let zoom_range: [u8; 2] = [reader.read_u8()?, reader.read_u8()?];
@@ -351,14 +364,22 @@ mod tests {
 	#[test]
 	fn invalid_header_length() {
 		let invalid_blob = Blob::from(vec![0; HEADER_LENGTH as usize - 1]);
-		assert!(FileHeader::from_blob(&invalid_blob).is_err());
+		let err = FileHeader::from_blob(&invalid_blob).unwrap_err();
+		assert!(matches!(
+			ContainerError::find(&err),
+			Some(ContainerError::Corrupt { offset: 0, .. })
+		));
 	}
 
 	#[test]
 	fn invalid_magic_word() {
 		let mut invalid_blob = Blob::from(vec![0; HEADER_LENGTH as usize]);
 		invalid_blob.as_mut_slice()[0..14].copy_from_slice(b"invalid_header");
-		assert!(FileHeader::from_blob(&invalid_blob).is_err());
+		let err = FileHeader::from_blob(&invalid_blob).unwrap_err();
+		assert!(matches!(
+			ContainerError::find(&err),
+			Some(ContainerError::Corrupt { offset: 0, .. })
+		));
 	}
 
 	#[test]
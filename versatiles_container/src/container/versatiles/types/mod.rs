@@ -8,11 +8,16 @@
 //!
 //! # Types
 //!
+//! - `AttachmentIndex`: Directory of the named blobs (e.g. sprites and glyphs) appended after the tile blocks.
 //! - `BlockDefinition`: Defines a block within the tile container, including its offset, coverage, and byte ranges.
 //! - `BlockIndex`: Manages a collection of `BlockDefinition`s, allowing for efficient lookups and conversions.
+//! - `SidecarIndex`: Copy of the block index and all tile indices, stored next to a `versatiles` file.
 //! - `FileHeader`: Represents the header of a `versatiles` file, containing metadata about the tile format, compression, and ranges.
 //! - `TileIndex`: Manages the byte ranges of individual tiles within the container, allowing for efficient access and modifications.
 
+mod attachment_index;
+pub use attachment_index::AttachmentIndex;
+
 mod block_definition;
 pub use block_definition::BlockDefinition;
 
@@ -25,5 +30,8 @@ pub use block_writer::BlockWriter;
 mod file_header;
 pub use file_header::FileHeader;
 
+mod sidecar_index;
+pub use sidecar_index::SidecarIndex;
+
 mod tile_index;
 pub use tile_index::TileIndex;
@@ -0,0 +1,207 @@
+//! This module defines the `SidecarIndex` struct, a copy of the block index and all tile indices of
+//! a versatiles file, stored in a separate file.
+//!
+//! Opening a large container on slow storage needs many reads for the tile indices of all blocks.
+//! With a sidecar index, the reader gets all of them from a single local file. The sidecar
+//! stores a fingerprint of the container (its size and header), so a sidecar of another file or
+//! of an older version of the same file is detected:
+//!
+//! ```notest
+//! [ "vs_sidecar_v1" | archive size (u64) | header (66 bytes) | block count (varint) | blocks... ]
+//! ```
+//!
+//! Every block consists of its block definition (33 bytes) and its Brotli-compressed tile index
+//! (varint length + bytes). The tile ranges are absolute offsets in the container. All integers
+//! are big endian.
+
+use super::{BlockDefinition, BlockIndex, FileHeader, TileIndex, file_header::HEADER_LENGTH};
+use anyhow::{Result, ensure};
+use std::{path::Path, sync::Arc};
+use versatiles_core::{io::*, *};
+use versatiles_derive::context;
+
+const MAGIC: &[u8; 13] = b"vs_sidecar_v1";
+const BLOCK_DEFINITION_LENGTH: u64 = 33;
+
+/// A struct representing the sidecar index of a versatiles file.
+#[derive(Debug, PartialEq)]
+pub struct SidecarIndex {
+	archive_size: u64,
+	header: Blob,
+	blocks: Vec<(BlockDefinition, Arc<TileIndex>)>,
+}
+
+impl SidecarIndex {
+	/// Creates a sidecar index for a container of `archive_size` bytes with the given header.
+	///
+	/// # Arguments
+	/// * `blocks` - All blocks of the container with their tile indices (absolute offsets).
+	#[context("Failed to create SidecarIndex")]
+	pub fn new(archive_size: u64, header: &FileHeader, blocks: Vec<(BlockDefinition, Arc<TileIndex>)>) -> Result<Self> {
+		Ok(Self {
+			archive_size,
+			header: header.to_blob()?,
+			blocks,
+		})
+	}
+
+	/// Returns `true` if the sidecar was created for the container with this header and size.
+	///
+	/// The size is only compared if it is known.
+	#[context("Failed to compare SidecarIndex with the container")]
+	pub fn matches(&self, header: &FileHeader, archive_size: Option<u64>) -> Result<bool> {
+		Ok(archive_size.is_none_or(|size| size == self.archive_size) && self.header == header.to_blob()?)
+	}
+
+	/// Returns the block index and the tile indices of all blocks.
+	pub fn into_parts(self) -> (BlockIndex, Vec<(TileCoord, Arc<TileIndex>)>) {
+		let mut block_index = BlockIndex::new_empty();
+		let mut tile_indexes = Vec::with_capacity(self.blocks.len());
+		for (block, tile_index) in self.blocks {
+			tile_indexes.push((*block.get_coord(), tile_index));
+			block_index.add_block(block);
+		}
+		(block_index, tile_indexes)
+	}
+
+	/// Converts the sidecar index into a binary blob.
+	#[context("Failed to convert SidecarIndex to blob")]
+	pub fn to_blob(&self) -> Result<Blob> {
+		let mut writer = ValueWriterBlob::new_be();
+		writer.write_slice(MAGIC)?;
+		writer.write_u64(self.archive_size)?;
+		writer.write_blob(&self.header)?;
+		writer.write_varint(self.blocks.len() as u64)?;
+		for (block, tile_index) in &self.blocks {
+			writer.write_blob(&block.as_blob()?)?;
+			let tile_index = tile_index.as_brotli_blob()?;
+			writer.write_varint(tile_index.len())?;
+			writer.write_blob(&tile_index)?;
+		}
+		Ok(writer.into_blob())
+	}
+
+	/// Creates a sidecar index from a binary blob.
+	#[context("Failed to create SidecarIndex from blob")]
+	pub fn from_blob(blob: &Blob) -> Result<Self> {
+		let mut reader = ValueReaderSlice::new_be(blob.as_slice());
+		ensure!(
+			blob.as_slice().starts_with(MAGIC),
+			"file is not a versatiles sidecar index"
+		);
+		reader.set_position(MAGIC.len() as u64)?;
+
+		let archive_size = reader.read_u64()?;
+		let header = reader.read_blob(HEADER_LENGTH)?;
+		let count = reader.read_varint()?;
+		let mut blocks = Vec::new();
+		for _ in 0..count {
+			let block = BlockDefinition::from_blob(&reader.read_blob(BLOCK_DEFINITION_LENGTH)?)?;
+			let length = reader.read_varint()?;
+			let tile_index = TileIndex::from_brotli_blob(reader.read_blob(length)?)?;
+			ensure!(
+				tile_index.len() as u64 == block.count_tiles(),
+				"tile index of block {:?} has {} entries, but the block contains {} tiles",
+				block.get_coord(),
+				tile_index.len(),
+				block.count_tiles()
+			);
+			blocks.push((block, Arc::new(tile_index)));
+		}
+		ensure!(!reader.has_remaining(), "sidecar index has trailing bytes");
+
+		Ok(Self {
+			archive_size,
+			header,
+			blocks,
+		})
+	}
+
+	/// Writes the sidecar index to a file.
+	#[context("Failed to write sidecar index to '{path:?}'")]
+	pub fn write_to_path(&self, path: &Path) -> Result<()> {
+		std::fs::write(path, self.to_blob()?.as_slice())?;
+		Ok(())
+	}
+
+	/// Reads a sidecar index from a file.
+	#[context("Failed to read sidecar index from '{path:?}'")]
+	pub fn from_path(path: &Path) -> Result<Self> {
+		Self::from_blob(&Blob::from(std::fs::read(path)?))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn sidecar() -> Result<(FileHeader, SidecarIndex)> {
+		let mut header = FileHeader::new(
+			TileFormat::MVT,
+			TileCompression::Gzip,
+			[0, 1],
+			&GeoBBox::new(-180.0, -85.0, 180.0, 85.0)?,
+		)?;
+		header.blocks_range = ByteRange::new(1000, 50);
+
+		let mut blocks = Vec::new();
+		for level in 0..=1 {
+			let mut block = BlockDefinition::new(&TileBBox::new_full(level)?)?;
+			block.set_tiles_range(ByteRange::new(100 * u64::from(level), 80));
+			block.set_index_range(ByteRange::new(100 * u64::from(level) + 80, 20));
+			let mut tile_index = TileIndex::new_empty(block.count_tiles() as usize);
+			tile_index.set(0, ByteRange::new(100 * u64::from(level), 80));
+			blocks.push((block, Arc::new(tile_index)));
+		}
+		let sidecar = SidecarIndex::new(1050, &header, blocks)?;
+		Ok((header, sidecar))
+	}
+
+	#[test]
+	fn round_trip() -> Result<()> {
+		let (header, sidecar) = sidecar()?;
+		let read = SidecarIndex::from_blob(&sidecar.to_blob()?)?;
+		assert_eq!(read, sidecar);
+
+		let (block_index, tile_indexes) = read.into_parts();
+		assert_eq!(block_index.len(), 2);
+		assert_eq!(tile_indexes.len(), 2);
+		let level1 = tile_indexes.iter().find(|(coord, _)| coord.level == 1).unwrap();
+		assert_eq!(level1.1.len(), 4);
+		assert_eq!(*level1.1.get(0), ByteRange::new(100, 80));
+		assert!(block_index.get_block(&TileCoord::new(1, 0, 0)?).is_some());
+
+		assert!(
+			SidecarIndex::from_blob(&sidecar.to_blob()?)
+				.unwrap()
+				.matches(&header, None)?
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn fingerprint() -> Result<()> {
+		let (mut header, sidecar) = sidecar()?;
+		assert!(sidecar.matches(&header, Some(1050))?);
+		assert!(sidecar.matches(&header, None)?);
+		assert!(!sidecar.matches(&header, Some(1051))?);
+
+		header.blocks_range = ByteRange::new(1000, 51);
+		assert!(!sidecar.matches(&header, Some(1050))?);
+		Ok(())
+	}
+
+	#[test]
+	fn invalid_blobs() -> Result<()> {
+		let (_, sidecar) = sidecar()?;
+		let blob = sidecar.to_blob()?;
+
+		assert!(SidecarIndex::from_blob(&Blob::from("no sidecar index")).is_err());
+		let truncated = Blob::from(&blob.as_slice()[..blob.len() as usize - 1]);
+		assert!(SidecarIndex::from_blob(&truncated).is_err());
+		let mut extended = blob.as_slice().to_vec();
+		extended.push(0);
+		assert!(SidecarIndex::from_blob(&Blob::from(extended)).is_err());
+		Ok(())
+	}
+}
@@ -15,6 +15,8 @@
 //!
 //! ## Behavior
 //! - All tiles are grouped in 256×256 blocks (`Traversal::new_any_size(256, 256)`).
+//! - Within a block, tiles are stored in the order the source delivers them. Only their byte
+//!   ranges are kept until the block's tile index is written, so blocks are never buffered.
 //! - The header is written twice: once before, and once after writing metadata and blocks.
 //! - Metadata (`TileJSON`) and block indices are compressed using Brotli for storage efficiency.
 //! - The writer supports both raster and vector tile formats.
@@ -41,19 +43,44 @@
 //! }
 //! ```
 //!
+//! ## Attachments
+//! [`AttachmentWriter`] appends named blobs like style sprites and font glyphs after the tile
+//! blocks, so one file can contain everything a map needs. The section is not referenced by the
+//! header, so readers without attachment support still read the tiles.
+//!
+//! ## Sidecar index
+//! [`VersaTilesWriter::write_to_path_with_sidecar`] also writes a separate file with the block
+//! index and all tile indices, so readers on slow storage don't have to fetch them from the
+//! container, see [`VersaTilesReaderOptions::sidecar_index`].
+//!
+//! ## Patching
+//! [`VersaTilesWriter::patch_to_path`] writes a copy of an existing container with a set of
+//! changed tiles applied. Blocks without changes are copied byte for byte, only blocks
+//! containing changed tiles are re-encoded.
+//!
 //! ## Errors
 //! Returns errors if writing fails, compression fails, or if metadata or bounding box
 //! information is invalid.
 
-use super::types::{BlockDefinition, BlockIndex, FileHeader};
+use super::types::{AttachmentIndex, BlockIndex, FileHeader};
 use crate::{
-	TileSource, TileSourceTraverseExt, TilesRuntime, TilesWriter, Traversal, container::versatiles::types::BlockWriter,
+	Tile, TileSource, TileSourceTraverseExt, TilesRuntime, TilesWriter, Traversal, VersaTilesReader,
+	VersaTilesReaderOptions, container::versatiles::types::BlockWriter,
 };
-use anyhow::{Result, anyhow};
+use anyhow::{Result, anyhow, ensure};
 use async_trait::async_trait;
 use futures::lock::Mutex;
-use std::sync::Arc;
-use versatiles_core::{io::DataWriterTrait, types::*, utils::compress};
+use std::{
+	fs::OpenOptions,
+	io::{Read, Write},
+	path::Path,
+	sync::Arc,
+};
+use versatiles_core::{
+	io::{DataWriterFile, DataWriterTrait},
+	types::*,
+	utils::compress,
+};
 use versatiles_derive::context;
 
 /// Writer for `.versatiles` containers.
@@ -124,6 +151,135 @@ impl TilesWriter for VersaTilesWriter {
 }
 
 impl VersaTilesWriter {
+	/// Writes `reader` to `path` and a sidecar index of the new file to `sidecar_path`.
+	///
+	/// See [`VersaTilesReader::write_sidecar_index`].
+	#[context("writing VersaTiles with sidecar index to '{path:?}'")]
+	pub async fn write_to_path_with_sidecar(
+		reader: &mut dyn TileSource,
+		path: &Path,
+		sidecar_path: &Path,
+		runtime: TilesRuntime,
+	) -> Result<()> {
+		Self::write_to_path(reader, path, runtime.clone()).await?;
+		let options = VersaTilesReaderOptions {
+			preload_index: true,
+			..Default::default()
+		};
+		VersaTilesReader::open_path_with_options(path, runtime, options)
+			.await?
+			.write_sidecar_index(sidecar_path)
+			.await
+	}
+
+	/// Writes a copy of `original` with the tiles of `changes` applied to `path`.
+	///
+	/// See [`VersaTilesWriter::patch_to_writer`].
+	pub async fn patch_to_path(original: &VersaTilesReader, changes: &dyn TileSource, path: &Path) -> Result<()> {
+		Self::patch_to_writer(original, changes, &mut DataWriterFile::from_path(path)?).await
+	}
+
+	/// Writes a copy of `original` with the tiles of `changes` applied.
+	///
+	/// The bbox pyramid of `changes` marks the changed area. Blocks that don't overlap it are
+	/// copied byte for byte from `original`, blocks that do are re-encoded with the tiles of
+	/// `changes` replacing the original ones. A tile with empty content deletes the tile, in any
+	/// compression of `changes`.
+	///
+	/// The result is a standalone `.versatiles` file; `original` itself is not modified.
+	///
+	/// # Errors
+	/// Returns an error if the tile formats differ, or if reading or writing fails.
+	#[context("patching VersaTiles")]
+	pub async fn patch_to_writer(
+		original: &VersaTilesReader,
+		changes: &dyn TileSource,
+		writer: &mut dyn DataWriterTrait,
+	) -> Result<()> {
+		let metadata = original.metadata();
+		let changed = &changes.metadata().bbox_pyramid;
+		ensure!(
+			changes.metadata().tile_format == metadata.tile_format,
+			"tile format of the changes ({:?}) must match the original ({:?})",
+			changes.metadata().tile_format,
+			metadata.tile_format
+		);
+		let tile_compression = metadata.tile_compression;
+
+		let mut bbox_pyramid = metadata.bbox_pyramid.clone();
+		bbox_pyramid.include_bbox_pyramid(changed);
+
+		let mut header = FileHeader::new(
+			metadata.tile_format,
+			tile_compression,
+			[
+				bbox_pyramid.get_level_min().ok_or(anyhow!("invalid minzoom"))?,
+				bbox_pyramid.get_level_max().ok_or(anyhow!("invalid maxzoom"))?,
+			],
+			&bbox_pyramid.get_geo_bbox().ok_or(anyhow!("invalid geo bounding box"))?,
+		)?;
+		writer.append(&header.to_blob()?)?;
+
+		header.meta_range = Self::write_meta(original, writer, tile_compression).await?;
+
+		let mut block_index = BlockIndex::new_empty();
+		for level_bbox in bbox_pyramid.iter_levels() {
+			for cell in level_bbox.iter_bbox_grid(256) {
+				let block_coord = TileCoord::new(cell.level, cell.x_min()? / 256, cell.y_min()? / 256)?;
+				let original_block = original.block_index().get_block(&block_coord);
+
+				let mut changed_bbox = *changed.get_bounding_bbox(cell.level);
+				changed_bbox.intersect_with(&cell)?;
+
+				if changed_bbox.is_empty() {
+					if let Some(block) = original_block {
+						log::trace!("copy block {block:?}");
+						let mut block = block.clone();
+						let tiles = original.read_range(block.get_tiles_range()).await?;
+						let index = original.read_range(block.get_index_range()).await?;
+						block.set_tiles_range(writer.append(&tiles)?);
+						block.set_index_range(writer.append(&index)?);
+						block_index.add_block(block);
+					}
+					continue;
+				}
+
+				let mut bbox = changed_bbox;
+				if let Some(block) = original_block {
+					bbox.include_bbox(block.get_global_bbox())?;
+				}
+				log::trace!("re-encode block {bbox:?}");
+
+				let mut tiles = original.get_tile_stream(bbox).await?.to_map().await?;
+				for (coord, mut tile) in changes.get_tile_stream(changed_bbox).await?.to_vec().await? {
+					if is_deletion(&mut tile)? {
+						tiles.remove(&coord);
+					} else {
+						tiles.insert(coord, tile);
+					}
+				}
+				if tiles.is_empty() {
+					continue;
+				}
+
+				let mut block_writer = BlockWriter::new(cell.level, writer);
+				for coord in bbox.iter_coords() {
+					if let Some(tile) = tiles.remove(&coord) {
+						block_writer.write_tile(coord, tile.into_blob(tile_compression)?)?;
+					}
+				}
+				if let Some(block) = block_writer.finalize()? {
+					block_index.add_block(block);
+				}
+			}
+		}
+
+		header.blocks_range = writer.append(&block_index.as_brotli_blob()?)?;
+		writer.write_start(&header.to_blob()?)?;
+
+		Ok(())
+	}
+
 	/// Write the TileJSON metadata as a Brotli-compressed blob to the writer.
 	///
 	/// Returns the byte range where the metadata was written.
@@ -164,40 +320,26 @@ impl VersaTilesWriter {
 		reader
 			.traverse_all_tiles(
 				&Traversal::new_any_size(256, 256)?,
-				|bbox, stream| {
+				|bbox, mut stream| {
 					let writer_mutex = Arc::clone(&writer_mutex);
 					let block_index_mutex = Arc::clone(&block_index_mutex);
 
 					Box::pin(async move {
-						// Log the start of the block
-						let mut block = BlockDefinition::new(&bbox).unwrap();
-						log::trace!("start block {block:?}");
-
-						// Create a new BlockWriter for the block
+						// Write the tiles in the order they arrive. The block only covers the written
+						// tiles, e.g. if empty tiles were dropped, so the bbox pyramid of the file only
+						// covers existing tiles.
 						let mut writer = writer_mutex.lock().await;
-						let mut block_writer = BlockWriter::new(&block, &mut **writer);
-						stream
-							.for_each_sync(|(coord, tile)| {
-								block_writer
-									.write_tile(coord, tile.into_blob(tile_compression).unwrap())
-									.unwrap();
-							})
-							.await;
-
-						// Finish the block
-						log::trace!("finish block {block:?}");
-
-						let (tiles_range, index_range) = block_writer.finalize()?;
-
-						if tiles_range.length + index_range.length == 0 {
-							// Block is empty, continue with the next block
-							return Ok(());
+						let mut block_writer = BlockWriter::new(bbox.level, &mut **writer);
+						log::trace!("start block {bbox:?}");
+						while let Some((coord, tile)) = stream.next().await? {
+							block_writer.write_tile(coord, tile.into_blob(tile_compression)?)?;
 						}
 
-						// Update the block with the tile and index range and add it to the block index
-						block.set_tiles_range(tiles_range);
-						block.set_index_range(index_range);
-						block_index_mutex.lock().await.add_block(block);
+						// Finish the block and add it to the block index, unless it is empty
+						if let Some(block) = block_writer.finalize()? {
+							log::trace!("finish block {block:?}");
+							block_index_mutex.lock().await.add_block(block);
+						}
 
 						Ok(())
 					})
@@ -216,3 +358,308 @@ impl VersaTilesWriter {
 		Ok(range)
 	}
 }
+
+/// Collects attachments and appends them to a `.versatiles` file.
+///
+/// ```rust,no_run
+/// use versatiles_container::*;
+/// use versatiles_core::*;
+///
+/// let mut attachments = AttachmentWriter::new();
+/// attachments.add_attachment("sprites/sprite.json", Blob::from("{}"), TileCompression::Gzip)?;
+/// attachments.append_to_path(std::path::Path::new("/data/map.versatiles"))?;
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[derive(Debug, Default)]
+pub struct AttachmentWriter {
+	attachments: Vec<(String, Blob, TileCompression)>,
+}
+
+impl AttachmentWriter {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Adds an uncompressed `blob` that is stored with the given compression under `path`.
+	///
+	/// # Errors
+	/// Returns an error if the path is empty, starts with `/` or was already added.
+	#[context("Failed to add attachment '{path}'")]
+	pub fn add_attachment(&mut self, path: &str, blob: Blob, compression: TileCompression) -> Result<()> {
+		ensure!(
+			!path.is_empty() && !path.starts_with('/'),
+			"attachment path must be relative and not empty"
+		);
+		ensure!(
+			self.attachments.iter().all(|(existing, _, _)| existing != path),
+			"attachment was already added"
+		);
+		self
+			.attachments
+			.push((path.to_string(), compress(blob, compression)?, compression));
+		Ok(())
+	}
+
+	/// Appends the attachments at the current position of `writer`, which must be the end of a
+	/// `.versatiles` file, e.g. directly after [`VersaTilesWriter::write_to_writer`].
+	#[context("Failed to write attachments")]
+	pub fn write_to_writer(&self, writer: &mut dyn DataWriterTrait) -> Result<()> {
+		let offset = writer.get_position()?;
+		writer.append(&self.to_blob(offset)?)?;
+		Ok(())
+	}
+
+	/// Appends the attachments to an existing `.versatiles` file.
+	///
+	/// Attachments that were appended before are replaced by this set.
+	#[context("Failed to append attachments to {path:?}")]
+	pub fn append_to_path(&self, path: &Path) -> Result<()> {
+		let mut file = OpenOptions::new().read(true).append(true).open(path)?;
+		let mut magic = [0u8; 14];
+		file.read_exact(&mut magic)?;
+		ensure!(&magic == b"versatiles_v02", "file is not a versatiles container");
+
+		let offset = file.metadata()?.len();
+		file.write_all(self.to_blob(offset)?.as_slice())?;
+		Ok(())
+	}
+
+	/// Returns the attachment blobs followed by their directory, to be written at `offset`.
+	fn to_blob(&self, offset: u64) -> Result<Blob> {
+		let mut buffer = Vec::new();
+		let mut index = AttachmentIndex::new_empty();
+		for (path, blob, compression) in &self.attachments {
+			let range = ByteRange::new(offset + buffer.len() as u64, blob.len());
+			buffer.extend_from_slice(blob.as_slice());
+			index.add(path, range, *compression);
+		}
+		let directory_offset = offset + buffer.len() as u64;
+		buffer.extend_from_slice(index.to_blob(directory_offset)?.as_slice());
+		Ok(Blob::from(buffer))
+	}
+}
+
+/// Returns `true` if a tile of a patch deletes the original tile, i.e. its uncompressed content
+/// is empty. An empty blob counts as deletion even if it claims to be compressed.
+fn is_deletion(tile: &mut Tile) -> Result<bool> {
+	Ok(tile.blob_len() == Some(0) || tile.as_blob(TileCompression::Uncompressed)?.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{MockReader, TileSourceMetadata};
+	use assert_fs::NamedTempFile;
+	use std::collections::HashMap;
+	use versatiles_core::utils::decompress;
+
+	/// Tile source that only contains the given JSON tiles, compressed with `compression`.
+	fn changes(tiles: &[(TileCoord, &str)], compression: TileCompression) -> Result<MockReader> {
+		let tiles = tiles
+			.iter()
+			.map(|(coord, text)| {
+				let blob = compress(Blob::from(*text), compression)?;
+				Ok((*coord, Tile::from_blob(blob, compression, TileFormat::JSON)))
+			})
+			.collect::<Result<Vec<_>>>()?;
+		MockReader::from_tiles(tiles)
+	}
+
+	/// Writes 1000 JSON tiles at level 9, spread over 4 blocks, and opens the file.
+	async fn original() -> Result<(NamedTempFile, VersaTilesReader)> {
+		let mut bbox_pyramid = TileBBoxPyramid::new_empty();
+		bbox_pyramid.include_bbox(&TileBBox::from_min_and_max(9, 200, 250, 299, 259)?);
+		let mut reader = MockReader::new_mock(
+			TileSourceMetadata::new_builder()
+				.with_format(TileFormat::JSON)
+				.with_compression(TileCompression::Uncompressed)
+				.with_bbox_pyramid(bbox_pyramid)
+				.build()?,
+		)?;
+
+		let file = NamedTempFile::new("original.versatiles")?;
+		VersaTilesWriter::write_to_path(&mut reader, &file, TilesRuntime::default()).await?;
+		let reader = VersaTilesReader::open_path(&file, TilesRuntime::default()).await?;
+		assert_eq!(reader.block_index().len(), 4);
+		Ok((file, reader))
+	}
+
+	async fn patch(original: &VersaTilesReader, changes: &MockReader) -> Result<(NamedTempFile, VersaTilesReader)> {
+		let file = NamedTempFile::new("patched.versatiles")?;
+		VersaTilesWriter::patch_to_path(original, changes, &file).await?;
+		let reader = VersaTilesReader::open_path(&file, TilesRuntime::default()).await?;
+		Ok((file, reader))
+	}
+
+	async fn read_all(reader: &VersaTilesReader) -> Result<HashMap<TileCoord, String>> {
+		let bbox = *reader.metadata().bbox_pyramid.get_bounding_bbox(9);
+		let mut tiles = HashMap::new();
+		for (coord, tile) in reader.get_tile_stream(bbox).await?.to_vec().await.unwrap() {
+			tiles.insert(coord, tile.into_blob(TileCompression::Uncompressed)?.into_string());
+		}
+		Ok(tiles)
+	}
+
+	#[tokio::test]
+	async fn patch_reuses_unchanged_blocks() -> Result<()> {
+		let (_original_file, original) = original().await?;
+		let changed = HashMap::from([
+			(TileCoord::new(9, 256, 256)?, "patched 1"),
+			(TileCoord::new(9, 270, 257)?, "patched 2"),
+			(TileCoord::new(9, 299, 259)?, "patched 3"),
+		]);
+		let changed_tiles = changed.iter().map(|(coord, text)| (*coord, *text)).collect::<Vec<_>>();
+		let (patched_file, patched) = patch(&original, &changes(&changed_tiles, TileCompression::Uncompressed)?).await?;
+
+		let tiles = read_all(&patched).await?;
+		assert_eq!(tiles.len(), 1000);
+		for (coord, text) in &tiles {
+			match changed.get(coord) {
+				Some(expected) => assert_eq!(text, expected),
+				None => assert_eq!(text, &coord.as_json()),
+			}
+		}
+
+		// Only the block containing the changes is re-encoded, the other blocks are copied verbatim.
+		let mut identical = 0;
+		for block in patched.block_index().iter() {
+			let original_block = original.block_index().get_block(block.get_coord()).unwrap();
+			let range = ByteRange::new(
+				block.get_tiles_range().offset,
+				block.get_tiles_range().length + block.get_index_range().length,
+			);
+			let original_range = ByteRange::new(original_block.get_tiles_range().offset, range.length);
+			if patched.read_range(&range).await? == original.read_range(&original_range).await? {
+				identical += range.length;
+			}
+		}
+		let size = std::fs::metadata(&patched_file)?.len();
+		assert!(identical * 2 > size, "only {identical} of {size} bytes are identical");
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn patch_deletes_empty_tiles() -> Result<()> {
+		let (_file, original) = original().await?;
+		let deleted = TileCoord::new(9, 200, 250)?;
+		let (_file, patched) = patch(&original, &changes(&[(deleted, "")], TileCompression::Uncompressed)?).await?;
+
+		let tiles = read_all(&patched).await?;
+		assert_eq!(tiles.len(), 999);
+		assert!(!tiles.contains_key(&deleted));
+		assert!(patched.get_tile(&deleted).await?.is_none());
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn patch_deletes_empty_tiles_of_compressed_changes() -> Result<()> {
+		let (_file, original) = original().await?;
+		let deleted = TileCoord::new(9, 200, 250)?;
+		let changed = TileCoord::new(9, 201, 250)?;
+		for compression in [TileCompression::Gzip, TileCompression::Brotli] {
+			let changes = changes(&[(deleted, ""), (changed, "changed")], compression)?;
+			let (_file, patched) = patch(&original, &changes).await?;
+
+			let tiles = read_all(&patched).await?;
+			assert_eq!(tiles.len(), 999, "{compression:?}");
+			assert!(!tiles.contains_key(&deleted), "{compression:?}");
+			assert_eq!(tiles[&changed], "changed", "{compression:?}");
+		}
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn attachments_round_trip() -> Result<()> {
+		let (file, original) = original().await?;
+		drop(original);
+		let before = std::fs::read(&file)?;
+
+		let sprite = r#"{"icon":{"x":0,"y":0,"width":16,"height":16,"pixelRatio":1}}"#;
+		let glyphs = Blob::from(vec![10, 4, 0x4e, 0x6f, 0x74, 0x6f]);
+		let mut attachments = AttachmentWriter::new();
+		attachments.add_attachment("sprites/sprite.json", Blob::from(sprite), TileCompression::Gzip)?;
+		attachments.add_attachment(
+			"fonts/Noto Sans/0-255.pbf",
+			glyphs.clone(),
+			TileCompression::Uncompressed,
+		)?;
+		attachments.append_to_path(&file)?;
+
+		// the section is strictly appended, so readers without attachment support still work
+		let after = std::fs::read(&file)?;
+		assert!(after.len() > before.len());
+		assert!(after.starts_with(&before));
+
+		let reader = VersaTilesReader::open_path(&file, TilesRuntime::default()).await?;
+		assert_eq!(read_all(&reader).await?.len(), 1000);
+		assert_eq!(
+			reader.attachment_paths().collect::<Vec<_>>(),
+			["fonts/Noto Sans/0-255.pbf", "sprites/sprite.json"]
+		);
+
+		let (blob, compression) = reader.get_attachment("sprites/sprite.json").await?.unwrap();
+		assert_eq!(compression, TileCompression::Gzip);
+		assert_eq!(decompress(blob, compression)?.as_str(), sprite);
+		let (blob, compression) = reader.get_attachment("fonts/Noto Sans/0-255.pbf").await?.unwrap();
+		assert_eq!((blob, compression), (glyphs, TileCompression::Uncompressed));
+		assert!(reader.get_attachment("sprites/sprite.png").await?.is_none());
+		Ok(())
+	}
+
+	#[test]
+	fn attachment_errors() -> Result<()> {
+		let mut attachments = AttachmentWriter::new();
+		attachments.add_attachment("a.json", Blob::from("{}"), TileCompression::Uncompressed)?;
+		let error = |path: &str| {
+			let mut attachments = AttachmentWriter::new();
+			attachments
+				.add_attachment("a.json", Blob::from("{}"), TileCompression::Uncompressed)
+				.unwrap();
+			format!(
+				"{:?}",
+				attachments
+					.add_attachment(path, Blob::new_empty(), TileCompression::Uncompressed)
+					.unwrap_err()
+			)
+		};
+		assert!(error("a.json").contains("attachment was already added"));
+		assert!(error("/b.json").contains("attachment path must be relative and not empty"));
+		assert!(error("").contains("attachment path must be relative and not empty"));
+
+		let file = NamedTempFile::new("not.versatiles")?;
+		std::fs::write(&file, "no versatiles container")?;
+		let err = attachments.append_to_path(&file).unwrap_err();
+		assert_eq!(
+			err.chain().last().unwrap().to_string(),
+			"file is not a versatiles container"
+		);
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn blocks_cover_only_existing_tiles() -> Result<()> {
+		let sparse = changes(
+			&[(TileCoord::new(9, 260, 251)?, "a"), (TileCoord::new(9, 262, 255)?, "b")],
+			TileCompression::Uncompressed,
+		)?;
+		// declare a larger pyramid than the tiles cover, spanning two blocks
+		let mut metadata = sparse.metadata().clone();
+		metadata
+			.bbox_pyramid
+			.include_bbox(&TileBBox::from_min_and_max(9, 250, 250, 269, 269)?);
+		let mut sparse = sparse.with_metadata(metadata);
+
+		let file = NamedTempFile::new("sparse.versatiles")?;
+		VersaTilesWriter::write_to_path(&mut sparse, &file, TilesRuntime::default()).await?;
+		let reader = VersaTilesReader::open_path(&file, TilesRuntime::default()).await?;
+
+		assert_eq!(reader.block_index().len(), 1);
+		assert_eq!(
+			*reader.metadata().bbox_pyramid.get_bounding_bbox(9),
+			TileBBox::from_min_and_max(9, 260, 251, 262, 255)?
+		);
+		assert_eq!(read_all(&reader).await?.len(), 2);
+		Ok(())
+	}
+}
@@ -8,7 +8,10 @@
 
 use crate::ProgressState;
 use arc_swap::ArcSwap;
-use std::sync::Arc;
+use std::sync::{
+	Arc,
+	atomic::{AtomicUsize, Ordering},
+};
 
 /// Event types that can be emitted by the runtime
 #[derive(Debug, Clone)]
@@ -56,7 +59,8 @@ type EventListener = Arc<dyn Fn(&Event) + Send + Sync>;
 /// Uses lock-free arc-swap for optimal performance when emitting frequent events.
 #[derive(Clone)]
 pub struct EventBus {
-	listeners: Arc<ArcSwap<Vec<EventListener>>>,
+	listeners: Arc<ArcSwap<Vec<(ListenerId, EventListener)>>>,
+	next_id: Arc<AtomicUsize>,
 }
 
 impl EventBus {
@@ -64,26 +68,40 @@ impl EventBus {
 	pub fn new() -> Self {
 		Self {
 			listeners: Arc::new(ArcSwap::from_pointee(Vec::new())),
+			next_id: Arc::new(AtomicUsize::new(0)),
 		}
 	}
 
 	/// Register an event listener
 	///
-	/// Returns a listener ID that can be used to unsubscribe (future enhancement).
+	/// Returns a listener ID that can be passed to [`EventBus::unsubscribe`].
 	/// The listener will be called for all events emitted on this bus.
 	/// Uses read-copy-update (RCU) for lock-free hot-reload.
 	pub fn subscribe<F>(&self, listener: F) -> ListenerId
 	where
 		F: Fn(&Event) + Send + Sync + 'static,
 	{
-		let listener = Arc::new(listener);
-		let id = self.listeners.load().len();
+		let listener: EventListener = Arc::new(listener);
+		let id = ListenerId(self.next_id.fetch_add(1, Ordering::Relaxed));
 		self.listeners.rcu(|old| {
 			let mut new = (**old).clone();
-			new.push(listener.clone());
+			new.push((id, listener.clone()));
 			new
 		});
-		ListenerId(id)
+		id
+	}
+
+	/// Remove a listener registered with [`EventBus::subscribe`]
+	///
+	/// Returns `false` if the listener was already removed.
+	pub fn unsubscribe(&self, id: ListenerId) -> bool {
+		let mut removed = false;
+		self.listeners.rcu(|old| {
+			let new: Vec<_> = old.iter().filter(|(other, _)| *other != id).cloned().collect();
+			removed = new.len() < old.len();
+			new
+		});
+		removed
 	}
 
 	/// Emit an event to all listeners
@@ -93,7 +111,7 @@ impl EventBus {
 	/// Lock-free load for optimal performance on frequent emissions.
 	pub fn emit(&self, event: Event) {
 		let listeners = self.listeners.load();
-		for listener in listeners.iter() {
+		for (_, listener) in listeners.iter() {
 			// Catch panics to prevent one bad listener from breaking others
 			let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
 				listener(&event);
@@ -211,6 +229,25 @@ mod tests {
 		assert_eq!(*counter.lock().unwrap(), 1);
 	}
 
+	#[test]
+	fn test_event_bus_unsubscribe() {
+		let bus = EventBus::new();
+		let counter = Arc::new(Mutex::new(0));
+		let counter1 = counter.clone();
+		let counter2 = counter.clone();
+
+		let id1 = bus.subscribe(move |_event| *counter1.lock().unwrap() += 1);
+		let id2 = bus.subscribe(move |_event| *counter2.lock().unwrap() += 10);
+		assert_ne!(id1, id2);
+
+		assert!(bus.unsubscribe(id1));
+		assert!(!bus.unsubscribe(id1));
+		bus.step("Test".to_string());
+
+		assert_eq!(*counter.lock().unwrap(), 10);
+		assert_eq!(bus.listeners.load().len(), 1);
+	}
+
 	#[test]
 	fn test_event_bus_multiple_subscribers() {
 		let bus = EventBus::new();
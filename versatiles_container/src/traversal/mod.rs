@@ -1,15 +1,17 @@
 //! This module provides traversal utilities and logic for handling data structures
 //! in various orders and sizes. It defines the main `Traversal` type and re-exports
-//! `order`, `processing`, and `size` submodules, which collectively provide traversal
-//! control, ordering logic, processing strategies, and size calculations.
+//! `order`, `processing`, `size` and `sort_order` submodules, which collectively provide traversal
+//! control, ordering logic, processing strategies, size calculations and the order of tiles.
 
 mod order;
 mod processing;
 mod size;
+mod sort_order;
 
 pub use order::*;
 pub use processing::*;
 pub use size::*;
+pub use sort_order::*;
 
 use anyhow::Result;
 use versatiles_core::{TileBBox, TileBBoxPyramid};
@@ -121,7 +123,12 @@ impl Traversal {
 	#[context("while traversing pyramid with Traversal {:?}", self.order)]
 	pub fn traverse_pyramid(&self, pyramid: &TileBBoxPyramid) -> Result<Vec<TileBBox>> {
 		let size = self.max_size()?;
-		let mut bboxes: Vec<TileBBox> = pyramid.level_bbox.iter().flat_map(|b| b.iter_bbox_grid(size)).collect();
+		// Grid cells in the gaps between the regions of a level are skipped.
+		let mut bboxes: Vec<TileBBox> = pyramid
+			.iter_levels()
+			.flat_map(|b| b.iter_bbox_grid(size))
+			.filter(|b| pyramid.overlaps_bbox(b))
+			.collect();
 		self.order.sort_bboxes(&mut bboxes, size);
 		Ok(bboxes)
 	}
@@ -155,7 +162,7 @@ impl std::fmt::Debug for Traversal {
 mod tests {
 	use super::*;
 	use enumset::EnumSet;
-	use versatiles_core::GeoBBox;
+	use versatiles_core::{GeoBBox, TileCoord};
 
 	#[test]
 	fn test_traverse_pyramid() {
@@ -343,4 +350,16 @@ mod tests {
 		assert_eq!(got.order(), &TraversalOrder::PMTiles);
 		assert_eq!(got.max_size().unwrap(), 16);
 	}
+
+	#[test]
+	fn test_traverse_pyramid_skips_gaps() -> Result<()> {
+		let mut pyramid = TileBBoxPyramid::new_empty();
+		pyramid.include_coord(&TileCoord::new(10, 10, 20)?);
+		pyramid.include_coord(&TileCoord::new(10, 900, 400)?);
+
+		let bboxes = Traversal::new(TraversalOrder::AnyOrder, 1, 256)?.traverse_pyramid(&pyramid)?;
+		let bboxes = bboxes.iter().map(ToString::to_string).collect::<Vec<_>>();
+		assert_eq!(bboxes, ["10:[10,20,255,255]", "10:[768,256,900,400]"]);
+		Ok(())
+	}
 }
@@ -0,0 +1,137 @@
+//! Orders of the tiles within a single zoom level.
+//!
+//! Unlike [`TraversalOrder`](super::TraversalOrder), which orders blocks of tiles, a
+//! [`SortOrder`] defines the order of the individual tiles, e.g. to maximise sequential I/O
+//! when writing a container.
+
+use anyhow::Result;
+use versatiles_core::{TileBBox, TileCoord, utils::HilbertIndex};
+
+/// Order of the tiles within a zoom level.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SortOrder {
+	/// Row by row from north to south, each row from west to east.
+	RowMajor,
+	/// Along the Hilbert curve, as used by `PMTiles`.
+	#[default]
+	Hilbert,
+	/// Along the Z-order curve, by interleaving the bits of x and y.
+	Morton,
+}
+
+impl SortOrder {
+	/// Returns the position of `coord` in this order. Only positions on the same level are comparable.
+	#[must_use]
+	pub fn key(&self, coord: &TileCoord) -> u64 {
+		match self {
+			SortOrder::RowMajor => (u64::from(coord.y) << 32) | u64::from(coord.x),
+			SortOrder::Hilbert => coord.get_hilbert_index().expect("tile coordinates are valid"),
+			SortOrder::Morton => interleave_bits(coord.x) | (interleave_bits(coord.y) << 1),
+		}
+	}
+
+	/// Sorts tiles of one zoom level in-place.
+	pub fn sort_tiles<T>(&self, tiles: &mut [(TileCoord, T)]) {
+		tiles.sort_by_key(|(coord, _)| self.key(coord));
+	}
+
+	/// Splits `bbox` into blocks of at most `max_tiles` tiles (but at least one tile) and returns
+	/// them in this order.
+	///
+	/// Every block covers a contiguous range of the order, so sorting the tiles of each block
+	/// and concatenating the blocks yields the tiles of `bbox` in order:
+	/// * `RowMajor`: strips of complete rows, or parts of a row if a row is too long.
+	/// * `Hilbert` and `Morton`: squares of 2ⁿ×2ⁿ tiles aligned to the grid of the level.
+	pub fn split_bbox(&self, bbox: TileBBox, max_tiles: usize) -> Result<Vec<TileBBox>> {
+		if bbox.is_empty() {
+			return Ok(Vec::new());
+		}
+		let max_tiles = max_tiles.max(1) as u64;
+
+		let mut blocks = Vec::new();
+		match self {
+			SortOrder::RowMajor => {
+				let width = u64::from(bbox.width());
+				let (x_min, y_min, x_max, y_max) = (bbox.x_min()?, bbox.y_min()?, bbox.x_max()?, bbox.y_max()?);
+				if width <= max_tiles {
+					let rows = (max_tiles / width).min(u64::from(bbox.height())) as u32;
+					for y in (y_min..=y_max).step_by(rows as usize) {
+						let y1 = (y + rows - 1).min(y_max);
+						blocks.push(TileBBox::from_min_and_max(bbox.level, x_min, y, x_max, y1)?);
+					}
+				} else {
+					let columns = max_tiles as u32;
+					for y in y_min..=y_max {
+						for x in (x_min..=x_max).step_by(columns as usize) {
+							let x1 = (x + columns - 1).min(x_max);
+							blocks.push(TileBBox::from_min_and_max(bbox.level, x, y, x1, y)?);
+						}
+					}
+				}
+			}
+			SortOrder::Hilbert | SortOrder::Morton => {
+				// largest power of two whose square fits into max_tiles
+				let side = 1u32 << ((max_tiles.ilog2() / 2).min(31));
+				blocks.extend(bbox.iter_bbox_grid(side));
+				blocks.sort_by_cached_key(|block| self.key(&block.min_corner().unwrap()));
+			}
+		}
+		Ok(blocks)
+	}
+}
+
+/// Spreads the bits of `value` to the even bit positions of a `u64`.
+fn interleave_bits(value: u32) -> u64 {
+	let mut v = u64::from(value);
+	v = (v | (v << 16)) & 0x0000_FFFF_0000_FFFF;
+	v = (v | (v << 8)) & 0x00FF_00FF_00FF_00FF;
+	v = (v | (v << 4)) & 0x0F0F_0F0F_0F0F_0F0F;
+	v = (v | (v << 2)) & 0x3333_3333_3333_3333;
+	v = (v | (v << 1)) & 0x5555_5555_5555_5555;
+	v
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use rstest::rstest;
+
+	/// Returns the coordinates of a 4×4 grid as "xy" strings in the given order.
+	fn sorted_grid(order: SortOrder) -> Vec<String> {
+		let bbox = TileBBox::from_min_and_max(2, 0, 0, 3, 3).unwrap();
+		let mut tiles = bbox.iter_coords().map(|c| (c, ())).collect::<Vec<_>>();
+		order.sort_tiles(&mut tiles);
+		tiles.iter().map(|(c, _)| format!("{}{}", c.x, c.y)).collect()
+	}
+
+	#[rstest]
+	#[case(SortOrder::RowMajor, "00 10 20 30 01 11 21 31 02 12 22 32 03 13 23 33")]
+	#[case(SortOrder::Morton, "00 10 01 11 20 30 21 31 02 12 03 13 22 32 23 33")]
+	#[case(SortOrder::Hilbert, "00 10 11 01 02 03 13 12 22 23 33 32 31 21 20 30")]
+	fn sort_tiles(#[case] order: SortOrder, #[case] expected: &str) {
+		assert_eq!(sorted_grid(order).join(" "), expected);
+	}
+
+	#[rstest]
+	#[case(SortOrder::RowMajor)]
+	#[case(SortOrder::Hilbert)]
+	#[case(SortOrder::Morton)]
+	fn split_bbox_keeps_order(#[case] order: SortOrder) -> Result<()> {
+		let bbox = TileBBox::from_min_and_max(4, 1, 2, 11, 9)?;
+		let mut expected = bbox.iter_coords().collect::<Vec<_>>();
+		expected.sort_by_key(|c| order.key(c));
+
+		for max_tiles in [0, 1, 3, 4, 7, 16, 50, 1000] {
+			let blocks = order.split_bbox(bbox, max_tiles)?;
+			let mut coords = Vec::new();
+			for block in blocks {
+				assert!(block.count_tiles() <= max_tiles.max(1) as u64);
+				let mut tiles = block.iter_coords().collect::<Vec<_>>();
+				tiles.sort_by_key(|c| order.key(c));
+				coords.extend(tiles);
+			}
+			assert_eq!(coords, expected, "max_tiles {max_tiles}");
+		}
+		Ok(())
+	}
+}
@@ -0,0 +1,304 @@
+//! Tile-by-tile comparison of two sources.
+//!
+//! [`compare_tiles`] walks the union of the bbox pyramids of two [`TileSource`]s and reports
+//! tiles that exist in only one of them and tiles whose decompressed bytes differ. Re-encoding
+//! vector tiles often changes the bytes but not the content, so [`CompareMode::Semantic`]
+//! decodes differing vector tiles and compares their layers and features instead.
+//!
+//! # Example
+//! ```rust
+//! use versatiles_container::*;
+//!
+//! #[tokio::main]
+//! async fn main() -> anyhow::Result<()> {
+//!     let a = MockReader::new_mock_profile(MockReaderProfile::Png)?;
+//!     let b = MockReader::new_mock_profile(MockReaderProfile::Png)?;
+//!     let report = compare_tiles(&a, &b, CompareMode::Bytes).await?;
+//!     assert!(report.is_equal());
+//!     Ok(())
+//! }
+//! ```
+
+use crate::{Tile, TileSource};
+use anyhow::Result;
+use std::time::{Duration, Instant};
+use versatiles_core::{
+	TileCompression, TileCoord, TileType,
+	json::{JsonObject, JsonValue},
+};
+use versatiles_geometry::vector_tile::VectorTile;
+
+/// Maximum number of coordinates kept in [`CompareReport::sample`].
+pub const COMPARE_SAMPLE_SIZE: usize = 20;
+
+/// Size of the chunks in which both sources are read.
+const CHUNK_SIZE: u32 = 64;
+
+/// How tiles that exist in both sources are compared.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CompareMode {
+	/// Tiles are equal if their decompressed bytes are equal.
+	Bytes,
+	/// Like `Bytes`, but vector tiles with different bytes are decoded and equal if they contain
+	/// the same layers with the same features, regardless of layer order and key tables.
+	Semantic,
+}
+
+/// Result of [`compare_tiles`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CompareReport {
+	/// Number of tiles that exist in both sources.
+	pub tiles_compared: u64,
+	/// Number of tiles that exist in both sources and are equal.
+	pub tiles_equal: u64,
+	/// Number of tiles that exist only in the first source.
+	pub only_in_first: u64,
+	/// Number of tiles that exist only in the second source.
+	pub only_in_second: u64,
+	/// Number of tiles that exist in both sources, but differ.
+	pub tiles_different: u64,
+	/// The first coordinates of tiles that are missing in one source or differ.
+	pub sample: Vec<TileCoord>,
+	/// Duration of the comparison.
+	pub elapsed: Duration,
+}
+
+impl CompareReport {
+	/// Returns `true` if both sources contain the same tiles.
+	#[must_use]
+	pub fn is_equal(&self) -> bool {
+		self.only_in_first == 0 && self.only_in_second == 0 && self.tiles_different == 0
+	}
+
+	/// Returns the report as JSON, with `elapsed` in milliseconds and each sample as `[z,x,y]`.
+	#[must_use]
+	pub fn as_json_value(&self) -> JsonValue {
+		let sample = self
+			.sample
+			.iter()
+			.map(|coord| JsonValue::from(vec![u64::from(coord.level), u64::from(coord.x), u64::from(coord.y)]))
+			.collect::<Vec<_>>();
+
+		let mut object = JsonObject::new();
+		object.set("tiles_compared", self.tiles_compared);
+		object.set("tiles_equal", self.tiles_equal);
+		object.set("only_in_first", self.only_in_first);
+		object.set("only_in_second", self.only_in_second);
+		object.set("tiles_different", self.tiles_different);
+		object.set("sample", sample);
+		object.set("elapsed_ms", self.elapsed.as_millis() as u64);
+		JsonValue::from(object)
+	}
+
+	fn add_to_sample(&mut self, coords: &mut [TileCoord]) {
+		coords.sort_by_key(|coord| (coord.y, coord.x));
+		let free = COMPARE_SAMPLE_SIZE.saturating_sub(self.sample.len());
+		self.sample.extend(coords.iter().take(free));
+	}
+}
+
+/// Compares all tiles of `first` and `second`.
+///
+/// Read errors of the sources are returned as errors. Tiles that can't be decompressed or
+/// decoded count as different.
+pub async fn compare_tiles<A, B>(first: &A, second: &B, mode: CompareMode) -> Result<CompareReport>
+where
+	A: TileSource + ?Sized,
+	B: TileSource + ?Sized,
+{
+	let start = Instant::now();
+	let mut report = CompareReport::default();
+
+	let mut pyramid = first.metadata().bbox_pyramid.clone();
+	pyramid.include_bbox_pyramid(&second.metadata().bbox_pyramid);
+
+	for level_bbox in pyramid.iter_levels() {
+		for bbox in level_bbox.iter_bbox_grid(CHUNK_SIZE) {
+			if !pyramid.overlaps_bbox(&bbox) {
+				continue;
+			}
+			let mut first_tiles = first.get_tile_stream(bbox).await?.to_map().await?;
+			let mut differences = Vec::new();
+
+			for (coord, mut tile) in second.get_tile_stream(bbox).await?.to_vec().await? {
+				let Some(mut other) = first_tiles.remove(&coord) else {
+					report.only_in_second += 1;
+					differences.push(coord);
+					continue;
+				};
+				report.tiles_compared += 1;
+				if tiles_are_equal(&mut other, &mut tile, mode) {
+					report.tiles_equal += 1;
+				} else {
+					report.tiles_different += 1;
+					differences.push(coord);
+				}
+			}
+
+			report.only_in_first += first_tiles.len() as u64;
+			differences.extend(first_tiles.into_keys());
+			report.add_to_sample(&mut differences);
+		}
+	}
+
+	report.elapsed = start.elapsed();
+	Ok(report)
+}
+
+fn tiles_are_equal(a: &mut Tile, b: &mut Tile, mode: CompareMode) -> bool {
+	let bytes_equal = match (
+		a.as_blob(TileCompression::Uncompressed),
+		b.as_blob(TileCompression::Uncompressed),
+	) {
+		(Ok(a), Ok(b)) => a.as_slice() == b.as_slice(),
+		_ => return false,
+	};
+	if bytes_equal || mode == CompareMode::Bytes {
+		return bytes_equal;
+	}
+	if a.format().to_type() != TileType::Vector || b.format().to_type() != TileType::Vector {
+		return false;
+	}
+	match (
+		a.as_vector().and_then(decode_layers),
+		b.as_vector().and_then(decode_layers),
+	) {
+		(Ok(a), Ok(b)) => a == b,
+		_ => false,
+	}
+}
+
+/// Returns the features of every layer as GeoJSON, sorted by layer name.
+fn decode_layers(tile: &VectorTile) -> Result<Vec<(String, Vec<JsonObject>)>> {
+	let mut layers = tile
+		.layers
+		.iter()
+		.map(|layer| {
+			let features = layer
+				.to_features()?
+				.iter()
+				.map(|feature| feature.to_json(None))
+				.collect();
+			Ok((layer.name.clone(), features))
+		})
+		.collect::<Result<Vec<_>>>()?;
+	layers.sort_by(|a, b| a.0.cmp(&b.0));
+	Ok(layers)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{MockReader, MockReaderProfile};
+	use versatiles_core::{Blob, TileFormat};
+	use versatiles_geometry::{
+		geo::{GeoFeature, GeoValue, Geometry},
+		vector_tile::VectorTileLayer,
+	};
+
+	fn coord(level: u8, x: u32, y: u32) -> TileCoord {
+		TileCoord::new(level, x, y).unwrap()
+	}
+
+	fn raw_tile(content: &[u8]) -> Tile {
+		Tile::from_blob(Blob::from(content), TileCompression::Uncompressed, TileFormat::PNG)
+	}
+
+	/// Creates a vector tile with one feature in each of the named layers.
+	fn vector_tile(layer_names: &[&str]) -> Tile {
+		let layers = layer_names
+			.iter()
+			.map(|name| {
+				let mut feature = GeoFeature::new(Geometry::new_point([1.0, 2.0]));
+				feature.properties.insert("name".to_string(), GeoValue::from(*name));
+				VectorTileLayer::from_features(name.to_string(), vec![feature], 4096, 1).unwrap()
+			})
+			.collect();
+		Tile::from_vector(VectorTile::new(layers), TileFormat::MVT).unwrap()
+	}
+
+	#[tokio::test]
+	async fn equal_sources() -> Result<()> {
+		let a = MockReader::new_mock_profile(MockReaderProfile::Pbf)?;
+		let b = MockReader::new_mock_profile(MockReaderProfile::Pbf)?;
+		let report = compare_tiles(&a, &b, CompareMode::Bytes).await?;
+		assert!(report.is_equal());
+		assert_eq!(report.tiles_compared, a.metadata().bbox_pyramid.count_tiles());
+		assert_eq!(report.tiles_equal, report.tiles_compared);
+		assert!(report.sample.is_empty());
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn missing_and_different_tiles() -> Result<()> {
+		let a = MockReader::from_tiles(vec![
+			(coord(1, 0, 0), raw_tile(b"same")),
+			(coord(1, 1, 0), raw_tile(b"first")),
+			(coord(2, 3, 3), raw_tile(b"only first")),
+		])?;
+		let b = MockReader::from_tiles(vec![
+			(coord(1, 0, 0), raw_tile(b"same")),
+			(coord(1, 1, 0), raw_tile(b"second")),
+			(coord(1, 0, 1), raw_tile(b"only second")),
+		])?;
+		let report = compare_tiles(&a, &b, CompareMode::Bytes).await?;
+		assert_eq!(
+			report.as_json_value().as_object()?.get("sample").unwrap().stringify(),
+			"[[1,1,0],[1,0,1],[2,3,3]]"
+		);
+		assert_eq!(
+			(
+				report.tiles_compared,
+				report.tiles_equal,
+				report.tiles_different,
+				report.only_in_first,
+				report.only_in_second
+			),
+			(2, 1, 1, 1, 1)
+		);
+		assert!(!report.is_equal());
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn semantic_vector_compare() -> Result<()> {
+		let compare = async |a: Tile, b: Tile, mode: CompareMode| {
+			let a = MockReader::from_tiles(vec![(coord(0, 0, 0), a)]).unwrap();
+			let b = MockReader::from_tiles(vec![(coord(0, 0, 0), b)]).unwrap();
+			compare_tiles(&a, &b, mode).await.unwrap().is_equal()
+		};
+
+		// compression doesn't matter
+		let mut compressed = vector_tile(&["place", "road"]);
+		compressed.change_compression(TileCompression::Gzip)?;
+		assert!(compare(vector_tile(&["place", "road"]), compressed, CompareMode::Bytes).await);
+
+		// the order of the layers only matters for bytes
+		let (a, b) = (vector_tile(&["place", "road"]), vector_tile(&["road", "place"]));
+		assert!(!compare(a.clone(), b.clone(), CompareMode::Bytes).await);
+		assert!(compare(a, b, CompareMode::Semantic).await);
+
+		// different content
+		let (a, b) = (vector_tile(&["place", "road"]), vector_tile(&["place", "water"]));
+		assert!(!compare(a, b, CompareMode::Semantic).await);
+		Ok(())
+	}
+
+	#[test]
+	fn json() -> Result<()> {
+		let report = CompareReport {
+			tiles_compared: 3,
+			tiles_equal: 2,
+			tiles_different: 1,
+			only_in_first: 0,
+			only_in_second: 0,
+			sample: vec![coord(1, 0, 1)],
+			elapsed: Duration::from_millis(12),
+		};
+		assert_eq!(
+			report.as_json_value().stringify(),
+			r#"{"elapsed_ms":12,"only_in_first":0,"only_in_second":0,"sample":[[1,0,1]],"tiles_compared":3,"tiles_different":1,"tiles_equal":2}"#
+		);
+		Ok(())
+	}
+}
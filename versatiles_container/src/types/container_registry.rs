@@ -304,12 +304,13 @@ pub async fn make_test_file(
 	// get dummy reader
 
 	use versatiles_core::TileBBoxPyramid;
-	let reader = MockReader::new_mock(TileSourceMetadata::new(
-		tile_format,
-		compression,
-		TileBBoxPyramid::new_full(max_zoom_level),
-		Traversal::ANY,
-	))?;
+	let reader = MockReader::new_mock(
+		TileSourceMetadata::new_builder()
+			.with_format(tile_format)
+			.with_compression(compression)
+			.with_bbox_pyramid(TileBBoxPyramid::new_full(max_zoom_level))
+			.build()?,
+	)?;
 
 	// get to test container converter
 	let container_file = match extension {
@@ -358,12 +359,13 @@ pub mod tests {
 			let _start = Instant::now();
 
 			// get dummy reader
-			let reader1 = MockReader::new_mock(TileSourceMetadata::new(
-				tile_format,
-				compression,
-				TileBBoxPyramid::new_full(2),
-				Traversal::ANY,
-			))?;
+			let reader1 = MockReader::new_mock(
+				TileSourceMetadata::new_builder()
+					.with_format(tile_format)
+					.with_compression(compression)
+					.with_bbox_pyramid(TileBBoxPyramid::new_full(2))
+					.build()?,
+			)?;
 
 			let path = TempDir::new()?.to_path_buf();
 			if !path.exists() {
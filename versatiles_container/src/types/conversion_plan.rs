@@ -0,0 +1,251 @@
+//! Work plan of a conversion, used for dry runs.
+//!
+//! [`ConversionPlan::new`] inspects a [`TilesConvertReader`] without writing anything: it lists the
+//! tiles per zoom level of the finalized pyramid, the source and target format/compression, and
+//! estimates the output size by converting a pseudo-random sample of tiles with the real
+//! recompression settings.
+
+use crate::{TileSource, TilesConvertReader};
+use anyhow::{Result, bail};
+use std::{
+	hash::{DefaultHasher, Hash, Hasher},
+	path::{Path, PathBuf},
+};
+#[cfg(feature = "cli")]
+use versatiles_core::utils::PrettyPrint;
+use versatiles_core::{TileBBoxPyramid, TileCompression, TileCoord, TileFormat};
+
+/// Number of tiles that are converted to estimate the output size.
+pub const CONVERSION_SAMPLE_SIZE: u64 = 100;
+
+/// What a conversion would do, see the module documentation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConversionPlan {
+	/// Output path.
+	pub path: PathBuf,
+	/// Number of tiles per zoom level of the finalized pyramid.
+	pub tiles_per_level: Vec<(u8, u64)>,
+	/// Tile format and compression of the source.
+	pub source: (TileFormat, TileCompression),
+	/// Tile format and compression of the output.
+	pub target: (TileFormat, TileCompression),
+	/// `true` if tiles are decompressed and compressed again, `false` if they are copied byte for byte.
+	pub recompress: bool,
+	/// Number of coordinates in the sample.
+	pub sample_coords: u64,
+	/// Number of sampled coordinates that contain a tile.
+	pub sample_tiles: u64,
+	/// Converted size of all sampled tiles in bytes.
+	pub sample_bytes: u64,
+}
+
+impl ConversionPlan {
+	/// Creates the plan for writing `converter` to `path`.
+	///
+	/// # Errors
+	/// Returns an error if a sample tile can't be read or converted.
+	pub async fn new(converter: &TilesConvertReader, path: &Path) -> Result<Self> {
+		let source_metadata = converter.source_metadata();
+		let metadata = converter.metadata();
+		let pyramid = &metadata.bbox_pyramid;
+
+		let mut plan = ConversionPlan {
+			path: path.to_path_buf(),
+			tiles_per_level: pyramid
+				.iter_levels_with_zoom()
+				.map(|(level, _)| (level, pyramid.count_level_tiles(level)))
+				.collect(),
+			source: (source_metadata.tile_format, source_metadata.tile_compression),
+			target: (metadata.tile_format, metadata.tile_compression),
+			recompress: converter.recompresses(),
+			sample_coords: 0,
+			sample_tiles: 0,
+			sample_bytes: 0,
+		};
+
+		for index in sample_indices(pyramid.count_tiles()) {
+			let coord = coord_at(pyramid, index)?;
+			plan.sample_coords += 1;
+			if let Some(tile) = converter.get_tile(&coord).await? {
+				plan.sample_tiles += 1;
+				plan.sample_bytes += tile.into_blob(metadata.tile_compression)?.len();
+			}
+		}
+
+		Ok(plan)
+	}
+
+	/// Total number of tile coordinates that will be processed.
+	#[must_use]
+	pub fn tile_count(&self) -> u64 {
+		self.tiles_per_level.iter().map(|(_, count)| count).sum()
+	}
+
+	/// Estimated size of all converted tiles in bytes, extrapolated from the sample.
+	#[must_use]
+	pub fn estimated_size(&self) -> u64 {
+		if self.sample_coords == 0 {
+			return 0;
+		}
+		(u128::from(self.sample_bytes) * u128::from(self.tile_count()) / u128::from(self.sample_coords)) as u64
+	}
+
+	/// Writes the plan to the CLI reporter.
+	#[cfg(feature = "cli")]
+	pub async fn pretty_print(&self, print: &mut PrettyPrint) {
+		print.add_key_value("output", &self.path).await;
+		let list = print.get_list("tile count per level").await;
+		for (level, count) in &self.tiles_per_level {
+			list.add_key_value(level, count).await;
+		}
+		print.add_key_value("tile count", &self.tile_count()).await;
+		print
+			.add_key_value("tile format", &format!("{} → {}", self.source.0, self.target.0))
+			.await;
+		print
+			.add_key_value("tile compression", &format!("{} → {}", self.source.1, self.target.1))
+			.await;
+		print
+			.add_key_value("tiles", if self.recompress { "recompressed" } else { "copied" })
+			.await;
+		print
+			.add_key_value(
+				"sample",
+				&format!(
+					"{} of {} tiles, {} bytes",
+					self.sample_tiles, self.sample_coords, self.sample_bytes
+				),
+			)
+			.await;
+		print.add_key_value("estimated size", &self.estimated_size()).await;
+	}
+}
+
+/// Returns up to [`CONVERSION_SAMPLE_SIZE`] pseudo-random tile indices, or all indices of small pyramids.
+///
+/// The indices are derived from hashes, so the sample is the same for every run.
+fn sample_indices(total: u64) -> Vec<u64> {
+	if total <= CONVERSION_SAMPLE_SIZE {
+		return (0..total).collect();
+	}
+	(0..CONVERSION_SAMPLE_SIZE)
+		.map(|i| {
+			let mut hasher = DefaultHasher::new();
+			i.hash(&mut hasher);
+			hasher.finish() % total
+		})
+		.collect()
+}
+
+/// Returns the coordinate of the tile with the given index, counting row by row through all boxes of all levels.
+fn coord_at(pyramid: &TileBBoxPyramid, mut index: u64) -> Result<TileCoord> {
+	for bbox in pyramid.iter_bboxes() {
+		let count = bbox.count_tiles();
+		if index < count {
+			let width = u64::from(bbox.width());
+			return TileCoord::new(
+				bbox.level,
+				bbox.x_min()? + (index % width) as u32,
+				bbox.y_min()? + (index / width) as u32,
+			);
+		}
+		index -= count;
+	}
+	bail!("tile index is outside of the pyramid")
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{MockReader, TileSourceMetadata, TilesConverterParameters, TilesRuntime, convert_tiles_container};
+	use std::sync::Arc;
+
+	fn get_converter(compression: TileCompression, max_level: u8) -> Result<TilesConvertReader> {
+		let metadata = TileSourceMetadata::new_builder()
+			.with_format(TileFormat::MVT)
+			.with_compression(TileCompression::Gzip)
+			.with_bbox_pyramid(TileBBoxPyramid::new_full(max_level))
+			.build()?;
+		let reader = Arc::new(MockReader::new_mock(metadata)?.boxed());
+		let cp = TilesConverterParameters {
+			tile_compression: Some(compression),
+			..Default::default()
+		};
+		TilesConvertReader::new_from_reader(reader, cp)
+	}
+
+	#[test]
+	fn coords() -> Result<()> {
+		let pyramid = TileBBoxPyramid::new_full(2);
+		let coords = (0..pyramid.count_tiles())
+			.map(|index| coord_at(&pyramid, index))
+			.collect::<Result<Vec<_>>>()?;
+		assert_eq!(coords[0], TileCoord::new(0, 0, 0)?);
+		assert_eq!(coords[3], TileCoord::new(1, 0, 1)?);
+		assert_eq!(coords[20], TileCoord::new(2, 3, 3)?);
+		assert!(coord_at(&pyramid, 21).is_err());
+
+		assert_eq!(sample_indices(5), [0, 1, 2, 3, 4]);
+		let indices = sample_indices(1_000_000);
+		assert_eq!(indices.len(), CONVERSION_SAMPLE_SIZE as usize);
+		assert!(indices.iter().all(|i| *i < 1_000_000));
+		assert_eq!(indices, sample_indices(1_000_000));
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn plan() -> Result<()> {
+		let converter = get_converter(TileCompression::Brotli, 2)?;
+		let plan = ConversionPlan::new(&converter, Path::new("out.versatiles")).await?;
+		assert_eq!(plan.tiles_per_level, [(0, 1), (1, 4), (2, 16)]);
+		assert_eq!(plan.source, (TileFormat::MVT, TileCompression::Gzip));
+		assert_eq!(plan.target, (TileFormat::MVT, TileCompression::Brotli));
+		assert!(plan.recompress);
+
+		// small pyramids are sampled completely, so the estimate is exact
+		assert_eq!((plan.sample_coords, plan.sample_tiles), (21, 21));
+		assert_eq!(plan.estimated_size(), plan.sample_bytes);
+
+		let plan = ConversionPlan::new(&get_converter(TileCompression::Gzip, 8)?, Path::new("out.pmtiles")).await?;
+		assert!(!plan.recompress);
+		assert_eq!(plan.sample_coords, CONVERSION_SAMPLE_SIZE);
+		assert!(plan.estimated_size() > plan.sample_bytes);
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn dry_run_writes_nothing() -> Result<()> {
+		let temp_dir = assert_fs::TempDir::new()?;
+		let path = temp_dir.path().join("out.versatiles");
+		let converter = get_converter(TileCompression::Brotli, 3)?;
+		let cp = TilesConverterParameters {
+			dry_run: true,
+			..Default::default()
+		};
+		convert_tiles_container(Arc::new(converter.boxed()), cp, &path, TilesRuntime::default()).await?;
+		assert!(!path.exists());
+		assert_eq!(std::fs::read_dir(temp_dir.path())?.count(), 0);
+		Ok(())
+	}
+
+	#[tokio::test]
+	#[cfg(feature = "cli")]
+	async fn pretty_print() -> Result<()> {
+		let converter = get_converter(TileCompression::Brotli, 2)?;
+		let plan = ConversionPlan::new(&converter, Path::new("out.versatiles")).await?;
+		let mut printer = PrettyPrint::new();
+		plan.pretty_print(&mut printer.get_category("dry run").await).await;
+		let output = printer.as_string().await;
+		for row in [
+			"0: 1",
+			"1: 4",
+			"2: 16",
+			"tile count: 21",
+			"gzip → brotli",
+			"recompressed",
+		] {
+			assert!(output.contains(row), "{output}");
+		}
+		Ok(())
+	}
+}
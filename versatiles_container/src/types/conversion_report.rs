@@ -0,0 +1,265 @@
+//! Machine-readable report of a conversion.
+//!
+//! If [`TilesConverterParameters::report_path`](crate::TilesConverterParameters::report_path) is set,
+//! [`convert_tiles_container`](crate::convert_tiles_container) writes a [`ConversionReport`] as JSON
+//! to that path when the conversion ends. Failed conversions also get a report: it contains
+//! everything that was recorded until the error, plus the error chain.
+//!
+//! The structs implement `serde::Deserialize`, so build systems can read archived reports with
+//! `serde_json`. The schema is versioned by `report_version`: new versions only add fields,
+//! renaming or removing a field increases [`CONVERSION_REPORT_VERSION`].
+
+use crate::{Event, EventBus, ListenerId, LogLevel, TileSource, TileSourceMetadata, TilesRuntime};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::{
+	collections::BTreeMap,
+	path::{Path, PathBuf},
+	sync::{Arc, Mutex},
+	time::Instant,
+};
+use versatiles_derive::context;
+
+/// Current version of the [`ConversionReport`] schema.
+pub const CONVERSION_REPORT_VERSION: u32 = 1;
+
+/// What a conversion did, see the module documentation.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ConversionReport {
+	/// Version of the schema, see [`CONVERSION_REPORT_VERSION`].
+	pub report_version: u32,
+	/// Description of the source, e.g. its container type and path.
+	pub input: String,
+	/// Output path.
+	pub output: PathBuf,
+	/// Metadata of the source, before the conversion parameters are applied.
+	pub source: ReportMetadata,
+	/// Metadata of the output, after the conversion parameters are applied.
+	/// `None` if the conversion failed before they were applied.
+	pub target: Option<ReportMetadata>,
+	/// Number of tiles per zoom level that were passed to the writer.
+	pub tiles_per_level: BTreeMap<u8, u64>,
+	/// Size of the output in bytes, summed over all files if the output is a directory.
+	/// `None` if the output doesn't exist.
+	pub bytes_written: Option<u64>,
+	/// Warnings emitted by the runtime during the conversion.
+	pub warnings: Vec<String>,
+	/// Wall time of the conversion in seconds.
+	pub elapsed_seconds: f64,
+	/// `true` if the conversion finished without an error.
+	pub success: bool,
+	/// The error and its causes, outermost first, if the conversion failed.
+	pub error: Option<Vec<String>>,
+}
+
+/// Tile format, compression and coverage of one side of a conversion.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ReportMetadata {
+	pub tile_format: String,
+	pub tile_compression: String,
+	/// Edge length of the tiles in pixels, if known.
+	pub tile_size: Option<u16>,
+	pub level_min: Option<u8>,
+	pub level_max: Option<u8>,
+	/// Bounding box as `[west, south, east, north]` in degrees, if the pyramid isn't empty.
+	pub bbox: Option<[f64; 4]>,
+	/// Number of tiles covered by the bbox pyramid.
+	pub tile_count: u64,
+}
+
+impl From<&TileSourceMetadata> for ReportMetadata {
+	fn from(metadata: &TileSourceMetadata) -> Self {
+		let pyramid = &metadata.bbox_pyramid;
+		ReportMetadata {
+			tile_format: metadata.tile_format.to_string(),
+			tile_compression: metadata.tile_compression.to_string(),
+			tile_size: metadata.tile_size.map(|size| size.size()),
+			level_min: pyramid.get_level_min(),
+			level_max: pyramid.get_level_max(),
+			bbox: pyramid.get_geo_bbox().map(|bbox| bbox.as_array()),
+			tile_count: pyramid.count_tiles(),
+		}
+	}
+}
+
+impl ConversionReport {
+	/// Writes the report as pretty-printed JSON to `path`.
+	#[context("writing conversion report to '{}'", path.display())]
+	pub fn write(&self, path: &Path) -> Result<()> {
+		let json = serde_json::to_string_pretty(self)?;
+		std::fs::write(path, json)?;
+		Ok(())
+	}
+
+	/// Reads a report that was written with [`ConversionReport::write`].
+	#[context("reading conversion report from '{}'", path.display())]
+	pub fn read(path: &Path) -> Result<Self> {
+		let json = std::fs::read_to_string(path)?;
+		Ok(serde_json::from_str(&json)?)
+	}
+}
+
+/// Collects the data of a [`ConversionReport`] while a conversion runs.
+pub(crate) struct ReportRecorder {
+	report: ConversionReport,
+	start: Instant,
+	/// Shared with the converter, which counts the tiles it passes to the writer.
+	pub tile_counts: Arc<Mutex<BTreeMap<u8, u64>>>,
+	/// `None` once the report is finished, so later events are ignored.
+	warnings: Arc<Mutex<Option<Vec<String>>>>,
+	/// Removes the warnings listener when the recorder is dropped.
+	_subscription: Subscription,
+}
+
+/// Unsubscribes a listener from the event bus when dropped, so long-lived runtimes don't
+/// collect a listener per conversion.
+struct Subscription {
+	events: EventBus,
+	id: ListenerId,
+}
+
+impl Drop for Subscription {
+	fn drop(&mut self) {
+		self.events.unsubscribe(self.id);
+	}
+}
+
+impl ReportRecorder {
+	/// Starts recording a conversion of `reader` to `output`.
+	pub fn start(reader: &dyn TileSource, output: &Path, runtime: &TilesRuntime) -> Self {
+		let warnings = Arc::new(Mutex::new(Some(Vec::new())));
+		let listener_warnings = Arc::clone(&warnings);
+		let id = runtime.events().subscribe(move |event| {
+			let message = match event {
+				Event::Warning { message } => message,
+				Event::Log {
+					level: LogLevel::Warn,
+					message,
+					..
+				} => message,
+				_ => return,
+			};
+			if let Some(warnings) = listener_warnings.lock().unwrap().as_mut() {
+				warnings.push(message.clone());
+			}
+		});
+
+		ReportRecorder {
+			report: ConversionReport {
+				report_version: CONVERSION_REPORT_VERSION,
+				input: reader.source_type().to_string(),
+				output: output.to_path_buf(),
+				source: ReportMetadata::from(reader.metadata()),
+				target: None,
+				tiles_per_level: BTreeMap::new(),
+				bytes_written: None,
+				warnings: Vec::new(),
+				elapsed_seconds: 0.0,
+				success: false,
+				error: None,
+			},
+			start: Instant::now(),
+			tile_counts: Arc::default(),
+			warnings,
+			_subscription: Subscription {
+				events: runtime.events().clone(),
+				id,
+			},
+		}
+	}
+
+	/// Records the metadata of the output.
+	pub fn set_target(&mut self, metadata: &TileSourceMetadata) {
+		self.report.target = Some(ReportMetadata::from(metadata));
+	}
+
+	/// Completes the report with the outcome of the conversion.
+	pub fn finish(self, result: &Result<()>) -> ConversionReport {
+		let mut report = self.report;
+		report.elapsed_seconds = self.start.elapsed().as_secs_f64();
+		report.tiles_per_level = self.tile_counts.lock().unwrap().clone();
+		report.bytes_written = disk_size(&report.output);
+		report.warnings = self.warnings.lock().unwrap().take().unwrap_or_default();
+		report.success = result.is_ok();
+		report.error = result
+			.as_ref()
+			.err()
+			.map(|err| err.chain().map(ToString::to_string).collect());
+		report
+	}
+}
+
+/// Returns the size of a file, or the total size of all files in a directory.
+fn disk_size(path: &Path) -> Option<u64> {
+	let metadata = std::fs::metadata(path).ok()?;
+	if !metadata.is_dir() {
+		return Some(metadata.len());
+	}
+	let mut size = 0;
+	for entry in std::fs::read_dir(path).ok()? {
+		size += disk_size(&entry.ok()?.path())?;
+	}
+	Some(size)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::MockReader;
+	use versatiles_core::{TileBBoxPyramid, TileCompression, TileFormat};
+
+	#[test]
+	fn report_metadata() -> Result<()> {
+		let metadata = TileSourceMetadata::new_builder()
+			.with_format(TileFormat::MVT)
+			.with_compression(TileCompression::Gzip)
+			.with_bbox_pyramid(TileBBoxPyramid::new_full(2))
+			.build()?;
+		let report = ReportMetadata::from(&metadata);
+		assert_eq!(report.tile_format, "mvt");
+		assert_eq!(report.tile_compression, "gzip");
+		assert_eq!((report.level_min, report.level_max), (Some(0), Some(2)));
+		assert_eq!(report.tile_count, 21);
+		Ok(())
+	}
+
+	#[test]
+	fn recorder_collects_warnings_and_errors() -> Result<()> {
+		let runtime = TilesRuntime::default();
+		let reader = MockReader::new_mock_profile(crate::MockReaderProfile::Pbf)?;
+		let mut recorder = ReportRecorder::start(&reader, Path::new("/does/not/exist.tar"), &runtime);
+		recorder.set_target(reader.metadata());
+		*recorder.tile_counts.lock().unwrap().entry(3).or_default() += 2;
+		runtime.events().warn("something odd".to_string());
+
+		let result = Err(anyhow::anyhow!("disk full").context("writing tiles"));
+		let report = recorder.finish(&result);
+		runtime.events().warn("too late".to_string());
+
+		assert_eq!(report.report_version, CONVERSION_REPORT_VERSION);
+		assert_eq!(report.target.as_ref(), Some(&report.source));
+		assert_eq!(report.tiles_per_level, BTreeMap::from([(3, 2)]));
+		assert_eq!(report.bytes_written, None);
+		assert_eq!(report.warnings, ["something odd"]);
+		assert!(!report.success);
+		assert_eq!(
+			report.error,
+			Some(vec!["writing tiles".to_string(), "disk full".to_string()])
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn write_and_read() -> Result<()> {
+		let runtime = TilesRuntime::default();
+		let reader = MockReader::new_mock_profile(crate::MockReaderProfile::Png)?;
+		let report = ReportRecorder::start(&reader, Path::new("out.versatiles"), &runtime).finish(&Ok(()));
+
+		let file = assert_fs::NamedTempFile::new("report.json")?;
+		report.write(&file)?;
+		let json = std::fs::read_to_string(&file)?;
+		assert!(json.contains("\"report_version\": 1"), "{json}");
+		assert_eq!(ConversionReport::read(&file)?, report);
+		Ok(())
+	}
+}
@@ -1,10 +1,59 @@
 //! Converts tile data between formats, compressions, and coordinate conventions.
 //!
 //! This module provides:
-//! - [`TilesConverterParameters`]: declarative knobs (bbox filter, compression override, `flip_y`, `swap_xy`)
+//! - [`TilesConverterParameters`]: declarative knobs (bbox filter, geographic bbox filter, compression override, `flip_y`, `swap_xy`, `force_recompress`, `brotli_level`, `tile_size`, `optimize_png`, `quantize_png`, `elide_empty_tiles`, `drop_blank_tiles`, `reproducible`, `dry_run`, `report_path`)
 //! - [`TilesConvertReader`]: an adapter that applies those conversions while reading
 //! - [`convert_tiles_container`]: a convenience function to convert and write to a target path using a [`ContainerRegistry`]
 //!
+//! ## Geographic filter
+//! Set `geo_bbox`, e.g. with [`TilesConverterParameters::with_bbox_filter`], to only keep the tiles
+//! that intersect a bounding box in degrees, e.g. to cut a city out of a world-wide tileset without
+//! knowing its tile coordinates. It is applied on every zoom level, in addition to `bbox_pyramid`.
+//!
+//! ## Byte-identical copies
+//! Tiles are only recompressed if the target compression differs from the source compression.
+//! Otherwise the original bytes are passed through untouched, so checksums of converted tiles
+//! stay stable. Set `force_recompress` to re-encode every tile anyway.
+//!
+//! ## Brotli quality
+//! Tiles compressed to Brotli use quality level 10 by default. Set `brotli_level` (0–11) to
+//! trade conversion time for size, e.g. 11 for the smallest tiles.
+//!
+//! ## Tile size
+//! Set `tile_size` to resample raster tiles to a different pixel size, e.g. 512 px tiles to 256 px
+//! for older clients. Images are resized with a Lanczos filter and encoded again in their format.
+//! Tiles that already have the target size keep their original bytes. Vector tiles pass through.
+//!
+//! ## PNG optimization
+//! Set `optimize_png` to re-encode PNG tiles losslessly with [`optimize`](versatiles_image::format::png::optimize),
+//! which takes much longer but usually saves 10–20 %. Tiles that don't get smaller keep their original bytes.
+//!
+//! Set `quantize_png` to a maximum number of colours to store PNG tiles as palette images instead,
+//! see [`quantize`](versatiles_image::format::png::quantize). This is lossy for tiles with more colours.
+//!
+//! ## Empty tiles
+//! Set `elide_empty_tiles` to drop tiles that can't show anything (fully transparent images,
+//! vector tiles without features, see [`is_empty_tile`]). Only tiles up to the given stored size
+//! are decoded, larger tiles are kept without checking.
+//!
+//! Set `drop_blank_tiles` to also drop raster tiles with a single uniform color, e.g. plain ocean,
+//! see [`blank_tile`]. Blank tiles include the empty ones, so this replaces `elide_empty_tiles`:
+//! every tile is decoded, whatever its size. The colors of the dropped tiles are counted and
+//! logged after the conversion, so clients can draw them as a background instead.
+//!
+//! ## Reproducible output
+//! Set `reproducible` to get byte-identical files for identical input, e.g. for artifact caching,
+//! see [`TilesConverterParameters::reproducible`].
+//!
+//! ## Dry runs
+//! With `dry_run` the conversion only reports its [`ConversionPlan`] (tiles per zoom level,
+//! format/compression mapping and an estimated output size) and doesn't write anything.
+//!
+//! ## Reports
+//! Set `report_path` to write a [`ConversionReport`] as JSON when the conversion ends: source and
+//! output metadata, tiles per zoom level, bytes written, warnings and wall time. Failed conversions
+//! get a partial report with the error chain, so build systems can archive what happened.
+//!
 //! ## Coordinate transforms
 //! - `flip_y`: inverts Y within the zoom level (useful to switch between TMS and XYZ-like schemes)
 //! - `swap_xy`: swaps X and Y (occasionally useful for sources with unconventional axis ordering)
@@ -40,12 +89,25 @@
 //! }
 //! ```
 
-use crate::{SourceType, Tile, TileSource, TileSourceMetadata, TilesRuntime};
-use anyhow::Result;
+use crate::{
+	BlankTile, ConversionPlan, ReportRecorder, SourceType, Tile, TileSource, TileSourceMetadata, TilesRuntime,
+	blank_tile, is_empty_tile,
+};
+use anyhow::{Result, ensure};
 use async_trait::async_trait;
-use std::{path::Path, sync::Arc};
-use versatiles_core::{TileBBox, TileBBoxPyramid, TileCompression, TileCoord, TileJSON, TileStream};
+use std::{
+	collections::{BTreeMap, HashMap},
+	path::{Path, PathBuf},
+	sync::{Arc, Mutex},
+};
+#[cfg(feature = "cli")]
+use versatiles_core::utils::PrettyPrint;
+use versatiles_core::{
+	GeoBBox, TileBBox, TileBBoxPyramid, TileCompression, TileCoord, TileFormat, TileJSON, TileSize, TileStream,
+	utils::{BROTLI_DEFAULT_LEVEL, BROTLI_MAX_LEVEL, compress_brotli_level, decompress_ref},
+};
 use versatiles_derive::context;
+use versatiles_image::{DynamicImage, DynamicImageTraitConvert, DynamicImageTraitOperation, format::png};
 
 /// Parameters that control how tiles are transformed during reading/conversion.
 ///
@@ -59,6 +121,9 @@ pub struct TilesConverterParameters {
 	/// Optional spatial/zoom restriction. When set, only tiles inside the given
 	/// [`TileBBoxPyramid`] are read/streamed. Existing bounds are intersected with this.
 	pub bbox_pyramid: Option<TileBBoxPyramid>,
+	/// Optional geographic restriction. When set, only tiles intersecting this [`GeoBBox`]
+	/// are read/streamed, on every zoom level. Applied in addition to `bbox_pyramid`.
+	pub geo_bbox: Option<GeoBBox>,
 	/// Optional compression override. When set, tile payloads are re-encoded to this
 	/// [`TileCompression`] (e.g., Gzip → Brotli). If `None`, the source compression is kept.
 	pub tile_compression: Option<TileCompression>,
@@ -66,6 +131,47 @@ pub struct TilesConverterParameters {
 	pub flip_y: bool,
 	/// If `true`, swap X and Y coordinates.
 	pub swap_xy: bool,
+	/// If `true`, decompress and compress every tile again, even if the compression doesn't change.
+	/// If `false` (default), tiles with matching compression are copied byte for byte.
+	pub force_recompress: bool,
+	/// Optional Brotli quality level (0–11) for tiles that are compressed to Brotli.
+	/// If `None`, the default level of [`compress_brotli`](versatiles_core::utils::compress_brotli) is used.
+	pub brotli_level: Option<u32>,
+	/// If set, raster tiles are resized to this [`TileSize`].
+	pub tile_size: Option<TileSize>,
+	/// If `true`, PNG tiles are re-encoded losslessly with the smallest possible size.
+	pub optimize_png: bool,
+	/// If set, PNG tiles are reduced to at most this many colours (2–256) and stored as palette images.
+	/// Takes precedence over `optimize_png`.
+	pub quantize_png: Option<u16>,
+	/// If set, tiles with a stored size of at most this many bytes are dropped if they are empty.
+	/// See [`DEFAULT_EMPTY_TILE_MAX_SIZE`](crate::DEFAULT_EMPTY_TILE_MAX_SIZE) for a sensible limit.
+	pub elide_empty_tiles: Option<u64>,
+	/// If `true`, blank tiles (a single uniform color, or no features) are dropped, whatever their size.
+	/// Empty tiles are blank too, so `elide_empty_tiles` has no effect then.
+	pub drop_blank_tiles: bool,
+	/// If `true`, converting the same input twice gives byte-identical output.
+	///
+	/// Tiles are processed in parallel, so they usually leave the converter in an order that depends
+	/// on timing. With this option the tiles of every requested bbox are sorted by coordinate, writers
+	/// traverse the bboxes in a fixed order, and `brotli_level` is pinned to [`BROTLI_DEFAULT_LEVEL`]
+	/// unless it is set explicitly.
+	///
+	/// What else matters depends on the writer:
+	/// - `.tar`: entries are written in stream order, always without mtime, uid and gid.
+	/// - `.versatiles`: tiles are written in stream order and blocks are indexed in coordinate order.
+	/// - `.pmtiles`: tiles are sorted within each block anyway, so only the tile bytes matter.
+	/// - TileJSON metadata: keys are always written in sorted order.
+	/// - `.mbtiles` and directories are not byte-reproducible: SQLite pages and file timestamps
+	///   depend on the time and order of writing.
+	///
+	/// Sorting buffers the tiles of each bbox in memory.
+	pub reproducible: bool,
+	/// If `true`, only report the [`ConversionPlan`] and don't write the output.
+	pub dry_run: bool,
+	/// If set, a [`ConversionReport`](crate::ConversionReport) is written as JSON to this path
+	/// when the conversion ends, also if it fails.
+	pub report_path: Option<PathBuf>,
 }
 
 impl Default for TilesConverterParameters {
@@ -73,16 +179,42 @@ impl Default for TilesConverterParameters {
 	fn default() -> Self {
 		TilesConverterParameters {
 			bbox_pyramid: None,
+			geo_bbox: None,
 			tile_compression: None,
 			flip_y: false,
 			swap_xy: false,
+			force_recompress: false,
+			brotli_level: None,
+			tile_size: None,
+			optimize_png: false,
+			quantize_png: None,
+			elide_empty_tiles: None,
+			drop_blank_tiles: false,
+			reproducible: false,
+			dry_run: false,
+			report_path: None,
 		}
 	}
 }
 
+impl TilesConverterParameters {
+	/// Restricts the output to the tiles intersecting `geo_bbox`, given as `[west, south, east, north]` in degrees.
+	///
+	/// ### Errors
+	/// Returns an error if `geo_bbox` is not a valid bounding box.
+	#[context("Invalid bbox filter {geo_bbox:?}")]
+	pub fn with_bbox_filter(mut self, geo_bbox: [f64; 4]) -> Result<Self> {
+		self.geo_bbox = Some(GeoBBox::try_from(geo_bbox)?);
+		Ok(self)
+	}
+}
+
 /// Converts tiles from the given reader and writes them to `path` using the provided runtime.
 ///
 /// The conversion is applied by wrapping `reader` in a [`TilesConvertReader`] configured by `cp`.
+/// If `cp.dry_run` is set, the [`ConversionPlan`] is printed instead and `path` is not touched.
+/// If `cp.report_path` is set, a [`ConversionReport`](crate::ConversionReport) is written there
+/// afterwards, whether the conversion succeeded or not.
 ///
 /// ### Arguments
 /// - `reader`: Source container reader.
@@ -92,22 +224,79 @@ impl Default for TilesConverterParameters {
 ///
 /// ### Errors
 /// Returns an error if reading tiles fails, if writing to the destination fails,
-/// or if no suitable writer is registered for the output path.
+/// if no suitable writer is registered for the output path, or if the report can't be written.
 ///
 /// ### Example
 /// See the module-level example.
-#[context("Converting tiles from reader to file")]
 pub async fn convert_tiles_container(
 	reader: Arc<Box<dyn TileSource>>,
 	cp: TilesConverterParameters,
 	path: &Path,
 	runtime: TilesRuntime,
+) -> Result<()> {
+	let Some(report_path) = cp.report_path.clone() else {
+		return run_conversion(reader, cp, path, runtime, None).await;
+	};
+
+	let mut recorder = ReportRecorder::start(&**reader, path, &runtime);
+	let result = run_conversion(reader, cp, path, runtime, Some(&mut recorder)).await;
+	let written = recorder.finish(&result).write(&report_path);
+	match (result, written) {
+		(Err(err), Err(report_err)) => {
+			// The conversion error is more important than the missing report.
+			log::error!("{report_err:?}");
+			Err(err)
+		}
+		(result, written) => result.and(written),
+	}
+}
+
+/// Does the work of [`convert_tiles_container`], recording it in `recorder` if given.
+///
+/// The error context is added here, so the report contains the same error chain as the result.
+#[context("Converting tiles from reader to file")]
+async fn run_conversion(
+	reader: Arc<Box<dyn TileSource>>,
+	cp: TilesConverterParameters,
+	path: &Path,
+	runtime: TilesRuntime,
+	mut recorder: Option<&mut ReportRecorder>,
 ) -> Result<()> {
 	runtime.events().step("Starting conversion".to_string());
 
-	let converter = TilesConvertReader::new_from_reader(reader, cp)?;
+	let mut converter = TilesConvertReader::new_from_reader(reader, cp)?;
+	if let Some(recorder) = recorder.as_deref_mut() {
+		recorder.set_target(converter.metadata());
+	}
+
+	if converter.converter_parameters.dry_run {
+		let plan = ConversionPlan::new(&converter, path).await?;
+		#[cfg(feature = "cli")]
+		plan
+			.pretty_print(&mut PrettyPrint::new().get_category("dry run").await)
+			.await;
+		#[cfg(not(feature = "cli"))]
+		log::info!("dry run: {plan:?}");
+		return Ok(());
+	}
+
+	if let Some(recorder) = recorder {
+		converter.tile_counts = Some(Arc::clone(&recorder.tile_counts));
+	}
+
+	let blank_tiles = Arc::clone(&converter.blank_tiles);
 	runtime.write_to_path(Arc::new(Box::new(converter)), path).await?;
 
+	let mut blank_tiles = blank_tiles.lock().unwrap().drain().collect::<Vec<_>>();
+	if !blank_tiles.is_empty() {
+		blank_tiles.sort_unstable();
+		let counts = blank_tiles
+			.iter()
+			.map(|(blank, count)| format!("{count}× {blank}"))
+			.collect::<Vec<_>>();
+		log::info!("dropped blank tiles: {}", counts.join(", "));
+	}
+
 	runtime.events().step("Conversion complete".to_string());
 	Ok(())
 }
@@ -124,6 +313,9 @@ pub struct TilesConvertReader {
 	converter_parameters: TilesConverterParameters,
 	reader_metadata: TileSourceMetadata,
 	tilejson: TileJSON,
+	blank_tiles: Arc<Mutex<HashMap<BlankTile, u64>>>,
+	/// If set, the tiles leaving [`TileSource::get_tile_stream`] are counted per zoom level.
+	tile_counts: Option<Arc<Mutex<BTreeMap<u8, u64>>>>,
 }
 
 impl TilesConvertReader {
@@ -137,8 +329,20 @@ impl TilesConvertReader {
 	#[context("Creating converter reader from existing reader")]
 	pub fn new_from_reader(
 		reader: Arc<Box<dyn TileSource>>,
-		cp: TilesConverterParameters,
+		mut cp: TilesConverterParameters,
 	) -> Result<TilesConvertReader> {
+		if let Some(level) = cp.brotli_level {
+			ensure!(
+				level <= BROTLI_MAX_LEVEL,
+				"brotli_level must be between 0 and {BROTLI_MAX_LEVEL}, but is {level}"
+			);
+		}
+
+		if cp.reproducible {
+			// don't depend on the default of the compression library
+			cp.brotli_level.get_or_insert(BROTLI_DEFAULT_LEVEL);
+		}
+
 		let rp: TileSourceMetadata = reader.metadata().to_owned();
 		let mut new_rp: TileSourceMetadata = rp.clone();
 
@@ -153,10 +357,20 @@ impl TilesConvertReader {
 			new_rp.bbox_pyramid.intersect(bbox_pyramid);
 		}
 
+		if let Some(geo_bbox) = &cp.geo_bbox {
+			new_rp.bbox_pyramid.intersect_geo_bbox(geo_bbox)?;
+		}
+
 		if let Some(tile_compression) = cp.tile_compression {
 			new_rp.tile_compression = tile_compression;
 		}
 
+		if let Some(size) = cp.tile_size
+			&& new_rp.tile_format.is_raster()
+		{
+			new_rp.tile_size = Some(size);
+		}
+
 		let mut tilejson = reader.tilejson().clone();
 		new_rp.update_tilejson(&mut tilejson);
 
@@ -165,8 +379,48 @@ impl TilesConvertReader {
 			converter_parameters: cp,
 			reader_metadata: new_rp,
 			tilejson,
+			blank_tiles: Arc::default(),
+			tile_counts: None,
 		})
 	}
+
+	/// Returns the metadata of the wrapped reader, i.e. before the conversion.
+	#[must_use]
+	pub fn source_metadata(&self) -> &TileSourceMetadata {
+		self.reader.metadata()
+	}
+
+	fn resizes_tiles(&self) -> bool {
+		self.converter_parameters.tile_size.is_some() && self.reader_metadata.tile_format.is_raster()
+	}
+
+	fn optimizes_png(&self) -> bool {
+		let cp = &self.converter_parameters;
+		(cp.optimize_png || cp.quantize_png.is_some()) && self.reader_metadata.tile_format == TileFormat::PNG
+	}
+
+	/// Returns how many tiles were dropped by `drop_blank_tiles`, per blank color, sorted.
+	#[must_use]
+	pub fn dropped_blank_tiles(&self) -> Vec<(BlankTile, u64)> {
+		let mut counts = self
+			.blank_tiles
+			.lock()
+			.unwrap()
+			.iter()
+			.map(|(blank, count)| (*blank, *count))
+			.collect::<Vec<_>>();
+		counts.sort_unstable();
+		counts
+	}
+
+	/// Returns `true` if tiles are decompressed and compressed again instead of being copied.
+	#[must_use]
+	pub fn recompresses(&self) -> bool {
+		self.converter_parameters.force_recompress
+			|| self.resizes_tiles()
+			|| self.optimizes_png()
+			|| self.reader.metadata().tile_compression != self.reader_metadata.tile_compression
+	}
 }
 
 #[async_trait]
@@ -183,6 +437,10 @@ impl TileSource for TilesConvertReader {
 		&self.tilejson
 	}
 
+	fn requires_ordered_traversal(&self) -> bool {
+		self.converter_parameters.reproducible
+	}
+
 	async fn get_tile(&self, coord: &TileCoord) -> Result<Option<Tile>> {
 		let mut coord = *coord;
 
@@ -196,10 +454,31 @@ impl TileSource for TilesConvertReader {
 
 		let tile = self.reader.get_tile(&coord).await?;
 
-		let mut tile = if let Some(tile) = tile { tile } else { return Ok(None) };
+		let Some(mut tile) = tile else { return Ok(None) };
+
+		let cp = &self.converter_parameters;
+		if is_dropped(&mut tile, cp.elide_empty_tiles, cp.drop_blank_tiles, &self.blank_tiles)? {
+			return Ok(None);
+		}
+
+		if let Some(size) = cp.tile_size
+			&& self.resizes_tiles()
+		{
+			tile = resize_tile(tile, size)?;
+		}
+
+		if self.optimizes_png() {
+			tile = optimize_png_tile(tile, cp.quantize_png)?;
+		}
 
-		if let Some(compression) = self.converter_parameters.tile_compression {
-			tile.change_compression(compression)?;
+		if cp.force_recompress || cp.tile_compression.is_some() {
+			let compression = self.reader_metadata.tile_compression;
+			return Ok(Some(recompress_tile(
+				tile,
+				compression,
+				cp.force_recompress,
+				cp.brotli_level,
+			)?));
 		}
 
 		Ok(Some(tile))
@@ -230,34 +509,186 @@ impl TileSource for TilesConvertReader {
 			});
 		}
 
-		if let Some(tile_compression) = self.converter_parameters.tile_compression {
-			stream = stream
-				.map_item_parallel(move |mut tile| {
-					tile.change_compression(tile_compression)?;
-					Ok(tile)
-				})
-				.unwrap_results();
+		let cp = &self.converter_parameters;
+		if cp.elide_empty_tiles.is_some() || cp.drop_blank_tiles {
+			let (elide_empty_tiles, drop_blank_tiles) = (cp.elide_empty_tiles, cp.drop_blank_tiles);
+			let blank_tiles = Arc::clone(&self.blank_tiles);
+			stream = stream.try_filter_map_item_parallel(move |mut tile| {
+				let dropped = is_dropped(&mut tile, elide_empty_tiles, drop_blank_tiles, &blank_tiles)?;
+				Ok((!dropped).then_some(tile))
+			});
+		}
+
+		if let Some(size) = cp.tile_size
+			&& self.resizes_tiles()
+		{
+			stream = stream.try_map_item_parallel(move |tile| resize_tile(tile, size));
+		}
+
+		if self.optimizes_png() {
+			let quantize = cp.quantize_png;
+			stream = stream.try_map_item_parallel(move |tile| optimize_png_tile(tile, quantize));
+		}
+
+		if cp.force_recompress || cp.tile_compression.is_some() {
+			let tile_compression = self.reader_metadata.tile_compression;
+			let force = cp.force_recompress;
+			let brotli_level = cp.brotli_level;
+			stream =
+				stream.try_map_item_parallel(move |tile| recompress_tile(tile, tile_compression, force, brotli_level));
+		}
+
+		if cp.reproducible {
+			let mut tiles = stream.to_vec().await?;
+			tiles.sort_by_key(|(coord, _)| coord.sort_index());
+			stream = TileStream::from_vec(tiles);
+		}
+
+		if let Some(tile_counts) = &self.tile_counts {
+			let tile_counts = Arc::clone(tile_counts);
+			stream = stream.map_coord(move |coord| {
+				*tile_counts.lock().unwrap().entry(coord.level).or_default() += 1;
+				coord
+			});
 		}
 
 		Ok(stream)
 	}
 }
 
+/// Returns `true` if `tile` is dropped by `elide_empty_tiles` or `drop_blank_tiles`.
+///
+/// Empty tiles are blank too, so with `drop_blank_tiles` only the blank check runs, for tiles of
+/// any size, and `elide_empty_tiles` has no effect.
+fn is_dropped(
+	tile: &mut Tile,
+	elide_empty_tiles: Option<u64>,
+	drop_blank_tiles: bool,
+	blank_tiles: &Mutex<HashMap<BlankTile, u64>>,
+) -> Result<bool> {
+	if drop_blank_tiles {
+		return is_blank(tile, blank_tiles);
+	}
+	match elide_empty_tiles {
+		Some(max_size) => is_elidable(tile, max_size),
+		None => Ok(false),
+	}
+}
+
+/// Returns `true` if `tile` is empty and its stored size is at most `max_size` bytes.
+///
+/// The stored bytes of the tile stay untouched, so kept tiles can still be copied byte for byte.
+fn is_elidable(tile: &mut Tile, max_size: u64) -> Result<bool> {
+	let format = tile.format();
+	let compression = tile.compression();
+	let blob = tile.as_blob(compression)?;
+	if blob.len() > max_size {
+		return Ok(false);
+	}
+	is_empty_tile(format, &decompress_ref(blob, compression)?)
+}
+
+/// Returns `true` if `tile` is blank and counts it in `blank_tiles`.
+///
+/// Like [`is_elidable`], the stored bytes of the tile stay untouched.
+fn is_blank(tile: &mut Tile, blank_tiles: &Mutex<HashMap<BlankTile, u64>>) -> Result<bool> {
+	let format = tile.format();
+	let compression = tile.compression();
+	let Some(blank) = blank_tile(format, &decompress_ref(tile.as_blob(compression)?, compression)?)? else {
+		return Ok(false);
+	};
+	*blank_tiles.lock().unwrap().entry(blank).or_default() += 1;
+	Ok(true)
+}
+
+/// Resizes a raster tile to `size`×`size` pixels, keeping its format and compression.
+/// Tiles that already have this size are returned untouched.
+fn resize_tile(mut tile: Tile, size: TileSize) -> Result<Tile> {
+	let format = tile.format();
+	let compression = tile.compression();
+	let size = u32::from(size.size());
+	let image = DynamicImage::from_blob(&decompress_ref(tile.as_blob(compression)?, compression)?, format)?;
+	if image.width() == size && image.height() == size {
+		return Ok(tile);
+	}
+	let mut tile = Tile::from_image(image.get_resized(size, size)?, format)?;
+	tile.change_compression(compression)?;
+	Ok(tile)
+}
+
+/// Re-encodes a PNG tile with [`optimize`](versatiles_image::format::png::optimize), or with
+/// [`quantize`](versatiles_image::format::png::quantize) if `quantize` is set, keeping its compression.
+/// The original tile is kept if the new PNG isn't smaller.
+fn optimize_png_tile(mut tile: Tile, quantize: Option<u16>) -> Result<Tile> {
+	let compression = tile.compression();
+	let original = decompress_ref(tile.as_blob(compression)?, compression)?;
+	let image = png::blob2image(&original)?;
+	let optimized = match quantize {
+		Some(max_colors) => png::quantize(&image, max_colors)?,
+		None => png::optimize(&image)?,
+	};
+	if optimized.len() >= original.len() {
+		return Ok(tile);
+	}
+	let mut tile = Tile::from_blob(optimized, TileCompression::Uncompressed, TileFormat::PNG);
+	tile.change_compression(compression)?;
+	Ok(tile)
+}
+
+/// Converts `tile` to `compression`, using `brotli_level` if the tile is compressed to Brotli.
+///
+/// Tiles that already have the target compression are only re-encoded if `force` is set.
+fn recompress_tile(
+	mut tile: Tile,
+	compression: TileCompression,
+	force: bool,
+	brotli_level: Option<u32>,
+) -> Result<Tile> {
+	let needs_encoding = force || tile.compression() != compression;
+	match brotli_level {
+		Some(level) if needs_encoding && compression == TileCompression::Brotli => {
+			let format = tile.format();
+			let blob = compress_brotli_level(&tile.into_blob(TileCompression::Uncompressed)?, level)?;
+			Ok(Tile::from_blob(blob, compression, format))
+		}
+		_ => {
+			if force {
+				tile.force_recompress(compression)?;
+			} else {
+				tile.change_compression(compression)?;
+			}
+			Ok(tile)
+		}
+	}
+}
+
 /// Integration tests verifying bbox intersection, coordinate transforms, traversal order,
 /// and compression override behavior.
 #[cfg(test)]
 mod tests {
 	use super::*;
-	use crate::{MockReader, Traversal, VersaTilesReader};
+	use crate::{CONVERSION_REPORT_VERSION, ConversionReport, MockReader, VersaTilesReader};
 	use assert_fs::NamedTempFile;
+	use std::{
+		collections::{HashMap, HashSet},
+		hash::{DefaultHasher, Hash, Hasher},
+	};
+	use versatiles_core::utils::compress;
 	use versatiles_core::{
+		Blob,
 		TileCompression::*,
 		TileFormat::{self, *},
 	};
+	use versatiles_image::{ImageBuffer, Rgb, Rgba};
 
 	fn get_mock_reader(tf: TileFormat, tc: TileCompression) -> Arc<Box<dyn TileSource>> {
 		let bbox_pyramid = TileBBoxPyramid::new_full(4);
-		let reader_metadata = TileSourceMetadata::new(tf, tc, bbox_pyramid, Traversal::ANY);
+		let reader_metadata = TileSourceMetadata::new_builder()
+			.with_format(tf)
+			.with_compression(tc)
+			.with_bbox_pyramid(bbox_pyramid)
+			.build()
+			.unwrap();
 		Arc::new(MockReader::new_mock(reader_metadata).unwrap().boxed())
 	}
 
@@ -273,7 +704,11 @@ mod tests {
 			let pyramid_convert = new_bbox([2, 3, 7, 7]);
 			let pyramid_out = new_bbox(bbox_out);
 
-			let reader_metadata = TileSourceMetadata::new(JSON, Uncompressed, pyramid_in, Traversal::ANY);
+			let reader_metadata = TileSourceMetadata::new_builder()
+				.with_format(JSON)
+				.with_compression(Uncompressed)
+				.with_bbox_pyramid(pyramid_in)
+				.build()?;
 			let reader = Arc::new(MockReader::new_mock(reader_metadata)?.boxed());
 
 			let temp_file = NamedTempFile::new("test.versatiles")?;
@@ -281,9 +716,20 @@ mod tests {
 
 			let cp = TilesConverterParameters {
 				bbox_pyramid: Some(pyramid_convert),
+				geo_bbox: None,
 				flip_y,
 				swap_xy,
 				tile_compression: None,
+				force_recompress: false,
+				brotli_level: None,
+				tile_size: None,
+				optimize_png: false,
+				quantize_png: None,
+				elide_empty_tiles: None,
+				drop_blank_tiles: false,
+				reproducible: false,
+				dry_run: false,
+				report_path: None,
 			};
 			convert_tiles_container(reader, cp, &temp_file, runtime.clone()).await?;
 
@@ -292,7 +738,7 @@ mod tests {
 			let tile_compression_out = parameters_out.tile_compression;
 			assert_eq!(parameters_out.bbox_pyramid, pyramid_out);
 
-			let bbox = pyramid_out.get_level_bbox(3);
+			let bbox = pyramid_out.get_bounding_bbox(3);
 			let mut tiles: Vec<String> = Vec::new();
 			for coord in bbox.iter_coords() {
 				let mut text = reader_out
@@ -322,13 +768,67 @@ mod tests {
 		Ok(())
 	}
 
+	#[tokio::test]
+	async fn bbox_filter() -> Result<()> {
+		let reader_metadata = TileSourceMetadata::new_builder()
+			.with_format(JSON)
+			.with_compression(Uncompressed)
+			.with_bbox_pyramid(TileBBoxPyramid::new_full(3))
+			.build()?;
+		let reader = Arc::new(MockReader::new_mock(reader_metadata)?.boxed());
+
+		let temp_file = NamedTempFile::new("berlin.versatiles")?;
+		let runtime = TilesRuntime::default();
+		let cp = TilesConverterParameters::default().with_bbox_filter([13.09, 52.33, 13.76, 52.68])?;
+		convert_tiles_container(reader, cp, &temp_file, runtime.clone()).await?;
+
+		let reader_out = VersaTilesReader::open_path(&temp_file, runtime).await?;
+		let mut coords = Vec::new();
+		for bbox in TileBBoxPyramid::new_full(3).iter_levels() {
+			for coord in bbox.iter_coords() {
+				if reader_out.get_tile(&coord).await?.is_some() {
+					coords.push(coord.as_json());
+				}
+			}
+		}
+		assert_eq!(
+			coords,
+			[
+				"{\"z\":0,\"x\":0,\"y\":0}",
+				"{\"z\":1,\"x\":1,\"y\":0}",
+				"{\"z\":2,\"x\":2,\"y\":1}",
+				"{\"z\":3,\"x\":4,\"y\":2}"
+			]
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn invalid_bbox_filter() {
+		let err = TilesConverterParameters::default()
+			.with_bbox_filter([13.76, 52.33, 13.09, 52.68])
+			.unwrap_err();
+		assert!(format!("{err:?}").contains("Invalid bbox filter"), "{err:?}");
+	}
+
 	#[test]
 	fn test_tiles_converter_parameters_new() {
 		let cp = TilesConverterParameters {
 			bbox_pyramid: Some(TileBBoxPyramid::new_full(1)),
+			geo_bbox: None,
 			flip_y: true,
 			swap_xy: true,
 			tile_compression: None,
+			force_recompress: false,
+			brotli_level: None,
+			tile_size: None,
+			optimize_png: false,
+			quantize_png: None,
+			elide_empty_tiles: None,
+			drop_blank_tiles: false,
+			reproducible: false,
+			dry_run: false,
+			report_path: None,
 		};
 
 		assert!(cp.bbox_pyramid.is_some());
@@ -343,6 +843,10 @@ mod tests {
 		assert_eq!(cp.bbox_pyramid, None);
 		assert!(!cp.flip_y);
 		assert!(!cp.swap_xy);
+		assert!(!cp.force_recompress);
+		assert_eq!(cp.brotli_level, None);
+		assert_eq!(cp.elide_empty_tiles, None);
+		assert!(!cp.dry_run);
 	}
 
 	#[test]
@@ -390,4 +894,448 @@ mod tests {
 
 		Ok(())
 	}
+
+	/// Hashes the stored bytes of all tiles of `reader` inside `pyramid`.
+	async fn tile_hashes(reader: &dyn TileSource, pyramid: &TileBBoxPyramid) -> Result<HashMap<TileCoord, u64>> {
+		let compression = reader.metadata().tile_compression;
+		let mut hashes = HashMap::new();
+		for bbox in pyramid.iter_levels() {
+			for (coord, tile) in reader.get_tile_stream(*bbox).await?.to_vec().await.unwrap() {
+				let mut hasher = DefaultHasher::new();
+				tile.into_blob(compression)?.as_slice().hash(&mut hasher);
+				hashes.insert(coord, hasher.finish());
+			}
+		}
+		Ok(hashes)
+	}
+
+	#[tokio::test]
+	async fn conversion_chain_keeps_tiles_byte_identical() -> Result<()> {
+		let runtime = TilesRuntime::default();
+		let temp_dir = assert_fs::TempDir::new()?;
+		let path_versatiles = temp_dir.path().join("berlin.versatiles");
+		let path_directory = temp_dir.path().join("berlin");
+		std::fs::create_dir(&path_directory)?;
+		let pyramid = TileBBoxPyramid::new_full(10);
+
+		let mbtiles = runtime.get_reader_from_str("../testdata/berlin.mbtiles").await?;
+		let cp = TilesConverterParameters {
+			bbox_pyramid: Some(pyramid.clone()),
+			..Default::default()
+		};
+		convert_tiles_container(mbtiles.clone(), cp, &path_versatiles, runtime.clone()).await?;
+
+		let versatiles = runtime.get_reader_from_str(path_versatiles.to_str().unwrap()).await?;
+		convert_tiles_container(
+			versatiles.clone(),
+			TilesConverterParameters::default(),
+			&path_directory,
+			runtime.clone(),
+		)
+		.await?;
+		let directory = runtime.get_reader_from_str(path_directory.to_str().unwrap()).await?;
+
+		let expected = tile_hashes(mbtiles.as_ref().as_ref(), &pyramid).await?;
+		assert!(!expected.is_empty());
+		assert_eq!(tile_hashes(versatiles.as_ref().as_ref(), &pyramid).await?, expected);
+		assert_eq!(tile_hashes(directory.as_ref().as_ref(), &pyramid).await?, expected);
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn force_recompress_reencodes_tiles() -> Result<()> {
+		let reader = get_mock_reader(MVT, Gzip);
+		let blob = reader
+			.get_tile(&TileCoord::new(2, 1, 1)?)
+			.await?
+			.unwrap()
+			.into_blob(Gzip)?;
+
+		let cp = TilesConverterParameters {
+			force_recompress: true,
+			..Default::default()
+		};
+		let tcr = TilesConvertReader::new_from_reader(reader, cp)?;
+		let tile = tcr.get_tile(&TileCoord::new(2, 1, 1)?).await?.unwrap();
+		assert_eq!(tile.compression(), Gzip);
+		assert_eq!(
+			tile.into_blob(Uncompressed)?,
+			versatiles_core::utils::decompress(blob, Gzip)?
+		);
+
+		Ok(())
+	}
+
+	#[test]
+	fn force_recompress_changes_bytes() -> Result<()> {
+		let raw = Blob::from(
+			(0..2000)
+				.map(|i| format!("{{\"i\":{i},\"v\":{}}}", i * i % 97))
+				.collect::<String>(),
+		);
+		let fast = compress_brotli_level(&raw, 1)?;
+		let small = compress_brotli_level(&raw, 11)?;
+		assert_ne!(fast, small);
+		let tile = Tile::from_blob(fast.clone(), Brotli, JSON);
+
+		// Same format and compression: the tile is copied byte for byte …
+		let copied = recompress_tile(tile.clone(), Brotli, false, Some(11))?;
+		assert_eq!(copied.into_blob(Brotli)?, fast);
+
+		// … unless recompression is forced.
+		let forced = recompress_tile(tile, Brotli, true, Some(11))?;
+		assert_eq!(forced.format(), JSON);
+		assert_eq!(forced.into_blob(Brotli)?, small);
+
+		Ok(())
+	}
+
+	#[test]
+	fn optimize_png_tile_shrinks_losslessly() -> Result<()> {
+		let image = DynamicImage::ImageRgba8(ImageBuffer::from_fn(256, 256, |x, y| {
+			Rgba([(x / 32) as u8 * 30, (y / 32) as u8 * 30, 0, 255])
+		}));
+		let fast = image.to_blob(PNG, None, Some(100))?;
+		let tile = Tile::from_blob(compress(fast.clone(), Gzip)?, Gzip, PNG);
+
+		let optimized = optimize_png_tile(tile, None)?;
+		assert_eq!(optimized.compression(), Gzip);
+		let optimized = optimized.into_blob(Uncompressed)?;
+		assert!(optimized.len() < fast.len());
+		assert_eq!(DynamicImage::from_blob(&optimized, PNG)?.to_rgba8(), image.to_rgba8());
+
+		// tiles that don't get smaller keep their bytes
+		let tile = Tile::from_blob(optimized.clone(), Uncompressed, PNG);
+		assert_eq!(optimize_png_tile(tile, None)?.into_blob(Uncompressed)?, optimized);
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn optimize_png_only_affects_png() -> Result<()> {
+		let cp = || TilesConverterParameters {
+			optimize_png: true,
+			..Default::default()
+		};
+		let tcr = TilesConvertReader::new_from_reader(get_mock_reader(PNG, Uncompressed), cp())?;
+		assert!(tcr.recompresses());
+		let tcr = TilesConvertReader::new_from_reader(get_mock_reader(MVT, Gzip), cp())?;
+		assert!(!tcr.recompresses());
+		Ok(())
+	}
+
+	#[test]
+	fn quantize_png_tile_reduces_colors() -> Result<()> {
+		let image = DynamicImage::ImageRgba8(ImageBuffer::from_fn(256, 256, |x, y| {
+			Rgba([x as u8, y as u8, ((x * y) % 251) as u8, 255])
+		}));
+		let original = image.to_blob(PNG, None, Some(0))?;
+		let tile = Tile::from_blob(compress(original.clone(), Gzip)?, Gzip, PNG);
+
+		let quantized = optimize_png_tile(tile, Some(16))?;
+		assert_eq!(quantized.compression(), Gzip);
+		let quantized = quantized.into_blob(Uncompressed)?;
+		assert!(quantized.len() < original.len());
+		let colors = DynamicImage::from_blob(&quantized, PNG)?
+			.to_rgba8()
+			.pixels()
+			.map(|p| p.0)
+			.collect::<HashSet<_>>();
+		assert!(colors.len() <= 16);
+		Ok(())
+	}
+
+	#[test]
+	fn resize_tile_changes_pixel_size() -> Result<()> {
+		let image = DynamicImage::ImageRgb8(ImageBuffer::from_fn(512, 512, |x, y| {
+			Rgb([(x / 2) as u8, (y / 2) as u8, 128])
+		}));
+		let blob = compress(image.to_blob(PNG, None, None)?, Gzip)?;
+		let tile = Tile::from_blob(blob.clone(), Gzip, PNG);
+
+		let resized = resize_tile(tile.clone(), TileSize::Size256)?;
+		assert_eq!(resized.compression(), Gzip);
+		let resized = DynamicImage::from_blob(&resized.into_blob(Uncompressed)?, PNG)?;
+		assert_eq!((resized.width(), resized.height()), (256, 256));
+
+		// tiles with the target size keep their bytes
+		assert_eq!(resize_tile(tile, TileSize::Size512)?.into_blob(Gzip)?, blob);
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn tile_size_only_affects_raster_tiles() -> Result<()> {
+		let cp = || TilesConverterParameters {
+			tile_size: Some(TileSize::Size256),
+			..Default::default()
+		};
+
+		let tcr = TilesConvertReader::new_from_reader(get_mock_reader(PNG, Uncompressed), cp())?;
+		assert!(tcr.recompresses());
+		assert_eq!(tcr.metadata().tile_size, Some(TileSize::Size256));
+
+		let tcr = TilesConvertReader::new_from_reader(get_mock_reader(MVT, Gzip), cp())?;
+		assert!(!tcr.recompresses());
+		assert_eq!(tcr.metadata().tile_size, None);
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn brotli_level() -> Result<()> {
+		let coord = TileCoord::new(2, 1, 1)?;
+		let raw = get_mock_reader(MVT, Gzip)
+			.get_tile(&coord)
+			.await?
+			.unwrap()
+			.into_blob(Uncompressed)?;
+
+		for level in [1, 11] {
+			let cp = TilesConverterParameters {
+				tile_compression: Some(Brotli),
+				brotli_level: Some(level),
+				..Default::default()
+			};
+			let tcr = TilesConvertReader::new_from_reader(get_mock_reader(MVT, Gzip), cp)?;
+			let tile = tcr.get_tile(&coord).await?.unwrap();
+			assert_eq!(tile.compression(), Brotli);
+			assert_eq!(tile.into_blob(Brotli)?, compress_brotli_level(&raw, level)?);
+
+			let bbox = TileBBox::from_min_and_max(2, 1, 1, 1, 1)?;
+			let tiles = tcr.get_tile_stream(bbox).await?.to_vec().await.unwrap();
+			assert_eq!(tiles.len(), 1);
+			assert_eq!(
+				tiles[0].1.clone().into_blob(Brotli)?,
+				compress_brotli_level(&raw, level)?
+			);
+		}
+
+		let cp = TilesConverterParameters {
+			brotli_level: Some(12),
+			..Default::default()
+		};
+		assert!(TilesConvertReader::new_from_reader(get_mock_reader(MVT, Gzip), cp).is_err());
+
+		Ok(())
+	}
+
+	/// PNG tiles at level 2 that are fully transparent in even columns, black in column 1 and a
+	/// gradient in column 3.
+	fn get_striped_reader() -> Result<Arc<Box<dyn TileSource>>> {
+		let tiles = TileBBox::from_min_and_max(2, 0, 0, 3, 3)?
+			.iter_coords()
+			.map(|coord| {
+				let image = match coord.x {
+					1 => DynamicImage::new_rgb8(16, 16),
+					3 => DynamicImage::from_fn(16, 16, |x, y| [x as u8 * 16, y as u8 * 16, 0]),
+					_ => DynamicImage::new_rgba8(16, 16),
+				};
+				Ok((
+					coord,
+					Tile::from_blob(image.to_blob(PNG, None, None)?, Uncompressed, PNG),
+				))
+			})
+			.collect::<Result<Vec<_>>>()?;
+		Ok(Arc::new(MockReader::from_tiles(tiles)?.boxed()))
+	}
+
+	#[tokio::test]
+	async fn elide_empty_tiles() -> Result<()> {
+		let cp = TilesConverterParameters {
+			elide_empty_tiles: Some(crate::DEFAULT_EMPTY_TILE_MAX_SIZE),
+			..Default::default()
+		};
+		let tcr = TilesConvertReader::new_from_reader(get_striped_reader()?, cp)?;
+		assert!(tcr.get_tile(&TileCoord::new(2, 0, 1)?).await?.is_none());
+		assert!(tcr.get_tile(&TileCoord::new(2, 1, 1)?).await?.is_some());
+
+		let bbox = TileBBox::from_min_and_max(2, 0, 0, 3, 3)?;
+		let coords = tcr.get_tile_stream(bbox).await?.to_vec().await.unwrap();
+		assert_eq!(coords.len(), 8);
+		assert!(coords.iter().all(|(coord, _)| coord.x % 2 == 1));
+
+		// kept tiles are copied byte for byte
+		let original = get_striped_reader()?
+			.get_tile(&TileCoord::new(2, 1, 1)?)
+			.await?
+			.unwrap();
+		let kept = tcr.get_tile(&TileCoord::new(2, 1, 1)?).await?.unwrap();
+		assert_eq!(kept.into_blob(Uncompressed)?, original.into_blob(Uncompressed)?);
+
+		// tiles larger than the limit are not checked
+		let cp = TilesConverterParameters {
+			elide_empty_tiles: Some(10),
+			..Default::default()
+		};
+		let tcr = TilesConvertReader::new_from_reader(get_striped_reader()?, cp)?;
+		assert_eq!(tcr.get_tile_stream(bbox).await?.to_vec().await.unwrap().len(), 16);
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn drop_blank_tiles() -> Result<()> {
+		let cp = || TilesConverterParameters {
+			drop_blank_tiles: true,
+			..Default::default()
+		};
+		// transparent and black tiles are blank, only the gradient is kept
+		let tcr = TilesConvertReader::new_from_reader(get_striped_reader()?, cp())?;
+		let bbox = TileBBox::from_min_and_max(2, 0, 0, 3, 3)?;
+		let coords = tcr.get_tile_stream(bbox).await?.to_vec().await.unwrap();
+		assert_eq!(coords.len(), 4);
+		assert!(coords.iter().all(|(coord, _)| coord.x == 3));
+		assert!(tcr.get_tile(&TileCoord::new(2, 1, 1)?).await?.is_none());
+		assert!(tcr.get_tile(&TileCoord::new(2, 3, 1)?).await?.is_some());
+		assert_eq!(
+			tcr.dropped_blank_tiles(),
+			[
+				(BlankTile::Color([0, 0, 0, 0]), 8),
+				(BlankTile::Color([0, 0, 0, 255]), 5)
+			]
+		);
+
+		// the size limit of elide_empty_tiles doesn't apply to blank tiles
+		let cp = TilesConverterParameters {
+			elide_empty_tiles: Some(10),
+			..cp()
+		};
+		let tcr = TilesConvertReader::new_from_reader(get_striped_reader()?, cp)?;
+		assert_eq!(tcr.get_tile_stream(bbox).await?.to_vec().await.unwrap().len(), 4);
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn elide_empty_tiles_shrinks_versatiles_bbox() -> Result<()> {
+		let cp = TilesConverterParameters {
+			elide_empty_tiles: Some(crate::DEFAULT_EMPTY_TILE_MAX_SIZE),
+			bbox_pyramid: Some(new_level_bbox(2, [0, 0, 2, 3])),
+			..Default::default()
+		};
+		let temp_file = NamedTempFile::new("elided.versatiles")?;
+		let runtime = TilesRuntime::default();
+		convert_tiles_container(get_striped_reader()?, cp, &temp_file, runtime.clone()).await?;
+
+		let reader = VersaTilesReader::open_path(&temp_file, runtime).await?;
+		assert_eq!(reader.metadata().bbox_pyramid, new_level_bbox(2, [1, 0, 1, 3]));
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn reproducible_output() -> Result<()> {
+		let temp_dir = assert_fs::TempDir::new()?;
+		let runtime = TilesRuntime::default();
+
+		for extension in ["tar", "versatiles"] {
+			let mut hashes = Vec::new();
+			for run in 0..2 {
+				let path = temp_dir.path().join(format!("run{run}.{extension}"));
+				let cp = TilesConverterParameters {
+					tile_compression: Some(Brotli),
+					reproducible: true,
+					..Default::default()
+				};
+				convert_tiles_container(get_mock_reader(MVT, Gzip), cp, &path, runtime.clone()).await?;
+
+				let mut hasher = DefaultHasher::new();
+				std::fs::read(&path)?.hash(&mut hasher);
+				hashes.push(hasher.finish());
+			}
+			assert_eq!(hashes[0], hashes[1], "{extension} output differs");
+		}
+		Ok(())
+	}
+
+	#[test]
+	fn reproducible_pins_brotli_level() -> Result<()> {
+		let cp = TilesConverterParameters {
+			reproducible: true,
+			..Default::default()
+		};
+		let tcr = TilesConvertReader::new_from_reader(get_mock_reader(MVT, Gzip), cp)?;
+		assert_eq!(tcr.converter_parameters.brotli_level, Some(BROTLI_DEFAULT_LEVEL));
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn report_of_conversion() -> Result<()> {
+		let temp_dir = assert_fs::TempDir::new()?;
+		let path = temp_dir.path().join("out.tar");
+		let report_path = temp_dir.path().join("report.json");
+		let cp = TilesConverterParameters {
+			tile_compression: Some(Brotli),
+			report_path: Some(report_path.clone()),
+			..Default::default()
+		};
+		convert_tiles_container(get_mock_reader(MVT, Gzip), cp, &path, TilesRuntime::default()).await?;
+
+		let report = ConversionReport::read(&report_path)?;
+		let pyramid = TileBBoxPyramid::new_full(4);
+		let expected = (0..=4)
+			.map(|level| (level, pyramid.count_level_tiles(level)))
+			.collect::<BTreeMap<_, _>>();
+		assert_eq!(report.report_version, CONVERSION_REPORT_VERSION);
+		assert_eq!(report.output, path);
+		assert_eq!(report.tiles_per_level, expected);
+		assert_eq!(report.source.tile_compression, "gzip");
+		assert_eq!(report.target.unwrap().tile_compression, "brotli");
+		assert_eq!(report.bytes_written, Some(std::fs::metadata(&path)?.len()));
+		assert!(report.success);
+		assert_eq!(report.error, None);
+		Ok(())
+	}
+
+	/// JSON tiles on levels 0 to 4. The tiles on level 3 are truncated gzip streams.
+	fn get_broken_reader() -> Result<Arc<Box<dyn TileSource>>> {
+		let tiles = TileBBoxPyramid::new_full(4)
+			.iter_levels()
+			.flat_map(|bbox| bbox.iter_coords().collect::<Vec<_>>())
+			.map(|coord| {
+				let tile = if coord.level == 3 {
+					Tile::from_blob(Blob::from(b"\x1f\x8b\x08\x00broken".to_vec()), Gzip, JSON)
+				} else {
+					Tile::from_blob(Blob::from(coord.as_json()), Uncompressed, JSON)
+				};
+				(coord, tile)
+			})
+			.collect();
+		let metadata = TileSourceMetadata::new_builder()
+			.with_format(JSON)
+			.with_compression(Uncompressed)
+			.with_bbox_pyramid(TileBBoxPyramid::new_full(4))
+			.build()?;
+		Ok(Arc::new(MockReader::from_tiles(tiles)?.with_metadata(metadata).boxed()))
+	}
+
+	#[tokio::test]
+	async fn report_of_failed_conversion() -> Result<()> {
+		let temp_dir = assert_fs::TempDir::new()?;
+		let report_path = temp_dir.path().join("report.json");
+		let cp = TilesConverterParameters {
+			report_path: Some(report_path.clone()),
+			..Default::default()
+		};
+		let path = temp_dir.path().join("out.tar");
+		let err = convert_tiles_container(get_broken_reader()?, cp, &path, TilesRuntime::default())
+			.await
+			.unwrap_err();
+
+		// the report is written anyway and contains everything until the error
+		let report = ConversionReport::read(&report_path)?;
+		assert!(!report.success);
+		let chain = report.error.unwrap();
+		assert_eq!(chain.len(), err.chain().count());
+		assert_eq!(chain[0], err.to_string());
+		for level in 0..=2 {
+			assert_eq!(report.tiles_per_level[&level], 4u64.pow(u32::from(level)));
+		}
+		assert!(!report.tiles_per_level.contains_key(&4));
+		assert_eq!(report.target.unwrap().tile_count, 341);
+		Ok(())
+	}
+
+	fn new_level_bbox(level: u8, b: [u32; 4]) -> TileBBoxPyramid {
+		let mut pyramid = TileBBoxPyramid::new_empty();
+		pyramid.include_bbox(&TileBBox::from_min_and_max(level, b[0], b[1], b[2], b[3]).unwrap());
+		pyramid
+	}
 }
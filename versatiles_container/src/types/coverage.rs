@@ -0,0 +1,225 @@
+//! Coverage bitmaps, showing which tiles of a zoom level exist.
+//!
+//! [`TileSource::coverage`] defaults to [`tile_coverage`], which checks every coordinate in the
+//! bounding box of a level with [`TileSource::has_tile`]. Containers with a tile index override it:
+//! `.versatiles` reads the coverage directly from its block and tile indices. The bitmap has one bit per tile, row by row from north to south: the tile at
+//! `(x_min + column, y_min + row)` is bit `i % 8` (least significant bit first) of byte `i / 8`,
+//! with `i = row * width + column`.
+//!
+//! # Example
+//! ```rust
+//! use versatiles_container::*;
+//!
+//! #[tokio::main]
+//! async fn main() -> anyhow::Result<()> {
+//!     let reader = MockReader::new_mock_profile(MockReaderProfile::Png)?;
+//!     let coverage = reader.coverage(2).await?;
+//!     assert_eq!((coverage.width, coverage.height), (3, 3));
+//!     assert_eq!(coverage.count(), 9);
+//!     println!("{}", coverage.as_json_value().stringify());
+//!     Ok(())
+//! }
+//! ```
+
+use crate::TileSource;
+use anyhow::{Result, ensure};
+use versatiles_core::{
+	TileCoord,
+	json::{JsonObject, JsonValue},
+};
+
+/// Largest number of tiles a coverage may have. Its bitmap takes 32 MiB, as much as a full
+/// level 14. [`tile_coverage`] checks every tile on its own, so larger coverages would also take very long.
+const MAX_COVERAGE_TILES: u64 = 1 << 28;
+
+/// Which tiles of a zoom level exist, see the module documentation for the bit layout.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TileCoverage {
+	/// Zoom level.
+	pub level: u8,
+	/// Column of the first bit.
+	pub x_min: u32,
+	/// Row of the first bit.
+	pub y_min: u32,
+	/// Number of tiles per row.
+	pub width: u32,
+	/// Number of rows.
+	pub height: u32,
+	/// One bit per tile, `width * height` bits padded to full bytes.
+	pub bitmap: Vec<u8>,
+}
+
+impl TileCoverage {
+	/// Returns `true` if the tile at `coord` exists.
+	#[must_use]
+	pub fn contains(&self, coord: &TileCoord) -> bool {
+		match self.bit_index(coord) {
+			Some(index) => self.bitmap[(index / 8) as usize] & (1 << (index % 8)) != 0,
+			None => false,
+		}
+	}
+
+	/// Number of existing tiles.
+	#[must_use]
+	pub fn count(&self) -> u64 {
+		self.bitmap.iter().map(|byte| u64::from(byte.count_ones())).sum()
+	}
+
+	/// Returns everything except the bitmap as JSON, for consumers of the raw bitmap.
+	#[must_use]
+	pub fn as_json_value(&self) -> JsonValue {
+		let mut object = JsonObject::new();
+		object.set("level", self.level);
+		object.set("x_min", self.x_min);
+		object.set("y_min", self.y_min);
+		object.set("width", self.width);
+		object.set("height", self.height);
+		object.set("count", self.count());
+		JsonValue::from(object)
+	}
+
+	fn bit_index(&self, coord: &TileCoord) -> Option<u64> {
+		if coord.level != self.level {
+			return None;
+		}
+		let column = coord.x.checked_sub(self.x_min).filter(|c| *c < self.width)?;
+		let row = coord.y.checked_sub(self.y_min).filter(|r| *r < self.height)?;
+		Some(u64::from(row) * u64::from(self.width) + u64::from(column))
+	}
+
+	/// Marks the tile at `coord` as existing. Coordinates outside the coverage are ignored.
+	pub(crate) fn set(&mut self, coord: &TileCoord) {
+		if let Some(index) = self.bit_index(coord) {
+			self.bitmap[(index / 8) as usize] |= 1 << (index % 8);
+		}
+	}
+
+	/// Returns a coverage of zoom `level` of `source` without any tiles, spanning the bounding
+	/// box of that level.
+	///
+	/// # Errors
+	/// Returns an error if `level` is greater than 31 or the bounding box of the level contains
+	/// more than `MAX_COVERAGE_TILES` tiles.
+	pub(crate) fn new_empty<S: TileSource + ?Sized>(source: &S, level: u8) -> Result<TileCoverage> {
+		ensure!(level <= 31, "level ({level}) must be <= 31");
+		let bbox = source.metadata().bbox_pyramid.get_bounding_bbox(level);
+		if bbox.is_empty() {
+			return Ok(TileCoverage {
+				level,
+				..Default::default()
+			});
+		}
+
+		let count = bbox.count_tiles();
+		ensure!(
+			count <= MAX_COVERAGE_TILES,
+			"level {level} has {count} tiles, but a coverage is limited to {MAX_COVERAGE_TILES} tiles"
+		);
+
+		Ok(TileCoverage {
+			level,
+			x_min: bbox.x_min()?,
+			y_min: bbox.y_min()?,
+			width: bbox.width(),
+			height: bbox.height(),
+			bitmap: vec![0; count.div_ceil(8) as usize],
+		})
+	}
+}
+
+/// Returns the coverage of zoom `level` of `source`, limited to the bounding box of that level.
+///
+/// # Errors
+/// Returns an error if `level` is greater than 31, the bounding box of the level contains more
+/// than `MAX_COVERAGE_TILES` tiles, or checking a tile fails.
+pub async fn tile_coverage<S: TileSource + ?Sized>(source: &S, level: u8) -> Result<TileCoverage> {
+	let mut coverage = TileCoverage::new_empty(source, level)?;
+	// Coordinates are built by hand, because the iterator of the bbox isn't `Send`.
+	for y in coverage.y_min..coverage.y_min + coverage.height {
+		for x in coverage.x_min..coverage.x_min + coverage.width {
+			let coord = TileCoord::new(level, x, y)?;
+			if source.has_tile(&coord).await? {
+				coverage.set(&coord);
+			}
+		}
+	}
+	Ok(coverage)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{MockReader, Tile, TileSourceMetadata};
+	use versatiles_core::{Blob, TileBBox, TileBBoxPyramid, TileCompression, TileFormat};
+
+	/// Contains the tiles on the diagonal `x == y` of a bbox at level 4.
+	fn diagonal_reader() -> Result<MockReader> {
+		let bbox = TileBBox::from_min_and_max(4, 2, 3, 6, 7)?;
+		let tiles = bbox
+			.iter_coords()
+			.filter(|coord| coord.x == coord.y)
+			.map(|coord| {
+				(
+					coord,
+					Tile::from_blob(Blob::from("tile"), TileCompression::Uncompressed, TileFormat::PNG),
+				)
+			})
+			.collect();
+		let mut bbox_pyramid = TileBBoxPyramid::new_empty();
+		bbox_pyramid.include_bbox(&bbox);
+		let metadata = TileSourceMetadata::new_builder()
+			.with_format(TileFormat::PNG)
+			.with_compression(TileCompression::Uncompressed)
+			.with_bbox_pyramid(bbox_pyramid)
+			.build()?;
+		Ok(MockReader::from_tiles(tiles)?.with_metadata(metadata))
+	}
+
+	#[tokio::test]
+	async fn diagonal() -> Result<()> {
+		let coverage = tile_coverage(&diagonal_reader()?, 4).await?;
+		assert_eq!(
+			(coverage.x_min, coverage.y_min, coverage.width, coverage.height),
+			(2, 3, 5, 5)
+		);
+		// diagonal tiles (3,3) … (6,6) are the bits 1, 7, 13 and 19
+		assert_eq!(coverage.bitmap, [0b1000_0010, 0b0010_0000, 0b0000_1000, 0b0000_0000]);
+		assert_eq!(coverage.count(), 4);
+		assert!(coverage.contains(&TileCoord::new(4, 5, 5)?));
+		assert!(!coverage.contains(&TileCoord::new(4, 5, 6)?));
+		assert!(!coverage.contains(&TileCoord::new(4, 1, 1)?));
+		assert!(!coverage.contains(&TileCoord::new(3, 5, 5)?));
+		assert_eq!(
+			coverage.as_json_value().stringify(),
+			r#"{"count":4,"height":5,"level":4,"width":5,"x_min":2,"y_min":3}"#
+		);
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn empty_level() -> Result<()> {
+		let coverage = tile_coverage(&diagonal_reader()?, 3).await?;
+		assert_eq!(coverage.level, 3);
+		assert_eq!((coverage.width, coverage.height, coverage.count()), (0, 0, 0));
+		assert!(coverage.bitmap.is_empty());
+
+		assert!(tile_coverage(&diagonal_reader()?, 32).await.is_err());
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn too_many_tiles() -> Result<()> {
+		let metadata = TileSourceMetadata::new_builder()
+			.with_format(TileFormat::MVT)
+			.with_compression(TileCompression::Uncompressed)
+			.with_bbox_pyramid(TileBBoxPyramid::new_full(15))
+			.build()?;
+		let reader = crate::MockReader::new_mock(metadata)?;
+		let error = tile_coverage(&reader, 15).await.unwrap_err();
+		assert_eq!(
+			error.to_string(),
+			"level 15 has 1073741824 tiles, but a coverage is limited to 268435456 tiles"
+		);
+		Ok(())
+	}
+}
@@ -0,0 +1,150 @@
+//! Detection of empty tiles, which can be dropped instead of being stored.
+//!
+//! A tile is empty if it can't show anything:
+//! * raster tiles whose pixels are all fully transparent,
+//! * vector tiles without any feature in any layer.
+//!
+//! Uniformly coloured, opaque raster tiles (e.g. plain ocean) are not empty, because clients
+//! render them visibly. Other formats are never considered empty.
+//!
+//! [`blank_tile`] is less strict: it also reports raster tiles with a single uniform color and
+//! returns that color, so the tiles can be dropped and drawn as a background instead.
+
+use anyhow::Result;
+use std::fmt::{Display, Formatter};
+use versatiles_core::{Blob, TileFormat};
+use versatiles_derive::context;
+use versatiles_geometry::vector_tile::VectorTile;
+use versatiles_image::{DynamicImage, DynamicImageTraitConvert, DynamicImageTraitInfo};
+
+/// A tile that shows at most a single color, see [`blank_tile`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum BlankTile {
+	/// A raster tile whose pixels all have this RGBA color. Fully transparent tiles are `[0, 0, 0, 0]`.
+	Color([u8; 4]),
+	/// A vector tile without any feature in any layer.
+	Featureless,
+}
+
+impl Display for BlankTile {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			BlankTile::Color([r, g, b, a]) => write!(f, "#{r:02x}{g:02x}{b:02x}{a:02x}"),
+			BlankTile::Featureless => write!(f, "featureless"),
+		}
+	}
+}
+
+/// Default size limit in bytes for [`is_empty_tile`] checks during conversion.
+///
+/// Empty tiles compress very well, so larger tiles are not worth decoding.
+pub const DEFAULT_EMPTY_TILE_MAX_SIZE: u64 = 2048;
+
+/// Returns `true` if the uncompressed `blob` of a tile in `format` is empty, see the module
+/// documentation.
+///
+/// # Errors
+/// Returns an error if the blob can't be decoded.
+#[context("checking whether a {format} tile ({} bytes) is empty", blob.len())]
+pub fn is_empty_tile(format: TileFormat, blob: &Blob) -> Result<bool> {
+	if format.is_raster() {
+		Ok(DynamicImage::from_blob(blob, format)?.is_empty())
+	} else if format == TileFormat::MVT {
+		Ok(VectorTile::from_blob(blob)?.is_empty())
+	} else {
+		Ok(false)
+	}
+}
+
+/// Checks whether the uncompressed `blob` of a tile in `format` is blank.
+///
+/// Returns the color of raster tiles with a single uniform color (including fully transparent
+/// tiles), [`BlankTile::Featureless`] for vector tiles without features, and `None` otherwise.
+///
+/// # Errors
+/// Returns an error if the blob can't be decoded.
+#[context("checking whether a {format} tile ({} bytes) is blank", blob.len())]
+pub fn blank_tile(format: TileFormat, blob: &Blob) -> Result<Option<BlankTile>> {
+	if format.is_raster() {
+		let image = DynamicImage::from_blob(blob, format)?;
+		Ok(image.uniform_color().map(|color| BlankTile::Color(color.0)))
+	} else if format == TileFormat::MVT {
+		Ok(VectorTile::from_blob(blob)?
+			.is_empty()
+			.then_some(BlankTile::Featureless))
+	} else {
+		Ok(None)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{MOCK_BYTES_PBF, MOCK_BYTES_PNG};
+	use versatiles_geometry::vector_tile::VectorTileLayer;
+
+	#[test]
+	fn raster() -> Result<()> {
+		let transparent = DynamicImage::new_rgba8(256, 256).to_blob(TileFormat::PNG, None, None)?;
+		assert!(is_empty_tile(TileFormat::PNG, &transparent)?);
+
+		let opaque = DynamicImage::new_rgb8(256, 256).to_blob(TileFormat::PNG, None, None)?;
+		assert!(!is_empty_tile(TileFormat::PNG, &opaque)?);
+		assert!(!is_empty_tile(TileFormat::PNG, &Blob::from(MOCK_BYTES_PNG.to_vec()))?);
+
+		assert!(is_empty_tile(TileFormat::PNG, &Blob::from("no image")).is_err());
+		Ok(())
+	}
+
+	#[test]
+	fn vector() -> Result<()> {
+		let featureless = VectorTile::new(vec![VectorTileLayer::new_standard("water")]).to_blob()?;
+		assert!(is_empty_tile(TileFormat::MVT, &featureless)?);
+		assert!(is_empty_tile(TileFormat::MVT, &Blob::new_empty())?);
+		assert!(!is_empty_tile(TileFormat::MVT, &Blob::from(MOCK_BYTES_PBF.to_vec()))?);
+		Ok(())
+	}
+
+	#[test]
+	fn other_formats() -> Result<()> {
+		assert!(!is_empty_tile(TileFormat::JSON, &Blob::from("{}"))?);
+		assert!(!is_empty_tile(TileFormat::BIN, &Blob::new_empty())?);
+		assert_eq!(blank_tile(TileFormat::JSON, &Blob::from("{}"))?, None);
+		Ok(())
+	}
+
+	#[test]
+	fn blank_raster() -> Result<()> {
+		let blank =
+			|image: DynamicImage, format: TileFormat| blank_tile(format, &image.to_blob(format, Some(100), None)?);
+
+		let ocean = DynamicImage::from_fn(256, 256, |_, _| [170, 211, 223]);
+		assert_eq!(
+			blank(ocean.clone(), TileFormat::PNG)?,
+			Some(BlankTile::Color([170, 211, 223, 255]))
+		);
+		assert_eq!(
+			blank(ocean, TileFormat::WEBP)?,
+			Some(BlankTile::Color([170, 211, 223, 255]))
+		);
+
+		let transparent = DynamicImage::from_fn(256, 256, |x, _| [x as u8, 0, 0, 0]);
+		assert_eq!(
+			blank(transparent, TileFormat::PNG)?,
+			Some(BlankTile::Color([0, 0, 0, 0]))
+		);
+
+		let gradient = DynamicImage::from_fn(256, 256, |x, y| [x as u8, y as u8, 0]);
+		assert_eq!(blank(gradient, TileFormat::PNG)?, None);
+		assert_eq!(BlankTile::Color([170, 211, 223, 255]).to_string(), "#aad3dfff");
+		Ok(())
+	}
+
+	#[test]
+	fn blank_vector() -> Result<()> {
+		let featureless = VectorTile::new(vec![VectorTileLayer::new_standard("water")]).to_blob()?;
+		assert_eq!(blank_tile(TileFormat::MVT, &featureless)?, Some(BlankTile::Featureless));
+		assert_eq!(blank_tile(TileFormat::MVT, &Blob::from(MOCK_BYTES_PBF.to_vec()))?, None);
+		Ok(())
+	}
+}
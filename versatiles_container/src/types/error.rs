@@ -0,0 +1,110 @@
+//! Typed errors of the container layer.
+//!
+//! [`TileSource`](crate::TileSource) and the readers return `anyhow::Result`, and that stays
+//! so. A few errors that library users may want to handle programmatically are created as
+//! [`ContainerError`] and travel inside the `anyhow::Error`, so `?` conversions and added
+//! context keep working. [`ContainerError::find`] is the supported way to get the typed error
+//! back; it searches the whole chain, so it also finds errors that were wrapped in context.
+//!
+//! Only these errors are typed: damaged VersaTiles headers and indices
+//! ([`ContainerError::Corrupt`]), unsupported formats and unknown pipeline operations
+//! ([`ContainerError::UnsupportedFormat`]), out-of-range tile coordinates
+//! ([`ContainerError::InvalidCoord`]) and unparsable metadata or pipelines
+//! ([`ContainerError::Parse`]). All other errors, e.g. I/O errors, are plain `anyhow` errors.
+//!
+//! # Example
+//! ```rust
+//! use versatiles_container::ContainerError;
+//! use versatiles_core::TileCoord;
+//!
+//! fn check(coord: &TileCoord) -> anyhow::Result<()> {
+//!     ContainerError::ensure_valid_coord(coord)?;
+//!     Ok(())
+//! }
+//!
+//! let coord = TileCoord { level: 2, x: 7, y: 0 };
+//! let err = check(&coord).unwrap_err();
+//! assert!(matches!(ContainerError::find(&err), Some(ContainerError::InvalidCoord(_))));
+//! ```
+
+use std::fmt::{self, Display};
+use versatiles_core::TileCoord;
+
+/// Errors of the container layer that callers can match on, see [`ContainerError::find`].
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum ContainerError {
+	/// The data is damaged: `offset` is the byte position in the container where the problem was found.
+	Corrupt { offset: u64, detail: String },
+	/// The tile format, compression or container variant is not supported.
+	UnsupportedFormat(String),
+	/// The tile coordinate is outside the valid range of its zoom level.
+	InvalidCoord(TileCoord),
+	/// A value could not be parsed: `what` names the value, `context` describes the problem.
+	Parse { what: String, context: String },
+}
+
+impl ContainerError {
+	/// Returns the first `ContainerError` in the chain of `err`, if any.
+	#[must_use]
+	pub fn find(err: &anyhow::Error) -> Option<&ContainerError> {
+		err.chain().find_map(|cause| cause.downcast_ref::<ContainerError>())
+	}
+
+	/// Returns [`ContainerError::InvalidCoord`] if `x` or `y` of `coord` exceed its zoom level.
+	pub fn ensure_valid_coord(coord: &TileCoord) -> Result<(), ContainerError> {
+		match TileCoord::new(coord.level, coord.x, coord.y) {
+			Ok(_) => Ok(()),
+			Err(_) => Err(ContainerError::InvalidCoord(*coord)),
+		}
+	}
+}
+
+impl Display for ContainerError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			ContainerError::Corrupt { offset, detail } => write!(f, "corrupt data at offset {offset}: {detail}"),
+			ContainerError::UnsupportedFormat(format) => write!(f, "unsupported format: {format}"),
+			ContainerError::InvalidCoord(coord) => write!(f, "invalid tile coordinate: {coord:?}"),
+			ContainerError::Parse { what, context } => write!(f, "failed to parse {what}: {context}"),
+		}
+	}
+}
+
+impl std::error::Error for ContainerError {}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Context;
+
+	#[test]
+	fn find_survives_context() {
+		let result: anyhow::Result<()> = Err(ContainerError::UnsupportedFormat(String::from("foo")).into());
+		let err = result.context("outer").context("outermost").unwrap_err();
+		assert!(matches!(
+			ContainerError::find(&err),
+			Some(ContainerError::UnsupportedFormat(format)) if format == "foo"
+		));
+		assert!(ContainerError::find(&anyhow::anyhow!("plain")).is_none());
+	}
+
+	#[test]
+	fn ensure_valid_coord() {
+		assert!(ContainerError::ensure_valid_coord(&TileCoord::new(3, 7, 7).unwrap()).is_ok());
+		let coord = TileCoord { level: 3, x: 8, y: 0 };
+		assert!(matches!(
+			ContainerError::ensure_valid_coord(&coord),
+			Err(ContainerError::InvalidCoord(c)) if c == coord
+		));
+	}
+
+	#[test]
+	fn display() {
+		let err = ContainerError::Corrupt {
+			offset: 12,
+			detail: String::from("bad magic"),
+		};
+		assert_eq!(err.to_string(), "corrupt data at offset 12: bad magic");
+	}
+}
@@ -0,0 +1,254 @@
+//! Extraction of a list of tiles, e.g. "just these 200 tiles" for a bug report.
+//!
+//! [`extract_tiles`] reads the given coordinates from a [`TileSource`] and writes the tiles that
+//! exist with [`convert_tiles_container`], so the output can be anything the registry can write,
+//! e.g. a directory or a `.tar` file. The output keeps the format, compression and TileJSON of the
+//! source. Next to it, a manifest `<output>.manifest.json` lists the number of requested, found and
+//! missing tiles and all missing coordinates.
+//!
+//! Coordinates can be read from a text file with [`parse_coord_list`], one `z/x/y` per line.
+//!
+//! # Example
+//! ```rust
+//! use versatiles_container::*;
+//!
+//! #[tokio::main]
+//! async fn main() -> anyhow::Result<()> {
+//!     let reader = MockReader::new_mock_profile(MockReaderProfile::Png)?.boxed();
+//!     let coords = parse_coord_list("4/3/5\n4/3/6\n")?;
+//!     let output = std::env::temp_dir().join("extract-example.tar");
+//!     let report = extract_tiles(reader.into(), &coords, &output, TilesRuntime::default()).await?;
+//!     assert_eq!(report.found, 2);
+//!     Ok(())
+//! }
+//! ```
+
+use crate::{
+	SourceType, Tile, TileSource, TileSourceMetadata, TilesConverterParameters, TilesRuntime, convert_tiles_container,
+};
+use anyhow::{Context, Result, ensure};
+use async_trait::async_trait;
+use std::{
+	collections::{HashMap, HashSet},
+	path::{Path, PathBuf},
+	sync::Arc,
+};
+use versatiles_core::{
+	TileBBox, TileBBoxPyramid, TileCoord, TileJSON, TileStream,
+	json::{JsonObject, JsonValue},
+};
+use versatiles_derive::context;
+
+/// Result of [`extract_tiles`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ExtractReport {
+	/// Number of distinct requested coordinates.
+	pub requested: usize,
+	/// Number of tiles that were found and written.
+	pub found: usize,
+	/// Requested coordinates that the source doesn't have, in the requested order.
+	pub missing: Vec<TileCoord>,
+}
+
+impl ExtractReport {
+	/// Returns the report as JSON, with each missing coordinate as `"z/x/y"`.
+	#[must_use]
+	pub fn as_json_value(&self) -> JsonValue {
+		let missing = self
+			.missing
+			.iter()
+			.map(|c| format!("{}/{}/{}", c.level, c.x, c.y))
+			.collect::<Vec<_>>();
+
+		let mut object = JsonObject::new();
+		object.set("requested", self.requested as u64);
+		object.set("found", self.found as u64);
+		object.set("missing_count", self.missing.len() as u64);
+		object.set("missing", missing);
+		JsonValue::from(object)
+	}
+}
+
+/// Parses a list of tile coordinates, one `z/x/y` per line.
+///
+/// Empty lines and lines starting with `#` are ignored.
+///
+/// # Errors
+/// Returns an error with the line number if a line is not a valid coordinate.
+pub fn parse_coord_list(text: &str) -> Result<Vec<TileCoord>> {
+	let mut coords = Vec::new();
+	for (index, line) in text.lines().enumerate() {
+		let line = line.trim();
+		if line.is_empty() || line.starts_with('#') {
+			continue;
+		}
+		let coord =
+			parse_coord(line).with_context(|| format!("invalid tile coordinate in line {}: '{line}'", index + 1))?;
+		coords.push(coord);
+	}
+	Ok(coords)
+}
+
+fn parse_coord(text: &str) -> Result<TileCoord> {
+	let parts = text.split('/').map(str::trim).collect::<Vec<_>>();
+	ensure!(parts.len() == 3, "expected 'z/x/y'");
+	TileCoord::new(parts[0].parse()?, parts[1].parse()?, parts[2].parse()?)
+}
+
+/// Returns the path of the manifest that [`extract_tiles`] writes next to `output`.
+#[must_use]
+pub fn extract_manifest_path(output: &Path) -> PathBuf {
+	let mut name = output.file_name().unwrap_or_default().to_os_string();
+	name.push(".manifest.json");
+	output.with_file_name(name)
+}
+
+/// Writes the tiles at `coords` that exist in `reader` to `output` and a manifest next to it,
+/// see the module documentation. Duplicate coordinates are extracted once.
+///
+/// # Errors
+/// Returns an error if reading a tile or writing the output fails.
+#[context("extracting {} tiles to {output:?}", coords.len())]
+pub async fn extract_tiles(
+	reader: Arc<Box<dyn TileSource>>,
+	coords: &[TileCoord],
+	output: &Path,
+	runtime: TilesRuntime,
+) -> Result<ExtractReport> {
+	let mut seen = HashSet::new();
+	let mut report = ExtractReport::default();
+	let mut tiles = HashMap::new();
+
+	// there is no bulk read for arbitrary coordinates, so every tile is fetched on its own
+	for coord in coords.iter().filter(|coord| seen.insert(**coord)) {
+		report.requested += 1;
+		match reader.get_tile(coord).await? {
+			Some(tile) => {
+				tiles.insert(*coord, tile);
+			}
+			None => report.missing.push(*coord),
+		}
+	}
+	report.found = tiles.len();
+
+	let extracted = ExtractedTiles::new(reader.as_ref().as_ref(), tiles);
+	convert_tiles_container(
+		Arc::new(extracted.boxed()),
+		TilesConverterParameters::default(),
+		output,
+		runtime,
+	)
+	.await?;
+
+	std::fs::write(extract_manifest_path(output), report.as_json_value().stringify())?;
+	Ok(report)
+}
+
+/// In-memory source of the extracted tiles, with the metadata of the original source.
+#[derive(Debug)]
+struct ExtractedTiles {
+	metadata: TileSourceMetadata,
+	tilejson: TileJSON,
+	source_type: Arc<SourceType>,
+	tiles: HashMap<TileCoord, Tile>,
+}
+
+impl ExtractedTiles {
+	fn new(source: &dyn TileSource, tiles: HashMap<TileCoord, Tile>) -> ExtractedTiles {
+		let mut bbox_pyramid = TileBBoxPyramid::new_empty();
+		for coord in tiles.keys() {
+			bbox_pyramid.include_coord(coord);
+		}
+		let mut metadata = source.metadata().clone();
+		metadata.bbox_pyramid = bbox_pyramid;
+
+		ExtractedTiles {
+			metadata,
+			tilejson: source.tilejson().clone(),
+			source_type: SourceType::new_processor("extract", source.source_type()),
+			tiles,
+		}
+	}
+}
+
+#[async_trait]
+impl TileSource for ExtractedTiles {
+	fn source_type(&self) -> Arc<SourceType> {
+		self.source_type.clone()
+	}
+
+	fn metadata(&self) -> &TileSourceMetadata {
+		&self.metadata
+	}
+
+	fn tilejson(&self) -> &TileJSON {
+		&self.tilejson
+	}
+
+	async fn get_tile(&self, coord: &TileCoord) -> Result<Option<Tile>> {
+		Ok(self.tiles.get(coord).cloned())
+	}
+
+	async fn get_tile_stream(&self, bbox: TileBBox) -> Result<TileStream<Tile>> {
+		Ok(TileStream::from_vec(
+			self
+				.tiles
+				.iter()
+				.filter(|(coord, _)| bbox.contains(coord))
+				.map(|(coord, tile)| (*coord, tile.clone()))
+				.collect(),
+		))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{MOCK_BYTES_PNG, MockReader, MockReaderProfile};
+	use assert_fs::TempDir;
+	use versatiles_core::TileCompression;
+
+	#[test]
+	fn parse_coords() -> Result<()> {
+		let coords = parse_coord_list("# bug 123\n4/3/5\n\n 5 / 10 / 11 \n")?;
+		assert_eq!(coords, [TileCoord::new(4, 3, 5)?, TileCoord::new(5, 10, 11)?]);
+
+		let err = parse_coord_list("4/3/5\n4/3\n").unwrap_err();
+		assert_eq!(err.to_string(), "invalid tile coordinate in line 2: '4/3'");
+		assert!(parse_coord_list("2/4/0").is_err());
+		assert!(parse_coord_list("a/b/c").is_err());
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn extract_from_mock_reader() -> Result<()> {
+		let reader: Arc<Box<dyn TileSource>> = Arc::new(MockReader::new_mock_profile(MockReaderProfile::Png)?.boxed());
+		// the mock reader has no tiles at level 7
+		let coords = parse_coord_list("2/0/1\n3/4/6\n4/15/0\n6/63/63\n7/1/1\n4/15/0\n")?;
+
+		let temp_dir = TempDir::new()?;
+		let output = temp_dir.path().join("extract.tar");
+		let runtime = TilesRuntime::default();
+		let report = extract_tiles(reader, &coords, &output, runtime.clone()).await?;
+
+		assert_eq!(report.requested, 5);
+		assert_eq!(report.found, 4);
+		assert_eq!(report.missing, [TileCoord::new(7, 1, 1)?]);
+
+		let manifest = std::fs::read_to_string(extract_manifest_path(&output))?;
+		assert_eq!(
+			manifest,
+			r#"{"found":4,"missing":["7/1/1"],"missing_count":1,"requested":5}"#
+		);
+
+		let extracted = runtime.get_reader_from_str(output.to_str().unwrap()).await?;
+		assert_eq!(extracted.metadata().bbox_pyramid.count_tiles(), 4);
+		let tile = extracted.get_tile(&TileCoord::new(3, 4, 6)?).await?.unwrap();
+		assert_eq!(
+			tile.into_blob(TileCompression::Uncompressed)?.as_slice(),
+			MOCK_BYTES_PNG
+		);
+		assert!(extracted.get_tile(&TileCoord::new(3, 4, 5)?).await?.is_none());
+		Ok(())
+	}
+}
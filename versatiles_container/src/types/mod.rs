@@ -1,23 +1,39 @@
+mod compare;
 mod container_registry;
+mod conversion_plan;
+mod conversion_report;
 mod converter;
+mod coverage;
 mod data_location;
 mod data_source;
+mod empty_tile;
+mod error;
+mod extract;
 mod processor;
 mod tile;
 mod tile_content;
 mod tile_source_metadata;
 mod tile_source_trait;
 mod tile_source_type;
+mod verify;
 mod writer;
 
+pub use compare::*;
 pub use container_registry::*;
+pub use conversion_plan::*;
+pub use conversion_report::*;
 pub use converter::*;
+pub use coverage::*;
 pub use data_location::*;
 pub use data_source::*;
+pub use empty_tile::*;
+pub use error::*;
+pub use extract::*;
 pub use processor::*;
 pub use tile::*;
 pub use tile_content::*;
 pub use tile_source_metadata::*;
 pub use tile_source_trait::*;
 pub use tile_source_type::*;
+pub use verify::*;
 pub use writer::*;
@@ -175,12 +175,13 @@ mod tests {
 	use versatiles_core::{TileBBoxPyramid, TileCompression, TileFormat};
 
 	fn create_mock_source() -> Box<dyn TileSource> {
-		let metadata = TileSourceMetadata::new(
-			TileFormat::PNG,
-			TileCompression::Uncompressed,
-			TileBBoxPyramid::new_full(10),
-			Traversal::new_any(),
-		);
+		let metadata = TileSourceMetadata::new_builder()
+			.with_format(TileFormat::PNG)
+			.with_compression(TileCompression::Uncompressed)
+			.with_bbox_pyramid(TileBBoxPyramid::new_full(10))
+			.with_traversal(Traversal::new_any())
+			.build()
+			.unwrap();
 		MockReader::new_mock(metadata).unwrap().boxed()
 	}
 
@@ -279,17 +280,18 @@ mod tests {
 	#[test]
 	fn test_with_parameters_builder() {
 		let source = create_mock_source();
-		let new_metadata = TileSourceMetadata::new(
-			TileFormat::JPG,
-			TileCompression::Gzip,
-			TileBBoxPyramid::new_full(5),
-			Traversal::new_any(),
-		);
+		let new_metadata = TileSourceMetadata::new_builder()
+			.with_format(TileFormat::JSON)
+			.with_compression(TileCompression::Gzip)
+			.with_bbox_pyramid(TileBBoxPyramid::new_full(5))
+			.with_traversal(Traversal::new_any())
+			.build()
+			.unwrap();
 
 		let processor = TileProcessor::new("test", source).with_parameters(new_metadata.clone());
 
 		// Verify parameters were replaced
-		assert_eq!(processor.parameters().tile_format, TileFormat::JPG);
+		assert_eq!(processor.parameters().tile_format, TileFormat::JSON);
 		assert_eq!(processor.parameters().tile_compression, TileCompression::Gzip);
 	}
 
@@ -318,12 +320,13 @@ mod tests {
 	#[test]
 	fn test_builder_chaining() {
 		let source = create_mock_source();
-		let new_metadata = TileSourceMetadata::new(
-			TileFormat::WEBP,
-			TileCompression::Brotli,
-			TileBBoxPyramid::new_full(8),
-			Traversal::new_any(),
-		);
+		let new_metadata = TileSourceMetadata::new_builder()
+			.with_format(TileFormat::JSON)
+			.with_compression(TileCompression::Brotli)
+			.with_bbox_pyramid(TileBBoxPyramid::new_full(8))
+			.with_traversal(Traversal::new_any())
+			.build()
+			.unwrap();
 		let new_tilejson = TileJSON::default();
 		let new_traversal = Traversal::new_any_size(128, 128).unwrap();
 
@@ -333,7 +336,7 @@ mod tests {
 			.with_traversal(new_traversal);
 
 		// Verify all were set
-		assert_eq!(processor.parameters().tile_format, TileFormat::WEBP);
+		assert_eq!(processor.parameters().tile_format, TileFormat::JSON);
 		assert_eq!(processor.parameters().tile_compression, TileCompression::Brotli);
 		assert!(processor.tilejson().as_string().contains("3.0.0"));
 		assert_eq!(processor.traversal().max_size().unwrap(), 128);
@@ -404,12 +407,13 @@ mod tests {
 	#[test]
 	fn test_processor_with_different_formats() {
 		// Test with MVT format
-		let metadata_mvt = TileSourceMetadata::new(
-			TileFormat::MVT,
-			TileCompression::Gzip,
-			TileBBoxPyramid::new_full(14),
-			Traversal::new_any(),
-		);
+		let metadata_mvt = TileSourceMetadata::new_builder()
+			.with_format(TileFormat::MVT)
+			.with_compression(TileCompression::Gzip)
+			.with_bbox_pyramid(TileBBoxPyramid::new_full(14))
+			.with_traversal(Traversal::new_any())
+			.build()
+			.unwrap();
 		let source_mvt = MockReader::new_mock(metadata_mvt).unwrap().boxed();
 		let processor_mvt = TileProcessor::new("mvt_processor", source_mvt);
 
@@ -420,12 +424,13 @@ mod tests {
 	#[test]
 	fn test_processor_with_different_compressions() {
 		// Test with Brotli compression
-		let metadata = TileSourceMetadata::new(
-			TileFormat::PNG,
-			TileCompression::Brotli,
-			TileBBoxPyramid::new_full(10),
-			Traversal::new_any(),
-		);
+		let metadata = TileSourceMetadata::new_builder()
+			.with_format(TileFormat::MVT)
+			.with_compression(TileCompression::Brotli)
+			.with_bbox_pyramid(TileBBoxPyramid::new_full(10))
+			.with_traversal(Traversal::new_any())
+			.build()
+			.unwrap();
 		let source = MockReader::new_mock(metadata).unwrap().boxed();
 		let processor = TileProcessor::new("brotli_processor", source);
 
@@ -437,12 +442,13 @@ mod tests {
 		// Create a pyramid with specific bbox
 		let pyramid = TileBBoxPyramid::new_full(5);
 
-		let metadata = TileSourceMetadata::new(
-			TileFormat::PNG,
-			TileCompression::Uncompressed,
-			pyramid.clone(),
-			Traversal::new_any(),
-		);
+		let metadata = TileSourceMetadata::new_builder()
+			.with_format(TileFormat::PNG)
+			.with_compression(TileCompression::Uncompressed)
+			.with_bbox_pyramid(pyramid.clone())
+			.with_traversal(Traversal::new_any())
+			.build()
+			.unwrap();
 		let source = MockReader::new_mock(metadata).unwrap().boxed();
 		let processor = TileProcessor::new("bbox_processor", source);
 
@@ -452,12 +458,13 @@ mod tests {
 	#[test]
 	fn test_processor_with_custom_traversal() {
 		let traversal = Traversal::new_any_size(64, 64).unwrap();
-		let metadata = TileSourceMetadata::new(
-			TileFormat::PNG,
-			TileCompression::Uncompressed,
-			TileBBoxPyramid::new_full(10),
-			traversal.clone(),
-		);
+		let metadata = TileSourceMetadata::new_builder()
+			.with_format(TileFormat::PNG)
+			.with_compression(TileCompression::Uncompressed)
+			.with_bbox_pyramid(TileBBoxPyramid::new_full(10))
+			.with_traversal(traversal.clone())
+			.build()
+			.unwrap();
 		let source = MockReader::new_mock(metadata).unwrap().boxed();
 		let processor = TileProcessor::new("traversal_processor", source);
 
@@ -19,7 +19,7 @@
 use anyhow::{Result, anyhow, ensure};
 use std::{fmt::Debug, io::Cursor};
 use versatiles_core::{
-	Blob, TileCompression, TileFormat,
+	Blob, TileCompression, TileFormat, TileSize,
 	utils::{decompress_ref, recompress},
 };
 use versatiles_derive::context;
@@ -201,6 +201,24 @@ impl Tile {
 		self.as_content()?.as_image()
 	}
 
+	#[context("detecting raster tile size")]
+	/// Return the edge length of a raster tile, decoding the blob on demand.
+	///
+	/// Returns `None` for non-raster formats and for images that are not square
+	/// or have no supported [`TileSize`].
+	pub fn tile_size(&mut self) -> Result<Option<TileSize>> {
+		if !self.format.is_raster() {
+			return Ok(None);
+		}
+		let image = self.as_image()?;
+		if image.width() != image.height() {
+			return Ok(None);
+		}
+		Ok(u16::try_from(image.width())
+			.ok()
+			.and_then(|size| TileSize::new(size).ok()))
+	}
+
 	#[context("accessing vector data from tile")]
 	/// Borrow the vector tile content, decoding the blob on demand.
 	///
@@ -272,6 +290,11 @@ impl Tile {
 	pub fn compression(&self) -> TileCompression {
 		self.compression
 	}
+	/// Return the size of the encoded blob in its current compression, or `None` if the tile
+	/// only holds decoded content. Unlike [`as_blob`](Self::as_blob), this never encodes.
+	pub fn blob_len(&self) -> Option<u64> {
+		self.blob.as_ref().map(Blob::len)
+	}
 
 	#[context("changing format: {:?} -> {:?} (q={:?}, s={:?})", self.format, format, quality, speed)]
 	/// Change the tile's **format** (e.g., `PNG` → `WEBP`) while preserving the content type.
@@ -281,8 +304,12 @@ impl Tile {
 	/// Passing `None` keeps the previous hint value.
 	///
 	/// The `format` must have the same type (raster vs. vector) as the current format.
+	/// If neither the format nor a hint changes, the tile (including its blob) is left untouched.
 	pub fn change_format(&mut self, format: TileFormat, quality: Option<u8>, speed: Option<u8>) -> Result<()> {
 		assert_eq!(format.to_type(), self.format.to_type());
+		if format == self.format && quality.is_none() && speed.is_none() {
+			return Ok(());
+		}
 		self.materialize_content()?;
 		self.delete_blob();
 		self.compression = TileCompression::Uncompressed;
@@ -297,6 +324,18 @@ impl Tile {
 		Ok(())
 	}
 
+	#[context("forcing recompression to {:?}", compression)]
+	/// Decompress the blob and compress it again with `compression`, even if the tile
+	/// already uses that compression.
+	///
+	/// Use [`change_compression`](Self::change_compression) to keep the original bytes
+	/// when the compression doesn't change.
+	pub fn force_recompress(&mut self, compression: TileCompression) -> Result<()> {
+		self.materialize_blob()?;
+		self.decompress_blob()?;
+		self.recompress_blob(compression)
+	}
+
 	#[context("changing compression to {:?}", compression)]
 	/// Update the outer **compression** flag and (if a blob is present) re-compress it.
 	///
@@ -404,6 +443,40 @@ mod tests {
 		Ok(())
 	}
 
+	#[rstest]
+	#[case(256, 256, Some(TileSize::Size256))]
+	#[case(512, 512, Some(TileSize::Size512))]
+	#[case(512, 256, None)]
+	#[case(2, 2, None)]
+	fn tile_size_of_raster_tiles(
+		#[case] width: u32,
+		#[case] height: u32,
+		#[case] expected: Option<TileSize>,
+	) -> Result<()> {
+		let image = DynamicImage::new_rgb8(width, height);
+		let blob = Tile::from_image(image, PNG)?.into_blob(Uncompressed)?;
+		let mut tile = Tile::from_blob(blob, Uncompressed, PNG);
+		assert_eq!(tile.tile_size()?, expected);
+		Ok(())
+	}
+
+	#[test]
+	fn blob_len_does_not_encode() -> Result<()> {
+		let mut tile = Tile::from_image(tiny_rgb_image(), PNG)?;
+		assert_eq!(tile.blob_len(), None);
+		assert!(!tile.has_blob());
+		let len = tile.as_blob(Uncompressed)?.len();
+		assert_eq!(tile.blob_len(), Some(len));
+		Ok(())
+	}
+
+	#[test]
+	fn tile_size_of_vector_tiles_is_unknown() -> Result<()> {
+		let mut tile = Tile::from_blob(Blob::from(vec![]), Uncompressed, MVT);
+		assert_eq!(tile.tile_size()?, None);
+		Ok(())
+	}
+
 	#[test]
 	fn decompress_blob_noop_when_uncompressed() -> Result<()> {
 		let mut tile = Tile::from_image(tiny_rgb_image(), PNG)?;
@@ -470,6 +543,18 @@ mod tests {
 		Ok(())
 	}
 
+	#[test]
+	fn change_format_to_same_format_keeps_blob() -> Result<()> {
+		let blob = Tile::from_image(tiny_rgb_image(), PNG)?.into_blob(Gzip)?;
+		let mut tile = Tile::from_blob(blob.clone(), Gzip, PNG);
+		tile.change_format(PNG, None, None)?;
+		assert!(tile.has_blob());
+		assert!(!tile.has_content());
+		assert_eq!(tile.compression(), Gzip);
+		assert_eq!(tile.into_blob(Gzip)?, blob);
+		Ok(())
+	}
+
 	#[test]
 	fn cachevalue_roundtrip_preserves_fields() -> Result<()> {
 		let img = tiny_rgb_image();
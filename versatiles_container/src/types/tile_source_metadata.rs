@@ -1,7 +1,11 @@
 //! This module defines metadata describing tile source output characteristics.
 
 use crate::Traversal;
-use versatiles_core::{TileBBoxPyramid, TileCompression, TileFormat, TileJSON, TileSchema, TileType};
+use anyhow::{Result, anyhow, ensure};
+use versatiles_core::{
+	TileBBoxPyramid, TileCompression, TileFormat, TileGrid, TileJSON, TileSchema, TileSize, TileType,
+};
+use versatiles_derive::context;
 
 /// Metadata describing the output characteristics of a tile source.
 ///
@@ -9,6 +13,8 @@ use versatiles_core::{TileBBoxPyramid, TileCompression, TileFormat, TileJSON, Ti
 /// - `bbox_pyramid`: The bounding box and zoom pyramid defining the tile coverage.
 /// - `tile_compression`: The compression algorithm applied to tiles (e.g., gzip, brotli).
 /// - `tile_format`: The format of the tiles (e.g., PNG, JPEG, PBF).
+/// - `tile_size`: The pixel size of the tiles, if known.
+/// - `tile_grid`: A custom tile grid, or `None` for the standard Web-Mercator grid.
 #[derive(Debug, Default, PartialEq, Clone)]
 pub struct TileSourceMetadata {
 	/// The bounding box and zoom pyramid defining the tile coverage.
@@ -19,9 +25,135 @@ pub struct TileSourceMetadata {
 	pub tile_format: TileFormat,
 
 	pub traversal: Traversal,
+	/// The edge length of the tiles in pixels, if known (e.g. 512 for "@2x" raster tiles).
+	pub tile_size: Option<TileSize>,
+	/// The tile grid of sources that are not tiled in Web Mercator, e.g. national grids.
+	/// `None` means the standard Web-Mercator grid.
+	pub tile_grid: Option<TileGrid>,
+}
+
+/// Result of comparing two [`TileSourceMetadata`] with [`TileSourceMetadata::compatible_with`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct CompatibilityReport {
+	/// Both sources have the same tile format. Combining sources requires this.
+	pub format_ok: bool,
+	/// Both sources have the same tile compression. Mismatches are handled by recompressing.
+	pub compression_ok: bool,
+	/// The tile sizes don't contradict each other. Combining sources requires this.
+	pub tile_size_ok: bool,
+	/// Human-readable notes about differences that don't prevent combining the sources.
+	pub warnings: Vec<String>,
+}
+
+/// Builder for [`TileSourceMetadata`], created with [`TileSourceMetadata::new_builder`].
+///
+/// Tile format and compression must be set, the bbox pyramid defaults to an empty pyramid and
+/// the traversal to [`Traversal::ANY`].
+///
+/// # Examples
+///
+/// ```
+/// use versatiles_container::TileSourceMetadata;
+/// use versatiles_core::{TileBBoxPyramid, TileCompression, TileFormat};
+///
+/// let metadata = TileSourceMetadata::new_builder()
+///     .with_format(TileFormat::MVT)
+///     .with_compression(TileCompression::Gzip)
+///     .with_bbox_pyramid(TileBBoxPyramid::new_full(14))
+///     .build()
+///     .unwrap();
+/// assert_eq!(metadata.tile_format, TileFormat::MVT);
+///
+/// assert!(TileSourceMetadata::new_builder().with_format(TileFormat::PNG).build().is_err());
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct TileSourceMetadataBuilder {
+	tile_format: Option<TileFormat>,
+	tile_compression: Option<TileCompression>,
+	bbox_pyramid: TileBBoxPyramid,
+	traversal: Traversal,
+	tile_size: Option<TileSize>,
+	tile_grid: Option<TileGrid>,
+}
+
+impl TileSourceMetadataBuilder {
+	/// Sets the tile format.
+	#[must_use]
+	pub fn with_format(mut self, tile_format: TileFormat) -> Self {
+		self.tile_format = Some(tile_format);
+		self
+	}
+
+	/// Sets the tile compression.
+	#[must_use]
+	pub fn with_compression(mut self, tile_compression: TileCompression) -> Self {
+		self.tile_compression = Some(tile_compression);
+		self
+	}
+
+	/// Sets the bounding box and zoom pyramid defining the tile coverage.
+	#[must_use]
+	pub fn with_bbox_pyramid(mut self, bbox_pyramid: TileBBoxPyramid) -> Self {
+		self.bbox_pyramid = bbox_pyramid;
+		self
+	}
+
+	/// Sets the traversal the source prefers for reading tiles.
+	#[must_use]
+	pub fn with_traversal(mut self, traversal: Traversal) -> Self {
+		self.traversal = traversal;
+		self
+	}
+
+	/// Sets the edge length of the tiles in pixels.
+	#[must_use]
+	pub fn with_tile_size(mut self, tile_size: TileSize) -> Self {
+		self.tile_size = Some(tile_size);
+		self
+	}
+
+	/// Sets a custom tile grid instead of the Web-Mercator grid.
+	#[must_use]
+	pub fn with_tile_grid(mut self, tile_grid: TileGrid) -> Self {
+		self.tile_grid = Some(tile_grid);
+		self
+	}
+
+	/// Builds the [`TileSourceMetadata`].
+	///
+	/// # Errors
+	/// Returns an error if the tile format or the tile compression was not set, or if a raster
+	/// format is combined with a tile compression: raster formats are already compressed.
+	#[context("building tile source metadata")]
+	pub fn build(self) -> Result<TileSourceMetadata> {
+		let tile_format = self.tile_format.ok_or_else(|| anyhow!("tile format is not set"))?;
+		let tile_compression = self
+			.tile_compression
+			.ok_or_else(|| anyhow!("tile compression is not set"))?;
+
+		ensure!(
+			!tile_format.is_raster() || tile_compression == TileCompression::Uncompressed,
+			"{tile_format} tiles are already compressed and can't have an additional {tile_compression} compression"
+		);
+
+		Ok(TileSourceMetadata {
+			bbox_pyramid: self.bbox_pyramid,
+			tile_compression,
+			tile_format,
+			traversal: self.traversal,
+			tile_size: self.tile_size,
+			tile_grid: self.tile_grid,
+		})
+	}
 }
 
 impl TileSourceMetadata {
+	/// Creates a [`TileSourceMetadataBuilder`].
+	#[must_use]
+	pub fn new_builder() -> TileSourceMetadataBuilder {
+		TileSourceMetadataBuilder::default()
+	}
+
 	/// Create a new `TileSourceMetadata`.
 	///
 	/// # Arguments
@@ -32,6 +164,7 @@ impl TileSourceMetadata {
 	/// # Returns
 	/// A new instance of `TileSourceMetadata` configured with the specified parameters.
 	#[must_use]
+	#[deprecated(note = "use TileSourceMetadata::new_builder()")]
 	pub fn new(
 		tile_format: TileFormat,
 		tile_compression: TileCompression,
@@ -43,6 +176,8 @@ impl TileSourceMetadata {
 			tile_compression,
 			tile_format,
 			traversal,
+			tile_size: None,
+			tile_grid: None,
 		}
 	}
 
@@ -60,6 +195,43 @@ impl TileSourceMetadata {
 			tile_compression,
 			bbox_pyramid: TileBBoxPyramid::new_full(31),
 			traversal,
+			tile_size: None,
+			tile_grid: None,
+		}
+	}
+
+	/// Checks whether tiles of `other` can be combined with tiles of this source.
+	///
+	/// The tile format must be equal, as well as the tile size if both sources know it.
+	/// A different compression only means that tiles have to be recompressed, and bbox pyramids
+	/// that don't overlap are reported as a warning.
+	#[must_use]
+	pub fn compatible_with(&self, other: &TileSourceMetadata) -> CompatibilityReport {
+		let format_ok = self.tile_format == other.tile_format;
+		let compression_ok = self.tile_compression == other.tile_compression;
+		let tile_size_ok = match (self.tile_size, other.tile_size) {
+			(Some(a), Some(b)) => a == b,
+			_ => true,
+		};
+
+		let mut warnings = Vec::new();
+		if !compression_ok {
+			warnings.push(format!(
+				"tile compression differs ({} vs {}), tiles will be recompressed",
+				self.tile_compression, other.tile_compression
+			));
+		}
+		let mut overlap = self.bbox_pyramid.clone();
+		overlap.intersect(&other.bbox_pyramid);
+		if overlap.is_empty() {
+			warnings.push("bbox pyramids don't overlap".to_string());
+		}
+
+		CompatibilityReport {
+			format_ok,
+			compression_ok,
+			tile_size_ok,
+			warnings,
 		}
 	}
 
@@ -67,6 +239,7 @@ impl TileSourceMetadata {
 	///
 	/// - Applies [`TileJSON::update_from_pyramid`] to intersect/set bounds and min/max zoom.
 	/// - Sets `tile_format` from the reader parameters and derives `tile_type` from it.
+	/// - Sets `tile_size` if the tile size is known.
 	/// - If `tile_schema` is absent or mismatched with `tile_type`, infers a suitable schema
 	///   (e.g., `RasterRGB` for rasters; for vectors, derived from `vector_layers`).
 	pub fn update_tilejson(&self, tile_json: &mut TileJSON) {
@@ -76,6 +249,10 @@ impl TileSourceMetadata {
 
 		tile_json.tile_type = tile_json.tile_format.map(|f| f.to_type());
 
+		if self.tile_size.is_some() {
+			tile_json.tile_size = self.tile_size;
+		}
+
 		if let Some(tile_type) = tile_json.tile_type
 			&& tile_json.tile_schema.map(|s| s.tile_type()) != tile_json.tile_type
 		{
@@ -96,6 +273,7 @@ mod tests {
 	use super::*;
 
 	#[test]
+	#[allow(deprecated)]
 	fn test_tiles_reader_parameters_new() {
 		let bbox_pyramid = TileBBoxPyramid::new_full(10);
 		let tile_format = TileFormat::PNG;
@@ -108,6 +286,82 @@ mod tests {
 		assert_eq!(params.bbox_pyramid, bbox_pyramid);
 	}
 
+	#[test]
+	fn builder_sets_all_fields() -> Result<()> {
+		let bbox_pyramid = TileBBoxPyramid::new_full(10);
+		let traversal = Traversal::new_any_size(1, 256)?;
+
+		let params = TileSourceMetadata::new_builder()
+			.with_format(TileFormat::MVT)
+			.with_compression(TileCompression::Brotli)
+			.with_bbox_pyramid(bbox_pyramid.clone())
+			.with_traversal(traversal.clone())
+			.build()?;
+
+		assert_eq!(params.tile_format, TileFormat::MVT);
+		assert_eq!(params.tile_compression, TileCompression::Brotli);
+		assert_eq!(params.bbox_pyramid, bbox_pyramid);
+		assert_eq!(params.traversal, traversal);
+		assert_eq!(params.tile_size, None);
+		assert_eq!(params.tile_grid, None);
+
+		let params = TileSourceMetadata::new_builder()
+			.with_format(TileFormat::PNG)
+			.with_compression(TileCompression::Uncompressed)
+			.with_tile_size(TileSize::Size512)
+			.build()?;
+		assert_eq!(params.tile_size, Some(TileSize::Size512));
+
+		let tile_grid = TileGrid::new(10.0, 70.0, 4.0, 2.0, vec![4096.0, 2048.0])?;
+		let params = TileSourceMetadata::new_builder()
+			.with_format(TileFormat::PNG)
+			.with_compression(TileCompression::Uncompressed)
+			.with_tile_grid(tile_grid.clone())
+			.build()?;
+		assert_eq!(params.tile_grid, Some(tile_grid));
+		Ok(())
+	}
+
+	#[test]
+	fn builder_uses_defaults() -> Result<()> {
+		let params = TileSourceMetadata::new_builder()
+			.with_format(TileFormat::PNG)
+			.with_compression(TileCompression::Uncompressed)
+			.build()?;
+
+		assert!(params.bbox_pyramid.is_empty());
+		assert_eq!(params.traversal, Traversal::ANY);
+		Ok(())
+	}
+
+	#[test]
+	fn builder_requires_format_and_compression() {
+		let error = TileSourceMetadata::new_builder()
+			.with_compression(TileCompression::Gzip)
+			.build()
+			.unwrap_err();
+		assert_eq!(error.root_cause().to_string(), "tile format is not set");
+
+		let error = TileSourceMetadata::new_builder()
+			.with_format(TileFormat::MVT)
+			.build()
+			.unwrap_err();
+		assert_eq!(error.root_cause().to_string(), "tile compression is not set");
+	}
+
+	#[test]
+	fn builder_rejects_compressed_raster_formats() {
+		let error = TileSourceMetadata::new_builder()
+			.with_format(TileFormat::PNG)
+			.with_compression(TileCompression::Gzip)
+			.build()
+			.unwrap_err();
+		assert_eq!(
+			error.root_cause().to_string(),
+			"png tiles are already compressed and can't have an additional gzip compression"
+		);
+	}
+
 	#[test]
 	fn test_tiles_reader_parameters_new_full() {
 		let tile_format = TileFormat::JPG;
@@ -120,6 +374,66 @@ mod tests {
 		assert_eq!(params.bbox_pyramid, TileBBoxPyramid::new_full(31));
 	}
 
+	#[test]
+	fn compatible_with_reports_compression_mismatch() {
+		let a = TileSourceMetadata::new_full(TileFormat::MVT, TileCompression::Gzip, Traversal::ANY);
+		let b = TileSourceMetadata::new_full(TileFormat::MVT, TileCompression::Brotli, Traversal::ANY);
+
+		let report = a.compatible_with(&b);
+		assert!(report.format_ok);
+		assert!(!report.compression_ok);
+		assert_eq!(
+			report.warnings,
+			["tile compression differs (gzip vs brotli), tiles will be recompressed"]
+		);
+
+		let report = a.compatible_with(&a);
+		assert!(report.format_ok && report.compression_ok && report.warnings.is_empty());
+	}
+
+	#[test]
+	fn compatible_with_reports_format_mismatch() {
+		let a = TileSourceMetadata::new_full(TileFormat::MVT, TileCompression::Gzip, Traversal::ANY);
+		let b = TileSourceMetadata::new_full(TileFormat::PNG, TileCompression::Gzip, Traversal::ANY);
+
+		let report = a.compatible_with(&b);
+		assert!(!report.format_ok);
+		assert!(report.compression_ok);
+	}
+
+	#[test]
+	fn compatible_with_checks_tile_size() {
+		let mut a = TileSourceMetadata::new_full(TileFormat::PNG, TileCompression::Uncompressed, Traversal::ANY);
+		let mut b = a.clone();
+		assert!(a.compatible_with(&b).tile_size_ok);
+
+		// an unknown tile size doesn't contradict a known one
+		a.tile_size = Some(TileSize::Size512);
+		assert!(a.compatible_with(&b).tile_size_ok);
+
+		b.tile_size = Some(TileSize::Size256);
+		assert!(!a.compatible_with(&b).tile_size_ok);
+
+		b.tile_size = Some(TileSize::Size512);
+		assert!(a.compatible_with(&b).tile_size_ok);
+	}
+
+	#[test]
+	fn compatible_with_warns_about_disjoint_pyramids() {
+		let a = TileSourceMetadata {
+			bbox_pyramid: TileBBoxPyramid::from_geo_bbox(0, 8, &GeoBBox::new(-10.0, -10.0, -5.0, -5.0).unwrap()),
+			..Default::default()
+		};
+		let b = TileSourceMetadata {
+			bbox_pyramid: TileBBoxPyramid::from_geo_bbox(10, 12, &GeoBBox::new(-10.0, -10.0, -5.0, -5.0).unwrap()),
+			..Default::default()
+		};
+
+		let report = a.compatible_with(&b);
+		assert!(report.format_ok && report.compression_ok);
+		assert_eq!(report.warnings, ["bbox pyramids don't overlap"]);
+	}
+
 	#[test]
 	fn should_update_tile_json() -> Result<()> {
 		let mut tj = TileJSON::default();
@@ -138,6 +452,21 @@ mod tests {
 		assert_eq!(tj.tile_format, Some(TileFormat::PNG));
 		assert_eq!(tj.tile_type, Some(TileType::Raster));
 		assert_eq!(tj.tile_schema, Some(TileSchema::RasterRGB));
+		// Unknown tile size keeps the TileJSON value
+		assert_eq!(tj.tile_size, None);
 		Ok(())
 	}
+
+	#[test]
+	fn should_update_tile_size_in_tile_json() {
+		let mut tj = TileJSON::default();
+		let rp = TileSourceMetadata {
+			tile_format: TileFormat::WEBP,
+			tile_size: Some(TileSize::Size512),
+			..Default::default()
+		};
+		rp.update_tilejson(&mut tj);
+		assert_eq!(tj.tile_size, Some(TileSize::Size512));
+		assert!(tj.as_string().contains("\"tile_size\":512"));
+	}
 }
@@ -17,16 +17,19 @@
 //! - Clear separation between data sources and transformations
 
 use crate::{
-	CacheMap, SourceType, Tile, TileSourceMetadata, TilesRuntime,
-	traversal::{Traversal, TraversalTranslationStep, translate_traversals},
+	CacheMap, SourceType, Tile, TileCoverage, TileSourceMetadata, TilesRuntime, VerifyLevel, VerifyReport,
+	tile_coverage,
+	traversal::{SortOrder, Traversal, TraversalTranslationStep, translate_traversals},
+	verify_tiles,
 };
-use anyhow::Result;
+use anyhow::{Result, ensure};
 use async_trait::async_trait;
 use futures::{StreamExt, future::BoxFuture, stream};
 use std::{fmt::Debug, sync::Arc};
 #[cfg(feature = "cli")]
 use versatiles_core::{ProbeDepth, utils::PrettyPrint};
-use versatiles_core::{TileBBox, TileCoord, TileJSON, TileStream};
+use versatiles_core::{TileBBox, TileCoord, TileJSON, TileStream, TileType};
+use versatiles_geometry::vector_tile::{TileInspection, inspect_tile};
 
 /// Unified object-safe interface for reading or processing tiles.
 ///
@@ -73,7 +76,44 @@ pub trait TileSource: Debug + Send + Sync + Unpin {
 	async fn get_tile(&self, coord: &TileCoord) -> Result<Option<Tile>> {
 		let bbox = coord.to_tile_bbox();
 		let mut stream = self.get_tile_stream(bbox).await?;
-		Ok(stream.next().await.map(|(_, t)| t))
+		Ok(stream.next().await?.map(|(_, t)| t))
+	}
+
+	/// Returns whether a tile exists at the given coordinate.
+	///
+	/// The default implementation fetches the tile. Containers with a tile index override this
+	/// to answer without reading tile data.
+	async fn has_tile(&self, coord: &TileCoord) -> Result<bool> {
+		Ok(self.get_tile(coord).await?.is_some())
+	}
+
+	/// Returns the size in bytes of the tile at `coord` as stored in the source, or `None` if
+	/// the tile doesn't exist.
+	///
+	/// The default implementation fetches the tile and encodes it in the compression of the
+	/// source. Containers with a tile index override this to answer without reading tile data.
+	async fn get_tile_size(&self, coord: &TileCoord) -> Result<Option<u64>> {
+		let compression = self.metadata().tile_compression;
+		match self.get_tile(coord).await? {
+			Some(tile) => Ok(Some(tile.into_blob(compression)?.len())),
+			None => Ok(None),
+		}
+	}
+
+	/// Fetches the vector tile at `coord` and summarizes its layers, see [`inspect_tile`].
+	///
+	/// Returns `Ok(None)` if the tile doesn't exist and an error for raster sources.
+	async fn inspect(&self, coord: &TileCoord) -> Result<Option<TileInspection>> {
+		let format = self.metadata().tile_format;
+		ensure!(
+			format.to_type() == TileType::Vector,
+			"can only inspect vector tiles, but the tile format is {format}"
+		);
+		let Some(tile) = self.get_tile(coord).await? else {
+			return Ok(None);
+		};
+		let compression = tile.compression();
+		inspect_tile(&tile.into_blob(compression)?, &compression).map(Some)
 	}
 
 	/// Asynchronously streams all tiles within the given bounding box.
@@ -92,6 +132,22 @@ pub trait TileSource: Debug + Send + Sync + Unpin {
 		))
 	}
 
+	/// Checks every tile of this source at the given level and returns a report.
+	///
+	/// See [`verify_tiles`](crate::verify_tiles) for details.
+	async fn deep_verify(&self, level: VerifyLevel) -> Result<VerifyReport> {
+		verify_tiles(self, level).await
+	}
+
+	/// Returns a bitmap of the existing tiles of a zoom level.
+	///
+	/// The default checks every tile with [`has_tile`](Self::has_tile), see
+	/// [`tile_coverage`](crate::tile_coverage) for details. Containers with a tile index should
+	/// read the coverage from it instead.
+	async fn coverage(&self, level: u8) -> Result<TileCoverage> {
+		tile_coverage(self, level).await
+	}
+
 	/// Performs a hierarchical CLI probe at the specified depth.
 	///
 	/// Probes metadata, container specifics, tiles, and tile contents
@@ -111,6 +167,8 @@ pub trait TileSource: Debug + Send + Sync + Unpin {
 
 		if matches!(level, Container | Tiles | TileContents) {
 			log::debug!("probing source {:?} at depth {:?}", self.source_type(), level);
+			// Reading a tile is not part of a shallow probe, which only looks at the metadata.
+			self.probe_sample_tile(&print.get_category("sample tile").await).await?;
 			self.probe_container(&print.get_category("container").await).await?;
 		}
 
@@ -142,9 +200,23 @@ pub trait TileSource: Debug + Send + Sync + Unpin {
 	async fn probe_metadata(&self, print: &mut PrettyPrint) -> Result<()> {
 		let metadata = self.metadata();
 		let p = print.get_list("bbox_pyramid").await;
-		for level in metadata.bbox_pyramid.iter_levels() {
-			p.add_value(level).await;
+		for bbox in metadata.bbox_pyramid.iter_bboxes() {
+			p.add_value(bbox).await;
+		}
+		if let (Some(min), Some(max)) = (
+			metadata.bbox_pyramid.get_level_min(),
+			metadata.bbox_pyramid.get_level_max(),
+		) {
+			print.add_key_value("zoom range", &format!("{min}..={max}")).await;
+		}
+		let p = print.get_list("tile count per level").await;
+		for (level, _) in metadata.bbox_pyramid.iter_levels_with_zoom() {
+			p.add_key_value(&level, &metadata.bbox_pyramid.count_level_tiles(level))
+				.await;
 		}
+		print
+			.add_key_value("tile count", &metadata.bbox_pyramid.count_tiles())
+			.await;
 		print
 			.add_key_value("bbox", &format!("{:?}", metadata.bbox_pyramid.get_geo_bbox()))
 			.await;
@@ -152,6 +224,36 @@ pub trait TileSource: Debug + Send + Sync + Unpin {
 			.add_key_value("tile compression", &metadata.tile_compression)
 			.await;
 		print.add_key_value("tile format", &metadata.tile_format).await;
+		if let Some(tile_size) = metadata.tile_size {
+			print.add_key_value("tile size", &tile_size.size()).await;
+		}
+		Ok(())
+	}
+
+	/// Writes the coordinate and encoded size of a sample tile at the center of the highest zoom level.
+	#[cfg(feature = "cli")]
+	async fn probe_sample_tile(&self, print: &PrettyPrint) -> Result<()> {
+		let bbox_pyramid = &self.metadata().bbox_pyramid;
+		let Some(level) = bbox_pyramid.get_level_max() else {
+			print.add_warning("no tiles available").await;
+			return Ok(());
+		};
+		let bbox = bbox_pyramid.get_bounding_bbox(level);
+		let coord = TileCoord::new(
+			level,
+			u32::midpoint(bbox.x_min()?, bbox.x_max()?),
+			u32::midpoint(bbox.y_min()?, bbox.y_max()?),
+		)?;
+		print.add_key_value("coord", &coord).await;
+
+		match self.get_tile(&coord).await? {
+			Some(tile) => {
+				let compression = tile.compression();
+				let size = tile.into_blob(compression)?.len();
+				print.add_key_value("size", &size).await;
+			}
+			None => print.add_warning("tile is missing").await,
+		}
 		Ok(())
 	}
 
@@ -184,6 +286,55 @@ pub trait TileSource: Debug + Send + Sync + Unpin {
 		Ok(())
 	}
 
+	/// Streams all tiles within `bbox` in the given [`SortOrder`].
+	///
+	/// Without `buffer_size` all tiles of `bbox` are collected and sorted, e.g. 65,536 tiles for a
+	/// 256×256 bbox. With `buffer_size` the bbox is split into blocks of at most that many tiles
+	/// that are contiguous in `order` (see [`SortOrder::split_bbox`]), so at most `buffer_size`
+	/// tiles are sorted at a time.
+	///
+	/// The streams of all blocks are requested up front, so errors of the source are returned here
+	/// and not hidden in the stream. Sources that read their tiles when a stream is requested, like
+	/// the VersaTiles reader, therefore still hold all tiles of `bbox` in memory; `buffer_size` only
+	/// limits the memory for sources with lazy streams.
+	async fn get_tile_stream_sorted(
+		&self,
+		bbox: TileBBox,
+		order: SortOrder,
+		buffer_size: Option<usize>,
+	) -> Result<TileStream<Tile>> {
+		let Some(buffer_size) = buffer_size else {
+			let mut tiles = self.get_tile_stream(bbox).await?.to_vec().await?;
+			order.sort_tiles(&mut tiles);
+			return Ok(TileStream::from_vec(tiles));
+		};
+
+		let mut streams = Vec::new();
+		for block in order.split_bbox(bbox, buffer_size)? {
+			streams.push(self.get_tile_stream(block).await?);
+		}
+		// one block at a time, so at most `buffer_size` tiles are sorted at once
+		let blocks = stream::iter(streams).map(move |stream| async move {
+			match stream.to_vec().await {
+				Ok(mut tiles) => {
+					order.sort_tiles(&mut tiles);
+					TileStream::from_vec(tiles)
+				}
+				Err(error) => TileStream::failed(error),
+			}
+		});
+		Ok(TileStream::from_streams_limited(blocks, 1))
+	}
+
+	/// Returns `true` if traversals must deliver the tiles in a deterministic order, e.g. for
+	/// reproducible output.
+	///
+	/// [`TileSourceTraverseExt::traverse_all_tiles`] then keeps the order of the bboxes it reads,
+	/// so a slow bbox holds back the ones after it.
+	fn requires_ordered_traversal(&self) -> bool {
+		false
+	}
+
 	/// Converts `self` into a boxed trait object for dynamic dispatch.
 	fn boxed(self) -> Box<dyn TileSource>
 	where
@@ -251,6 +402,7 @@ pub trait TileSourceTraverseExt: TileSource {
 				}
 			}
 			let progress = runtime.create_progress(&progress_message, u64::midpoint(tn_read, tn_write));
+			let ordered = self.requires_ordered_traversal();
 
 			let mut ti_read = 0;
 			let mut ti_write = 0;
@@ -261,28 +413,25 @@ pub trait TileSourceTraverseExt: TileSource {
 					Push(bboxes, index) => {
 						log::trace!("Cache {bboxes:?} at index {index}");
 						let limits = versatiles_core::ConcurrencyLimits::default();
-						stream::iter(bboxes.clone())
-							.map(|bbox| {
-								let progress = progress.clone();
-								let c = cache.clone();
-								async move {
-									let vec = self
-										.get_tile_stream(bbox)
-										.await?
-										.inspect(move || progress.inc(1))
-										.to_vec()
-										.await;
-
-									c.append(&index, vec)?;
-
-									Ok::<_, anyhow::Error>(())
-								}
-							})
-							.buffer_unordered(limits.io_bound) // I/O-bound: reading tiles from disk/network
-							.collect::<Vec<_>>()
-							.await
-							.into_iter()
-							.collect::<Result<Vec<_>>>()?;
+						let reads = stream::iter(bboxes.clone()).map(|bbox| {
+							let progress = progress.clone();
+							let c = cache.clone();
+							async move {
+								let stream = self.get_tile_stream(bbox).await?;
+								let vec = stream.inspect(move || progress.inc(1)).to_vec().await?;
+
+								c.append(&index, vec)?;
+
+								Ok::<_, anyhow::Error>(())
+							}
+						});
+						// I/O-bound: reading tiles from disk/network. In order, the cached tiles don't depend on timing.
+						let results: Vec<Result<()>> = if ordered {
+							reads.buffered(limits.io_bound).collect().await
+						} else {
+							reads.buffer_unordered(limits.io_bound).collect().await
+						};
+						results.into_iter().collect::<Result<Vec<_>>>()?;
 						ti_read += bboxes.iter().map(TileBBox::count_tiles).sum::<u64>();
 					}
 					Pop(index, bbox) => {
@@ -299,14 +448,21 @@ pub trait TileSourceTraverseExt: TileSource {
 						let streams = stream::iter(bboxes.clone()).map(move |bbox| {
 							let progress = progress.clone();
 							async move {
-								self
-									.get_tile_stream(bbox)
-									.await
-									.unwrap()
-									.inspect(move || progress.inc(2))
+								match self.get_tile_stream(bbox).await {
+									Ok(stream) => stream.inspect(move || progress.inc(2)),
+									Err(error) => TileStream::failed(error),
+								}
 							}
 						});
-						callback(bbox, TileStream::from_streams(streams)).await?;
+						let stream = if ordered {
+							TileStream::from_streams_ordered(streams)
+						} else {
+							TileStream::from_streams(streams)
+						};
+						// Errors of the sources end the stream early, so check them after the callback
+						let failure = stream.failure_handle();
+						callback(bbox, stream).await?;
+						failure.check()?;
 						ti_read += bboxes.iter().map(TileBBox::count_tiles).sum::<u64>();
 						ti_write += bbox.count_tiles();
 					}
@@ -329,6 +485,8 @@ mod tests {
 	#[cfg(feature = "cli")]
 	use super::ProbeDepth;
 	use super::*;
+	use crate::{MockReader, MockReaderProfile};
+	use std::time::{Duration, Instant};
 	#[cfg(feature = "cli")]
 	use versatiles_core::utils::PrettyPrint;
 	use versatiles_core::{Blob, TileBBoxPyramid, TileCompression, TileFormat};
@@ -349,6 +507,8 @@ mod tests {
 					tile_compression: TileCompression::Gzip,
 					tile_format: TileFormat::MVT,
 					traversal: Traversal::ANY,
+					tile_size: None,
+					tile_grid: None,
 				},
 				tilejson,
 			}
@@ -408,7 +568,83 @@ mod tests {
 		let bbox = TileBBox::from_min_and_max(1, 0, 0, 1, 1)?;
 		let stream = reader.get_tile_stream(bbox).await?;
 
-		assert_eq!(stream.drain_and_count().await, 4); // Assuming 4 tiles in a 2x2 bbox
+		assert_eq!(stream.drain_and_count().await.unwrap(), 4); // Assuming 4 tiles in a 2x2 bbox
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_has_tile() -> Result<()> {
+		let reader = TestReader::new_dummy();
+		assert!(reader.has_tile(&TileCoord::new(2, 1, 3)?).await?);
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_inspect() -> Result<()> {
+		use crate::{MockReader, MockReaderProfile};
+
+		let reader = MockReader::new_mock_profile(MockReaderProfile::Pbf)?;
+		let inspection = reader.inspect(&TileCoord::new(2, 1, 1)?).await?.unwrap();
+		assert_eq!(inspection.layers.len(), 1);
+		let layer = &inspection.layers[0];
+		assert_eq!(layer.name, "ocean");
+		assert_eq!(layer.feature_counts.get("polygon"), Some(&1));
+		assert_eq!(layer.properties.keys().collect::<Vec<_>>(), ["x", "y"]);
+
+		let err = MockReader::new_mock_profile(MockReaderProfile::Png)?
+			.inspect(&TileCoord::new(0, 0, 0)?)
+			.await
+			.unwrap_err();
+		assert!(err.to_string().contains("can only inspect vector tiles"), "{err}");
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn concurrent_get_tile() -> Result<()> {
+		// serialized calls would take 100 × 100 ms
+		let reader =
+			Arc::new(MockReader::new_mock_profile(MockReaderProfile::Pbf)?.with_delay(Duration::from_millis(100)));
+		let coords = TileBBox::new_full(4)?.iter_coords().take(100).collect::<Vec<_>>();
+
+		let start = Instant::now();
+		let results = futures::future::join_all(coords.iter().map(|coord| {
+			let reader = Arc::clone(&reader);
+			async move { reader.get_tile(coord).await }
+		}))
+		.await;
+		let elapsed = start.elapsed();
+
+		for result in results {
+			assert!(result?.is_some());
+		}
+		assert!(
+			elapsed < Duration::from_secs(5),
+			"get_tile calls were serialized: {elapsed:?}"
+		);
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_get_tile_stream_sorted() -> Result<()> {
+		let reader = TestReader::new_dummy();
+		let bbox = TileBBox::new_full(2)?;
+
+		for (order, expected) in [
+			(SortOrder::RowMajor, "00 10 20 30 01 11 21 31 02 12 22 32 03 13 23 33"),
+			(SortOrder::Morton, "00 10 01 11 20 30 21 31 02 12 03 13 22 32 23 33"),
+			(SortOrder::Hilbert, "00 10 11 01 02 03 13 12 22 23 33 32 31 21 20 30"),
+		] {
+			for buffer_size in [None, Some(1), Some(4), Some(6)] {
+				let tiles = reader
+					.get_tile_stream_sorted(bbox, order, buffer_size)
+					.await?
+					.to_vec()
+					.await
+					.unwrap();
+				let coords = tiles.iter().map(|(c, _)| format!("{}{}", c.x, c.y)).collect::<Vec<_>>();
+				assert_eq!(coords.join(" "), expected, "{order:?} {buffer_size:?}");
+			}
+		}
 		Ok(())
 	}
 
@@ -436,6 +672,31 @@ mod tests {
 		Ok(())
 	}
 
+	#[cfg(feature = "cli")]
+	#[tokio::test]
+	async fn test_probe_metadata_output() -> Result<()> {
+		let reader = TestReader::new_dummy();
+		let mut print = PrettyPrint::new();
+		reader.probe_metadata(&mut print).await?;
+		let output = print.as_string().await;
+		assert!(output.contains("zoom range: \"0..=3\"\n"));
+		assert!(output.contains("tile count per level:\n  0: 1\n  1: 4\n  2: 16\n  3: 64\n"));
+		assert!(output.contains("tile count: 85\n"));
+		Ok(())
+	}
+
+	#[cfg(feature = "cli")]
+	#[tokio::test]
+	async fn test_probe_sample_tile() -> Result<()> {
+		let reader = TestReader::new_dummy();
+		let print = PrettyPrint::new();
+		reader.probe_sample_tile(&print).await?;
+		let output = print.as_string().await;
+		assert!(output.contains("coord: TileCoord(3, [3, 3])\n"), "{output}");
+		assert!(output.contains("size: 14\n"), "{output}");
+		Ok(())
+	}
+
 	#[cfg(feature = "cli")]
 	#[tokio::test]
 	async fn test_probe_container() -> Result<()> {
@@ -0,0 +1,248 @@
+//! Verification of all tiles of a source.
+//!
+//! [`verify_tiles`] streams every tile of a [`TileSource`] and checks it at the requested
+//! [`VerifyLevel`]. The resulting [`VerifyReport`] can be serialized to JSON for CI jobs or
+//! printed as a summary for humans.
+//!
+//! # Example
+//! ```rust
+//! use versatiles_container::*;
+//!
+//! #[tokio::main]
+//! async fn main() -> anyhow::Result<()> {
+//!     let reader = MockReader::new_mock_profile(MockReaderProfile::Png)?;
+//!     let report = reader.deep_verify(VerifyLevel::Decode).await?;
+//!     assert!(report.is_ok());
+//!     println!("{}", report.as_json_value().stringify());
+//!     Ok(())
+//! }
+//! ```
+
+use crate::{Tile, TileSource};
+use anyhow::{Result, ensure};
+use std::time::{Duration, Instant};
+#[cfg(feature = "cli")]
+use versatiles_core::utils::PrettyPrint;
+use versatiles_core::{
+	TileCompression, TileCoord, TileType,
+	json::{JsonObject, JsonValue},
+};
+
+/// How thoroughly each tile is checked. Every level includes the checks of the previous ones.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum VerifyLevel {
+	/// The tile is not empty and gzip compressed tiles start with the gzip header.
+	Header,
+	/// The tile can be decompressed.
+	Decompress,
+	/// Raster tiles can be decoded to images and vector tiles to layers.
+	Decode,
+}
+
+/// Result of [`verify_tiles`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct VerifyReport {
+	/// Number of tiles that were checked.
+	pub tiles_checked: u64,
+	/// Number of tiles without errors.
+	pub tiles_ok: u64,
+	/// Coordinates of the broken tiles and what is wrong with them.
+	pub errors: Vec<(TileCoord, String)>,
+	/// Duration of the verification.
+	pub elapsed: Duration,
+}
+
+impl VerifyReport {
+	/// Returns `true` if no tile has errors.
+	#[must_use]
+	pub fn is_ok(&self) -> bool {
+		self.errors.is_empty()
+	}
+
+	/// Returns the report as JSON, with `elapsed` in milliseconds and each error as
+	/// `{"z":…,"x":…,"y":…,"error":…}`.
+	#[must_use]
+	pub fn as_json_value(&self) -> JsonValue {
+		let errors = self
+			.errors
+			.iter()
+			.map(|(coord, message)| {
+				let mut object = JsonObject::new();
+				object.set("z", coord.level);
+				object.set("x", coord.x);
+				object.set("y", coord.y);
+				object.set("error", message);
+				JsonValue::from(object)
+			})
+			.collect::<Vec<_>>();
+
+		let mut object = JsonObject::new();
+		object.set("tiles_checked", self.tiles_checked);
+		object.set("tiles_ok", self.tiles_ok);
+		object.set("errors", errors);
+		object.set("elapsed_ms", self.elapsed.as_millis() as u64);
+		JsonValue::from(object)
+	}
+
+	/// Writes a summary and the first errors to the CLI reporter.
+	#[cfg(feature = "cli")]
+	pub async fn pretty_print(&self, print: &PrettyPrint) {
+		print.add_key_value("tiles checked", &self.tiles_checked).await;
+		print.add_key_value("tiles ok", &self.tiles_ok).await;
+		print.add_key_value("errors", &self.errors.len()).await;
+		print.add_key_value("elapsed", &self.elapsed).await;
+		for (coord, message) in self.errors.iter().take(10) {
+			print.add_warning(&format!("{coord:?}: {message}")).await;
+		}
+		if self.errors.len() > 10 {
+			print
+				.add_warning(&format!("… and {} more errors", self.errors.len() - 10))
+				.await;
+		}
+	}
+}
+
+/// Checks all tiles of `source` at the given level.
+///
+/// Read errors of the source itself are returned as errors; problems with single tiles are
+/// collected in the report.
+pub async fn verify_tiles<S: TileSource + ?Sized>(source: &S, level: VerifyLevel) -> Result<VerifyReport> {
+	let start = Instant::now();
+	let mut report = VerifyReport::default();
+
+	for bbox in source.metadata().bbox_pyramid.iter_bboxes() {
+		let mut results = source
+			.get_tile_stream(*bbox)
+			.await?
+			// keep the messages of failed checks out of the stream's error handling, which
+			// would prefix them with the coordinate that the report already contains
+			.map_item_parallel(move |tile| Ok(check_tile(tile, level).map_err(|err| format!("{err:#}"))))
+			.to_vec()
+			.await?;
+		results.sort_by_key(|(coord, _)| (coord.y, coord.x));
+
+		for (coord, result) in results {
+			report.tiles_checked += 1;
+			match result? {
+				Ok(()) => report.tiles_ok += 1,
+				Err(message) => report.errors.push((coord, message)),
+			}
+		}
+	}
+
+	report.elapsed = start.elapsed();
+	Ok(report)
+}
+
+fn check_tile(mut tile: Tile, level: VerifyLevel) -> Result<()> {
+	let compression = tile.compression();
+	let blob = tile.as_blob(compression)?;
+	ensure!(!blob.is_empty(), "tile is empty");
+	if compression == TileCompression::Gzip {
+		ensure!(
+			blob.as_slice().starts_with(&[0x1f, 0x8b]),
+			"tile is not gzip compressed"
+		);
+	}
+	if level == VerifyLevel::Header {
+		return Ok(());
+	}
+
+	tile.as_blob(TileCompression::Uncompressed)?;
+	if level == VerifyLevel::Decompress {
+		return Ok(());
+	}
+
+	match tile.format().to_type() {
+		TileType::Raster => {
+			tile.as_image()?;
+		}
+		TileType::Vector => {
+			tile.as_vector()?;
+		}
+		TileType::Unknown => {}
+	}
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{MockReader, MockReaderProfile};
+	use versatiles_core::{Blob, TileBBoxPyramid, TileFormat};
+
+	/// Delivers the same blob for every tile of zoom levels 0 and 1.
+	fn blob_source(blob: &[u8], format: TileFormat, compression: TileCompression) -> Result<MockReader> {
+		let tiles = TileBBoxPyramid::new_full(1)
+			.iter_levels()
+			.flat_map(|bbox| bbox.iter_coords().collect::<Vec<_>>())
+			.map(|coord| (coord, Tile::from_blob(Blob::from(blob), compression, format)))
+			.collect();
+		MockReader::from_tiles(tiles)
+	}
+
+	#[tokio::test]
+	async fn valid_tiles() -> Result<()> {
+		let reader = MockReader::new_mock_profile(MockReaderProfile::Png)?;
+		let report = reader.deep_verify(VerifyLevel::Decode).await?;
+		assert!(report.is_ok());
+		assert_eq!(report.tiles_checked, reader.metadata().bbox_pyramid.count_tiles());
+		assert_eq!(report.tiles_ok, report.tiles_checked);
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn levels() -> Result<()> {
+		// a gzip header followed by garbage
+		let source = blob_source(&[0x1f, 0x8b, 0, 0], TileFormat::MVT, TileCompression::Gzip)?;
+		assert!(verify_tiles(&source, VerifyLevel::Header).await?.is_ok());
+		let report = verify_tiles(&source, VerifyLevel::Decompress).await?;
+		assert_eq!((report.tiles_checked, report.tiles_ok), (5, 0));
+
+		// uncompressed garbage
+		let source = blob_source(b"garbage", TileFormat::PNG, TileCompression::Uncompressed)?;
+		assert!(verify_tiles(&source, VerifyLevel::Decompress).await?.is_ok());
+		let report = verify_tiles(&source, VerifyLevel::Decode).await?;
+		assert_eq!(report.errors.len(), 5);
+		assert_eq!(report.errors[0].0, TileCoord::new(0, 0, 0)?);
+
+		// not compressed at all
+		let source = blob_source(b"garbage", TileFormat::MVT, TileCompression::Gzip)?;
+		let report = verify_tiles(&source, VerifyLevel::Header).await?;
+		assert_eq!(report.errors[0].1, "tile is not gzip compressed");
+		Ok(())
+	}
+
+	#[test]
+	fn json() -> Result<()> {
+		let report = VerifyReport {
+			tiles_checked: 3,
+			tiles_ok: 2,
+			errors: vec![(TileCoord::new(1, 0, 1)?, String::from("tile is empty"))],
+			elapsed: Duration::from_millis(1234),
+		};
+		assert_eq!(
+			report.as_json_value().stringify(),
+			r#"{"elapsed_ms":1234,"errors":[{"error":"tile is empty","x":0,"y":1,"z":1}],"tiles_checked":3,"tiles_ok":2}"#
+		);
+		assert!(!report.is_ok());
+		Ok(())
+	}
+
+	#[tokio::test]
+	#[cfg(feature = "cli")]
+	async fn pretty_print() -> Result<()> {
+		let report = VerifyReport {
+			tiles_checked: 3,
+			tiles_ok: 2,
+			errors: vec![(TileCoord::new(1, 0, 1)?, String::from("tile is empty"))],
+			elapsed: Duration::from_millis(1234),
+		};
+		let mut printer = PrettyPrint::new();
+		report.pretty_print(&printer.get_category("verify").await).await;
+		let output = printer.as_string().await;
+		assert!(output.contains("tiles checked: 3"), "{output}");
+		assert!(output.contains("TileCoord(1, [0, 1]): tile is empty"), "{output}");
+		Ok(())
+	}
+}
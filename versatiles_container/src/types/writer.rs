@@ -34,7 +34,8 @@ use crate::{TileSource, TilesRuntime};
 use anyhow::Result;
 use async_trait::async_trait;
 use std::path::Path;
-use versatiles_core::io::*;
+use versatiles_core::{Blob, io::*, utils::compress};
+use versatiles_derive::context;
 
 /// Object‑safe interface for writing tiles from a reader into a container format.
 ///
@@ -74,3 +75,51 @@ pub trait TilesWriter: Send {
 		runtime: TilesRuntime,
 	) -> Result<()>;
 }
+
+/// Encodes the TileJSON of `reader` for storage next to its tiles.
+///
+/// The TileJSON is compressed with the same `tile_compression` that is used for the tiles,
+/// so metadata and tiles can't drift apart. Returns the filename, `tiles.json[.<br|gz>]`,
+/// together with the compressed blob.
+///
+/// Used by container writers that store metadata as a file (directory, tar).
+#[context("encoding TileJSON file")]
+pub fn encode_tilejson_file(reader: &dyn TileSource) -> Result<(String, Blob)> {
+	let tile_compression = reader.metadata().tile_compression;
+	let filename = format!("tiles.json{}", tile_compression.as_extension());
+	let blob = compress(reader.tilejson().into(), tile_compression)?;
+	Ok((filename, blob))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{MockReader, TileSourceMetadata};
+	use rstest::rstest;
+	use versatiles_core::{TileBBoxPyramid, TileCompression, TileFormat, utils::decompress};
+
+	#[rstest]
+	#[case(TileCompression::Uncompressed, "tiles.json")]
+	#[case(TileCompression::Gzip, "tiles.json.gz")]
+	#[case(TileCompression::Brotli, "tiles.json.br")]
+	fn tilejson_file_matches_tile_compression(
+		#[case] compression: TileCompression,
+		#[case] expected_filename: &str,
+	) -> Result<()> {
+		let reader = MockReader::new_mock(
+			TileSourceMetadata::new_builder()
+				.with_format(TileFormat::MVT)
+				.with_compression(compression)
+				.with_bbox_pyramid(TileBBoxPyramid::new_full(2))
+				.build()?,
+		)?;
+
+		let (filename, blob) = encode_tilejson_file(&reader)?;
+		assert_eq!(filename, expected_filename);
+		assert_eq!(
+			decompress(blob, compression)?.as_str(),
+			"{\"tilejson\":\"3.0.0\",\"type\":\"dummy\"}"
+		);
+		Ok(())
+	}
+}
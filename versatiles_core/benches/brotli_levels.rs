@@ -0,0 +1,36 @@
+//! Compares Brotli quality levels 1 (fast), 6 (balanced) and 11 (maximum) on a real vector tile.
+//!
+//! Prints the compression ratio of each level once; criterion reports the throughput.
+
+use criterion::{Criterion, Throughput, criterion_group, criterion_main};
+use std::hint::black_box;
+use versatiles_core::{Blob, utils::compress_brotli_level};
+
+const LEVELS: [u32; 3] = [1, 6, 11];
+
+fn benchmark_brotli_levels(c: &mut Criterion) {
+	let blob = Blob::from(std::fs::read("../testdata/shortbread-tile.pbf").unwrap());
+
+	let mut group = c.benchmark_group("brotli_level");
+	group.throughput(Throughput::Bytes(blob.len()));
+	for level in LEVELS {
+		let compressed = compress_brotli_level(&blob, level).unwrap();
+		println!(
+			"level {level:>2}: {} -> {} bytes, ratio {:.3}",
+			blob.len(),
+			compressed.len(),
+			compressed.len() as f64 / blob.len() as f64
+		);
+		group.bench_function(format!("level {level}"), |b| {
+			b.iter(|| compress_brotli_level(black_box(&blob), level).unwrap());
+		});
+	}
+	group.finish();
+}
+
+criterion_group!(
+	name = benches;
+	config = Criterion::default().significance_level(0.1).sample_size(10);
+	targets = benchmark_brotli_levels
+);
+criterion_main!(benches);
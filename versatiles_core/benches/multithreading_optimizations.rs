@@ -56,7 +56,7 @@ fn bench_tile_stream_concurrency(c: &mut Criterion) {
 			let work = Arc::clone(&cpu_work);
 			rt.block_on(async {
 				let stream = TileStream::from_iter_coord_parallel(coords_clone.into_iter(), move |c| work(c));
-				stream.drain_and_count().await
+				stream.drain_and_count().await.unwrap()
 			})
 		});
 	});
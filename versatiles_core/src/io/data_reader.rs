@@ -3,7 +3,8 @@
 //! # Overview
 //!
 //! The `DataReaderTrait` trait provides an interface for reading data from different sources. Implementations
-//! of this trait can read specific ranges of bytes or all the data from the source. This module also defines
+//! of this trait read specific ranges of bytes; reading all the data is provided on top of that for sources
+//! that know their size. This module also defines
 //! the `DataReader` type alias for a boxed dynamic implementation of the trait.
 //!
 //! # Examples
@@ -26,8 +27,8 @@
 //!         Ok(Blob::from(data_slice.to_vec()))
 //!     }
 //!
-//!     async fn read_all(&self) -> Result<Blob> {
-//!         Ok(Blob::from(self.data.clone()))
+//!     fn get_size(&self) -> Option<u64> {
+//!         Some(self.data.len() as u64)
 //!     }
 //!
 //!     fn get_name(&self) -> &str {
@@ -54,7 +55,7 @@
 //! ```
 
 use crate::{Blob, ByteRange};
-use anyhow::Result;
+use anyhow::{Result, anyhow};
 use async_trait::async_trait;
 use std::fmt::Debug;
 
@@ -65,8 +66,11 @@ pub type DataReader = Box<dyn DataReaderTrait>;
 ///
 /// # Required Methods
 /// - `read_range`: Reads a specific range of bytes from the data source.
-/// - `read_all`: Reads all the data from the data source.
 /// - `get_name`: Gets the name of the data source.
+///
+/// # Provided Methods
+/// - `get_size`: Gets the size of the data source, if known.
+/// - `read_all`: Reads all the data from the data source.
 #[async_trait]
 pub trait DataReaderTrait: Debug + Send + Sync {
 	/// Reads a specific range of bytes from the data source.
@@ -80,13 +84,32 @@ pub trait DataReaderTrait: Debug + Send + Sync {
 	/// * A Result containing a Blob with the read data or an error.
 	async fn read_range(&self, range: &ByteRange) -> Result<Blob>;
 
+	/// Gets the size of the data source in bytes.
+	///
+	/// # Returns
+	///
+	/// * The size in bytes, or `None` if it is unknown without reading the data.
+	fn get_size(&self) -> Option<u64> {
+		None
+	}
+
 	/// Reads all the data from the data source.
 	///
+	/// The default implementation reads the range from 0 to [`get_size`](Self::get_size).
+	/// Sources of unknown size must override it.
+	///
 	/// # Returns
 	///
 	/// * A Result containing a Blob with all the data or an error.
-	#[allow(dead_code)]
-	async fn read_all(&self) -> Result<Blob>;
+	async fn read_all(&self) -> Result<Blob> {
+		let size = self.get_size().ok_or_else(|| {
+			anyhow!(
+				"can't read all data from '{}', because its size is unknown",
+				self.get_name()
+			)
+		})?;
+		self.read_range(&ByteRange::new(0, size)).await
+	}
 
 	/// Gets the name of the data source.
 	///
@@ -95,3 +118,51 @@ pub trait DataReaderTrait: Debug + Send + Sync {
 	/// * A string slice representing the name of the data source.
 	fn get_name(&self) -> &str;
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[derive(Debug)]
+	struct TestReader {
+		data: Vec<u8>,
+		size_known: bool,
+	}
+
+	#[async_trait]
+	impl DataReaderTrait for TestReader {
+		async fn read_range(&self, range: &ByteRange) -> Result<Blob> {
+			Ok(Blob::from(&self.data[range.as_range_usize()]))
+		}
+
+		fn get_size(&self) -> Option<u64> {
+			self.size_known.then_some(self.data.len() as u64)
+		}
+
+		fn get_name(&self) -> &str {
+			"test"
+		}
+	}
+
+	#[tokio::test]
+	async fn read_all_uses_read_range() -> Result<()> {
+		let reader = TestReader {
+			data: vec![1, 2, 3, 4],
+			size_known: true,
+		};
+		assert_eq!(reader.read_all().await?.as_slice(), &[1, 2, 3, 4]);
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn read_all_fails_for_unknown_size() {
+		let reader = TestReader {
+			data: vec![1, 2, 3, 4],
+			size_known: false,
+		};
+		assert_eq!(
+			reader.read_all().await.unwrap_err().to_string(),
+			"can't read all data from 'test', because its size is unknown"
+		);
+	}
+}
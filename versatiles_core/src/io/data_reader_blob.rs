@@ -84,14 +84,9 @@ impl DataReaderTrait for DataReaderBlob {
 		Ok(Blob::from(&blob[start..end]))
 	}
 
-	/// Reads all the data from the reader.
-	///
-	/// # Returns
-	///
-	/// * A Result containing a Blob with all the data or an error.
-	#[context("while reading all data from DataReaderBlob")]
-	async fn read_all(&self) -> Result<Blob> {
-		Ok(Blob::from(self.blob.get_ref()))
+	/// Gets the size of the data in bytes.
+	fn get_size(&self) -> Option<u64> {
+		Some(self.blob.get_ref().len() as u64)
 	}
 
 	/// Gets the name of the data source.
@@ -121,38 +121,9 @@ impl DataReaderTrait for DataReaderFile {
 		Ok(Blob::from(buffer))
 	}
 
-	/// Reads all the data from the file.
-	///
-	/// # Returns
-	///
-	/// * A Result containing a Blob with all the data or an error.
-	///
-	/// # Thread Safety
-	///
-	/// This method uses position-independent I/O which is thread-safe.
-	#[context("while reading all bytes from file '{}'", self.name)]
-	async fn read_all(&self) -> Result<Blob> {
-		let mut buffer = vec![0; self.size as usize];
-
-		#[cfg(unix)]
-		{
-			use std::os::unix::fs::FileExt;
-			self
-				.file
-				.read_exact_at(&mut buffer, 0)
-				.with_context(|| format!("failed to read all {} bytes from file '{}'", self.size, self.name))?;
-		}
-
-		#[cfg(windows)]
-		{
-			use std::os::windows::fs::FileExt;
-			self
-				.file
-				.seek_read(&mut buffer, 0)
-				.with_context(|| format!("failed to read all {} bytes from file '{}'", self.size, self.name))?;
-		}
-
-		Ok(Blob::from(buffer))
+	/// Gets the size of the file in bytes.
+	fn get_size(&self) -> Option<u64> {
+		Some(self.size)
 	}
 
 	/// Gets the name of the data source.
@@ -336,6 +336,62 @@ impl Blob {
 		result
 	}
 
+	/// Returns the 64-bit XXH3 hash of the bytes.
+	///
+	/// XXH3 is very fast and well distributed, but not cryptographically secure. Use it for
+	/// deduplication, caching and quick comparisons. The value is stable across runs and platforms.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use versatiles_core::Blob;
+	///
+	/// assert_eq!(Blob::new_empty().xxhash(), 0x2d06_8005_38d3_94c2);
+	/// assert_ne!(Blob::from("tile a").xxhash(), Blob::from("tile b").xxhash());
+	/// ```
+	#[must_use]
+	pub fn xxhash(&self) -> u64 {
+		xxhash_rust::xxh3::xxh3_64(&self.0)
+	}
+
+	/// Returns the SHA-256 digest of the bytes.
+	///
+	/// Slower than [`xxhash`](Self::xxhash), but suitable where collisions must be practically
+	/// impossible, e.g. for content-addressed storage.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use versatiles_core::Blob;
+	///
+	/// let digest = Blob::new_empty().sha256();
+	/// assert_eq!(digest[0..4], [0xe3, 0xb0, 0xc4, 0x42]);
+	/// ```
+	#[must_use]
+	pub fn sha256(&self) -> [u8; 32] {
+		use sha2::{Digest, Sha256};
+		Sha256::digest(&self.0).into()
+	}
+
+	/// Returns `true` if both blobs contain the same bytes.
+	///
+	/// Different lengths or hashes rule out equality early; only blobs with matching hashes
+	/// are compared byte by byte.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use versatiles_core::Blob;
+	///
+	/// let blob = Blob::from("tile");
+	/// assert!(blob.is_equal_to(&Blob::from("tile")));
+	/// assert!(!blob.is_equal_to(&Blob::from("tilf")));
+	/// ```
+	#[must_use]
+	pub fn is_equal_to(&self, other: &Blob) -> bool {
+		self.0.len() == other.0.len() && self.xxhash() == other.xxhash() && self.0 == other.0
+	}
+
 	/// Returns the length of the underlying byte slice.
 	///
 	/// # Examples
@@ -836,4 +892,31 @@ mod tests {
 
 		Ok(())
 	}
+
+	#[test]
+	fn test_xxhash() {
+		let blob = Blob::from("Hello, world!");
+		assert_eq!(blob.xxhash(), 0xf3c3_4bf1_1915_e869);
+		assert_eq!(blob.xxhash(), blob.clone().xxhash());
+		assert_ne!(blob.xxhash(), Blob::from("Hello, world?").xxhash());
+	}
+
+	#[test]
+	fn test_sha256() {
+		let blob = Blob::from("Hello, world!");
+		assert_eq!(
+			Blob::from(&blob.sha256()).as_hex().replace(' ', ""),
+			"315f5bdb76d078c43b8ac0064e4a0164612b1fce77c869345bfc94c75894edd3"
+		);
+		assert_ne!(blob.sha256(), Blob::from("Hello, world?").sha256());
+	}
+
+	#[test]
+	fn test_is_equal_to() {
+		let blob = Blob::from("Hello, world!");
+		assert!(blob.is_equal_to(&Blob::from("Hello, world!")));
+		assert!(!blob.is_equal_to(&Blob::from("Hello, world?")));
+		assert!(!blob.is_equal_to(&Blob::from("Hello")));
+		assert!(Blob::new_empty().is_equal_to(&Blob::new_empty()));
+	}
 }
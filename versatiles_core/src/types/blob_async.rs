@@ -0,0 +1,64 @@
+//! Conversions between [`Blob`] and tokio's asynchronous I/O traits.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use versatiles_core::Blob;
+//!
+//! # tokio::runtime::Runtime::new().unwrap().block_on(async {
+//! let reader = Blob::from("Xylofön").into_async_cursor();
+//! let blob = Blob::from_async_reader(reader).await.unwrap();
+//! assert_eq!(blob.as_str(), "Xylofön");
+//! # });
+//! ```
+
+use super::Blob;
+use anyhow::Result;
+use std::io::Cursor;
+use tokio::io::{AsyncRead, AsyncReadExt};
+use versatiles_derive::context;
+
+impl Blob {
+	/// Reads `reader` to the end and returns the bytes as a `Blob`.
+	#[context("reading blob from async reader")]
+	pub async fn from_async_reader(mut reader: impl AsyncRead + Unpin) -> Result<Blob> {
+		let mut buffer = Vec::new();
+		reader.read_to_end(&mut buffer).await?;
+		Ok(Blob::from(buffer))
+	}
+
+	/// Consumes the `Blob` and returns a cursor over its bytes, which implements [`AsyncRead`].
+	#[must_use]
+	pub fn into_async_cursor(self) -> Cursor<Vec<u8>> {
+		Cursor::new(self.into_vec())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[tokio::test]
+	async fn from_async_reader() -> Result<()> {
+		let data: Vec<u8> = (0..=255).cycle().take(100_000).collect();
+		let blob = Blob::from_async_reader(Cursor::new(data.clone())).await?;
+		assert_eq!(blob.len(), 100_000);
+		assert_eq!(blob.as_slice(), data.as_slice());
+
+		let blob = Blob::from_async_reader(Cursor::new(Vec::new())).await?;
+		assert!(blob.is_empty());
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn into_async_cursor() -> Result<()> {
+		let mut cursor = Blob::from(vec![1, 2, 3, 4, 5]).into_async_cursor();
+		let mut buffer = [0u8; 2];
+		cursor.read_exact(&mut buffer).await?;
+		assert_eq!(buffer, [1, 2]);
+
+		let rest = Blob::from_async_reader(cursor).await?;
+		assert_eq!(rest.as_slice(), &[3, 4, 5]);
+		Ok(())
+	}
+}
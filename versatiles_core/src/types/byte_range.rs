@@ -19,6 +19,7 @@
 //! assert_eq!(range.as_range_usize().end, 65); // 23 + 42 = 65
 //! ```
 
+use anyhow::{Result, ensure};
 use std::fmt;
 use std::ops::Range;
 
@@ -170,6 +171,87 @@ impl ByteRange {
 		self.offset = self.offset.wrapping_sub(offset);
 	}
 
+	/// Splits the `ByteRange` into two adjacent ranges at `at`, relative to `offset`.
+	///
+	/// The first range covers `at` bytes, the second one the rest.
+	///
+	/// # Errors
+	///
+	/// Returns an error if `at` is larger than `length`.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use versatiles_core::ByteRange;
+	///
+	/// let (a, b) = ByteRange::new(10, 5).split(2).unwrap();
+	/// assert_eq!(a, ByteRange::new(10, 2));
+	/// assert_eq!(b, ByteRange::new(12, 3));
+	/// ```
+	pub fn split(&self, at: u64) -> Result<(ByteRange, ByteRange)> {
+		ensure!(
+			at <= self.length,
+			"can't split {self:?} at {at}, because it is only {} bytes long",
+			self.length
+		);
+		Ok((
+			ByteRange::new(self.offset, at),
+			ByteRange::new(self.offset + at, self.length - at),
+		))
+	}
+
+	/// Returns `true` if `offset` lies within `[self.offset, self.offset + self.length)`.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use versatiles_core::ByteRange;
+	///
+	/// let range = ByteRange::new(10, 5);
+	/// assert!(range.contains(10));
+	/// assert!(range.contains(14));
+	/// assert!(!range.contains(15));
+	/// ```
+	#[must_use]
+	pub fn contains(&self, offset: u64) -> bool {
+		offset >= self.offset && offset - self.offset < self.length
+	}
+
+	/// Returns the range spanning all `ranges`, if they are contiguous.
+	///
+	/// The order of `ranges` does not matter. Returns `None` if `ranges` is empty,
+	/// if there are gaps or overlaps between them, or if the merged range would end
+	/// beyond `u64::MAX`.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use versatiles_core::ByteRange;
+	///
+	/// let ranges = [ByteRange::new(15, 5), ByteRange::new(10, 5)];
+	/// assert_eq!(ByteRange::merge(&ranges), Some(ByteRange::new(10, 10)));
+	///
+	/// let ranges = [ByteRange::new(10, 5), ByteRange::new(16, 5)];
+	/// assert_eq!(ByteRange::merge(&ranges), None);
+	/// ```
+	#[must_use]
+	pub fn merge(ranges: &[ByteRange]) -> Option<ByteRange> {
+		let mut ranges = ranges.to_vec();
+		ranges.sort_by_key(|range| range.offset);
+
+		let mut iter = ranges.into_iter();
+		let mut merged = iter.next()?;
+		merged.offset.checked_add(merged.length)?;
+		for range in iter {
+			if range.offset != merged.offset + merged.length {
+				return None;
+			}
+			merged.length = merged.length.checked_add(range.length)?;
+			merged.offset.checked_add(merged.length)?;
+		}
+		Some(merged)
+	}
+
 	/// Converts the `ByteRange` to a `std::ops::Range<usize>`.
 	///
 	/// # Returns
@@ -274,6 +356,59 @@ mod tests {
 		assert_eq!(range.offset, 15, "Offset should be 20 - 5 = 15");
 	}
 
+	#[test]
+	fn test_split() {
+		let range = ByteRange::new(10, 5);
+		assert_eq!(range.split(0).unwrap(), (ByteRange::new(10, 0), ByteRange::new(10, 5)));
+		assert_eq!(range.split(3).unwrap(), (ByteRange::new(10, 3), ByteRange::new(13, 2)));
+		assert_eq!(range.split(5).unwrap(), (ByteRange::new(10, 5), ByteRange::new(15, 0)));
+		assert_eq!(
+			range.split(6).unwrap_err().to_string(),
+			"can't split ByteRange[10,5] at 6, because it is only 5 bytes long"
+		);
+	}
+
+	#[test]
+	fn test_contains() {
+		let range = ByteRange::new(10, 5);
+		assert!(!range.contains(9));
+		assert!(range.contains(10));
+		assert!(range.contains(14));
+		assert!(!range.contains(15));
+		assert!(!ByteRange::new(10, 0).contains(10));
+	}
+
+	#[test]
+	fn test_merge() {
+		let r = ByteRange::new;
+		assert_eq!(ByteRange::merge(&[]), None);
+		assert_eq!(ByteRange::merge(&[r(3, 4)]), Some(r(3, 4)));
+		assert_eq!(ByteRange::merge(&[r(3, 4), r(7, 1), r(8, 2)]), Some(r(3, 7)));
+		assert_eq!(ByteRange::merge(&[r(8, 2), r(3, 4), r(7, 1)]), Some(r(3, 7)));
+		// gap
+		assert_eq!(ByteRange::merge(&[r(3, 4), r(8, 2)]), None);
+		// overlap
+		assert_eq!(ByteRange::merge(&[r(3, 4), r(6, 2)]), None);
+	}
+
+	#[test]
+	fn test_merge_near_u64_max() {
+		let r = ByteRange::new;
+		let max = u64::MAX;
+		// ends exactly at u64::MAX
+		assert_eq!(ByteRange::merge(&[r(max - 3, 2), r(max - 1, 1)]), Some(r(max - 3, 3)));
+		assert_eq!(
+			ByteRange::merge(&[r(max - 3, 2), r(max - 1, 1), r(max, 0)]),
+			Some(r(max - 3, 3))
+		);
+		assert_eq!(ByteRange::merge(&[r(max - 2, 1), r(max - 1, 1)]), Some(r(max - 2, 2)));
+		assert_eq!(ByteRange::merge(&[r(max - 1, 1), r(max, 0)]), Some(r(max - 1, 1)));
+		// the end of the merged range overflows
+		assert_eq!(ByteRange::merge(&[r(max - 1, 1), r(max, 1)]), None);
+		assert_eq!(ByteRange::merge(&[r(max, 1)]), None);
+		assert_eq!(ByteRange::merge(&[r(0, max), r(max, max)]), None);
+	}
+
 	/// Verifies that debug output matches the expected format.
 	#[test]
 	fn test_debug() {
@@ -8,6 +8,7 @@
 //!
 //! ## Supported operations
 //! * Strict constructor [`GeoBBox::new`], plus [`GeoBBox::new_normalized`] for unordered input.
+//! * Bounds of a WKT `POLYGON` or `MULTIPOLYGON` via [`GeoBBox::from_wkt`].
 //! * In‑place clamp to the valid Web‑Mercator domain via [`GeoBBox::limit_to_mercator`].
 //! * Set/return as tuple/array/vec/strings; extend & intersect (mutating and non‑mutating).
 //! * Conversion to EPSG:3857 using the spherical Web‑Mercator formulas.
@@ -15,7 +16,7 @@
 //! ## Antimeridian & empties
 //! * Bounding boxes are **not** wrapped across the antimeridian; all input coordinates must lie within `[-180, 180]` longitude and `[-90, 90]` latitude.
 //! * Some operations (such as [`intersect`]) may yield an "empty" box with `x_min > x_max` or `y_min > y_max` to signal that there is no overlap.
-use anyhow::{Result, ensure};
+use anyhow::{Result, bail, ensure};
 use std::fmt::Debug;
 use versatiles_derive::context;
 
@@ -163,6 +164,75 @@ impl GeoBBox {
 		}
 	}
 
+	/// Returns the bounding box of all coordinates of a WKT `POLYGON` or `MULTIPOLYGON`
+	/// in degrees, e.g. a coverage area exported from PostGIS.
+	///
+	/// Only 2D coordinates are supported.
+	///
+	/// # Errors
+	/// Returns an error if the text isn't a non-empty (multi)polygon or a coordinate is out of range.
+	///
+	/// # Examples
+	/// ```
+	/// use versatiles_core::GeoBBox;
+	///
+	/// let bbox = GeoBBox::from_wkt("POLYGON((1 2, 5 2, 5 3, 1 2), (2 2.5, 3 2.5, 3 2.8, 2 2.5))").unwrap();
+	/// assert_eq!(bbox.as_tuple(), (1.0, 2.0, 5.0, 3.0));
+	/// ```
+	#[context("Failed to parse WKT '{wkt}'")]
+	pub fn from_wkt(wkt: &str) -> Result<GeoBBox> {
+		let text = wkt.trim().to_ascii_uppercase();
+		// number of nested parentheses around the coordinates
+		let (depth, rest) = if let Some(rest) = text.strip_prefix("MULTIPOLYGON") {
+			(3, rest)
+		} else if let Some(rest) = text.strip_prefix("POLYGON") {
+			(2, rest)
+		} else {
+			bail!("expected POLYGON or MULTIPOLYGON");
+		};
+
+		let mut bounds = [f64::INFINITY, f64::INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY];
+		let mut level = 0;
+		let mut token = String::new();
+		for c in rest.chars() {
+			match c {
+				'(' => {
+					ensure!(token.trim().is_empty(), "unexpected '{}'", token.trim());
+					ensure!(level < depth, "too many nested parentheses");
+					level += 1;
+				}
+				')' | ',' => {
+					if level == depth {
+						let values = token
+							.split_whitespace()
+							.map(str::parse::<f64>)
+							.collect::<Result<Vec<f64>, _>>()?;
+						ensure!(values.len() == 2, "expected 2D coordinates, found '{}'", token.trim());
+						bounds = [
+							bounds[0].min(values[0]),
+							bounds[1].min(values[1]),
+							bounds[2].max(values[0]),
+							bounds[3].max(values[1]),
+						];
+					} else {
+						ensure!(token.trim().is_empty(), "unexpected '{}'", token.trim());
+					}
+					token.clear();
+					if c == ')' {
+						ensure!(level > 0, "unbalanced parentheses");
+						level -= 1;
+					}
+				}
+				_ => token.push(c),
+			}
+		}
+		ensure!(token.trim().is_empty(), "unexpected '{}'", token.trim());
+		ensure!(level == 0, "unbalanced parentheses");
+		ensure!(bounds[0] <= bounds[2], "no coordinates found");
+
+		GeoBBox::new(bounds[0], bounds[1], bounds[2], bounds[3])
+	}
+
 	/// Clamps the bounding box *in‑place* to the latitude/longitude limits of the
 	/// Web Mercator projection.
 	///
@@ -487,6 +557,36 @@ mod tests {
 		assert_eq!(bbox.y_max, 5.0);
 	}
 
+	#[rstest]
+	#[case("POLYGON((0 0, 10 0, 10 5, 0 0))", [0.0, 0.0, 10.0, 5.0])]
+	#[case(" polygon ( (-1.5 -2, 3 4e1,-1.5 -2) ) ", [-1.5, -2.0, 3.0, 40.0])]
+	#[case(
+		"MULTIPOLYGON(((0 0, 1 0, 1 1, 0 0)), ((-20 -10, -19 -10, -19 -9, -20 -10), (1 1, 2 2, 1 2, 1 1)))",
+		[-20.0, -10.0, 2.0, 2.0]
+	)]
+	fn test_from_wkt(#[case] wkt: &str, #[case] expected: [f64; 4]) -> Result<()> {
+		assert_eq!(GeoBBox::from_wkt(wkt)?.as_array(), expected);
+		Ok(())
+	}
+
+	#[rstest]
+	#[case("POINT(1 2)", "expected POLYGON or MULTIPOLYGON")]
+	#[case("POLYGON EMPTY", "unexpected 'EMPTY'")]
+	#[case("POLYGON Z((0 0 1, 1 0 1, 0 0 1))", "unexpected 'Z'")]
+	#[case("POLYGON((0 0, 1 0, 0 0)", "unbalanced parentheses")]
+	#[case("POLYGON((0 0, 1 0, 0 0)))", "unbalanced parentheses")]
+	#[case("POLYGON(((0 0, 1 0, 0 0)))", "too many nested parentheses")]
+	#[case("MULTIPOLYGON((0 0, 1 0, 0 0))", "unexpected '0 0'")]
+	#[case("POLYGON((0 0 0, 1 0 0, 0 0 0))", "expected 2D coordinates, found '0 0 0'")]
+	#[case("POLYGON((0 0, 1 a, 0 0))", "invalid float literal")]
+	#[case("POLYGON(())", "expected 2D coordinates, found ''")]
+	#[case("POLYGON((0 0, 200 0, 0 0))", "x_max (200) must be <= 180")]
+	fn test_from_wkt_errors(#[case] wkt: &str, #[case] message: &str) {
+		let err = GeoBBox::from_wkt(wkt).unwrap_err();
+		assert_eq!(err.to_string(), format!("Failed to parse WKT '{wkt}'"));
+		assert_eq!(err.root_cause().to_string(), message);
+	}
+
 	#[test]
 	fn test_from_option_vec() -> Result<()> {
 		// Some valid Vec
@@ -78,6 +78,27 @@ where
 		}
 	}
 
+	/// Creates a new `LimitedCache` that holds at most `maximum_length` entries.
+	///
+	/// # Panics
+	///
+	/// Panics if `maximum_length` is zero.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use versatiles_core::LimitedCache;
+	///
+	/// let cache: LimitedCache<u64, Vec<u8>> = LimitedCache::with_maximum_length(16);
+	/// assert_eq!(cache.capacity(), 16);
+	/// ```
+	#[must_use]
+	pub fn with_maximum_length(maximum_length: usize) -> Self {
+		Self {
+			cache: LruCache::new(NonZeroUsize::new(maximum_length).expect("maximum length must be greater than 0")),
+		}
+	}
+
 	/// Retrieves a cloned value from the cache by its key, updating the last access time.
 	///
 	/// If the key exists:
@@ -209,6 +230,22 @@ where
 	pub fn capacity(&self) -> usize {
 		self.cache.cap().get()
 	}
+
+	/// Iterates over all values, from most to least recently used, without updating access times.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use versatiles_core::LimitedCache;
+	///
+	/// let mut cache = LimitedCache::with_maximum_length(10);
+	/// cache.add("foo", 1);
+	/// cache.add("bar", 2);
+	/// assert_eq!(cache.values().sum::<i32>(), 3);
+	/// ```
+	pub fn values(&self) -> impl Iterator<Item = &V> {
+		self.cache.iter().map(|(_, value)| value)
+	}
 }
 
 impl<K, V> Debug for LimitedCache<K, V>
@@ -305,6 +342,18 @@ mod tests {
 		assert_eq!(cache.get(&5), Some(500));
 	}
 
+	/// Ensures that `with_maximum_length` limits the number of entries.
+	#[test]
+	fn test_maximum_length() {
+		let mut cache: LimitedCache<u64, Vec<u8>> = LimitedCache::with_maximum_length(2);
+		cache.add(1, vec![1]);
+		cache.add(2, vec![2; 1000]);
+		cache.add(3, vec![3]);
+		assert_eq!(cache.len(), 2);
+		assert_eq!(cache.get(&1), None);
+		assert_eq!(cache.values().map(Vec::len).sum::<usize>(), 1001);
+	}
+
 	/// Tests that accessing items updates their LRU position
 	#[test]
 	fn test_lru_updates_on_access() {
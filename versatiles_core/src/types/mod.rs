@@ -14,6 +14,7 @@
 //! - [`TileType`]: Tile content classification (raster, vector, unknown)
 //! - [`TileSchema`]: Tile schema identifiers (RGB, RGBA, OpenMapTiles, etc.)
 //! - [`TileSize`]: Pixel dimensions (256×256, 512×512)
+//! - [`TileGrid`]: Tile grids of other projections than Web Mercator
 //!
 //! # Data Handling
 //! - [`Blob`]: Binary data wrapper with utility methods
@@ -53,6 +54,8 @@
 mod blob;
 pub use blob::*;
 
+mod blob_async;
+
 mod byte_range;
 pub use byte_range::*;
 
@@ -86,6 +89,9 @@ pub use tile_coord::*;
 mod tile_format;
 pub use tile_format::*;
 
+mod tile_grid;
+pub use tile_grid::*;
+
 mod tilejson;
 pub use tilejson::*;
 
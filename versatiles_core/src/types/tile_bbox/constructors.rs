@@ -16,9 +16,11 @@
 //! ## Common tasks
 //! - Build from min+size: [`TileBBox::from_min_and_size`]
 //! - Build from min+max:  [`TileBBox::from_min_and_max`]
+//! - Build from untrusted input: [`TileBBox::try_new`]
 //! - Cover full level:    [`TileBBox::new_full`]
 //! - Empty at level:      [`TileBBox::new_empty`]
 //! - Convert from lon/lat: [`TileBBox::from_geo`]
+//! - Convert from WKT:     [`TileBBox::from_wkt`]
 //!
 //! ## Examples
 //! Create a 3×2 bbox at z=4 starting at (5,6):
@@ -109,13 +111,15 @@ impl TileBBox {
 			"height ({height}) + y_min ({y_min}) must be <= size ({size})"
 		);
 
-		Ok(TileBBox {
+		let bbox = TileBBox {
 			level,
 			x_min,
 			y_min,
 			width,
 			height,
-		})
+		};
+		bbox.assert_valid();
+		Ok(bbox)
 	}
 
 	/// Creates a new `TileBBox` with specified coordinates and zoom level.
@@ -157,13 +161,48 @@ impl TileBBox {
 		ensure!(x_max <= max, "x_max ({x_max}) must be <= max ({max})");
 		ensure!(y_max <= max, "y_max ({y_max}) must be <= max ({max})");
 
-		Ok(TileBBox {
+		let bbox = TileBBox {
 			level,
 			x_min,
 			y_min,
 			width: x_max + 1 - x_min,
 			height: y_max + 1 - y_min,
-		})
+		};
+		bbox.assert_valid();
+		Ok(bbox)
+	}
+
+	/// Creates a `TileBBox` from min and max coordinates that may come from untrusted input.
+	///
+	/// Works like [`TileBBox::from_min_and_max`], but accepts `u64` coordinates, so that values
+	/// parsed from user input can be validated without truncating them first.
+	///
+	/// # Errors
+	///
+	/// - If `level` > 31.
+	/// - If `x_min > x_max` or `y_min > y_max`.
+	/// - If any coordinate exceeds the maximum allowed by the zoom level.
+	///
+	/// # Example
+	/// ```
+	/// # use versatiles_core::TileBBox;
+	/// assert!(TileBBox::try_new(4, 1, 2, 3, 4).unwrap().is_valid());
+	/// assert!(TileBBox::try_new(4, 3, 2, 1, 4).is_err());
+	/// assert!(TileBBox::try_new(4, 0, 0, 1 << 40, 4).is_err());
+	/// ```
+	#[context("Failed to create TileBBox from min ({x_min}, {y_min}) and max ({x_max}, {y_max}) at level {level}")]
+	pub fn try_new(level: u8, x_min: u64, y_min: u64, x_max: u64, y_max: u64) -> Result<TileBBox> {
+		ensure!(level <= 31, "level ({level}) must be <= 31");
+
+		let max = (1u64 << level) - 1;
+
+		ensure!(x_min <= x_max, "x_min ({x_min}) must be <= x_max ({x_max})");
+		ensure!(y_min <= y_max, "y_min ({y_min}) must be <= y_max ({y_max})");
+		ensure!(x_max <= max, "x_max ({x_max}) must be <= max ({max})");
+		ensure!(y_max <= max, "y_max ({y_max}) must be <= max ({max})");
+
+		// All values are <= 2^31 - 1 now, so they fit into u32.
+		Self::from_min_and_max(level, x_min as u32, y_min as u32, x_max as u32, y_max as u32)
 	}
 
 	/// Creates a `TileBBox` covering the entire range of tiles at the specified zoom level.
@@ -216,13 +255,15 @@ impl TileBBox {
 	#[context("Failed to create empty TileBBox at level {level}")]
 	pub fn new_empty(level: u8) -> Result<TileBBox> {
 		ensure!(level <= 31, "level ({level}) must be <= 31");
-		Ok(TileBBox {
+		let bbox = TileBBox {
 			level,
 			x_min: 0,
 			y_min: 0,
 			width: 0,
 			height: 0,
-		})
+		};
+		bbox.assert_valid();
+		Ok(bbox)
 	}
 
 	/// Constructs a `TileBBox` from geographical coordinates.
@@ -265,6 +306,22 @@ impl TileBBox {
 		Self::from_min_and_max(level, p_min.x, p_min.y, p_max.x, p_max.y)
 	}
 
+	/// Creates a bounding box covering a WKT `POLYGON` or `MULTIPOLYGON` at the given zoom level.
+	///
+	/// Uses the bounds of all coordinates, see [`GeoBBox::from_wkt`], and converts them with
+	/// [`TileBBox::from_geo`].
+	///
+	/// # Example
+	/// ```
+	/// # use versatiles_core::TileBBox;
+	/// let bb = TileBBox::from_wkt(4, "POLYGON((0 0, 10 0, 10 10, 0 0))").unwrap();
+	/// assert_eq!(bb.as_array().unwrap(), [8, 7, 8, 7]);
+	/// ```
+	#[context("Failed to create TileBBox from WKT at level {level}")]
+	pub fn from_wkt(level: u8, wkt: &str) -> Result<TileBBox> {
+		Self::from_geo(level, &GeoBBox::from_wkt(wkt)?)
+	}
+
 	/// Returns whether the bounding box is empty.
 	///
 	/// A `TileBBox` is empty if its width or height is zero.
@@ -282,6 +339,42 @@ impl TileBBox {
 		self.width == 0 || self.height == 0
 	}
 
+	/// Returns whether the bounding box satisfies its invariants.
+	///
+	/// The level must be `<= 31` and, unless the bbox is empty, all tiles must lie
+	/// within `0..2^level` on both axes. Empty bboxes are valid regardless of their
+	/// minimum coordinates.
+	///
+	/// # Example
+	/// ```
+	/// # use versatiles_core::TileBBox;
+	/// assert!(TileBBox::from_min_and_max(3, 1, 2, 5, 7).unwrap().is_valid());
+	/// assert!(TileBBox::new_empty(3).unwrap().is_valid());
+	/// ```
+	#[must_use]
+	pub fn is_valid(&self) -> bool {
+		if self.level > 31 {
+			return false;
+		}
+		if self.is_empty() {
+			return true;
+		}
+		let size = 1u64 << self.level;
+		u64::from(self.x_min) + u64::from(self.width) <= size && u64::from(self.y_min) + u64::from(self.height) <= size
+	}
+
+	/// Checks the invariants of the bounding box, see [`TileBBox::is_valid`].
+	///
+	/// Called at the end of every constructor and mutating method.
+	///
+	/// # Panics
+	///
+	/// Panics in debug builds if the bbox is invalid. In release builds this is a no-op.
+	#[inline]
+	pub fn assert_valid(&self) {
+		debug_assert!(self.is_valid(), "invalid TileBBox: {self:?}");
+	}
+
 	/// Returns the total number of tiles covered by this bbox.
 	///
 	/// The count is computed as `width × height`.
@@ -372,6 +465,7 @@ impl TileBBox {
 	pub fn set_width(&mut self, width: u32) -> Result<()> {
 		ensure!(!self.is_empty(), "Cannot set width of an empty TileBBox");
 		self.width = width.min(self.max_count().saturating_sub(self.x_min));
+		self.assert_valid();
 		Ok(())
 	}
 
@@ -379,6 +473,7 @@ impl TileBBox {
 	pub fn set_height(&mut self, height: u32) -> Result<()> {
 		ensure!(!self.is_empty(), "Cannot set height of an empty TileBBox");
 		self.height = height.min(self.max_count().saturating_sub(self.y_min));
+		self.assert_valid();
 		Ok(())
 	}
 
@@ -443,6 +538,7 @@ impl TileBBox {
 		} else {
 			self.width = 0;
 		}
+		self.assert_valid();
 		Ok(())
 	}
 
@@ -460,6 +556,7 @@ impl TileBBox {
 		} else {
 			self.height = 0;
 		}
+		self.assert_valid();
 		Ok(())
 	}
 
@@ -469,6 +566,7 @@ impl TileBBox {
 			std::mem::swap(&mut self.x_min, &mut self.y_min);
 			std::mem::swap(&mut self.width, &mut self.height);
 		}
+		self.assert_valid();
 	}
 	/// Sets the bounding box to an empty state.
 	///
@@ -476,6 +574,7 @@ impl TileBBox {
 	pub fn set_empty(&mut self) {
 		self.width = 0;
 		self.height = 0;
+		self.assert_valid();
 	}
 
 	/// Sets the bbox to cover all tiles at its level (convenience for tests).
@@ -485,6 +584,7 @@ impl TileBBox {
 		self.y_min = 0;
 		self.width = max;
 		self.height = max;
+		self.assert_valid();
 	}
 
 	/// Sets the bounding box to a full state, covering the entire tile range at its zoom level.
@@ -515,6 +615,7 @@ impl TileBBox {
 		self.y_min = y_min;
 		self.width = width;
 		self.height = height;
+		self.assert_valid();
 		Ok(())
 	}
 
@@ -532,6 +633,7 @@ impl TileBBox {
 		self.y_min = y_min;
 		self.width = x_max - x_min + 1;
 		self.height = y_max - y_min + 1;
+		self.assert_valid();
 		Ok(())
 	}
 }
@@ -545,6 +647,19 @@ mod tests {
 	use super::*;
 	use rstest::rstest;
 
+	#[test]
+	fn from_wkt_germany() -> Result<()> {
+		let germany = "POLYGON((5.87 47.27, 15.04 47.27, 15.04 55.06, 5.87 55.06, 5.87 47.27))";
+		let bbox = TileBBox::from_wkt(8, germany)?;
+		assert_eq!(bbox.level, 8);
+		assert_eq!(bbox.as_array()?, [132, 80, 138, 89]);
+		assert!(bbox.contains(&TileCoord::new(8, 137, 83)?));
+
+		assert!(TileBBox::from_wkt(8, "POINT(10 50)").is_err());
+		assert!(TileBBox::from_wkt(32, germany).is_err());
+		Ok(())
+	}
+
 	#[rstest]
 	#[case((4, 5, 12, 5, 12), 1)]
 	#[case((4, 5, 12, 7, 15), 12)]
@@ -658,6 +773,54 @@ mod tests {
 		Ok(())
 	}
 
+	// ------------------------------
+	// try_new / is_valid
+	// ------------------------------
+	#[rstest]
+	#[case((0, 0, 0, 0, 0))]
+	#[case((4, 5, 6, 7, 9))]
+	#[case((31, 0, 0, (1 << 31) - 1, (1 << 31) - 1))]
+	fn try_new_valid(#[case] args: (u8, u64, u64, u64, u64)) -> Result<()> {
+		let (lvl, x0, y0, x1, y1) = args;
+		let bb = TileBBox::try_new(lvl, x0, y0, x1, y1)?;
+		assert!(bb.is_valid());
+		assert_eq!(bb.as_array()?.map(u64::from), [x0, y0, x1, y1]);
+		Ok(())
+	}
+
+	#[rstest]
+	#[case((32, 0, 0, 0, 0), "level (32) must be <= 31")]
+	#[case((3, 5, 6, 4, 6), "x_min (5) must be <= x_max (4)")]
+	#[case((3, 5, 6, 5, 5), "y_min (6) must be <= y_max (5)")]
+	#[case((2, 0, 0, 5, 0), "x_max (5) must be <= max (3)")]
+	#[case((4, 0, 0, 0, 1 << 32), "y_max (4294967296) must be <= max (15)")]
+	fn try_new_invalid(#[case] args: (u8, u64, u64, u64, u64), #[case] message: &str) {
+		let (lvl, x0, y0, x1, y1) = args;
+		let err = TileBBox::try_new(lvl, x0, y0, x1, y1).unwrap_err();
+		assert_eq!(err.root_cause().to_string(), message);
+	}
+
+	#[test]
+	fn is_valid_checks_bounds() {
+		let mut bb = TileBBox::from_min_and_size(2, 1, 1, 3, 3).unwrap();
+		assert!(bb.is_valid());
+		bb.width = 4;
+		assert!(!bb.is_valid());
+		bb.set_empty();
+		assert!(bb.is_valid());
+		bb.level = 32;
+		assert!(!bb.is_valid());
+	}
+
+	#[test]
+	#[cfg(debug_assertions)]
+	#[should_panic(expected = "invalid TileBBox")]
+	fn assert_valid_panics_in_debug_builds() {
+		let mut bb = TileBBox::new_full(2).unwrap();
+		bb.x_min = 1;
+		bb.assert_valid();
+	}
+
 	#[rstest]
 	#[case(0)]
 	#[case(8)]
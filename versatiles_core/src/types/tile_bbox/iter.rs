@@ -84,6 +84,49 @@ impl TileBBox {
 				.filter(|bbox| !bbox.is_empty()),
 		)
 	}
+
+	/// Splits the bounding box into horizontal stripes of at most `max_rows` rows.
+	///
+	/// Every stripe spans the full x range of the bounding box, so reading the stripes in order
+	/// visits the tiles row by row, e.g. for sequential SQLite queries or file writes. The last
+	/// stripe may have fewer rows. An empty bounding box yields no stripes.
+	///
+	/// # Panics
+	///
+	/// Panics if `max_rows` is 0.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use versatiles_core::TileBBox;
+	///
+	/// let bbox = TileBBox::from_min_and_max(4, 2, 3, 9, 7).unwrap();
+	/// let stripes: Vec<TileBBox> = bbox.iter_bbox_row_slices(2).collect();
+	/// assert_eq!(stripes.len(), 3);
+	/// assert_eq!(stripes[2], TileBBox::from_min_and_max(4, 2, 7, 9, 7).unwrap());
+	/// ```
+	pub fn iter_bbox_row_slices(&self, max_rows: u32) -> impl Iterator<Item = TileBBox> + Send + use<> {
+		assert!(max_rows != 0, "max_rows must be greater than 0");
+
+		let level = self.level;
+		let ranges = if self.is_empty() {
+			None
+		} else {
+			Some((
+				self.x_min().unwrap(),
+				self.x_max().unwrap(),
+				self.y_min().unwrap(),
+				self.y_max().unwrap(),
+			))
+		};
+
+		ranges.into_iter().flat_map(move |(x_min, x_max, y_min, y_max)| {
+			(y_min..=y_max).step_by(max_rows as usize).map(move |y| {
+				let y_end = y.saturating_add(max_rows - 1).min(y_max);
+				TileBBox::from_min_and_max(level, x_min, y, x_max, y_end).unwrap()
+			})
+		})
+	}
 }
 
 #[cfg(test)]
@@ -182,6 +225,67 @@ mod tests {
 		Ok(())
 	}
 
+	// ------------------------------
+	// iter_bbox_row_slices
+	// ------------------------------
+	fn stripes(bb: &TileBBox, max_rows: u32) -> Vec<(u32, u32, u32, u32)> {
+		bb.iter_bbox_row_slices(max_rows)
+			.map(|s| {
+				(
+					s.x_min().unwrap(),
+					s.y_min().unwrap(),
+					s.x_max().unwrap(),
+					s.y_max().unwrap(),
+				)
+			})
+			.collect()
+	}
+
+	#[rstest]
+	#[case::smaller_than_stripe((6, 3, 4, 9, 6), 8, vec![(3, 4, 9, 6)])]
+	#[case::exact_multiple((6, 3, 4, 9, 9), 3, vec![(3, 4, 9, 6), (3, 7, 9, 9)])]
+	#[case::non_aligned((6, 3, 4, 9, 10), 3, vec![(3, 4, 9, 6), (3, 7, 9, 9), (3, 10, 9, 10)])]
+	#[case::single_rows((2, 0, 0, 3, 2), 1, vec![(0, 0, 3, 0), (0, 1, 3, 1), (0, 2, 3, 2)])]
+	#[case::full_level((1, 0, 0, 1, 1), 4, vec![(0, 0, 1, 1)])]
+	fn row_slices(
+		#[case] minmax: (u8, u32, u32, u32, u32),
+		#[case] max_rows: u32,
+		#[case] expected: Vec<(u32, u32, u32, u32)>,
+	) -> Result<()> {
+		let (z, x0, y0, x1, y1) = minmax;
+		let bb = TileBBox::from_min_and_max(z, x0, y0, x1, y1)?;
+		assert_eq!(stripes(&bb, max_rows), expected);
+
+		// the stripes cover every tile exactly once, in row-major order
+		let coords: Vec<_> = bb
+			.iter_bbox_row_slices(max_rows)
+			.flat_map(|s| s.into_iter_coords())
+			.collect();
+		assert_eq!(coords, bb.iter_coords().collect::<Vec<_>>());
+		Ok(())
+	}
+
+	#[test]
+	fn row_slices_of_empty_bbox() {
+		assert!(stripes(&TileBBox::new_empty(5).unwrap(), 4).is_empty());
+	}
+
+	#[test]
+	fn row_slices_at_max_level_edge() -> Result<()> {
+		// a stripe must not overflow the last row of a deep level
+		let max = (1u32 << 30) - 1;
+		let bb = TileBBox::from_min_and_max(30, 0, max - 2, 1, max)?;
+		assert_eq!(stripes(&bb, 2), vec![(0, max - 2, 1, max - 1), (0, max, 1, max)]);
+		Ok(())
+	}
+
+	#[test]
+	#[should_panic(expected = "max_rows must be greater than 0")]
+	fn row_slices_panic_on_zero_rows() {
+		let bb = TileBBox::from_min_and_max(4, 0, 0, 3, 3).unwrap();
+		let _ = bb.iter_bbox_row_slices(0).count();
+	}
+
 	#[test]
 	#[should_panic(expected = "size must be greater than 0")]
 	fn grid_panics_on_zero_size() {
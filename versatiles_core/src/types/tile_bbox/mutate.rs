@@ -5,7 +5,8 @@
 //! scaling, zoom-level changes, rounding to block boundaries, and vertical
 //! flipping to match XYZ/TMS conventions.
 //!
-//! All operations preserve invariants:
+//! All operations preserve invariants, which are checked with
+//! [`TileBBox::assert_valid`] in debug builds:
 //! * Coordinates are clamped to the valid range for the bbox level.
 //! * Empty bboxes remain empty unless explicitly expanded.
 //! * Methods that cannot fail are infallible; those that validate inputs
@@ -50,6 +51,7 @@ impl TileBBox {
 				self.set_y_max(y).unwrap();
 			}
 		}
+		self.assert_valid();
 	}
 
 	/// Includes a tile coordinate (`TileCoord`) within the bounding box.
@@ -160,6 +162,7 @@ impl TileBBox {
 			)?
 		}
 
+		self.assert_valid();
 		Ok(())
 	}
 
@@ -210,14 +213,16 @@ impl TileBBox {
 			self.set_min_and_max(x_min, y_min, x_max, y_max)?;
 		}
 
+		self.assert_valid();
 		Ok(())
 	}
 
-	/// Intersect with the pyramid’s bbox at this bbox’s zoom level.
+	/// Intersect with the bounding box of the pyramid at this bbox’s zoom level.
 	///
-	/// Equivalent to `self.intersect_with(pyramid.get_level_bbox(self.level))`.
+	/// Equivalent to `self.intersect_with(pyramid.get_bounding_bbox(self.level))`, so the result may
+	/// include gaps between disjoint regions of the pyramid.
 	pub fn intersect_with_pyramid(&mut self, pyramid: &TileBBoxPyramid) {
-		self.intersect_with(pyramid.get_level_bbox(self.level)).unwrap();
+		self.intersect_with(pyramid.get_bounding_bbox(self.level)).unwrap();
 	}
 
 	/// Shift the bbox by integer offsets `(dx, dy)`.
@@ -324,14 +329,16 @@ impl TileBBox {
 	pub fn level_up(&mut self) {
 		assert!(self.level < 31, "level must be less than 31");
 		self.level += 1;
-		self.scale_up(2).unwrap()
+		self.scale_up(2).unwrap();
+		self.assert_valid();
 	}
 
 	/// Decrease the zoom level by one and divide coordinates by 2.
 	pub fn level_down(&mut self) {
 		assert!(self.level > 0, "level must be greater than 0");
 		self.level -= 1;
-		self.scale_down(2)
+		self.scale_down(2);
+		self.assert_valid();
 	}
 
 	/// Return a copy of this bbox at the next zoom level (×2 coordinates).
@@ -365,6 +372,7 @@ impl TileBBox {
 			bbox.scale_down(scale);
 			bbox.level = level;
 		}
+		bbox.assert_valid();
 		bbox
 	}
 
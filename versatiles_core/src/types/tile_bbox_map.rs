@@ -215,7 +215,7 @@ impl<I> TileBBoxMap<Option<I>> {
 		I: Clone + Send,
 	{
 		let mut container = TileBBoxMap::<Option<I>>::new_default(bbox);
-		let vec = stream.to_vec().await;
+		let vec = stream.to_vec().await?;
 		for (coord, item) in vec {
 			container.insert(coord, Some(item))?;
 		}
@@ -1,27 +1,40 @@
 //! This module defines the `TileBBoxPyramid` struct, which represents a pyramid of tile bounding boxes
 //! across multiple zoom levels. It provides methods to create, manipulate, and query these bounding boxes.
+//!
+//! Every zoom level holds a list of disjoint bounding boxes, so a tileset covering e.g. Europe and
+//! Japan doesn't include the empty area between them. On insert, the already covered parts of a box
+//! are cut away and boxes are only joined where their union is a rectangle.
 
 use anyhow::Result;
 use versatiles_derive::context;
 
-use crate::{GeoBBox, GeoCenter, TileBBox, TileCoord};
+use crate::{GeoBBox, GeoCenter, TileBBox, TileCoord, TileStream};
+use futures::StreamExt;
 use std::array::from_fn;
 use std::fmt;
 
 const MAX_ZOOM_LEVEL: u8 = 32;
 
+/// Maximum number of disjoint bounding boxes per zoom level.
+///
+/// If a level would hold more boxes, the pairs whose merge adds the fewest tiles are merged,
+/// so including many scattered tiles one by one stays cheap.
+const MAX_BBOXES_PER_LEVEL: usize = 16;
+
 /// A struct that represents a pyramid of tile bounding boxes across multiple zoom levels.
 ///
-/// Each level (`0` through `MAX_ZOOM_LEVEL-1`) corresponds to a [`TileBBox`], which captures
-/// the range of tile coordinates valid for that zoom level. Methods in this struct allow
+/// Each level (`0` through `MAX_ZOOM_LEVEL-1`) holds a list of disjoint [`TileBBox`]es, which
+/// capture the ranges of tile coordinates valid for that zoom level. Methods in this struct allow
 /// you to intersect these bounding boxes with geographical extents, combine them with other
 /// bounding boxes or pyramids, and query the pyramid for relevant information.
 #[derive(Clone, Eq)]
 pub struct TileBBoxPyramid {
-	/// An array of tile bounding boxes, one for each zoom level up to `MAX_ZOOM_LEVEL`.
+	/// Disjoint, non-empty bounding boxes for each zoom level, sorted row by row.
 	///
 	/// Levels beyond your area of interest might remain empty.
-	pub level_bbox: [TileBBox; MAX_ZOOM_LEVEL as usize],
+	level_bboxes: [Vec<TileBBox>; MAX_ZOOM_LEVEL as usize],
+	/// Bounding box of all boxes of each zoom level, empty if the level has no boxes.
+	level_bounds: [TileBBox; MAX_ZOOM_LEVEL as usize],
 }
 
 #[allow(dead_code)]
@@ -44,29 +57,23 @@ impl TileBBoxPyramid {
 	/// May panic if `max_zoom_level` exceeds `MAX_ZOOM_LEVEL - 1`.
 	#[must_use]
 	pub fn new_full(max_zoom_level: u8) -> TileBBoxPyramid {
-		// Create an array of tile bounding boxes via `from_fn`.
-		// If index <= max_zoom_level, create a full bounding box;
-		// otherwise, create an empty bounding box.
-		TileBBoxPyramid {
-			level_bbox: from_fn(|z| {
-				if z <= max_zoom_level as usize {
-					TileBBox::new_full(z as u8).unwrap()
-				} else {
-					TileBBox::new_empty(z as u8).unwrap()
-				}
-			}),
+		let mut pyramid = TileBBoxPyramid::new_empty();
+		for z in 0..=max_zoom_level.min(MAX_ZOOM_LEVEL - 1) {
+			pyramid.set_level_bbox(TileBBox::new_full(z).unwrap());
 		}
+		pyramid
 	}
 
 	/// Creates a new `TileBBoxPyramid` with empty coverage for **all** zoom levels.
 	///
 	/// # Returns
 	///
-	/// A `TileBBoxPyramid` where each level is an empty bounding box.
+	/// A `TileBBoxPyramid` where each level is empty.
 	#[must_use]
 	pub fn new_empty() -> TileBBoxPyramid {
 		TileBBoxPyramid {
-			level_bbox: from_fn(|z| TileBBox::new_empty(z as u8).unwrap()),
+			level_bboxes: from_fn(|_| Vec::new()),
+			level_bounds: from_fn(|z| TileBBox::new_empty(z as u8).unwrap()),
 		}
 	}
 
@@ -92,6 +99,48 @@ impl TileBBoxPyramid {
 		pyramid
 	}
 
+	/// Builds a provisional pyramid from the first `sample_n` tiles of a stream whose coverage is
+	/// not known in advance, e.g. a remote tile source.
+	///
+	/// The sampled tiles are put back in front of the remaining tiles, so the returned stream
+	/// still yields every tile of the original stream. The pyramid only covers the sampled tiles.
+	///
+	/// # Examples
+	/// ```
+	/// # use versatiles_core::{Blob, TileBBoxPyramid, TileCoord, TileStream};
+	/// # async fn test() {
+	/// let stream = TileStream::from_vec(vec![
+	///     (TileCoord::new(3, 1, 2).unwrap(), Blob::from("a")),
+	///     (TileCoord::new(3, 2, 2).unwrap(), Blob::from("b")),
+	///     (TileCoord::new(4, 7, 7).unwrap(), Blob::from("c")),
+	/// ]);
+	/// let (pyramid, stream) = TileBBoxPyramid::from_tile_stream_preview(stream, 2).await;
+	/// assert_eq!(pyramid.count_tiles(), 2);
+	/// assert_eq!(stream.to_vec().await.unwrap().len(), 3);
+	/// # }
+	/// ```
+	pub async fn from_tile_stream_preview<'a, T>(
+		mut stream: TileStream<'a, T>,
+		sample_n: usize,
+	) -> (TileBBoxPyramid, TileStream<'a, T>)
+	where
+		T: Send + 'a,
+	{
+		let mut pyramid = TileBBoxPyramid::new_empty();
+		let mut sample = Vec::with_capacity(sample_n);
+		while sample.len() < sample_n {
+			// read the inner stream, so a failure is still returned when the rest is consumed
+			let Some(item) = stream.inner.next().await else {
+				break;
+			};
+			pyramid.include_coord(&item.0);
+			sample.push(item);
+		}
+		let rest = std::mem::replace(&mut stream.inner, futures::stream::empty().boxed());
+		stream.inner = futures::stream::iter(sample).chain(rest).boxed();
+		(pyramid, stream)
+	}
+
 	/// Intersects each bounding box in the pyramid with the bounding box derived from the provided [`GeoBBox`].
 	///
 	/// # Arguments
@@ -99,8 +148,13 @@ impl TileBBoxPyramid {
 	/// * `geo_bbox` - The geographical bounding box to intersect with.
 	#[context("Failed to intersect {self} with {geo_bbox:?}")]
 	pub fn intersect_geo_bbox(&mut self, geo_bbox: &GeoBBox) -> Result<()> {
-		for (z, tile_bbox) in self.level_bbox.iter_mut().enumerate() {
-			tile_bbox.intersect_with(&TileBBox::from_geo(z as u8, geo_bbox)?)?;
+		for level in 0..MAX_ZOOM_LEVEL {
+			let limit = TileBBox::from_geo(level, geo_bbox)?;
+			let mut bboxes = self.take_level_bboxes(level);
+			for bbox in &mut bboxes {
+				bbox.intersect_with(&limit)?;
+			}
+			self.set_level_bboxes(level, bboxes);
 		}
 		Ok(())
 	}
@@ -108,10 +162,14 @@ impl TileBBoxPyramid {
 	/// Expands each bounding box in the pyramid by the specified border offsets.
 	///
 	/// This effectively shifts each bounding box outward by `(x_min, y_min, x_max, y_max)`.
-	/// If a bounding box is already empty, adding a border does nothing.
+	/// If a level is empty, adding a border does nothing. Tiles covered by several boxes afterwards are kept only once.
 	pub fn add_border(&mut self, x_min: u32, y_min: u32, x_max: u32, y_max: u32) {
-		for bbox in &mut self.level_bbox {
-			bbox.expand_by(x_min, y_min, x_max, y_max);
+		for level in 0..MAX_ZOOM_LEVEL {
+			let mut bboxes = self.take_level_bboxes(level);
+			for bbox in &mut bboxes {
+				bbox.expand_by(x_min, y_min, x_max, y_max);
+			}
+			self.set_level_bboxes(level, bboxes);
 		}
 	}
 
@@ -119,86 +177,136 @@ impl TileBBoxPyramid {
 	///
 	/// Each zoom level is intersected independently with the corresponding level in `other_bbox_pyramid`.
 	pub fn intersect(&mut self, other_bbox_pyramid: &TileBBoxPyramid) {
-		for (level, bbox) in self.level_bbox.iter_mut().enumerate() {
-			let other_bbox = other_bbox_pyramid.get_level_bbox(level as u8);
-			bbox.intersect_with(other_bbox).unwrap();
+		for level in 0..MAX_ZOOM_LEVEL {
+			let mut bboxes = Vec::new();
+			for bbox in self.get_level_bboxes(level) {
+				for other_bbox in other_bbox_pyramid.get_level_bboxes(level) {
+					let mut bbox = *bbox;
+					bbox.intersect_with(other_bbox).unwrap();
+					bboxes.push(bbox);
+				}
+			}
+			self.set_level_bboxes(level, bboxes);
 		}
 	}
 
-	/// Returns a reference to the bounding box at the specified zoom level.
+	/// Returns a reference to the bounding box of all boxes at the specified zoom level.
+	///
+	/// Use it where a single rectangle is needed, e.g. for the bounds of a `TileJSON`.
+	/// It may include tiles that are not covered by the pyramid, see [`Self::get_level_bboxes`].
+	///
+	/// # Panics
+	///
+	/// Panics if `level` >= `MAX_ZOOM_LEVEL`.
+	#[must_use]
+	pub fn get_bounding_bbox(&self, level: u8) -> &TileBBox {
+		&self.level_bounds[level as usize]
+	}
+
+	/// Returns the disjoint, non-empty bounding boxes at the specified zoom level, sorted row by row.
 	///
 	/// # Panics
 	///
 	/// Panics if `level` >= `MAX_ZOOM_LEVEL`.
 	#[must_use]
-	pub fn get_level_bbox(&self, level: u8) -> &TileBBox {
-		&self.level_bbox[level as usize]
+	pub fn get_level_bboxes(&self, level: u8) -> &[TileBBox] {
+		&self.level_bboxes[level as usize]
 	}
 
-	/// Sets (in-place) the bounding box at the specified zoom level.
+	/// Sets (in-place) the bounding box at the specified zoom level, replacing all boxes of that level.
 	///
 	/// # Panics
 	///
 	/// Panics if `level` >= `MAX_ZOOM_LEVEL`.
 	pub fn set_level_bbox(&mut self, bbox: TileBBox) {
-		let level = bbox.level as usize;
-		self.level_bbox[level] = bbox;
+		self.set_level_bboxes(bbox.level, vec![bbox]);
 	}
 
-	/// Includes a single tile coordinate in the pyramid, updating the bounding box
-	/// at the coordinate’s zoom level to ensure it now encompasses `(x, y)`.
+	/// Includes a single tile coordinate in the pyramid.
+	///
+	/// The tile is joined with a box if their union is a rectangle, otherwise it becomes a new box.
 	pub fn include_coord(&mut self, coord: &TileCoord) {
-		self.level_bbox[coord.level as usize].include(coord.x, coord.y);
+		self.include_bbox(&coord.to_tile_bbox());
 	}
 
-	/// Includes another bounding box in the pyramid, merging it with the existing bounding box
-	/// at that bounding box’s zoom level.
+	/// Includes another bounding box in the pyramid at that bounding box’s zoom level.
+	///
+	/// Only the tiles of the box are added; parts that are already covered are cut away.
 	pub fn include_bbox(&mut self, bbox: &TileBBox) {
-		self.level_bbox[bbox.level as usize].include_bbox(bbox).unwrap();
+		let mut bboxes = self.take_level_bboxes(bbox.level);
+		bboxes.push(*bbox);
+		self.set_level_bboxes(bbox.level, bboxes);
 	}
 
 	/// Includes all bounding boxes from another `TileBBoxPyramid` into this pyramid.
 	///
 	/// Each zoom level from `pyramid` is included into the corresponding level in `self`.
+	/// The result covers exactly the tiles of both pyramids, as long as a level doesn't exceed
+	/// [`MAX_BBOXES_PER_LEVEL`] boxes.
 	pub fn include_bbox_pyramid(&mut self, pyramid: &TileBBoxPyramid) {
-		for bbox in pyramid.iter_levels() {
-			self.level_bbox[bbox.level as usize].include_bbox(bbox).unwrap();
+		for level in 0..MAX_ZOOM_LEVEL {
+			let mut bboxes = self.take_level_bboxes(level);
+			bboxes.extend_from_slice(pyramid.get_level_bboxes(level));
+			self.set_level_bboxes(level, bboxes);
 		}
 	}
 
 	/// Checks if the pyramid contains the given `(x, y, z)` tile coordinate.
 	#[must_use]
 	pub fn contains_coord(&self, coord: &TileCoord) -> bool {
-		if let Some(bbox) = self.level_bbox.get(coord.level as usize) {
-			bbox.contains(coord)
-		} else {
-			false
-		}
+		self
+			.level_bboxes
+			.get(coord.level as usize)
+			.is_some_and(|bboxes| bboxes.iter().any(|bbox| bbox.contains(coord)))
 	}
 
 	/// Checks if the pyramid overlaps the specified bounding box at the bounding box’s zoom level.
 	#[must_use]
 	pub fn overlaps_bbox(&self, bbox: &TileBBox) -> bool {
-		if let Some(local_bbox) = self.level_bbox.get(bbox.level as usize) {
-			local_bbox.overlaps_bbox(bbox).unwrap_or(false)
-		} else {
-			false
-		}
+		self.level_bboxes.get(bbox.level as usize).is_some_and(|bboxes| {
+			bboxes
+				.iter()
+				.any(|local_bbox| local_bbox.overlaps_bbox(bbox).unwrap_or(false))
+		})
 	}
 
-	/// Returns an iterator over all **non-empty** bounding boxes in this pyramid.
+	/// Returns an iterator over the bounding boxes (see [`Self::get_bounding_bbox`]) of all
+	/// **non-empty** levels in this pyramid, ordered by level.
+	///
+	/// Empty levels are skipped, so callers don't need to check [`TileBBox::is_empty`].
 	///
 	/// # Examples
 	///
 	/// ```
 	/// # use versatiles_core::TileBBoxPyramid;
-	/// // Suppose `pyramid` is a filled pyramid...
-	/// // for bbox in pyramid.iter_levels() {
-	/// //     println!("Level {} has some tiles", bbox.level);
-	/// // }
+	/// let pyramid = TileBBoxPyramid::new_full(2);
+	/// for bbox in pyramid.iter_levels() {
+	///     assert!(!bbox.is_empty());
+	/// }
 	/// ```
 	pub fn iter_levels(&self) -> impl Iterator<Item = &TileBBox> {
-		self.level_bbox.iter().filter(|bbox| !bbox.is_empty())
+		self.level_bounds.iter().filter(|bbox| !bbox.is_empty())
+	}
+
+	/// Like [`Self::iter_levels`], but also yields the zoom level of each bounding box.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// # use versatiles_core::TileBBoxPyramid;
+	/// let pyramid = TileBBoxPyramid::new_full(1);
+	/// let levels: Vec<u8> = pyramid.iter_levels_with_zoom().map(|(level, _)| level).collect();
+	/// assert_eq!(levels, [0, 1]);
+	/// ```
+	pub fn iter_levels_with_zoom(&self) -> impl Iterator<Item = (u8, &TileBBox)> {
+		self.iter_levels().map(|bbox| (bbox.level, bbox))
+	}
+
+	/// Returns an iterator over all disjoint bounding boxes of all levels, ordered by level.
+	///
+	/// Unlike [`Self::iter_levels`], this skips the gaps between the covered regions of a level.
+	pub fn iter_bboxes(&self) -> impl Iterator<Item = &TileBBox> {
+		self.level_bboxes.iter().flatten()
 	}
 
 	/// Finds the minimum zoom level that contains any tiles.
@@ -206,11 +314,7 @@ impl TileBBoxPyramid {
 	/// Returns `None` if **all** levels are empty.
 	#[must_use]
 	pub fn get_level_min(&self) -> Option<u8> {
-		self
-			.level_bbox
-			.iter()
-			.find(|bbox| !bbox.is_empty())
-			.map(|bbox| bbox.level)
+		self.iter_levels_with_zoom().next().map(|(level, _)| level)
 	}
 
 	/// Finds the maximum zoom level that contains any tiles.
@@ -219,7 +323,7 @@ impl TileBBoxPyramid {
 	#[must_use]
 	pub fn get_level_max(&self) -> Option<u8> {
 		self
-			.level_bbox
+			.level_bounds
 			.iter()
 			.rev()
 			.find(|bbox| !bbox.is_empty())
@@ -232,58 +336,58 @@ impl TileBBoxPyramid {
 	/// a threshold of `> 10` tiles. Returns `None` if none meet that threshold.
 	#[must_use]
 	pub fn get_good_level(&self) -> Option<u8> {
-		self
-			.level_bbox
-			.iter()
+		(0..MAX_ZOOM_LEVEL)
 			.rev()
-			.find(|bbox| bbox.count_tiles() > 10)
-			.map(|bbox| bbox.level)
+			.find(|level| self.count_level_tiles(*level) > 10)
 	}
 
 	/// Clears bounding boxes for all levels < `zoom_level_min`.
 	pub fn set_level_min(&mut self, zoom_level_min: u8) {
-		for (index, bbox) in self.level_bbox.iter_mut().enumerate() {
-			if (index as u8) < zoom_level_min {
-				bbox.set_empty();
-			}
+		for level in 0..zoom_level_min.min(MAX_ZOOM_LEVEL) {
+			self.set_level_bboxes(level, Vec::new());
 		}
 	}
 
 	/// Clears bounding boxes for all levels > `zoom_level_max`.
 	pub fn set_level_max(&mut self, zoom_level_max: u8) {
-		for (index, bbox) in self.level_bbox.iter_mut().enumerate() {
-			if (index as u8) > zoom_level_max {
-				bbox.set_empty();
-			}
+		for level in zoom_level_max.saturating_add(1)..MAX_ZOOM_LEVEL {
+			self.set_level_bboxes(level, Vec::new());
 		}
 	}
 
-	/// Counts the total number of tiles across all non-empty bounding boxes in this pyramid.
+	/// Counts the tiles in all bounding boxes of the specified zoom level.
 	#[must_use]
-	pub fn count_tiles(&self) -> u64 {
+	pub fn count_level_tiles(&self, level: u8) -> u64 {
 		self
-			.level_bbox
-			.iter()
-			.map(super::tile_bbox::TileBBox::count_tiles)
-			.sum()
+			.level_bboxes
+			.get(level as usize)
+			.map_or(0, |bboxes| bboxes.iter().map(TileBBox::count_tiles).sum())
+	}
+
+	/// Counts the total number of tiles across all bounding boxes in this pyramid.
+	///
+	/// Tiles in the gaps between the boxes of a level are not counted.
+	#[must_use]
+	pub fn count_tiles(&self) -> u64 {
+		self.iter_bboxes().map(TileBBox::count_tiles).sum()
 	}
 
-	/// Checks if **all** bounding boxes in this pyramid are empty.
+	/// Checks if **all** levels in this pyramid are empty.
 	#[must_use]
 	pub fn is_empty(&self) -> bool {
-		self.level_bbox.iter().all(super::tile_bbox::TileBBox::is_empty)
+		self.level_bboxes.iter().all(Vec::is_empty)
 	}
 
 	/// Checks if this pyramid is “full” up to the specified zoom level, meaning
-	/// each relevant bounding box is flagged as full coverage.
+	/// each relevant level is covered by a single full bounding box.
 	#[cfg(test)]
 	#[must_use]
 	pub fn is_full(&self, max_zoom_level: u8) -> bool {
-		self.level_bbox.iter().all(|bbox| {
-			if bbox.level <= max_zoom_level {
-				bbox.is_full()
+		self.level_bboxes.iter().enumerate().all(|(level, bboxes)| {
+			if level <= max_zoom_level as usize {
+				bboxes.len() == 1 && bboxes[0].is_full()
 			} else {
-				bbox.is_empty()
+				bboxes.is_empty()
 			}
 		})
 	}
@@ -294,7 +398,7 @@ impl TileBBoxPyramid {
 	#[must_use]
 	pub fn get_geo_bbox(&self) -> Option<GeoBBox> {
 		let max_zoom = self.get_level_max()?;
-		self.get_level_bbox(max_zoom).to_geo_bbox()
+		self.get_bounding_bbox(max_zoom).to_geo_bbox()
 	}
 
 	/// Calculates a geographic center based on the bounding box at a middle zoom level.
@@ -310,50 +414,162 @@ impl TileBBoxPyramid {
 		Some(GeoCenter(center_lon, center_lat, zoom))
 	}
 	pub fn swap_xy(&mut self) {
-		self.level_bbox.iter_mut().for_each(|b| {
-			b.swap_xy();
-		});
+		for level in 0..MAX_ZOOM_LEVEL {
+			let mut bboxes = self.take_level_bboxes(level);
+			bboxes.iter_mut().for_each(TileBBox::swap_xy);
+			self.set_level_bboxes(level, bboxes);
+		}
 	}
 	pub fn flip_y(&mut self) {
-		self.level_bbox.iter_mut().for_each(|b| {
-			b.flip_y();
-		});
+		for level in 0..MAX_ZOOM_LEVEL {
+			let mut bboxes = self.take_level_bboxes(level);
+			bboxes.iter_mut().for_each(TileBBox::flip_y);
+			self.set_level_bboxes(level, bboxes);
+		}
 	}
+
+	/// Removes and returns all boxes of `level`. Pass them to [`Self::set_level_bboxes`] afterwards.
+	fn take_level_bboxes(&mut self, level: u8) -> Vec<TileBBox> {
+		std::mem::take(&mut self.level_bboxes[level as usize])
+	}
+
+	/// Replaces the boxes of `level`, keeping them disjoint and at most [`MAX_BBOXES_PER_LEVEL`].
+	fn set_level_bboxes(&mut self, level: u8, bboxes: Vec<TileBBox>) {
+		let mut result = Vec::with_capacity(bboxes.len());
+		for bbox in bboxes {
+			if !bbox.is_empty() {
+				insert_disjoint(&mut result, bbox);
+			}
+		}
+
+		while result.len() > MAX_BBOXES_PER_LEVEL {
+			let (i, j) = cheapest_merge(&result);
+			merge_pair(&mut result, i, j);
+		}
+
+		result.sort_by_key(|bbox| (bbox.y_min().unwrap(), bbox.x_min().unwrap()));
+		let mut bounds = TileBBox::new_empty(level).unwrap();
+		for bbox in &result {
+			bounds.include_bbox(bbox).unwrap();
+		}
+		self.level_bboxes[level as usize] = result;
+		self.level_bounds[level as usize] = bounds;
+	}
+}
+
+/// Adds the tiles of `bbox` to the disjoint `bboxes` without covering any other tiles.
+///
+/// The parts of `bbox` that are already covered are cut away, then boxes whose union is a
+/// rectangle are joined.
+fn insert_disjoint(bboxes: &mut Vec<TileBBox>, bbox: TileBBox) {
+	let mut pieces = vec![bbox];
+	for other in bboxes.iter() {
+		pieces = pieces.iter().flat_map(|piece| subtract(piece, other)).collect();
+	}
+	bboxes.extend(pieces);
+	join_adjacent(bboxes);
+}
+
+/// Replaces the boxes `i` and `j` with their bounding box, cutting it away from all other boxes.
+fn merge_pair(bboxes: &mut Vec<TileBBox>, i: usize, j: usize) {
+	let mut merged = bboxes[i];
+	merged.include_bbox(&bboxes[j]).unwrap();
+	let others = std::mem::take(bboxes);
+	bboxes.push(merged);
+	for (index, other) in others.iter().enumerate() {
+		if index != i && index != j {
+			bboxes.extend(subtract(other, &merged));
+		}
+	}
+	join_adjacent(bboxes);
+}
+
+/// Returns the tiles of `bbox` that are not in `other` as up to four disjoint boxes.
+fn subtract(bbox: &TileBBox, other: &TileBBox) -> Vec<TileBBox> {
+	let mut inner = *bbox;
+	inner.intersect_with(other).unwrap();
+	if inner.is_empty() {
+		return vec![*bbox];
+	}
+
+	let [x0, y0, x1, y1] = bbox.as_array().unwrap();
+	let [ix0, iy0, ix1, iy1] = inner.as_array().unwrap();
+	let level = bbox.level;
+	let mut pieces = Vec::new();
+	if iy0 > y0 {
+		pieces.push(TileBBox::from_min_and_max(level, x0, y0, x1, iy0 - 1).unwrap());
+	}
+	if iy1 < y1 {
+		pieces.push(TileBBox::from_min_and_max(level, x0, iy1 + 1, x1, y1).unwrap());
+	}
+	if ix0 > x0 {
+		pieces.push(TileBBox::from_min_and_max(level, x0, iy0, ix0 - 1, iy1).unwrap());
+	}
+	if ix1 < x1 {
+		pieces.push(TileBBox::from_min_and_max(level, ix1 + 1, iy0, x1, iy1).unwrap());
+	}
+	pieces
+}
+
+/// Joins pairs of boxes that share a full edge, until no such pair is left.
+fn join_adjacent(bboxes: &mut Vec<TileBBox>) {
+	'search: loop {
+		for i in 0..bboxes.len() {
+			for j in (i + 1)..bboxes.len() {
+				if union_is_rect(&bboxes[i], &bboxes[j]) {
+					let other = bboxes.swap_remove(j);
+					bboxes[i].include_bbox(&other).unwrap();
+					continue 'search;
+				}
+			}
+		}
+		return;
+	}
+}
+
+/// Returns `true` if the disjoint boxes together form a rectangle.
+fn union_is_rect(a: &TileBBox, b: &TileBBox) -> bool {
+	let [ax0, ay0, ax1, ay1] = a.as_array().unwrap();
+	let [bx0, by0, bx1, by1] = b.as_array().unwrap();
+	let same_columns = ax0 == bx0 && ax1 == bx1 && (ay1 + 1 == by0 || by1 + 1 == ay0);
+	let same_rows = ay0 == by0 && ay1 == by1 && (ax1 + 1 == bx0 || bx1 + 1 == ax0);
+	same_columns || same_rows
+}
+
+/// Returns the indices `(i, j)` with `i < j` of the two boxes whose bounding box adds the fewest tiles.
+fn cheapest_merge(bboxes: &[TileBBox]) -> (usize, usize) {
+	let mut best = (0, 1, u64::MAX);
+	for (i, a) in bboxes.iter().enumerate() {
+		for (j, b) in bboxes.iter().enumerate().skip(i + 1) {
+			let mut merged = *a;
+			merged.include_bbox(b).unwrap();
+			let cost = merged.count_tiles() - a.count_tiles() - b.count_tiles();
+			if cost < best.2 {
+				best = (i, j, cost);
+			}
+		}
+	}
+	(best.0, best.1)
 }
 
 impl fmt::Debug for TileBBoxPyramid {
 	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-		// Debug: show only non-empty levels
-		f.debug_list().entries(self.iter_levels()).finish()
+		// Debug: show only the boxes of non-empty levels
+		f.debug_list().entries(self.iter_bboxes()).finish()
 	}
 }
 
 impl fmt::Display for TileBBoxPyramid {
 	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-		// Display: also show only non-empty levels
-		f.debug_list().entries(self.iter_levels()).finish()
+		// Display: also show only the boxes of non-empty levels
+		f.debug_list().entries(self.iter_bboxes()).finish()
 	}
 }
 
 impl PartialEq for TileBBoxPyramid {
 	fn eq(&self, other: &Self) -> bool {
-		for level in 0..MAX_ZOOM_LEVEL {
-			let bbox0 = self.get_level_bbox(level);
-			let bbox1 = other.get_level_bbox(level);
-			// If one is empty and the other is not, they're not equal
-			if bbox0.is_empty() != bbox1.is_empty() {
-				return false;
-			}
-			// If both are empty, skip
-			if bbox0.is_empty() {
-				continue;
-			}
-			// Otherwise, compare
-			if bbox0 != bbox1 {
-				return false;
-			}
-		}
-		true
+		// The boxes of every level are disjoint and sorted, so they can be compared directly.
+		self.level_bboxes == other.level_bboxes
 	}
 }
 
@@ -403,7 +619,7 @@ mod tests {
 		assert!(pyramid.is_full(8));
 		// Levels above 8 are empty
 		for lvl in 9..MAX_ZOOM_LEVEL {
-			assert!(pyramid.get_level_bbox(lvl).is_empty());
+			assert!(pyramid.get_bounding_bbox(lvl).is_empty());
 		}
 	}
 
@@ -486,7 +702,7 @@ mod tests {
 			let mut pyramid = TileBBoxPyramid::new_empty();
 			let bbox = TileBBox::new_full(level).unwrap();
 			pyramid.set_level_bbox(bbox);
-			assert_eq!(pyramid.get_level_bbox(level), &bbox);
+			assert_eq!(pyramid.get_bounding_bbox(level), &bbox);
 		};
 
 		test(0);
@@ -561,7 +777,7 @@ mod tests {
 		assert!(!pyramid.is_empty());
 		// We won't check exact tile coords since that depends on the TileBBox logic,
 		// but we can check that level 6+ is still empty:
-		assert!(pyramid.get_level_bbox(6).is_empty());
+		assert!(pyramid.get_bounding_bbox(6).is_empty());
 	}
 
 	#[test]
@@ -599,10 +815,10 @@ mod tests {
 	}
 
 	#[test]
-	fn test_get_level_bbox() {
+	fn test_get_bounding_bbox() {
 		let pyramid = TileBBoxPyramid::new_full(2);
 		// Level 0, 1, 2 are full, 3 is empty
-		assert!(pyramid.get_level_bbox(3).is_empty());
+		assert!(pyramid.get_bounding_bbox(3).is_empty());
 	}
 
 	#[test]
@@ -610,7 +826,7 @@ mod tests {
 		let mut pyramid = TileBBoxPyramid::new_empty();
 		let custom_bbox = TileBBox::new_full(3).unwrap();
 		pyramid.set_level_bbox(custom_bbox);
-		assert_eq!(pyramid.get_level_bbox(3), &custom_bbox);
+		assert_eq!(pyramid.get_bounding_bbox(3), &custom_bbox);
 	}
 
 	#[test]
@@ -618,7 +834,7 @@ mod tests {
 		let mut pyramid = TileBBoxPyramid::new_empty();
 		let coord = TileCoord::new(15, 5, 10).unwrap();
 		pyramid.include_coord(&coord);
-		assert!(!pyramid.get_level_bbox(15).is_empty());
+		assert!(!pyramid.get_bounding_bbox(15).is_empty());
 	}
 
 	#[test]
@@ -626,10 +842,10 @@ mod tests {
 		let mut pyramid = TileBBoxPyramid::new_empty();
 		let tb = TileBBox::from_min_and_max(6, 10, 10, 12, 12).unwrap();
 		pyramid.include_bbox(&tb);
-		assert!(!pyramid.get_level_bbox(6).is_empty());
+		assert!(!pyramid.get_bounding_bbox(6).is_empty());
 		// No other level should be affected
-		assert!(pyramid.get_level_bbox(5).is_empty());
-		assert!(pyramid.get_level_bbox(7).is_empty());
+		assert!(pyramid.get_bounding_bbox(5).is_empty());
+		assert!(pyramid.get_bounding_bbox(7).is_empty());
 	}
 
 	#[test]
@@ -638,10 +854,32 @@ mod tests {
 		let p2 = TileBBoxPyramid::new_full(2);
 		p1.include_bbox_pyramid(&p2);
 		// Now p1 should have coverage at levels 0..=2
-		assert!(p1.get_level_bbox(0).is_full());
-		assert!(p1.get_level_bbox(1).is_full());
-		assert!(p1.get_level_bbox(2).is_full());
-		assert!(p1.get_level_bbox(3).is_empty());
+		assert!(p1.get_bounding_bbox(0).is_full());
+		assert!(p1.get_bounding_bbox(1).is_full());
+		assert!(p1.get_bounding_bbox(2).is_full());
+		assert!(p1.get_bounding_bbox(3).is_empty());
+	}
+
+	#[test]
+	fn test_include_bbox_pyramid_germany_france() {
+		let germany = GeoBBox::new(5.87, 47.27, 15.04, 55.06).unwrap();
+		let france = GeoBBox::new(-5.14, 41.33, 9.56, 51.09).unwrap();
+		let mut pyramid = TileBBoxPyramid::from_geo_bbox(5, 10, &germany);
+		pyramid.include_bbox_pyramid(&TileBBoxPyramid::from_geo_bbox(5, 10, &france));
+
+		let contains =
+			|lon: f64, lat: f64, level: u8| pyramid.contains_coord(&TileCoord::from_geo(lon, lat, level).unwrap());
+		for level in 5..=10 {
+			assert!(contains(13.40, 52.52, level), "Berlin");
+			assert!(contains(2.35, 48.86, level), "Paris");
+			assert!(!contains(37.62, 55.76, level), "Moscow");
+		}
+		assert!(!contains(-3.70, 40.42, 10), "Madrid");
+		assert!(!contains(-6.26, 53.35, 10), "Dublin");
+		assert!(!contains(-0.13, 51.51, 10), "London");
+
+		assert_eq!(pyramid.get_level_min(), Some(5));
+		assert_eq!(pyramid.get_level_max(), Some(10));
 	}
 
 	#[rstest]
@@ -679,6 +917,21 @@ mod tests {
 		assert_eq!(levels, vec![0, 1, 2]);
 	}
 
+	#[test]
+	fn test_iter_levels_with_zoom_skips_empty_levels() {
+		let mut p = TileBBoxPyramid::new_empty();
+		p.include_bbox(&TileBBox::from_min_and_max(3, 1, 2, 3, 4).unwrap());
+		p.include_bbox(&TileBBox::from_min_and_max(7, 5, 6, 7, 8).unwrap());
+		let levels: Vec<(u8, u64)> = p
+			.iter_levels_with_zoom()
+			.map(|(level, bbox)| {
+				assert_eq!(bbox.level, level);
+				(level, bbox.count_tiles())
+			})
+			.collect();
+		assert_eq!(levels, [(3, 9), (7, 9)]);
+	}
+
 	#[test]
 	fn test_zoom_min_max1() {
 		let p = TileBBoxPyramid::new_full(3);
@@ -735,13 +988,93 @@ mod tests {
 		assert!(maybe_center.is_some());
 	}
 
+	fn bboxes(pyramid: &TileBBoxPyramid) -> Vec<String> {
+		pyramid.iter_bboxes().map(ToString::to_string).collect()
+	}
+
+	#[test]
+	fn test_disjoint_regions() -> Result<()> {
+		let mut pyramid = TileBBoxPyramid::new_empty();
+		pyramid.include_coord(&TileCoord::new(10, 10, 20)?);
+		pyramid.include_coord(&TileCoord::new(10, 900, 400)?);
+		assert_eq!(pyramid.count_tiles(), 2);
+		assert_eq!(pyramid.count_level_tiles(10), 2);
+		assert_eq!(pyramid.get_level_bboxes(10).len(), 2);
+		assert_eq!(pyramid.get_bounding_bbox(10).to_string(), "10:[10,20,900,400]");
+		assert_eq!(bboxes(&pyramid), ["10:[10,20,10,20]", "10:[900,400,900,400]"]);
+
+		assert!(pyramid.contains_coord(&TileCoord::new(10, 900, 400)?));
+		assert!(!pyramid.contains_coord(&TileCoord::new(10, 500, 200)?));
+		assert!(!pyramid.overlaps_bbox(&TileBBox::from_min_and_max(10, 11, 21, 899, 399)?));
+		assert!(pyramid.overlaps_bbox(&TileBBox::from_min_and_max(10, 11, 21, 900, 400)?));
+
+		// Diagonal neighbours stay separate boxes.
+		pyramid.include_coord(&TileCoord::new(10, 11, 21)?);
+		assert_eq!(
+			bboxes(&pyramid),
+			["10:[10,20,10,20]", "10:[11,21,11,21]", "10:[900,400,900,400]"]
+		);
+		assert_eq!(pyramid.count_tiles(), 3);
+
+		// Neighbours forming a rectangle are joined.
+		pyramid.include_coord(&TileCoord::new(10, 11, 20)?);
+		pyramid.include_coord(&TileCoord::new(10, 10, 21)?);
+		assert_eq!(bboxes(&pyramid), ["10:[10,20,11,21]", "10:[900,400,900,400]"]);
+		assert_eq!(pyramid.count_tiles(), 5);
+
+		// A box overlapping both regions only adds its own tiles.
+		pyramid.include_bbox(&TileBBox::from_min_and_max(10, 11, 21, 900, 400)?);
+		assert_eq!(pyramid.count_tiles(), 3 + 890 * 380);
+		assert!(!pyramid.contains_coord(&TileCoord::new(10, 12, 20)?));
+		Ok(())
+	}
+
+	#[test]
+	fn test_disjoint_regions_intersect_and_geo() -> Result<()> {
+		let europe = GeoBBox::new(-10.0, 35.0, 30.0, 70.0)?;
+		let japan = GeoBBox::new(129.0, 31.0, 146.0, 46.0)?;
+		let mut pyramid = TileBBoxPyramid::from_geo_bbox(0, 8, &europe);
+		pyramid.include_bbox_pyramid(&TileBBoxPyramid::from_geo_bbox(0, 8, &japan));
+		assert_eq!(pyramid.get_level_bboxes(8).len(), 2);
+		let count = pyramid.count_level_tiles(8);
+		assert!(count < pyramid.get_bounding_bbox(8).count_tiles() / 2);
+
+		// Limiting to Europe drops Japan.
+		let mut limited = pyramid.clone();
+		limited.intersect_geo_bbox(&europe)?;
+		assert_eq!(limited.get_level_bboxes(8).len(), 1);
+		assert_eq!(limited, TileBBoxPyramid::from_geo_bbox(0, 8, &europe));
+
+		let mut intersected = pyramid.clone();
+		intersected.intersect(&TileBBoxPyramid::from_geo_bbox(0, 8, &japan));
+		assert_eq!(intersected, TileBBoxPyramid::from_geo_bbox(0, 8, &japan));
+
+		pyramid.intersect(&TileBBoxPyramid::new_full(8));
+		assert_eq!(pyramid.count_level_tiles(8), count);
+		Ok(())
+	}
+
+	#[test]
+	fn test_max_bboxes_per_level() -> Result<()> {
+		let mut pyramid = TileBBoxPyramid::new_empty();
+		for i in 0..100 {
+			pyramid.include_coord(&TileCoord::new(12, i * 10, 0)?);
+		}
+		assert_eq!(pyramid.get_level_bboxes(12).len(), MAX_BBOXES_PER_LEVEL);
+		assert_eq!(pyramid.get_bounding_bbox(12).to_string(), "12:[0,0,990,0]");
+		for i in 0..100 {
+			assert!(pyramid.contains_coord(&TileCoord::new(12, i * 10, 0)?));
+		}
+		Ok(())
+	}
+
 	#[test]
 	fn pyramid_swap_xy_transform() {
 		let mut pyramid = TileBBoxPyramid::new_empty();
 		pyramid.include_bbox(&TileBBox::from_min_and_max(4, 0, 1, 2, 3).unwrap());
 		pyramid.swap_xy();
 		assert_eq!(
-			pyramid.get_level_bbox(4),
+			pyramid.get_bounding_bbox(4),
 			&TileBBox::from_min_and_max(4, 1, 0, 3, 2).unwrap()
 		);
 	}
@@ -752,8 +1085,25 @@ mod tests {
 		pyramid.include_bbox(&TileBBox::from_min_and_max(4, 0, 1, 2, 3).unwrap());
 		pyramid.flip_y();
 		assert_eq!(
-			pyramid.get_level_bbox(4),
+			pyramid.get_bounding_bbox(4),
 			&TileBBox::from_min_and_max(4, 0, 12, 2, 14).unwrap()
 		);
 	}
+
+	#[tokio::test]
+	async fn from_tile_stream_preview() -> Result<()> {
+		let tiles = (0..10)
+			.map(|x| Ok((TileCoord::new(5, x, 3)?, x)))
+			.collect::<Result<Vec<_>>>()?;
+		let (pyramid, stream) = TileBBoxPyramid::from_tile_stream_preview(TileStream::from_vec(tiles.clone()), 4).await;
+		assert_eq!(pyramid.get_bounding_bbox(5).to_string(), "5:[0,3,3,3]");
+		assert_eq!(stream.to_vec().await.unwrap(), tiles);
+
+		// a stream shorter than the sample is covered entirely
+		let (pyramid, stream) =
+			TileBBoxPyramid::from_tile_stream_preview(TileStream::from_vec(tiles[..2].to_vec()), 4).await;
+		assert_eq!(pyramid.count_tiles(), 2);
+		assert_eq!(stream.to_vec().await.unwrap().len(), 2);
+		Ok(())
+	}
 }
@@ -7,6 +7,7 @@
 //!
 //! - Supports `None`, `Gzip`, and `Brotli` compression algorithms.
 //! - Provides methods for getting file extensions and extracting compression type from filenames.
+//! - Maps compression types to and from HTTP content-coding tokens (`Content-Encoding`).
 //!
 //! # Examples
 //!
@@ -33,7 +34,7 @@ use std::fmt::Display;
 
 /// Enum representing possible compression algorithms.
 #[cfg_attr(feature = "cli", derive(ValueEnum))]
-#[derive(Debug, Default, EnumSetType, PartialOrd, Ord)]
+#[derive(Debug, Default, EnumSetType, Hash, PartialOrd, Ord)]
 pub enum TileCompression {
 	#[default]
 	/// No compression.
@@ -127,12 +128,7 @@ impl TileCompression {
 	/// ```
 	pub fn from_filename(filename: &mut String) -> TileCompression {
 		if let Some(index) = filename.rfind('.') {
-			let compression = match filename.get(index..).unwrap() {
-				".gz" => Gzip,
-				".br" => Brotli,
-				_ => Uncompressed,
-			};
-
+			let compression = Self::from_extension(&filename[index..]).unwrap_or(Uncompressed);
 			if compression != Uncompressed {
 				filename.truncate(index);
 			}
@@ -140,6 +136,79 @@ impl TileCompression {
 		}
 		Uncompressed
 	}
+
+	/// Determines the compression type from a file extension.
+	///
+	/// The leading dot is optional and the comparison is case-insensitive.
+	/// An empty extension means [`TileCompression::Uncompressed`], so this is the inverse of
+	/// [`TileCompression::as_extension`]. Returns `None` for unknown extensions.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use versatiles_core::TileCompression;
+	///
+	/// assert_eq!(TileCompression::from_extension(".gz"), Some(TileCompression::Gzip));
+	/// assert_eq!(TileCompression::from_extension("br"), Some(TileCompression::Brotli));
+	/// assert_eq!(TileCompression::from_extension(""), Some(TileCompression::Uncompressed));
+	/// assert_eq!(TileCompression::from_extension(".png"), None);
+	/// ```
+	#[must_use]
+	pub fn from_extension(extension: &str) -> Option<TileCompression> {
+		let extension = extension.strip_prefix('.').unwrap_or(extension);
+		Some(match extension.to_ascii_lowercase().as_str() {
+			"" => Uncompressed,
+			"gz" => Gzip,
+			"br" => Brotli,
+			_ => return None,
+		})
+	}
+
+	/// Returns the HTTP `Content-Encoding` token for this compression type.
+	///
+	/// Returns `None` for [`TileCompression::Uncompressed`], since no header should be sent.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use versatiles_core::TileCompression;
+	///
+	/// assert_eq!(TileCompression::Uncompressed.as_content_encoding(), None);
+	/// assert_eq!(TileCompression::Gzip.as_content_encoding(), Some("gzip"));
+	/// assert_eq!(TileCompression::Brotli.as_content_encoding(), Some("br"));
+	/// ```
+	#[must_use]
+	pub fn as_content_encoding(&self) -> Option<&'static str> {
+		match self {
+			Uncompressed => None,
+			Gzip => Some("gzip"),
+			Brotli => Some("br"),
+		}
+	}
+
+	/// Determines the compression type from an HTTP content-coding token,
+	/// as used in `Content-Encoding` and `Accept-Encoding` headers.
+	///
+	/// The comparison is case-insensitive. Returns `None` for unsupported tokens.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use versatiles_core::TileCompression;
+	///
+	/// assert_eq!(TileCompression::from_content_encoding("br"), Some(TileCompression::Brotli));
+	/// assert_eq!(TileCompression::from_content_encoding("identity"), Some(TileCompression::Uncompressed));
+	/// assert_eq!(TileCompression::from_content_encoding("deflate"), None);
+	/// ```
+	#[must_use]
+	pub fn from_content_encoding(token: &str) -> Option<TileCompression> {
+		Some(match token.trim().to_ascii_lowercase().as_str() {
+			"identity" => Uncompressed,
+			"gzip" | "x-gzip" => Gzip,
+			"br" => Brotli,
+			_ => return None,
+		})
+	}
 }
 
 impl TryFrom<&str> for TileCompression {
@@ -230,6 +299,47 @@ mod tests {
 		assert_eq!(filename_string, expected_remainder);
 	}
 
+	#[rstest]
+	#[case(".gz", Some(Gzip))]
+	#[case("gz", Some(Gzip))]
+	#[case(".BR", Some(Brotli))]
+	#[case("", Some(Uncompressed))]
+	#[case(".", Some(Uncompressed))]
+	#[case(".zip", None)]
+	#[case("png", None)]
+	fn test_from_extension(#[case] extension: &str, #[case] expected: Option<TileCompression>) {
+		assert_eq!(TileCompression::from_extension(extension), expected);
+	}
+
+	#[test]
+	fn test_extension_round_trip() {
+		for compression in EnumSet::<TileCompression>::all() {
+			assert_eq!(
+				TileCompression::from_extension(compression.as_extension()),
+				Some(compression)
+			);
+		}
+	}
+
+	#[rstest]
+	#[case("gzip", Some(Gzip))]
+	#[case("x-gzip", Some(Gzip))]
+	#[case(" BR ", Some(Brotli))]
+	#[case("identity", Some(Uncompressed))]
+	#[case("deflate", None)]
+	#[case("*", None)]
+	fn test_from_content_encoding(#[case] token: &str, #[case] expected: Option<TileCompression>) {
+		assert_eq!(TileCompression::from_content_encoding(token), expected);
+	}
+
+	#[test]
+	fn test_content_encoding_round_trip() {
+		for compression in EnumSet::<TileCompression>::all() {
+			let token = compression.as_content_encoding().unwrap_or("identity");
+			assert_eq!(TileCompression::from_content_encoding(token), Some(compression));
+		}
+	}
+
 	#[rstest]
 	#[case("none", Ok(TileCompression::Uncompressed))]
 	#[case("gzip", Ok(TileCompression::Gzip))]
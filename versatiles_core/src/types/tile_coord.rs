@@ -373,6 +373,20 @@ impl TileCoord {
 	}
 }
 
+/// Converts `(level, x, y)` in wider integer types, e.g. from database rows or file indices.
+///
+/// Fails if a value doesn't fit into `u8`/`u32` or the coordinate is invalid for its level.
+impl TryFrom<(u64, u64, u64)> for TileCoord {
+	type Error = anyhow::Error;
+
+	fn try_from((level, x, y): (u64, u64, u64)) -> Result<Self> {
+		let level = u8::try_from(level).map_err(|_| anyhow::anyhow!("level ({level}) must be <= 31"))?;
+		let x = u32::try_from(x).map_err(|_| anyhow::anyhow!("x ({x}) out of bounds for level {level}"))?;
+		let y = u32::try_from(y).map_err(|_| anyhow::anyhow!("y ({y}) out of bounds for level {level}"))?;
+		TileCoord::new(level, x, y)
+	}
+}
+
 /// Custom `Debug` format as `TileCoord(z, [x, y])` for readability.
 impl Debug for TileCoord {
 	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -405,6 +419,19 @@ mod tests {
 		hash::{Hash, Hasher},
 	};
 
+	#[rstest]
+	#[case((0, 0, 0), Ok(TileCoord { level: 0, x: 0, y: 0 }))]
+	#[case((3, 7, 5), Ok(TileCoord { level: 3, x: 7, y: 5 }))]
+	#[case((0, 1, 0), Err("x (1) out of bounds for level 0"))]
+	#[case((0, 0, 1), Err("y (1) out of bounds for level 0"))]
+	#[case((200, 0, 0), Err("level (200) must be <= 31"))]
+	#[case((300, 0, 0), Err("level (300) must be <= 31"))]
+	#[case((31, 1 << 32, 0), Err("x (4294967296) out of bounds for level 31"))]
+	fn try_from_wide_integers(#[case] input: (u64, u64, u64), #[case] expected: Result<TileCoord, &str>) {
+		let result = TileCoord::try_from(input).map_err(|e| e.to_string());
+		assert_eq!(result, expected.map_err(String::from));
+	}
+
 	#[test]
 	fn partial_eq() {
 		let c = TileCoord::new(2, 2, 2).unwrap();
@@ -109,7 +109,26 @@ impl TileFormat {
 
 	#[context("Could not convert string '{value}' to TileFormat")]
 	pub fn try_from_str(value: &str) -> Result<Self> {
-		Ok(match value.to_lowercase().trim_matches([' ', '.']) {
+		match Self::from_extension(value.trim_matches([' ', '.'])) {
+			Some(format) => Ok(format),
+			None => bail!("Unknown tile format: '{value}'"),
+		}
+	}
+
+	/// Returns the tile format for a file extension, with or without leading dot.
+	///
+	/// Matching is case-insensitive and accepts the common aliases `jpeg` and `mvt`.
+	///
+	/// # Examples
+	/// ```
+	/// use versatiles_core::TileFormat;
+	/// assert_eq!(TileFormat::from_extension(".pbf"), Some(TileFormat::MVT));
+	/// assert_eq!(TileFormat::from_extension("JPEG"), Some(TileFormat::JPG));
+	/// assert_eq!(TileFormat::from_extension("abc"), None);
+	/// ```
+	#[must_use]
+	pub fn from_extension(extension: &str) -> Option<Self> {
+		Some(match extension.trim_start_matches('.').to_lowercase().as_str() {
 			"avif" => AVIF,
 			"bin" => BIN,
 			"geojson" => GEOJSON,
@@ -120,7 +139,7 @@ impl TileFormat {
 			"svg" => SVG,
 			"topojson" => TOPOJSON,
 			"webp" => WEBP,
-			_ => bail!("Unknown tile format: '{value}'"),
+			_ => return None,
 		})
 	}
 
@@ -215,7 +234,7 @@ impl TileFormat {
 
 	/// Attempts to extract a `TileFormat` from the file extension in `filename`.
 	///
-	/// If a matching extension (e.g. `.pbf` or `.jpeg`) is found, the `TileFormat`
+	/// If a matching extension (e.g. `.pbf` or `.jpeg`, see [`from_extension`](Self::from_extension)) is found, the `TileFormat`
 	/// is returned and the filename is truncated to remove the extension.
 	/// If no known extension is found, returns `None`.
 	///
@@ -239,26 +258,10 @@ impl TileFormat {
 	/// assert_eq!("file.abc", unknown);
 	/// ```
 	pub fn from_filename(filename: &mut String) -> Option<Self> {
-		if let Some(index) = filename.rfind('.') {
-			let extension = filename[index..].to_lowercase();
-			let format = match extension.as_str() {
-				".avif" => AVIF,
-				".bin" => BIN,
-				".geojson" => GEOJSON,
-				".jpg" | ".jpeg" => JPG,
-				".json" => JSON,
-				".pbf" => MVT,
-				".png" => PNG,
-				".svg" => SVG,
-				".topojson" => TOPOJSON,
-				".webp" => WEBP,
-				_ => return None,
-			};
-			filename.truncate(index);
-			Some(format)
-		} else {
-			None
-		}
+		let index = filename.rfind('.')?;
+		let format = Self::from_extension(&filename[index..])?;
+		filename.truncate(index);
+		Some(format)
 	}
 
 	/// Convert this tile format to its corresponding [`TileType`].
@@ -424,6 +427,8 @@ mod tests {
 	#[case("image.jpg", Some(JPG), "image")]
 	#[case("document.json", Some(JSON), "document")]
 	#[case("map.pbf", Some(MVT), "map")]
+	#[case("map.mvt", Some(MVT), "map")]
+	#[case("archive.tar.png", Some(PNG), "archive.tar")]
 	#[case("picture.png", Some(PNG), "picture")]
 	#[case("diagram.svg", Some(SVG), "diagram")]
 	#[case("vector.SVG", Some(SVG), "vector")]
@@ -464,6 +469,16 @@ mod tests {
 		}
 	}
 
+	#[test]
+	fn should_round_trip_extensions() {
+		for format in EnumSet::<TileFormat>::all() {
+			assert_eq!(TileFormat::from_extension(format.as_extension()), Some(format));
+			assert_eq!(TileFormat::from_extension(format.as_str()), Some(format));
+		}
+		assert_eq!(TileFormat::from_extension(""), None);
+		assert_eq!(TileFormat::from_extension(".gz"), None);
+	}
+
 	#[test]
 	fn should_provide_meaningful_strings_for_debug_and_display() {
 		let format = TileFormat::PNG;
@@ -0,0 +1,177 @@
+//! Defines `TileGrid`, a regular tile grid for tiling schemes other than Web Mercator.
+//!
+//! National grids (e.g. the Danish or the Swedish SWEREF99 grid) don't double the number of
+//! tiles per zoom level. A `TileGrid` describes such a grid by its origin, the extent of a tile
+//! at the first level and a resolution per level.
+
+use crate::json::{JsonObject, JsonValue};
+use anyhow::{Result, ensure};
+
+/// A regular tile grid in geographic coordinates.
+///
+/// Tiles are counted from the origin, the north-west corner of the grid: `x` grows to the east,
+/// `y` grows to the south. The resolutions only matter relative to each other: at `level`, a tile
+/// covers `tile_width_deg * resolutions[level] / resolutions[0]` degrees of longitude (and
+/// likewise for latitude), so level 0 tiles are exactly `tile_width_deg` × `tile_height_deg`.
+///
+/// # Examples
+///
+/// ```
+/// use versatiles_core::TileGrid;
+///
+/// // 3 tiles per level 0 tile on level 1
+/// let grid = TileGrid::new(10.0, 58.0, 1.5, 1.0, vec![300.0, 100.0]).unwrap();
+/// assert_eq!(grid.tile_for_point(11.6, 57.5, 0), Some((1, 0)));
+/// assert_eq!(grid.tile_for_point(11.6, 57.5, 1), Some((3, 1)));
+/// assert_eq!(grid.tile_for_point(11.6, 57.5, 2), None);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct TileGrid {
+	/// Longitude of the north-west corner of the grid.
+	pub origin_lon: f64,
+	/// Latitude of the north-west corner of the grid.
+	pub origin_lat: f64,
+	/// Width of a level 0 tile in degrees.
+	pub tile_width_deg: f64,
+	/// Height of a level 0 tile in degrees.
+	pub tile_height_deg: f64,
+	/// Resolution of every level, starting with level 0.
+	pub resolutions: Vec<f64>,
+}
+
+impl TileGrid {
+	/// Creates a tile grid.
+	///
+	/// # Errors
+	///
+	/// Returns an error if the origin is not finite, a tile extent or resolution is not positive,
+	/// or there are no resolutions at all.
+	pub fn new(
+		origin_lon: f64,
+		origin_lat: f64,
+		tile_width_deg: f64,
+		tile_height_deg: f64,
+		resolutions: Vec<f64>,
+	) -> Result<TileGrid> {
+		ensure!(
+			origin_lon.is_finite() && origin_lat.is_finite(),
+			"origin ({origin_lon}, {origin_lat}) must be finite"
+		);
+		ensure!(
+			is_positive(tile_width_deg) && is_positive(tile_height_deg),
+			"tile extent ({tile_width_deg} x {tile_height_deg}) must be positive"
+		);
+		ensure!(!resolutions.is_empty(), "a tile grid needs at least one resolution");
+		ensure!(
+			resolutions.iter().all(|r| is_positive(*r)),
+			"resolutions {resolutions:?} must be positive"
+		);
+		Ok(TileGrid {
+			origin_lon,
+			origin_lat,
+			tile_width_deg,
+			tile_height_deg,
+			resolutions,
+		})
+	}
+
+	/// Returns the highest level of the grid.
+	#[must_use]
+	pub fn max_level(&self) -> u8 {
+		(self.resolutions.len().saturating_sub(1)).min(u8::MAX as usize) as u8
+	}
+
+	/// Returns width and height of a tile at `level` in degrees, or `None` if the grid has no such level.
+	#[must_use]
+	pub fn tile_size_deg(&self, level: u8) -> Option<(f64, f64)> {
+		let scale = self.resolutions.get(level as usize)? / self.resolutions[0];
+		Some((self.tile_width_deg * scale, self.tile_height_deg * scale))
+	}
+
+	/// Returns the column and row of the tile at `level` that contains the point,
+	/// or `None` if the grid has no such level.
+	///
+	/// Points west or north of the origin end up in column or row 0.
+	#[must_use]
+	pub fn tile_for_point(&self, lon: f64, lat: f64, level: u8) -> Option<(u64, u64)> {
+		let (width, height) = self.tile_size_deg(level)?;
+		let x = ((lon - self.origin_lon) / width).floor();
+		let y = ((self.origin_lat - lat) / height).floor();
+		// float to int casts saturate, so negative values become 0
+		Some((x as u64, y as u64))
+	}
+
+	/// Returns the grid as JSON, e.g. for server metadata.
+	#[must_use]
+	pub fn as_json_value(&self) -> JsonValue {
+		let mut object = JsonObject::new();
+		object.set("origin", vec![self.origin_lon, self.origin_lat]);
+		object.set("tile_width_deg", self.tile_width_deg);
+		object.set("tile_height_deg", self.tile_height_deg);
+		object.set("resolutions", self.resolutions.clone());
+		JsonValue::from(object)
+	}
+}
+
+fn is_positive(value: f64) -> bool {
+	value.is_finite() && value > 0.0
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use rstest::rstest;
+
+	fn sweden() -> TileGrid {
+		// a SWEREF99-like grid: the resolution halves, but level 0 is not a single tile
+		TileGrid::new(10.0, 70.0, 4.0, 2.0, vec![4096.0, 2048.0, 1024.0, 512.0]).unwrap()
+	}
+
+	#[rstest]
+	#[case(18.07, 59.33, 0, (2, 5))]
+	#[case(18.07, 59.33, 1, (4, 10))]
+	#[case(18.07, 59.33, 3, (16, 42))]
+	#[case(10.0, 70.0, 3, (0, 0))]
+	#[case(5.0, 75.0, 2, (0, 0))]
+	fn tile_for_point(#[case] lon: f64, #[case] lat: f64, #[case] level: u8, #[case] expected: (u64, u64)) {
+		assert_eq!(sweden().tile_for_point(lon, lat, level), Some(expected));
+	}
+
+	#[test]
+	fn non_power_of_two_levels() {
+		let grid = TileGrid::new(0.0, 0.0, 1.0, 1.0, vec![10.0, 4.0]).unwrap();
+		assert_eq!(grid.tile_size_deg(1), Some((0.4, 0.4)));
+		assert_eq!(grid.tile_for_point(0.9, -0.9, 1), Some((2, 2)));
+		assert_eq!(grid.tile_size_deg(2), None);
+		assert_eq!(grid.max_level(), 1);
+	}
+
+	#[test]
+	fn tile_for_point_beyond_max_level() {
+		assert_eq!(sweden().tile_for_point(18.0, 59.0, 4), None);
+	}
+
+	#[rstest]
+	#[case(f64::NAN, 0.0, 1.0, 1.0, vec![1.0])]
+	#[case(0.0, 0.0, 0.0, 1.0, vec![1.0])]
+	#[case(0.0, 0.0, 1.0, -1.0, vec![1.0])]
+	#[case(0.0, 0.0, 1.0, 1.0, vec![])]
+	#[case(0.0, 0.0, 1.0, 1.0, vec![1.0, 0.0])]
+	fn new_rejects_invalid_grids(
+		#[case] lon: f64,
+		#[case] lat: f64,
+		#[case] width: f64,
+		#[case] height: f64,
+		#[case] resolutions: Vec<f64>,
+	) {
+		assert!(TileGrid::new(lon, lat, width, height, resolutions).is_err());
+	}
+
+	#[test]
+	fn json() {
+		assert_eq!(
+			sweden().as_json_value().stringify(),
+			r#"{"origin":[10,70],"resolutions":[4096,2048,1024,512],"tile_height_deg":2,"tile_width_deg":4}"#
+		);
+	}
+}
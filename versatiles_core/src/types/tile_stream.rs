@@ -10,6 +10,9 @@
 //! - **Buffering**: Collect or process data in configurable batches
 //! - **Flexible Callbacks**: Choose between sync and async processing steps
 //! - **Stream Composition**: Flatten and combine multiple tile streams
+//! - **Fan-out**: Feed one stream to multiple consumers with [`TileStream::tee`]
+//! - **Error Handling**: Stop at the first failing tile with [`TileStream::try_map_item_parallel`]
+//!   and return its error from the consumer, see [`StreamFailure`]
 //!
 //! # Examples
 //!
@@ -36,13 +39,18 @@
 //! # }
 //! ```
 use crate::{Blob, ConcurrencyLimits, TileCoord};
-use anyhow::Result;
+use anyhow::{Context, Result};
 use futures::{
-	Future, Stream, StreamExt,
+	Future, FutureExt, Stream, StreamExt,
 	future::ready,
 	stream::{self, BoxStream},
 };
-use std::{collections::HashMap, pin::Pin, sync::Arc};
+use std::{
+	collections::{HashMap, VecDeque},
+	pin::Pin,
+	sync::{Arc, Mutex},
+	task::{Context as TaskContext, Poll, Waker},
+};
 
 /// A stream of tiles represented by `(TileCoord, T)` pairs.
 ///
@@ -52,9 +60,52 @@ use std::{collections::HashMap, pin::Pin, sync::Arc};
 ///
 /// # Fields
 /// - `stream`: The internal boxed stream that emits `(TileCoord, T)` pairs.
+/// - `failure`: The first error of a fallible step, see [`StreamFailure`].
 pub struct TileStream<'a, T = Blob> {
 	/// The internal boxed stream, emitting `(TileCoord, T)` pairs.
-	pub inner: BoxStream<'a, (TileCoord, T)>,
+	pub(crate) inner: BoxStream<'a, (TileCoord, T)>,
+	failure: StreamFailure,
+}
+
+/// The first error of a [`TileStream`].
+///
+/// A `TileStream<T>` can't yield errors, so fallible steps like
+/// [`TileStream::try_map_item_parallel`] end the stream at the first error and store it here.
+/// The handle is shared by all streams derived from the same source.
+///
+/// The consumers (`to_vec`, `for_each_*`, …) return the error once the stream has ended.
+/// Code that hands the stream to someone else can keep a handle from
+/// [`TileStream::failure_handle`] and call [`StreamFailure::check`] afterwards.
+#[derive(Clone, Default)]
+pub struct StreamFailure(Arc<Mutex<Option<anyhow::Error>>>);
+
+impl StreamFailure {
+	/// Stores `error`, unless an earlier error is stored already.
+	fn record(&self, error: anyhow::Error) {
+		self.0.lock().unwrap().get_or_insert(error);
+	}
+
+	/// Moves a stored error into `target`.
+	fn forward_to(&self, target: &StreamFailure) {
+		if Arc::ptr_eq(&self.0, &target.0) {
+			return;
+		}
+		let error = self.0.lock().unwrap().take();
+		if let Some(error) = error {
+			target.record(error);
+		}
+	}
+
+	/// Returns the stored error, if a fallible step of the stream failed.
+	///
+	/// # Errors
+	/// Returns the first error of the stream.
+	pub fn check(&self) -> Result<()> {
+		match self.0.lock().unwrap().take() {
+			Some(error) => Err(error),
+			None => Ok(()),
+		}
+	}
 }
 
 impl<'a, T> TileStream<'a, T>
@@ -72,9 +123,31 @@ where
 	pub fn empty() -> TileStream<'a, T> {
 		TileStream {
 			inner: stream::empty().boxed(),
+			failure: StreamFailure::default(),
 		}
 	}
 
+	/// Creates an empty `TileStream` that fails with `error`, see [`StreamFailure`].
+	///
+	/// Useful where a stream must be returned, but creating it failed.
+	///
+	/// # Examples
+	/// ```
+	/// # use versatiles_core::{Blob, TileStream};
+	/// # async fn test() {
+	/// let stream = TileStream::<Blob>::failed(anyhow::anyhow!("source is gone"));
+	/// let failure = stream.failure_handle();
+	/// assert!(stream.to_vec().await.unwrap().is_empty());
+	/// assert_eq!(failure.check().unwrap_err().to_string(), "source is gone");
+	/// # }
+	/// ```
+	#[must_use]
+	pub fn failed(error: anyhow::Error) -> TileStream<'a, T> {
+		let stream = TileStream::empty();
+		stream.failure.record(error);
+		stream
+	}
+
 	/// Creates a `TileStream` from an existing `Stream` of `(TileCoord, T)`.
 	///
 	/// # Examples
@@ -90,7 +163,10 @@ where
 	/// ```
 	#[must_use]
 	pub fn from_stream(stream: Pin<Box<dyn Stream<Item = (TileCoord, T)> + Send + 'a>>) -> Self {
-		TileStream { inner: stream }
+		TileStream {
+			inner: stream,
+			failure: StreamFailure::default(),
+		}
 	}
 
 	/// Constructs a `TileStream` from a vector of `(TileCoord, T)` items.
@@ -110,6 +186,7 @@ where
 	pub fn from_vec(vec: Vec<(TileCoord, T)>) -> Self {
 		TileStream {
 			inner: stream::iter(vec).boxed(),
+			failure: StreamFailure::default(),
 		}
 	}
 
@@ -161,7 +238,7 @@ where
 					_ => None,
 				}
 			});
-		TileStream { inner: s.boxed() }
+		TileStream::from_stream(s.boxed())
 	}
 
 	/// Creates a `TileStream` by sequentially filtering and mapping coordinates from an iterator.
@@ -200,7 +277,7 @@ where
 	///     }
 	/// });
 	///
-	/// let items = stream.to_vec().await;
+	/// let items = stream.to_vec().await.unwrap();
 	/// assert_eq!(items.len(), 2); // levels 0 and 2
 	/// # }
 	/// ```
@@ -209,9 +286,9 @@ where
 		F: Fn(TileCoord) -> Option<T> + Send + Sync + 'static,
 		T: 'static,
 	{
-		TileStream {
-			inner: stream::iter(iter.filter_map(move |coord| callback(coord).map(|item| (coord, item)))).boxed(),
-		}
+		TileStream::from_stream(
+			stream::iter(iter.filter_map(move |coord| callback(coord).map(|item| (coord, item)))).boxed(),
+		)
 	}
 
 	/// Creates a `TileStream` by filtering and mapping an async closure over a vector of tile coordinates.
@@ -234,7 +311,7 @@ where
 	/// };
 	///
 	/// let tile_stream = TileStream::from_coord_vec_async(coords, closure);
-	/// let items = tile_stream.to_vec().await;
+	/// let items = tile_stream.to_vec().await.unwrap();
 	/// assert_eq!(items.len(), 1);
 	/// # }
 	/// ```
@@ -244,7 +321,7 @@ where
 		Fut: Future<Output = Option<(TileCoord, T)>> + Send + 'a,
 	{
 		let s = stream::iter(vec).filter_map(callback);
-		TileStream { inner: s.boxed() }
+		TileStream::from_stream(s.boxed())
 	}
 
 	// -------------------------------------------------------------------------
@@ -266,7 +343,7 @@ where
 	/// #
 	/// async fn example(tile_streams: Vec<impl std::future::Future<Output=TileStream<'static>> + Send + 'static>) {
 	///     let merged = TileStream::from_streams(stream::iter(tile_streams));
-	///     let all_items = merged.to_vec().await;
+	///     let all_items = merged.to_vec().await.unwrap();
 	///     // `all_items` now contains items from all child streams
 	/// }
 	/// ```
@@ -274,9 +351,59 @@ where
 	where
 		FutureStream: Future<Output = TileStream<'a, T>> + Send + 'a,
 	{
-		let limits = ConcurrencyLimits::default();
+		Self::from_streams_limited(streams, ConcurrencyLimits::default().io_bound) // I/O-bound: awaiting async streams
+	}
+
+	/// Like [`TileStream::from_streams`], but processes at most `max_concurrent` streams at a time.
+	///
+	/// A stream counts from the moment its future is polled until its last tile is yielded, so
+	/// the limit bounds the work and memory of futures that buffer tiles as well as of lazy
+	/// streams that produce their tiles while they are drained. Up to `max_concurrent` streams
+	/// are drained concurrently, so their tiles are interleaved.
+	///
+	/// # Panics
+	/// Panics if `max_concurrent` is 0.
+	pub fn from_streams_limited<FutureStream>(
+		streams: impl Stream<Item = FutureStream> + Send + 'a,
+		max_concurrent: usize,
+	) -> TileStream<'a, T>
+	where
+		FutureStream: Future<Output = TileStream<'a, T>> + Send + 'a,
+	{
+		assert!(max_concurrent > 0, "max_concurrent must be greater than 0");
+		let failure = StreamFailure::default();
+		let target = failure.clone();
 		TileStream {
-			inner: Box::pin(streams.buffer_unordered(limits.io_bound).map(|s| s.inner).flatten()), // I/O-bound: awaiting async streams
+			inner: Box::pin(
+				streams
+					.map(move |future| {
+						let target = target.clone();
+						future.map(move |s| s.forward_failure(target)).flatten_stream().boxed()
+					})
+					.flatten_unordered(max_concurrent),
+			),
+			failure,
+		}
+	}
+
+	/// Like [`TileStream::from_streams`], but keeps the order of the streams.
+	///
+	/// The futures are still awaited concurrently, but the tiles of a stream are only yielded after
+	/// all tiles of the streams before it, so the output order doesn't depend on timing.
+	pub fn from_streams_ordered<FutureStream>(streams: impl Stream<Item = FutureStream> + Send + 'a) -> TileStream<'a, T>
+	where
+		FutureStream: Future<Output = TileStream<'a, T>> + Send + 'a,
+	{
+		let failure = StreamFailure::default();
+		let target = failure.clone();
+		TileStream {
+			inner: Box::pin(
+				streams
+					.buffered(ConcurrencyLimits::default().io_bound) // I/O-bound: awaiting async streams
+					.map(move |s| s.forward_failure(target.clone()))
+					.flatten(),
+			),
+			failure,
 		}
 	}
 
@@ -296,12 +423,14 @@ where
 	///     (TileCoord::new(0,0,0).unwrap(), Blob::from("data0")),
 	///     (TileCoord::new(1,1,1).unwrap(), Blob::from("data1")),
 	/// ]);
-	/// let items = stream.to_vec().await;
+	/// let items = stream.to_vec().await.unwrap();
 	/// assert_eq!(items.len(), 2);
 	/// # }
 	/// ```
-	pub async fn to_vec(self) -> Vec<(TileCoord, T)> {
-		self.inner.collect().await
+	pub async fn to_vec(self) -> Result<Vec<(TileCoord, T)>> {
+		let vec = self.inner.collect().await;
+		self.failure.check()?;
+		Ok(vec)
 	}
 
 	/// Collects all items from the stream into a [`HashMap`] keyed by coordinate.
@@ -326,7 +455,7 @@ where
 	/// ];
 	///
 	/// let stream = TileStream::from_vec(items);
-	/// let map: HashMap<TileCoord, Blob> = stream.to_map().await;
+	/// let map: HashMap<TileCoord, Blob> = stream.to_map().await.unwrap();
 	///
 	/// // Fast lookup by coordinate
 	/// let coord = TileCoord::new(1, 0, 0).unwrap();
@@ -334,8 +463,10 @@ where
 	/// assert_eq!(map.get(&coord).unwrap().as_str(), "tile1");
 	/// # }
 	/// ```
-	pub async fn to_map(self) -> HashMap<TileCoord, T> {
-		self.inner.collect().await
+	pub async fn to_map(self) -> Result<HashMap<TileCoord, T>> {
+		let map = self.inner.collect().await;
+		self.failure.check()?;
+		Ok(map)
 	}
 
 	/// Retrieves the next `(TileCoord, T)` item from this stream, or `None` if the stream is empty.
@@ -351,16 +482,20 @@ where
 	///     (TileCoord::new(1,1,1).unwrap(), Blob::from("data1")),
 	/// ]);
 	///
-	/// let first = stream.next().await;
+	/// let first = stream.next().await.unwrap();
 	/// assert!(first.is_some());
-	/// let second = stream.next().await;
+	/// let second = stream.next().await.unwrap();
 	/// assert!(second.is_some());
-	/// let third = stream.next().await;
+	/// let third = stream.next().await.unwrap();
 	/// assert!(third.is_none());
 	/// # }
 	/// ```
-	pub async fn next(&mut self) -> Option<(TileCoord, T)> {
-		self.inner.next().await
+	pub async fn next(&mut self) -> Result<Option<(TileCoord, T)>> {
+		let item = self.inner.next().await;
+		if item.is_none() {
+			self.failure.check()?;
+		}
+		Ok(item)
 	}
 
 	/// Applies an asynchronous callback `callback` to each `(TileCoord, T)` item.
@@ -382,12 +517,13 @@ where
 	/// }).await;
 	/// # }
 	/// ```
-	pub async fn for_each_async<F, Fut>(self, callback: F)
+	pub async fn for_each_async<F, Fut>(self, callback: F) -> Result<()>
 	where
 		F: FnMut((TileCoord, T)) -> Fut,
 		Fut: Future<Output = ()>,
 	{
 		self.inner.for_each(callback).await;
+		self.failure.check()
 	}
 
 	/// Applies an async callback to each item in parallel with concurrency limits.
@@ -437,13 +573,14 @@ where
 	/// assert_eq!(counter.load(Ordering::Relaxed), 3); // 3 tiles processed
 	/// # }
 	/// ```
-	pub async fn for_each_async_parallel<F, Fut>(self, callback: F)
+	pub async fn for_each_async_parallel<F, Fut>(self, callback: F) -> Result<()>
 	where
 		F: FnMut((TileCoord, T)) -> Fut,
 		Fut: Future<Output = ()>,
 	{
 		let limits = ConcurrencyLimits::default();
 		self.inner.for_each_concurrent(limits.mixed, callback).await; // Mixed: async callback (I/O + CPU)
+		self.failure.check()
 	}
 
 	/// Applies a synchronous callback `callback` to each `(TileCoord, T)` item.
@@ -464,7 +601,7 @@ where
 	/// }).await;
 	/// # }
 	/// ```
-	pub async fn for_each_sync<F>(self, mut callback: F)
+	pub async fn for_each_sync<F>(self, mut callback: F) -> Result<()>
 	where
 		F: FnMut((TileCoord, T)),
 	{
@@ -475,6 +612,7 @@ where
 				ready(())
 			})
 			.await;
+		self.failure.check()
 	}
 
 	/// Buffers items in chunks of size `buffer_size`, then calls `callback` with each full or final chunk.
@@ -499,7 +637,7 @@ where
 	/// // "Processing chunk of size: 1"
 	/// # }
 	/// ```
-	pub async fn for_each_buffered<F>(mut self, buffer_size: usize, mut callback: F)
+	pub async fn for_each_buffered<F>(mut self, buffer_size: usize, mut callback: F) -> Result<()>
 	where
 		F: FnMut(Vec<(TileCoord, T)>),
 	{
@@ -515,6 +653,7 @@ where
 		if !buffer.is_empty() {
 			callback(buffer);
 		}
+		self.failure.check()
 	}
 
 	// -------------------------------------------------------------------------
@@ -550,16 +689,7 @@ where
 	/// });
 	///
 	/// // Collect results, failing fast on first error
-	/// let items: Vec<(TileCoord, Blob)> = mapped
-	///     .inner
-	///     .filter_map(|(coord, result)| async move {
-	///         match result {
-	///             Ok(item) => Some(Ok((coord, item))),
-	///             Err(e) => Some(Err(e)),
-	///         }
-	///     })
-	///     .try_collect()
-	///     .await?;
+	/// let items: Vec<(TileCoord, Blob)> = mapped.try_to_vec().await?;
 	/// # Ok(())
 	/// # }
 	/// ```
@@ -568,6 +698,38 @@ where
 		F: Fn(T) -> Result<O> + Send + Sync + 'static,
 		T: 'static,
 		O: Send + Sync + 'static,
+	{
+		self.map_coord_item_parallel(move |_coord, item| callback(item))
+	}
+
+	/// Transforms the **value of type `T`** for each tile in parallel, passing the tile coordinate to the callback.
+	///
+	/// Works like [`map_item_parallel`](Self::map_item_parallel), but the callback also receives the
+	/// [`TileCoord`] of the tile it is processing, e.g. to clip geometries or to vary behavior per zoom level.
+	/// The coordinate association is kept intact through the parallel processing.
+	///
+	/// # Examples
+	/// ```
+	/// # use versatiles_core::{TileCoord, Blob, TileStream};
+	/// # async fn test() {
+	/// let stream = TileStream::from_vec(vec![
+	///     (TileCoord::new(0,0,0).unwrap(), Blob::from("data0")),
+	///     (TileCoord::new(1,1,1).unwrap(), Blob::from("data1")),
+	/// ]);
+	///
+	/// let mapped = stream.map_coord_item_parallel(|coord, value| {
+	///     Ok(Blob::from(format!("{} at level {}", value.as_str(), coord.level)))
+	/// });
+	///
+	/// let items = mapped.unwrap_results().to_vec().await.unwrap();
+	/// assert_eq!(items.len(), 2);
+	/// # }
+	/// ```
+	pub fn map_coord_item_parallel<F, O>(self, callback: F) -> TileStream<'a, Result<O>>
+	where
+		F: Fn(TileCoord, T) -> Result<O> + Send + Sync + 'static,
+		T: 'static,
+		O: Send + Sync + 'static,
 	{
 		let arc_cb = Arc::new(callback);
 		let limits = ConcurrencyLimits::default();
@@ -575,7 +737,7 @@ where
 			.inner
 			.map(move |(coord, item)| {
 				let cb = Arc::clone(&arc_cb);
-				tokio::task::spawn_blocking(move || (coord, cb(item)))
+				tokio::task::spawn_blocking(move || (coord, cb(coord, item)))
 			})
 			.buffer_unordered(limits.cpu_bound) // CPU-bound: spawn_blocking
 			.map(|result| match result {
@@ -583,7 +745,10 @@ where
 				Ok((coord, Err(e))) => (coord, Err(e.context(format!("Failed to process tile at {coord:?}")))),
 				Err(e) => panic!("Spawned task panicked: {e}"), // Task panic is still a panic (unexpected)
 			});
-		TileStream { inner: s.boxed() }
+		TileStream {
+			inner: s.boxed(),
+			failure: self.failure,
+		}
 	}
 
 	/// Transforms each tile into multiple tiles using parallel processing.
@@ -627,16 +792,7 @@ where
 	/// });
 	///
 	/// // Collect results, failing fast on first error
-	/// let tiles: Vec<(TileCoord, Blob)> = subdivided
-	///     .inner
-	///     .filter_map(|(coord, result)| async move {
-	///         match result {
-	///             Ok(item) => Some(Ok((coord, item))),
-	///             Err(e) => Some(Err(e)),
-	///         }
-	///     })
-	///     .try_collect()
-	///     .await?;
+	/// let tiles: Vec<(TileCoord, Blob)> = subdivided.try_to_vec().await?;
 	/// assert_eq!(tiles.len(), 4); // 1 input tile → 4 output tiles
 	/// # Ok(())
 	/// # }
@@ -650,6 +806,7 @@ where
 	{
 		let arc_cb = Arc::new(callback);
 		let limits = ConcurrencyLimits::default();
+		let target = self.failure.clone();
 		let s = self
 			.inner
 			.map(move |(coord, item)| {
@@ -657,10 +814,13 @@ where
 				tokio::task::spawn_blocking(move || (coord, cb(coord, item)))
 			})
 			.buffer_unordered(limits.cpu_bound) // CPU-bound: spawn_blocking
-			.flat_map_unordered(None, |result| match result {
+			.flat_map_unordered(None, move |result| match result {
 				Ok((_coord, Ok(sub_stream))) => {
 					// Successful callback: wrap each item from the sub-stream in Ok
-					sub_stream.inner.map(|(c, item)| (c, Ok(item))).boxed()
+					sub_stream
+						.forward_failure(target.clone())
+						.map(|(c, item)| (c, Ok(item)))
+						.boxed()
 				}
 				Ok((coord, Err(e))) => {
 					// Failed callback: emit a single error
@@ -672,7 +832,10 @@ where
 				}
 				Err(e) => panic!("Spawned task panicked: {e}"), // Task panic is still a panic (unexpected)
 			});
-		TileStream { inner: s.boxed() }
+		TileStream {
+			inner: s.boxed(),
+			failure: self.failure,
+		}
 	}
 
 	/// Filters and transforms the **value of type `T`** for each tile in parallel, discarding items where `callback` returns `None`.
@@ -707,16 +870,7 @@ where
 	/// });
 	///
 	/// // Collect results, failing fast on first error
-	/// let items: Vec<(TileCoord, Blob)> = filtered
-	///     .inner
-	///     .filter_map(|(coord, result)| async move {
-	///         match result {
-	///             Ok(item) => Some(Ok((coord, item))),
-	///             Err(e) => Some(Err(e)),
-	///         }
-	///     })
-	///     .try_collect()
-	///     .await?;
+	/// let items: Vec<(TileCoord, Blob)> = filtered.try_to_vec().await?;
 	/// assert_eq!(items.len(), 1);
 	/// # Ok(())
 	/// # }
@@ -726,6 +880,39 @@ where
 		F: Fn(T) -> Result<Option<O>> + Send + Sync + 'static,
 		T: 'static,
 		O: Send + Sync + 'static,
+	{
+		self.filter_map_coord_item_parallel(move |_coord, item| callback(item))
+	}
+
+	/// Filters and transforms the **value of type `T`** for each tile in parallel, passing the tile coordinate to the callback.
+	///
+	/// Works like [`filter_map_item_parallel`](Self::filter_map_item_parallel), but the callback also
+	/// receives the [`TileCoord`] of the tile it is processing. Items where the callback returns `Ok(None)`
+	/// are dropped, errors are propagated as items in the stream.
+	///
+	/// # Examples
+	/// ```
+	/// # use versatiles_core::{TileCoord, Blob, TileStream};
+	/// # async fn test() {
+	/// let stream = TileStream::from_vec(vec![
+	///     (TileCoord::new(0,0,0).unwrap(), Blob::from("data0")),
+	///     (TileCoord::new(1,1,1).unwrap(), Blob::from("data1")),
+	/// ]);
+	///
+	/// // Keep only tiles at level 0
+	/// let filtered = stream.filter_map_coord_item_parallel(|coord, value| {
+	///     Ok((coord.level == 0).then_some(value))
+	/// });
+	///
+	/// let items = filtered.unwrap_results().to_vec().await.unwrap();
+	/// assert_eq!(items.len(), 1);
+	/// # }
+	/// ```
+	pub fn filter_map_coord_item_parallel<F, O>(self, callback: F) -> TileStream<'a, Result<O>>
+	where
+		F: Fn(TileCoord, T) -> Result<Option<O>> + Send + Sync + 'static,
+		T: 'static,
+		O: Send + Sync + 'static,
 	{
 		let arc_cb = Arc::new(callback);
 		let limits = ConcurrencyLimits::default();
@@ -733,7 +920,7 @@ where
 			.inner
 			.map(move |(coord, item)| {
 				let cb = Arc::clone(&arc_cb);
-				tokio::task::spawn_blocking(move || (coord, cb(item)))
+				tokio::task::spawn_blocking(move || (coord, cb(coord, item)))
 			})
 			.buffer_unordered(limits.cpu_bound) // CPU-bound: spawn_blocking
 			.filter_map(|result| async move {
@@ -744,7 +931,74 @@ where
 					Err(e) => panic!("Spawned task panicked: {e}"), // Task panic is still a panic (unexpected)
 				}
 			});
-		TileStream { inner: s.boxed() }
+		TileStream {
+			inner: s.boxed(),
+			failure: self.failure,
+		}
+	}
+
+	/// Like [`map_item_parallel`](Self::map_item_parallel), but ends the stream at the first error.
+	///
+	/// Use it where a failing tile must fail the whole operation: the error, with the coordinate
+	/// of its tile, is returned to whoever checks the [`StreamFailure`] of the stream, and no
+	/// worker panics.
+	///
+	/// # Examples
+	/// ```
+	/// # use versatiles_core::{TileCoord, Blob, TileStream};
+	/// # async fn test() -> anyhow::Result<()> {
+	/// let stream = TileStream::from_vec(vec![
+	///     (TileCoord::new(0,0,0)?, Blob::from("data")),
+	///     (TileCoord::new(1,1,1)?, Blob::from("")),
+	/// ]);
+	///
+	/// let mapped = stream.try_map_item_parallel(|blob| {
+	///     anyhow::ensure!(!blob.is_empty(), "empty tile");
+	///     Ok(blob.len())
+	/// });
+	///
+	/// let failure = mapped.failure_handle();
+	/// let items = mapped.to_vec().await.unwrap();
+	/// assert!(items.len() <= 1);
+	/// assert_eq!(failure.check().unwrap_err().to_string(), "Failed to process tile at TileCoord(1, [1, 1])");
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn try_map_item_parallel<F, O>(self, callback: F) -> TileStream<'a, O>
+	where
+		F: Fn(T) -> Result<O> + Send + Sync + 'static,
+		T: 'static,
+		O: Send + Sync + 'static,
+	{
+		self.map_item_parallel(callback).stop_at_error()
+	}
+
+	/// Like [`filter_map_item_parallel`](Self::filter_map_item_parallel), but ends the stream at the
+	/// first error, see [`try_map_item_parallel`](Self::try_map_item_parallel).
+	///
+	/// # Examples
+	/// ```
+	/// # use versatiles_core::{TileCoord, Blob, TileStream};
+	/// # async fn test() -> anyhow::Result<()> {
+	/// let stream = TileStream::from_vec(vec![
+	///     (TileCoord::new(0,0,0)?, Blob::from("keep")),
+	///     (TileCoord::new(1,1,1)?, Blob::from("drop")),
+	/// ]);
+	///
+	/// let filtered = stream.try_filter_map_item_parallel(|blob| Ok((blob.as_str() == "keep").then_some(blob)));
+	/// let failure = filtered.failure_handle();
+	/// assert_eq!(filtered.to_vec().await.unwrap().len(), 1);
+	/// failure.check()?;
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn try_filter_map_item_parallel<F, O>(self, callback: F) -> TileStream<'a, O>
+	where
+		F: Fn(T) -> Result<Option<O>> + Send + Sync + 'static,
+		T: 'static,
+		O: Send + Sync + 'static,
+	{
+		self.filter_map_item_parallel(callback).stop_at_error()
 	}
 
 	// -------------------------------------------------------------------------
@@ -768,7 +1022,7 @@ where
 	///     TileCoord::new(coord.level + 1, coord.x, coord.y).unwrap()
 	/// });
 	///
-	/// let items = mapped_coords.to_vec().await;
+	/// let items = mapped_coords.to_vec().await.unwrap();
 	/// // The tile data remains the same, but each coordinate has its level incremented.
 	/// # }
 	/// ```
@@ -777,7 +1031,10 @@ where
 		F: FnMut(TileCoord) -> TileCoord + Send + 'a,
 	{
 		let s = self.inner.map(move |(coord, item)| (callback(coord), item)).boxed();
-		TileStream { inner: s }
+		TileStream {
+			inner: s,
+			failure: self.failure,
+		}
 	}
 
 	/// Filters the stream by **tile coordinate** using an *asynchronous* predicate.
@@ -802,7 +1059,7 @@ where
 	///
 	/// // Keep only tiles at zoom level 0.
 	/// let filtered = stream.filter_coord(|coord| async move { coord.level == 0 });
-	/// let items = filtered.to_vec().await;
+	/// let items = filtered.to_vec().await.unwrap();
 	///
 	/// assert_eq!(items.len(), 1);
 	/// assert_eq!(items[0].0.level, 0);
@@ -814,7 +1071,10 @@ where
 		Fut: Future<Output = bool> + Send + 'a,
 	{
 		let s = self.inner.filter(move |(coord, _item)| callback(*coord)).boxed();
-		TileStream { inner: s }
+		TileStream {
+			inner: s,
+			failure: self.failure,
+		}
 	}
 
 	/// Observes each item passing through the stream by calling a callback.
@@ -852,7 +1112,8 @@ where
 	///         counter_clone.fetch_add(1, Ordering::Relaxed);
 	///     })
 	///     .to_vec()
-	///     .await;
+	///     .await
+	///     .unwrap();
 	///
 	/// assert_eq!(counter.load(Ordering::Relaxed), 3);
 	/// assert_eq!(result.len(), 3);
@@ -870,6 +1131,7 @@ where
 					item
 				})
 				.boxed(),
+			failure: self.failure,
 		}
 	}
 
@@ -888,11 +1150,11 @@ where
 	///     (TileCoord::new(1,1,1).unwrap(), Blob::from("data1")),
 	/// ]);
 	///
-	/// let count = stream.drain_and_count().await;
+	/// let count = stream.drain_and_count().await.unwrap();
 	/// assert_eq!(count, 2);
 	/// # }
 	/// ```
-	pub async fn drain_and_count(self) -> u64 {
+	pub async fn drain_and_count(self) -> Result<u64> {
 		let mut count = 0u64;
 		self
 			.inner
@@ -901,7 +1163,38 @@ where
 				ready(())
 			})
 			.await;
-		count
+		self.failure.check()?;
+		Ok(count)
+	}
+
+	/// Returns a handle to the first error of a fallible step of this stream, see [`StreamFailure`].
+	///
+	/// Use it where the stream is consumed by code that doesn't report the error, and call
+	/// [`StreamFailure::check`] afterwards.
+	///
+	/// # Examples
+	/// ```
+	/// # use versatiles_core::{TileCoord, Blob, TileStream};
+	/// # async fn test() -> anyhow::Result<()> {
+	/// let stream = TileStream::from_vec(vec![(TileCoord::new(0,0,0)?, Blob::from("data"))])
+	///     .try_map_item_parallel(|blob| Ok(blob.len()));
+	/// let failure = stream.failure_handle();
+	/// let items = stream.to_vec().await.unwrap();
+	/// failure.check()?;
+	/// assert_eq!(items.len(), 1);
+	/// # Ok(())
+	/// # }
+	/// ```
+	#[must_use]
+	pub fn failure_handle(&self) -> StreamFailure {
+		self.failure.clone()
+	}
+
+	/// Returns the inner stream, moving an error of this stream into `target` once it ends.
+	fn forward_failure(self, target: StreamFailure) -> BoxStream<'a, (TileCoord, T)> {
+		let failure = self.failure;
+		let end = stream::once(async move { failure.forward_to(&target) }).filter_map(|()| ready(None));
+		self.inner.chain(end).boxed()
 	}
 }
 
@@ -916,8 +1209,8 @@ where
 	/// fine-grained error handling. If any item in the stream is an `Err`, this will panic
 	/// with the error message.
 	///
-	/// For proper error handling, use the stream's `.inner` field directly with `try_collect()`,
-	/// `try_for_each()`, or similar methods from `TryStreamExt`.
+	/// For proper error handling, use [`try_for_each`](Self::try_for_each) or
+	/// [`try_to_vec`](Self::try_to_vec) instead.
 	///
 	/// # Panics
 	/// Panics if any item in the stream is an `Err`.
@@ -947,6 +1240,300 @@ where
 					(coord, item)
 				})
 				.boxed(),
+			failure: self.failure,
+		}
+	}
+
+	/// Ends the stream at the first `Err` item and stores the error, see [`StreamFailure`].
+	///
+	/// Unlike [`unwrap_results`](Self::unwrap_results), an error doesn't panic in the middle of
+	/// the stream, so whoever checks the failure handle can return it.
+	///
+	/// # Examples
+	/// ```
+	/// # use versatiles_core::{TileCoord, TileStream};
+	/// # async fn example() -> anyhow::Result<()> {
+	/// let stream = TileStream::from_vec(vec![
+	///     (TileCoord::new(0,0,0)?, Ok(1)),
+	///     (TileCoord::new(1,0,0)?, Err(anyhow::anyhow!("broken"))),
+	///     (TileCoord::new(1,1,0)?, Ok(2)),
+	/// ])
+	/// .stop_at_error();
+	/// let failure = stream.failure_handle();
+	/// assert_eq!(stream.to_vec().await.unwrap(), [(TileCoord::new(0,0,0)?, 1)]);
+	/// assert_eq!(failure.check().unwrap_err().to_string(), "broken");
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn stop_at_error(self) -> TileStream<'a, T> {
+		let failure = self.failure.clone();
+		TileStream {
+			inner: self
+				.inner
+				.scan((), move |(), (coord, result)| {
+					ready(match result {
+						Ok(item) => Some((coord, item)),
+						Err(e) => {
+							failure.record(e);
+							None
+						}
+					})
+				})
+				.boxed(),
+			failure: self.failure,
+		}
+	}
+
+	/// Drops all `Err` items from the stream and logs each of them as a warning.
+	///
+	/// Use this where a single broken tile must not stop the whole stream. The log message
+	/// contains the coordinate of the tile, so it can be located in the source.
+	///
+	/// # Examples
+	/// ```
+	/// # use versatiles_core::{TileCoord, Blob, TileStream};
+	/// # async fn example() -> anyhow::Result<()> {
+	/// let stream = TileStream::from_vec(vec![
+	///     (TileCoord::new(0,0,0)?, Blob::from("data")),
+	///     (TileCoord::new(1,0,0)?, Blob::from("")),
+	/// ]);
+	/// let items = stream
+	///     .map_item_parallel(|blob| {
+	///         anyhow::ensure!(!blob.is_empty(), "empty tile");
+	///         Ok(blob.len())
+	///     })
+	///     .skip_errors()
+	///     .to_vec()
+	///     .await?;
+	/// assert_eq!(items, [(TileCoord::new(0,0,0)?, 4)]);
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn skip_errors(self) -> TileStream<'a, T> {
+		TileStream {
+			inner: self
+				.inner
+				.filter_map(|(coord, result)| async move {
+					match result {
+						Ok(item) => Some((coord, item)),
+						Err(e) => {
+							log::warn!("skipping tile at {coord:?}: {e:#}");
+							None
+						}
+					}
+				})
+				.boxed(),
+			failure: self.failure,
+		}
+	}
+
+	/// Calls `callback` for every item, stopping at the first error.
+	///
+	/// Returns the first `Err` item of the stream, or the first error returned by `callback`
+	/// with the coordinate of its tile as context. Remaining items are not processed.
+	///
+	/// # Examples
+	/// ```
+	/// # use versatiles_core::{TileCoord, Blob, TileStream};
+	/// # async fn example() -> anyhow::Result<()> {
+	/// let stream = TileStream::from_vec(vec![
+	///     (TileCoord::new(0,0,0)?, Blob::from("data0")),
+	///     (TileCoord::new(1,1,1)?, Blob::from("data1")),
+	/// ]);
+	///
+	/// let mut sizes = Vec::new();
+	/// stream
+	///     .map_item_parallel(|blob| Ok(blob.len()))
+	///     .try_for_each(|_coord, size| {
+	///         sizes.push(size);
+	///         Ok(())
+	///     })
+	///     .await?;
+	/// assert_eq!(sizes, [5, 5]);
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub async fn try_for_each<F>(mut self, mut callback: F) -> Result<()>
+	where
+		F: FnMut(TileCoord, T) -> Result<()>,
+	{
+		while let Some((coord, result)) = self.inner.next().await {
+			callback(coord, result?).with_context(|| format!("Failed to process tile at {coord:?}"))?;
+		}
+		self.failure.check()
+	}
+
+	/// Collects all items into a vector, stopping at the first error.
+	///
+	/// # Examples
+	/// ```
+	/// # use versatiles_core::{TileCoord, Blob, TileStream};
+	/// # async fn example() -> anyhow::Result<()> {
+	/// let stream = TileStream::from_vec(vec![(TileCoord::new(0,0,0)?, Blob::from("data"))]);
+	/// let items = stream.map_item_parallel(|blob| Ok(blob.len())).try_to_vec().await?;
+	/// assert_eq!(items, [(TileCoord::new(0,0,0)?, 4)]);
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub async fn try_to_vec(self) -> Result<Vec<(TileCoord, T)>> {
+		let mut vec = Vec::new();
+		self
+			.try_for_each(|coord, item| {
+				vec.push((coord, item));
+				Ok(())
+			})
+			.await?;
+		Ok(vec)
+	}
+}
+
+/// Number of tiles [`TileStream::tee`] buffers before pausing the fastest consumer.
+pub const DEFAULT_TEE_BUFFER_TILES: usize = 1024;
+
+/// Methods for streams whose items can be cloned
+impl<'a, T> TileStream<'a, T>
+where
+	T: Clone + Send + 'a,
+{
+	/// Splits this stream into `n` independent streams that all yield the same items in the
+	/// same order, buffering at most [`DEFAULT_TEE_BUFFER_TILES`] tiles.
+	///
+	/// See [`TileStream::tee_buffered`] for details.
+	///
+	/// # Examples
+	/// ```
+	/// # use versatiles_core::{TileCoord, Blob, TileStream};
+	/// # async fn test() {
+	/// let stream = TileStream::from_vec(vec![
+	///     (TileCoord::new(0, 0, 0).unwrap(), Blob::from("a")),
+	///     (TileCoord::new(1, 0, 0).unwrap(), Blob::from("b")),
+	/// ]);
+	///
+	/// let mut streams = stream.tee(2);
+	/// let (a, b) = futures::join!(streams.remove(0).to_vec(), streams.remove(0).to_vec());
+	/// assert_eq!(a.unwrap(), b.unwrap());
+	/// # }
+	/// ```
+	#[must_use]
+	pub fn tee(self, n: usize) -> Vec<TileStream<'a, T>> {
+		self.tee_buffered(n, DEFAULT_TEE_BUFFER_TILES)
+	}
+
+	/// Splits this stream into `n` independent streams that all yield the same items in the
+	/// same order.
+	///
+	/// The source is polled by whichever consumer needs the next item first. Items are kept in
+	/// a shared buffer until the slowest consumer has read them. If the buffer holds
+	/// `buffer_tiles` items, the fastest consumers wait for the slowest one, so the consumers
+	/// must be polled concurrently (e.g. with `join!`) unless `buffer_tiles` covers the whole
+	/// stream. Dropping a consumer releases its share of the buffer.
+	#[must_use]
+	pub fn tee_buffered(self, n: usize, buffer_tiles: usize) -> Vec<TileStream<'a, T>> {
+		let state = Arc::new(Mutex::new(TeeState {
+			source: Some(self.inner),
+			buffer: VecDeque::new(),
+			offset: 0,
+			positions: vec![Some(0); n],
+			buffer_tiles: buffer_tiles.max(1),
+			wakers: Vec::new(),
+		}));
+		(0..n)
+			.map(|index| TileStream {
+				inner: Box::pin(TeeConsumer {
+					state: Arc::clone(&state),
+					index,
+				}),
+				failure: self.failure.clone(),
+			})
+			.collect()
+	}
+}
+
+/// Shared state of all streams created by [`TileStream::tee_buffered`].
+struct TeeState<'a, T> {
+	/// The source stream, `None` once it is exhausted.
+	source: Option<BoxStream<'a, (TileCoord, T)>>,
+	/// Items that not every consumer has read yet.
+	buffer: VecDeque<(TileCoord, T)>,
+	/// Stream index of the first item in `buffer`.
+	offset: usize,
+	/// Stream index of the next item of every consumer, `None` for dropped consumers.
+	positions: Vec<Option<usize>>,
+	/// Maximum number of items in `buffer`.
+	buffer_tiles: usize,
+	/// Consumers waiting for the source or for buffer space.
+	wakers: Vec<Waker>,
+}
+
+impl<T> TeeState<'_, T> {
+	/// Removes all items that every consumer has read.
+	fn trim(&mut self) {
+		let min = self.positions.iter().flatten().min().copied().unwrap_or(usize::MAX);
+		while self.offset < min && self.buffer.pop_front().is_some() {
+			self.offset += 1;
+		}
+	}
+
+	fn wake_all(&mut self) {
+		self.wakers.drain(..).for_each(Waker::wake);
+	}
+}
+
+/// One of the streams created by [`TileStream::tee_buffered`].
+struct TeeConsumer<'a, T> {
+	state: Arc<Mutex<TeeState<'a, T>>>,
+	index: usize,
+}
+
+impl<T: Clone> Stream for TeeConsumer<'_, T> {
+	type Item = (TileCoord, T);
+
+	fn poll_next(self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<Option<Self::Item>> {
+		let mut state = self.state.lock().unwrap();
+		let position = state.positions[self.index].expect("a polled consumer is not dropped");
+
+		if position == state.offset + state.buffer.len() {
+			if state.buffer.len() >= state.buffer_tiles {
+				// the buffer is full: wait for the slowest consumer
+				state.wakers.push(cx.waker().clone());
+				return Poll::Pending;
+			}
+			let Some(source) = state.source.as_mut() else {
+				return Poll::Ready(None);
+			};
+			match source.poll_next_unpin(cx) {
+				Poll::Ready(Some(item)) => state.buffer.push_back(item),
+				Poll::Ready(None) => state.source = None,
+				Poll::Pending => {
+					// the source only wakes the last consumer that polled it
+					state.wakers.push(cx.waker().clone());
+					return Poll::Pending;
+				}
+			}
+			// other consumers may be waiting for this item or for the end of the stream
+			state.wake_all();
+			if state.source.is_none() && position == state.offset + state.buffer.len() {
+				return Poll::Ready(None);
+			}
+		}
+
+		let item = state.buffer[position - state.offset].clone();
+		state.positions[self.index] = Some(position + 1);
+		let len = state.buffer.len();
+		state.trim();
+		if state.buffer.len() < len {
+			state.wake_all();
+		}
+		Poll::Ready(Some(item))
+	}
+}
+
+impl<T> Drop for TeeConsumer<'_, T> {
+	fn drop(&mut self) {
+		if let Ok(mut state) = self.state.lock() {
+			state.positions[self.index] = None;
+			state.trim();
+			state.wake_all();
 		}
 	}
 }
@@ -954,7 +1541,7 @@ where
 #[cfg(test)]
 mod tests {
 	use super::*;
-	use futures::TryStreamExt;
+	use futures::{FutureExt, TryStreamExt};
 	use std::sync::atomic::{AtomicUsize, Ordering};
 	use tokio::sync::Mutex;
 
@@ -962,6 +1549,58 @@ mod tests {
 		TileCoord::new(level, x, y).unwrap()
 	}
 
+	fn blob_stream(count: u32) -> TileStream<'static> {
+		let coords = (0..count).map(|i| tc(8, i, i * 2)).collect::<Vec<_>>();
+		TileStream::from_iter_coord_parallel(coords.into_iter(), |coord| {
+			Some(Blob::from(format!("tile {}/{}/{}", coord.level, coord.x, coord.y)))
+		})
+	}
+
+	#[tokio::test]
+	async fn tee_yields_identical_sequences() {
+		let mut streams = blob_stream(100).tee_buffered(2, 4);
+		let (a, b) = futures::join!(streams.remove(0).to_vec(), streams.remove(0).to_vec());
+		let (a, b) = (a.unwrap(), b.unwrap());
+		assert_eq!(a.len(), 100);
+		assert_eq!(a, b);
+	}
+
+	#[tokio::test]
+	async fn tee_pauses_fastest_consumer() {
+		let items = (0..10).map(|i| (tc(4, i, 0), i)).collect::<Vec<_>>();
+		let mut streams = TileStream::from_vec(items).tee_buffered(2, 3);
+		let mut b = streams.pop().unwrap();
+		let mut a = streams.pop().unwrap();
+
+		let next = |s: &mut TileStream<'_, u32>| s.inner.next().now_or_never().map(|item| item.unwrap().1);
+		assert_eq!([next(&mut a), next(&mut a), next(&mut a)], [Some(0), Some(1), Some(2)]);
+		// the buffer is full until `b` catches up
+		assert_eq!(next(&mut a), None);
+		assert_eq!(next(&mut b), Some(0));
+		assert_eq!(next(&mut a), Some(3));
+		assert_eq!(next(&mut a), None);
+
+		// dropping `b` releases the buffer
+		drop(b);
+		assert_eq!(a.to_vec().await.unwrap().len(), 6);
+	}
+
+	#[tokio::test]
+	async fn tee_edge_cases() {
+		assert!(blob_stream(3).tee(0).is_empty());
+
+		let mut streams = blob_stream(3).tee(3);
+		assert_eq!(streams.len(), 3);
+		streams.remove(1);
+		let (a, b) = futures::join!(streams.remove(0).to_vec(), streams.remove(0).to_vec());
+		let (a, b) = (a.unwrap(), b.unwrap());
+		assert_eq!(a, b);
+		assert_eq!(
+			TileStream::<u8>::empty().tee(2).pop().unwrap().to_vec().await.unwrap(),
+			[]
+		);
+	}
+
 	#[tokio::test]
 	async fn should_flat_map_parallel_and_flatten_results() {
 		// Base stream with two coords
@@ -1008,7 +1647,7 @@ mod tests {
 		let tile_data = vec![(tc(0, 0, 0), Blob::from("tile0")), (tc(1, 1, 1), Blob::from("tile1"))];
 
 		let tile_stream = TileStream::from_vec(tile_data.clone());
-		let collected = tile_stream.to_vec().await;
+		let collected = tile_stream.to_vec().await.unwrap();
 
 		assert_eq!(collected, tile_data);
 	}
@@ -1028,7 +1667,8 @@ mod tests {
 			.for_each_sync(|(coord, blob)| {
 				result.push(format!("{}, {}", coord.as_json(), blob.as_str()));
 			})
-			.await;
+			.await
+			.unwrap();
 
 		assert_eq!(
 			result,
@@ -1046,7 +1686,7 @@ mod tests {
 
 		let mapped = original.map_coord(|coord| tc(coord.level + 1, coord.x * 2, coord.y * 2));
 
-		let items = mapped.to_vec().await;
+		let items = mapped.to_vec().await.unwrap();
 		assert_eq!(items.len(), 1);
 		let (coord, blob) = &items[0];
 		assert_eq!(coord.x, 2);
@@ -1064,7 +1704,7 @@ mod tests {
 		];
 
 		let tile_stream = TileStream::from_vec(tile_data);
-		let count = tile_stream.drain_and_count().await;
+		let count = tile_stream.drain_and_count().await.unwrap();
 		assert_eq!(count, 3, "Should drain exactly 3 items");
 	}
 
@@ -1084,7 +1724,8 @@ mod tests {
 				// Each chunk is at most size 2
 				results.push(chunk.len());
 			})
-			.await;
+			.await
+			.unwrap();
 
 		// Should process a chunk of size 2, then a chunk of size 1
 		assert_eq!(results, vec![2, 1]);
@@ -1155,10 +1796,70 @@ mod tests {
 		assert_eq!(texts, ["kept-keep0", "kept-keep2"]);
 	}
 
+	#[tokio::test]
+	async fn should_pass_coord_to_parallel_mapping() {
+		let coords = crate::TileBBox::new_full(3)
+			.unwrap()
+			.into_iter_coords()
+			.collect::<Vec<_>>();
+		let tile_data = coords.iter().map(|c| (*c, Blob::new_empty())).collect::<Vec<_>>();
+
+		let items = TileStream::from_vec(tile_data)
+			.map_coord_item_parallel(|coord, _blob| Ok(Blob::from(coord.as_json())))
+			.unwrap_results()
+			.to_vec()
+			.await
+			.unwrap();
+
+		assert_eq!(items.len(), coords.len());
+		for (coord, blob) in items {
+			assert_eq!(blob.as_str(), coord.as_json());
+		}
+	}
+
+	#[tokio::test]
+	async fn should_pass_coord_to_parallel_filter_mapping() {
+		let tile_data = vec![
+			(tc(0, 0, 0), Blob::new_empty()),
+			(tc(1, 1, 1), Blob::new_empty()),
+			(tc(2, 2, 2), Blob::new_empty()),
+		];
+
+		let items = TileStream::from_vec(tile_data)
+			.filter_map_coord_item_parallel(|coord, _blob| Ok((coord.level != 1).then(|| Blob::from(coord.as_json()))))
+			.unwrap_results()
+			.to_map()
+			.await
+			.unwrap();
+
+		assert_eq!(items.len(), 2);
+		assert_eq!(items[&tc(0, 0, 0)].as_str(), tc(0, 0, 0).as_json());
+		assert_eq!(items[&tc(2, 2, 2)].as_str(), tc(2, 2, 2).as_json());
+	}
+
+	#[tokio::test]
+	async fn should_propagate_errors_with_coord() {
+		let tile_data = vec![(tc(0, 0, 0), Blob::new_empty()), (tc(1, 1, 1), Blob::new_empty())];
+
+		let mut items = TileStream::from_vec(tile_data)
+			.map_coord_item_parallel(|coord, blob| {
+				anyhow::ensure!(coord.level == 0, "bad level");
+				Ok(blob)
+			})
+			.to_vec()
+			.await
+			.unwrap();
+		items.sort_by_key(|(coord, _)| coord.level);
+
+		assert!(items[0].1.is_ok());
+		let err = items[1].1.as_ref().unwrap_err();
+		assert_eq!(err.root_cause().to_string(), "bad level");
+	}
+
 	#[tokio::test]
 	async fn should_construct_empty_stream() {
 		let empty = TileStream::<Blob>::empty();
-		let collected = empty.to_vec().await;
+		let collected = empty.to_vec().await.unwrap();
 		assert!(collected.is_empty());
 	}
 
@@ -1174,14 +1875,98 @@ mod tests {
 
 		// Merge them
 		let merged = TileStream::<Blob>::from_streams(stream::iter(substreams));
-		let items = merged.to_vec().await;
+		let items = merged.to_vec().await.unwrap();
 		assert_eq!(items.len(), 2);
 	}
 
+	#[tokio::test]
+	async fn should_limit_concurrent_streams() {
+		use std::sync::atomic::{AtomicUsize, Ordering};
+
+		let active = Arc::new(AtomicUsize::new(0));
+		let max_active = Arc::new(AtomicUsize::new(0));
+		let futures = (0..6u32).map(|x| {
+			let (active, max_active) = (active.clone(), max_active.clone());
+			async move {
+				let now = active.fetch_add(1, Ordering::SeqCst) + 1;
+				max_active.fetch_max(now, Ordering::SeqCst);
+				for _ in 0..3 {
+					tokio::task::yield_now().await;
+				}
+				active.fetch_sub(1, Ordering::SeqCst);
+				TileStream::from_vec(vec![(tc(3, x, 0), Blob::from("tile"))])
+			}
+		});
+
+		let items = TileStream::<Blob>::from_streams_limited(stream::iter(futures), 2)
+			.to_vec()
+			.await
+			.unwrap();
+		assert_eq!(items.len(), 6);
+		assert_eq!(max_active.load(Ordering::SeqCst), 2);
+	}
+
+	#[tokio::test]
+	async fn should_limit_concurrent_lazy_streams() {
+		use std::sync::atomic::{AtomicUsize, Ordering};
+
+		// the futures are ready at once, the streams do their work while they are drained
+		let active = Arc::new(AtomicUsize::new(0));
+		let max_active = Arc::new(AtomicUsize::new(0));
+		let futures = (0..6u32).map(|x| {
+			let (active, max_active) = (active.clone(), max_active.clone());
+			async move {
+				let tiles = stream::unfold(0u32, move |y| {
+					let (active, max_active) = (active.clone(), max_active.clone());
+					async move {
+						if y == 0 {
+							let now = active.fetch_add(1, Ordering::SeqCst) + 1;
+							max_active.fetch_max(now, Ordering::SeqCst);
+						}
+						tokio::task::yield_now().await;
+						if y == 3 {
+							active.fetch_sub(1, Ordering::SeqCst);
+							return None;
+						}
+						Some(((tc(3, x, y), Blob::from("tile")), y + 1))
+					}
+				});
+				TileStream::from_stream(tiles.boxed())
+			}
+		});
+
+		let items = TileStream::<Blob>::from_streams_limited(stream::iter(futures), 2)
+			.to_vec()
+			.await
+			.unwrap();
+		assert_eq!(items.len(), 18);
+		assert_eq!(max_active.load(Ordering::SeqCst), 2);
+	}
+
+	#[tokio::test]
+	async fn should_keep_order_of_ordered_streams() {
+		// later streams are ready earlier
+		let futures = (0..4u32).map(|x| async move {
+			for _ in 0..(4 - x) * 3 {
+				tokio::task::yield_now().await;
+			}
+			TileStream::from_vec(vec![(tc(3, x, 0), Blob::from("a")), (tc(3, x, 1), Blob::from("b"))])
+		});
+
+		let coords = TileStream::<Blob>::from_streams_ordered(stream::iter(futures))
+			.to_vec()
+			.await
+			.unwrap()
+			.into_iter()
+			.map(|(coord, _)| (coord.x, coord.y))
+			.collect::<Vec<_>>();
+		assert_eq!(coords, [(0, 0), (0, 1), (1, 0), (1, 1), (2, 0), (2, 1), (3, 0), (3, 1)]);
+	}
+
 	#[tokio::test]
 	async fn should_return_none_if_stream_is_empty() {
 		let mut empty = TileStream::<Blob>::empty();
-		assert!(empty.next().await.is_none());
+		assert!(empty.next().await.unwrap().is_none());
 	}
 
 	#[tokio::test]
@@ -1198,7 +1983,8 @@ mod tests {
 				collected.lock().await.push((coord, blob));
 			}
 		})
-		.await;
+		.await
+		.unwrap();
 
 		let collected = collected_mutex.lock().await;
 		assert_eq!(collected.len(), 2);
@@ -1211,7 +1997,7 @@ mod tests {
 		let stream = TileStream::from_vec(vec![(tc(0, 0, 0), Blob::from("z0")), (tc(1, 1, 1), Blob::from("z1"))]);
 
 		let filtered = stream.filter_coord(|coord| async move { coord.level == 0 });
-		let items = filtered.to_vec().await;
+		let items = filtered.to_vec().await.unwrap();
 
 		assert_eq!(items.len(), 1);
 		assert_eq!(items[0].0.level, 0);
@@ -1226,7 +2012,7 @@ mod tests {
 			Some(Blob::from(format!("v{}", coord.level)))
 		});
 
-		let mut items = stream.to_vec().await;
+		let mut items = stream.to_vec().await.unwrap();
 		// Sort for deterministic assertion on unordered parallel output
 		items.sort_by_key(|(coord, _)| coord.level);
 
@@ -1247,7 +2033,7 @@ mod tests {
 			}
 		});
 
-		let items = stream.to_vec().await;
+		let items = stream.to_vec().await.unwrap();
 		assert_eq!(items.len(), 1);
 		assert_eq!(items[0].0.level, 0);
 		assert_eq!(items[0].1.as_str(), "keep");
@@ -1387,7 +2173,8 @@ mod tests {
 					counter.fetch_add(1, Ordering::SeqCst);
 				}
 			})
-			.await;
+			.await
+			.unwrap();
 
 		assert_eq!(counter.load(Ordering::SeqCst), 6);
 		assert!(max_parallel.load(Ordering::SeqCst) > 1);
@@ -1403,7 +2190,128 @@ mod tests {
 				as Pin<Box<dyn Future<Output = TileStream<'static>> + Send>>,
 		];
 		let merged = TileStream::<Blob>::from_streams(stream::iter(substreams));
-		let items = merged.to_vec().await;
+		let items = merged.to_vec().await.unwrap();
 		assert_eq!(items.len(), 2);
 	}
+	#[tokio::test]
+	async fn try_for_each_stops_at_first_error() {
+		let stream = TileStream::from_vec((0..10).map(|x| (tc(4, x, 0), x)).collect());
+		let mut seen = Vec::new();
+		let err = stream
+			.map_coord_item_parallel(|coord, x| {
+				if x == 5 {
+					anyhow::bail!("bad tile")
+				} else {
+					Ok((coord, x))
+				}
+			})
+			.try_for_each(|_coord, (_, x)| {
+				seen.push(x);
+				Ok(())
+			})
+			.await
+			.unwrap_err();
+		assert_eq!(format!("{err}"), "Failed to process tile at TileCoord(4, [5, 0])");
+		assert!(format!("{err:?}").contains("bad tile"));
+		assert!(!seen.contains(&5));
+	}
+
+	#[tokio::test]
+	async fn try_for_each_reports_callback_errors_with_coord() {
+		let stream = TileStream::from_vec(vec![(tc(1, 0, 0), Ok(1)), (tc(1, 1, 0), Ok(2)), (tc(1, 1, 1), Ok(3))]);
+		let mut count = 0;
+		let err = stream
+			.try_for_each(|_coord, x: u32| {
+				count += 1;
+				anyhow::ensure!(x < 2, "value {x} is too big");
+				Ok(())
+			})
+			.await
+			.unwrap_err();
+		assert_eq!(count, 2);
+		assert_eq!(format!("{err}"), "Failed to process tile at TileCoord(1, [1, 0])");
+		assert!(format!("{err:?}").contains("value 2 is too big"));
+	}
+
+	#[tokio::test]
+	async fn skip_errors_drops_failed_tiles() {
+		let stream: TileStream<Result<u32>> = TileStream::from_vec(vec![
+			(tc(0, 0, 0), Ok(1)),
+			(tc(1, 0, 0), Err(anyhow::anyhow!("broken"))),
+			(tc(1, 1, 0), Ok(3)),
+		]);
+		assert_eq!(
+			stream.skip_errors().to_vec().await.unwrap(),
+			[(tc(0, 0, 0), 1), (tc(1, 1, 0), 3)]
+		);
+	}
+
+	#[tokio::test]
+	async fn try_to_vec_collects_or_fails() -> Result<()> {
+		let ok: TileStream<Result<u32>> = TileStream::from_vec(vec![(tc(0, 0, 0), Ok(1)), (tc(1, 0, 0), Ok(2))]);
+		assert_eq!(ok.try_to_vec().await?, [(tc(0, 0, 0), 1), (tc(1, 0, 0), 2)]);
+
+		let failing: TileStream<Result<u32>> = TileStream::from_vec(vec![
+			(tc(0, 0, 0), Ok(1)),
+			(tc(1, 0, 0), Err(anyhow::anyhow!("broken"))),
+		]);
+		assert_eq!(failing.try_to_vec().await.unwrap_err().to_string(), "broken");
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn try_map_item_parallel_reports_errors_through_merged_streams() {
+		let streams = (0..3u32).map(|y| async move {
+			TileStream::from_vec((0..4).map(|x| (tc(4, x, y), x + y)).collect()).try_map_item_parallel(|v| {
+				anyhow::ensure!(v != 4, "bad value");
+				Ok(v)
+			})
+		});
+		let stream = TileStream::from_streams(stream::iter(streams)).map_coord(|coord| coord);
+		let err = stream.to_vec().await.unwrap_err();
+		assert!(format!("{err:?}").contains("bad value"));
+	}
+
+	#[tokio::test]
+	async fn failure_handle_reports_the_error_once() {
+		let stream = TileStream::from_vec(vec![(tc(0, 0, 0), 1u32), (tc(1, 0, 0), 2)]).try_map_item_parallel(|v| {
+			anyhow::ensure!(v != 2, "bad value");
+			Ok(v)
+		});
+		let failure = stream.failure_handle();
+		let err = stream.to_vec().await.unwrap_err();
+		assert!(format!("{err:?}").contains("bad value"));
+		// the error is only returned once
+		assert!(failure.check().is_ok());
+	}
+
+	#[tokio::test]
+	async fn try_for_each_returns_stored_failure() {
+		let stream: TileStream<Result<u32>> = TileStream::from_vec(vec![
+			(tc(0, 0, 0), Ok(1)),
+			(tc(1, 0, 0), Err(anyhow::anyhow!("broken"))),
+		])
+		.stop_at_error()
+		.map_item_parallel(Ok);
+		assert_eq!(stream.try_to_vec().await.unwrap_err().to_string(), "broken");
+	}
+
+	#[tokio::test]
+	async fn consumers_return_the_failure_at_the_end() {
+		let stream = || -> TileStream<u32> {
+			TileStream::from_vec(vec![
+				(tc(0, 0, 0), Ok(1)),
+				(tc(1, 0, 0), Err(anyhow::anyhow!("broken"))),
+			])
+			.stop_at_error()
+		};
+		assert_eq!(stream().to_vec().await.unwrap_err().to_string(), "broken");
+		assert_eq!(stream().to_map().await.unwrap_err().to_string(), "broken");
+		assert_eq!(stream().drain_and_count().await.unwrap_err().to_string(), "broken");
+		assert_eq!(stream().for_each_sync(|_| {}).await.unwrap_err().to_string(), "broken");
+
+		let mut stream = stream();
+		assert_eq!(stream.next().await.unwrap(), Some((tc(0, 0, 0), 1)));
+		assert_eq!(stream.next().await.unwrap_err().to_string(), "broken");
+	}
 }
@@ -8,3 +8,4 @@ use tilejson_values::TileJsonValues;
 use vector_layer::VectorLayers;
 
 pub use lib::TileJSON;
+pub use vector_layer::VectorLayer;
@@ -245,7 +245,7 @@ impl FromIterator<(String, VectorLayer)> for VectorLayers {
 /// - `fields`: A mapping from field names -> field types (both `String`).
 /// - `description`: An optional textual description of the layer.
 /// - `minzoom`, `maxzoom`: Optional `u8` values (0..=30).
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Default, Debug, PartialEq)]
 pub struct VectorLayer {
 	pub fields: BTreeMap<String, String>,
 	pub description: Option<String>,
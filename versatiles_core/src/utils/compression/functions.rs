@@ -205,6 +205,36 @@ pub fn decompress_ref(blob: &Blob, compression: TileCompression) -> Result<Blob>
 	}
 }
 
+/// Guesses the compression of a data blob from its magic bytes.
+///
+/// Recognizes the gzip header (`1F 8B`) and the signature of the Brotli framing format
+/// (`CE B2 CF 81`). Everything else is reported as [`TileCompression::Uncompressed`].
+///
+/// Raw Brotli streams, like the ones produced by [`compress_brotli`], have no signature and are
+/// therefore also reported as uncompressed. Use this only if the compression isn't known otherwise.
+///
+/// # Examples
+///
+/// ```rust
+/// use versatiles_core::{utils::*, *};
+///
+/// let gzip = compress_gzip(&Blob::from("hello"))?;
+/// assert_eq!(detect_compression(&gzip), TileCompression::Gzip);
+/// assert_eq!(detect_compression(&Blob::from("hello")), TileCompression::Uncompressed);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[must_use]
+pub fn detect_compression(blob: &Blob) -> TileCompression {
+	let bytes = blob.as_slice();
+	if bytes.starts_with(&[0x1F, 0x8B]) {
+		TileCompression::Gzip
+	} else if bytes.starts_with(&[0xCE, 0xB2, 0xCF, 0x81]) {
+		TileCompression::Brotli
+	} else {
+		TileCompression::Uncompressed
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::super::tests::generate_test_data;
@@ -384,6 +414,24 @@ mod tests {
 		Ok(())
 	}
 
+	#[rstest::rstest]
+	#[case(&[0x1F, 0x8B, 0x08, 0x00], TileCompression::Gzip)]
+	#[case(&[0xCE, 0xB2, 0xCF, 0x81, 0x00], TileCompression::Brotli)]
+	#[case(&[0x1A, 0x02, 0x78, 0x00], TileCompression::Uncompressed)]
+	#[case(&[0x1F], TileCompression::Uncompressed)]
+	#[case(&[], TileCompression::Uncompressed)]
+	fn test_detect_compression(#[case] bytes: &[u8], #[case] expected: TileCompression) {
+		assert_eq!(detect_compression(&Blob::from(bytes)), expected);
+	}
+
+	#[test]
+	fn test_detect_compression_of_compressed_data() -> Result<()> {
+		let data = generate_test_data(512);
+		assert_eq!(detect_compression(&compress_gzip(&data)?), TileCompression::Gzip);
+		assert_eq!(detect_compression(&data), TileCompression::Uncompressed);
+		Ok(())
+	}
+
 	#[test]
 	fn test_optimize_compression_decompress_when_only_uncompressed_allowed() -> Result<()> {
 		let original = generate_test_data(256);
@@ -1,10 +1,16 @@
 use crate::Blob;
-use anyhow::Result;
+use anyhow::{Result, ensure};
 use brotli::{BrotliCompress, BrotliDecompress, enc::BrotliEncoderParams};
 use std::io::Cursor;
 use versatiles_derive::context;
 
-/// Compresses data using Brotli.
+/// Quality level used by [`compress_brotli`].
+pub const BROTLI_DEFAULT_LEVEL: u32 = 10;
+
+/// Highest Brotli quality level.
+pub const BROTLI_MAX_LEVEL: u32 = 11;
+
+/// Compresses data using Brotli with quality level [`BROTLI_DEFAULT_LEVEL`].
 ///
 /// # Arguments
 ///
@@ -18,11 +24,37 @@ use versatiles_derive::context;
 /// # Errors
 ///
 /// * If the Brotli compression process fails.
-#[context("Compressing blob ({} bytes) using Brotli with highest quality settings", blob.len())]
 pub fn compress_brotli(blob: &Blob) -> Result<Blob> {
+	compress_brotli_level(blob, BROTLI_DEFAULT_LEVEL)
+}
+
+/// Compresses data using Brotli with the given quality level.
+///
+/// Level 11 is typically 10–30 % smaller than level 6, but takes much longer.
+///
+/// # Arguments
+///
+/// * `blob` - The data blob to compress.
+/// * `level` - Quality level from 0 (fastest) to [`BROTLI_MAX_LEVEL`] (smallest).
+///
+/// # Returns
+///
+/// * `Ok(Blob)` containing the Brotli-compressed data.
+/// * `Err(anyhow::Error)` if compression fails.
+///
+/// # Errors
+///
+/// * If `level` is greater than [`BROTLI_MAX_LEVEL`].
+/// * If the Brotli compression process fails.
+#[context("Compressing blob ({} bytes) using Brotli with quality level {level}", blob.len())]
+pub fn compress_brotli_level(blob: &Blob, level: u32) -> Result<Blob> {
+	ensure!(
+		level <= BROTLI_MAX_LEVEL,
+		"Brotli quality level must be between 0 and {BROTLI_MAX_LEVEL}, but is {level}"
+	);
 	let params = BrotliEncoderParams {
-		quality: 10, // Highest quality
-		lgwin: 19,   // Window size
+		quality: level as i32,
+		lgwin: 19, // Window size
 		size_hint: blob.len() as usize,
 		..Default::default()
 	};
@@ -98,6 +130,26 @@ mod tests {
 		Ok(())
 	}
 
+	#[test]
+	fn should_compress_with_all_levels() -> Result<()> {
+		let data = generate_test_data(10_000);
+		for level in 0..=BROTLI_MAX_LEVEL {
+			let compressed = compress_brotli_level(&data, level)?;
+			assert_eq!(decompress_brotli(&compressed)?, data, "level {level}");
+		}
+		assert_eq!(
+			compress_brotli(&data)?,
+			compress_brotli_level(&data, BROTLI_DEFAULT_LEVEL)?
+		);
+
+		let error = compress_brotli_level(&data, 12).unwrap_err();
+		assert_eq!(
+			error.chain().last().unwrap().to_string(),
+			"Brotli quality level must be between 0 and 11, but is 12"
+		);
+		Ok(())
+	}
+
 	#[test]
 	fn should_compress_and_decompress_brotli_fast_correctly() -> Result<()> {
 		let data = generate_test_data(10_000);
@@ -1,7 +1,10 @@
 use crate::Blob;
 use anyhow::Result;
-use flate2::bufread::{GzDecoder, GzEncoder};
-use std::io::Read;
+use flate2::bufread::{DeflateDecoder, GzEncoder, MultiGzDecoder, ZlibDecoder};
+use std::{
+	fmt::{Display, Formatter},
+	io::Read,
+};
 use versatiles_derive::context;
 
 /// Compresses data using Gzip with highest quality settings.
@@ -54,8 +57,47 @@ pub fn compress_gzip_fast(blob: &Blob) -> Result<Blob> {
 	Ok(Blob::from(compressed_data))
 }
 
+/// Container format of deflate-compressed data, see [`detect_gzip_variant`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GzipVariant {
+	/// One or more gzip members, starting with the magic bytes `1F 8B`.
+	Gzip,
+	/// A deflate stream with a zlib header.
+	Zlib,
+	/// A deflate stream without any header, as written by some old tools.
+	RawDeflate,
+}
+
+impl Display for GzipVariant {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		f.write_str(match self {
+			GzipVariant::Gzip => "gzip",
+			GzipVariant::Zlib => "zlib",
+			GzipVariant::RawDeflate => "raw deflate",
+		})
+	}
+}
+
+/// Detects the container format of data that is supposed to be Gzip-compressed.
+///
+/// Data without the gzip magic bytes is treated as zlib if it starts with a valid zlib header,
+/// otherwise as raw deflate.
+#[must_use]
+pub fn detect_gzip_variant(data: &[u8]) -> GzipVariant {
+	match data {
+		[0x1F, 0x8B, ..] => GzipVariant::Gzip,
+		[cmf, flg, ..] if cmf & 0x0F == 8 && cmf >> 4 <= 7 && (u16::from(*cmf) << 8 | u16::from(*flg)) % 31 == 0 => {
+			GzipVariant::Zlib
+		}
+		_ => GzipVariant::RawDeflate,
+	}
+}
+
 /// Decompresses data that was compressed using Gzip.
 ///
+/// Multi-member gzip data is decompressed completely. Data without a gzip header is decoded as
+/// zlib or raw deflate stream, see [`detect_gzip_variant`].
+///
 /// # Arguments
 ///
 /// * `blob` - The Gzip-compressed data blob.
@@ -70,11 +112,29 @@ pub fn compress_gzip_fast(blob: &Blob) -> Result<Blob> {
 /// * If the Gzip decompression process fails.
 #[context("Decompressing blob ({} bytes) using Gzip", blob.len())]
 pub fn decompress_gzip(blob: &Blob) -> Result<Blob> {
-	let mut decoder = GzDecoder::new(blob.as_slice());
+	let data = blob.as_slice();
 	let mut decompressed_data = Vec::new();
-	decoder
-		.read_to_end(&mut decompressed_data)
-		.context("Failed to decompress data using Gzip")?;
+	match detect_gzip_variant(data) {
+		GzipVariant::Gzip => {
+			MultiGzDecoder::new(data)
+				.read_to_end(&mut decompressed_data)
+				.context("Failed to decompress data using Gzip")?;
+		}
+		GzipVariant::Zlib => {
+			// a raw deflate stream can start with bytes that look like a zlib header
+			if ZlibDecoder::new(data).read_to_end(&mut decompressed_data).is_err() {
+				decompressed_data.clear();
+				DeflateDecoder::new(data)
+					.read_to_end(&mut decompressed_data)
+					.context("Failed to decompress data using zlib or raw deflate")?;
+			}
+		}
+		GzipVariant::RawDeflate => {
+			DeflateDecoder::new(data)
+				.read_to_end(&mut decompressed_data)
+				.context("Failed to decompress data using raw deflate")?;
+		}
+	}
 	Ok(Blob::from(decompressed_data))
 }
 
@@ -82,6 +142,7 @@ pub fn decompress_gzip(blob: &Blob) -> Result<Blob> {
 mod tests {
 	use super::super::tests::generate_test_data;
 	use super::*;
+	use flate2::bufread::{DeflateEncoder, ZlibEncoder};
 
 	#[test]
 	fn should_compress_and_decompress_gzip_correctly() -> Result<()> {
@@ -100,4 +161,41 @@ mod tests {
 		assert_eq!(data, decompressed, "Fast Gzip compression and decompression failed");
 		Ok(())
 	}
+
+	fn encode(mut encoder: impl Read) -> Vec<u8> {
+		let mut data = Vec::new();
+		encoder.read_to_end(&mut data).unwrap();
+		data
+	}
+
+	#[test]
+	fn should_decompress_all_variants() -> Result<()> {
+		let payload = generate_test_data(10_000);
+		let (head, tail) = payload.as_slice().split_at(4_000);
+		let level = flate2::Compression::default();
+		let fixtures = [
+			(GzipVariant::Gzip, encode(GzEncoder::new(payload.as_slice(), level))),
+			(
+				GzipVariant::Gzip,
+				[encode(GzEncoder::new(head, level)), encode(GzEncoder::new(tail, level))].concat(),
+			),
+			(GzipVariant::Zlib, encode(ZlibEncoder::new(payload.as_slice(), level))),
+			(
+				GzipVariant::RawDeflate,
+				encode(DeflateEncoder::new(payload.as_slice(), level)),
+			),
+		];
+
+		for (variant, fixture) in fixtures {
+			assert_eq!(detect_gzip_variant(&fixture), variant);
+			assert_eq!(decompress_gzip(&Blob::from(fixture))?, payload, "{variant}");
+		}
+		Ok(())
+	}
+
+	#[test]
+	fn should_fail_on_invalid_data() {
+		assert!(decompress_gzip(&Blob::from(vec![0x1F, 0x8B, 0x08, 0x00])).is_err());
+		assert!(decompress_gzip(&Blob::from(vec![0xFF; 16])).is_err());
+	}
 }
@@ -6,6 +6,7 @@ mod target_compression;
 #[cfg(test)]
 pub mod tests;
 
+pub use compression_goal::CompressionGoal;
 pub use functions::*;
 pub use method_brotli::*;
 pub use method_gzip::*;
@@ -1,7 +1,7 @@
 //! CSV utilities.
 //!
-//! Provides a small CSV reader with configurable separator, RFC4180-style quoted fields (double-quote escaping), and tolerant handling of `\n`/`\r\n` and empty lines.
-//! Exposes `read_csv_iter` for consumers and keeps parsing helpers internal.
+//! Provides a small CSV reader with configurable separator and quote character, RFC4180-style quoted fields (doubled-quote escaping), and tolerant handling of `\n`/`\r\n` and empty lines.
+//! Exposes `read_csv_iter` and `read_csv_iter_with_quote` for consumers and keeps parsing helpers internal.
 
 use crate::byte_iterator::ByteIterator;
 use anyhow::{Error, Result, bail};
@@ -10,22 +10,25 @@ use versatiles_derive::context;
 
 /// Parses a quoted CSV field (`"..."`) with RFC&nbsp;4180-style escaping.
 ///
-/// Two consecutive quotes (`""`) are decoded as a single literal `"` inside the field.
+/// Two consecutive quotes (`""`) are decoded as a single literal quote inside the field.
+///
+/// # Arguments
+/// * `quote` — The byte used to quote fields (usually `b'"'`).
 ///
 /// # Errors
-/// Returns an error if the first byte is not `"` or if UTF‑8 decoding fails.
-#[context("parsing quoted CSV field")]
-fn parse_quoted_csv_string(iter: &mut ByteIterator) -> Result<String> {
-	if iter.expect_next_byte()? != b'"' {
-		bail!(iter.format_error("expected '\"' while parsing a string"));
+/// Returns an error if the first byte is not `quote` or if UTF‑8 decoding fails.
+#[context("parsing quoted CSV field (quote='{}')", quote as char)]
+fn parse_quoted_csv_string(iter: &mut ByteIterator, quote: u8) -> Result<String> {
+	if iter.expect_next_byte()? != quote {
+		bail!(iter.format_error(&format!("expected '{}' while parsing a string", quote as char)));
 	}
 
 	let mut bytes: Vec<u8> = Vec::new();
 	loop {
 		match iter.consume() {
-			Some(b'"') => match iter.peek() {
-				Some(b'"') => {
-					bytes.push(b'"');
+			Some(q) if q == quote => match iter.peek() {
+				Some(q) if q == quote => {
+					bytes.push(quote);
 					iter.advance();
 				}
 				_ => return String::from_utf8(bytes).map_err(Error::from),
@@ -40,13 +43,14 @@ fn parse_quoted_csv_string(iter: &mut ByteIterator) -> Result<String> {
 ///
 /// # Arguments
 /// * `separator` — The byte used to separate fields (e.g., `b','`).
+/// * `quote` — The byte used to quote fields (e.g., `b'"'`).
 ///
 /// # Errors
-/// Returns an error if a leading `"` is encountered (quoted fields must use [`parse_quoted_csv_string`]) or if UTF‑8 decoding fails.
+/// Returns an error if a leading `quote` is encountered (quoted fields must use [`parse_quoted_csv_string`]) or if UTF‑8 decoding fails.
 #[context("parsing unquoted CSV field (sep='{}')", separator as char)]
-fn parse_simple_csv_string(iter: &mut ByteIterator, separator: u8) -> Result<String> {
-	if iter.expect_peeked_byte()? == b'"' {
-		bail!(iter.format_error("unexpected '\"' while parsing a string"));
+fn parse_simple_csv_string(iter: &mut ByteIterator, separator: u8, quote: u8) -> Result<String> {
+	if iter.expect_peeked_byte()? == quote {
+		bail!(iter.format_error(&format!("unexpected '{}' while parsing a string", quote as char)));
 	}
 
 	let mut bytes: Vec<u8> = Vec::new();
@@ -70,12 +74,14 @@ fn parse_simple_csv_string(iter: &mut ByteIterator, separator: u8) -> Result<Str
 /// # Arguments
 /// * `reader` — Any `BufRead` source.
 /// * `separator` — Field separator byte.
+/// * `quote` — Quote byte.
 ///
 /// # Returns
 /// An iterator over `Result<(Vec<String>, usize)>`.
 fn read_csv_fields<'a>(
 	reader: impl BufRead + Send + 'a,
 	separator: u8,
+	quote: u8,
 ) -> impl Iterator<Item = Result<(Vec<String>, usize)>> {
 	let mut iter = ByteIterator::from_reader(reader, true);
 
@@ -86,11 +92,11 @@ fn read_csv_fields<'a>(
 
 		loop {
 			let value = match iter.peek() {
-				Some(b'"') => match parse_quoted_csv_string(&mut iter) {
+				Some(q) if q == quote => match parse_quoted_csv_string(&mut iter, quote) {
 					Ok(v) => v,
 					Err(e) => return Some(Err(e)),
 				},
-				Some(_) => match parse_simple_csv_string(&mut iter, separator) {
+				Some(_) => match parse_simple_csv_string(&mut iter, separator, quote) {
 					Ok(v) => v,
 					Err(e) => return Some(Err(e)),
 				},
@@ -149,7 +155,32 @@ pub fn read_csv_iter<'a>(
 	reader: impl BufRead + Send + 'a,
 	separator: u8,
 ) -> Result<impl Iterator<Item = Result<(Vec<String>, usize, usize)>> + 'a> {
-	let iter = read_csv_fields(reader, separator);
+	read_csv_iter_with_quote(reader, separator, b'"')
+}
+
+/// Like [`read_csv_iter`], but with a custom quote byte instead of `"`, e.g. `b'\''`.
+///
+/// Inside a quoted field, the quote byte is escaped by doubling it.
+///
+/// # Example
+/// ```
+/// use std::io::Cursor;
+/// use versatiles_core::utils::read_csv_iter_with_quote;
+/// # use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// let input = "name;note\n'Doe; Jane';'it''s'";
+/// let mut it = read_csv_iter_with_quote(Cursor::new(input), b';', b'\'')?;
+/// it.next();
+/// let (row, _, _) = it.next().unwrap().unwrap();
+/// assert_eq!(row, vec!["Doe; Jane", "it's"]);
+/// # Ok(()) }
+/// ```
+pub fn read_csv_iter_with_quote<'a>(
+	reader: impl BufRead + Send + 'a,
+	separator: u8,
+	quote: u8,
+) -> Result<impl Iterator<Item = Result<(Vec<String>, usize, usize)>> + 'a> {
+	let iter = read_csv_fields(reader, separator, quote);
 	let mut line_pos = 0usize;
 	let mut option_len: Option<usize> = None;
 
@@ -177,7 +208,7 @@ mod tests {
 	fn test_parse_simple_csv_string() {
 		fn test(input: &str, part1: &str, part2: &str) {
 			let mut reader = ByteIterator::from_reader(Cursor::new(input), true);
-			let value = parse_simple_csv_string(&mut reader, b',').unwrap();
+			let value = parse_simple_csv_string(&mut reader, b',', b'"').unwrap();
 			assert_eq!(value, part1);
 			assert_eq!(reader.into_string().unwrap(), part2);
 		}
@@ -192,7 +223,7 @@ mod tests {
 	fn test_parse_quoted_csv_string() {
 		fn test(input: &str, part1: &str, part2: &str) {
 			let mut reader = ByteIterator::from_reader(Cursor::new(input), true);
-			let value = parse_quoted_csv_string(&mut reader).unwrap();
+			let value = parse_quoted_csv_string(&mut reader, b'"').unwrap();
 			assert_eq!(value, part1);
 			assert_eq!(reader.into_string().unwrap(), part2);
 		}
@@ -210,7 +241,7 @@ mod tests {
 	#[test]
 	fn test_read_csv_fields_basic() {
 		let mut reader = Cursor::new("name,age\nJohn Doe,30\r\nJane Doe,29");
-		let iter = read_csv_fields(&mut reader, b',');
+		let iter = read_csv_fields(&mut reader, b',', b'"');
 
 		assert_eq!(
 			check(iter),
@@ -221,7 +252,7 @@ mod tests {
 	#[test]
 	fn test_read_csv_fields_with_quotes() {
 		let mut reader = Cursor::new("name,age\n\"John, A. Doe\",30\r\n\"Jane Doe\",29");
-		let iter = read_csv_fields(&mut reader, b',');
+		let iter = read_csv_fields(&mut reader, b',', b'"');
 
 		assert_eq!(
 			check(iter),
@@ -232,7 +263,7 @@ mod tests {
 	#[test]
 	fn test_read_csv_fields_with_escaped_quotes() {
 		let mut reader = Cursor::new("name,age\n\"John \"\"The Man\"\" Doe\",30\n\"Jane Doe\",29");
-		let iter = read_csv_fields(&mut reader, b',');
+		let iter = read_csv_fields(&mut reader, b',', b'"');
 
 		assert_eq!(
 			check(iter),
@@ -247,7 +278,7 @@ mod tests {
 	#[test]
 	fn test_read_csv_fields_empty_lines() {
 		let mut reader = Cursor::new("name,age\n\nJohn Doe,30\n\nJane Doe,29\n\n");
-		let iter = read_csv_fields(&mut reader, b',');
+		let iter = read_csv_fields(&mut reader, b',', b'"');
 
 		assert_eq!(
 			check(iter),
@@ -258,7 +289,7 @@ mod tests {
 	#[test]
 	fn test_read_csv_fields_different_separator() {
 		let mut reader = Cursor::new("name|age\nJohn Doe|30\nJane Doe|29");
-		let iter = read_csv_fields(&mut reader, b'|');
+		let iter = read_csv_fields(&mut reader, b'|', b'"');
 
 		assert_eq!(
 			check(iter),
@@ -292,6 +323,16 @@ mod tests {
 		Ok(())
 	}
 
+	#[test]
+	fn test_read_csv_iter_with_custom_quote() -> Result<()> {
+		let data = "'a;a';\"b\"\n'it''s';d";
+		let rows = read_csv_iter_with_quote(Cursor::new(data), b';', b'\'')?
+			.map(|e| e.map(|(fields, _, _)| fields))
+			.collect::<Result<Vec<_>>>()?;
+		assert_eq!(rows, vec![vec!["a;a", "\"b\""], vec!["it's", "d"]]);
+		Ok(())
+	}
+
 	#[test]
 	fn test_read_csv_iter_inconsistent_fields() {
 		let data = "a,b\nc,d,e\n";
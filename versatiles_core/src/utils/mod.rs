@@ -3,16 +3,22 @@
 //! - `compression`: for handling tile compression and decompression.
 //! - `csv`: for lightweight CSV parsing utilities.
 //! - `pretty_print` (enabled with the `cli` feature): for formatted command-line output.
+//! - `tile_extension`: for the file extensions of tiles, e.g. `.pbf.gz`.
+//! - `tile_format_detection`: for guessing the tile format from magic bytes.
 //! - `tile_hilbert_index`: for Hilbert index calculations and spatial ordering of tiles.
 
 mod compression;
 mod csv;
 #[cfg(feature = "cli")]
 mod pretty_print;
+mod tile_extension;
+mod tile_format_detection;
 mod tile_hilbert_index;
 
 pub use compression::*;
 pub use csv::*;
 #[cfg(feature = "cli")]
 pub use pretty_print::*;
+pub use tile_extension::*;
+pub use tile_format_detection::*;
 pub use tile_hilbert_index::*;
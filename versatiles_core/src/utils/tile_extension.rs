@@ -0,0 +1,111 @@
+//! File extensions of tile files, e.g. `12.pbf.gz`.
+//!
+//! Readers accept every alias of [`TileFormat::from_extension`] (like `jpeg` or `mvt`, in any
+//! case), writers always use the canonical extensions of [`TileFormat::as_extension`] and
+//! [`TileCompression::as_extension`].
+//!
+//! # Examples
+//! ```
+//! use versatiles_core::{TileCompression, TileFormat, utils::*};
+//!
+//! assert_eq!(extension_for(TileFormat::MVT, TileCompression::Gzip), ".pbf.gz");
+//! assert_eq!(
+//!     parse_filename("12.mvt.br"),
+//!     Some((TileFormat::MVT, TileCompression::Brotli))
+//! );
+//! assert_eq!(
+//!     split_tile_filename("7.jpeg"),
+//!     Some(("7", TileFormat::JPG, TileCompression::Uncompressed))
+//! );
+//! assert_eq!(parse_filename("readme.txt"), None);
+//! ```
+
+use crate::{TileCompression, TileFormat};
+
+/// Returns the canonical extension of a tile file, e.g. `.pbf.gz`.
+#[must_use]
+pub fn extension_for(format: TileFormat, compression: TileCompression) -> String {
+	format!("{}{}", format.as_extension(), compression.as_extension())
+}
+
+/// Splits a tile file name like `12.pbf.gz` into the stem (`12`), the format and the compression.
+///
+/// Returns `None` if the name has no known format extension.
+#[must_use]
+pub fn split_tile_filename(name: &str) -> Option<(&str, TileFormat, TileCompression)> {
+	let (rest, compression) = match name.rsplit_once('.') {
+		Some((rest, extension)) => match TileCompression::from_extension(extension) {
+			Some(compression) if compression != TileCompression::Uncompressed => (rest, compression),
+			_ => (name, TileCompression::Uncompressed),
+		},
+		None => return None,
+	};
+	let (stem, extension) = rest.rsplit_once('.')?;
+	Some((stem, TileFormat::from_extension(extension)?, compression))
+}
+
+/// Returns the format and compression of a tile file name like `12.pbf.gz`.
+///
+/// Returns `None` if the name has no known format extension.
+#[must_use]
+pub fn parse_filename(name: &str) -> Option<(TileFormat, TileCompression)> {
+	split_tile_filename(name).map(|(_, format, compression)| (format, compression))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use TileCompression::*;
+	use TileFormat::*;
+	use enumset::EnumSet;
+	use rstest::rstest;
+
+	#[test]
+	fn round_trip_all_formats_and_compressions() {
+		for format in EnumSet::<TileFormat>::all() {
+			for compression in EnumSet::<TileCompression>::all() {
+				let name = format!("42{}", extension_for(format, compression));
+				assert_eq!(split_tile_filename(&name), Some(("42", format, compression)), "{name}");
+			}
+		}
+	}
+
+	#[rstest]
+	#[case(AVIF, Uncompressed, ".avif")]
+	#[case(BIN, Gzip, ".bin.gz")]
+	#[case(GEOJSON, Brotli, ".geojson.br")]
+	#[case(JPG, Uncompressed, ".jpg")]
+	#[case(JSON, Gzip, ".json.gz")]
+	#[case(MVT, Gzip, ".pbf.gz")]
+	#[case(MVT, Uncompressed, ".pbf")]
+	#[case(PNG, Brotli, ".png.br")]
+	#[case(SVG, Uncompressed, ".svg")]
+	#[case(TOPOJSON, Gzip, ".topojson.gz")]
+	#[case(WEBP, Uncompressed, ".webp")]
+	fn canonical_extensions(#[case] format: TileFormat, #[case] compression: TileCompression, #[case] expected: &str) {
+		assert_eq!(extension_for(format, compression), expected);
+	}
+
+	#[rstest]
+	#[case("1.jpeg", Some((JPG, Uncompressed)))]
+	#[case("1.JPG", Some((JPG, Uncompressed)))]
+	#[case("1.mvt", Some((MVT, Uncompressed)))]
+	#[case("1.mvt.gz", Some((MVT, Gzip)))]
+	#[case("1.pbf.br", Some((MVT, Brotli)))]
+	#[case("1.PBF.GZ", Some((MVT, Gzip)))]
+	#[case("1.svg", Some((SVG, Uncompressed)))]
+	#[case("1.gz", None)]
+	#[case("1.txt", None)]
+	#[case("1", None)]
+	#[case("", None)]
+	fn aliases(#[case] name: &str, #[case] expected: Option<(TileFormat, TileCompression)>) {
+		assert_eq!(parse_filename(name), expected);
+	}
+
+	#[test]
+	fn stem() {
+		assert_eq!(split_tile_filename("3.png"), Some(("3", PNG, Uncompressed)));
+		assert_eq!(split_tile_filename("a.b.pbf.gz"), Some(("a.b", MVT, Gzip)));
+		assert_eq!(split_tile_filename(".pbf"), Some(("", MVT, Uncompressed)));
+	}
+}
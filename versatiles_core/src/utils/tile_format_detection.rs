@@ -0,0 +1,92 @@
+//! Detection of the tile format from the magic bytes of uncompressed tile data.
+//!
+//! Use [`detect_tile_format`] only if neither a file extension nor metadata tells the format.
+//! Compressed data must be decompressed first, see [`detect_compression`](super::detect_compression).
+//!
+//! # Examples
+//! ```
+//! use versatiles_core::{Blob, TileFormat, utils::*};
+//!
+//! let png = Blob::from(b"\x89PNG\r\n\x1a\n".to_vec());
+//! assert_eq!(detect_tile_format(&png), Some(TileFormat::PNG));
+//! assert_eq!(detect_tile_format(&Blob::from("{}")), None);
+//! ```
+
+use crate::{Blob, TileFormat};
+
+/// Guesses the format of uncompressed tile data from its magic bytes.
+///
+/// Recognizes:
+/// * PNG: `89 50 4E 47`
+/// * JPEG: `FF D8 FF`
+/// * WebP: `RIFF`, 4 bytes of size, `WEBP`
+/// * AVIF: an ISO BMFF `ftyp` box with the brand `avif` or `avis`
+/// * MVT: a first byte that is a valid protobuf field tag (wire type 0, 1, 2 or 5 and field
+///   number ≥ 1), e.g. `1A` for the layers of a vector tile
+///
+/// The MVT check is only a weak heuristic, so it runs last. Returns `None` for everything else,
+/// including empty data and text formats like JSON or SVG.
+#[must_use]
+pub fn detect_tile_format(blob: &Blob) -> Option<TileFormat> {
+	let bytes = blob.as_slice();
+	if bytes.starts_with(&[0x89, 0x50, 0x4E, 0x47]) {
+		Some(TileFormat::PNG)
+	} else if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+		Some(TileFormat::JPG)
+	} else if bytes.len() >= 12 && bytes.starts_with(b"RIFF") && &bytes[8..12] == b"WEBP" {
+		Some(TileFormat::WEBP)
+	} else if is_avif(bytes) {
+		Some(TileFormat::AVIF)
+	} else if bytes.first().is_some_and(|&tag| is_protobuf_tag(tag)) {
+		Some(TileFormat::MVT)
+	} else {
+		None
+	}
+}
+
+/// Checks the major and the compatible brands of a leading `ftyp` box.
+fn is_avif(bytes: &[u8]) -> bool {
+	if bytes.len() < 12 || &bytes[4..8] != b"ftyp" {
+		return false;
+	}
+	let box_size = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize;
+	let end = box_size.clamp(12, bytes.len());
+	// major brand, then minor version (skipped) and the compatible brands
+	let mut brands = std::iter::once(&bytes[8..12]).chain(bytes.get(16..end).unwrap_or_default().chunks_exact(4));
+	brands.any(|brand| brand == b"avif" || brand == b"avis")
+}
+
+/// Wire types 3 and 4 (groups) are deprecated, 6 and 7 don't exist. Field number 0 is invalid.
+fn is_protobuf_tag(tag: u8) -> bool {
+	matches!(tag & 0x07, 0 | 1 | 2 | 5) && tag >= 0x08
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use TileFormat::*;
+	use rstest::rstest;
+
+	#[rstest]
+	#[case(b"\x89PNG\r\n\x1a\n", Some(PNG))]
+	#[case(b"\xFF\xD8\xFF\xE0", Some(JPG))]
+	#[case(b"RIFF\x24\x00\x00\x00WEBPVP8 ", Some(WEBP))]
+	#[case(b"\x00\x00\x00\x1Cftypavif\x00\x00\x00\x00avifmif1", Some(AVIF))]
+	#[case(b"\x00\x00\x00\x1Cftypavis\x00\x00\x00\x00avismif1", Some(AVIF))]
+	#[case(b"\x00\x00\x00\x18ftypmif1\x00\x00\x00\x00avif", Some(AVIF))]
+	#[case(b"\x1A\x05\x0A\x03abc", Some(MVT))]
+	#[case(b"\x0A\x00", Some(MVT))]
+	#[case(b"\x0D", Some(MVT))]
+	#[case(b"", None)]
+	#[case(b"{\"type\":\"FeatureCollection\"}", None)]
+	#[case(b"[]", None)]
+	#[case(b"<svg/>", None)]
+	#[case(b"\x00", None)]
+	#[case(b"\x0B", None)]
+	#[case(b"\x0F", None)]
+	#[case(b"\x00\x00\x00\x18ftypisom\x00\x00\x00\x00mp41", None)]
+	#[case(b"\x00\x00\x00\x10ftypmif1\x00\x00\x00\x00avif", None)]
+	fn magic_bytes(#[case] bytes: &[u8], #[case] expected: Option<TileFormat>) {
+		assert_eq!(detect_tile_format(&Blob::from(bytes.to_vec())), expected);
+	}
+}
@@ -1,3 +1,4 @@
+use crate::validate_vpl::has_constraints;
 use proc_macro2::TokenStream;
 use quote::quote;
 use syn::{Attribute, DataStruct, DeriveInput, Fields, Meta};
@@ -14,6 +15,19 @@ pub fn extract_comment(attr: &Attribute) -> Option<String> {
 	None
 }
 
+/// Returns the value of a `#[vpl_default("...")]` attribute.
+fn extract_default(attrs: &[Attribute]) -> Option<String> {
+	attrs
+		.iter()
+		.find(|attr| attr.path().is_ident("vpl_default"))
+		.map(|attr| {
+			attr
+				.parse_args::<syn::LitStr>()
+				.expect("vpl_default expects a string literal, e.g. #[vpl_default(\"30\")]")
+				.value()
+		})
+}
+
 pub fn decode_struct(input: DeriveInput, data_struct: DataStruct) -> TokenStream {
 	let name = input.ident;
 
@@ -34,9 +48,10 @@ pub fn decode_struct(input: DeriveInput, data_struct: DataStruct) -> TokenStream
 	};
 
 	let mut parser_fields: Vec<TokenStream> = Vec::new();
-	let mut doc_fields: Vec<String> = Vec::new();
+	let mut doc_fields: Vec<TokenStream> = Vec::new();
 	let mut doc_sources: Option<String> = None;
 	let mut field_names: Vec<String> = Vec::new();
+	let validate = fields.iter().any(|field| has_constraints(&field.attrs));
 
 	for field in fields {
 		let field_name = &field.ident;
@@ -45,7 +60,7 @@ pub fn decode_struct(input: DeriveInput, data_struct: DataStruct) -> TokenStream
 		let field_type_str = quote!(#field_type).to_string().replace(' ', "");
 
 		field_names.push(field_str.clone());
-		let mut comment = field
+		let comment = field
 			.attrs
 			.iter()
 			.filter_map(extract_comment)
@@ -60,93 +75,120 @@ pub fn decode_struct(input: DeriveInput, data_struct: DataStruct) -> TokenStream
 				(field_type_str == "Vec<VPLPipeline>"),
 				"type of 'sources' must be 'Vec<VPLPipeline>', but is '{field_type_str}'"
 			);
-			doc_sources = Some(format!("### Sources:\n{comment}"));
+			doc_sources = Some(comment);
 			parser_fields.push(quote! { sources: node.sources.clone() });
 		} else {
-			if !comment.is_empty() {
-				comment = format!(" - {comment}");
-			}
-			let (doc_field, parser_field) = match field_type_str.as_str() {
+			let (type_name, parser_field) = match field_type_str.as_str() {
 				"String" => (
-					format!("- **`{field_str}`: String (required)**{comment}"),
+					"String",
 					quote! { #field_name: node.get_property_string_required(#field_str)? },
 				),
 				"bool" => (
-					format!("- **`{field_str}`: Boolean (required)**{comment}"),
+					"Boolean",
 					quote! { #field_name: node.get_property_bool_required(#field_str)? },
 				),
 				"u8" => (
-					format!("- **`{field_str}`: u8 (required)**{comment}"),
+					"u8",
 					quote! { #field_name: node.get_property_number_required::<u8>(#field_str)? },
 				),
 				"[f64;4]" => (
-					format!("- **`{field_str}`: [f64,f64,f64,f64] (required)**{comment}"),
+					"[f64,f64,f64,f64]",
 					quote! { #field_name: node.get_property_number_array_required::<f64>(#field_str)? },
 				),
+				"Vec<String>" => (
+					"[String]",
+					quote! { #field_name: node.get_property_string_array_required(#field_str)? },
+				),
 				"Option<bool>" => (
-					format!("- *`{field_str}`: bool (optional)*{comment}"),
+					"bool",
 					quote! { #field_name: node.get_property_bool_option(#field_str)? },
 				),
 				"Option<String>" => (
-					format!("- *`{field_str}`: String (optional)*{comment}"),
+					"String",
 					quote! { #field_name: node.get_property_string_option(#field_str)? },
 				),
 				"Option<f32>" => (
-					format!("- *`{field_str}`: f32 (optional)*{comment}"),
+					"f32",
 					quote! { #field_name: node.get_property_number_option::<f32>(#field_str)? },
 				),
 				"Option<u8>" => (
-					format!("- *`{field_str}`: u8 (optional)*{comment}"),
+					"u8",
 					quote! { #field_name: node.get_property_number_option::<u8>(#field_str)? },
 				),
 				"Option<u16>" => (
-					format!("- *`{field_str}`: u16 (optional)*{comment}"),
+					"u16",
 					quote! { #field_name: node.get_property_number_option::<u16>(#field_str)? },
 				),
 				"Option<u32>" => (
-					format!("- *`{field_str}`: u32 (optional)*{comment}"),
+					"u32",
 					quote! { #field_name: node.get_property_number_option::<u32>(#field_str)? },
 				),
 				"Option<[f64;4]>" => (
-					format!("- *`{field_str}`: [f64,f64,f64,f64] (optional)*{comment}"),
+					"[f64,f64,f64,f64]",
 					quote! { #field_name: node.get_property_number_array_option::<f64, 4>(#field_str)? },
 				),
 				"Option<[u8;3]>" => (
-					format!("- *`{field_str}`: [u8,u8,u8] (optional)*{comment}"),
+					"[u8,u8,u8]",
 					quote! { #field_name: node.get_property_number_array_option::<u8, 3>(#field_str)? },
 				),
 				"Option<TileCompression>" => (
-					format!("- *`{field_str}`: TileCompression (optional)*{comment}"),
+					"TileCompression",
 					quote! { #field_name: node.get_property_enum_option::<TileCompression>(#field_str)? },
 				),
 				"Option<TileSchema>" => (
-					format!("- *`{field_str}`: TileSchema (optional)*{comment}"),
+					"TileSchema",
 					quote! { #field_name: node.get_property_enum_option::<TileSchema>(#field_str)? },
 				),
 				"Option<TileFormat>" => (
-					format!("- *`{field_str}`: TileFormat (optional)*{comment}"),
+					"TileFormat",
 					quote! { #field_name: node.get_property_enum_option::<TileFormat>(#field_str)? },
 				),
 				_ => panic!("unknown type field: {field_type_str}"),
 			};
-			doc_fields.push(doc_field.trim().to_string());
+			let required = !field_type_str.starts_with("Option<");
+			let default = extract_default(&field.attrs);
+			assert!(
+				!required || default.is_none(),
+				"required field '{field_str}' can't have a default value"
+			);
+			let default = match default {
+				Some(default) => quote! { Some(#default) },
+				None => quote! { None },
+			};
+			doc_fields.push(quote! {
+				crate::PropertyDocs {
+					name: #field_str,
+					type_name: #type_name,
+					required: #required,
+					default: #default,
+					doc: #comment,
+				}
+			});
 			parser_fields.push(parser_field);
 		}
 	}
 
-	let doc_fields = if doc_fields.is_empty() {
-		String::new()
-	} else {
-		format!("### Parameters:\n{}", doc_fields.join("\n"))
+	let doc_sources = match doc_sources {
+		Some(doc) => quote! { Some(#doc) },
+		None => quote! { None },
 	};
 
-	let doc = vec![doc_struct, doc_sources.unwrap_or_default(), doc_fields]
-		.into_iter()
-		.filter(|s| !s.is_empty())
-		.collect::<Vec<String>>()
-		.join("\n")
-		.trim()
-		.to_string();
+	// fields with `#[vpl(...)]` constraints are checked by `validate` of the `VPLValidate` derive
+	let result = if validate {
+		quote! {
+			let args = Self {
+				#(#parser_fields),*
+			};
+			args.validate()?;
+			Ok(args)
+		}
+	} else {
+		quote! {
+			Ok(Self {
+				#(#parser_fields),*
+			})
+		}
+	};
 
 	quote! {
 		impl #name {
@@ -165,13 +207,15 @@ pub fn decode_struct(input: DeriveInput, data_struct: DataStruct) -> TokenStream
 					}
 				}
 
-				Ok(Self {
-					#(#parser_fields),*
-				})
+				#result
 			}
 
-			pub fn get_docs() -> String {
-				#doc.to_string()
+			pub fn get_docs() -> crate::OperationDocs {
+				crate::OperationDocs {
+					description: #doc_struct,
+					sources: #doc_sources,
+					properties: vec![#(#doc_fields),*],
+				}
 			}
 		}
 	}
@@ -181,6 +225,7 @@ pub fn decode_struct(input: DeriveInput, data_struct: DataStruct) -> TokenStream
 mod tests {
 	use super::decode_struct;
 	use pretty_assertions::assert_eq;
+	use quote::quote;
 	use syn::{DeriveInput, parse_quote};
 
 	fn pretty_tokens(ts: proc_macro2::TokenStream) -> Vec<String> {
@@ -224,9 +269,15 @@ mod tests {
 				"            field1: node.get_property_string_required(\"field1\")?,",
 				"        })",
 				"    }",
-				"    pub fn get_docs() -> String {",
-				"        \"Struct documentation\\n### Parameters:\\n- **`field1`: String (required)** - Field documentation\"",
-				"            .to_string()",
+				"    pub fn get_docs() -> crate::OperationDocs {",
+				"        crate::OperationDocs {",
+				"            description: \"Struct documentation\",",
+				"            sources: None,",
+				"            properties: vec![",
+				"                crate ::PropertyDocs { name : \"field1\", type_name : \"String\", required :",
+				"                true, default : None, doc : \"Field documentation\", }",
+				"            ],",
+				"        }",
 				"    }",
 				"}",
 				""
@@ -238,7 +289,7 @@ mod tests {
 	fn test_decode_struct_all_field_types() {
 		use syn::parse_quote;
 		// Struct covering all supported field types
-		let cases: Vec<(DeriveInput, &str, &str)> = vec![
+		let cases: Vec<(DeriveInput, &str, &str, bool)> = vec![
 			(
 				parse_quote!(
 					struct T {
@@ -246,7 +297,8 @@ mod tests {
 					}
 				),
 				"get_property_string_required",
-				"**`v`: String (required)**",
+				"String",
+				true,
 			),
 			(
 				parse_quote!(
@@ -255,7 +307,8 @@ mod tests {
 					}
 				),
 				"get_property_bool_required",
-				"**`v`: Boolean (required)**",
+				"Boolean",
+				true,
 			),
 			(
 				parse_quote!(
@@ -264,7 +317,8 @@ mod tests {
 					}
 				),
 				"get_property_number_required::<u8>",
-				"**`v`: u8 (required)**",
+				"u8",
+				true,
 			),
 			(
 				parse_quote!(
@@ -273,7 +327,8 @@ mod tests {
 					}
 				),
 				"get_property_number_array_required::<f64>",
-				"**`v`: [f64,f64,f64,f64] (required)**",
+				"[f64,f64,f64,f64]",
+				true,
 			),
 			(
 				parse_quote!(
@@ -282,7 +337,8 @@ mod tests {
 					}
 				),
 				"get_property_bool_option",
-				"*`v`: bool (optional)*",
+				"bool",
+				false,
 			),
 			(
 				parse_quote!(
@@ -291,7 +347,8 @@ mod tests {
 					}
 				),
 				"get_property_string_option",
-				"*`v`: String (optional)*",
+				"String",
+				false,
 			),
 			(
 				parse_quote!(
@@ -300,7 +357,8 @@ mod tests {
 					}
 				),
 				"get_property_number_option::<f32>",
-				"*`v`: f32 (optional)*",
+				"f32",
+				false,
 			),
 			(
 				parse_quote!(
@@ -309,7 +367,8 @@ mod tests {
 					}
 				),
 				"get_property_number_option::<u8>",
-				"*`v`: u8 (optional)*",
+				"u8",
+				false,
 			),
 			(
 				parse_quote!(
@@ -318,7 +377,8 @@ mod tests {
 					}
 				),
 				"get_property_number_option::<u32>",
-				"*`v`: u32 (optional)*",
+				"u32",
+				false,
 			),
 			(
 				parse_quote!(
@@ -327,7 +387,8 @@ mod tests {
 					}
 				),
 				"get_property_number_array_option::<f64, 4>",
-				"*`v`: [f64,f64,f64,f64] (optional)*",
+				"[f64,f64,f64,f64]",
+				false,
 			),
 			(
 				parse_quote!(
@@ -336,18 +397,20 @@ mod tests {
 					}
 				),
 				"get_property_enum_option::<TileFormat>",
-				"*`v`: TileFormat (optional)*",
+				"TileFormat",
+				false,
 			),
 		];
 
-		for (input, getter, comment) in cases {
+		for (input, getter, type_name, required) in cases {
 			let data_struct = match &input.data {
 				syn::Data::Struct(ds) => ds.clone(),
 				_ => panic!("Expected struct data"),
 			};
 			let ts = decode_struct(input.clone(), data_struct);
+			let code = ts.to_string();
 			assert_eq!(
-				pretty_tokens(ts),
+				pretty_tokens(ts)[..16],
 				[
 					"impl T {",
 					"    pub fn from_vpl_node(node: &VPLNode) -> Result<Self> {",
@@ -365,13 +428,9 @@ mod tests {
 					&format!("            v: node.{getter}(\"v\")?,"),
 					"        })",
 					"    }",
-					"    pub fn get_docs() -> String {",
-					&format!("        \"### Parameters:\\n- {comment}\".to_string()"),
-					"    }",
-					"}",
-					""
 				]
 			);
+			assert!(code.contains(&quote! { name: "v", type_name: #type_name, required: #required, }.to_string()));
 		}
 	}
 
@@ -391,8 +450,56 @@ mod tests {
 		};
 		let ts = decode_struct(input.clone(), data_struct);
 		let code = ts.to_string();
-		// Ensure get_docs includes Sources section
-		assert!(code.contains("### Sources:"));
-		assert!(code.contains("List of sources"));
+		// Ensure get_docs includes the sources
+		assert!(code.contains(&quote! { sources: Some("List of sources"), properties: vec![], }.to_string()));
+	}
+
+	#[test]
+	fn test_decode_struct_with_default() {
+		let input: DeriveInput = parse_quote!(
+			struct T {
+				/// Maximum zoom level.
+				#[vpl_default("30")]
+				level_max: Option<u8>,
+			}
+		);
+		let data_struct = match &input.data {
+			syn::Data::Struct(ds) => ds.clone(),
+			_ => panic!("Expected struct data"),
+		};
+		let code = decode_struct(input.clone(), data_struct).to_string();
+		assert!(code.contains(&quote! { required: false, default: Some("30"), doc: "Maximum zoom level.", }.to_string()));
+	}
+
+	#[test]
+	fn test_decode_struct_with_constraints_validates() {
+		let input: DeriveInput = parse_quote!(
+			struct T {
+				#[vpl(max = 100)]
+				v: Option<u8>,
+			}
+		);
+		let data_struct = match &input.data {
+			syn::Data::Struct(ds) => ds.clone(),
+			_ => panic!("Expected struct data"),
+		};
+		let code = decode_struct(input.clone(), data_struct).to_string();
+		assert!(code.contains(&quote! { args.validate()?; Ok(args) }.to_string()));
+	}
+
+	#[test]
+	#[should_panic(expected = "required field 'v' can't have a default value")]
+	fn test_decode_struct_default_of_required_field() {
+		let input: DeriveInput = parse_quote!(
+			struct T {
+				#[vpl_default("1")]
+				v: u8,
+			}
+		);
+		let data_struct = match &input.data {
+			syn::Data::Struct(ds) => ds.clone(),
+			_ => panic!("Expected struct data"),
+		};
+		decode_struct(input.clone(), data_struct);
 	}
 }
@@ -5,14 +5,16 @@
 //!
 //! # Provided macros
 //! - `#[derive(VPLDecode)]`: Derive macro to decode VPL data into Rust structs.
+//! - `#[derive(VPLValidate)]`: Derive macro to check `#[vpl(...)]` constraints of decoded VPL arguments.
 //! - `#[derive(ConfigDoc)]`: Derive macro to generate YAML documentation for configuration structs.
 //! - `#[context("...")]`: Attribute macro to add error context to functions returning `Result`.
 
 mod args;
 mod config_doc;
 mod decode_vpl;
+mod validate_vpl;
 
-use crate::{args::*, config_doc::*, decode_vpl::*};
+use crate::{args::*, config_doc::*, decode_vpl::*, validate_vpl::*};
 use proc_macro::TokenStream;
 use proc_macro2::{Ident, Span};
 use quote::{ToTokens, quote};
@@ -22,7 +24,11 @@ use syn::{Fields, parse_macro_input, spanned::Spanned};
 ///
 /// This macro can be applied to named-field structs to automatically generate decoding logic
 /// from VPL (VersaTiles Programming Language) data.
-#[proc_macro_derive(VPLDecode)]
+///
+/// It also generates `get_docs()`, which returns the doc comments of the struct and its fields
+/// as `crate::OperationDocs`. Optional fields can document their default value with
+/// `#[vpl_default("...")]`.
+#[proc_macro_derive(VPLDecode, attributes(vpl_default))]
 pub fn decode_vpl(input: TokenStream) -> TokenStream {
 	let input = parse_macro_input!(input as syn::DeriveInput);
 
@@ -34,6 +40,51 @@ pub fn decode_vpl(input: TokenStream) -> TokenStream {
 	TokenStream::from(expanded)
 }
 
+/// Derive macro to check the values of decoded VPL arguments.
+///
+/// Fields can be annotated with constraints:
+/// - `#[vpl(min = 0.0, max = 100.0)]`: numeric bounds, both inclusive
+/// - `#[vpl(non_empty)]`: strings or lists must not be empty
+/// - `#[vpl(one_of = "eq|ne|lt")]`: the string must be one of the `|`-separated values
+///
+/// Constraints of `Option` fields are only checked if a value is set. The macro generates
+/// `validate(&self) -> anyhow::Result<()>`, which lists all violations in one error.
+/// If the struct also derives `VPLDecode`, `from_vpl_node` calls `validate` automatically.
+///
+/// # Example
+///
+/// ```rust
+/// use versatiles_derive::VPLValidate;
+///
+/// #[derive(VPLValidate)]
+/// struct Args {
+///     #[vpl(min = 0, max = 100)]
+///     quality: Option<u8>,
+///     #[vpl(one_of = "eq|ne")]
+///     operator: String,
+/// }
+///
+/// let args = Args { quality: Some(120), operator: String::from("gt") };
+/// assert_eq!(
+///     args.validate().unwrap_err().to_string(),
+///     "invalid arguments:\n'quality' must be at most 100, but is 120\n'operator' must be one of 'eq', 'ne', but is 'gt'"
+/// );
+///
+/// let args = Args { quality: None, operator: String::from("eq") };
+/// assert!(args.validate().is_ok());
+/// ```
+#[proc_macro_derive(VPLValidate, attributes(vpl))]
+pub fn validate_vpl(input: TokenStream) -> TokenStream {
+	let input = parse_macro_input!(input as syn::DeriveInput);
+
+	let expanded = match input.data.clone() {
+		syn::Data::Struct(data_struct) => validate_struct(input, data_struct),
+		_ => panic!("VPLValidate can only be derived for structs, but: {:?}", input.data),
+	};
+
+	TokenStream::from(expanded)
+}
+
 /// Derive macro to generate YAML configuration documentation.
 ///
 /// `ConfigDoc` generates a YAML-formatted demo of the configuration struct, including documentation
@@ -0,0 +1,189 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Attribute, DataStruct, DeriveInput, Expr, Fields, LitStr};
+
+/// Constraints of one field, parsed from `#[vpl(...)]` attributes.
+#[derive(Default)]
+struct Constraints {
+	min: Option<Expr>,
+	max: Option<Expr>,
+	non_empty: bool,
+	one_of: Option<Vec<String>>,
+}
+
+impl Constraints {
+	fn is_empty(&self) -> bool {
+		self.min.is_none() && self.max.is_none() && !self.non_empty && self.one_of.is_none()
+	}
+}
+
+/// Returns `true` if a field has a `#[vpl(...)]` attribute, i.e. if the struct needs validation.
+pub fn has_constraints(attrs: &[Attribute]) -> bool {
+	attrs.iter().any(|attr| attr.path().is_ident("vpl"))
+}
+
+fn parse_constraints(attrs: &[Attribute]) -> syn::Result<Constraints> {
+	let mut constraints = Constraints::default();
+	for attr in attrs.iter().filter(|attr| attr.path().is_ident("vpl")) {
+		attr.parse_nested_meta(|meta| {
+			if meta.path.is_ident("min") {
+				constraints.min = Some(meta.value()?.parse()?);
+			} else if meta.path.is_ident("max") {
+				constraints.max = Some(meta.value()?.parse()?);
+			} else if meta.path.is_ident("non_empty") {
+				constraints.non_empty = true;
+			} else if meta.path.is_ident("one_of") {
+				let values = meta.value()?.parse::<LitStr>()?.value();
+				constraints.one_of = Some(values.split('|').map(str::to_string).collect());
+			} else {
+				return Err(meta.error("expected `min`, `max`, `non_empty` or `one_of`"));
+			}
+			Ok(())
+		})?;
+	}
+	Ok(constraints)
+}
+
+pub fn validate_struct(input: DeriveInput, data_struct: DataStruct) -> TokenStream {
+	let name = input.ident;
+
+	let Fields::Named(fields_named) = data_struct.fields else {
+		panic!("VPLValidate can only be derived for structs with named fields");
+	};
+
+	let mut checks: Vec<TokenStream> = Vec::new();
+	for field in fields_named.named {
+		let constraints = match parse_constraints(&field.attrs) {
+			Ok(constraints) => constraints,
+			Err(err) => return err.to_compile_error(),
+		};
+		if constraints.is_empty() {
+			continue;
+		}
+
+		let field_name = field.ident.expect("named field");
+		let field_str = field_name.to_string();
+		let field_type = &field.ty;
+		let optional = quote!(#field_type).to_string().replace(' ', "").starts_with("Option<");
+
+		let mut field_checks: Vec<TokenStream> = Vec::new();
+		if let Some(min) = constraints.min {
+			field_checks.push(quote! {
+				if (*value as f64) < (#min as f64) {
+					errors.push(format!("'{}' must be at least {}, but is {}", #field_str, #min, value));
+				}
+			});
+		}
+		if let Some(max) = constraints.max {
+			field_checks.push(quote! {
+				if (*value as f64) > (#max as f64) {
+					errors.push(format!("'{}' must be at most {}, but is {}", #field_str, #max, value));
+				}
+			});
+		}
+		if constraints.non_empty {
+			field_checks.push(quote! {
+				if value.is_empty() {
+					errors.push(format!("'{}' must not be empty", #field_str));
+				}
+			});
+		}
+		if let Some(one_of) = constraints.one_of {
+			let list = one_of.iter().map(|v| format!("'{v}'")).collect::<Vec<_>>().join(", ");
+			field_checks.push(quote! {
+				if ![#(#one_of),*].contains(&value.as_str()) {
+					errors.push(format!("'{}' must be one of {}, but is '{}'", #field_str, #list, value));
+				}
+			});
+		}
+
+		checks.push(if optional {
+			quote! {
+				if let Some(value) = &self.#field_name {
+					#(#field_checks)*
+				}
+			}
+		} else {
+			quote! {
+				{
+					let value = &self.#field_name;
+					#(#field_checks)*
+				}
+			}
+		});
+	}
+
+	quote! {
+		impl #name {
+			/// Checks the `#[vpl(...)]` constraints of all fields and reports every violation at once.
+			#[allow(clippy::unnecessary_cast, unused_mut)]
+			pub fn validate(&self) -> anyhow::Result<()> {
+				let mut errors: Vec<String> = Vec::new();
+				#(#checks)*
+				if !errors.is_empty() {
+					anyhow::bail!("invalid arguments:\n{}", errors.join("\n"));
+				}
+				Ok(())
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use syn::parse_quote;
+
+	fn validate(input: DeriveInput) -> String {
+		let data_struct = match &input.data {
+			syn::Data::Struct(ds) => ds.clone(),
+			_ => panic!("Expected struct data"),
+		};
+		validate_struct(input.clone(), data_struct).to_string()
+	}
+
+	#[test]
+	fn test_validate_struct_checks() {
+		let code = validate(parse_quote!(
+			struct T {
+				#[vpl(min = 0.0, max = 100.0)]
+				quality: Option<f32>,
+				#[vpl(non_empty)]
+				name: String,
+				#[vpl(one_of = "eq|ne")]
+				op: Option<String>,
+				other: bool,
+			}
+		));
+		assert!(code.contains(&quote! { if let Some(value) = &self.quality }.to_string()));
+		assert!(code.contains(&quote! { if (*value as f64) < (0.0 as f64) }.to_string()));
+		assert!(code.contains(&quote! { if (*value as f64) > (100.0 as f64) }.to_string()));
+		assert!(code.contains(&quote! { let value = &self.name; if value.is_empty() }.to_string()));
+		assert!(code.contains(&quote! { if !["eq", "ne"].contains(&value.as_str()) }.to_string()));
+		assert!(code.contains("'eq', 'ne'"));
+		assert!(!code.contains("self . other"));
+	}
+
+	#[test]
+	fn test_validate_struct_without_constraints() {
+		let code = validate(parse_quote!(
+			struct T {
+				v: u8,
+			}
+		));
+		assert!(code.contains("let mut errors"));
+		assert!(!code.contains("self . v"));
+	}
+
+	#[test]
+	fn test_validate_struct_unknown_constraint() {
+		let code = validate(parse_quote!(
+			struct T {
+				#[vpl(between = 3)]
+				v: u8,
+			}
+		));
+		assert!(code.contains("compile_error"));
+		assert!(code.contains("expected `min`, `max`, `non_empty` or `one_of`"));
+	}
+}
@@ -0,0 +1,196 @@
+//! Conversion between WGS-84 coordinates and the integer coordinate space of a single tile.
+//!
+//! A [`CoordTransform`] is created for one tile and one extent (usually 4096, as in vector tiles).
+//! It precomputes the scale and offset of the Web-Mercator projection, so converting many
+//! coordinates of the same tile is cheap. Tile space starts at the north-west corner of the tile,
+//! with `x` growing to the east and `y` growing to the south.
+//!
+//! # Example
+//! ```rust
+//! use versatiles_core::TileCoord;
+//! use versatiles_geometry::coord_transform::CoordTransform;
+//!
+//! let transform = CoordTransform::new(&TileCoord::new(1, 1, 0).unwrap(), 4096);
+//! assert_eq!(transform.transform_lon_lat(90.0, 0.0), (2048, 4096));
+//! assert_eq!(transform.inverse_transform(0, 4096), (0.0, 0.0));
+//! ```
+
+use crate::geo::{
+	Coordinates, Geometry, LineStringGeometry, MultiLineStringGeometry, MultiPointGeometry, MultiPolygonGeometry,
+	PointGeometry, PolygonGeometry, RingGeometry,
+};
+use std::f64::consts::PI;
+use versatiles_core::TileCoord;
+
+/// Affine transform from Web-Mercator world coordinates (`0..1`) into the space of one tile.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CoordTransform {
+	/// Size of the whole world in tile units, `2^level * extent`.
+	scale: f64,
+	/// Position of the tile's north-west corner in tile units.
+	offset_x: f64,
+	offset_y: f64,
+	extent: u32,
+}
+
+impl CoordTransform {
+	/// Creates the transform for the tile at `coord` with a coordinate range of `0..=extent`.
+	#[must_use]
+	pub fn new(coord: &TileCoord, extent: u32) -> CoordTransform {
+		let extent_f = f64::from(extent);
+		CoordTransform {
+			scale: 2.0f64.powi(i32::from(coord.level)) * extent_f,
+			offset_x: f64::from(coord.x) * extent_f,
+			offset_y: f64::from(coord.y) * extent_f,
+			extent,
+		}
+	}
+
+	/// Returns the extent of the tile space.
+	#[must_use]
+	pub fn extent(&self) -> u32 {
+		self.extent
+	}
+
+	/// Converts longitude and latitude in degrees into rounded tile coordinates.
+	///
+	/// Coordinates outside the tile are clamped to `[0, extent]`.
+	#[must_use]
+	pub fn transform_lon_lat(&self, lon: f64, lat: f64) -> (i32, i32) {
+		let world_x = lon / 360.0 + 0.5;
+		let world_y = 0.5 - (PI / 4.0 + lat.to_radians() / 2.0).tan().ln() / (2.0 * PI);
+		(
+			self.quantize(world_x, self.offset_x),
+			self.quantize(world_y, self.offset_y),
+		)
+	}
+
+	/// Converts tile coordinates back into longitude and latitude in degrees.
+	#[must_use]
+	pub fn inverse_transform(&self, x: i32, y: i32) -> (f64, f64) {
+		let world_x = (f64::from(x) + self.offset_x) / self.scale;
+		let world_y = (f64::from(y) + self.offset_y) / self.scale;
+		let lon = (world_x - 0.5) * 360.0;
+		let lat = (PI * (1.0 - 2.0 * world_y)).sinh().atan().to_degrees();
+		(lon, lat)
+	}
+
+	/// Returns a copy of `geom` with all coordinates converted into tile space.
+	#[must_use]
+	pub fn transform_geometry(&self, geom: &Geometry) -> Geometry {
+		map_geometry(geom, &|c| {
+			let (x, y) = self.transform_lon_lat(c.x(), c.y());
+			Coordinates::new(f64::from(x), f64::from(y))
+		})
+	}
+
+	/// Returns a copy of `geom` with all coordinates converted from tile space back into longitude and latitude.
+	#[must_use]
+	pub fn inverse_transform_geometry(&self, geom: &Geometry) -> Geometry {
+		map_geometry(geom, &|c| {
+			let (lon, lat) = self.inverse_transform(c.x().round() as i32, c.y().round() as i32);
+			Coordinates::new(lon, lat)
+		})
+	}
+
+	fn quantize(&self, world: f64, offset: f64) -> i32 {
+		// NaN (e.g. for latitudes beyond the poles) ends up as 0
+		(world * self.scale - offset).round().clamp(0.0, f64::from(self.extent)) as i32
+	}
+}
+
+/// Returns a copy of `geom` with `f` applied to every coordinate.
+fn map_geometry(geom: &Geometry, f: &dyn Fn(&Coordinates) -> Coordinates) -> Geometry {
+	let line = |coordinates: &[Coordinates]| coordinates.iter().map(f).collect::<Vec<_>>();
+	let polygon =
+		|polygon: &PolygonGeometry| PolygonGeometry(polygon.0.iter().map(|ring| RingGeometry(line(&ring.0))).collect());
+	match geom {
+		Geometry::Point(g) => Geometry::Point(PointGeometry(f(&g.0))),
+		Geometry::LineString(g) => Geometry::LineString(LineStringGeometry(line(&g.0))),
+		Geometry::Polygon(g) => Geometry::Polygon(polygon(g)),
+		Geometry::MultiPoint(g) => {
+			Geometry::MultiPoint(MultiPointGeometry(g.0.iter().map(|p| PointGeometry(f(&p.0))).collect()))
+		}
+		Geometry::MultiLineString(g) => Geometry::MultiLineString(MultiLineStringGeometry(
+			g.0.iter().map(|l| LineStringGeometry(line(&l.0))).collect(),
+		)),
+		Geometry::MultiPolygon(g) => Geometry::MultiPolygon(MultiPolygonGeometry(g.0.iter().map(polygon).collect())),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use rstest::rstest;
+
+	#[rstest]
+	#[case(10, 4096)]
+	#[case(14, 4096)]
+	#[case(14, 512)]
+	#[case(18, 256)]
+	fn round_trip_precision(#[case] level: u8, #[case] extent: u32) {
+		// Berlin
+		let (lon, lat) = (13.404954, 52.520008);
+		let coord = TileCoord::from_geo(lon, lat, level).unwrap();
+		let transform = CoordTransform::new(&coord, extent);
+
+		for i in 0..10 {
+			let [west, south, east, north] = coord.to_geo_bbox().as_array();
+			let lon = west + (east - west) * f64::from(i) / 10.0;
+			let lat = south + (north - south) * f64::from(i) / 10.0;
+			let (x, y) = transform.transform_lon_lat(lon, lat);
+			let (lon2, lat2) = transform.inverse_transform(x, y);
+			assert!((lon - lon2).abs() < 1.0 / f64::from(extent), "lon {lon} -> {lon2}");
+			assert!((lat - lat2).abs() < 1.0 / f64::from(extent), "lat {lat} -> {lat2}");
+		}
+	}
+
+	#[test]
+	fn corners_and_clamping() {
+		let transform = CoordTransform::new(&TileCoord::new(2, 1, 1).unwrap(), 4096);
+		let [west, south, east, north] = TileCoord::new(2, 1, 1).unwrap().to_geo_bbox().as_array();
+		assert_eq!(transform.transform_lon_lat(west, north), (0, 0));
+		assert_eq!(transform.transform_lon_lat(east, south), (4096, 4096));
+		assert_eq!(transform.transform_lon_lat(-180.0, 90.0), (0, 0));
+		assert_eq!(transform.transform_lon_lat(180.0, -90.0), (4096, 4096));
+		assert_eq!(transform.transform_lon_lat(0.0, 100.0), (4096, 0));
+	}
+
+	#[test]
+	fn geometry() {
+		let transform = CoordTransform::new(&TileCoord::new(0, 0, 0).unwrap(), 4096);
+		let polygon = Geometry::new_polygon(vec![vec![[0.0, 0.0], [90.0, 0.0], [90.0, -45.0], [0.0, 0.0]]]);
+		assert_eq!(
+			transform.transform_geometry(&polygon),
+			Geometry::new_polygon(vec![vec![
+				[2048.0, 2048.0],
+				[3072.0, 2048.0],
+				[3072.0, 2623.0],
+				[2048.0, 2048.0]
+			]])
+		);
+
+		let points = Geometry::new_multi_point(vec![[-180.0, 0.0], [0.0, 85.0511287798066]]);
+		assert_eq!(
+			transform.transform_geometry(&points),
+			Geometry::new_multi_point(vec![[0.0, 2048.0], [2048.0, 0.0]])
+		);
+	}
+
+	#[test]
+	fn inverse_geometry() {
+		let transform = CoordTransform::new(&TileCoord::new(0, 0, 0).unwrap(), 4096);
+		let line = Geometry::new_line_string(vec![[2048.0, 2048.0], [3072.0, 2048.0]]);
+		assert_eq!(
+			transform.inverse_transform_geometry(&line),
+			Geometry::new_line_string(vec![[0.0, 0.0], [90.0, 0.0]])
+		);
+
+		let polygon = Geometry::new_polygon(vec![vec![[0.0, 0.0], [90.0, 0.0], [90.0, -45.0], [0.0, 0.0]]]);
+		let tile_space = transform.transform_geometry(&polygon);
+		assert_eq!(
+			transform.transform_geometry(&transform.inverse_transform_geometry(&tile_space)),
+			tile_space
+		);
+	}
+}
@@ -12,6 +12,8 @@ mod collection;
 mod feature;
 mod geometry;
 mod properties;
+mod sanitize;
+mod simplify;
 mod types;
 mod value;
 
@@ -4,11 +4,58 @@
 use versatiles_core::json::JsonObject;
 
 use super::GeoValue;
+use anyhow::{Result, bail, ensure};
 use std::{
 	collections::{BTreeMap, btree_map},
 	fmt::Debug,
+	str::FromStr,
 };
 
+/// How [`GeoProperties::update_with_mode`] treats keys that exist on both sides.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum UpdateMode {
+	/// New values replace existing ones.
+	#[default]
+	Overwrite,
+	/// Existing values are kept, only missing keys are added.
+	FillMissing,
+	/// All new keys get this prefix, so existing keys are left alone.
+	Prefix(String),
+}
+
+/// Parses `overwrite`, `fill_missing` or `prefix=<str>`.
+impl FromStr for UpdateMode {
+	type Err = anyhow::Error;
+
+	fn from_str(text: &str) -> Result<UpdateMode> {
+		Ok(match text {
+			"overwrite" => UpdateMode::Overwrite,
+			"fill_missing" => UpdateMode::FillMissing,
+			_ => match text.strip_prefix("prefix=") {
+				Some(prefix) => {
+					ensure!(
+						!prefix.is_empty(),
+						"the prefix of update mode '{text}' must not be empty"
+					);
+					UpdateMode::Prefix(prefix.to_string())
+				}
+				None => bail!("invalid update mode '{text}', expected 'overwrite', 'fill_missing' or 'prefix=<str>'"),
+			},
+		})
+	}
+}
+
+impl UpdateMode {
+	/// Returns the key under which a new property with `key` is stored.
+	#[must_use]
+	pub fn target_key(&self, key: &str) -> String {
+		match self {
+			UpdateMode::Prefix(prefix) => format!("{prefix}{key}"),
+			_ => key.to_string(),
+		}
+	}
+}
+
 /// A key–value map of properties associated with a geographic feature.
 ///
 /// Used by `GeoFeature` to store attributes that accompany geometry data.
@@ -36,8 +83,23 @@ impl GeoProperties {
 
 	/// Merges another `GeoProperties` into this one, overwriting existing keys.
 	pub fn update(&mut self, new_properties: &GeoProperties) {
+		self.update_with_mode(new_properties, &UpdateMode::Overwrite);
+	}
+
+	/// Merges another `GeoProperties` into this one, resolving conflicting keys as given by `mode`.
+	pub fn update_with_mode(&mut self, new_properties: &GeoProperties, mode: &UpdateMode) {
 		for (k, v) in new_properties.iter() {
-			self.0.insert(k.to_string(), v.clone());
+			match mode {
+				UpdateMode::Overwrite => {
+					self.0.insert(k.to_string(), v.clone());
+				}
+				UpdateMode::FillMissing => {
+					self.0.entry(k.to_string()).or_insert_with(|| v.clone());
+				}
+				UpdateMode::Prefix(_) => {
+					self.0.insert(mode.target_key(k), v.clone());
+				}
+			}
 		}
 	}
 
@@ -170,6 +232,56 @@ mod tests {
 		assert_eq!(base.get("c"), Some(&gv("3")));
 	}
 
+	#[test]
+	fn update_with_mode_fill_missing() {
+		let mut base = GeoProperties::from(vec![("a", gv("1")), ("b", gv("2"))]);
+		let add = GeoProperties::from(vec![("b", gv("B")), ("c", gv("3"))]);
+		base.update_with_mode(&add, &UpdateMode::FillMissing);
+		assert_eq!(
+			base,
+			GeoProperties::from(vec![("a", gv("1")), ("b", gv("2")), ("c", gv("3"))])
+		);
+	}
+
+	#[test]
+	fn update_with_mode_prefix() {
+		let mut base = GeoProperties::from(vec![("a", gv("1")), ("b", gv("2"))]);
+		let add = GeoProperties::from(vec![("b", gv("B")), ("c", gv("3"))]);
+		base.update_with_mode(&add, &"prefix=csv_".parse().unwrap());
+		assert_eq!(
+			base,
+			GeoProperties::from(vec![
+				("a", gv("1")),
+				("b", gv("2")),
+				("csv_b", gv("B")),
+				("csv_c", gv("3"))
+			])
+		);
+	}
+
+	#[test]
+	fn update_with_mode_overwrite() {
+		let mut base = GeoProperties::from(vec![("a", gv("1")), ("b", gv("2"))]);
+		let add = GeoProperties::from(vec![("b", gv("B")), ("c", gv("3"))]);
+		base.update_with_mode(&add, &UpdateMode::default());
+		assert_eq!(
+			base,
+			GeoProperties::from(vec![("a", gv("1")), ("b", gv("B")), ("c", gv("3"))])
+		);
+	}
+
+	#[test]
+	fn parse_update_mode() {
+		assert_eq!("overwrite".parse::<UpdateMode>().unwrap(), UpdateMode::Overwrite);
+		assert_eq!("fill_missing".parse::<UpdateMode>().unwrap(), UpdateMode::FillMissing);
+		assert_eq!(
+			"prefix=src_".parse::<UpdateMode>().unwrap(),
+			UpdateMode::Prefix("src_".to_string())
+		);
+		assert!("prefix=".parse::<UpdateMode>().is_err());
+		assert!("merge".parse::<UpdateMode>().is_err());
+	}
+
 	#[test]
 	fn remove_and_clear() {
 		let mut p = GeoProperties::from(vec![("x", gv("1")), ("y", gv("2"))]);
@@ -0,0 +1,174 @@
+//! Repair of degenerate geometries.
+//!
+//! Geometries that went through quantization or property updates can end up with
+//! duplicate points, unclosed rings or collapsed parts that some renderers reject.
+//! [`Geometry::repaired`] fixes the trivial issues and removes the parts that cannot
+//! be fixed.
+
+use super::{
+	CompositeGeometryTrait, Coordinates, Geometry, GeometryTrait, LineStringGeometry, MultiLineStringGeometry,
+	MultiPointGeometry, MultiPolygonGeometry, PolygonGeometry, RingGeometry,
+};
+
+impl Geometry {
+	/// Returns a repaired copy of this geometry, or `None` if no valid part remains.
+	///
+	/// - consecutive duplicate points are removed from lines and rings,
+	/// - unclosed rings are closed,
+	/// - lines with fewer than two distinct points are removed,
+	/// - rings with fewer than four points or zero area are removed,
+	/// - polygons whose outer ring was removed are removed entirely.
+	///
+	/// A geometry without issues is returned unchanged.
+	#[must_use]
+	pub fn repaired(&self) -> Option<Geometry> {
+		match self {
+			Geometry::Point(g) => Some(Geometry::Point(g.clone())),
+			Geometry::LineString(g) => repair_line_string(g).map(Geometry::LineString),
+			Geometry::Polygon(g) => repair_polygon(g).map(Geometry::Polygon),
+			Geometry::MultiPoint(g) => repair_multi_point(g).map(Geometry::MultiPoint),
+			Geometry::MultiLineString(g) => repair_multi_line_string(g).map(Geometry::MultiLineString),
+			Geometry::MultiPolygon(g) => repair_multi_polygon(g).map(Geometry::MultiPolygon),
+		}
+	}
+}
+
+fn dedup_points(points: &[Coordinates]) -> Vec<Coordinates> {
+	let mut points = points.to_vec();
+	points.dedup();
+	points
+}
+
+fn repair_line_string(line_string: &LineStringGeometry) -> Option<LineStringGeometry> {
+	let points = dedup_points(line_string.as_vec());
+	(points.len() >= 2).then_some(LineStringGeometry(points))
+}
+
+fn repair_ring(ring: &RingGeometry) -> Option<RingGeometry> {
+	let mut points = dedup_points(ring.as_vec());
+	if points.first() != points.last() {
+		points.push(points[0].clone());
+	}
+	let ring = RingGeometry(points);
+	(ring.len() >= 4 && ring.area().abs() > 1e-14).then_some(ring)
+}
+
+fn repair_polygon(polygon: &PolygonGeometry) -> Option<PolygonGeometry> {
+	let (outer, inner) = polygon.as_vec().split_first()?;
+	let mut rings = vec![repair_ring(outer)?];
+	rings.extend(inner.iter().filter_map(repair_ring));
+	Some(PolygonGeometry(rings))
+}
+
+fn repair_multi_point(multi_point: &MultiPointGeometry) -> Option<MultiPointGeometry> {
+	(!multi_point.is_empty()).then(|| multi_point.clone())
+}
+
+fn repair_multi_line_string(multi_line_string: &MultiLineStringGeometry) -> Option<MultiLineStringGeometry> {
+	let lines = multi_line_string
+		.as_vec()
+		.iter()
+		.filter_map(repair_line_string)
+		.collect::<Vec<_>>();
+	(!lines.is_empty()).then_some(MultiLineStringGeometry(lines))
+}
+
+fn repair_multi_polygon(multi_polygon: &MultiPolygonGeometry) -> Option<MultiPolygonGeometry> {
+	let polygons = multi_polygon
+		.as_vec()
+		.iter()
+		.filter_map(repair_polygon)
+		.collect::<Vec<_>>();
+	(!polygons.is_empty()).then_some(MultiPolygonGeometry(polygons))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn valid_geometries_are_unchanged() {
+		let geometries = [
+			Geometry::new_point([1.0, 2.0]),
+			Geometry::new_line_string(vec![[0.0, 0.0], [1.0, 1.0]]),
+			Geometry::new_multi_point(vec![[1.0, 2.0], [1.0, 2.0]]),
+			Geometry::new_example(),
+		];
+		for geometry in geometries {
+			assert_eq!(geometry.repaired(), Some(geometry));
+		}
+	}
+
+	#[test]
+	fn duplicate_points_are_removed() {
+		let geometry = Geometry::new_line_string(vec![[0.0, 0.0], [0.0, 0.0], [1.0, 1.0], [1.0, 1.0], [2.0, 0.0]]);
+		assert_eq!(
+			geometry.repaired(),
+			Some(Geometry::new_line_string(vec![[0.0, 0.0], [1.0, 1.0], [2.0, 0.0]]))
+		);
+	}
+
+	#[test]
+	fn zero_length_lines_are_removed() {
+		let geometry = Geometry::new_line_string(vec![[3.0, 3.0], [3.0, 3.0], [3.0, 3.0]]);
+		assert_eq!(geometry.repaired(), None);
+
+		let geometry = Geometry::new_multi_line_string(vec![vec![[3.0, 3.0], [3.0, 3.0]], vec![[0.0, 0.0], [1.0, 0.0]]]);
+		assert_eq!(
+			geometry.repaired(),
+			Some(Geometry::new_multi_line_string(vec![vec![[0.0, 0.0], [1.0, 0.0]]]))
+		);
+	}
+
+	#[test]
+	fn unclosed_rings_are_closed() {
+		let geometry = Geometry::new_polygon(vec![vec![[0.0, 0.0], [4.0, 0.0], [4.0, 4.0], [0.0, 4.0]]]);
+		assert_eq!(
+			geometry.repaired(),
+			Some(Geometry::new_polygon(vec![vec![
+				[0.0, 0.0],
+				[4.0, 0.0],
+				[4.0, 4.0],
+				[0.0, 4.0],
+				[0.0, 0.0]
+			]]))
+		);
+	}
+
+	#[test]
+	fn degenerate_rings_are_removed() {
+		let outer = vec![[0.0, 0.0], [4.0, 0.0], [4.0, 4.0], [0.0, 0.0]];
+		let too_short = vec![[1.0, 1.0], [2.0, 1.0], [1.0, 1.0]];
+		let zero_area = vec![[1.0, 1.0], [2.0, 2.0], [3.0, 3.0], [1.0, 1.0]];
+
+		let geometry = Geometry::new_polygon(vec![outer.clone(), too_short.clone(), zero_area.clone()]);
+		assert_eq!(geometry.repaired(), Some(Geometry::new_polygon(vec![outer.clone()])));
+
+		// A collapsed outer ring removes the whole polygon, including its holes.
+		let geometry = Geometry::new_multi_polygon(vec![vec![zero_area, outer.clone()], vec![outer.clone()]]);
+		assert_eq!(
+			geometry.repaired(),
+			Some(Geometry::new_multi_polygon(vec![vec![outer]]))
+		);
+
+		let geometry = Geometry::new_polygon(vec![too_short]);
+		assert_eq!(geometry.repaired(), None);
+	}
+
+	#[test]
+	fn ring_with_duplicates_collapses() {
+		// After removing duplicates only two distinct points remain.
+		let geometry = Geometry::new_polygon(vec![vec![[0.0, 0.0], [0.0, 0.0], [5.0, 5.0], [5.0, 5.0], [0.0, 0.0]]]);
+		assert_eq!(geometry.repaired(), None);
+	}
+
+	#[test]
+	fn empty_multi_geometries_are_removed() {
+		assert_eq!(Geometry::MultiPoint(MultiPointGeometry::new()).repaired(), None);
+		assert_eq!(
+			Geometry::MultiLineString(MultiLineStringGeometry::new()).repaired(),
+			None
+		);
+		assert_eq!(Geometry::MultiPolygon(MultiPolygonGeometry::new()).repaired(), None);
+	}
+}
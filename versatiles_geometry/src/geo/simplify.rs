@@ -0,0 +1,188 @@
+//! Simplification and rescaling of geometries.
+//!
+//! Low zoom vector tiles often carry far more vertices than can be rendered.
+//! [`Geometry::simplified`] removes vertices with the Douglas-Peucker algorithm and
+//! [`Geometry::rescaled`] moves all coordinates onto a coarser integer grid.
+
+use super::{
+	Coordinates, Geometry, LineStringGeometry, MultiLineStringGeometry, MultiPointGeometry, MultiPolygonGeometry,
+	PointGeometry, PolygonGeometry, RingGeometry,
+};
+
+impl Geometry {
+	/// Returns a copy of this geometry with all lines and rings simplified by the Douglas-Peucker
+	/// algorithm, or `None` if no valid part remains.
+	///
+	/// Vertices closer than `tolerance` to the simplified line are removed. Parts that collapse
+	/// are removed like in [`Geometry::repaired`]. A `tolerance` of zero or less returns the
+	/// geometry unchanged.
+	#[must_use]
+	pub fn simplified(&self, tolerance: f64) -> Option<Geometry> {
+		if tolerance <= 0.0 {
+			return Some(self.clone());
+		}
+		let line = |g: &LineStringGeometry| LineStringGeometry(douglas_peucker(&g.0, tolerance));
+		let polygon = |g: &PolygonGeometry| {
+			PolygonGeometry(
+				g.0.iter()
+					.map(|ring| RingGeometry(douglas_peucker(&ring.0, tolerance)))
+					.collect(),
+			)
+		};
+		let simplified = match self {
+			Geometry::Point(_) | Geometry::MultiPoint(_) => self.clone(),
+			Geometry::LineString(g) => Geometry::LineString(line(g)),
+			Geometry::Polygon(g) => Geometry::Polygon(polygon(g)),
+			Geometry::MultiLineString(g) => {
+				Geometry::MultiLineString(MultiLineStringGeometry(g.0.iter().map(line).collect()))
+			}
+			Geometry::MultiPolygon(g) => Geometry::MultiPolygon(MultiPolygonGeometry(g.0.iter().map(polygon).collect())),
+		};
+		simplified.repaired()
+	}
+
+	/// Returns a copy of this geometry with all coordinates multiplied by `factor` and rounded
+	/// to integers, e.g. to reduce the extent of a vector tile layer from 4096 to 1024.
+	///
+	/// The result may contain duplicate points or collapsed parts; use [`Geometry::repaired`]
+	/// to remove them.
+	#[must_use]
+	pub fn rescaled(&self, factor: f64) -> Geometry {
+		let coord = |c: &Coordinates| Coordinates::new((c.x() * factor).round(), (c.y() * factor).round());
+		let coords = |c: &[Coordinates]| c.iter().map(coord).collect::<Vec<_>>();
+		let polygon =
+			|g: &PolygonGeometry| PolygonGeometry(g.0.iter().map(|ring| RingGeometry(coords(&ring.0))).collect());
+		match self {
+			Geometry::Point(g) => Geometry::Point(PointGeometry(coord(&g.0))),
+			Geometry::LineString(g) => Geometry::LineString(LineStringGeometry(coords(&g.0))),
+			Geometry::Polygon(g) => Geometry::Polygon(polygon(g)),
+			Geometry::MultiPoint(g) => Geometry::MultiPoint(MultiPointGeometry(
+				g.0.iter().map(|p| PointGeometry(coord(&p.0))).collect(),
+			)),
+			Geometry::MultiLineString(g) => Geometry::MultiLineString(MultiLineStringGeometry(
+				g.0.iter().map(|line| LineStringGeometry(coords(&line.0))).collect(),
+			)),
+			Geometry::MultiPolygon(g) => Geometry::MultiPolygon(MultiPolygonGeometry(g.0.iter().map(polygon).collect())),
+		}
+	}
+}
+
+/// Simplifies a line or closed ring with the Douglas-Peucker algorithm. The first and the last
+/// point are always kept.
+fn douglas_peucker(points: &[Coordinates], tolerance: f64) -> Vec<Coordinates> {
+	if points.len() < 3 {
+		return points.to_vec();
+	}
+
+	let mut keep = vec![false; points.len()];
+	keep[0] = true;
+	keep[points.len() - 1] = true;
+
+	// Iterative instead of recursive, so long lines can't overflow the stack.
+	let mut ranges = vec![(0, points.len() - 1)];
+	while let Some((start, end)) = ranges.pop() {
+		let (index, distance) = (start + 1..end)
+			.map(|i| (i, segment_distance(&points[i], &points[start], &points[end])))
+			.fold((0, -1.0), |max, item| if item.1 > max.1 { item } else { max });
+		if distance > tolerance {
+			keep[index] = true;
+			ranges.push((start, index));
+			ranges.push((index, end));
+		}
+	}
+
+	points
+		.iter()
+		.zip(keep)
+		.filter(|(_, keep)| *keep)
+		.map(|(point, _)| point.clone())
+		.collect()
+}
+
+/// Returns the distance of point `p` to the segment from `a` to `b`.
+fn segment_distance(p: &Coordinates, a: &Coordinates, b: &Coordinates) -> f64 {
+	let (dx, dy) = (b.x() - a.x(), b.y() - a.y());
+	let length2 = dx * dx + dy * dy;
+	let t = if length2 == 0.0 {
+		0.0
+	} else {
+		(((p.x() - a.x()) * dx + (p.y() - a.y()) * dy) / length2).clamp(0.0, 1.0)
+	};
+	(p.x() - a.x() - t * dx).hypot(p.y() - a.y() - t * dy)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// A line along the x axis with 101 points that wiggle by ±1 unit.
+	fn dense_line() -> Geometry {
+		Geometry::new_line_string(
+			(0..=100)
+				.map(|i| [f64::from(i) * 10.0, if i % 2 == 0 { 0.0 } else { 1.0 }])
+				.collect::<Vec<_>>(),
+		)
+	}
+
+	#[test]
+	fn dense_line_is_simplified() {
+		let simplified = dense_line().simplified(8.0).unwrap();
+		assert_eq!(simplified, Geometry::new_line_string(vec![[0.0, 0.0], [1000.0, 0.0]]));
+	}
+
+	#[test]
+	fn zero_tolerance_keeps_geometry() {
+		assert_eq!(dense_line().simplified(0.0), Some(dense_line()));
+	}
+
+	#[test]
+	fn small_tolerance_keeps_significant_points() {
+		let line = Geometry::new_line_string(vec![[0.0, 0.0], [5.0, 4.5], [10.0, 10.0], [20.0, 10.0]]);
+		assert_eq!(
+			line.simplified(1.0),
+			Some(Geometry::new_line_string(vec![[0.0, 0.0], [10.0, 10.0], [20.0, 10.0]]))
+		);
+	}
+
+	#[test]
+	fn rings_are_simplified_and_collapsed_rings_removed() {
+		let outer = vec![
+			[0.0, 0.0],
+			[50.0, 1.0],
+			[100.0, 0.0],
+			[100.0, 100.0],
+			[0.0, 100.0],
+			[0.0, 0.0],
+		];
+		let tiny_hole = vec![[10.0, 10.0], [12.0, 10.0], [12.0, 12.0], [10.0, 10.0]];
+		let polygon = Geometry::new_polygon(vec![outer, tiny_hole]);
+
+		assert_eq!(
+			polygon.simplified(4.0).unwrap(),
+			Geometry::new_polygon(vec![vec![
+				[0.0, 0.0],
+				[100.0, 0.0],
+				[100.0, 100.0],
+				[0.0, 100.0],
+				[0.0, 0.0]
+			]])
+		);
+
+		// a polygon smaller than the tolerance collapses entirely
+		let small = Geometry::new_polygon(vec![vec![[0.0, 0.0], [3.0, 0.0], [3.0, 3.0], [0.0, 3.0], [0.0, 0.0]]]);
+		assert_eq!(small.simplified(4.0), None);
+	}
+
+	#[test]
+	fn rescale_rounds_to_the_coarser_grid() {
+		let line = Geometry::new_line_string(vec![[0.0, 0.0], [2.0, 2.0], [4096.0, 1023.0]]);
+		assert_eq!(
+			line.rescaled(0.25),
+			Geometry::new_line_string(vec![[0.0, 0.0], [1.0, 1.0], [1024.0, 256.0]])
+		);
+		assert_eq!(
+			Geometry::new_point([6.0, 10.0]).rescaled(0.25),
+			Geometry::new_point([2.0, 3.0])
+		);
+	}
+}
@@ -1,6 +1,7 @@
 //! This crate provides geometric data structures and utilities for the VersaTiles ecosystem.
 //!
 //! It includes modules for:
+//! - `coord_transform`: conversion between WGS-84 coordinates and the integer space of a tile.
 //! - `geo`: core geometry primitives and traits (e.g., `Point`, `Polygon`, etc.).
 //! - `geojson`: parsing and serialization for GeoJSON and NDGeoJSON.
 //! - `tile_outline`: helper for generating polygonal outlines from tile bounding boxes.
@@ -8,6 +9,7 @@
 //!
 //! These modules form the geometric backbone for reading, transforming, and exporting geospatial data in VersaTiles.
 
+pub mod coord_transform;
 pub mod geo;
 pub mod geojson;
 pub mod tile_outline;
@@ -1,6 +1,7 @@
 #![allow(dead_code)]
 
 use super::{geometry_type::GeomType, layer::VectorTileLayer};
+use crate::coord_transform::CoordTransform;
 use crate::geo::{
 	CompositeGeometryTrait, Coordinates, GeoFeature, GeoProperties, GeoValue, Geometry, GeometryTrait,
 	MultiLineStringGeometry, MultiPointGeometry, MultiPolygonGeometry, RingGeometry, SingleGeometryTrait,
@@ -8,7 +9,7 @@ use crate::geo::{
 use anyhow::{Context, Result, bail, ensure};
 use byteorder::LE;
 use versatiles_core::{
-	Blob,
+	Blob, TileCoord,
 	io::{ValueReader, ValueReaderSlice, ValueWriter, ValueWriterBlob},
 };
 
@@ -87,54 +88,59 @@ impl VectorTileFeature {
 		Ok(writer.into_blob())
 	}
 
-	pub fn to_geometry(&self) -> Result<Geometry> {
+	/// Decodes the geometry commands into paths, one per MoveTo command.
+	///
+	/// ClosePath commands are resolved by repeating the first point of the path.
+	fn decode_paths(&self) -> Result<Vec<Vec<Coordinates>>> {
 		// https://github.com/mapbox/vector-tile-spec/blob/master/2.1/README.md#43-geometry-encoding
 
-		let coordinates = {
-			let mut reader = ValueReaderSlice::new_le(self.geom_data.as_slice());
-
-			let mut lines: Vec<Vec<Coordinates>> = Vec::new();
-			let mut line: Vec<Coordinates> = Vec::new();
-			let mut x = 0;
-			let mut y = 0;
-
-			while reader.has_remaining() {
-				let value = reader
-					.read_varint()
-					.context("Failed to read varint for geometry command")?;
-				let command = value & 0x7;
-				let count = value >> 3;
-
-				match command {
-					1 | 2 => {
-						for _ in 0..count {
-							if command == 1 && !line.is_empty() {
-								// MoveTo command indicates the start of a new linestring
-								lines.push(line);
-								line = Vec::new();
-							}
-
-							x += reader.read_svarint().context("Failed to read x coordinate")?;
-							y += reader.read_svarint().context("Failed to read y coordinate")?;
-
-							line.push(Coordinates::new(x as f64, y as f64));
+		let mut reader = ValueReaderSlice::new_le(self.geom_data.as_slice());
+
+		let mut lines: Vec<Vec<Coordinates>> = Vec::new();
+		let mut line: Vec<Coordinates> = Vec::new();
+		let mut x = 0;
+		let mut y = 0;
+
+		while reader.has_remaining() {
+			let value = reader
+				.read_varint()
+				.context("Failed to read varint for geometry command")?;
+			let command = value & 0x7;
+			let count = value >> 3;
+
+			match command {
+				1 | 2 => {
+					for _ in 0..count {
+						if command == 1 && !line.is_empty() {
+							// MoveTo command indicates the start of a new linestring
+							lines.push(line);
+							line = Vec::new();
 						}
+
+						x += reader.read_svarint().context("Failed to read x coordinate")?;
+						y += reader.read_svarint().context("Failed to read y coordinate")?;
+
+						line.push(Coordinates::new(x as f64, y as f64));
 					}
-					7 => {
-						// ClosePath command
-						ensure!(!line.is_empty(), "ClosePath command found on an empty linestring");
-						line.push(line[0].clone());
-					}
-					_ => bail!("Unknown command {command}"),
 				}
+				7 => {
+					// ClosePath command
+					ensure!(!line.is_empty(), "ClosePath command found on an empty linestring");
+					line.push(line[0].clone());
+				}
+				_ => bail!("Unknown command {command}"),
 			}
+		}
 
-			if !line.is_empty() {
-				lines.push(line);
-			}
+		if !line.is_empty() {
+			lines.push(line);
+		}
 
-			lines
-		};
+		Ok(lines)
+	}
+
+	pub fn to_geometry(&self) -> Result<Geometry> {
+		let coordinates = self.decode_paths()?;
 
 		match self.geom_type {
 			GeomType::Unknown => bail!("Unknown geometry type"),
@@ -199,6 +205,34 @@ impl VectorTileFeature {
 		}
 	}
 
+	/// Decodes the geometry like [`to_geometry`](Self::to_geometry), but without validating it.
+	///
+	/// Degenerate lines and rings are kept, so that they can be inspected or repaired.
+	/// Rings are grouped into polygons by the sign of their area; rings with zero area
+	/// are attached to the current polygon.
+	pub fn to_unverified_geometry(&self) -> Result<Geometry> {
+		let coordinates = self.decode_paths()?;
+
+		match self.geom_type {
+			GeomType::Unknown => bail!("Unknown geometry type"),
+			GeomType::MultiPoint => Ok(Geometry::new_multi_point(
+				coordinates.into_iter().flatten().collect::<Vec<Coordinates>>(),
+			)),
+			GeomType::MultiLineString => Ok(Geometry::new_multi_line_string(coordinates)),
+			GeomType::MultiPolygon => {
+				let mut polygons: Vec<Vec<RingGeometry>> = Vec::new();
+				for ring in coordinates {
+					let ring = RingGeometry(ring);
+					match polygons.last_mut() {
+						Some(polygon) if ring.area() <= 1e-14 => polygon.push(ring),
+						_ => polygons.push(vec![ring]),
+					}
+				}
+				Ok(Geometry::new_multi_polygon(polygons))
+			}
+		}
+	}
+
 	pub fn decode_properties(&self, layer: &VectorTileLayer) -> Result<GeoProperties> {
 		layer.decode_tag_ids(&self.tag_ids)
 	}
@@ -215,6 +249,13 @@ impl VectorTileFeature {
 		Ok(feature)
 	}
 
+	/// Converts the feature of a tile at `coord` into a GeoJSON feature with WGS-84 coordinates.
+	pub fn to_geojson(&self, layer: &VectorTileLayer, coord: &TileCoord) -> Result<GeoFeature> {
+		let mut feature = self.to_feature(layer)?;
+		feature.geometry = CoordTransform::new(coord, layer.extent).inverse_transform_geometry(&feature.geometry);
+		Ok(feature)
+	}
+
 	pub fn from_geometry(id: Option<u64>, tag_ids: Vec<u32>, geometry: Geometry) -> Result<VectorTileFeature> {
 		fn write_coord(writer: &mut ValueWriterBlob<LE>, coord0: &mut (i64, i64), coord: &Coordinates) -> Result<()> {
 			let x = coord.x().round() as i64;
@@ -1,6 +1,6 @@
 use crate::geo::Geometry;
 
-#[derive(Copy, Clone, Debug, Default, PartialEq)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
 pub enum GeomType {
 	#[default]
 	Unknown = 0,
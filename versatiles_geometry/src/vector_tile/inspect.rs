@@ -0,0 +1,252 @@
+//! Summaries of the contents of vector tiles.
+//!
+//! [`inspect_tile`] answers questions like "what's inside tile 14/8800/5377?" without
+//! converting the whole tile to GeoJSON: for every layer it counts the features per
+//! geometry type and the value types per property key, and reports how many of the
+//! encoded bytes the layer takes up.
+
+use super::{VectorTile, geometry_type::GeomType};
+use crate::geo::GeoValue;
+use anyhow::Result;
+use std::collections::BTreeMap;
+use versatiles_core::{
+	Blob, TileCompression,
+	json::{JsonObject, JsonValue},
+	utils::decompress_ref,
+};
+use versatiles_derive::context;
+
+/// Summary of a vector tile, see [`inspect_tile`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct TileInspection {
+	/// Size of the uncompressed tile in bytes.
+	pub size: u64,
+	/// One entry per layer, in storage order.
+	pub layers: Vec<LayerInspection>,
+}
+
+/// Summary of a single layer of a vector tile.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LayerInspection {
+	pub name: String,
+	pub extent: u32,
+	pub version: u32,
+	/// Number of features per geometry type, e.g. `{"polygon": 3}`.
+	pub feature_counts: BTreeMap<&'static str, u64>,
+	/// Number of values per value type for every property key, e.g. `{"name": {"string": 3}}`.
+	pub properties: BTreeMap<String, BTreeMap<&'static str, u64>>,
+	/// Size of the encoded layer in bytes.
+	pub size: u64,
+	/// Share of the uncompressed tile size taken up by this layer, between `0` and `1`.
+	pub size_share: f64,
+}
+
+/// Decompresses and decodes a vector tile and summarizes its layers.
+#[context("Failed to inspect vector tile ({} bytes, {compression:?})", blob.len())]
+pub fn inspect_tile(blob: &Blob, compression: &TileCompression) -> Result<TileInspection> {
+	let blob = decompress_ref(blob, *compression)?;
+	let tile = VectorTile::from_blob(&blob)?;
+	let size = blob.len();
+
+	let layers = tile
+		.layers
+		.iter()
+		.map(|layer| {
+			let mut feature_counts = BTreeMap::new();
+			let mut properties = BTreeMap::<String, BTreeMap<&'static str, u64>>::new();
+			for feature in &layer.features {
+				*feature_counts.entry(geom_type_name(feature.geom_type)).or_default() += 1;
+				for (key, value) in layer.decode_tag_ids(&feature.tag_ids)?.iter() {
+					*properties
+						.entry(key.clone())
+						.or_default()
+						.entry(value_type_name(value))
+						.or_default() += 1;
+				}
+			}
+			let layer_size = layer.to_blob()?.len();
+			#[allow(clippy::cast_precision_loss)]
+			let size_share = if size == 0 {
+				0.0
+			} else {
+				layer_size as f64 / size as f64
+			};
+			Ok(LayerInspection {
+				name: layer.name.clone(),
+				extent: layer.extent,
+				version: layer.version,
+				feature_counts,
+				properties,
+				size: layer_size,
+				size_share,
+			})
+		})
+		.collect::<Result<Vec<_>>>()?;
+
+	Ok(TileInspection { size, layers })
+}
+
+fn geom_type_name(geom_type: GeomType) -> &'static str {
+	match geom_type {
+		GeomType::Unknown => "unknown",
+		GeomType::MultiPoint => "point",
+		GeomType::MultiLineString => "linestring",
+		GeomType::MultiPolygon => "polygon",
+	}
+}
+
+fn value_type_name(value: &GeoValue) -> &'static str {
+	match value {
+		GeoValue::Bool(_) => "bool",
+		GeoValue::Double(_) => "double",
+		GeoValue::Float(_) => "float",
+		GeoValue::Int(_) => "int",
+		GeoValue::Null => "null",
+		GeoValue::String(_) => "string",
+		GeoValue::UInt(_) => "uint",
+	}
+}
+
+/// Converts a map of counts into a JSON object.
+fn counts_to_json(counts: &BTreeMap<&'static str, u64>) -> JsonObject {
+	let mut object = JsonObject::new();
+	for (key, count) in counts {
+		object.set(key, *count);
+	}
+	object
+}
+
+impl TileInspection {
+	/// Converts the summary to JSON, e.g. to print it with the `--json` option of a CLI tool.
+	#[must_use]
+	pub fn as_json_value(&self) -> JsonValue {
+		let mut object = JsonObject::new();
+		object.set("size", self.size);
+		object.set(
+			"layers",
+			self
+				.layers
+				.iter()
+				.map(LayerInspection::as_json_value)
+				.collect::<Vec<_>>(),
+		);
+		JsonValue::from(object)
+	}
+
+	/// Writes the summary with one category per layer.
+	#[cfg(feature = "cli")]
+	pub async fn pretty_print(&self, print: &mut versatiles_core::utils::PrettyPrint) {
+		print.add_key_value("size", &self.size).await;
+		for layer in &self.layers {
+			let mut p = print.get_category(&format!("layer \"{}\"", layer.name)).await;
+			p.add_key_value("extent", &layer.extent).await;
+			p.add_key_value("version", &layer.version).await;
+			p.add_key_value("size", &layer.size).await;
+			p.add_key_value("size share", &format!("{:.1}%", layer.size_share * 100.0))
+				.await;
+			let features = p.get_list("features").await;
+			for (geom_type, count) in &layer.feature_counts {
+				features.add_key_value(geom_type, count).await;
+			}
+			let properties = p.get_list("properties").await;
+			for (key, types) in &layer.properties {
+				let types = types.iter().map(|(t, c)| format!("{t}: {c}")).collect::<Vec<_>>();
+				properties.add_key_value(key, &types.join(", ")).await;
+			}
+		}
+	}
+}
+
+impl LayerInspection {
+	#[must_use]
+	pub fn as_json_value(&self) -> JsonValue {
+		let mut properties = JsonObject::new();
+		for (key, types) in &self.properties {
+			properties.set(key, counts_to_json(types));
+		}
+
+		let mut object = JsonObject::new();
+		object.set("name", &self.name);
+		object.set("extent", self.extent);
+		object.set("version", self.version);
+		object.set("feature_counts", counts_to_json(&self.feature_counts));
+		object.set("properties", properties);
+		object.set("size", self.size);
+		object.set("size_share", self.size_share);
+		JsonValue::from(object)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		geo::{GeoFeature, Geometry},
+		vector_tile::VectorTileLayer,
+	};
+	use versatiles_core::utils::compress;
+
+	fn feature(id: u64, name: &str) -> GeoFeature {
+		let mut feature = GeoFeature::new(Geometry::new_point([100.0, 100.0]));
+		feature.set_property("tile_id".to_string(), GeoValue::UInt(id));
+		feature.set_property("tile_name".to_string(), GeoValue::from(name));
+		feature
+	}
+
+	fn tile_blob() -> Blob {
+		let places =
+			VectorTileLayer::from_features("places".to_string(), vec![feature(1, "a"), feature(2, "b")], 4096, 2).unwrap();
+		VectorTile::new(vec![places]).to_blob().unwrap()
+	}
+
+	#[test]
+	fn inspect_layers() -> Result<()> {
+		let blob = compress(tile_blob(), TileCompression::Gzip)?;
+		let inspection = inspect_tile(&blob, &TileCompression::Gzip)?;
+
+		assert_eq!(inspection.size, tile_blob().len());
+		assert_eq!(inspection.layers.len(), 1);
+		let layer = &inspection.layers[0];
+		assert_eq!(layer.name, "places");
+		assert_eq!((layer.extent, layer.version), (4096, 2));
+		assert_eq!(layer.feature_counts, BTreeMap::from([("point", 2)]));
+		assert_eq!(
+			layer.properties,
+			BTreeMap::from([
+				("tile_id".to_string(), BTreeMap::from([("uint", 2)])),
+				("tile_name".to_string(), BTreeMap::from([("string", 2)])),
+			])
+		);
+		assert!(layer.size_share > 0.9 && layer.size_share < 1.0);
+		Ok(())
+	}
+
+	#[test]
+	fn inspection_as_json() -> Result<()> {
+		let inspection = inspect_tile(&tile_blob(), &TileCompression::Uncompressed)?;
+		let json = inspection.as_json_value().stringify();
+		let expected = r#"{"layers":[{"extent":4096,"feature_counts":{"point":2},"name":"places","properties":{"tile_id":{"uint":2},"tile_name":{"string":2}},"size":"#;
+		assert!(json.starts_with(expected), "{json}");
+		Ok(())
+	}
+
+	#[cfg(feature = "cli")]
+	#[tokio::test]
+	async fn pretty_print() -> Result<()> {
+		let mut print = versatiles_core::utils::PrettyPrint::new();
+		inspect_tile(&tile_blob(), &TileCompression::Uncompressed)?
+			.pretty_print(&mut print)
+			.await;
+		let output = print.as_string().await;
+		assert!(output.contains("layer \"places\":\n  extent: 4_096\n"), "{output}");
+		assert!(output.contains("  features:\n    point: 2\n"), "{output}");
+		assert!(output.contains("    tile_name: \"string: 2\"\n"), "{output}");
+		Ok(())
+	}
+
+	#[test]
+	fn invalid_tile() {
+		let err = inspect_tile(&Blob::from(vec![0xff; 4]), &TileCompression::Uncompressed).unwrap_err();
+		assert!(format!("{err:?}").contains("Failed to inspect vector tile (4 bytes, Uncompressed)"));
+	}
+}
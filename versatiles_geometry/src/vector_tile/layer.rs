@@ -15,14 +15,15 @@
 //!  * field 15: `version` (varint, default 1)
 
 use crate::{
-	geo::{GeoFeature, GeoProperties, GeoValue},
+	coord_transform::CoordTransform,
+	geo::{GeoCollection, GeoFeature, GeoProperties, GeoValue},
 	vector_tile::{feature::VectorTileFeature, property_manager::PropertyManager, value::GeoValuePBF},
 };
 use anyhow::{Context, Result, anyhow, bail};
 use byteorder::LE;
-use std::mem::swap;
+use std::{collections::HashSet, mem::swap};
 use versatiles_core::{
-	Blob,
+	Blob, TileCoord,
 	io::{ValueReader, ValueWriter, ValueWriterBlob},
 };
 
@@ -273,6 +274,60 @@ impl VectorTileLayer {
 		Ok(())
 	}
 
+	/// Returns the number of features in this layer.
+	#[must_use]
+	pub fn feature_count(&self) -> usize {
+		self.features.len()
+	}
+
+	/// Returns `true` if this layer has no features.
+	#[must_use]
+	pub fn is_empty(&self) -> bool {
+		self.features.is_empty()
+	}
+
+	/// Estimates the size of the encoded layer in bytes, without encoding it.
+	///
+	/// Counts the key table string lengths, the encoded values (8 bytes per double, 4 per float
+	/// and integer, the length of strings), 2 bytes per tag ID pair and the already encoded
+	/// geometry commands of each feature, plus a few bytes of protobuf framing per entry.
+	/// See [`VectorTile::tile_size_bytes`](super::VectorTile::tile_size_bytes).
+	#[must_use]
+	pub fn layer_size_bytes(&self) -> usize {
+		// every embedded entry needs a field key and a length prefix
+		const FRAMING: usize = 2;
+
+		let keys: usize = self.property_manager.iter_key().map(|key| key.len() + FRAMING).sum();
+		let values: usize = self
+			.property_manager
+			.iter_val()
+			.map(|value| {
+				FRAMING
+					+ match value {
+						GeoValue::Null => 0,
+						GeoValue::Bool(_) => 2,
+						GeoValue::Double(_) => 9,
+						GeoValue::Float(_) | GeoValue::Int(_) | GeoValue::UInt(_) => 5,
+						GeoValue::String(s) => s.len() + FRAMING,
+					}
+			})
+			.sum();
+		let features: usize = self
+			.features
+			.iter()
+			.map(|feature| {
+				// the geometry type and the framing of the feature, its tags and its geometry
+				let mut size = 2 + 3 * FRAMING + feature.tag_ids.len() + feature.geom_data.as_slice().len();
+				if feature.id.is_some() {
+					size += 5;
+				}
+				size
+			})
+			.sum();
+
+		FRAMING + self.name.len() + keys + values + features
+	}
+
 	/// Retains only features that satisfy `filter_fn` (applies to raw `VectorTileFeature`s).
 	pub fn retain_features<F>(&mut self, filter_fn: F)
 	where
@@ -281,6 +336,42 @@ impl VectorTileLayer {
 		self.features.retain(filter_fn);
 	}
 
+	/// Removes every feature whose value of property `key` was already seen in an earlier feature.
+	/// Features without this property are kept. Returns the number of removed features.
+	pub fn dedup_features(&mut self, key: &str) -> usize {
+		let Ok(key_id) = self.property_manager.key.find(&key.to_string()) else {
+			return 0;
+		};
+		let values = &self.property_manager.val;
+		let count = self.features.len();
+		let mut seen = HashSet::new();
+		self.features.retain(|feature| {
+			let value = feature
+				.tag_ids
+				.chunks_exact(2)
+				.find(|tag| tag[0] == key_id)
+				.and_then(|tag| values.get(tag[1]).ok());
+			value.is_none_or(|value| seen.insert(value))
+		});
+		count - self.features.len()
+	}
+
+	/// Removes every feature whose geometry is identical to that of an earlier feature, regardless of
+	/// properties. Geometries are compared by type and encoded coordinate sequence, so the same shape
+	/// with a different start point or ring orientation is not a duplicate. Returns the number of removed features.
+	pub fn dedup_features_by_geometry(&mut self) -> usize {
+		let mut seen = HashSet::new();
+		let keep = self
+			.features
+			.iter()
+			.map(|feature| seen.insert((feature.geom_type, feature.geom_data.as_slice())))
+			.collect::<Vec<bool>>();
+		let count = self.features.len();
+		let mut keep = keep.into_iter();
+		self.features.retain(|_| keep.next().unwrap_or(true));
+		count - self.features.len()
+	}
+
 	/// Encodes a property map to vector‑tile `tag_ids` using/expanding this layer's property tables.
 	pub fn encode_tag_ids(&mut self, properties: GeoProperties) -> Vec<u32> {
 		self.property_manager.encode_tag_ids(properties)
@@ -319,6 +410,37 @@ impl VectorTileLayer {
 		})
 	}
 
+	/// Builds a layer of the tile at `coord` from GeoJSON features with WGS-84 coordinates.
+	///
+	/// Coordinates are quantized to `0..=extent`, coordinates outside the tile are clamped to its edges.
+	pub fn from_geojson(
+		name: String,
+		collection: GeoCollection,
+		coord: &TileCoord,
+		extent: u32,
+	) -> Result<VectorTileLayer> {
+		let transform = CoordTransform::new(coord, extent);
+		let features = collection
+			.features
+			.into_iter()
+			.map(|mut feature| {
+				feature.geometry = transform.transform_geometry(&feature.geometry);
+				feature
+			})
+			.collect();
+		VectorTileLayer::from_features(name, features, extent, 1)
+	}
+
+	/// Converts all features of the tile at `coord` into GeoJSON features with WGS-84 coordinates.
+	pub fn to_geojson(&self, coord: &TileCoord) -> Result<GeoCollection> {
+		let features = self
+			.features
+			.iter()
+			.map(|feature| feature.to_geojson(self, coord))
+			.collect::<Result<Vec<_>>>()?;
+		Ok(GeoCollection::from(features))
+	}
+
 	/// Test helper that constructs a deterministic example layer with one example feature.
 	#[cfg(test)]
 	pub fn new_example() -> Self {
@@ -329,6 +451,7 @@ impl VectorTileLayer {
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use crate::geo::Geometry;
 	use versatiles_core::io::ValueReaderSlice;
 
 	#[test]
@@ -416,4 +539,79 @@ mod tests {
 		assert_eq!(layer.version, 1);
 		Ok(())
 	}
+
+	fn point(x: f64, name: Option<&str>) -> GeoFeature {
+		let mut feature = GeoFeature::new(Geometry::new_point([x, 1.0]));
+		if let Some(name) = name {
+			feature.set_property("name".to_string(), name);
+		}
+		feature
+	}
+
+	fn names(layer: &VectorTileLayer) -> Result<Vec<String>> {
+		Ok(layer
+			.to_features()?
+			.iter()
+			.map(|f| f.properties.get("name").map_or(String::from("-"), ToString::to_string))
+			.collect())
+	}
+
+	#[test]
+	fn test_dedup_features() -> Result<()> {
+		let features = vec![
+			point(1.0, Some("a")),
+			point(2.0, Some("b")),
+			point(3.0, Some("a")),
+			point(4.0, None),
+			point(5.0, None),
+			point(6.0, Some("b")),
+		];
+		let mut layer = VectorTileLayer::from_features("dups".to_string(), features, 4096, 2)?;
+		assert_eq!(layer.dedup_features("name"), 2);
+		assert_eq!(names(&layer)?, ["a", "b", "-", "-"]);
+		assert_eq!(layer.dedup_features("name"), 0);
+		assert_eq!(layer.dedup_features("unknown"), 0);
+		assert_eq!(layer.feature_count(), 4);
+		Ok(())
+	}
+
+	#[test]
+	fn test_dedup_features_by_geometry() -> Result<()> {
+		let features = vec![
+			point(1.0, Some("a")),
+			point(2.0, Some("b")),
+			point(1.0, Some("c")),
+			point(1.0, None),
+			point(2.0, Some("d")),
+			point(3.0, Some("e")),
+		];
+		let mut layer = VectorTileLayer::from_features("dups".to_string(), features, 4096, 2)?;
+		assert_eq!(layer.dedup_features_by_geometry(), 3);
+		assert_eq!(names(&layer)?, ["a", "b", "e"]);
+		assert_eq!(layer.dedup_features_by_geometry(), 0);
+		Ok(())
+	}
+
+	#[test]
+	fn test_geojson_round_trip() -> Result<()> {
+		let coord = TileCoord::new(10, 550, 335)?;
+		let extent = 4096;
+		// the center of the tile
+		let (lon, lat) = CoordTransform::new(&coord, extent).inverse_transform(2048, 2048);
+		let collection = GeoCollection::from_json_str(&format!(
+			r#"{{"type":"FeatureCollection","features":[{{"type":"Feature","geometry":{{"type":"Point","coordinates":[{lon},{lat}]}},"properties":{{"name":"center"}}}}]}}"#
+		))?;
+
+		let layer = VectorTileLayer::from_geojson("points".to_string(), collection, &coord, extent)?;
+		assert_eq!(
+			layer.to_features()?[0].geometry,
+			Geometry::new_multi_point(vec![[2048.0, 2048.0]])
+		);
+
+		let features = layer.to_geojson(&coord)?.features;
+		assert_eq!(features.len(), 1);
+		assert_eq!(features[0].properties.get("name"), Some(&GeoValue::from("center")));
+		assert_eq!(features[0].geometry, Geometry::new_multi_point(vec![[lon, lat]]));
+		Ok(())
+	}
 }
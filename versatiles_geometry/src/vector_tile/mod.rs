@@ -12,10 +12,15 @@
 
 mod feature;
 mod geometry_type;
+mod inspect;
 mod layer;
 mod property_manager;
+mod sanitize;
+mod simplify;
 mod tile;
 mod value;
 
+pub use inspect::{LayerInspection, TileInspection, inspect_tile};
 pub use layer::VectorTileLayer;
+pub use sanitize::{GeometrySanitizeMode, SanitizeStats};
 pub use tile::VectorTile;
@@ -42,6 +42,19 @@ where
 			.copied()
 	}
 
+	/// Replaces the entry with `id` by `entry`, keeping the id. Fails if `entry` already exists.
+	pub fn replace(&mut self, id: u32, entry: T) -> Result<()> {
+		ensure!(!self.map.contains_key(&entry), "entry '{entry:?}' already exists");
+		let old = self
+			.list
+			.get_mut(id as usize)
+			.ok_or_else(|| anyhow!("id '{id:?}' not found"))?;
+		self.map.remove(old);
+		self.map.insert(entry.clone(), id);
+		*old = entry;
+		Ok(())
+	}
+
 	pub fn get(&self, id: u32) -> Result<&T> {
 		self
 			.list
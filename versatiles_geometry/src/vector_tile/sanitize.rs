@@ -0,0 +1,242 @@
+//! Validation and repair of feature geometries in vector tiles.
+//!
+//! Decodes every feature geometry, checks it with [`Geometry::repaired`] and, depending
+//! on the [`GeometrySanitizeMode`], keeps, repairs or drops the feature. Features without
+//! issues keep their original geometry encoding.
+
+use super::{VectorTile, VectorTileLayer, feature::VectorTileFeature};
+use crate::geo::Geometry;
+use std::fmt::Display;
+
+/// How [`VectorTile::sanitize_geometries`] treats features with degenerate geometries.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GeometrySanitizeMode {
+	/// Only count the affected features and leave the tile unchanged.
+	Report,
+	/// Fix trivial issues (duplicate points, unclosed rings), remove degenerate parts and
+	/// drop features without any valid part.
+	Repair,
+	/// Drop every feature with an invalid geometry.
+	Drop,
+}
+
+/// Counts of features checked and changed by [`VectorTile::sanitize_geometries`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SanitizeStats {
+	/// Number of features checked.
+	pub checked: u64,
+	/// Number of features with an invalid geometry.
+	pub invalid: u64,
+	/// Number of features whose geometry was repaired.
+	pub fixed: u64,
+	/// Number of features that were removed.
+	pub dropped: u64,
+}
+
+impl SanitizeStats {
+	/// Adds the counts of `other` to these counts.
+	pub fn add(&mut self, other: &SanitizeStats) {
+		self.checked += other.checked;
+		self.invalid += other.invalid;
+		self.fixed += other.fixed;
+		self.dropped += other.dropped;
+	}
+}
+
+impl Display for SanitizeStats {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(
+			f,
+			"{} features checked, {} invalid, {} fixed, {} dropped",
+			self.checked, self.invalid, self.fixed, self.dropped
+		)
+	}
+}
+
+impl VectorTile {
+	/// Checks the geometries of all features in all layers and handles invalid ones according to `mode`.
+	pub fn sanitize_geometries(&mut self, mode: GeometrySanitizeMode) -> SanitizeStats {
+		let mut stats = SanitizeStats::default();
+		for layer in &mut self.layers {
+			stats.add(&layer.sanitize_geometries(mode));
+		}
+		stats
+	}
+}
+
+impl VectorTileLayer {
+	/// Checks the geometries of all features in this layer and handles invalid ones according to `mode`.
+	pub fn sanitize_geometries(&mut self, mode: GeometrySanitizeMode) -> SanitizeStats {
+		let mut stats = SanitizeStats::default();
+		self.features.retain_mut(|feature| {
+			stats.checked += 1;
+
+			// Features that can't be decoded at all are treated like features without a valid part.
+			let (geometry, repaired) = match feature.to_unverified_geometry() {
+				Ok(geometry) => {
+					let repaired = geometry.repaired();
+					if repaired.as_ref() == Some(&geometry) {
+						return true;
+					}
+					(Some(geometry), repaired)
+				}
+				Err(err) => {
+					log::trace!("failed to decode geometry of feature {:?}: {err:?}", feature.id);
+					(None, None)
+				}
+			};
+			stats.invalid += 1;
+			log::trace!("invalid geometry in layer '{}': {geometry:?}", self.name);
+
+			match (mode, repaired) {
+				(GeometrySanitizeMode::Report, _) => true,
+				(GeometrySanitizeMode::Repair, Some(repaired)) => match replace_geometry(feature, repaired) {
+					Ok(()) => {
+						stats.fixed += 1;
+						true
+					}
+					Err(err) => {
+						log::trace!("failed to encode repaired geometry: {err:?}");
+						stats.dropped += 1;
+						false
+					}
+				},
+				(GeometrySanitizeMode::Repair | GeometrySanitizeMode::Drop, _) => {
+					stats.dropped += 1;
+					false
+				}
+			}
+		});
+		stats
+	}
+}
+
+fn replace_geometry(feature: &mut VectorTileFeature, geometry: Geometry) -> anyhow::Result<()> {
+	*feature = VectorTileFeature::from_geometry(feature.id, std::mem::take(&mut feature.tag_ids), geometry)?;
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		geo::{GeoFeature, GeoProperties, GeoValue},
+		vector_tile::geometry_type::GeomType,
+	};
+
+	fn feature(id: u64, geometry: Geometry) -> GeoFeature {
+		let mut feature = GeoFeature::new(geometry);
+		feature.set_id(GeoValue::from(id));
+		feature.properties = GeoProperties::from(vec![("name", GeoValue::from(format!("feature {id}")))]);
+		feature
+	}
+
+	fn square() -> Vec<[f64; 2]> {
+		vec![[0.0, 0.0], [10.0, 0.0], [10.0, 10.0], [0.0, 10.0], [0.0, 0.0]]
+	}
+
+	fn degenerate_tile() -> VectorTile {
+		let features = vec![
+			// valid
+			feature(1, Geometry::new_polygon(vec![square()])),
+			// duplicate points: fixable
+			feature(
+				2,
+				Geometry::new_line_string(vec![[0.0, 0.0], [0.0, 0.0], [5.0, 5.0], [5.0, 5.0]]),
+			),
+			// zero-length line: unfixable
+			feature(3, Geometry::new_line_string(vec![[2.0, 2.0], [2.0, 2.0]])),
+			// one valid and one zero-length part: fixable
+			feature(
+				4,
+				Geometry::new_multi_line_string(vec![vec![[1.0, 1.0], [1.0, 1.0]], vec![[0.0, 0.0], [3.0, 0.0]]]),
+			),
+		];
+		let layer = VectorTileLayer::from_features("test".to_string(), features, 4096, 1).unwrap();
+		VectorTile::new(vec![layer])
+	}
+
+	fn feature_ids(tile: &VectorTile) -> Vec<u64> {
+		tile.layers[0].features.iter().map(|f| f.id.unwrap()).collect()
+	}
+
+	#[test]
+	fn report_keeps_tile_unchanged() {
+		let mut tile = degenerate_tile();
+		let stats = tile.sanitize_geometries(GeometrySanitizeMode::Report);
+		assert_eq!(tile, degenerate_tile());
+		assert_eq!(
+			stats,
+			SanitizeStats {
+				checked: 4,
+				invalid: 3,
+				fixed: 0,
+				dropped: 0
+			}
+		);
+	}
+
+	#[test]
+	fn repair_fixes_and_drops() {
+		let mut tile = degenerate_tile();
+		let stats = tile.sanitize_geometries(GeometrySanitizeMode::Repair);
+		assert_eq!(stats.to_string(), "4 features checked, 3 invalid, 2 fixed, 1 dropped");
+		assert_eq!(feature_ids(&tile), [1, 2, 4]);
+
+		let layer = &tile.layers[0];
+		let geometries = layer
+			.features
+			.iter()
+			.map(|f| f.to_geometry().unwrap())
+			.collect::<Vec<_>>();
+		assert_eq!(geometries[0], Geometry::new_multi_polygon(vec![vec![square()]]));
+		assert_eq!(
+			geometries[1],
+			Geometry::new_multi_line_string(vec![vec![[0.0, 0.0], [5.0, 5.0]]])
+		);
+		assert_eq!(
+			geometries[2],
+			Geometry::new_multi_line_string(vec![vec![[0.0, 0.0], [3.0, 0.0]]])
+		);
+
+		// properties survive the repair
+		assert_eq!(
+			layer.features[1].decode_properties(layer).unwrap().get("name"),
+			Some(&GeoValue::from("feature 2"))
+		);
+
+		// a second pass finds nothing
+		let stats = tile.sanitize_geometries(GeometrySanitizeMode::Repair);
+		assert_eq!(stats.invalid, 0);
+	}
+
+	#[test]
+	fn drop_removes_all_invalid_features() {
+		let mut tile = degenerate_tile();
+		let stats = tile.sanitize_geometries(GeometrySanitizeMode::Drop);
+		assert_eq!(stats.to_string(), "4 features checked, 3 invalid, 0 fixed, 3 dropped");
+		assert_eq!(feature_ids(&tile), [1]);
+	}
+
+	#[test]
+	fn unclosed_ring_is_closed() {
+		// MoveTo(0,0) LineTo(10,0)(10,10)(0,10) without ClosePath
+		let mut feature = VectorTileFeature::from_geometry(
+			Some(1),
+			vec![],
+			Geometry::new_line_string(vec![[0.0, 0.0], [10.0, 0.0], [10.0, 10.0], [0.0, 10.0]]),
+		)
+		.unwrap();
+		feature.geom_type = GeomType::MultiPolygon;
+		assert!(feature.to_geometry().is_err());
+
+		let mut layer = VectorTileLayer::new_standard("test");
+		layer.features.push(feature);
+		let stats = layer.sanitize_geometries(GeometrySanitizeMode::Repair);
+		assert_eq!(stats.fixed, 1);
+		assert_eq!(
+			layer.features[0].to_geometry().unwrap(),
+			Geometry::new_multi_polygon(vec![vec![square()]])
+		);
+	}
+}
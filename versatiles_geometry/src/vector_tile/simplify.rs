@@ -0,0 +1,137 @@
+//! Simplification and extent reduction of feature geometries in vector tiles.
+//!
+//! Decodes every feature geometry, optionally rescales it to a smaller layer extent,
+//! simplifies it with [`Geometry::simplified`] and drops features that collapse.
+//! Features that don't change keep their original geometry encoding.
+
+use super::{VectorTile, VectorTileLayer, feature::VectorTileFeature};
+use crate::geo::Geometry;
+
+impl VectorTile {
+	/// Simplifies the geometries of all features in all layers, see [`VectorTileLayer::simplify_geometries`].
+	/// Returns the number of removed features.
+	pub fn simplify_geometries(&mut self, tolerance: f64, extent: Option<u32>) -> usize {
+		self
+			.layers
+			.iter_mut()
+			.map(|layer| layer.simplify_geometries(tolerance, extent))
+			.sum()
+	}
+}
+
+impl VectorTileLayer {
+	/// Simplifies the geometries of all features in this layer.
+	///
+	/// If `extent` is set and differs from the extent of the layer, all coordinates are rescaled
+	/// to the new extent first. `tolerance` is measured in units of the resulting extent.
+	/// Features whose geometry collapses are removed; features that can't be decoded are kept
+	/// unchanged. Returns the number of removed features.
+	pub fn simplify_geometries(&mut self, tolerance: f64, extent: Option<u32>) -> usize {
+		let factor = match extent {
+			Some(extent) if extent != self.extent => Some(f64::from(extent) / f64::from(self.extent)),
+			_ => None,
+		};
+		if factor.is_none() && tolerance <= 0.0 {
+			return 0;
+		}
+
+		let count = self.features.len();
+		self.features.retain_mut(|feature| {
+			let geometry = match feature.to_geometry() {
+				Ok(geometry) => geometry,
+				Err(err) => {
+					log::trace!("failed to decode geometry of feature {:?}: {err:?}", feature.id);
+					return true;
+				}
+			};
+			let simplified = match factor {
+				Some(factor) => geometry.rescaled(factor).repaired(),
+				None => Some(geometry.clone()),
+			}
+			.and_then(|g| g.simplified(tolerance));
+
+			match simplified {
+				Some(simplified) if simplified == geometry => true,
+				Some(simplified) => match replace_geometry(feature, simplified) {
+					Ok(()) => true,
+					Err(err) => {
+						log::trace!("failed to encode simplified geometry: {err:?}");
+						false
+					}
+				},
+				None => false,
+			}
+		});
+		if let Some(extent) = extent {
+			self.extent = extent;
+		}
+		count - self.features.len()
+	}
+}
+
+fn replace_geometry(feature: &mut VectorTileFeature, geometry: Geometry) -> anyhow::Result<()> {
+	*feature = VectorTileFeature::from_geometry(feature.id, std::mem::take(&mut feature.tag_ids), geometry)?;
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::geo::GeoFeature;
+
+	fn layer(geometries: Vec<Geometry>) -> VectorTileLayer {
+		let features = geometries.into_iter().map(GeoFeature::new).collect();
+		VectorTileLayer::from_features("test".to_string(), features, 4096, 1).unwrap()
+	}
+
+	fn geometries(layer: &VectorTileLayer) -> Vec<Geometry> {
+		layer.features.iter().map(|f| f.to_geometry().unwrap()).collect()
+	}
+
+	fn dense_line() -> Vec<[f64; 2]> {
+		(0..=400)
+			.map(|i| [f64::from(i) * 10.0, f64::from(i % 2) * 2.0])
+			.collect()
+	}
+
+	#[test]
+	fn simplify_drops_points_and_collapsed_features() {
+		let square = vec![[0.0, 0.0], [3.0, 0.0], [3.0, 3.0], [0.0, 3.0], [0.0, 0.0]];
+		let mut layer = layer(vec![
+			Geometry::new_line_string(dense_line()),
+			Geometry::new_polygon(vec![square]),
+		]);
+		let size = layer.to_blob().unwrap().len();
+
+		assert_eq!(layer.simplify_geometries(8.0, None), 1);
+		assert_eq!(
+			geometries(&layer),
+			[Geometry::new_multi_line_string(vec![vec![[0.0, 0.0], [4000.0, 0.0]]])]
+		);
+		assert!(layer.to_blob().unwrap().len() < size);
+	}
+
+	#[test]
+	fn zero_tolerance_keeps_layer_unchanged() {
+		let mut layer = layer(vec![Geometry::new_line_string(dense_line())]);
+		let original = layer.clone();
+		assert_eq!(layer.simplify_geometries(0.0, None), 0);
+		assert_eq!(layer, original);
+		assert_eq!(layer.simplify_geometries(0.0, Some(4096)), 0);
+		assert_eq!(layer, original);
+	}
+
+	#[test]
+	fn extent_is_reduced() {
+		let mut tile = VectorTile::new(vec![layer(vec![
+			Geometry::new_line_string(vec![[0.0, 0.0], [4096.0, 2048.0]]),
+			Geometry::new_line_string(vec![[0.0, 0.0], [1.0, 1.0]]),
+		])]);
+		assert_eq!(tile.simplify_geometries(0.0, Some(1024)), 1);
+		assert_eq!(tile.layers[0].extent, 1024);
+		assert_eq!(
+			geometries(&tile.layers[0]),
+			[Geometry::new_multi_line_string(vec![vec![[0.0, 0.0], [1024.0, 512.0]]])]
+		);
+	}
+}
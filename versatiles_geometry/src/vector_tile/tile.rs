@@ -77,6 +77,31 @@ impl VectorTile {
 		Ok(writer.into_blob())
 	}
 
+	/// Estimates the size of the encoded tile in bytes, without encoding it, e.g. to check the
+	/// recommended limit of 500 KB before doing expensive work.
+	///
+	/// The estimate is usually within 20% of the length of [`VectorTile::to_blob`], see
+	/// [`VectorTileLayer::layer_size_bytes`].
+	#[must_use]
+	pub fn tile_size_bytes(&self) -> usize {
+		self.layers.iter().map(|layer| 3 + layer.layer_size_bytes()).sum()
+	}
+
+	/// Returns `true` if none of the layers has any features.
+	#[must_use]
+	pub fn is_empty(&self) -> bool {
+		self.layers.iter().all(VectorTileLayer::is_empty)
+	}
+
+	/// Removes layers without features, e.g. after features were filtered out.
+	///
+	/// With `name`, only empty layers of that name are removed and all others are kept.
+	pub fn remove_empty_layers(&mut self, name: Option<&str>) {
+		self
+			.layers
+			.retain(|layer| !layer.is_empty() || name.is_some_and(|name| name != layer.name));
+	}
+
 	/// Returns a reference to the first layer with the given `name`, if present.
 	#[must_use]
 	pub fn find_layer(&self, name: &str) -> Option<&VectorTileLayer> {
@@ -116,4 +141,86 @@ mod tests {
 		assert_eq!(tile1, tile2);
 		Ok(())
 	}
+
+	#[tokio::test]
+	async fn tile_size_estimate() -> Result<()> {
+		let tile = get_tile().await?;
+		let estimate = tile.tile_size_bytes() as f64;
+		let actual = tile.to_blob()?.len() as f64;
+		assert!(
+			(estimate / actual - 1.0).abs() < 0.2,
+			"estimate {estimate}, actual {actual}"
+		);
+
+		for layer in &tile.layers {
+			let estimate = layer.layer_size_bytes() as f64;
+			let actual = layer.to_blob()?.len() as f64;
+			assert!(
+				(estimate / actual - 1.0).abs() < 0.2,
+				"{}: {estimate} / {actual}",
+				layer.name
+			);
+		}
+
+		assert_eq!(VectorTile::default().tile_size_bytes(), 0);
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn remove_empty_layers() -> Result<()> {
+		let mut tile = get_tile().await?;
+		let names = tile.layers.iter().map(|layer| layer.name.clone()).collect::<Vec<_>>();
+		tile
+			.layers
+			.insert(1, VectorTileLayer::new("empty".to_string(), 4096, 2));
+		tile.remove_empty_layers(Some("other"));
+		assert_eq!(tile.layers[1].name, "empty");
+		tile.remove_empty_layers(Some("empty"));
+		assert_eq!(
+			tile.layers.iter().map(|layer| &layer.name).collect::<Vec<_>>(),
+			names.iter().collect::<Vec<_>>()
+		);
+
+		tile.layers.iter_mut().for_each(|layer| layer.features.clear());
+		tile.remove_empty_layers(None);
+		assert!(tile.layers.is_empty());
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn feature_counts() -> Result<()> {
+		let mut tile = get_tile().await?;
+		let counts = tile
+			.layers
+			.iter()
+			.map(|layer| (layer.name.as_str(), layer.feature_count()))
+			.collect::<Vec<_>>();
+		assert_eq!(
+			counts,
+			[
+				("place_labels", 6),
+				("addresses", 2609),
+				("water_lines", 1),
+				("street_polygons", 11),
+				("streets_polygons_labels", 11),
+				("streets", 4224),
+				("street_labels", 608),
+				("public_transport", 4),
+				("buildings", 2328),
+				("water_polygons", 7),
+				("water_polygons_labels", 2),
+				("land", 871),
+				("sites", 333),
+			]
+		);
+		assert!(!tile.is_empty());
+
+		for layer in &mut tile.layers {
+			layer.retain_features(|_| false);
+			assert!(layer.is_empty());
+		}
+		assert!(tile.is_empty());
+		assert!(VectorTile::default().is_empty());
+		Ok(())
+	}
 }
@@ -9,10 +9,18 @@
 //! - Accepts **L8, LA8, RGB8, RGBA8** (1–4 channels). Other layouts are rejected.
 //! - If an image **has alpha but is fully opaque**, the encoder will **drop alpha** to save bytes.
 //! - Uses `image::codecs::png::PngEncoder` with a speed → (compression, filter) mapping.
+//! - [`optimize`] is a much slower, lossless alternative that searches for the smallest encoding,
+//!   including grey and palette images.
+//! - [`quantize`] is a lossy alternative that reduces the image to a palette of at most `max_colors`
+//!   colours (median cut) and stores it as an indexed PNG.
 
 use crate::traits::{DynamicImageTraitInfo, DynamicImageTraitOperation};
-use anyhow::{Result, anyhow, bail};
-use image::{DynamicImage, ImageEncoder, ImageFormat, codecs::png, load_from_memory_with_format};
+use anyhow::{Result, anyhow, bail, ensure};
+use image::{
+	ColorType, DynamicImage, GrayAlphaImage, GrayImage, ImageEncoder, ImageFormat, codecs::png,
+	load_from_memory_with_format,
+};
+use std::{borrow::Cow, collections::HashMap};
 use versatiles_core::Blob;
 use versatiles_derive::context;
 
@@ -64,6 +72,282 @@ pub fn encode(image: &DynamicImage, speed: Option<u8>) -> Result<Blob> {
 	Ok(Blob::from(buffer))
 }
 
+#[context("optimizing {}x{} {:?} as PNG", image.width(), image.height(), image.color())]
+/// Encode a `DynamicImage` into a PNG [`Blob`] that is as small as possible, trading CPU time for size.
+///
+/// The encoding is lossless:
+/// * Fully opaque images are stored without alpha, grey RGB(A) images as grey.
+/// * Images with at most 256 colours are additionally tried as palette images.
+/// * Every candidate is encoded with the highest zlib effort and several filters; the smallest wins.
+///
+/// Several times slower than [`encode`], so it's only used if explicitly requested.
+pub fn optimize(image: &DynamicImage) -> Result<Blob> {
+	if image.bits_per_value() != 8 {
+		bail!("png only supports 8-bit images");
+	}
+
+	let mut image = Cow::Borrowed(image);
+	if image.has_alpha() && image.is_opaque() {
+		image = Cow::Owned(image.as_no_alpha()?);
+	}
+	if let Some(grey) = as_grey(&image) {
+		image = Cow::Owned(grey);
+	}
+
+	let color_type = match image.color() {
+		ColorType::L8 => ::png::ColorType::Grayscale,
+		ColorType::La8 => ::png::ColorType::GrayscaleAlpha,
+		ColorType::Rgb8 => ::png::ColorType::Rgb,
+		ColorType::Rgba8 => ::png::ColorType::Rgba,
+		_ => bail!("png only supports Grey, GreyA, RGB or RGBA"),
+	};
+
+	use ::png::Filter::{Adaptive, Avg, NoFilter, Paeth, Sub, Up};
+	let mut candidates = Vec::new();
+	for filter in [NoFilter, Sub, Up, Avg, Paeth, Adaptive] {
+		candidates.push(write_png(
+			&image,
+			color_type,
+			::png::BitDepth::Eight,
+			None,
+			image.as_bytes(),
+			filter,
+		)?);
+	}
+	if image.color() != ColorType::L8
+		&& let Some(palette) = Palette::from_pixels(image.to_rgba8().pixels().map(|p| p.0))
+	{
+		candidates.append(&mut write_indexed(&image, &palette)?);
+	}
+
+	let smallest = candidates.into_iter().min_by_key(Vec::len).unwrap();
+	Ok(Blob::from(smallest))
+}
+
+#[context("quantizing {}x{} {:?} PNG to {} colours", image.width(), image.height(), image.color(), max_colors)]
+/// Encode a `DynamicImage` as an indexed PNG [`Blob`] with at most `max_colors` (2..=256) colours.
+///
+/// Images that already have few enough colours are stored losslessly. Otherwise the colours are
+/// reduced with median cut, which is lossy. Fully transparent pixels are merged into one palette
+/// entry, so a fully transparent tile becomes a tiny 1-bit image.
+pub fn quantize(image: &DynamicImage, max_colors: u16) -> Result<Blob> {
+	ensure!(
+		(2..=256).contains(&max_colors),
+		"max_colors ({max_colors}) must be between 2 and 256"
+	);
+
+	let pixels = image
+		.to_rgba8()
+		.pixels()
+		.map(|p| if p.0[3] == 0 { [0; 4] } else { p.0 })
+		.collect::<Vec<[u8; 4]>>();
+
+	let mut histogram: HashMap<[u8; 4], u32> = HashMap::new();
+	for pixel in &pixels {
+		*histogram.entry(*pixel).or_default() += 1;
+	}
+
+	let palette = if histogram.len() <= usize::from(max_colors) {
+		Palette::from_pixels(pixels.into_iter())
+	} else {
+		let lookup = median_cut(histogram, usize::from(max_colors));
+		Palette::from_pixels(pixels.into_iter().map(|pixel| lookup[&pixel]))
+	}
+	.expect("palette has at most 256 colours");
+
+	let smallest = write_indexed(image, &palette)?
+		.into_iter()
+		.min_by_key(Vec::len)
+		.unwrap();
+	Ok(Blob::from(smallest))
+}
+
+/// Reduces the colours of `histogram` to at most `max_colors` and returns the replacement of every colour.
+///
+/// Repeatedly splits the box of colours with the largest channel range at the weighted median of that
+/// channel. Each box is replaced by the weighted mean of its colours.
+fn median_cut(histogram: HashMap<[u8; 4], u32>, max_colors: usize) -> HashMap<[u8; 4], [u8; 4]> {
+	fn widest_channel(colors: &[([u8; 4], u32)]) -> (usize, u8) {
+		(0..4)
+			.map(|channel| {
+				let values = colors.iter().map(|(color, _)| color[channel]);
+				(channel, values.clone().max().unwrap() - values.min().unwrap())
+			})
+			.max_by_key(|(_, range)| *range)
+			.unwrap()
+	}
+
+	let mut boxes = vec![histogram.into_iter().collect::<Vec<_>>()];
+	while boxes.len() < max_colors {
+		let Some((index, channel)) = boxes
+			.iter()
+			.enumerate()
+			.map(|(index, colors)| (index, widest_channel(colors)))
+			.filter(|(_, (_, range))| *range > 0)
+			.max_by_key(|(_, (_, range))| *range)
+			.map(|(index, (channel, _))| (index, channel))
+		else {
+			break;
+		};
+
+		let mut colors = boxes.swap_remove(index);
+		colors.sort_unstable_by_key(|(color, _)| color[channel]);
+		let half = colors.iter().map(|(_, count)| u64::from(*count)).sum::<u64>() / 2;
+		let mut sum = 0;
+		let mut split = colors
+			.iter()
+			.position(|(_, count)| {
+				sum += u64::from(*count);
+				sum > half
+			})
+			.unwrap();
+		// both halves must keep at least one colour
+		split = split.clamp(1, colors.len() - 1);
+		boxes.push(colors.split_off(split));
+		boxes.push(colors);
+	}
+
+	let mut lookup = HashMap::new();
+	for colors in boxes {
+		let total = colors.iter().map(|(_, count)| u64::from(*count)).sum::<u64>();
+		let mut mean = [0u8; 4];
+		for (channel, value) in mean.iter_mut().enumerate() {
+			let sum = colors
+				.iter()
+				.map(|(color, count)| u64::from(color[channel]) * u64::from(*count))
+				.sum::<u64>();
+			*value = ((sum + total / 2) / total) as u8;
+		}
+		for (color, _) in colors {
+			lookup.insert(color, mean);
+		}
+	}
+	lookup
+}
+
+/// Palette, transparency and pixel indexes of an image with at most 256 colours.
+struct Palette {
+	rgb: Vec<u8>,
+	alpha: Vec<u8>,
+	indexes: Vec<u8>,
+}
+
+impl Palette {
+	/// Returns `None` if there are more than 256 colours.
+	fn from_pixels(pixels: impl Iterator<Item = [u8; 4]>) -> Option<Palette> {
+		let mut lookup = HashMap::new();
+		let mut colors: Vec<[u8; 4]> = Vec::new();
+		let mut indexes = Vec::with_capacity(pixels.size_hint().0);
+		for pixel in pixels {
+			let index = match lookup.get(&pixel) {
+				Some(index) => *index,
+				None => {
+					let index = u8::try_from(colors.len()).ok()?;
+					lookup.insert(pixel, index);
+					colors.push(pixel);
+					index
+				}
+			};
+			indexes.push(index);
+		}
+
+		// trailing opaque entries can be omitted from the tRNS chunk
+		let mut alpha = colors.iter().map(|c| c[3]).collect::<Vec<u8>>();
+		while alpha.last() == Some(&255) {
+			alpha.pop();
+		}
+
+		Some(Palette {
+			rgb: colors.iter().flat_map(|c| [c[0], c[1], c[2]]).collect(),
+			alpha,
+			indexes,
+		})
+	}
+
+	/// Returns the smallest bit depth for the palette size and the indexes packed into rows of that depth.
+	fn packed_indexes(&self, width: u32) -> (::png::BitDepth, Cow<'_, [u8]>) {
+		use ::png::BitDepth::{Eight, Four, One, Two};
+		let (depth, bits) = match self.rgb.len() / 3 {
+			0..=2 => (One, 1),
+			3..=4 => (Two, 2),
+			5..=16 => (Four, 4),
+			_ => return (Eight, Cow::Borrowed(&self.indexes)),
+		};
+
+		let per_byte = 8 / bits;
+		let mut packed = Vec::new();
+		for row in self.indexes.chunks(width as usize) {
+			for chunk in row.chunks(per_byte) {
+				let byte = chunk
+					.iter()
+					.enumerate()
+					.fold(0u8, |byte, (i, index)| byte | (index << (8 - bits * (i + 1))));
+				packed.push(byte);
+			}
+		}
+		(depth, Cow::Owned(packed))
+	}
+}
+
+/// Encodes an image with its palette, once per filter that suits indexed images.
+fn write_indexed(image: &DynamicImage, palette: &Palette) -> Result<Vec<Vec<u8>>> {
+	let (depth, data) = palette.packed_indexes(image.width());
+	[::png::Filter::NoFilter, ::png::Filter::Adaptive]
+		.into_iter()
+		.map(|filter| write_png(image, ::png::ColorType::Indexed, depth, Some(palette), &data, filter))
+		.collect()
+}
+
+/// Converts an 8-bit RGB(A) image to grey if all pixels are grey.
+fn as_grey(image: &DynamicImage) -> Option<DynamicImage> {
+	let channels = image.channel_count() as usize;
+	if channels < 3 {
+		return None;
+	}
+	let bytes = image.as_bytes();
+	if !bytes.chunks_exact(channels).all(|p| p[0] == p[1] && p[1] == p[2]) {
+		return None;
+	}
+
+	let (width, height) = (image.width(), image.height());
+	if channels == 3 {
+		GrayImage::from_raw(width, height, bytes.chunks_exact(3).map(|p| p[0]).collect()).map(DynamicImage::ImageLuma8)
+	} else {
+		GrayAlphaImage::from_raw(
+			width,
+			height,
+			bytes.chunks_exact(4).flat_map(|p| [p[0], p[3]]).collect(),
+		)
+		.map(DynamicImage::ImageLumaA8)
+	}
+}
+
+fn write_png(
+	image: &DynamicImage,
+	color_type: ::png::ColorType,
+	depth: ::png::BitDepth,
+	palette: Option<&Palette>,
+	data: &[u8],
+	filter: ::png::Filter,
+) -> Result<Vec<u8>> {
+	let mut buffer = Vec::new();
+	let mut encoder = ::png::Encoder::new(&mut buffer, image.width(), image.height());
+	encoder.set_color(color_type);
+	encoder.set_depth(depth);
+	encoder.set_compression(::png::Compression::High);
+	encoder.set_filter(filter);
+	if let Some(palette) = palette {
+		encoder.set_palette(palette.rgb.as_slice());
+		if !palette.alpha.is_empty() {
+			encoder.set_trns(palette.alpha.as_slice());
+		}
+	}
+	let mut writer = encoder.write_header()?;
+	writer.write_image_data(data)?;
+	writer.finish()?;
+	Ok(buffer)
+}
+
 #[context("encoding image {:?} as PNG", image.color())]
 /// Convenience wrapper for [`encode`] with default speed.
 pub fn image2blob(image: &DynamicImage) -> Result<Blob> {
@@ -116,4 +400,78 @@ mod tests {
 		assert!(!blob2image(&encode(&img, Some(100))?)?.has_alpha());
 		Ok(())
 	}
+
+	#[rstest]
+	#[case::grey(DynamicImage::new_test_grey())]
+	#[case::greya(DynamicImage::new_test_greya())]
+	#[case::rgb(DynamicImage::new_test_rgb())]
+	#[case::rgba(DynamicImage::new_test_rgba())]
+	fn optimize_is_lossless_and_smaller(#[case] img: DynamicImage) -> Result<()> {
+		let optimized = optimize(&img)?;
+		let decoded = blob2image(&optimized)?;
+		assert_eq!(decoded.to_rgba8(), img.to_rgba8());
+		assert!(optimized.len() <= encode(&img, Some(0))?.len());
+		Ok(())
+	}
+
+	#[test]
+	fn optimize_reduces_colors() -> Result<()> {
+		// grey pixels, stored as RGB
+		let grey = DynamicImage::ImageRgb8(image::RgbImage::from_fn(64, 64, |x, y| {
+			let v = (x * 4) as u8 ^ (y * 4) as u8;
+			image::Rgb([v, v, v])
+		}));
+		let decoded = blob2image(&optimize(&grey)?)?;
+		assert_eq!(decoded.color(), ColorType::L8);
+		assert_eq!(decoded.to_rgb8(), grey.to_rgb8());
+
+		// four colours, one of them transparent
+		let colors = [[255, 0, 0, 255], [0, 255, 0, 255], [0, 0, 255, 128], [0, 0, 0, 0]];
+		let palette = DynamicImage::ImageRgba8(image::RgbaImage::from_fn(64, 64, |x, y| {
+			image::Rgba(colors[((x / 8 + y / 8) % 4) as usize])
+		}));
+		let optimized = optimize(&palette)?;
+		assert!(optimized.len() < encode(&palette, Some(0))?.len());
+		assert_eq!(blob2image(&optimized)?.to_rgba8(), palette.to_rgba8());
+		Ok(())
+	}
+
+	#[test]
+	fn quantize_reduces_colors() -> Result<()> {
+		let img = DynamicImage::new_test_rgb();
+		for max_colors in [2, 16, 256] {
+			let colors = blob2image(&quantize(&img, max_colors)?)?
+				.to_rgba8()
+				.pixels()
+				.map(|p| p.0)
+				.collect::<std::collections::HashSet<_>>();
+			assert!(colors.len() <= usize::from(max_colors));
+		}
+
+		assert!(quantize(&img, 1).is_err());
+		assert!(quantize(&img, 257).is_err());
+		Ok(())
+	}
+
+	#[test]
+	fn quantize_keeps_few_colors() -> Result<()> {
+		let colors = [[255, 0, 0, 255], [0, 255, 0, 255], [0, 0, 255, 128]];
+		let img = DynamicImage::ImageRgba8(image::RgbaImage::from_fn(37, 19, |x, y| {
+			image::Rgba(colors[((x / 3 + y / 5) % 3) as usize])
+		}));
+		assert_eq!(blob2image(&quantize(&img, 3)?)?.to_rgba8(), img.to_rgba8());
+		Ok(())
+	}
+
+	#[test]
+	fn quantize_collapses_transparent_pixels() -> Result<()> {
+		let img = DynamicImage::ImageRgba8(image::RgbaImage::from_fn(256, 256, |x, y| {
+			image::Rgba([x as u8, y as u8, 3, 0])
+		}));
+		let quantized = quantize(&img, 256)?;
+		assert!(quantized.len() < 150);
+		let decoded = blob2image(&quantized)?.to_rgba8();
+		assert!(decoded.pixels().all(|p| p.0 == [0, 0, 0, 0]));
+		Ok(())
+	}
 }
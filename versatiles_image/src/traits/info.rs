@@ -7,12 +7,13 @@
 //! - Validating compatibility between images (same size / same color model)
 //! - Computing simple per-channel differences between two images
 //! - Determining transparency characteristics (empty/opaque) and mapping empty images to `None`
+//! - Detecting images with a single uniform color
 //!
 //! The trait builds on top of [`super::convert::DynamicImageTraitConvert`], notably its
 //! `iter_pixels()` method for zero-copy pixel traversal.
 use super::convert::DynamicImageTraitConvert;
 use anyhow::{Result, ensure};
-use image::{DynamicImage, ExtendedColorType};
+use image::{DynamicImage, ExtendedColorType, GenericImageView, Rgba};
 use versatiles_derive::context;
 
 /// Utilities to inspect/compare images and reason about alpha while avoiding extra allocations.
@@ -56,6 +57,12 @@ pub trait DynamicImageTraitInfo: DynamicImageTraitConvert {
 	/// Returns `true` when the image has an alpha channel and **all alpha values are `255`**.
 	/// Images **without** an alpha channel are treated as fully opaque (`true`).
 	fn is_opaque(&self) -> bool;
+
+	/// Returns the color of the image as RGBA if **all pixels are identical**, otherwise `None`.
+	///
+	/// Empty images (see [`is_empty`](Self::is_empty)) always return `[0, 0, 0, 0]`,
+	/// whatever the color values of their transparent pixels are.
+	fn uniform_color(&self) -> Option<Rgba<u8>>;
 }
 
 impl DynamicImageTraitInfo for DynamicImage
@@ -140,6 +147,15 @@ where
 		let alpha_channel = (self.color().channel_count() - 1) as usize;
 		return self.iter_pixels().all(|p| p[alpha_channel] == 255);
 	}
+
+	fn uniform_color(&self) -> Option<Rgba<u8>> {
+		if self.is_empty() {
+			return Some(Rgba([0, 0, 0, 0]));
+		}
+		let mut pixels = self.iter_pixels();
+		let first = pixels.next()?;
+		pixels.all(|p| p == first).then(|| self.get_pixel(0, 0))
+	}
 }
 
 /// Tests cover metadata queries, size/meta validation, empty/opaque logic and per-channel diffs.
@@ -200,6 +216,18 @@ mod tests {
 		assert_eq!(img.is_opaque(), expect_opaque);
 	}
 
+	// --- uniform_color -----------------------------------------------------
+	#[rstest]
+	#[case::l8_solid(DynamicImage::from_fn(4, 3, |_, _| [7]), Some([7, 7, 7, 255]))]
+	#[case::rgb8_solid(DynamicImage::from_fn(4, 3, |_, _| [10, 20, 30]), Some([10, 20, 30, 255]))]
+	#[case::rgba8_solid(DynamicImage::from_fn(4, 3, |_, _| [10, 20, 30, 40]), Some([10, 20, 30, 40]))]
+	#[case::rgba8_empty(sample_rgba8(0), Some([0, 0, 0, 0]))]
+	#[case::rgb8_mixed(sample_rgb8(), None)]
+	#[case::rgba8_partial(sample_rgba8(100), None)]
+	fn uniform_color(#[case] img: DynamicImage, #[case] expect: Option<[u8; 4]>) {
+		assert_eq!(img.uniform_color().map(|c| c.0), expect);
+	}
+
 	// --- into_optional -----------------------------------------------------
 	#[test]
 	fn into_optional_behaviour() {
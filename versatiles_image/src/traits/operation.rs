@@ -5,7 +5,7 @@
 //!
 //! - Removing alpha channels (unconditionally or only if fully opaque)
 //! - Computing a quick representative/average color
-//! - Cropping with resampling and downscaling with configurable filters
+//! - Cropping with resampling, resizing and downscaling with configurable filters
 //! - Alpha-aware flattening against a background color
 //! - In-place mutation of color channels (leaving alpha intact)
 //! - In-place overlay compositing of two images with size validation
@@ -40,6 +40,11 @@ pub trait DynamicImageTraitOperation: DynamicImageTraitInfo {
 	/// Coordinates are given in source pixel space. Returns an error on resize failures.
 	fn get_extract(&self, x: f64, y: f64, w: f64, h: f64, width_dst: u32, height_dst: u32) -> Result<DynamicImage>;
 
+	/// Resamples the whole image to `width_dst × height_dst` using a **Lanczos3 filter**.
+	///
+	/// Works for up- and downscaling. Returns an error on resize failures.
+	fn get_resized(&self, width_dst: u32, height_dst: u32) -> Result<DynamicImage>;
+
 	/// Produces a scaled‑down copy by the integer `factor` using a **box filter**.
 	///
 	/// Panics if `factor == 0`. Returns an error on resize failures.
@@ -110,6 +115,17 @@ where
 		Ok(dst_image)
 	}
 
+	#[context("resizing {}x{} to {}x{} ({:?})", self.width(), self.height(), width_dst, height_dst, self.color())]
+	fn get_resized(&self, width_dst: u32, height_dst: u32) -> Result<DynamicImage> {
+		let mut dst_image = DynamicImage::new(width_dst, height_dst, self.color());
+		Resizer::new().resize(
+			self,
+			&mut dst_image,
+			&ResizeOptions::default().resize_alg(ResizeAlg::Convolution(FilterType::Lanczos3)),
+		)?;
+		Ok(dst_image)
+	}
+
 	#[context("downscaling {}x{} by factor {} ({:?})", self.width(), self.height(), factor, self.color())]
 	fn get_scaled_down(&self, factor: u32) -> Result<DynamicImage> {
 		assert!(factor > 0, "Scaling factor must be greater than zero");
@@ -434,6 +450,24 @@ mod tests {
 		assert_eq!(out.extended_color_type(), ECT::Rgb8);
 	}
 
+	#[rstest]
+	#[case::down(128, 64)]
+	#[case::up(512, 300)]
+	fn get_resized_returns_requested_size(#[case] width: u32, #[case] height: u32) {
+		let img = DynamicImage::new_test_rgba();
+		let out = img.get_resized(width, height).unwrap();
+		assert_eq!(out.dimensions(), (width, height));
+		assert_eq!(out.extended_color_type(), ECT::Rgba8);
+
+		// a solid color stays (almost) the same
+		let solid = DynamicImage::from_fn(64, 64, |_x, _y| [10, 20, 30]);
+		let out = solid.get_resized(16, 16).unwrap();
+		assert!(
+			out.iter_pixels()
+				.all(|p| p.iter().zip([10u8, 20, 30]).all(|(a, b)| a.abs_diff(b) <= 1))
+		);
+	}
+
 	#[test]
 	fn overlay_draws_top_over_bottom() {
 		// Bottom: black RGB 16x16
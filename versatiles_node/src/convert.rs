@@ -87,9 +87,20 @@ pub(crate) async fn convert_tiles_with_options(
 
 	let params = TilesConverterParameters {
 		bbox_pyramid,
+		geo_bbox: None,
 		tile_compression,
 		flip_y: opts.flip_y.unwrap_or(false),
 		swap_xy: opts.swap_xy.unwrap_or(false),
+		force_recompress: false,
+		brotli_level: None,
+		optimize_png: false,
+		quantize_png: None,
+		drop_blank_tiles: false,
+		tile_size: None,
+		elide_empty_tiles: None,
+		reproducible: false,
+		dry_run: false,
+		report_path: None,
 	};
 
 	// Create a new runtime for this conversion with event bridging to JavaScript
@@ -532,9 +543,20 @@ mod tests {
 
 		let params = TilesConverterParameters {
 			bbox_pyramid,
+			geo_bbox: None,
 			tile_compression: compression,
 			flip_y,
 			swap_xy,
+			force_recompress: false,
+			brotli_level: None,
+			optimize_png: false,
+			quantize_png: None,
+			drop_blank_tiles: false,
+			tile_size: None,
+			elide_empty_tiles: None,
+			reproducible: false,
+			dry_run: false,
+			report_path: None,
 		};
 
 		assert_eq!(params.tile_compression.is_some(), compression.is_some());
@@ -310,6 +310,11 @@ impl TileServer {
 			config.static_sources.push(StaticSourceConfig {
 				src: data_location,
 				prefix: url_prefix.clone(),
+				directory_listing: None,
+				follow_symlinks: None,
+				serve_dotfiles: None,
+				cache_responses: None,
+				cache_ttl_seconds: None,
 			});
 		}
 
@@ -29,7 +29,6 @@ impl From<&TileSourceMetadata> for SourceMetadata {
 #[cfg(test)]
 mod tests {
 	use super::*;
-	use versatiles_container::Traversal;
 	use versatiles_core::{TileBBoxPyramid, TileCompression, TileFormat};
 
 	fn create_test_parameters(
@@ -42,7 +41,15 @@ mod tests {
 		bbox_pyramid.set_level_min(min_zoom);
 		bbox_pyramid.set_level_max(max_zoom);
 
-		TileSourceMetadata::new(format, compression, bbox_pyramid, Traversal::ANY)
+		let mut metadata = TileSourceMetadata::new_builder()
+			.with_format(format)
+			.with_compression(TileCompression::Uncompressed)
+			.with_bbox_pyramid(bbox_pyramid)
+			.build()
+			.unwrap();
+		// The builder rejects compressed raster formats, so the compression is set afterwards.
+		metadata.tile_compression = compression;
+		metadata
 	}
 
 	#[test]
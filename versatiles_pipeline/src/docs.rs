@@ -0,0 +1,281 @@
+//! Structured documentation of pipeline operations.
+//!
+//! `#[derive(VPLDecode)]` generates a `get_docs()` function for the arguments of every operation,
+//! which returns an [`OperationDocs`] built from the doc comments of the struct and its fields.
+//! Formatting it with `{}` renders Markdown, as used by [`PipelineFactory::get_docs`](crate::PipelineFactory::get_docs).
+//!
+//! [`PipelineFactory::list_operations`](crate::PipelineFactory::list_operations) returns an
+//! [`OperationInfo`] for every registered operation, including a JSON schema of its arguments.
+
+use std::fmt::{Display, Formatter, Result};
+use versatiles_core::json::{JsonObject, JsonValue};
+
+/// Kind of a pipeline operation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum OperationKind {
+	/// Starts a pipeline by providing tiles, e.g. `from_container`.
+	ReadSource,
+	/// Modifies the tiles of its source, e.g. `filter`.
+	Transform,
+	/// Writes tiles. No sink operation is registered yet, pipelines are written by `versatiles convert`.
+	Sink,
+}
+
+impl OperationKind {
+	/// Returns a lowercase name, e.g. `read`.
+	#[must_use]
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			OperationKind::ReadSource => "read",
+			OperationKind::Transform => "transform",
+			OperationKind::Sink => "sink",
+		}
+	}
+}
+
+/// Origin of a registered pipeline operation.
+///
+/// If several operations are registered under the same tag name, the one with the highest
+/// priority is used: `User` beats `Plugin`, which beats `BuiltIn`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum OperationPriority {
+	/// Operations that ship with this crate.
+	BuiltIn,
+	/// Operations registered by libraries or extensions.
+	Plugin,
+	/// Operations registered by the application or the user.
+	User,
+}
+
+impl OperationPriority {
+	/// Returns a lowercase name, e.g. `builtin`.
+	#[must_use]
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			OperationPriority::BuiltIn => "builtin",
+			OperationPriority::Plugin => "plugin",
+			OperationPriority::User => "user",
+		}
+	}
+}
+
+/// Summary of a registered pipeline operation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OperationInfo {
+	/// VPL tag name, e.g. `from_container`.
+	pub tag_name: String,
+	/// Kind of the operation.
+	pub kind: OperationKind,
+	/// Where the operation was registered from.
+	pub priority: OperationPriority,
+	/// Documentation as Markdown.
+	pub docs: String,
+	/// JSON schema of the arguments, see [`OperationDocs::arg_schema`].
+	pub arg_schema: JsonValue,
+}
+
+/// Documentation of a single pipeline operation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OperationDocs {
+	/// What the operation does.
+	pub description: &'static str,
+	/// What the sources of the operation must provide, if it has sources.
+	pub sources: Option<&'static str>,
+	/// All properties of the operation, in the order of declaration.
+	pub properties: Vec<PropertyDocs>,
+}
+
+/// Documentation of a single property of a pipeline operation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PropertyDocs {
+	/// Name of the property, e.g. `level_max`.
+	pub name: &'static str,
+	/// Name of the expected type, e.g. `u8` or `[String]`.
+	pub type_name: &'static str,
+	/// Whether the property must be set.
+	pub required: bool,
+	/// Default value of an optional property, if it is a fixed value.
+	pub default: Option<&'static str>,
+	/// What the property does.
+	pub doc: &'static str,
+}
+
+impl OperationDocs {
+	/// Returns a JSON schema (`"type": "object"`) describing the properties of the operation.
+	#[must_use]
+	pub fn arg_schema(&self) -> JsonValue {
+		let mut properties = JsonObject::new();
+		for property in &self.properties {
+			properties.set(property.name, property.schema());
+		}
+		let required = self
+			.properties
+			.iter()
+			.filter(|p| p.required)
+			.map(|p| p.name)
+			.collect::<Vec<_>>();
+
+		let mut schema = JsonObject::new();
+		schema.set("type", "object");
+		schema.set("properties", properties);
+		schema.set("required", required);
+		schema.set("additionalProperties", false);
+		JsonValue::from(schema)
+	}
+}
+
+impl PropertyDocs {
+	/// Returns the JSON schema of this property.
+	fn schema(&self) -> JsonObject {
+		let (json_type, item_type) = match self.type_name {
+			"Boolean" | "bool" => ("boolean", None),
+			"u8" | "u16" | "u32" => ("integer", None),
+			"f32" => ("number", None),
+			"[f64,f64,f64,f64]" => ("array", Some("number")),
+			"[u8,u8,u8]" => ("array", Some("integer")),
+			"[String]" => ("array", Some("string")),
+			_ => ("string", None),
+		};
+
+		let mut schema = JsonObject::new();
+		schema.set("type", json_type);
+		if let Some(item_type) = item_type {
+			schema.set("items", JsonObject::from(vec![("type", item_type)]));
+		}
+		if !self.doc.is_empty() {
+			schema.set("description", self.doc);
+		}
+		if let Some(default) = self.default {
+			let value = match json_type {
+				"boolean" => default.parse::<bool>().map(JsonValue::from).ok(),
+				"integer" | "number" => default.parse::<f64>().map(JsonValue::from).ok(),
+				_ => None,
+			};
+			schema.set("default", value.unwrap_or_else(|| JsonValue::from(default)));
+		}
+		schema
+	}
+}
+
+impl Display for OperationDocs {
+	fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+		let mut parts = Vec::new();
+		if !self.description.is_empty() {
+			parts.push(self.description.to_string());
+		}
+		if let Some(sources) = self.sources {
+			parts.push(format!("### Sources:\n{sources}"));
+		}
+		if !self.properties.is_empty() {
+			let properties = self.properties.iter().map(ToString::to_string).collect::<Vec<_>>();
+			parts.push(format!("### Parameters:\n{}", properties.join("\n")));
+		}
+		write!(f, "{}", parts.join("\n").trim())
+	}
+}
+
+impl Display for PropertyDocs {
+	fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+		let (emphasis, requirement) = if self.required {
+			("**", "required")
+		} else {
+			("*", "optional")
+		};
+		let default = self.default.map(|d| format!(", default: `{d}`")).unwrap_or_default();
+		write!(
+			f,
+			"- {emphasis}`{}`: {} ({requirement}{default}){emphasis}",
+			self.name, self.type_name
+		)?;
+		if !self.doc.is_empty() {
+			write!(f, " - {}", self.doc)?;
+		}
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn property(name: &'static str, required: bool, default: Option<&'static str>) -> PropertyDocs {
+		PropertyDocs {
+			name,
+			type_name: "u8",
+			required,
+			default,
+			doc: "Zoom level.",
+		}
+	}
+
+	#[test]
+	fn property_markdown() {
+		assert_eq!(
+			property("level", true, None).to_string(),
+			"- **`level`: u8 (required)** - Zoom level."
+		);
+		assert_eq!(
+			property("level", false, None).to_string(),
+			"- *`level`: u8 (optional)* - Zoom level."
+		);
+		assert_eq!(
+			property("level", false, Some("30")).to_string(),
+			"- *`level`: u8 (optional, default: `30`)* - Zoom level."
+		);
+	}
+
+	#[test]
+	fn operation_markdown() {
+		let docs = OperationDocs {
+			description: "Merges sources.",
+			sources: Some("All sources must be vector tiles."),
+			properties: vec![property("level", false, None)],
+		};
+		assert_eq!(
+			docs.to_string(),
+			"Merges sources.\n### Sources:\nAll sources must be vector tiles.\n### Parameters:\n- *`level`: u8 (optional)* - Zoom level."
+		);
+
+		let docs = OperationDocs {
+			description: "Does nothing.",
+			sources: None,
+			properties: vec![],
+		};
+		assert_eq!(docs.to_string(), "Does nothing.");
+	}
+
+	#[test]
+	fn arg_schema() {
+		let docs = OperationDocs {
+			description: "Filters tiles.",
+			sources: None,
+			properties: vec![
+				PropertyDocs {
+					name: "bbox",
+					type_name: "[f64,f64,f64,f64]",
+					required: true,
+					default: None,
+					doc: "",
+				},
+				property("level", false, Some("30")),
+				PropertyDocs {
+					name: "strategy",
+					type_name: "String",
+					required: false,
+					default: Some("first_wins"),
+					doc: "",
+				},
+			],
+		};
+		assert_eq!(
+			docs.arg_schema().stringify(),
+			concat!(
+				r#"{"additionalProperties":false,"properties":{"#,
+				r#""bbox":{"items":{"type":"number"},"type":"array"},"#,
+				r#""level":{"default":30,"description":"Zoom level.","type":"integer"},"#,
+				r#""strategy":{"default":"first_wins","type":"string"}"#,
+				r#"},"required":["bbox"],"type":"object"}"#
+			)
+		);
+	}
+}
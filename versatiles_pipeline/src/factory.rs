@@ -9,22 +9,32 @@
 //! The factory can be instantiated empty (for custom registration) or with defaults that
 //! register all built-in read/transform operations. For testing and demos there is also
 //! a "dummy" mode that resolves filenames to synthetic vector/raster sources.
+//!
+//! Every operation is registered with an [`OperationPriority`]. Applications can replace a
+//! built-in operation by registering their own under the same tag name with
+//! [`PipelineFactory::register_override`].
+//!
+//! Other VPL files can be included as sources (see [`PipelineFactory::operation_from_vpl_file`]),
+//! so several pipelines can share a common base pipeline.
 
 use crate::{
+	OperationInfo, OperationKind, OperationPriority,
 	helpers::{dummy_image_source::DummyImageSource, dummy_vector_source::DummyVectorSource},
 	operations::{get_read_operation_factories, get_transform_operation_factories},
-	traits::{ReadOperationFactoryTrait, TransformOperationFactoryTrait},
-	vpl::{VPLNode, VPLPipeline, parse_vpl},
+	traits::{OperationFactory, OperationFactoryTrait, ReadOperationFactoryTrait, TransformOperationFactoryTrait},
+	vpl::{VPLNode, VPLPipeline, parse_vpl, parse_yaml},
 };
-use anyhow::{Result, anyhow, bail};
+use anyhow::{Result, anyhow, bail, ensure};
 use futures::future::BoxFuture;
 use itertools::Itertools;
 use std::{
 	collections::HashMap,
 	path::{Path, PathBuf},
+	rc::Rc,
+	sync::Arc,
 	vec,
 };
-use versatiles_container::{TileSource, TilesRuntime};
+use versatiles_container::{ContainerError, TileSource, TilesRuntime};
 use versatiles_core::{TileFormat, TileType};
 use versatiles_derive::context;
 
@@ -34,45 +44,47 @@ use versatiles_derive::context;
 /// It receives the resolved path (relative to `dir`) and returns a boxed reader.
 type Callback = Box<dyn Fn(String) -> BoxFuture<'static, Result<Box<dyn TileSource>>>>;
 
+/// An operation factory together with the priority it was registered with.
+struct Registered<F: ?Sized> {
+	priority: OperationPriority,
+	factory: Box<F>,
+}
+
 /// Builder that registers read/transform operation factories and produces an operation graph.
 ///
 /// `PipelineFactory` maintains:
-/// - `read_ops` and `tran_ops`: registries keyed by VPL tag name.
+/// - `read_ops` and `tran_ops`: registries keyed by VPL tag name, holding one factory per tag.
 /// - `dir`: base directory used to resolve relative filenames.
 /// - `create_reader`: callback to open external containers as [`TileSource`].
 /// - `runtime`: runtime configuration forwarded to operations.
+/// - `include_stack`: canonical paths of the VPL files currently being built, outermost first.
 pub struct PipelineFactory {
-	read_ops: HashMap<String, Box<dyn ReadOperationFactoryTrait>>,
-	tran_ops: HashMap<String, Box<dyn TransformOperationFactoryTrait>>,
+	read_ops: Arc<HashMap<String, Registered<dyn ReadOperationFactoryTrait>>>,
+	tran_ops: Arc<HashMap<String, Registered<dyn TransformOperationFactoryTrait>>>,
 	dir: PathBuf,
-	create_reader: Callback,
+	create_reader: Rc<Callback>,
 	runtime: TilesRuntime,
+	include_stack: Vec<PathBuf>,
 }
 
 impl PipelineFactory {
 	/// Creates an empty factory with no registered operations.
 	pub fn new_empty(dir: &Path, create_reader: Callback, runtime: TilesRuntime) -> Self {
 		PipelineFactory {
-			read_ops: HashMap::new(),
-			tran_ops: HashMap::new(),
+			read_ops: Arc::new(HashMap::new()),
+			tran_ops: Arc::new(HashMap::new()),
 			dir: dir.to_path_buf(),
-			create_reader,
+			create_reader: Rc::new(create_reader),
 			runtime,
+			include_stack: Vec::new(),
 		}
 	}
 
 	/// Creates a factory pre-loaded with all built-in read and transform operation factories.
 	pub fn new_default(dir: &Path, create_reader: Callback, runtime: TilesRuntime) -> Self {
 		let mut factory = PipelineFactory::new_empty(dir, create_reader, runtime);
-
-		for f in get_read_operation_factories() {
-			factory.add_read_factory(f)
-		}
-
-		for f in get_transform_operation_factories() {
-			factory.add_tran_factory(f)
-		}
-
+		factory.read_ops = Arc::new(built_in(get_read_operation_factories()));
+		factory.tran_ops = Arc::new(built_in(get_transform_operation_factories()));
 		factory
 	}
 
@@ -119,14 +131,31 @@ impl PipelineFactory {
 		PipelineFactory::new_default(Path::new(""), create_reader, runtime)
 	}
 
-	/// Registers a read operation factory under its VPL tag name.
-	fn add_read_factory(&mut self, factory: Box<dyn ReadOperationFactoryTrait>) {
-		self.read_ops.insert(factory.get_tag_name().to_string(), factory);
+	/// Registers an operation factory under the VPL tag name `tag`.
+	///
+	/// If an operation of the same kind is already registered under `tag`, the one with the
+	/// higher priority wins; on equal priority the new one replaces the old one. Replacing or
+	/// ignoring an operation is logged as a warning. Returns `true` if `factory` was registered.
+	///
+	/// # Errors
+	///
+	/// Returns an error if `tag` differs from the tag name of `factory`, or if the factory is
+	/// currently shared with an included VPL file.
+	pub fn register_override(
+		&mut self,
+		tag: &str,
+		factory: OperationFactory,
+		priority: OperationPriority,
+	) -> Result<bool> {
+		match factory {
+			OperationFactory::Read(factory) => register(&mut self.read_ops, tag, factory, priority),
+			OperationFactory::Transform(factory) => register(&mut self.tran_ops, tag, factory, priority),
+		}
 	}
 
-	/// Registers a transform operation factory under its VPL tag name.
-	fn add_tran_factory(&mut self, factory: Box<dyn TransformOperationFactoryTrait>) {
-		self.tran_ops.insert(factory.get_tag_name().to_string(), factory);
+	/// Marks `path` as the VPL file this factory builds, so that including it again is reported as a cycle.
+	pub fn set_origin(&mut self, path: &Path) {
+		self.include_stack = vec![std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())];
 	}
 
 	/// Resolves `filename` relative to `dir` and invokes `create_reader` to open a container.
@@ -138,7 +167,55 @@ impl PipelineFactory {
 	/// Parses VPL text and builds the corresponding operation graph.
 	#[context("Failed to create reader from VPL")]
 	pub async fn operation_from_vpl(&self, text: &str) -> Result<Box<dyn TileSource>> {
-		self.build_pipeline(parse_vpl(text)?).await
+		let pipeline = parse_vpl(text).map_err(|err| ContainerError::Parse {
+			what: String::from("VPL"),
+			context: format!("{err:#}"),
+		})?;
+		self.build_pipeline(pipeline).await
+	}
+
+	/// Reads the VPL file at `path` and builds its pipeline, e.g. to include a shared base pipeline.
+	///
+	/// Relative filenames inside the file are resolved against the file's directory.
+	/// Returns an error if the file is already being built, i.e. if the includes form a cycle.
+	#[context("Failed to include VPL file '{}'", path.display())]
+	pub async fn operation_from_vpl_file(&self, path: &Path) -> Result<Box<dyn TileSource>> {
+		let path = std::fs::canonicalize(path)?;
+		if self.include_stack.contains(&path) {
+			let chain = self
+				.include_stack
+				.iter()
+				.chain([&path])
+				.map(|p| p.display().to_string())
+				.join(" -> ");
+			bail!("VPL include cycle: {chain}");
+		}
+		let text = std::fs::read_to_string(&path)?;
+
+		let mut include_stack = self.include_stack.clone();
+		include_stack.push(path.clone());
+		let factory = PipelineFactory {
+			read_ops: Arc::clone(&self.read_ops),
+			tran_ops: Arc::clone(&self.tran_ops),
+			dir: path.parent().unwrap_or(self.dir.as_path()).to_path_buf(),
+			create_reader: Rc::clone(&self.create_reader),
+			runtime: self.runtime.clone(),
+			include_stack,
+		};
+		factory.operation_from_vpl(&text).await
+	}
+
+	/// Parses a YAML pipeline and builds the corresponding operation graph.
+	///
+	/// Sequences are pipelines and mappings are nodes with `name`, `properties` and `sources`,
+	/// see [`parse_yaml`].
+	#[context("Failed to create reader from YAML")]
+	pub async fn operation_from_yaml(&self, yaml: &str) -> Result<Box<dyn TileSource>> {
+		let pipeline = parse_yaml(yaml).map_err(|err| ContainerError::Parse {
+			what: String::from("YAML"),
+			context: format!("{err:#}"),
+		})?;
+		self.build_pipeline(pipeline).await
 	}
 
 	/// Builds an executable operation graph from a parsed `VPLPipeline`.
@@ -160,23 +237,23 @@ impl PipelineFactory {
 	/// Instantiates a read operation from a VPL node using the registered factory.
 	#[context("Failed to create read operation from VPL node")]
 	async fn read_operation_from_node(&self, node: VPLNode) -> Result<Box<dyn TileSource>> {
-		let factory = self
+		let registered = self
 			.read_ops
 			.get(&node.name)
-			.ok_or_else(|| anyhow!("read operation '{}' unknown", node.name))?;
+			.ok_or_else(|| ContainerError::UnsupportedFormat(format!("read operation '{}' unknown", node.name)))?;
 
-		factory.build(node, self).await
+		registered.factory.build(node, self).await
 	}
 
 	/// Instantiates a transform operation from a VPL node using the registered factory.
 	#[context("Failed to create transform operation from VPL node")]
 	async fn tran_operation_from_node(&self, node: VPLNode, source: Box<dyn TileSource>) -> Result<Box<dyn TileSource>> {
-		let factory = self
+		let registered = self
 			.tran_ops
 			.get(&node.name)
-			.ok_or_else(|| anyhow!("transform operation '{}' unknown", node.name))?;
+			.ok_or_else(|| ContainerError::UnsupportedFormat(format!("transform operation '{}' unknown", node.name)))?;
 
-		factory.build(node, source, self).await
+		registered.factory.build(node, source, self).await
 	}
 
 	/// Returns the absolute/normalized string path for a VPL-referenced `filename`.
@@ -189,33 +266,417 @@ impl PipelineFactory {
 		self.dir.join(filename)
 	}
 
-	/// Returns rendered Markdown help listing all registered operations and their docs.
-	pub fn help_md(&self) -> String {
+	/// Returns Markdown documentation of all registered read and transform operations.
+	pub fn get_docs(&self) -> String {
 		[
-			include_str!("help.md").to_string(),
 			String::from("---\n# READ operations"),
 			self
 				.read_ops
-				.values()
-				.sorted_by_key(|f| f.get_tag_name())
-				.map(|f| format!("\n## {}\n{}\n", f.get_tag_name(), f.get_docs()))
+				.iter()
+				.sorted_by_key(|(tag, _)| *tag)
+				.map(|(tag, r)| format!("\n## {tag}\n{}\n", r.factory.get_docs()))
 				.join(""),
 			String::from("---\n# TRANSFORM operations"),
 			self
 				.tran_ops
-				.values()
-				.sorted_by_key(|f| f.get_tag_name())
-				.map(|f| format!("\n## {}\n{}\n", f.get_tag_name(), f.get_docs()))
+				.iter()
+				.sorted_by_key(|(tag, _)| *tag)
+				.map(|(tag, r)| format!("\n## {tag}\n{}\n", r.factory.get_docs()))
 				.join(""),
 		]
 		.join("\n")
 	}
 
+	/// Returns one entry per registered operation: read operations first, each kind sorted by tag name.
+	///
+	/// Operations that were overridden are not listed; `priority` tells where the listed one comes from.
+	pub fn list_operations(&self) -> Vec<OperationInfo> {
+		let read_ops = self
+			.read_ops
+			.iter()
+			.map(|(tag, r)| (OperationKind::ReadSource, tag, r.priority, r.factory.get_docs()));
+		let tran_ops = self
+			.tran_ops
+			.iter()
+			.map(|(tag, r)| (OperationKind::Transform, tag, r.priority, r.factory.get_docs()));
+		read_ops
+			.chain(tran_ops)
+			.map(|(kind, tag_name, priority, docs)| OperationInfo {
+				tag_name: tag_name.clone(),
+				kind,
+				priority,
+				docs: docs.to_string(),
+				arg_schema: docs.arg_schema(),
+			})
+			.sorted_by(|a, b| (a.kind, &a.tag_name).cmp(&(b.kind, &b.tag_name)))
+			.collect()
+	}
+
+	/// Returns rendered Markdown help: the VPL introduction followed by [`get_docs`](Self::get_docs).
+	pub fn help_md(&self) -> String {
+		[include_str!("help.md").to_string(), self.get_docs()].join("\n")
+	}
+
 	/// Returns the runtime associated with this factory.
 	pub fn runtime(&self) -> TilesRuntime {
 		self.runtime.clone()
 	}
 }
 
+/// Collects built-in operation factories by their tag names.
+fn built_in<F: OperationFactoryTrait + ?Sized>(factories: Vec<Box<F>>) -> HashMap<String, Registered<F>> {
+	factories
+		.into_iter()
+		.map(|factory| {
+			let registered = Registered {
+				priority: OperationPriority::BuiltIn,
+				factory,
+			};
+			(registered.factory.get_tag_name().to_string(), registered)
+		})
+		.collect()
+}
+
+/// Inserts `factory` into `ops` unless an operation with a higher priority is registered under `tag`.
+fn register<F: OperationFactoryTrait + ?Sized>(
+	ops: &mut Arc<HashMap<String, Registered<F>>>,
+	tag: &str,
+	factory: Box<F>,
+	priority: OperationPriority,
+) -> Result<bool> {
+	ensure!(
+		factory.get_tag_name() == tag,
+		"can't register operation '{}' under the tag name '{tag}'",
+		factory.get_tag_name()
+	);
+	let Some(ops) = Arc::get_mut(ops) else {
+		bail!("can't register operation '{tag}' while the operations are shared with an included VPL file");
+	};
+	if let Some(existing) = ops.get(tag) {
+		if existing.priority > priority {
+			log::warn!(
+				"ignoring {} operation '{tag}', because a {} operation with that name is registered",
+				priority.as_str(),
+				existing.priority.as_str()
+			);
+			return Ok(false);
+		}
+		log::warn!(
+			"{} operation '{tag}' is overridden by a {} operation",
+			existing.priority.as_str(),
+			priority.as_str()
+		);
+	}
+	ops.insert(tag.to_string(), Registered { priority, factory });
+	Ok(true)
+}
+
 unsafe impl Sync for PipelineFactory {}
 unsafe impl Send for PipelineFactory {}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::OperationDocs;
+	use assert_fs::TempDir;
+	use versatiles_core::{Blob, TileBBox, TileBBoxPyramid, TileCompression, TileCoord};
+
+	async fn get_error(vpl: &str) -> anyhow::Error {
+		PipelineFactory::new_dummy().operation_from_vpl(vpl).await.unwrap_err()
+	}
+
+	#[tokio::test]
+	async fn invalid_vpl_is_a_parse_error() {
+		let err = get_error("from_container filename=\"unterminated").await;
+		assert!(matches!(
+			ContainerError::find(&err),
+			Some(ContainerError::Parse { what, .. }) if what == "VPL"
+		));
+	}
+
+	#[tokio::test]
+	async fn unknown_operations_are_unsupported() {
+		let err = get_error("from_nowhere").await;
+		assert!(matches!(
+			ContainerError::find(&err),
+			Some(ContainerError::UnsupportedFormat(detail)) if detail == "read operation 'from_nowhere' unknown"
+		));
+
+		let err = get_error("from_container filename=dummy.png | do_magic").await;
+		assert!(matches!(
+			ContainerError::find(&err),
+			Some(ContainerError::UnsupportedFormat(detail)) if detail == "transform operation 'do_magic' unknown"
+		));
+	}
+
+	#[test]
+	fn all_operations_are_documented() {
+		let factory = PipelineFactory::new_dummy();
+		let read_docs = factory
+			.read_ops
+			.values()
+			.map(|r| (r.factory.get_tag_name(), r.factory.get_docs()));
+		let tran_docs = factory
+			.tran_ops
+			.values()
+			.map(|r| (r.factory.get_tag_name(), r.factory.get_docs()));
+		for (name, docs) in read_docs.chain(tran_docs) {
+			assert!(!docs.description.is_empty(), "operation '{name}' has no description");
+			assert!(
+				docs.sources.is_none_or(|s| !s.is_empty()),
+				"sources of '{name}' are not documented"
+			);
+			for property in &docs.properties {
+				assert!(
+					!property.doc.is_empty(),
+					"property '{}' of '{name}' is not documented",
+					property.name
+				);
+				assert!(property.default.is_none() || !property.required);
+			}
+		}
+
+		let docs = factory.get_docs();
+		for name in factory.read_ops.keys().chain(factory.tran_ops.keys()) {
+			assert!(docs.contains(&format!("\n## {name}\n")), "'{name}' is missing");
+		}
+		assert!(docs.contains("- *`level_max`: u8 (optional, default: `30`)* - use this as maximum zoom level."));
+		assert!(factory.help_md().ends_with(&docs));
+	}
+
+	#[test]
+	fn list_operations() {
+		let factory = PipelineFactory::new_dummy();
+		let operations = factory.list_operations();
+		assert_eq!(operations.len(), factory.read_ops.len() + factory.tran_ops.len());
+
+		let names = operations.iter().map(|o| o.tag_name.as_str()).collect::<Vec<_>>();
+		assert_eq!(
+			names.iter().unique().count(),
+			names.len(),
+			"duplicate tag names: {names:?}"
+		);
+		for name in factory.read_ops.keys().chain(factory.tran_ops.keys()) {
+			assert!(names.contains(&name.as_str()), "'{name}' is missing");
+		}
+
+		let filter = operations.iter().find(|o| o.tag_name == "filter").unwrap();
+		assert_eq!(filter.kind, OperationKind::Transform);
+		assert_eq!(filter.priority, OperationPriority::BuiltIn);
+		assert!(
+			filter
+				.docs
+				.starts_with("Filter tiles by bounding box and/or zoom levels.")
+		);
+		assert!(
+			filter
+				.arg_schema
+				.stringify()
+				.contains(r#""level_max":{"description":"maximal zoom level","type":"integer"}"#)
+		);
+		let from_container = operations.iter().find(|o| o.tag_name == "from_container").unwrap();
+		assert_eq!(from_container.kind, OperationKind::ReadSource);
+		assert!(
+			from_container
+				.arg_schema
+				.stringify()
+				.contains(r#""required":["filename"]"#)
+		);
+	}
+
+	/// A `from_container` replacement that ignores its arguments and returns a small dummy source.
+	struct UserReadFactory;
+
+	impl OperationFactoryTrait for UserReadFactory {
+		fn get_tag_name(&self) -> &str {
+			"from_container"
+		}
+		fn get_docs(&self) -> OperationDocs {
+			OperationDocs {
+				description: "Reads tiles the user's way.",
+				sources: None,
+				properties: Vec::new(),
+			}
+		}
+	}
+
+	#[async_trait::async_trait]
+	impl ReadOperationFactoryTrait for UserReadFactory {
+		async fn build<'a>(&self, _vpl_node: VPLNode, _factory: &'a PipelineFactory) -> Result<Box<dyn TileSource>> {
+			Ok(Box::new(DummyVectorSource::new(
+				&[],
+				Some(TileBBoxPyramid::new_full(2)),
+			)))
+		}
+	}
+
+	#[tokio::test]
+	async fn user_operation_shadows_builtin() -> Result<()> {
+		let vpl = "from_container filename=dummy.pbf";
+		let mut factory = PipelineFactory::new_dummy();
+		let builtin = factory.operation_from_vpl(vpl).await?;
+		assert_ne!(builtin.metadata().bbox_pyramid, TileBBoxPyramid::new_full(2));
+
+		let user = || OperationFactory::Read(Box::new(UserReadFactory));
+		assert!(factory.register_override("from_container", user(), OperationPriority::User)?);
+		let shadowed = factory.operation_from_vpl(vpl).await?;
+		assert_eq!(shadowed.metadata().bbox_pyramid, TileBBoxPyramid::new_full(2));
+
+		// a lower priority can't replace the user's operation
+		let builtin_factory = get_read_operation_factories()
+			.into_iter()
+			.find(|f| f.get_tag_name() == "from_container")
+			.unwrap();
+		assert!(!factory.register_override(
+			"from_container",
+			OperationFactory::Read(builtin_factory),
+			OperationPriority::Plugin
+		)?);
+		let operation = factory.operation_from_vpl(vpl).await?;
+		assert_eq!(operation.metadata().bbox_pyramid, TileBBoxPyramid::new_full(2));
+
+		let operations = factory.list_operations();
+		let info = operations.iter().find(|o| o.tag_name == "from_container").unwrap();
+		assert_eq!(info.priority, OperationPriority::User);
+		assert_eq!(info.docs.lines().next(), Some("Reads tiles the user's way."));
+		assert!(
+			operations
+				.iter()
+				.filter(|o| o.tag_name != "from_container")
+				.all(|o| o.priority == OperationPriority::BuiltIn)
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn register_override_rejects_invalid_registrations() {
+		let mut factory = PipelineFactory::new_dummy();
+		let user = || OperationFactory::Read(Box::new(UserReadFactory));
+
+		let err = factory
+			.register_override("from_tiles", user(), OperationPriority::User)
+			.unwrap_err();
+		assert_eq!(
+			err.to_string(),
+			"can't register operation 'from_container' under the tag name 'from_tiles'"
+		);
+
+		let shared = Arc::clone(&factory.read_ops);
+		let err = factory
+			.register_override("from_container", user(), OperationPriority::User)
+			.unwrap_err();
+		assert!(err.to_string().contains("shared with an included VPL file"), "{err}");
+		drop(shared);
+
+		assert!(
+			factory
+				.register_override("from_container", user(), OperationPriority::User)
+				.unwrap()
+		);
+	}
+
+	#[tokio::test]
+	async fn operation_from_yaml() {
+		let factory = PipelineFactory::new_dummy();
+		let yaml = "- name: from_container\n  properties:\n    filename: dummy.pbf\n";
+		let from_yaml = factory.operation_from_yaml(yaml).await.unwrap();
+		let from_vpl = factory
+			.operation_from_vpl("from_container filename=dummy.pbf")
+			.await
+			.unwrap();
+		assert_eq!(from_yaml.metadata(), from_vpl.metadata());
+
+		let err = factory.operation_from_yaml("- name: [").await.unwrap_err();
+		assert!(matches!(
+			ContainerError::find(&err),
+			Some(ContainerError::Parse { what, .. }) if what == "YAML"
+		));
+	}
+
+	/// Returns a dummy factory resolving filenames against `dir`.
+	fn dummy_factory_in(dir: &Path) -> PipelineFactory {
+		let mut factory = PipelineFactory::new_dummy();
+		factory.dir = dir.to_path_buf();
+		factory
+	}
+
+	async fn get_tiles(operation: &dyn TileSource) -> Result<HashMap<TileCoord, Blob>> {
+		let mut tiles = HashMap::new();
+		for (coord, tile) in operation
+			.get_tile_stream(TileBBox::new_full(3)?)
+			.await?
+			.to_vec()
+			.await
+			.unwrap()
+		{
+			tiles.insert(coord, tile.into_blob(TileCompression::Uncompressed)?);
+		}
+		Ok(tiles)
+	}
+
+	#[tokio::test]
+	async fn includes_vpl_files() -> Result<()> {
+		let temp_dir = TempDir::new()?;
+		let dir = temp_dir.path().canonicalize()?;
+		std::fs::create_dir(dir.join("base"))?;
+		std::fs::write(
+			dir.join("base/base.vpl"),
+			"from_container filename=water.pbf | filter level_max=4",
+		)?;
+		std::fs::write(
+			dir.join("base/mid.vpl"),
+			"from_container filename=base.vpl | filter level_min=2",
+		)?;
+
+		let included = dummy_factory_in(&dir)
+			.operation_from_vpl("from_container filename=\"base/mid.vpl\" | filter bbox=[0,0,40,20]")
+			.await?;
+		let flattened = dummy_factory_in(&dir.join("base"))
+			.operation_from_vpl(
+				"from_container filename=water.pbf | filter level_max=4 | filter level_min=2 | filter bbox=[0,0,40,20]",
+			)
+			.await?;
+
+		assert_eq!(included.metadata(), flattened.metadata());
+		let tiles = get_tiles(included.as_ref()).await?;
+		assert!(!tiles.is_empty());
+		assert_eq!(tiles, get_tiles(flattened.as_ref()).await?);
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn include_cycle_is_an_error() -> Result<()> {
+		let temp_dir = TempDir::new()?;
+		let dir = temp_dir.path().canonicalize()?;
+		std::fs::create_dir(dir.join("sub"))?;
+		std::fs::write(dir.join("a.vpl"), "from_container filename=\"sub/b.vpl\"")?;
+		std::fs::write(dir.join("sub/b.vpl"), "from_container filename=\"../a.vpl\"")?;
+
+		let a = dir.join("a.vpl");
+		let b = dir.join("sub/b.vpl");
+		let err = dummy_factory_in(&dir).operation_from_vpl_file(&a).await.unwrap_err();
+		let messages = err.chain().map(ToString::to_string).collect::<Vec<_>>();
+		assert_eq!(
+			messages.last().unwrap(),
+			&format!(
+				"VPL include cycle: {} -> {} -> {}",
+				a.display(),
+				b.display(),
+				a.display()
+			)
+		);
+		let includes = messages
+			.iter()
+			.filter(|m| m.starts_with("Failed to include VPL file"))
+			.collect::<Vec<_>>();
+		assert_eq!(
+			includes,
+			[
+				&format!("Failed to include VPL file '{}'", a.display()),
+				&format!("Failed to include VPL file '{}'", b.display()),
+				&format!("Failed to include VPL file '{}'", dir.join("sub/../a.vpl").display()),
+			]
+		);
+		Ok(())
+	}
+}
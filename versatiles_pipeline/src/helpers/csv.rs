@@ -8,6 +8,9 @@
 //! - **Auto-detection**: Automatically uses tab separator for `.tsv` files
 //! - **Configurable field separator**: Support for comma (`,`), semicolon (`;`), tab (`\t`), or any character
 //! - **Configurable decimal separator**: Support for dot (`.`) or comma (`,`) in numeric values
+//! - **Configurable quote character**: Fields may be quoted with `"` (default) or any other character
+//! - **Type inference**: Values become numbers and booleans, or optionally stay strings
+//! - **Header normalization**: Optionally trims and lowercases column names
 //! - **Progress reporting**: Integrates with the tiles runtime for progress feedback
 //!
 //! # Examples
@@ -39,7 +42,7 @@
 use anyhow::{Result, bail};
 use std::{io::BufReader, path::Path};
 use versatiles_container::TilesRuntime;
-use versatiles_core::utils::read_csv_iter;
+use versatiles_core::utils::read_csv_iter_with_quote;
 use versatiles_derive::context;
 use versatiles_geometry::geo::*;
 
@@ -79,6 +82,16 @@ pub struct CsvReader {
 	/// - `Some(',')`: Uses `,` as decimal separator (common in German/French locales)
 	pub decimal_separator: Option<char>,
 
+	/// If `true`, column names are normalized with [`normalize_key`]. Defaults to `false`.
+	pub normalize_header: bool,
+
+	/// Quote byte for fields containing separators or line breaks. Defaults to `b'"'`.
+	pub quote_char: u8,
+
+	/// If `true` (default), values are parsed as numbers or booleans where possible.
+	/// If `false`, all values are kept as strings.
+	pub infer_types: bool,
+
 	runtime: TilesRuntime,
 	path: std::path::PathBuf,
 }
@@ -114,6 +127,9 @@ impl CsvReader {
 		Self {
 			field_separator,
 			decimal_separator: None,
+			normalize_header: false,
+			quote_char: b'"',
+			infer_types: true,
 			runtime,
 			path: path.to_path_buf(),
 		}
@@ -162,8 +178,59 @@ impl CsvReader {
 		self
 	}
 
+	/// Trims and lowercases the column names, so `" City_ID "` becomes `"city_id"`.
+	///
+	/// # Example
+	///
+	/// ```ignore
+	/// let reader = CsvReader::new(path, runtime)
+	///     .with_normalized_header(true);
+	/// ```
+	#[must_use]
+	pub fn with_normalized_header(mut self, normalize: bool) -> Self {
+		self.normalize_header = normalize;
+		self
+	}
+
+	/// Sets a custom quote character, e.g. `'` for files like `'Doe; Jane';42`.
+	///
+	/// Inside a quoted field, the quote character is escaped by doubling it.
+	///
+	/// # Example
+	///
+	/// ```ignore
+	/// let reader = CsvReader::new(path, runtime)
+	///     .with_quote_char('\'');
+	/// ```
+	#[must_use]
+	pub fn with_quote_char(mut self, quote: char) -> Self {
+		self.quote_char = quote as u8;
+		self
+	}
+
+	/// Enables or disables type inference for values.
+	///
+	/// With inference (default), `"5"` becomes a number and `"true"` a boolean. Without it,
+	/// every value stays a string, e.g. to keep leading zeros of postal codes like `"01067"`.
+	/// The difference matters for style expressions, which treat `"5"` and `5` differently.
+	///
+	/// # Example
+	///
+	/// ```ignore
+	/// let reader = CsvReader::new(path, runtime)
+	///     .with_type_inference(false);
+	/// ```
+	#[must_use]
+	pub fn with_type_inference(mut self, infer: bool) -> Self {
+		self.infer_types = infer;
+		self
+	}
+
 	/// Converts a string value to a [`GeoValue`], applying decimal separator conversion if needed.
 	fn convert_value(&self, value: &str) -> GeoValue {
+		if !self.infer_types {
+			return GeoValue::from(value);
+		}
 		if let Some(decimal_sep) = self.decimal_separator {
 			// Replace decimal separator with '.' for parsing
 			let converted = value.replace(decimal_sep, ".");
@@ -175,9 +242,10 @@ impl CsvReader {
 	/// Reads the CSV/TSV file and returns all rows as [`GeoProperties`].
 	///
 	/// The first row is treated as the header and defines the property names.
+	/// A leading UTF-8 byte order mark is removed from the first column name.
 	/// Each subsequent row becomes a [`GeoProperties`] map where keys are
-	/// column names and values are automatically parsed as numbers, booleans,
-	/// or strings.
+	/// column names and values are parsed as numbers, booleans, or strings
+	/// (see [`CsvReader::with_type_inference`]).
 	///
 	/// # Returns
 	///
@@ -214,8 +282,16 @@ impl CsvReader {
 		let reader = BufReader::new(file);
 
 		let mut errors = vec![];
-		let mut iter = read_csv_iter(reader, self.field_separator)?;
-		let header: Vec<String> = iter.next().unwrap()?.0;
+		let mut iter = read_csv_iter_with_quote(reader, self.field_separator, self.quote_char)?;
+		let mut header: Vec<String> = iter.next().unwrap()?.0;
+		if let Some(first) = header.first_mut()
+			&& let Some(name) = first.strip_prefix('\u{feff}')
+		{
+			*first = name.to_string();
+		}
+		if self.normalize_header {
+			header = header.iter().map(|name| normalize_key(name)).collect();
+		}
 		let data: Vec<GeoProperties> = iter
 			.filter_map(|e| {
 				e.map(|(fields, _line_pos, byte_pos)| {
@@ -244,6 +320,12 @@ impl CsvReader {
 	}
 }
 
+/// Normalizes a column or field name for matching: trims whitespace and lowercases it.
+#[must_use]
+pub fn normalize_key(key: &str) -> String {
+	key.trim().to_lowercase()
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -292,6 +374,23 @@ mod tests {
 		Ok(())
 	}
 
+	#[tokio::test]
+	async fn test_read_csv_file_with_bom_and_padded_header() -> Result<()> {
+		let file_path = make_temp_csv("\u{feff}City_ID , Name\n1,Berlin")?;
+
+		let data = CsvReader::new(file_path.path(), runtime()).read().await?;
+		assert_eq!(data[0].get("City_ID ").unwrap(), &GeoValue::from(1));
+		assert_eq!(data[0].get(" Name").unwrap(), &GeoValue::from("Berlin"));
+
+		let data = CsvReader::new(file_path.path(), runtime())
+			.with_normalized_header(true)
+			.read()
+			.await?;
+		assert_eq!(data[0].get("city_id").unwrap(), &GeoValue::from(1));
+		assert_eq!(data[0].get("name").unwrap(), &GeoValue::from("Berlin"));
+		Ok(())
+	}
+
 	#[tokio::test]
 	async fn test_read_empty_csv_file() -> Result<()> {
 		let file_path = make_temp_csv("name,age,city")?;
@@ -398,6 +497,41 @@ mod tests {
 		Ok(())
 	}
 
+	// ───────────────────────── Quote and Type Tests ─────────────────────────
+
+	#[tokio::test]
+	async fn test_read_csv_custom_quote_char() -> Result<()> {
+		let file_path = make_temp_csv("name;note\n'Doe; Jane';'it''s \"quoted\"'")?;
+		let data = CsvReader::new(file_path.path(), runtime())
+			.with_field_separator(';')
+			.with_quote_char('\'')
+			.read()
+			.await?;
+
+		assert_eq!(data.len(), 1);
+		assert_eq!(data[0].get("name").unwrap(), &GeoValue::from("Doe; Jane"));
+		assert_eq!(data[0].get("note").unwrap(), &GeoValue::from("it's \"quoted\""));
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_read_csv_without_type_inference() -> Result<()> {
+		let file_path = make_temp_csv("zip;count;open\n01067;5;true")?;
+		let reader = CsvReader::new(file_path.path(), runtime()).with_field_separator(';');
+
+		let inferred = reader.clone().read().await?;
+		assert_eq!(inferred[0].get("count").unwrap(), &GeoValue::from(5));
+		assert_eq!(inferred[0].get("open").unwrap(), &GeoValue::Bool(true));
+
+		let strings = reader.with_type_inference(false).read().await?;
+		assert_eq!(strings[0].get("zip").unwrap(), &GeoValue::from("01067"));
+		assert_eq!(strings[0].get("count").unwrap(), &GeoValue::from("5"));
+		assert_eq!(strings[0].get("open").unwrap(), &GeoValue::from("true"));
+
+		Ok(())
+	}
+
 	// ───────────────────────── Decimal Separator Tests ─────────────────────────
 
 	#[tokio::test]
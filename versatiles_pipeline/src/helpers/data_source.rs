@@ -0,0 +1,410 @@
+//! Loaders for tabular data files that are joined with vector tile features.
+//!
+//! Every supported format implements [`DataSource`]. [`new_data_source`] picks the
+//! implementation by file extension (or by an explicit format name), so operations like
+//! `vector_update_properties` only depend on the trait.
+//!
+//! | Format    | Extensions                 | Layout                                   |
+//! |-----------|----------------------------|------------------------------------------|
+//! | `csv`     | `.csv`, anything unknown   | header row, one row per record           |
+//! | `tsv`     | `.tsv`                     | like `csv`, separated by tabs            |
+//! | `json`    | `.json`                    | an array of flat objects                 |
+//! | `ndjson`  | `.ndjson`, `.jsonl`        | one flat object per line                 |
+//! | `parquet` | `.parquet`                 | one row per record, `parquet` feature    |
+
+use super::{CsvReader, normalize_key};
+use anyhow::{Result, bail};
+use async_trait::async_trait;
+use std::path::Path;
+use versatiles_container::TilesRuntime;
+use versatiles_core::json::{JsonObject, JsonValue};
+use versatiles_derive::context;
+use versatiles_geometry::geo::{GeoProperties, GeoValue};
+
+/// A format of tabular data that can be loaded as a list of [`GeoProperties`], one per record.
+#[async_trait]
+pub trait DataSource: Send + Sync {
+	/// Loads all records of the file at `path`.
+	async fn load(&self, path: &Path) -> Result<Vec<GeoProperties>>;
+}
+
+/// Options for loading data files. The separators, the quote character and type inference
+/// only apply to CSV/TSV files, since JSON values are already typed.
+#[derive(Clone, Debug)]
+pub struct DataSourceOptions {
+	pub field_separator: Option<char>,
+	pub decimal_separator: Option<char>,
+	pub quote_char: Option<char>,
+	pub infer_types: bool,
+	pub normalize_keys: bool,
+}
+
+impl Default for DataSourceOptions {
+	fn default() -> Self {
+		Self {
+			field_separator: None,
+			decimal_separator: None,
+			quote_char: None,
+			infer_types: true,
+			normalize_keys: false,
+		}
+	}
+}
+
+/// Returns the [`DataSource`] for `path`.
+///
+/// `format` (`csv`, `tsv`, `json`, `ndjson` or `parquet`) overrides the detection by file extension.
+/// Files with an unknown extension are read as CSV.
+#[context("Failed to find a data loader for {path:?}")]
+pub fn new_data_source(
+	path: &Path,
+	format: Option<&str>,
+	options: DataSourceOptions,
+	runtime: TilesRuntime,
+) -> Result<Box<dyn DataSource>> {
+	let (format, is_explicit) = match format {
+		Some(format) => (format.trim().to_lowercase(), true),
+		None => (
+			path
+				.extension()
+				.map(|e| e.to_string_lossy().to_lowercase())
+				.unwrap_or_default(),
+			false,
+		),
+	};
+
+	Ok(match format.as_str() {
+		"csv" => Box::new(CsvDataSource { options, runtime }),
+		"tsv" => Box::new(CsvDataSource {
+			options: DataSourceOptions {
+				field_separator: options.field_separator.or(Some('\t')),
+				..options
+			},
+			runtime,
+		}),
+		"json" => Box::new(JsonDataSource { options }),
+		"ndjson" | "jsonl" => Box::new(NdjsonDataSource { options }),
+		#[cfg(feature = "parquet")]
+		"parquet" => Box::new(ParquetDataSource { options }),
+		#[cfg(not(feature = "parquet"))]
+		"parquet" => bail!("Parquet files are only supported if versatiles is built with the `parquet` feature"),
+		_ if !is_explicit => Box::new(CsvDataSource { options, runtime }),
+		_ => bail!("unknown data format '{format}', expected 'csv', 'tsv', 'json', 'ndjson' or 'parquet'"),
+	})
+}
+
+/// Loads the data file at `path` with the [`DataSource`] returned by [`new_data_source`].
+pub async fn read_data_source(
+	path: &Path,
+	format: Option<&str>,
+	options: DataSourceOptions,
+	runtime: TilesRuntime,
+) -> Result<Vec<GeoProperties>> {
+	new_data_source(path, format, options, runtime)?.load(path).await
+}
+
+/// CSV and TSV files, read with [`CsvReader`].
+struct CsvDataSource {
+	options: DataSourceOptions,
+	runtime: TilesRuntime,
+}
+
+#[async_trait]
+impl DataSource for CsvDataSource {
+	async fn load(&self, path: &Path) -> Result<Vec<GeoProperties>> {
+		let options = &self.options;
+		let mut reader = CsvReader::new(path, self.runtime.clone())
+			.with_normalized_header(options.normalize_keys)
+			.with_type_inference(options.infer_types);
+		if let Some(sep) = options.field_separator {
+			reader = reader.with_field_separator(sep);
+		}
+		if let Some(sep) = options.decimal_separator {
+			reader = reader.with_decimal_separator(sep);
+		}
+		if let Some(quote) = options.quote_char {
+			reader = reader.with_quote_char(quote);
+		}
+		reader.read().await
+	}
+}
+
+/// JSON files containing an array of objects.
+struct JsonDataSource {
+	options: DataSourceOptions,
+}
+
+#[async_trait]
+impl DataSource for JsonDataSource {
+	#[context("Failed to read JSON file at path: {path:?}")]
+	async fn load(&self, path: &Path) -> Result<Vec<GeoProperties>> {
+		let json = std::fs::read_to_string(path)?;
+		JsonValue::parse_str(&json)?
+			.into_array()?
+			.iter()
+			.enumerate()
+			.map(|(index, value)| {
+				let object = value.as_object().with_context(|| format!("record {index}"))?;
+				Ok(json_to_properties(object, self.options.normalize_keys))
+			})
+			.collect()
+	}
+}
+
+/// Newline-delimited JSON files with one object per line. Empty lines are skipped.
+struct NdjsonDataSource {
+	options: DataSourceOptions,
+}
+
+#[async_trait]
+impl DataSource for NdjsonDataSource {
+	#[context("Failed to read NDJSON file at path: {path:?}")]
+	async fn load(&self, path: &Path) -> Result<Vec<GeoProperties>> {
+		let ndjson = std::fs::read_to_string(path)?;
+		ndjson
+			.lines()
+			.enumerate()
+			.filter(|(_, line)| !line.trim().is_empty())
+			.map(|(index, line)| {
+				let object = JsonObject::parse_str(line).with_context(|| format!("line {}", index + 1))?;
+				Ok(json_to_properties(&object, self.options.normalize_keys))
+			})
+			.collect()
+	}
+}
+
+/// Parquet files, read in record batches with the Arrow reader of the `parquet` crate.
+///
+/// Columns are converted by their Arrow type: booleans, integers, floats and strings keep
+/// their type, all other types (dates, decimals, lists …) are stored as their display string.
+/// `null` values are skipped.
+#[cfg(feature = "parquet")]
+struct ParquetDataSource {
+	options: DataSourceOptions,
+}
+
+#[cfg(feature = "parquet")]
+#[async_trait]
+impl DataSource for ParquetDataSource {
+	#[context("Failed to read Parquet file at path: {path:?}")]
+	async fn load(&self, path: &Path) -> Result<Vec<GeoProperties>> {
+		use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+		let reader = ParquetRecordBatchReaderBuilder::try_new(std::fs::File::open(path)?)?.build()?;
+		let mut records = Vec::new();
+		for batch in reader {
+			let batch = batch?;
+			let keys = batch
+				.schema()
+				.fields()
+				.iter()
+				.map(|field| {
+					if self.options.normalize_keys {
+						normalize_key(field.name())
+					} else {
+						field.name().clone()
+					}
+				})
+				.collect::<Vec<_>>();
+			for row in 0..batch.num_rows() {
+				let mut properties = GeoProperties::new();
+				for (key, column) in keys.iter().zip(batch.columns()) {
+					let value = arrow_to_value(column.as_ref(), row)
+						.with_context(|| format!("column '{key}', record {}", records.len()))?;
+					if let Some(value) = value {
+						properties.insert(key.clone(), value);
+					}
+				}
+				records.push(properties);
+			}
+		}
+		Ok(records)
+	}
+}
+
+/// Converts the value in `row` of an Arrow array, or returns `None` if it is `null`.
+#[cfg(feature = "parquet")]
+fn arrow_to_value(array: &dyn arrow::array::Array, row: usize) -> Result<Option<GeoValue>> {
+	use arrow::{array::cast::AsArray, datatypes::*, util::display::ArrayFormatter};
+
+	if array.is_null(row) {
+		return Ok(None);
+	}
+	Ok(Some(match array.data_type() {
+		DataType::Boolean => GeoValue::Bool(array.as_boolean().value(row)),
+		DataType::Int8 => GeoValue::Int(i64::from(array.as_primitive::<Int8Type>().value(row))),
+		DataType::Int16 => GeoValue::Int(i64::from(array.as_primitive::<Int16Type>().value(row))),
+		DataType::Int32 => GeoValue::Int(i64::from(array.as_primitive::<Int32Type>().value(row))),
+		DataType::Int64 => GeoValue::Int(array.as_primitive::<Int64Type>().value(row)),
+		DataType::UInt8 => GeoValue::UInt(u64::from(array.as_primitive::<UInt8Type>().value(row))),
+		DataType::UInt16 => GeoValue::UInt(u64::from(array.as_primitive::<UInt16Type>().value(row))),
+		DataType::UInt32 => GeoValue::UInt(u64::from(array.as_primitive::<UInt32Type>().value(row))),
+		DataType::UInt64 => GeoValue::UInt(array.as_primitive::<UInt64Type>().value(row)),
+		DataType::Float32 => GeoValue::Float(array.as_primitive::<Float32Type>().value(row)),
+		DataType::Float64 => GeoValue::Double(array.as_primitive::<Float64Type>().value(row)),
+		DataType::Utf8 => GeoValue::from(array.as_string::<i32>().value(row)),
+		DataType::LargeUtf8 => GeoValue::from(array.as_string::<i64>().value(row)),
+		DataType::Utf8View => GeoValue::from(array.as_string_view().value(row)),
+		_ => GeoValue::from(
+			ArrayFormatter::try_new(array, &Default::default())?
+				.value(row)
+				.to_string(),
+		),
+	}))
+}
+
+/// Converts the top-level members of a JSON object into properties. `null` members are skipped,
+/// nested arrays and objects are stored as JSON strings.
+fn json_to_properties(object: &JsonObject, normalize_keys: bool) -> GeoProperties {
+	GeoProperties::from_iter(object.iter().filter_map(|(key, value)| {
+		let value = match value {
+			JsonValue::Null => return None,
+			JsonValue::Boolean(b) => GeoValue::Bool(*b),
+			JsonValue::String(s) => GeoValue::from(s),
+			JsonValue::Number(n) => number_to_value(*n),
+			JsonValue::Array(_) | JsonValue::Object(_) => GeoValue::from(value.stringify()),
+		};
+		let key = if normalize_keys {
+			normalize_key(key)
+		} else {
+			key.clone()
+		};
+		Some((key, value))
+	}))
+}
+
+/// Stores integral numbers as integers, so an ID like `1` matches the feature ID `1`, not `1.0`.
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn number_to_value(n: f64) -> GeoValue {
+	if n.fract() != 0.0 || n.abs() > 9e15 {
+		GeoValue::Double(n)
+	} else if n < 0.0 {
+		GeoValue::Int(n as i64)
+	} else {
+		GeoValue::UInt(n as u64)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use assert_fs::TempDir;
+	use rstest::rstest;
+
+	async fn load(name: &str, content: &str, format: Option<&str>) -> Result<Vec<GeoProperties>> {
+		let dir = TempDir::new()?;
+		let path = dir.path().join(name);
+		std::fs::write(&path, content)?;
+		read_data_source(&path, format, DataSourceOptions::default(), TilesRuntime::new_silent()).await
+	}
+
+	fn expected() -> Vec<GeoProperties> {
+		vec![
+			GeoProperties::from(vec![("id", GeoValue::from(1)), ("name", GeoValue::from("a"))]),
+			GeoProperties::from(vec![("id", GeoValue::from(2)), ("name", GeoValue::from("b"))]),
+		]
+	}
+
+	#[rstest]
+	#[case("data.csv", "id,name\n1,a\n2,b", None)]
+	#[case("data.tsv", "id\tname\n1\ta\n2\tb", None)]
+	#[case("data.txt", "id,name\n1,a\n2,b", None)]
+	#[case("data.txt", "id\tname\n1\ta\n2\tb", Some("tsv"))]
+	#[case("data.json", r#"[{"id":1,"name":"a"},{"id":2,"name":"b","skip":null}]"#, None)]
+	#[case("data.ndjson", "{\"id\":1,\"name\":\"a\"}\n\n{\"id\":2,\"name\":\"b\"}\n", None)]
+	#[case("data.jsonl", "{\"id\":1,\"name\":\"a\"}\n{\"id\":2,\"name\":\"b\"}", None)]
+	#[case("data.data", "{\"id\":1,\"name\":\"a\"}\n{\"id\":2,\"name\":\"b\"}", Some("ndjson"))]
+	#[tokio::test]
+	async fn loads_by_extension_or_format(
+		#[case] name: &str,
+		#[case] content: &str,
+		#[case] format: Option<&str>,
+	) -> Result<()> {
+		assert_eq!(load(name, content, format).await?, expected());
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn json_values() -> Result<()> {
+		let data = load(
+			"data.json",
+			r#"[{"a":-3,"b":1.5,"c":true,"d":[1,2],"e":{"f":"g"}}]"#,
+			None,
+		)
+		.await?;
+		assert_eq!(
+			data,
+			[GeoProperties::from(vec![
+				("a", GeoValue::Int(-3)),
+				("b", GeoValue::Double(1.5)),
+				("c", GeoValue::Bool(true)),
+				("d", GeoValue::from("[1,2]")),
+				("e", GeoValue::from("{\"f\":\"g\"}")),
+			])]
+		);
+		Ok(())
+	}
+
+	#[rstest]
+	#[case("data.json", "{\"id\":1}", None, "expected a JSON array")]
+	#[case("data.json", "[1]", None, "record 0")]
+	#[case("data.ndjson", "{\"id\":1}\n[1]", None, "line 2")]
+	#[case("data.csv", "", Some("xml"), "unknown data format 'xml'")]
+	#[tokio::test]
+	async fn errors(#[case] name: &str, #[case] content: &str, #[case] format: Option<&str>, #[case] message: &str) {
+		let err = format!("{:?}", load(name, content, format).await.unwrap_err());
+		assert!(err.contains(message), "{err}");
+	}
+
+	#[cfg(not(feature = "parquet"))]
+	#[tokio::test]
+	async fn parquet_needs_feature() {
+		let err = format!("{:?}", load("data.parquet", "", None).await.unwrap_err());
+		assert!(
+			err.contains("only supported if versatiles is built with the `parquet` feature"),
+			"{err}"
+		);
+	}
+
+	#[cfg(feature = "parquet")]
+	#[tokio::test]
+	async fn parquet_values() -> Result<()> {
+		use arrow::array::{ArrayRef, BooleanArray, Date32Array, Float64Array, Int64Array, RecordBatch, StringArray};
+		use parquet::arrow::ArrowWriter;
+		use std::sync::Arc;
+
+		let batch = RecordBatch::try_from_iter([
+			("id", Arc::new(Int64Array::from(vec![1, 2])) as ArrayRef),
+			("name", Arc::new(StringArray::from(vec![Some("a"), None])) as ArrayRef),
+			("share", Arc::new(Float64Array::from(vec![0.5, 2.0])) as ArrayRef),
+			("active", Arc::new(BooleanArray::from(vec![true, false])) as ArrayRef),
+			("since", Arc::new(Date32Array::from(vec![0, 365])) as ArrayRef),
+		])?;
+		let dir = TempDir::new()?;
+		let path = dir.path().join("data.parquet");
+		let mut writer = ArrowWriter::try_new(std::fs::File::create(&path)?, batch.schema(), None)?;
+		writer.write(&batch)?;
+		writer.close()?;
+
+		let data = read_data_source(&path, None, DataSourceOptions::default(), TilesRuntime::new_silent()).await?;
+		assert_eq!(
+			data,
+			[
+				GeoProperties::from(vec![
+					("id", GeoValue::Int(1)),
+					("name", GeoValue::from("a")),
+					("share", GeoValue::Double(0.5)),
+					("active", GeoValue::Bool(true)),
+					("since", GeoValue::from("1970-01-01")),
+				]),
+				GeoProperties::from(vec![
+					("id", GeoValue::Int(2)),
+					("share", GeoValue::Double(2.0)),
+					("active", GeoValue::Bool(false)),
+					("since", GeoValue::from("1971-01-01")),
+				]),
+			]
+		);
+		Ok(())
+	}
+}
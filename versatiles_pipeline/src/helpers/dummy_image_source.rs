@@ -2,7 +2,7 @@ use anyhow::{Result, ensure};
 use async_trait::async_trait;
 use imageproc::image::DynamicImage;
 use std::sync::Arc;
-use versatiles_container::{SourceType, Tile, TileSource, TileSourceMetadata, Traversal};
+use versatiles_container::{SourceType, Tile, TileSource, TileSourceMetadata};
 use versatiles_core::*;
 use versatiles_derive::context;
 use versatiles_image::traits::*;
@@ -37,8 +37,21 @@ impl DummyImageSource {
 	#[context("Creating DummyImageSource from image, tile_format='{tile_format}'")]
 	pub fn from_image(image: DynamicImage, tile_format: TileFormat, pyramid: Option<TileBBoxPyramid>) -> Result<Self> {
 		ensure!(tile_format.is_raster(), "tile_format must be a raster format");
+		let tile_size = if image.width() == image.height() {
+			u16::try_from(image.width())
+				.ok()
+				.and_then(|size| TileSize::new(size).ok())
+		} else {
+			None
+		};
+
 		let tile = Arc::new(Tile::from_image(image, tile_format)?);
-		Self::new(move |_coord| Some((*tile).clone()), tile_format, pyramid)
+		let mut source = Self::new(move |_coord| Some((*tile).clone()), tile_format, pyramid)?;
+		if tile_size.is_some() {
+			source.metadata.tile_size = tile_size;
+			source.metadata.update_tilejson(&mut source.tilejson);
+		}
+		Ok(source)
 	}
 
 	#[context("Creating DummyImageSource from image, tile_format='{tile_format}'")]
@@ -48,12 +61,11 @@ impl DummyImageSource {
 	{
 		ensure!(tile_format.is_raster(), "tile_format must be a raster format");
 
-		let metadata = TileSourceMetadata::new(
-			tile_format,
-			TileCompression::Uncompressed,
-			pyramid.unwrap_or_else(|| TileBBoxPyramid::new_full(8)),
-			Traversal::ANY,
-		);
+		let metadata = TileSourceMetadata::new_builder()
+			.with_format(tile_format)
+			.with_compression(TileCompression::Uncompressed)
+			.with_bbox_pyramid(pyramid.unwrap_or_else(|| TileBBoxPyramid::new_full(8)))
+			.build()?;
 
 		let mut tilejson = TileJSON::default();
 		tilejson.set_string("name", "dummy raster source")?;
@@ -37,12 +37,12 @@ impl DummyVectorSource {
 			.collect();
 
 		// Initialize the parameters with the given bounding box or a default one
-		let metadata = TileSourceMetadata::new(
-			TileFormat::MVT,
-			TileCompression::Uncompressed,
-			pyramid.unwrap_or_else(|| TileBBoxPyramid::new_full(8)),
-			Traversal::ANY,
-		);
+		let metadata = TileSourceMetadata::new_builder()
+			.with_format(TileFormat::MVT)
+			.with_compression(TileCompression::Uncompressed)
+			.with_bbox_pyramid(pyramid.unwrap_or_else(|| TileBBoxPyramid::new_full(8)))
+			.build()
+			.unwrap();
 
 		let mut tilejson = TileJSON::default();
 		tilejson.set_string("name", "dummy vector source").unwrap();
@@ -0,0 +1,454 @@
+//! A minimal expression language over the properties of vector tile features.
+//!
+//! An [`Expression`] like `class == "path" && (surface != "paved" || width < 2)` is parsed once
+//! and then evaluated for every feature, either as a condition ([`Expression::matches`]) or as a
+//! value ([`Expression::evaluate`]), e.g. `coalesce(rank, 10) / 2`. Supported are:
+//!
+//! - literals: numbers (`2`, `-1.5e3`), strings (`"paved"` or `'paved'`), `true`, `false` and `null`
+//! - property names: letters, digits, `_`, `:` and `.`, starting with a letter or `_`, e.g. `name:de`
+//! - arithmetic: `+`, `-`, `*`, `/`, where `+` concatenates if one side is a string
+//! - functions: `coalesce(a, b, ...)` returns the first argument that isn't `null`
+//! - comparisons: `==`, `!=`, `<`, `<=`, `>`, `>=`
+//! - boolean logic: `!`, `&&`, `||`, grouped with parentheses
+//!
+//! Properties that a feature doesn't have evaluate to `null`. Numbers are compared by value, whatever
+//! their type, and strings lexicographically. Values of different types are never equal and can't be
+//! ordered, so `<`, `<=`, `>` and `>=` are `false` for them. As a condition, `null`, `false`, `0` and
+//! `""` count as `false`, everything else as `true`.
+//!
+//! Arithmetic with `null`, booleans or, except for `+`, strings results in `null`, as does a division
+//! by zero. Integers stay integers, except for `/`, which always results in a floating point number.
+
+use anyhow::{Result, anyhow, ensure};
+use nom::{
+	IResult, Parser,
+	branch::alt,
+	bytes::complete::{escaped_transform, is_not, tag, take_while},
+	character::complete::{char, multispace0, none_of, satisfy},
+	combinator::{all_consuming, cut, opt, recognize, value},
+	error::context,
+	multi::{many0, separated_list1},
+	number::complete::recognize_float,
+	sequence::{delimited, pair, preceded, terminated},
+};
+use nom_language::error::{VerboseError, convert_error};
+use std::cmp::Ordering;
+use versatiles_derive::context;
+use versatiles_geometry::geo::{GeoProperties, GeoValue};
+
+/// Comparison operators of an [`Expression`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CompareOp {
+	Eq,
+	Ne,
+	Lt,
+	Le,
+	Gt,
+	Ge,
+}
+
+/// Arithmetic operators of an [`Expression`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ArithmeticOp {
+	Add,
+	Sub,
+	Mul,
+	Div,
+}
+
+/// A parsed expression over the properties of a feature, see the [module documentation](self).
+#[derive(Clone, Debug, PartialEq)]
+pub enum Expression {
+	Literal(GeoValue),
+	Property(String),
+	Arithmetic(Box<Expression>, ArithmeticOp, Box<Expression>),
+	Coalesce(Vec<Expression>),
+	Not(Box<Expression>),
+	And(Box<Expression>, Box<Expression>),
+	Or(Box<Expression>, Box<Expression>),
+	Compare(Box<Expression>, CompareOp, Box<Expression>),
+}
+
+impl Expression {
+	/// Parses an expression like `class == "path" && surface != "paved"`.
+	#[context("Failed to parse expression '{text}'")]
+	pub fn parse(text: &str) -> Result<Expression> {
+		ensure!(!text.trim().is_empty(), "expression is empty");
+		match all_consuming(delimited(multispace0, parse_or, multispace0)).parse(text) {
+			Ok((_, expression)) => Ok(expression),
+			Err(nom::Err::Error(e)) | Err(nom::Err::Failure(e)) => Err(anyhow!(convert_error(text, e))),
+			Err(e) => Err(anyhow!("Error parsing expression: {e:?}")),
+		}
+	}
+
+	/// Evaluates the expression as a condition for a feature with these properties.
+	#[must_use]
+	pub fn matches(&self, properties: &GeoProperties) -> bool {
+		match self {
+			Expression::Not(e) => !e.matches(properties),
+			Expression::And(a, b) => a.matches(properties) && b.matches(properties),
+			Expression::Or(a, b) => a.matches(properties) || b.matches(properties),
+			Expression::Compare(a, op, b) => {
+				let ordering = compare(&a.evaluate(properties), &b.evaluate(properties));
+				match op {
+					CompareOp::Eq => ordering == Some(Ordering::Equal),
+					CompareOp::Ne => ordering != Some(Ordering::Equal),
+					CompareOp::Lt => ordering == Some(Ordering::Less),
+					CompareOp::Le => matches!(ordering, Some(Ordering::Less | Ordering::Equal)),
+					CompareOp::Gt => ordering == Some(Ordering::Greater),
+					CompareOp::Ge => matches!(ordering, Some(Ordering::Greater | Ordering::Equal)),
+				}
+			}
+			Expression::Literal(_) | Expression::Property(_) | Expression::Arithmetic(..) | Expression::Coalesce(_) => {
+				is_truthy(&self.evaluate(properties))
+			}
+		}
+	}
+
+	/// Returns the value of the expression for a feature with these properties; conditions evaluate
+	/// to a [`GeoValue::Bool`].
+	#[must_use]
+	pub fn evaluate(&self, properties: &GeoProperties) -> GeoValue {
+		match self {
+			Expression::Literal(value) => value.clone(),
+			Expression::Property(key) => properties.get(key).cloned().unwrap_or(GeoValue::Null),
+			Expression::Arithmetic(a, op, b) => calculate(&a.evaluate(properties), *op, &b.evaluate(properties)),
+			Expression::Coalesce(args) => args
+				.iter()
+				.map(|arg| arg.evaluate(properties))
+				.find(|value| *value != GeoValue::Null)
+				.unwrap_or(GeoValue::Null),
+			_ => GeoValue::Bool(self.matches(properties)),
+		}
+	}
+}
+
+fn as_integer(value: &GeoValue) -> Option<i64> {
+	match value {
+		GeoValue::Int(v) => Some(*v),
+		GeoValue::UInt(v) => i64::try_from(*v).ok(),
+		_ => None,
+	}
+}
+
+fn integer_to_value(value: i64) -> GeoValue {
+	u64::try_from(value).map_or(GeoValue::Int(value), GeoValue::UInt)
+}
+
+fn calculate(a: &GeoValue, op: ArithmeticOp, b: &GeoValue) -> GeoValue {
+	if op == ArithmeticOp::Add
+		&& matches!((a, b), (GeoValue::String(_), _) | (_, GeoValue::String(_)))
+		&& !matches!((a, b), (GeoValue::Null, _) | (_, GeoValue::Null))
+	{
+		return GeoValue::String(format!("{a}{b}"));
+	}
+	if op != ArithmeticOp::Div
+		&& let (Some(a), Some(b)) = (as_integer(a), as_integer(b))
+	{
+		let result = match op {
+			ArithmeticOp::Add => a.checked_add(b),
+			ArithmeticOp::Sub => a.checked_sub(b),
+			_ => a.checked_mul(b),
+		};
+		if let Some(result) = result {
+			return integer_to_value(result);
+		}
+	}
+	let (Some(a), Some(b)) = (as_number(a), as_number(b)) else {
+		return GeoValue::Null;
+	};
+	match op {
+		ArithmeticOp::Add => GeoValue::Double(a + b),
+		ArithmeticOp::Sub => GeoValue::Double(a - b),
+		ArithmeticOp::Mul => GeoValue::Double(a * b),
+		ArithmeticOp::Div if b == 0.0 => GeoValue::Null,
+		ArithmeticOp::Div => GeoValue::Double(a / b),
+	}
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn as_number(value: &GeoValue) -> Option<f64> {
+	match value {
+		GeoValue::Double(v) => Some(*v),
+		GeoValue::Float(v) => Some(f64::from(*v)),
+		GeoValue::Int(v) => Some(*v as f64),
+		GeoValue::UInt(v) => Some(*v as f64),
+		_ => None,
+	}
+}
+
+/// Compares two values of the same type; numbers are compared by value, whatever their type.
+fn compare(a: &GeoValue, b: &GeoValue) -> Option<Ordering> {
+	if let (Some(a), Some(b)) = (as_number(a), as_number(b)) {
+		return a.partial_cmp(&b);
+	}
+	match (a, b) {
+		(GeoValue::String(a), GeoValue::String(b)) => Some(a.cmp(b)),
+		(GeoValue::Bool(a), GeoValue::Bool(b)) => Some(a.cmp(b)),
+		(GeoValue::Null, GeoValue::Null) => Some(Ordering::Equal),
+		_ => None,
+	}
+}
+
+fn is_truthy(value: &GeoValue) -> bool {
+	match value {
+		GeoValue::Bool(b) => *b,
+		GeoValue::Null => false,
+		GeoValue::String(s) => !s.is_empty(),
+		_ => as_number(value).is_some_and(|n| n != 0.0),
+	}
+}
+
+type ParseResult<'a, T> = IResult<&'a str, T, VerboseError<&'a str>>;
+
+fn parse_or(input: &str) -> ParseResult<'_, Expression> {
+	let (input, first) = parse_and(input)?;
+	let (input, rest) = many0(preceded((multispace0, tag("||"), multispace0), cut(parse_and))).parse(input)?;
+	let expression = rest
+		.into_iter()
+		.fold(first, |a, b| Expression::Or(Box::new(a), Box::new(b)));
+	Ok((input, expression))
+}
+
+fn parse_and(input: &str) -> ParseResult<'_, Expression> {
+	let (input, first) = parse_not(input)?;
+	let (input, rest) = many0(preceded((multispace0, tag("&&"), multispace0), cut(parse_not))).parse(input)?;
+	let expression = rest
+		.into_iter()
+		.fold(first, |a, b| Expression::And(Box::new(a), Box::new(b)));
+	Ok((input, expression))
+}
+
+fn parse_not(input: &str) -> ParseResult<'_, Expression> {
+	alt((
+		preceded((char('!'), multispace0), cut(parse_not)).map(|e| Expression::Not(Box::new(e))),
+		parse_comparison,
+	))
+	.parse(input)
+}
+
+fn parse_comparison(input: &str) -> ParseResult<'_, Expression> {
+	let (input, a) = parse_sum(input)?;
+	let (input, rest) = opt((delimited(multispace0, parse_compare_op, multispace0), cut(parse_sum))).parse(input)?;
+	Ok((
+		input,
+		match rest {
+			Some((op, b)) => Expression::Compare(Box::new(a), op, Box::new(b)),
+			None => a,
+		},
+	))
+}
+
+fn parse_compare_op(input: &str) -> ParseResult<'_, CompareOp> {
+	context(
+		"parsing comparison operator",
+		alt((
+			value(CompareOp::Eq, tag("==")),
+			value(CompareOp::Ne, tag("!=")),
+			value(CompareOp::Le, tag("<=")),
+			value(CompareOp::Ge, tag(">=")),
+			value(CompareOp::Lt, tag("<")),
+			value(CompareOp::Gt, tag(">")),
+		)),
+	)
+	.parse(input)
+}
+
+fn parse_sum(input: &str) -> ParseResult<'_, Expression> {
+	let op = alt((value(ArithmeticOp::Add, char('+')), value(ArithmeticOp::Sub, char('-'))));
+	let (input, first) = parse_product(input)?;
+	let (input, rest) = many0((delimited(multispace0, op, multispace0), cut(parse_product))).parse(input)?;
+	let expression = rest
+		.into_iter()
+		.fold(first, |a, (op, b)| Expression::Arithmetic(Box::new(a), op, Box::new(b)));
+	Ok((input, expression))
+}
+
+fn parse_product(input: &str) -> ParseResult<'_, Expression> {
+	let op = alt((value(ArithmeticOp::Mul, char('*')), value(ArithmeticOp::Div, char('/'))));
+	let (input, first) = parse_operand(input)?;
+	let (input, rest) = many0((delimited(multispace0, op, multispace0), cut(parse_operand))).parse(input)?;
+	let expression = rest
+		.into_iter()
+		.fold(first, |a, (op, b)| Expression::Arithmetic(Box::new(a), op, Box::new(b)));
+	Ok((input, expression))
+}
+
+fn parse_operand(input: &str) -> ParseResult<'_, Expression> {
+	context(
+		"parsing operand",
+		alt((
+			delimited((char('('), multispace0), cut(parse_or), cut((multispace0, char(')')))),
+			parse_string.map(|s| Expression::Literal(GeoValue::String(s))),
+			parse_number,
+			parse_coalesce,
+			parse_name,
+		)),
+	)
+	.parse(input)
+}
+
+fn parse_coalesce(input: &str) -> ParseResult<'_, Expression> {
+	preceded(
+		(tag("coalesce"), multispace0, char('('), multispace0),
+		cut(terminated(
+			separated_list1((multispace0, char(','), multispace0), parse_or),
+			(multispace0, char(')')),
+		)),
+	)
+	.map(Expression::Coalesce)
+	.parse(input)
+}
+
+fn parse_string(input: &str) -> ParseResult<'_, String> {
+	context(
+		"parsing string",
+		alt((
+			delimited(
+				char('"'),
+				opt(escaped_transform(
+					none_of("\\\""),
+					'\\',
+					alt((value("\\", tag("\\")), value("\"", tag("\"")))),
+				))
+				.map(Option::unwrap_or_default),
+				cut(char('"')),
+			),
+			delimited(
+				char('\''),
+				opt(is_not("'")).map(|s: Option<&str>| s.unwrap_or_default().to_string()),
+				cut(char('\'')),
+			),
+		)),
+	)
+	.parse(input)
+}
+
+/// Parses a number; numbers without a decimal point or exponent are integers.
+fn parse_number(input: &str) -> ParseResult<'_, Expression> {
+	context("parsing number", recognize_float)
+		.map(|s: &str| {
+			Expression::Literal(match s.parse::<i64>() {
+				Ok(v) => integer_to_value(v),
+				Err(_) => GeoValue::Double(s.parse().unwrap_or(f64::NAN)),
+			})
+		})
+		.parse(input)
+}
+
+/// Parses a property name or one of the keywords `true`, `false` and `null`.
+fn parse_name(input: &str) -> ParseResult<'_, Expression> {
+	context(
+		"parsing property name",
+		recognize(pair(
+			satisfy(|c| c.is_ascii_alphabetic() || c == '_'),
+			take_while(|c: char| c.is_ascii_alphanumeric() || "_:.".contains(c)),
+		)),
+	)
+	.map(|name: &str| match name {
+		"true" => Expression::Literal(GeoValue::Bool(true)),
+		"false" => Expression::Literal(GeoValue::Bool(false)),
+		"null" => Expression::Literal(GeoValue::Null),
+		_ => Expression::Property(name.to_string()),
+	})
+	.parse(input)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use rstest::rstest;
+
+	fn properties() -> GeoProperties {
+		GeoProperties::from(vec![
+			("class", GeoValue::from("path")),
+			("surface", GeoValue::from("gravel")),
+			("width", GeoValue::UInt(2)),
+			("lit", GeoValue::Bool(false)),
+			("name:de", GeoValue::from("Weg")),
+		])
+	}
+
+	#[rstest]
+	#[case(r#"class == "path""#, true)]
+	#[case(r#"class == 'path' && surface != "paved""#, true)]
+	#[case(r#"class=="path"&&surface=="paved""#, false)]
+	#[case(r#"class == "road" || width >= 2"#, true)]
+	#[case("width < 2.5 && width > 1", true)]
+	#[case("width == 2", true)]
+	#[case("width <= -1e3", false)]
+	#[case("!lit", true)]
+	#[case("!(width == 2)", false)]
+	#[case(r#"!(class == "path" && (surface == "gravel" || lit))"#, false)]
+	#[case(r#""Weg" == name:de"#, true)]
+	#[case("lit == false", true)]
+	#[case("width", true)]
+	#[case("missing", false)]
+	#[case("missing == null", true)]
+	#[case("missing != 1", true)]
+	#[case("missing < 1 || missing >= 1", false)]
+	#[case(r#"class < "road" && class > "a""#, true)]
+	#[case(r#"width == "2""#, false)]
+	#[case("  true  ", true)]
+	fn matches(#[case] text: &str, #[case] expected: bool) -> Result<()> {
+		assert_eq!(Expression::parse(text)?.matches(&properties()), expected, "{text}");
+		Ok(())
+	}
+
+	#[rstest]
+	#[case("width + 1", GeoValue::UInt(3))]
+	#[case("width - 5", GeoValue::Int(-3))]
+	#[case("width * 2 + 1", GeoValue::UInt(5))]
+	#[case("width * (2 + 1)", GeoValue::UInt(6))]
+	#[case("width / 4", GeoValue::Double(0.5))]
+	#[case("width / 0", GeoValue::Null)]
+	#[case("width * 1.5", GeoValue::Double(3.0))]
+	#[case("width-1", GeoValue::UInt(1))]
+	#[case("-2 - -1", GeoValue::Int(-1))]
+	#[case(r#"class + "-" + width"#, GeoValue::from("path-2"))]
+	#[case("class * 2", GeoValue::Null)]
+	#[case("missing + 1", GeoValue::Null)]
+	#[case("missing + 'x'", GeoValue::Null)]
+	#[case("coalesce(missing, width)", GeoValue::UInt(2))]
+	#[case("coalesce(missing, 'none')", GeoValue::from("none"))]
+	#[case("coalesce( class , 'none' )", GeoValue::from("path"))]
+	#[case("coalesce(missing)", GeoValue::Null)]
+	#[case("coalesce(missing, width * 10) / 4", GeoValue::Double(5.0))]
+	#[case("width >= 2", GeoValue::Bool(true))]
+	fn evaluate(#[case] text: &str, #[case] expected: GeoValue) -> Result<()> {
+		assert_eq!(Expression::parse(text)?.evaluate(&properties()), expected, "{text}");
+		Ok(())
+	}
+
+	#[test]
+	fn precedence() -> Result<()> {
+		// `&&` binds tighter than `||`
+		let e = Expression::parse("a || b && c")?;
+		assert_eq!(
+			e,
+			Expression::Or(
+				Box::new(Expression::Property("a".to_string())),
+				Box::new(Expression::And(
+					Box::new(Expression::Property("b".to_string())),
+					Box::new(Expression::Property("c".to_string()))
+				))
+			)
+		);
+		Ok(())
+	}
+
+	#[rstest]
+	#[case("", "expression is empty")]
+	#[case("class ==", "parsing operand")]
+	#[case("(a == 1", "expected ')'")]
+	#[case(r#"a == "b"#, "expected '\"'")]
+	#[case("a = 1", "Failed to parse expression 'a = 1'")]
+	#[case("a == 1 b", "Failed to parse expression")]
+	#[case("a +", "parsing operand")]
+	#[case("coalesce(a b)", "expected ')'")]
+	#[case("coalesce()", "Failed to parse expression")]
+	fn errors(#[case] text: &str, #[case] message: &str) {
+		let err = format!("{:?}", Expression::parse(text).unwrap_err());
+		assert!(err.contains(message), "{err}");
+	}
+}
@@ -2,12 +2,18 @@
 mod arrange_tiles;
 mod container_registry;
 mod csv;
+mod data_source;
 pub mod dummy_image_source;
 pub mod dummy_vector_source;
+mod expression;
 mod pipeline_reader;
+mod source_guard;
 
 #[cfg(test)]
 pub use arrange_tiles::*;
 pub use container_registry::*;
 pub use csv::*;
+pub use data_source::*;
+pub use expression::*;
 pub use pipeline_reader::PipelineReader;
+pub use source_guard::*;
@@ -36,9 +36,15 @@ impl<'a> PipelineReader {
 	#[context("opening VPL path '{}'", path.display())]
 	pub async fn open_path(path: &Path, runtime: TilesRuntime) -> Result<PipelineReader> {
 		let vpl = std::fs::read_to_string(path).with_context(|| anyhow!("Failed to open {path:?}"))?;
-		Self::from_str(&vpl, path.to_str().unwrap(), path.parent().unwrap(), runtime)
-			.await
-			.with_context(|| format!("failed parsing {path:?} as VPL"))
+		Self::from_str(
+			&vpl,
+			path.to_str().unwrap(),
+			path.parent().unwrap(),
+			Some(path),
+			runtime,
+		)
+		.await
+		.with_context(|| format!("failed parsing {path:?} as VPL"))
 	}
 
 	/// Opens a `PipelineReader` from an arbitrary [`DataReader`] containing VPL.
@@ -47,7 +53,7 @@ impl<'a> PipelineReader {
 	#[context("opening VPL from reader '{}'", reader.get_name())]
 	pub async fn open_reader(reader: DataReader, dir: &Path, runtime: TilesRuntime) -> Result<PipelineReader> {
 		let vpl = reader.read_all().await?.into_string();
-		Self::from_str(&vpl, reader.get_name(), dir, runtime)
+		Self::from_str(&vpl, reader.get_name(), dir, None, runtime)
 			.await
 			.with_context(|| format!("failed parsing {} as VPL", reader.get_name()))
 	}
@@ -55,15 +61,16 @@ impl<'a> PipelineReader {
 	/// Test helper: constructs a `PipelineReader` from a raw VPL string.
 	#[context("opening VPL from string")]
 	pub async fn open_str(vpl: &str, dir: &Path, runtime: TilesRuntime) -> Result<PipelineReader> {
-		Self::from_str(vpl, "from str", dir, runtime).await
+		Self::from_str(vpl, "from str", dir, None, runtime).await
 	}
 
 	/// Internal constructor that parses VPL and wires up the callback used by `PipelineFactory`
-	/// to resolve nested readers via `ContainerRegistry`.
+	/// to resolve nested readers via `ContainerRegistry`. `origin` is the path of the VPL file, if any.
 	fn from_str(
 		vpl: &'a str,
 		name: &'a str,
 		dir: &'a Path,
+		origin: Option<&'a Path>,
 		runtime: TilesRuntime,
 	) -> BoxFuture<'a, Result<PipelineReader>> {
 		Box::pin(async move {
@@ -76,7 +83,10 @@ impl<'a> PipelineReader {
 						.map_err(|_| anyhow::anyhow!("Cannot get exclusive access to reader for pipeline"))
 				})
 			});
-			let factory = PipelineFactory::new_default(dir, callback, runtime);
+			let mut factory = PipelineFactory::new_default(dir, callback, runtime);
+			if let Some(path) = origin {
+				factory.set_origin(path);
+			}
 			let operation: Box<dyn TileSource> = factory.operation_from_vpl(vpl).await?;
 
 			Ok(PipelineReader {
@@ -114,7 +124,7 @@ impl TileSource for PipelineReader {
 			.get_tile_stream(coord.to_tile_bbox())
 			.await?
 			.to_vec()
-			.await;
+			.await?;
 
 		ensure!(vec.len() <= 1, "PipelineReader should return at most one tile");
 
@@ -146,6 +156,7 @@ impl std::fmt::Debug for PipelineReader {
 mod tests {
 	use super::*;
 	use pretty_assertions::assert_eq;
+	use rstest::rstest;
 	use versatiles_container::MockWriter;
 
 	pub const VPL: &str = include_str!("../../../testdata/berlin.vpl");
@@ -191,12 +202,48 @@ mod tests {
 		Ok(())
 	}
 
+	#[rstest]
+	#[case("berlin.mbtiles")]
+	#[case("berlin.pmtiles")]
+	#[case("berlin.versatiles")]
+	#[tokio::test]
+	async fn read_picks_the_reader_by_extension(#[case] filename: &str) -> Result<()> {
+		let runtime = TilesRuntime::new_silent();
+		let temp_dir = assert_fs::TempDir::new()?;
+		let dir = if filename.ends_with(".versatiles") {
+			// there is no VersaTiles fixture, so convert the MBTiles file
+			let reader = runtime.get_reader_from_str("../testdata/berlin.mbtiles").await?;
+			runtime.write_to_path(reader, &temp_dir.join(filename)).await?;
+			temp_dir.path()
+		} else {
+			Path::new("../testdata/")
+		};
+
+		let vpl = format!("read filename=\"{filename}\"");
+		let reader = PipelineReader::open_str(&vpl, dir, runtime).await?;
+		let extension = filename.rsplit('.').next().unwrap();
+		let source_type = reader.operation.source_type().to_string();
+		assert!(
+			source_type.starts_with(&format!("container '{extension}'")),
+			"{source_type}"
+		);
+
+		let blob = reader
+			.get_tile(&TileCoord::new(14, 8800, 5377)?)
+			.await?
+			.unwrap()
+			.into_blob(TileCompression::Uncompressed)?;
+		assert_eq!(blob.len(), 141415);
+
+		Ok(())
+	}
+
 	#[tokio::test]
 	async fn test_tile_pipeline_reader_get_tile_stream() -> Result<()> {
 		let reader = PipelineReader::open_str(VPL, Path::new("../testdata/"), TilesRuntime::new_silent()).await?;
 		let bbox = TileBBox::from_min_and_max(1, 0, 0, 1, 1)?;
 		let result_stream = reader.get_tile_stream(bbox).await?;
-		let result = result_stream.to_vec().await;
+		let result = result_stream.to_vec().await.unwrap();
 
 		assert!(!result.is_empty());
 
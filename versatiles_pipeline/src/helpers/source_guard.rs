@@ -0,0 +1,232 @@
+//! Timeouts and a circuit breaker for the sources of composite operations.
+//!
+//! A [`SourceGuard`] wraps every request to one source. Requests that fail or take longer than
+//! the timeout count as failures. After too many consecutive failures the source is skipped for
+//! a cool-down period and then tried again, so a single slow source can't stall every tile.
+
+use anyhow::Result;
+use std::{
+	future::Future,
+	sync::{
+		Mutex,
+		atomic::{AtomicU64, Ordering},
+	},
+	time::{Duration, Instant},
+};
+
+/// Cool-down of a skipped source if not configured otherwise.
+pub const DEFAULT_COOL_DOWN: Duration = Duration::from_secs(30);
+
+/// Resilience settings shared by all sources of an operation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GuardSettings {
+	/// Maximum duration of a single request.
+	pub timeout: Option<Duration>,
+	/// Number of consecutive failures after which a source is skipped.
+	pub skip_after_failures: Option<u32>,
+	/// How long a source is skipped before it is tried again.
+	pub cool_down: Duration,
+}
+
+impl Default for GuardSettings {
+	fn default() -> Self {
+		Self {
+			timeout: None,
+			skip_after_failures: None,
+			cool_down: DEFAULT_COOL_DOWN,
+		}
+	}
+}
+
+impl GuardSettings {
+	/// Creates settings from the optional millisecond values of VPL properties.
+	pub fn from_millis(timeout_ms: Option<u32>, skip_after_failures: Option<u32>, cool_down_ms: Option<u32>) -> Self {
+		Self {
+			timeout: timeout_ms.map(|ms| Duration::from_millis(u64::from(ms))),
+			skip_after_failures,
+			cool_down: cool_down_ms.map_or(DEFAULT_COOL_DOWN, |ms| Duration::from_millis(u64::from(ms))),
+		}
+	}
+
+	/// Without a timeout or a failure limit, errors are passed on unchanged.
+	pub fn is_active(&self) -> bool {
+		self.timeout.is_some() || self.skip_after_failures.is_some()
+	}
+}
+
+/// Guards the requests to one source, see the module documentation.
+#[derive(Debug)]
+pub struct SourceGuard {
+	name: String,
+	settings: GuardSettings,
+	consecutive_failures: AtomicU64,
+	skipped_until: Mutex<Option<Instant>>,
+	requests: AtomicU64,
+	failures: AtomicU64,
+	timeouts: AtomicU64,
+	skipped: AtomicU64,
+}
+
+impl SourceGuard {
+	pub fn new(name: String, settings: GuardSettings) -> Self {
+		Self {
+			name,
+			settings,
+			consecutive_failures: AtomicU64::new(0),
+			skipped_until: Mutex::new(None),
+			requests: AtomicU64::new(0),
+			failures: AtomicU64::new(0),
+			timeouts: AtomicU64::new(0),
+			skipped: AtomicU64::new(0),
+		}
+	}
+
+	/// Runs a request to the source.
+	///
+	/// Returns `Ok(None)` if the source is currently skipped, or if the request failed or timed out
+	/// while the settings are active. Otherwise errors are returned as they are.
+	pub async fn run<T>(&self, request: impl Future<Output = Result<T>>) -> Result<Option<T>> {
+		if !self.settings.is_active() {
+			return request.await.map(Some);
+		}
+
+		self.requests.fetch_add(1, Ordering::Relaxed);
+		if self.is_skipped() {
+			self.skipped.fetch_add(1, Ordering::Relaxed);
+			return Ok(None);
+		}
+
+		let result = match self.settings.timeout {
+			Some(timeout) => match tokio::time::timeout(timeout, request).await {
+				Ok(result) => result,
+				Err(_) => {
+					self.timeouts.fetch_add(1, Ordering::Relaxed);
+					Err(anyhow::anyhow!("request timed out after {timeout:?}"))
+				}
+			},
+			None => request.await,
+		};
+
+		match result {
+			Ok(value) => {
+				self.consecutive_failures.store(0, Ordering::Relaxed);
+				Ok(Some(value))
+			}
+			Err(err) => {
+				self.failures.fetch_add(1, Ordering::Relaxed);
+				log::debug!("source {} failed: {err:#}", self.name);
+				self.register_failure();
+				Ok(None)
+			}
+		}
+	}
+
+	/// Returns whether the source is in its cool-down. An expired cool-down is reset.
+	fn is_skipped(&self) -> bool {
+		let mut skipped_until = self.skipped_until.lock().unwrap();
+		match *skipped_until {
+			Some(until) if Instant::now() < until => true,
+			Some(_) => {
+				*skipped_until = None;
+				false
+			}
+			None => false,
+		}
+	}
+
+	fn register_failure(&self) {
+		let failures = self.consecutive_failures.fetch_add(1, Ordering::Relaxed) + 1;
+		let Some(limit) = self.settings.skip_after_failures else {
+			return;
+		};
+		if failures >= u64::from(limit) {
+			self.consecutive_failures.store(0, Ordering::Relaxed);
+			*self.skipped_until.lock().unwrap() = Some(Instant::now() + self.settings.cool_down);
+			log::warn!(
+				"source {} failed {failures} times in a row, skipping it for {:?}",
+				self.name,
+				self.settings.cool_down
+			);
+		}
+	}
+
+	/// Describes the counters, or returns `None` if the source never failed.
+	pub fn summary(&self) -> Option<String> {
+		let failures = self.failures.load(Ordering::Relaxed);
+		let skipped = self.skipped.load(Ordering::Relaxed);
+		if failures == 0 && skipped == 0 {
+			return None;
+		}
+		Some(format!(
+			"source {}: {} requests, {failures} failures ({} timeouts), {skipped} skipped",
+			self.name,
+			self.requests.load(Ordering::Relaxed),
+			self.timeouts.load(Ordering::Relaxed),
+		))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::bail;
+
+	fn guard(timeout_ms: Option<u32>, skip_after_failures: Option<u32>, cool_down_ms: u32) -> SourceGuard {
+		let settings = GuardSettings::from_millis(timeout_ms, skip_after_failures, Some(cool_down_ms));
+		SourceGuard::new(String::from("#0"), settings)
+	}
+
+	async fn fail() -> Result<u8> {
+		bail!("broken")
+	}
+
+	async fn sleep() -> Result<u8> {
+		tokio::time::sleep(Duration::from_secs(10)).await;
+		Ok(1)
+	}
+
+	#[tokio::test]
+	async fn inactive_settings_pass_errors_on() {
+		let guard = SourceGuard::new(String::from("#0"), GuardSettings::default());
+		assert_eq!(guard.run(async { Ok(1) }).await.unwrap(), Some(1));
+		assert!(guard.run(fail()).await.is_err());
+		assert_eq!(guard.summary(), None);
+	}
+
+	#[tokio::test]
+	async fn timeout_and_circuit_breaker() {
+		let guard = guard(Some(20), Some(2), 100);
+
+		let start = Instant::now();
+		assert_eq!(guard.run(sleep()).await.unwrap(), None);
+		assert_eq!(guard.run(sleep()).await.unwrap(), None);
+		assert!(start.elapsed() < Duration::from_secs(2));
+
+		// skipped without calling the source
+		let start = Instant::now();
+		assert_eq!(guard.run(async { Ok(1) }).await.unwrap(), None);
+		assert!(start.elapsed() < Duration::from_millis(20));
+
+		// retried after the cool-down
+		tokio::time::sleep(Duration::from_millis(150)).await;
+		assert_eq!(guard.run(async { Ok(1) }).await.unwrap(), Some(1));
+
+		assert_eq!(
+			guard.summary().unwrap(),
+			"source #0: 4 requests, 2 failures (2 timeouts), 1 skipped"
+		);
+	}
+
+	#[tokio::test]
+	async fn successes_reset_the_failure_count() {
+		let guard = guard(None, Some(2), 10_000);
+		for _ in 0..3 {
+			assert_eq!(guard.run(fail()).await.unwrap(), None);
+			assert_eq!(guard.run(async { Ok(1) }).await.unwrap(), Some(1));
+		}
+		assert_eq!(
+			guard.summary().unwrap(),
+			"source #0: 6 requests, 3 failures (0 timeouts), 0 skipped"
+		);
+	}
+}
@@ -8,12 +8,15 @@
 //!
 //! This crate integrates tightly with [`versatiles_container`] and [`versatiles_core`] for tile I/O and metadata management.
 
+mod docs;
 mod factory;
 mod helpers;
 mod operations;
 mod traits;
 mod vpl;
 
+pub use docs::{OperationDocs, OperationInfo, OperationKind, OperationPriority, PropertyDocs};
 pub use factory::PipelineFactory;
 pub use helpers::{PipelineReader, register_pipeline_readers};
+pub use traits::{OperationFactory, OperationFactoryTrait, ReadOperationFactoryTrait, TransformOperationFactoryTrait};
 pub use vpl::VPLNode;
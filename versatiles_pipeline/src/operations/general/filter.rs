@@ -1,4 +1,4 @@
-use crate::{PipelineFactory, traits::*, vpl::VPLNode};
+use crate::{OperationDocs, PipelineFactory, traits::*, vpl::VPLNode};
 use anyhow::{Result, bail};
 use async_trait::async_trait;
 use std::{fmt::Debug, sync::Arc};
@@ -11,6 +11,8 @@ use versatiles_derive::context;
 struct Args {
 	/// Bounding box in WGS84: [min lng, min lat, max lng, max lat].
 	bbox: Option<[f64; 4]>,
+	/// Area as WKT `POLYGON` or `MULTIPOLYGON`, e.g. exported from PostGIS. Its bounding box is used like `bbox`.
+	bbox_wkt: Option<String>,
 	/// minimal zoom level
 	level_min: Option<u8>,
 	/// maximal zoom level
@@ -54,6 +56,10 @@ impl Operation {
 			metadata.bbox_pyramid.intersect_geo_bbox(&GeoBBox::try_from(&bbox)?)?;
 		}
 
+		if let Some(wkt) = &args.bbox_wkt {
+			metadata.bbox_pyramid.intersect_geo_bbox(&GeoBBox::from_wkt(wkt)?)?;
+		}
+
 		if metadata.bbox_pyramid.is_empty() {
 			log::warn!(
 				"Filter operation in VPL node {:?} results in empty bbox_pyramid",
@@ -99,7 +105,7 @@ impl TileSource for Operation {
 pub struct Factory {}
 
 impl OperationFactoryTrait for Factory {
-	fn get_docs(&self) -> String {
+	fn get_docs(&self) -> OperationDocs {
 		Args::get_docs()
 	}
 	fn get_tag_name(&self) -> &str {
@@ -161,7 +167,13 @@ mod tests {
 			for x in 0..max_xy {
 				for y in 0..max_xy {
 					let coord = TileCoord::new(level, x, y)?;
-					let count = op.get_tile_stream(coord.to_tile_bbox()).await?.to_vec().await.len();
+					let count = op
+						.get_tile_stream(coord.to_tile_bbox())
+						.await?
+						.to_vec()
+						.await
+						.unwrap()
+						.len();
 					if set.contains(&(level, x, y)) {
 						assert!(count == 1, "Expected one tile for {coord:?}, found {count}");
 					} else {
@@ -191,12 +203,43 @@ mod tests {
 
 		for z in 0..=6 {
 			let coord = TileCoord::new(z, 0, 0)?;
-			let n = op.get_tile_stream(coord.to_tile_bbox()).await?.to_vec().await.len();
+			let n = op
+				.get_tile_stream(coord.to_tile_bbox())
+				.await?
+				.to_vec()
+				.await
+				.unwrap()
+				.len();
 			assert_eq!(n == 1, (3..=4).contains(&z), "z={z}");
 		}
 		Ok(())
 	}
 
+	#[tokio::test]
+	async fn test_filter_bbox_wkt() -> Result<()> {
+		let factory = PipelineFactory::new_dummy();
+		let op = factory
+			.operation_from_vpl(
+				"from_debug format=mvt | filter bbox_wkt=\"POLYGON((5.87 47.27, 15.04 47.27, 15.04 55.06, 5.87 55.06, 5.87 47.27))\"",
+			)
+			.await?;
+
+		let bounds: [f64; 4] = op.tilejson().as_object().get_number_array("bounds")?.unwrap();
+		for (value, expected) in bounds.iter().zip([5.87, 47.27, 15.04, 55.06]) {
+			assert!((value - expected).abs() < 1e-4, "{bounds:?}");
+		}
+		assert_eq!(
+			op.metadata().bbox_pyramid.get_bounding_bbox(8).as_array()?,
+			[132, 80, 138, 89]
+		);
+
+		let result = factory
+			.operation_from_vpl("from_debug format=mvt | filter bbox_wkt=\"POINT(10 50)\"")
+			.await;
+		assert!(result.is_err());
+		Ok(())
+	}
+
 	#[tokio::test]
 	async fn test_invalid_zoom_range_errors() {
 		let factory = PipelineFactory::new_dummy();
@@ -233,12 +276,12 @@ mod tests {
 
 		// Sanity: tiles outside the final bbox shouldn’t pass
 		let outside = TileCoord::new(4, 0, 0)?.to_tile_bbox();
-		let n_out = op.get_tile_stream(outside).await?.to_vec().await.len();
+		let n_out = op.get_tile_stream(outside).await?.to_vec().await.unwrap().len();
 		assert_eq!(n_out, 0);
 
 		// Inside tile at z=4 should pass
 		let inside = TileCoord::new(4, 8, 7)?.to_tile_bbox(); // somewhere within [10,5,40,20]
-		let n_in = op.get_tile_stream(inside).await?.to_vec().await.len();
+		let n_in = op.get_tile_stream(inside).await?.to_vec().await.unwrap().len();
 		assert_eq!(n_in, 1);
 
 		Ok(())
@@ -1,4 +1,4 @@
-use crate::{PipelineFactory, traits::*, vpl::VPLNode};
+use crate::{OperationDocs, PipelineFactory, traits::*, vpl::VPLNode};
 use anyhow::Result;
 use async_trait::async_trait;
 use std::{fmt::Debug, sync::Arc};
@@ -83,7 +83,7 @@ impl TileSource for Operation {
 pub struct Factory {}
 
 impl OperationFactoryTrait for Factory {
-	fn get_docs(&self) -> String {
+	fn get_docs(&self) -> OperationDocs {
 		Args::get_docs()
 	}
 	fn get_tag_name(&self) -> &str {
@@ -14,8 +14,12 @@ pub fn get_transform_operation_factories() -> Vec<Box<dyn TransformOperationFact
 		Box::new(raster::raster_levels::Factory {}),
 		Box::new(raster::raster_overscale::Factory {}),
 		Box::new(raster::raster_overview::Factory {}),
+		Box::new(vector::vector_compute_property::Factory {}),
+		Box::new(vector::vector_filter_features::Factory {}),
 		Box::new(vector::vector_filter_layers::Factory {}),
 		Box::new(vector::vector_filter_properties::Factory {}),
+		Box::new(vector::vector_rename_properties::Factory {}),
+		Box::new(vector::vector_simplify::Factory {}),
 		Box::new(vector::vector_update_properties::Factory {}),
 	]
 }
@@ -23,6 +27,7 @@ pub fn get_transform_operation_factories() -> Vec<Box<dyn TransformOperationFact
 pub fn get_read_operation_factories() -> Vec<Box<dyn ReadOperationFactoryTrait>> {
 	vec![
 		Box::new(read::from_container::Factory {}),
+		Box::new(read::from_container::ReadFactory {}),
 		Box::new(read::from_debug::Factory {}),
 		Box::new(read::from_stacked::Factory {}),
 		Box::new(read::from_stacked_raster::Factory {}),
@@ -1,4 +1,4 @@
-use crate::{PipelineFactory, traits::*, vpl::VPLNode};
+use crate::{OperationDocs, PipelineFactory, traits::*, vpl::VPLNode};
 use anyhow::Result;
 use async_trait::async_trait;
 use imageproc::image::Rgb;
@@ -59,7 +59,7 @@ impl TileSource for Operation {
 			.source
 			.get_tile_stream(bbox)
 			.await?
-			.map_item_parallel(move |mut tile| {
+			.try_map_item_parallel(move |mut tile| {
 				if tile.as_image()?.has_alpha() {
 					let format = tile.format();
 					let image = tile.into_image()?.into_flattened(color)?;
@@ -68,15 +68,14 @@ impl TileSource for Operation {
 				} else {
 					Ok(tile)
 				}
-			})
-			.unwrap_results())
+			}))
 	}
 }
 
 pub struct Factory {}
 
 impl OperationFactoryTrait for Factory {
-	fn get_docs(&self) -> String {
+	fn get_docs(&self) -> OperationDocs {
 		Args::get_docs()
 	}
 	fn get_tag_name(&self) -> &str {
@@ -111,11 +110,11 @@ mod tests {
 			.await?;
 
 		let bbox = TileCoord::new(2, 1, 1)?.to_tile_bbox();
-		let image = op.get_tile_stream(bbox).await?.next().await.unwrap().1.into_image()?;
+		let image = op.get_tile_stream(bbox).await?.next().await?.unwrap().1.into_image()?;
 		assert_eq!(image.average_color(), [238, 119, 0]);
 
 		let bbox = TileCoord::new(2, 2, 1)?.to_tile_bbox();
-		let image = op.get_tile_stream(bbox).await?.next().await.unwrap().1.into_image()?;
+		let image = op.get_tile_stream(bbox).await?.next().await?.unwrap().1.into_image()?;
 		assert_eq!(image.average_color(), [254, 135, 16]);
 
 		Ok(())
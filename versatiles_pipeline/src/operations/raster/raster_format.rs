@@ -1,4 +1,4 @@
-use crate::{PipelineFactory, traits::*, vpl::VPLNode};
+use crate::{OperationDocs, PipelineFactory, traits::*, vpl::VPLNode};
 use anyhow::{Result, bail, ensure};
 use async_trait::async_trait;
 use std::{fmt::Debug, str, sync::Arc};
@@ -6,7 +6,7 @@ use versatiles_container::{SourceType, Tile, TileSource, TileSourceMetadata};
 use versatiles_core::*;
 use versatiles_derive::context;
 
-#[derive(versatiles_derive::VPLDecode, Clone, Debug)]
+#[derive(versatiles_derive::VPLDecode, versatiles_derive::VPLValidate, Clone, Debug)]
 /// Filter tiles by bounding box and/or zoom levels.
 struct Args {
 	/// The desired tile format. Allowed values are: AVIF, JPG, PNG or WEBP.
@@ -17,6 +17,7 @@ struct Args {
 	/// "80,70,14:50,15:20", where the first value is the default quality, and the other values specify the quality for the specified zoom level (and higher).
 	quality: Option<String>,
 	/// Compression speed (only AVIF), between 0 (slowest) and 100 (fastest).
+	#[vpl(max = 100)]
 	speed: Option<u8>,
 }
 
@@ -160,19 +161,17 @@ impl TileSource for Operation {
 		let stream = self.source.get_tile_stream(bbox).await?;
 		let format: TileFormat = self.format.into();
 
-		Ok(stream
-			.map_item_parallel(move |mut tile| {
-				tile.change_format(format, quality, speed)?;
-				Ok(tile)
-			})
-			.unwrap_results())
+		Ok(stream.try_map_item_parallel(move |mut tile| {
+			tile.change_format(format, quality, speed)?;
+			Ok(tile)
+		}))
 	}
 }
 
 pub struct Factory {}
 
 impl OperationFactoryTrait for Factory {
-	fn get_docs(&self) -> String {
+	fn get_docs(&self) -> OperationDocs {
 		Args::get_docs()
 	}
 	fn get_tag_name(&self) -> &str {
@@ -280,10 +279,20 @@ mod tests {
 
 		// Stream should still yield exactly one tile and the tile should be WEBP now
 		let bbox = TileCoord::new(3, 2, 2)?.to_tile_bbox();
-		let mut items = op.get_tile_stream(bbox).await?.to_vec().await;
+		let mut items = op.get_tile_stream(bbox).await?.to_vec().await.unwrap();
 		assert_eq!(items.len(), 1, "expected exactly one tile at z=3, x=2, y=2");
 		let (_coord, tile) = items.remove(0);
 		assert_eq!(tile.format(), TileFormat::WEBP);
 		Ok(())
 	}
+
+	#[tokio::test]
+	async fn test_raster_format_speed_out_of_range() {
+		let factory = PipelineFactory::new_dummy();
+		let result = factory
+			.operation_from_vpl("from_debug format=png | raster_format format=avif speed=101")
+			.await;
+		let err = format!("{:?}", result.err().unwrap());
+		assert!(err.contains("'speed' must be at most 100, but is 101"), "{err}");
+	}
 }
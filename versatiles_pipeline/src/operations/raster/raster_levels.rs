@@ -1,4 +1,4 @@
-use crate::{PipelineFactory, traits::*, vpl::VPLNode};
+use crate::{OperationDocs, PipelineFactory, traits::*, vpl::VPLNode};
 use anyhow::Result;
 use async_trait::async_trait;
 use std::{fmt::Debug, sync::Arc};
@@ -10,11 +10,14 @@ use versatiles_image::traits::*;
 #[derive(versatiles_derive::VPLDecode, Clone, Debug)]
 /// Adjust brightness, contrast and gamma of raster tiles.
 struct Args {
-	/// Brightness adjustment, between -255 and 255. Defaults to 0.0 (no change).
+	/// Brightness adjustment, between -255 and 255. The default makes no change.
+	#[vpl_default("0.0")]
 	brightness: Option<f32>,
-	/// Contrast adjustment, between 0 and infinity. Defaults to 1.0 (no change).
+	/// Contrast adjustment, between 0 and infinity. The default makes no change.
+	#[vpl_default("1.0")]
 	contrast: Option<f32>,
-	/// Gamma adjustment, between 0 and infinity. Defaults to 1.0 (no change).
+	/// Gamma adjustment, between 0 and infinity. The default makes no change.
+	#[vpl_default("1.0")]
 	gamma: Option<f32>,
 }
 
@@ -68,21 +71,20 @@ impl TileSource for Operation {
 			.source
 			.get_tile_stream(bbox)
 			.await?
-			.map_item_parallel(move |mut tile| {
+			.try_map_item_parallel(move |mut tile| {
 				tile.as_image_mut()?.mut_color_values(|v| {
 					let v = ((v as f32 - 127.5) * contrast + 0.5 + brightness).powf(gamma) * 255.0;
 					v.round().clamp(0.0, 255.0) as u8
 				});
 				Ok(tile)
-			})
-			.unwrap_results())
+			}))
 	}
 }
 
 pub struct Factory {}
 
 impl OperationFactoryTrait for Factory {
-	fn get_docs(&self) -> String {
+	fn get_docs(&self) -> OperationDocs {
 		Args::get_docs()
 	}
 	fn get_tag_name(&self) -> &str {
@@ -144,7 +146,8 @@ mod tests {
 			.get_tile_stream(TileBBox::from_min_and_max(8, 56, 56, 56, 56)?)
 			.await?
 			.to_vec()
-			.await;
+			.await
+			.unwrap();
 		assert_eq!(tiles.len(), 1);
 		assert_eq!(tiles[0].1.as_image()?.average_color(), color_out);
 		Ok(())
@@ -171,7 +174,7 @@ mod tests {
 			.await?;
 
 		let bbox = TileCoord::new(3, 2, 1)?.to_tile_bbox();
-		let adj = op.get_tile_stream(bbox).await?.next().await.unwrap().1.into_image()?;
+		let adj = op.get_tile_stream(bbox).await?.next().await?.unwrap().1.into_image()?;
 		assert_eq!(adj.average_color(), expected_color);
 		Ok(())
 	}
@@ -1,4 +1,4 @@
-use crate::{PipelineFactory, traits::*, vpl::VPLNode};
+use crate::{OperationDocs, PipelineFactory, traits::*, vpl::VPLNode};
 use anyhow::{Result, ensure};
 use async_trait::async_trait;
 use moka::future::Cache;
@@ -9,13 +9,15 @@ use versatiles_derive::context;
 use versatiles_image::{DynamicImage, traits::*};
 
 #[derive(versatiles_derive::VPLDecode, Clone, Debug)]
-/// Raster overscale operation - generates tiles beyond the source's native resolution.
+/// Raster overscale operation - generates tiles beyond the source's native resolution ("overzooming"). Only raster sources are supported.
 struct Args {
 	/// use this zoom level to build the overscale. Defaults to the maximum zoom level of the source.
 	level_base: Option<u8>,
-	/// use this as maximum zoom level. Defaults to 30.
+	/// use this as maximum zoom level.
+	#[vpl_default("30")]
 	level_max: Option<u8>,
-	/// Enable tile climbing when overscaling. Defaults to false.
+	/// Enable tile climbing when overscaling.
+	#[vpl_default("false")]
 	enable_climbing: Option<bool>,
 }
 
@@ -52,6 +54,11 @@ impl Operation {
 	{
 		let args = Args::from_vpl_node(&vpl_node)?;
 		let mut metadata = source.as_ref().metadata().clone();
+		ensure!(
+			metadata.tile_format.is_raster(),
+			"raster_overscale only supports raster tiles, but the source provides '{}' tiles",
+			metadata.tile_format
+		);
 
 		let level_base = args
 			.level_base
@@ -60,8 +67,8 @@ impl Operation {
 
 		let level_max = args.level_max.unwrap_or(30).clamp(level_base, 30);
 
-		let mut level_bbox = *metadata.bbox_pyramid.get_level_bbox(level_base);
-		while level_bbox.level <= level_max {
+		let mut level_bbox = *metadata.bbox_pyramid.get_bounding_bbox(level_base);
+		while level_bbox.level < level_max {
 			level_bbox.level_up();
 			metadata.bbox_pyramid.set_level_bbox(level_bbox);
 		}
@@ -130,7 +137,7 @@ impl Operation {
 		let bbox = coord.to_tile_bbox();
 		let mut stream = self.source.get_tile_stream(bbox).await?;
 
-		if let Some((found_coord, tile)) = stream.next().await
+		if let Some((found_coord, tile)) = stream.next().await?
 			&& found_coord == coord
 		{
 			let image = Arc::new(tile.into_image()?);
@@ -234,7 +241,7 @@ impl TileSource for Operation {
 pub struct Factory {}
 
 impl OperationFactoryTrait for Factory {
-	fn get_docs(&self) -> String {
+	fn get_docs(&self) -> OperationDocs {
 		Args::get_docs()
 	}
 	fn get_tag_name(&self) -> &str {
@@ -259,7 +266,7 @@ impl TransformOperationFactoryTrait for Factory {
 #[cfg(test)]
 mod tests {
 	use super::*;
-	use crate::helpers::dummy_image_source::DummyImageSource;
+	use crate::helpers::{dummy_image_source::DummyImageSource, dummy_vector_source::DummyVectorSource};
 	use rstest::rstest;
 	use versatiles_image::DynamicImage;
 
@@ -277,7 +284,7 @@ mod tests {
 	async fn get_avg(op: &Operation, coord: (u8, u8, u8), scale: u32) -> Vec<u8> {
 		let (level, x, y) = coord;
 		let coord = TileCoord::new(level, x as u32, y as u32).unwrap().to_tile_bbox();
-		let mut tiles = op.get_tile_stream(coord).await.unwrap().to_vec().await;
+		let mut tiles = op.get_tile_stream(coord).await.unwrap().to_vec().await.unwrap();
 		assert_eq!(tiles.len(), 1);
 		let mut tile = tiles.pop().unwrap().1;
 		let image = tile.as_image().unwrap();
@@ -348,17 +355,17 @@ mod tests {
 
 		// Should work for tiles at level_base (z=2)
 		let coord_base = TileCoord::new(2, 0, 0)?.to_tile_bbox();
-		let tiles = op.get_tile_stream(coord_base).await?.to_vec().await;
+		let tiles = op.get_tile_stream(coord_base).await?.to_vec().await.unwrap();
 		assert_eq!(tiles.len(), 1, "Should return tile at level_base");
 
 		// Should work for tiles above level_base (extracted from level_base)
 		let coord_high = TileCoord::new(3, 0, 0)?.to_tile_bbox();
-		let tiles = op.get_tile_stream(coord_high).await?.to_vec().await;
+		let tiles = op.get_tile_stream(coord_high).await?.to_vec().await.unwrap();
 		assert_eq!(tiles.len(), 1, "Should extract from level_base for high zoom");
 
 		// Multiple high-zoom tiles should reuse cached base tile
 		let coord_high2 = TileCoord::new(3, 1, 0)?.to_tile_bbox();
-		let tiles2 = op.get_tile_stream(coord_high2).await?.to_vec().await;
+		let tiles2 = op.get_tile_stream(coord_high2).await?.to_vec().await.unwrap();
 		assert_eq!(tiles2.len(), 1, "Should also work for adjacent tile");
 
 		Ok(())
@@ -379,9 +386,73 @@ mod tests {
 
 		// Should work with climbing enabled
 		let coord = TileCoord::new(3, 0, 0)?.to_tile_bbox();
-		let tiles = op.get_tile_stream(coord).await?.to_vec().await;
+		let tiles = op.get_tile_stream(coord).await?.to_vec().await.unwrap();
 		assert_eq!(tiles.len(), 1, "Should return tile with climbing enabled");
 
 		Ok(())
 	}
+
+	#[tokio::test]
+	async fn bbox_pyramid_ends_at_level_max() -> Result<()> {
+		let source =
+			DummyImageSource::from_color(&[255, 0, 0], 256, TileFormat::PNG, Some(TileBBoxPyramid::new_full(2)))?;
+		let op = Operation::build(
+			VPLNode::try_from_str("raster_overscale level_max=5")?,
+			Box::new(source),
+			&PipelineFactory::new_dummy(),
+		)
+		.await?;
+
+		assert_eq!(op.metadata().bbox_pyramid.get_level_min(), Some(0));
+		assert_eq!(op.metadata().bbox_pyramid.get_level_max(), Some(5));
+
+		let bbox = TileCoord::new(6, 0, 0)?.to_tile_bbox();
+		assert!(op.get_tile_stream(bbox).await?.to_vec().await.unwrap().is_empty());
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn quadrants_are_upscaled_to_pure_tiles() -> Result<()> {
+		const COLORS: [[u8; 3]; 4] = [[255, 0, 0], [0, 255, 0], [0, 0, 255], [255, 255, 0]];
+
+		// 2×2 colored image: red, green / blue, yellow
+		let image = DynamicImage::from_fn(256, 256, |x, y| COLORS[(x / 128 + (y / 128) * 2) as usize]);
+		let source = DummyImageSource::from_image(image, TileFormat::PNG, Some(TileBBoxPyramid::new_full(2)))?;
+		let op = Operation::build(
+			VPLNode::try_from_str("raster_overscale level_max=3")?,
+			Box::new(source),
+			&PipelineFactory::new_dummy(),
+		)
+		.await?;
+
+		for (index, color) in COLORS.iter().enumerate() {
+			let coord = TileCoord::new(3, index as u32 % 2, index as u32 / 2)?;
+			let mut tiles = op.get_tile_stream(coord.to_tile_bbox()).await?.to_vec().await.unwrap();
+			assert_eq!(tiles.len(), 1);
+			let image = tiles.pop().unwrap().1.into_image()?.to_rgb8();
+			assert_eq!((image.width(), image.height()), (256, 256));
+
+			// Resampling may only blend colors at the border that touches the neighbouring quadrant.
+			for (x, y) in [(16, 16), (128, 128), (240, 16), (16, 240), (240, 240)] {
+				assert_eq!(&image.get_pixel(x, y).0, color, "pixel ({x}, {y}) of tile {coord:?}");
+			}
+		}
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn vector_sources_are_rejected() {
+		let source = DummyVectorSource::new(&[("layer", &[&[("key", "value")]])], None);
+		let error = Operation::build(
+			VPLNode::try_from_str("raster_overscale level_max=18").unwrap(),
+			Box::new(source),
+			&PipelineFactory::new_dummy(),
+		)
+		.await
+		.unwrap_err();
+		assert_eq!(
+			error.chain().last().unwrap().to_string(),
+			"raster_overscale only supports raster tiles, but the source provides 'mvt' tiles"
+		);
+	}
 }
@@ -1,4 +1,4 @@
-use crate::{PipelineFactory, traits::*, vpl::VPLNode};
+use crate::{OperationDocs, PipelineFactory, traits::*, vpl::VPLNode};
 use anyhow::{Result, bail, ensure};
 use async_trait::async_trait;
 use dashmap::DashMap;
@@ -16,7 +16,7 @@ static BLOCK_TILE_COUNT: u32 = 32;
 struct Args {
 	/// use this zoom level to build the overview. Defaults to the maximum zoom level of the source.
 	level: Option<u8>,
-	/// Size of the tiles in pixels. Defaults to 512.
+	/// Size of the tiles in pixels. Defaults to the tile size of the source, or 512 if unknown.
 	tile_size: Option<u32>,
 }
 
@@ -46,7 +46,7 @@ impl Operation {
 			.level
 			.unwrap_or_else(|| source.metadata().bbox_pyramid.get_level_max().unwrap());
 
-		let mut level_bbox = *metadata.bbox_pyramid.get_level_bbox(level_base);
+		let mut level_bbox = *metadata.bbox_pyramid.get_bounding_bbox(level_base);
 		while level_bbox.level > 0 {
 			level_bbox.level_down();
 			metadata.bbox_pyramid.set_level_bbox(level_bbox);
@@ -55,7 +55,10 @@ impl Operation {
 		let mut tilejson = source.tilejson().clone();
 		metadata.update_tilejson(&mut tilejson);
 
-		let tile_size = args.tile_size.unwrap_or(512);
+		let tile_size = args
+			.tile_size
+			.or_else(|| metadata.tile_size.map(|size| u32::from(size.size())))
+			.unwrap_or(512);
 		let cache = Arc::new(DashMap::new());
 		metadata.traversal = Traversal::new(TraversalOrder::DepthFirst, BLOCK_TILE_COUNT, BLOCK_TILE_COUNT)?;
 
@@ -198,16 +201,16 @@ impl TileSource for Operation {
 
 		let container: TileBBoxMap<Option<DynamicImage>> = if bbox.level == self.level_base {
 			log::trace!("Fetching images from source for bbox {:?}", bbox);
-			TileBBoxMap::<Option<DynamicImage>>::from_stream(
+			TileBBoxMap::<Option<DynamicImage>>::from_iter(
 				bbox,
 				self
 					.source
 					.get_tile_stream(bbox)
 					.await?
 					.map_item_parallel(|tile| tile.into_image())
-					.unwrap_results(),
-			)
-			.await?
+					.try_to_vec()
+					.await?,
+			)?
 		} else {
 			log::trace!("Building images from cache for bbox {:?}", bbox);
 			self.build_images_from_cache(bbox0).await?
@@ -241,7 +244,7 @@ impl TileSource for Operation {
 pub struct Factory {}
 
 impl OperationFactoryTrait for Factory {
-	fn get_docs(&self) -> String {
+	fn get_docs(&self) -> OperationDocs {
 		Args::get_docs()
 	}
 	fn get_tag_name(&self) -> &str {
@@ -6,7 +6,7 @@
 //! [`TileSource`] so that the rest of the pipeline can treat it like any
 //! other data source.
 
-use crate::{PipelineFactory, operations::read::traits::ReadTileSource, traits::*, vpl::VPLNode};
+use crate::{OperationDocs, PipelineFactory, operations::read::traits::ReadTileSource, traits::*, vpl::VPLNode};
 use anyhow::Result;
 use async_trait::async_trait;
 use std::fmt::Debug;
@@ -18,7 +18,7 @@ use versatiles_derive::context;
 /// Reads a tile container, such as a `*.versatiles`, `*.mbtiles`, `*.pmtiles` or `*.tar` file.
 struct Args {
 	/// The filename of the tile container. This is relative to the path of the VPL file.
-	/// For example: `filename="world.versatiles"`.
+	/// For example: `filename="world.versatiles"`. A `*.vpl` file is included as a pipeline.
 	filename: String,
 }
 
@@ -39,7 +39,14 @@ impl ReadTileSource for Operation {
 		Self: Sized + TileSource,
 	{
 		let args = Args::from_vpl_node(&vpl_node)?;
-		let source = factory.get_reader(&factory.resolve_filename(&args.filename)).await?;
+		let source = if args.filename.ends_with(".vpl") {
+			// built by this factory, so that include cycles are detected
+			factory
+				.operation_from_vpl_file(&factory.resolve_path(&args.filename))
+				.await?
+		} else {
+			factory.get_reader(&factory.resolve_filename(&args.filename)).await?
+		};
 		let mut tilejson = source.tilejson().clone();
 		source.metadata().update_tilejson(&mut tilejson);
 
@@ -77,7 +84,7 @@ impl TileSource for Operation {
 pub struct Factory {}
 
 impl OperationFactoryTrait for Factory {
-	fn get_docs(&self) -> String {
+	fn get_docs(&self) -> OperationDocs {
 		Args::get_docs()
 	}
 	fn get_tag_name(&self) -> &str {
@@ -92,6 +99,28 @@ impl ReadOperationFactoryTrait for Factory {
 	}
 }
 
+/// Registers `from_container` under the tag name `read`.
+pub struct ReadFactory {}
+
+impl OperationFactoryTrait for ReadFactory {
+	fn get_docs(&self) -> OperationDocs {
+		OperationDocs {
+			description: "Alias of `from_container`. Reads a tile container and picks the reader by the file extension, e.g. `*.versatiles`, `*.mbtiles` or `*.pmtiles`.",
+			..Args::get_docs()
+		}
+	}
+	fn get_tag_name(&self) -> &str {
+		"read"
+	}
+}
+
+#[async_trait]
+impl ReadOperationFactoryTrait for ReadFactory {
+	async fn build<'a>(&self, vpl_node: VPLNode, factory: &'a PipelineFactory) -> Result<Box<dyn TileSource>> {
+		Operation::build(vpl_node, factory).await
+	}
+}
+
 #[cfg(test)]
 pub fn operation_from_reader(reader: Box<dyn TileSource>) -> Box<dyn TileSource> {
 	let mut tilejson = reader.tilejson().clone();
@@ -145,7 +174,7 @@ mod tests {
 			.await?;
 
 		let mut n = 0;
-		while let Some((coord, tile)) = stream.next().await {
+		while let Some((coord, tile)) = stream.next().await? {
 			assert!(tile.into_blob(Uncompressed)?.len() > 50);
 			assert!(coord.x >= 1 && coord.x <= 2);
 			assert!(coord.y >= 1 && coord.y <= 3);
@@ -195,7 +224,7 @@ mod tests {
 			.await?;
 
 		let mut n = 0;
-		while let Some((coord, tile)) = stream.next().await {
+		while let Some((coord, tile)) = stream.next().await? {
 			assert!(tile.into_blob(Uncompressed)?.len() > 50);
 			assert!(coord.x >= 1 && coord.x <= 2);
 			assert!(coord.y >= 1 && coord.y <= 3);
@@ -17,22 +17,47 @@
 mod image;
 mod vector;
 
-use crate::{PipelineFactory, operations::read::traits::ReadTileSource, traits::*, vpl::VPLNode};
+use crate::{
+	OperationDocs, PipelineFactory, PropertyDocs, operations::read::traits::ReadTileSource, traits::*, vpl::VPLNode,
+};
 use anyhow::{Result, bail};
 use async_trait::async_trait;
 use image::create_debug_image;
 use std::{fmt::Debug, sync::Arc};
 use vector::create_debug_vector_tile;
-use versatiles_container::{SourceType, Tile, TileSource, TileSourceMetadata, Traversal};
+use versatiles_container::{SourceType, Tile, TileSource, TileSourceMetadata};
 use versatiles_core::*;
 
-#[derive(versatiles_derive::VPLDecode, Clone, Debug)]
 /// Generates debug tiles that display their coordinates as text.
+#[derive(Clone, Debug)]
 struct Args {
 	/// Target tile format: one of `"mvt"` (default), `"avif"`, `"jpg"`, `"png"` or `"webp"`
 	format: Option<String>,
 }
 
+impl Args {
+	fn from_vpl_node(node: &VPLNode) -> Result<Self> {
+		node.ensure_known_properties(&["format"])?;
+		Ok(Self {
+			format: node.get_optional_string("format"),
+		})
+	}
+
+	fn get_docs() -> OperationDocs {
+		OperationDocs {
+			description: "Generates debug tiles that display their coordinates as text.",
+			sources: None,
+			properties: vec![PropertyDocs {
+				name: "format",
+				type_name: "String",
+				required: false,
+				default: Some("mvt"),
+				doc: "Target tile format: one of `\"mvt\"`, `\"avif\"`, `\"jpg\"`, `\"png\"` or `\"webp\"`",
+			}],
+		}
+	}
+}
+
 /// Implements [`TileSource`] by fabricating debug tiles entirely in
 /// memory.  No I/O other than the caller’s request/response is performed.
 #[derive(Debug)]
@@ -43,12 +68,11 @@ pub struct Operation {
 
 impl Operation {
 	pub fn from_parameters(tile_format: TileFormat) -> Result<Self> {
-		let metadata = TileSourceMetadata::new(
-			tile_format,
-			TileCompression::Uncompressed,
-			TileBBoxPyramid::new_full(30),
-			Traversal::ANY,
-		);
+		let metadata = TileSourceMetadata::new_builder()
+			.with_format(tile_format)
+			.with_compression(TileCompression::Uncompressed)
+			.with_bbox_pyramid(TileBBoxPyramid::new_full(30))
+			.build()?;
 
 		let mut tilejson = TileJSON::default();
 
@@ -127,7 +151,7 @@ impl TileSource for Operation {
 pub struct Factory {}
 
 impl OperationFactoryTrait for Factory {
-	fn get_docs(&self) -> String {
+	fn get_docs(&self) -> OperationDocs {
 		Args::get_docs()
 	}
 	fn get_tag_name(&self) -> &str {
@@ -158,7 +182,7 @@ mod tests {
 			.get_tile_stream(coord.to_tile_bbox())
 			.await?
 			.next()
-			.await
+			.await?
 			.unwrap()
 			.1;
 
@@ -170,7 +194,7 @@ mod tests {
 			.await?;
 
 		let mut n = 0;
-		while let Some((coord, tile)) = stream.next().await {
+		while let Some((coord, tile)) = stream.next().await? {
 			assert!(!tile.into_blob(Uncompressed)?.is_empty(), "for '{format}'");
 			assert!(coord.x >= 1 && coord.x <= 2, "for '{format}'");
 			assert!(coord.y >= 1 && coord.y <= 3, "for '{format}'");
@@ -182,6 +206,19 @@ mod tests {
 		Ok(())
 	}
 
+	#[test]
+	fn test_args() -> Result<()> {
+		let args = Args::from_vpl_node(&VPLNode::try_from_str("from_debug format=png")?)?;
+		assert_eq!(args.format.as_deref(), Some("png"));
+
+		let args = Args::from_vpl_node(&VPLNode::try_from_str("from_debug")?)?;
+		assert_eq!(args.format, None);
+
+		let err = Args::from_vpl_node(&VPLNode::try_from_str("from_debug fromat=png")?).unwrap_err();
+		assert!(err.to_string().contains("does not support the argument 'fromat'"));
+		Ok(())
+	}
+
 	#[tokio::test]
 	async fn test_build_tile_avif() {
 		test(
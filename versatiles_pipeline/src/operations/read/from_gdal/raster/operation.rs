@@ -7,12 +7,12 @@
 //! other data source.
 
 use super::RasterSource;
-use crate::{PipelineFactory, operations::read::traits::ReadTileSource, traits::*, vpl::VPLNode};
+use crate::{OperationDocs, PipelineFactory, operations::read::traits::ReadTileSource, traits::*, vpl::VPLNode};
 use anyhow::Result;
 use async_trait::async_trait;
 use imageproc::image::DynamicImage;
 use std::{fmt::Debug, sync::Arc, vec};
-use versatiles_container::{SourceType, Tile, TileSource, TileSourceMetadata, Traversal};
+use versatiles_container::{SourceType, Tile, TileSource, TileSourceMetadata};
 use versatiles_core::*;
 use versatiles_derive::context;
 use versatiles_image::traits::*;
@@ -24,20 +24,24 @@ struct Args {
 	/// The filename of the GDAL raster dataset to read.
 	/// For example: `filename="world.tif"`.
 	filename: String,
-	/// The size of the generated tiles in pixels. (default: 512)
+	/// The size of the generated tiles in pixels.
+	#[vpl_default("512")]
 	tile_size: Option<u32>,
-	/// The tile format to use for the output tiles. (default: `PNG`)
+	/// The tile format to use for the output tiles.
+	#[vpl_default("PNG")]
 	tile_format: Option<TileFormat>,
 	/// The maximum zoom level to generate tiles for.
 	/// (default: the maximum zoom level based on the dataset's native resolution)
 	level_max: Option<u8>,
 	/// The minimum zoom level to generate tiles for. (default: level_max)
 	level_min: Option<u8>,
-	/// How often to reuse an GDAL instances. (default: 100)
+	/// How often to reuse an GDAL instances.
 	/// Set to a lower value if you have problems like memory leaks in GDAL.
+	#[vpl_default("100")]
 	gdal_reuse_limit: Option<u32>,
-	/// The number of maximum concurrent GDAL instances to allow. (default: 4)
+	/// The number of maximum concurrent GDAL instances to allow.
 	/// Set to a higher value if you have enough system resources and want to increase throughput.
+	#[vpl_default("4")]
 	gdal_concurrency_limit: Option<u8>,
 }
 
@@ -89,12 +93,12 @@ impl Operation {
 		);
 		let bbox_pyramid = TileBBoxPyramid::from_geo_bbox(level_min, level_max, bbox);
 
-		let metadata = TileSourceMetadata::new(
-			args.tile_format.unwrap_or(TileFormat::PNG),
-			TileCompression::Uncompressed,
-			bbox_pyramid,
-			Traversal::ANY,
-		);
+		let mut metadata = TileSourceMetadata::new_builder()
+			.with_format(args.tile_format.unwrap_or(TileFormat::PNG))
+			.with_compression(TileCompression::Uncompressed)
+			.with_bbox_pyramid(bbox_pyramid)
+			.build()?;
+		metadata.tile_size = u16::try_from(tile_size).ok().and_then(|size| TileSize::new(size).ok());
 		log::trace!(
 			"Parameters: format={:?}, compression={:?}",
 			metadata.tile_format,
@@ -229,7 +233,7 @@ impl TileSource for Operation {
 pub struct Factory {}
 
 impl OperationFactoryTrait for Factory {
-	fn get_docs(&self) -> String {
+	fn get_docs(&self) -> OperationDocs {
 		Args::get_docs()
 	}
 	fn get_tag_name(&self) -> &str {
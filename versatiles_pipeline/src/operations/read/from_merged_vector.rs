@@ -15,7 +15,7 @@
 //!    pyramid handling.
 
 use crate::{
-	PipelineFactory,
+	OperationDocs, PipelineFactory,
 	operations::read::traits::ReadTileSource,
 	traits::*,
 	vpl::{VPLNode, VPLPipeline},
@@ -101,7 +101,12 @@ impl ReadTileSource for Operation {
 			);
 		}
 
-		let metadata = TileSourceMetadata::new(tile_format, tile_compression, pyramid, traversal);
+		let metadata = TileSourceMetadata::new_builder()
+			.with_format(tile_format)
+			.with_compression(tile_compression)
+			.with_bbox_pyramid(pyramid)
+			.with_traversal(traversal)
+			.build()?;
 		metadata.update_tilejson(&mut tilejson);
 
 		Ok(Box::new(Self {
@@ -140,7 +145,7 @@ impl TileSource for Operation {
 				let mut tiles = TileBBoxMap::<Vec<VectorTile>>::new_default(bbox);
 
 				for source in self.sources.iter() {
-					source
+					let result = source
 						.get_tile_stream(bbox)
 						.await
 						.unwrap()
@@ -148,6 +153,9 @@ impl TileSource for Operation {
 							tiles.get_mut(&coord).unwrap().push(tile.into_vector().unwrap());
 						})
 						.await;
+					if let Err(error) = result {
+						return TileStream::failed(error);
+					}
 				}
 
 				let format = self.metadata.tile_format;
@@ -175,7 +183,7 @@ impl TileSource for Operation {
 pub struct Factory {}
 
 impl OperationFactoryTrait for Factory {
-	fn get_docs(&self) -> String {
+	fn get_docs(&self) -> OperationDocs {
 		Args::get_docs()
 	}
 	fn get_tag_name(&self) -> &str {
@@ -300,7 +308,7 @@ mod tests {
 			.await?;
 
 		let bbox = TileBBox::new_full(3)?;
-		let tiles = result.get_tile_stream(bbox).await?.to_vec().await;
+		let tiles = result.get_tile_stream(bbox).await?.to_vec().await.unwrap();
 
 		assert_eq!(
 			arrange_tiles(tiles, |tile| {
@@ -1,14 +1,26 @@
 //! # from_stacked operation
 //!
-//! Selects the **first** non‑empty tile from a chain of sources that all
-//! share the *same* tile type (raster *or* vector).  Think of it as a
-//! “transparent overlay”: the moment a source can deliver a tile for the
+//! Selects one non‑empty tile from a chain of sources that all share the
+//! *same* tile type (raster *or* vector).  Think of it as a “transparent
+//! overlay”: by default, the moment a source can deliver a tile for the
 //! requested coordinate, downstream sources are ignored for that tile.
 //!
 //! * Sources are evaluated in the **order** provided in the VPL list.  
-//! * No blending occurs – it is a *winner‑takes‑first* strategy.  
+//! * No blending occurs – the [`OverlayStrategy`] picks one winner per tile.
+//!   Only `first_wins` (default) can skip downstream sources; the other
+//!   strategies query every source for every tile.  
 //! * All sources must expose an identical tile type and compression; only
 //!   their spatial coverage may differ.
+//! * A source is only queried for tiles inside its bbox pyramid. To use a
+//!   source only on some zoom levels, end its pipeline with e.g.
+//!   `filter level_min=10`; the other levels skip it entirely.
+//! * Tiles are requested in grid cells of `grid_size`² tiles, and at most
+//!   `max_buffered_cells` cells are processed at the same time. With
+//!   `first_wins` the tiles of each source are yielded as soon as the source
+//!   is done with a cell; the other strategies buffer a complete cell.
+//! * With `timeout_ms` and/or `skip_after_failures` a slow or failing source
+//!   is treated as empty, and skipped for a while after repeated failures
+//!   (see [`SourceGuard`]).
 //!
 //! The file provides:
 //! 1. [`Args`] – CLI / VPL configuration,  
@@ -16,24 +28,101 @@
 //! 3. Tests that verify error handling and overlay semantics.
 
 use crate::{
-	PipelineFactory,
+	OperationDocs, PipelineFactory,
+	helpers::{GuardSettings, SourceGuard},
 	operations::read::traits::ReadTileSource,
 	traits::*,
 	vpl::{VPLNode, VPLPipeline},
 };
-use anyhow::{Result, ensure};
+use anyhow::{Result, bail, ensure};
 use async_trait::async_trait;
 use futures::{StreamExt, future::join_all, stream};
-use std::sync::Arc;
+use std::{
+	hash::{DefaultHasher, Hash, Hasher},
+	sync::Arc,
+};
 use versatiles_container::{SourceType, Tile, TileSource, TileSourceMetadata, Traversal};
 use versatiles_core::*;
 use versatiles_derive::context;
 
+/// Default edge length of the grid cells in which tiles are requested from the sources.
+pub const DEFAULT_GRID_SIZE: u32 = 32;
+
+/// Default number of grid cells that are processed at the same time.
+pub const DEFAULT_MAX_BUFFERED_CELLS: usize = 32;
+
 #[derive(versatiles_derive::VPLDecode, Clone, Debug)]
-/// Overlays multiple tile sources, using the tile from the first source that provides it.
+/// Overlays multiple tile sources, by default using the tile from the first source that provides it.
 struct Args {
 	/// All tile sources must have the same format.
+	/// A source is only used for the zoom levels and areas of its bbox pyramid, so e.g. `from_container filename="regional.versatiles" | filter level_min=10` restricts a source to zoom levels 10 and above.
 	sources: Vec<VPLPipeline>,
+
+	/// Maximum time in milliseconds to wait for a source. Slower requests count as failures and the next source is used.
+	timeout_ms: Option<u32>,
+
+	/// Skip a source after this many consecutive failures or timeouts.
+	skip_after_failures: Option<u32>,
+
+	/// How long in milliseconds a source is skipped before it is tried again.
+	#[vpl_default("30000")]
+	cool_down_ms: Option<u32>,
+
+	/// Which tile wins if several sources provide it: `first_wins`, `smallest` or `largest` (by blob size),
+	/// or `random` (a pseudo-random source per tile, stable for each coordinate).
+	#[vpl_default("first_wins")]
+	strategy: Option<String>,
+
+	/// Edge length of the grid cells in which tiles are requested from the sources.
+	#[vpl_default("32")]
+	grid_size: Option<u32>,
+
+	/// Maximum number of grid cells that are processed at the same time. Together with `grid_size` this bounds the number of buffered tiles.
+	#[vpl_default("32")]
+	max_buffered_cells: Option<u32>,
+}
+
+/// Selects the winning tile if several sources provide one.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum OverlayStrategy {
+	/// The tile of the first source in the list.
+	FirstWins,
+	/// The tile with the smallest blob.
+	Smallest,
+	/// The tile with the largest blob.
+	Largest,
+	/// The tile of a pseudo-random source, derived from the coordinate.
+	Random,
+}
+
+impl OverlayStrategy {
+	fn parse(value: Option<&str>) -> Result<Self> {
+		Ok(match value.unwrap_or("first_wins") {
+			"first_wins" => Self::FirstWins,
+			"smallest" => Self::Smallest,
+			"largest" => Self::Largest,
+			"random" => Self::Random,
+			value => bail!("strategy must be 'first_wins', 'smallest', 'largest' or 'random', but is '{value}'"),
+		})
+	}
+
+	/// Ranks the tile of source `index`. The lowest rank wins, ties go to the earlier source.
+	fn rank(self, index: usize, coord: &TileCoord, tile: &mut Tile) -> Result<u64> {
+		Ok(match self {
+			Self::FirstWins => index as u64,
+			Self::Smallest => blob_size(tile)?,
+			Self::Largest => u64::MAX - blob_size(tile)?,
+			Self::Random => {
+				let mut hasher = DefaultHasher::new();
+				(coord.level, coord.x, coord.y, index).hash(&mut hasher);
+				hasher.finish()
+			}
+		})
+	}
+}
+
+fn blob_size(tile: &mut Tile) -> Result<u64> {
+	Ok(tile.as_blob(tile.compression())?.len())
 }
 
 #[derive(Debug)]
@@ -46,6 +135,10 @@ struct Args {
 struct Operation {
 	metadata: TileSourceMetadata,
 	sources: Vec<Box<dyn TileSource>>,
+	guards: Vec<SourceGuard>,
+	strategy: OverlayStrategy,
+	grid_size: u32,
+	max_buffered_cells: usize,
 	tilejson: TileJSON,
 }
 
@@ -61,19 +154,32 @@ impl ReadTileSource for Operation {
 			.into_iter()
 			.collect::<Result<Vec<_>>>()?;
 
-		Ok(Box::new(Operation::new(sources)?) as Box<dyn TileSource>)
+		let settings = GuardSettings::from_millis(args.timeout_ms, args.skip_after_failures, args.cool_down_ms);
+		let strategy = OverlayStrategy::parse(args.strategy.as_deref())?;
+
+		let mut operation = Operation::new(sources, settings, strategy)?;
+		if let Some(grid_size) = args.grid_size {
+			ensure!(grid_size > 0, "grid_size must be greater than 0");
+			operation.grid_size = grid_size;
+		}
+		if let Some(max_buffered_cells) = args.max_buffered_cells {
+			ensure!(max_buffered_cells > 0, "max_buffered_cells must be greater than 0");
+			operation.max_buffered_cells = max_buffered_cells as usize;
+		}
+
+		Ok(Box::new(operation) as Box<dyn TileSource>)
 	}
 }
 
 impl Operation {
 	#[context("Failed to create from_stacked operation")]
-	fn new(sources: Vec<Box<dyn TileSource>>) -> Result<Operation> {
+	fn new(sources: Vec<Box<dyn TileSource>>, settings: GuardSettings, strategy: OverlayStrategy) -> Result<Operation> {
 		ensure!(sources.len() > 1, "must have at least two sources");
 
 		let mut tilejson = TileJSON::default();
-		let parameters = sources.first().unwrap().metadata();
-		let tile_format = parameters.tile_format;
-		let tile_compression = parameters.tile_compression;
+		let first_metadata = sources.first().unwrap().metadata();
+		let tile_format = first_metadata.tile_format;
+		let tile_compression = first_metadata.tile_compression;
 
 		let mut pyramid = TileBBoxPyramid::new_empty();
 		let mut traversal = Traversal::default();
@@ -85,21 +191,144 @@ impl Operation {
 			traversal.intersect(&metadata.traversal)?;
 			pyramid.include_bbox_pyramid(&metadata.bbox_pyramid);
 
+			let report = first_metadata.compatible_with(metadata);
 			ensure!(
-				metadata.tile_format == tile_format,
-				"all sources must have the same tile format"
+				report.format_ok,
+				"all sources must have the same tile format ({tile_format} vs {})",
+				metadata.tile_format
 			);
+			ensure!(
+				report.tile_size_ok,
+				"all sources must have the same tile size ({:?} vs {:?})",
+				first_metadata.tile_size.map(|s| s.size()),
+				metadata.tile_size.map(|s| s.size())
+			);
+			for warning in report.warnings {
+				log::warn!("from_stacked: {warning}");
+			}
 		}
 
-		let metadata = TileSourceMetadata::new(tile_format, tile_compression, pyramid, traversal);
+		let mut metadata = TileSourceMetadata::new_builder()
+			.with_format(tile_format)
+			.with_compression(tile_compression)
+			.with_bbox_pyramid(pyramid)
+			.with_traversal(traversal)
+			.build()?;
+		metadata.tile_size = sources.iter().find_map(|source| source.metadata().tile_size);
 		metadata.update_tilejson(&mut tilejson);
 
+		let guards = sources
+			.iter()
+			.enumerate()
+			.map(|(index, source)| SourceGuard::new(format!("#{index} ({})", source.source_type()), settings.clone()))
+			.collect();
+
 		Ok(Self {
 			tilejson,
 			metadata,
 			sources,
+			guards,
+			strategy,
+			grid_size: DEFAULT_GRID_SIZE,
+			max_buffered_cells: DEFAULT_MAX_BUFFERED_CELLS,
 		})
 	}
+
+	/// Streams the tiles of one grid cell with the `first_wins` strategy.
+	///
+	/// The sources are queried one after another for the tiles that are still missing. The tiles
+	/// of each source are yielded as soon as it is done, so only flags of the filled coordinates
+	/// are kept for the whole cell.
+	fn stream_first_wins(&self, bbox: TileBBox) -> TileStream<'_, Tile> {
+		let format = self.metadata.tile_format;
+		let sources = self.sources.iter().zip(&self.guards);
+		let filled = TileBBoxMap::<bool>::new_default(bbox);
+
+		let stream = stream::unfold((sources, filled), move |(mut sources, mut filled)| async move {
+			let mut bbox_left = TileBBox::new_empty(bbox.level).unwrap();
+			for (coord, done) in filled.iter() {
+				if !*done {
+					bbox_left.include_coord(&coord).unwrap();
+				}
+			}
+			if bbox_left.is_empty() {
+				return None;
+			}
+
+			// Sources without tiles in this area are skipped without a request.
+			let (source, guard) = sources.find(|(source, _)| source.metadata().bbox_pyramid.overlaps_bbox(&bbox_left))?;
+
+			// A chunk that timed out keeps the tiles that arrived so far.
+			let mut tiles = Vec::new();
+			guard
+				.run(async {
+					let stream = source.get_tile_stream(bbox_left).await?;
+					stream
+						.for_each_sync(|(coord, mut tile)| {
+							let done = filled.get_mut(&coord).unwrap();
+							if !*done {
+								*done = true;
+								tile.change_format(format, None, None).unwrap();
+								tiles.push((coord, tile));
+							}
+						})
+						.await
+				})
+				.await
+				.unwrap();
+			Some((stream::iter(tiles), (sources, filled)))
+		});
+		TileStream::from_stream(Box::pin(stream.flatten()))
+	}
+
+	/// Streams the tiles of one grid cell with a ranking strategy, which needs the tiles of all
+	/// sources before a winner can be picked.
+	async fn stream_ranked(&self, bbox: TileBBox) -> TileStream<'static, Tile> {
+		let format = self.metadata.tile_format;
+		let strategy = self.strategy;
+		let mut tiles = TileBBoxMap::<Option<(u64, Tile)>>::new_default(bbox);
+
+		for (index, (source, guard)) in self.sources.iter().zip(&self.guards).enumerate() {
+			if !source.metadata().bbox_pyramid.overlaps_bbox(&bbox) {
+				continue;
+			}
+			// A chunk that timed out keeps the tiles that arrived so far.
+			guard
+				.run(async {
+					let stream = source.get_tile_stream(bbox).await?;
+					stream
+						.for_each_sync(|(coord, mut tile)| {
+							let rank = strategy.rank(index, &coord, &mut tile).unwrap();
+							let entry = tiles.get_mut(&coord).unwrap();
+							if entry.as_ref().is_none_or(|(best, _)| rank < *best) {
+								*entry = Some((rank, tile));
+							}
+						})
+						.await
+				})
+				.await
+				.unwrap();
+		}
+
+		let vec = tiles
+			.into_iter()
+			.flat_map(|(coord, item)| {
+				item.map(|(_, mut tile)| {
+					tile.change_format(format, None, None).unwrap();
+					(coord, tile)
+				})
+			})
+			.collect::<Vec<_>>();
+		TileStream::from_vec(vec)
+	}
+}
+
+impl Drop for Operation {
+	fn drop(&mut self) {
+		for summary in self.guards.iter().filter_map(SourceGuard::summary) {
+			log::info!("from_stacked: {summary}");
+		}
+	}
 }
 
 #[async_trait]
@@ -119,55 +348,54 @@ impl TileSource for Operation {
 		SourceType::new_composite("from_stacked", &source_types)
 	}
 
+	/// Returns the tile selected by the strategy.
+	#[context("Failed to get stacked tile {:?}", coord)]
+	async fn get_tile(&self, coord: &TileCoord) -> Result<Option<Tile>> {
+		let mut winner: Option<(u64, Tile)> = None;
+		for (index, (source, guard)) in self.sources.iter().zip(&self.guards).enumerate() {
+			if !source.metadata().bbox_pyramid.contains_coord(coord) {
+				continue;
+			}
+			if let Some(Some(mut tile)) = guard.run(source.get_tile(coord)).await? {
+				if self.strategy == OverlayStrategy::FirstWins {
+					winner = Some((0, tile));
+					break;
+				}
+				let rank = self.strategy.rank(index, coord, &mut tile)?;
+				if winner.as_ref().is_none_or(|(best, _)| rank < *best) {
+					winner = Some((rank, tile));
+				}
+			}
+		}
+		let Some((_, mut tile)) = winner else {
+			return Ok(None);
+		};
+		tile.change_format(self.metadata.tile_format, None, None)?;
+		Ok(Some(tile))
+	}
+
 	/// Stream packed tiles intersecting `bbox` using the overlay strategy.
 	#[context("Failed to get stacked tile stream for bbox: {:?}", bbox)]
 	async fn get_tile_stream(&self, bbox: TileBBox) -> Result<TileStream<Tile>> {
 		log::debug!("get_stream {:?}", bbox);
-		// We need the desired output compression inside the closure, so copy it.
-		let format = self.metadata.tile_format;
 
-		let sub_bboxes: Vec<TileBBox> = bbox.clone().iter_bbox_grid(32).collect();
+		let sub_bboxes: Vec<TileBBox> = bbox.iter_bbox_grid(self.grid_size).collect();
+		let streams = stream::iter(sub_bboxes).map(move |bbox| async move {
+			if self.strategy == OverlayStrategy::FirstWins {
+				self.stream_first_wins(bbox)
+			} else {
+				self.stream_ranked(bbox).await
+			}
+		});
 
-		Ok(TileStream::from_streams(stream::iter(sub_bboxes).map(
-			move |bbox| async move {
-				let mut tiles = TileBBoxMap::<Option<Tile>>::new_default(bbox);
-
-				for source in self.sources.iter() {
-					let mut bbox_left = TileBBox::new_empty(bbox.level).unwrap();
-					for (coord, slot) in tiles.iter() {
-						if slot.is_none() {
-							bbox_left.include_coord(&coord).unwrap();
-						}
-					}
-					if bbox_left.is_empty() {
-						continue;
-					}
-
-					let stream = source.get_tile_stream(bbox_left).await.unwrap();
-					stream
-						.for_each_sync(|(coord, mut tile)| {
-							let entry = tiles.get_mut(&coord).unwrap();
-							if entry.is_none() {
-								tile.change_format(format, None, None).unwrap();
-								*entry = Some(tile);
-							}
-						})
-						.await;
-				}
-				let vec = tiles
-					.into_iter()
-					.flat_map(|(coord, item)| item.map(|tile| (coord, tile)))
-					.collect::<Vec<_>>();
-				TileStream::from_vec(vec)
-			},
-		)))
+		Ok(TileStream::from_streams_limited(streams, self.max_buffered_cells))
 	}
 }
 
 pub struct Factory {}
 
 impl OperationFactoryTrait for Factory {
-	fn get_docs(&self) -> String {
+	fn get_docs(&self) -> OperationDocs {
 		Args::get_docs()
 	}
 	fn get_tag_name(&self) -> &str {
@@ -183,11 +411,14 @@ impl ReadOperationFactoryTrait for Factory {
 }
 #[cfg(test)]
 mod tests {
-	use versatiles_container::TraversalOrder;
+	use versatiles_container::{MockReader, TraversalOrder};
 
 	use super::*;
 	use crate::helpers::{arrange_tiles, dummy_vector_source::DummyVectorSource};
-	use std::sync::LazyLock;
+	use std::{
+		sync::LazyLock,
+		time::{Duration, Instant},
+	};
 
 	static RESULT_PATTERN: LazyLock<Vec<String>> = LazyLock::new(|| {
 		vec![
@@ -248,6 +479,19 @@ mod tests {
 		error("from_stacked [ from_container filename=1.pbf ]").await;
 	}
 
+	#[tokio::test]
+	async fn test_operation_format_mismatch() {
+		let factory = PipelineFactory::new_dummy();
+		let error = factory
+			.operation_from_vpl("from_stacked [ from_container filename=1.pbf, from_container filename=07.png ]")
+			.await
+			.unwrap_err();
+		assert_eq!(
+			error.chain().last().unwrap().to_string(),
+			"all sources must have the same tile format (mvt vs png)"
+		);
+	}
+
 	#[tokio::test]
 	async fn test_tilejson() -> Result<()> {
 		let factory = PipelineFactory::new_dummy();
@@ -299,9 +543,45 @@ mod tests {
 
 		let bbox = TileBBox::new_full(3)?;
 
-		let tiles = result.get_tile_stream(bbox).await?.to_vec().await;
+		let tiles = result.get_tile_stream(bbox).await?.to_vec().await.unwrap();
+		assert_eq!(arrange_tiles(tiles, check_vector), *RESULT_PATTERN);
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_small_grid_cells() -> Result<()> {
+		let factory = PipelineFactory::new_dummy();
+		let result = factory
+			.operation_from_vpl(
+				&[
+					"from_stacked grid_size=2 max_buffered_cells=1 [",
+					"   from_container filename=\"🟦.pbf\" | filter bbox=[-130,-20,20,70],",
+					"   from_container filename=\"🟨.pbf\" | filter bbox=[-20,-70,130,20]",
+					"]",
+				]
+				.join(""),
+			)
+			.await?;
+
+		let tiles = result
+			.get_tile_stream(TileBBox::new_full(3)?)
+			.await?
+			.to_vec()
+			.await
+			.unwrap();
 		assert_eq!(arrange_tiles(tiles, check_vector), *RESULT_PATTERN);
 
+		let error = factory
+			.operation_from_vpl(
+				"from_stacked grid_size=0 [ from_container filename=1.pbf, from_container filename=2.pbf ]",
+			)
+			.await
+			.unwrap_err();
+		assert_eq!(
+			error.chain().last().unwrap().to_string(),
+			"grid_size must be greater than 0"
+		);
 		Ok(())
 	}
 
@@ -322,12 +602,247 @@ mod tests {
 
 		let bbox = TileBBox::new_full(3)?;
 
-		let tiles = result.get_tile_stream(bbox).await?.to_vec().await;
+		let tiles = result.get_tile_stream(bbox).await?.to_vec().await.unwrap();
 		assert_eq!(arrange_tiles(tiles, check_image), *RESULT_PATTERN);
 
 		Ok(())
 	}
 
+	/// A vector source whose every request fails after an optional delay.
+	fn broken_source(delay: Option<Duration>) -> MockReader {
+		let reader = MockReader::new_mock(vector_source("🟦.pbf").metadata().clone())
+			.unwrap()
+			.with_failure("source is broken");
+		match delay {
+			Some(delay) => reader.with_delay(delay),
+			None => reader,
+		}
+	}
+
+	fn vector_source(filename: &str) -> Box<dyn TileSource> {
+		Box::new(DummyVectorSource::new(
+			&[("dummy", &[&[("filename", filename)]])],
+			Some(TileBBoxPyramid::new_full(8)),
+		))
+	}
+
+	#[tokio::test]
+	async fn test_slow_source_is_skipped() -> Result<()> {
+		let settings = GuardSettings::from_millis(Some(50), Some(2), Some(60_000));
+		let op = Operation::new(
+			vec![
+				broken_source(Some(Duration::from_secs(10))).boxed(),
+				vector_source("🟨.pbf"),
+			],
+			settings,
+			OverlayStrategy::FirstWins,
+		)?;
+		let coord = TileCoord::new(3, 2, 2)?;
+
+		// the first two requests wait for the timeout
+		let start = Instant::now();
+		for _ in 0..2 {
+			assert_eq!(check_vector(op.get_tile(&coord).await?.unwrap()), "🟨");
+		}
+		assert!(start.elapsed() < Duration::from_secs(2));
+
+		// then the slow source is skipped
+		let start = Instant::now();
+		assert_eq!(check_vector(op.get_tile(&coord).await?.unwrap()), "🟨");
+		assert!(start.elapsed() < Duration::from_millis(50));
+		let tiles = op
+			.get_tile_stream(TileBBox::new_full(3)?)
+			.await?
+			.to_vec()
+			.await
+			.unwrap();
+		assert_eq!(tiles.len(), 64);
+
+		assert_eq!(
+			op.guards[0].summary().unwrap(),
+			"source #0 (container 'dummy' ('dummy')): 4 requests, 2 failures (2 timeouts), 2 skipped"
+		);
+		assert_eq!(op.guards[1].summary(), None);
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_stream_timeout() -> Result<()> {
+		let settings = GuardSettings::from_millis(Some(50), None, None);
+		let op = Operation::new(
+			vec![
+				broken_source(Some(Duration::from_secs(10))).boxed(),
+				vector_source("🟨.pbf"),
+			],
+			settings,
+			OverlayStrategy::FirstWins,
+		)?;
+
+		let start = Instant::now();
+		let tiles = op
+			.get_tile_stream(TileBBox::new_full(2)?)
+			.await?
+			.to_vec()
+			.await
+			.unwrap();
+		assert!(start.elapsed() < Duration::from_secs(2));
+		assert_eq!(tiles.len(), 16);
+		for (_, tile) in tiles {
+			assert_eq!(check_vector(tile), "🟨");
+		}
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_errors_without_settings() -> Result<()> {
+		let op = Operation::new(
+			vec![broken_source(None).boxed(), vector_source("🟨.pbf")],
+			GuardSettings::default(),
+			OverlayStrategy::FirstWins,
+		)?;
+		let err = op.get_tile(&TileCoord::new(3, 2, 2)?).await.unwrap_err();
+		assert_eq!(err.chain().last().unwrap().to_string(), "source is broken");
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_sources_per_zoom_level() -> Result<()> {
+		let factory = PipelineFactory::new_dummy();
+		let op = factory
+			.operation_from_vpl(
+				&[
+					"from_stacked [",
+					"   from_container filename=\"🟦.pbf\" | filter level_min=3,",
+					"   from_container filename=\"🟨.pbf\" | filter level_max=2",
+					"]",
+				]
+				.join(""),
+			)
+			.await?;
+
+		let pyramid = &op.metadata().bbox_pyramid;
+		assert_eq!((pyramid.get_level_min(), pyramid.get_level_max()), (Some(0), Some(8)));
+
+		for (level, expected) in [(0, "🟨"), (2, "🟨"), (3, "🟦"), (5, "🟦")] {
+			let tiles = op
+				.get_tile_stream(TileBBox::new_full(level)?)
+				.await?
+				.to_vec()
+				.await
+				.unwrap();
+			assert_eq!(tiles.len() as u64, 4u64.pow(u32::from(level)), "level {level}");
+			for (_, tile) in tiles {
+				assert_eq!(check_vector(tile), expected, "level {level}");
+			}
+			let tile = op.get_tile(&TileCoord::new(level, 0, 0)?).await?.unwrap();
+			assert_eq!(check_vector(tile), expected, "level {level}");
+		}
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_sources_outside_their_levels_are_skipped() -> Result<()> {
+		for strategy in [OverlayStrategy::FirstWins, OverlayStrategy::Smallest] {
+			// the broken source fails every request, but is only used for zoom levels 3 and above
+			let broken = broken_source(None);
+			let mut metadata = broken.metadata().clone();
+			metadata.bbox_pyramid.set_level_min(3);
+			let op = Operation::new(
+				vec![broken.with_metadata(metadata).boxed(), vector_source("🟨.pbf")],
+				GuardSettings::default(),
+				strategy,
+			)?;
+
+			let tile = op.get_tile(&TileCoord::new(2, 1, 1)?).await?.unwrap();
+			assert_eq!(check_vector(tile), "🟨");
+			let tiles = op
+				.get_tile_stream(TileBBox::new_full(2)?)
+				.await?
+				.to_vec()
+				.await
+				.unwrap();
+			assert_eq!(tiles.len(), 16);
+
+			assert!(op.get_tile(&TileCoord::new(3, 1, 1)?).await.is_err());
+		}
+		Ok(())
+	}
+
+	async fn winner(strategy: OverlayStrategy) -> Result<String> {
+		// the first source delivers larger blobs than the second one
+		let op = Operation::new(
+			vec![vector_source("🟦 large large large.pbf"), vector_source("🟨.pbf")],
+			GuardSettings::default(),
+			strategy,
+		)?;
+		let from_tile = check_vector(op.get_tile(&TileCoord::new(3, 2, 2)?).await?.unwrap());
+
+		let tiles = op
+			.get_tile_stream(TileBBox::new_full(2)?)
+			.await?
+			.to_vec()
+			.await
+			.unwrap();
+		assert_eq!(tiles.len(), 16);
+		for (_, tile) in tiles {
+			assert_eq!(check_vector(tile), from_tile);
+		}
+		Ok(from_tile)
+	}
+
+	#[tokio::test]
+	async fn test_strategies() -> Result<()> {
+		assert_eq!(winner(OverlayStrategy::FirstWins).await?, "🟦 large large large");
+		assert_eq!(winner(OverlayStrategy::Smallest).await?, "🟨");
+		assert_eq!(winner(OverlayStrategy::Largest).await?, "🟦 large large large");
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_random_strategy_is_stable() -> Result<()> {
+		let op = Operation::new(
+			vec![vector_source("🟦.pbf"), vector_source("🟨.pbf")],
+			GuardSettings::default(),
+			OverlayStrategy::Random,
+		)?;
+		let bbox = TileBBox::new_full(3)?;
+		let mut tiles = op.get_tile_stream(bbox).await?.to_vec().await.unwrap();
+		tiles.sort_by_key(|(coord, _)| (coord.y, coord.x));
+		let pattern = tiles
+			.into_iter()
+			.map(|(_, tile)| check_vector(tile))
+			.collect::<Vec<_>>();
+
+		// both sources win somewhere, and single requests agree with the stream
+		assert!(pattern.contains(&String::from("🟦")));
+		assert!(pattern.contains(&String::from("🟨")));
+		for (coord, expected) in bbox.iter_coords().zip(&pattern) {
+			assert_eq!(&check_vector(op.get_tile(&coord).await?.unwrap()), expected);
+		}
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_strategy_from_vpl() -> Result<()> {
+		let factory = PipelineFactory::new_dummy();
+		let vpl = |strategy: &str| {
+			format!(
+				"from_stacked strategy={strategy} [ from_container filename=\"🟦🟦.pbf\", from_container filename=\"🟨.pbf\" ]"
+			)
+		};
+
+		let op = factory.operation_from_vpl(&vpl("smallest")).await?;
+		let tile = op.get_tile(&TileCoord::new(3, 2, 2)?).await?.unwrap();
+		assert_eq!(check_vector(tile), "🟨");
+
+		let err = factory.operation_from_vpl(&vpl("newest")).await.unwrap_err();
+		assert_eq!(
+			err.chain().last().unwrap().to_string(),
+			"strategy must be 'first_wins', 'smallest', 'largest' or 'random', but is 'newest'"
+		);
+		Ok(())
+	}
+
 	#[test]
 	fn test_traversal_orders_overlay() {
 		use crate::operations::read::from_container::operation_from_reader;
@@ -338,10 +853,14 @@ mod tests {
 		src1.set_traversal(Traversal::new_any_size(1, 16).unwrap());
 		src2.set_traversal(Traversal::new(TraversalOrder::PMTiles, 4, 256).unwrap());
 
-		let op = Operation::new(vec![
-			operation_from_reader(Box::new(src1)),
-			operation_from_reader(Box::new(src2)),
-		])
+		let op = Operation::new(
+			vec![
+				operation_from_reader(Box::new(src1)),
+				operation_from_reader(Box::new(src2)),
+			],
+			GuardSettings::default(),
+			OverlayStrategy::FirstWins,
+		)
 		.unwrap();
 
 		assert_eq!(
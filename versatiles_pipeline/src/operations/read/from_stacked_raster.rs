@@ -11,14 +11,18 @@
 //! [`Operation`] implementation that performs the blending.
 
 use crate::{
-	PipelineFactory,
+	OperationDocs, PipelineFactory,
 	operations::read::traits::ReadTileSource,
 	traits::*,
 	vpl::{VPLNode, VPLPipeline},
 };
 use anyhow::{Result, ensure};
 use async_trait::async_trait;
-use futures::{StreamExt, future::join_all, stream};
+use futures::{
+	StreamExt,
+	future::{join_all, try_join_all},
+	stream,
+};
 use std::{sync::Arc, vec};
 use versatiles_container::{SourceType, Tile, TileSource, TileSourceMetadata, Traversal};
 use versatiles_core::*;
@@ -98,6 +102,7 @@ impl ReadTileSource for Operation {
 
 		let mut pyramid = TileBBoxPyramid::new_empty();
 		let mut traversal = Traversal::new_any();
+		let mut tile_size = None;
 
 		for source in sources.iter() {
 			tilejson.merge(source.tilejson())?;
@@ -110,9 +115,23 @@ impl ReadTileSource for Operation {
 				metadata.tile_format.to_type() == TileType::Raster,
 				"all sources must be raster tiles"
 			);
+
+			// Sources of unknown tile size are assumed to match the others.
+			if let Some(size) = metadata.tile_size {
+				ensure!(
+					*tile_size.get_or_insert(size) == size,
+					"all sources must have the same tile size"
+				);
+			}
 		}
 
-		let metadata = TileSourceMetadata::new(tile_format, tile_compression, pyramid, traversal);
+		let mut metadata = TileSourceMetadata::new_builder()
+			.with_format(tile_format)
+			.with_compression(tile_compression)
+			.with_bbox_pyramid(pyramid)
+			.with_traversal(traversal)
+			.build()?;
+		metadata.tile_size = tile_size;
 		metadata.update_tilejson(&mut tilejson);
 
 		Ok(Box::new(Self {
@@ -151,43 +170,50 @@ impl TileSource for Operation {
 
 		Ok(TileStream::from_streams(stream::iter(bboxes).map(
 			move |bbox| async move {
-				let mut tiles = TileBBoxMap::<Vec<Tile>>::new_default(bbox);
-
-				let streams = sources.iter().map(async |source| {
-					let stream = source.get_tile_stream(bbox).await.unwrap();
-					stream.to_vec().await
-				});
-				let results: Vec<Vec<(TileCoord, Tile)>> = futures::future::join_all(streams).await;
-
-				for result in results.into_iter() {
-					for (coord, mut tile) in result.into_iter() {
-						let image = tile.as_image().unwrap();
-						if !image.is_empty() {
-							tiles.get_mut(&coord).unwrap().push(tile);
-						}
-					}
-				}
-
-				tiles
-					.into_stream()
-					.filter_map_item_parallel(move |v| match stack_tiles(v) {
-						Ok(Some(mut tile)) => {
-							tile.change_format(tile_format, None, None).unwrap();
-							Ok(Some(tile))
-						}
-						Ok(None) => Ok(None),
-						Err(err) => Err(err),
-					})
-					.unwrap_results()
+				stack_bbox(sources, bbox, tile_format)
+					.await
+					.unwrap_or_else(TileStream::failed)
 			},
 		)))
 	}
 }
 
+/// Stacks the tiles of all `sources` within `bbox` and converts them to `tile_format`.
+///
+/// Errors while reading the sources are returned; errors while stacking a tile stop the
+/// returned stream and are reported through its failure handle.
+async fn stack_bbox(
+	sources: &[Box<dyn TileSource>],
+	bbox: TileBBox,
+	tile_format: TileFormat,
+) -> Result<TileStream<'static, Tile>> {
+	let mut tiles = TileBBoxMap::<Vec<Tile>>::new_default(bbox);
+
+	let streams = sources
+		.iter()
+		.map(async |source| source.get_tile_stream(bbox).await?.to_vec().await);
+
+	for result in try_join_all(streams).await? {
+		for (coord, mut tile) in result.into_iter() {
+			if !tile.as_image()?.is_empty() {
+				tiles.get_mut(&coord)?.push(tile);
+			}
+		}
+	}
+
+	Ok(tiles.into_stream().try_filter_map_item_parallel(move |v| {
+		let Some(mut tile) = stack_tiles(v)? else {
+			return Ok(None);
+		};
+		tile.change_format(tile_format, None, None)?;
+		Ok(Some(tile))
+	}))
+}
+
 pub struct Factory {}
 
 impl OperationFactoryTrait for Factory {
-	fn get_docs(&self) -> String {
+	fn get_docs(&self) -> OperationDocs {
 		Args::get_docs()
 	}
 	fn get_tag_name(&self) -> &str {
@@ -279,7 +305,7 @@ mod tests {
 			.await?;
 
 		let bbox = TileBBox::new_full(3)?;
-		let tiles = result.get_tile_stream(bbox).await?.to_vec().await;
+		let tiles = result.get_tile_stream(bbox).await?.to_vec().await.unwrap();
 
 		assert_eq!(
 			arrange_tiles(tiles, |mut tile| {
@@ -370,6 +396,41 @@ mod tests {
 		Ok(())
 	}
 
+	#[tokio::test]
+	async fn test_operation_tile_size() -> Result<()> {
+		let factory =
+			PipelineFactory::new_dummy_reader(Box::new(|filename: String| -> BoxFuture<Result<Box<dyn TileSource>>> {
+				Box::pin(async move {
+					let size = filename[0..filename.len() - 4].parse::<u32>()?;
+					Ok(
+						Box::new(DummyImageSource::from_color(&[0, 0, 0], size, TileFormat::PNG, None)?)
+							as Box<dyn TileSource>,
+					)
+				})
+			}));
+
+		let result = factory
+			.operation_from_vpl(
+				r#"from_stacked_raster [ from_container filename="512.png", from_container filename="512.png" ]"#,
+			)
+			.await?;
+		assert_eq!(result.metadata().tile_size, Some(TileSize::Size512));
+		assert_eq!(result.tilejson().tile_size, Some(TileSize::Size512));
+
+		let error = factory
+			.operation_from_vpl(
+				r#"from_stacked_raster [ from_container filename="256.png", from_container filename="512.png" ]"#,
+			)
+			.await
+			.unwrap_err();
+		assert_eq!(
+			error.chain().last().unwrap().to_string(),
+			"all sources must have the same tile size"
+		);
+
+		Ok(())
+	}
+
 	#[tokio::test]
 	async fn test_merge_tiles_multiple_layers() -> Result<()> {
 		use versatiles_core::TileFormat::PNG;
@@ -391,8 +452,8 @@ mod tests {
 		let plain = factory.operation_from_vpl("from_container filename=00F7.png").await?;
 
 		let bbox = TileBBox::new_full(3)?;
-		let stacked_tiles = stacked.get_tile_stream(bbox).await?.to_vec().await;
-		let plain_tiles = plain.get_tile_stream(bbox).await?.to_vec().await;
+		let stacked_tiles = stacked.get_tile_stream(bbox).await?.to_vec().await.unwrap();
+		let plain_tiles = plain.get_tile_stream(bbox).await?.to_vec().await.unwrap();
 
 		// Convert to maps for easy lookup
 		use std::collections::HashMap;
@@ -425,9 +486,15 @@ mod tests {
 		let bbox = TileBBox::new_full(3)?;
 		let coord = TileCoord::new(3, 2, 2)?; // a tile that lies in the overlap area in our dummy dataset
 
-		let stacked_tile = stacked.get_tile_stream(bbox).await?.to_map().await.remove(&coord);
-		let tile1 = src1.get_tile_stream(bbox).await?.to_map().await.remove(&coord);
-		let tile2 = src2.get_tile_stream(bbox).await?.to_map().await.remove(&coord);
+		let stacked_tile = stacked
+			.get_tile_stream(bbox)
+			.await?
+			.to_map()
+			.await
+			.unwrap()
+			.remove(&coord);
+		let tile1 = src1.get_tile_stream(bbox).await?.to_map().await.unwrap().remove(&coord);
+		let tile2 = src2.get_tile_stream(bbox).await?.to_map().await.unwrap().remove(&coord);
 
 		if let Some(mut stacked_tile) = stacked_tile {
 			// If both sources produced a tile here, blended output must differ from each single-source blob
@@ -1,4 +1,8 @@
 mod traits;
+pub mod vector_compute_property;
+pub mod vector_filter_features;
 pub mod vector_filter_layers;
 pub mod vector_filter_properties;
+pub mod vector_rename_properties;
+pub mod vector_simplify;
 pub mod vector_update_properties;
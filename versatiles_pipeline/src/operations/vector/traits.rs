@@ -2,13 +2,20 @@ use anyhow::{Result, ensure};
 use async_trait::async_trait;
 use std::sync::Arc;
 use versatiles_container::{SourceType, Tile, TileSource, TileSourceMetadata};
-use versatiles_core::{TileBBox, TileJSON, TileStream, TileType};
+use versatiles_core::{TileBBox, TileCoord, TileJSON, TileStream, TileType};
 use versatiles_derive::context;
 use versatiles_geometry::vector_tile::VectorTile;
 
 pub trait RunnerTrait: std::fmt::Debug + Send + Sync + 'static {
 	fn update_tilejson(&self, tilejson: &mut TileJSON);
 	fn run(&self, tile: VectorTile) -> Result<Option<VectorTile>>;
+
+	/// Runs the transformation for the tile at `coord`.
+	///
+	/// Defaults to [`run`](Self::run); runners that need to know which tile they are processing override this.
+	fn run_at(&self, _coord: &TileCoord, tile: VectorTile) -> Result<Option<VectorTile>> {
+		self.run(tile)
+	}
 }
 
 /// Generic “transform” operation that delegates all real work to a `Runner`.
@@ -42,15 +49,16 @@ impl<R: RunnerTrait> TileSource for TransformOp<R> {
 			.source
 			.get_tile_stream(bbox)
 			.await?
-			.filter_map_item_parallel(move |tile| {
+			.filter_map_coord_item_parallel(move |coord, tile| {
 				let vector = tile.into_vector()?;
-				if let Some(transformed_vector) = runner.run(vector)? {
+				if let Some(transformed_vector) = runner.run_at(&coord, vector)? {
 					Ok(Some(Tile::from_vector(transformed_vector, tile_format)?))
 				} else {
 					Ok(None)
 				}
 			})
-			.unwrap_results())
+			// a single malformed tile must not abort the whole stream
+			.skip_errors())
 	}
 }
 
@@ -82,3 +90,45 @@ where
 		tilejson,
 	}) as Box<dyn TileSource>)
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::helpers::dummy_vector_source::DummyVectorSource;
+	use versatiles_container::MockReader;
+	use versatiles_core::{Blob, TileCompression, TileFormat};
+
+	#[derive(Debug)]
+	struct IdentityRunner;
+
+	impl RunnerTrait for IdentityRunner {
+		fn update_tilejson(&self, _tilejson: &mut TileJSON) {}
+		fn run(&self, tile: VectorTile) -> Result<Option<VectorTile>> {
+			Ok(Some(tile))
+		}
+	}
+
+	#[tokio::test]
+	async fn malformed_tiles_are_skipped() -> Result<()> {
+		// a valid tile at (3, 1, 1) and a malformed one at (3, 2, 1)
+		let dummy = DummyVectorSource::new(&[("layer", &[&[("key", "value")]])], None);
+		let valid = TileCoord::new(3, 1, 1)?;
+		let broken = Tile::from_blob(Blob::from("broken"), TileCompression::Uncompressed, TileFormat::MVT);
+		let source = MockReader::from_tiles(vec![
+			(valid, dummy.get_tile(&valid).await?.unwrap()),
+			(TileCoord::new(3, 2, 1)?, broken),
+		])?
+		.with_metadata(dummy.metadata().clone());
+		let operation = build_transform(source.boxed(), IdentityRunner).await?;
+
+		let tiles = operation
+			.get_tile_stream(TileBBox::new_full(3)?)
+			.await?
+			.to_vec()
+			.await
+			.unwrap();
+		assert_eq!(tiles.len(), 1);
+		assert_eq!(tiles[0].0, TileCoord::new(3, 1, 1)?);
+		Ok(())
+	}
+}
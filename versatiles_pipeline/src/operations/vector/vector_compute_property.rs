@@ -0,0 +1,204 @@
+use crate::{
+	OperationDocs, PipelineFactory,
+	helpers::Expression,
+	operations::vector::traits::{RunnerTrait, build_transform},
+	traits::{OperationFactoryTrait, TransformOperationFactoryTrait},
+	vpl::VPLNode,
+};
+use anyhow::Result;
+use async_trait::async_trait;
+use versatiles_container::TileSource;
+use versatiles_core::TileJSON;
+use versatiles_derive::context;
+use versatiles_geometry::{geo::GeoValue, vector_tile::VectorTile};
+
+#[derive(versatiles_derive::VPLDecode, Clone, Debug)]
+/// Computes a property of every feature from its other properties, e.g. `vector_compute_property field="min_zoom" expr="coalesce(rank, 10) / 2 + 4"`.
+struct Args {
+	/// Name of the property to write. An existing property with this name is replaced.
+	field: String,
+
+	/// Expression that computes the value: numbers, strings in single or double quotes, `true`, `false`, `null`,
+	/// property names like `name:de`, the arithmetic operators `+`, `-`, `*`, `/` (`+` concatenates strings),
+	/// `coalesce(a, b, ...)`, comparisons, boolean operators and parentheses, like in `vector_filter_features`.
+	/// Properties that a feature doesn't have evaluate to `null`. If the result is `null`, the property is removed.
+	expr: String,
+
+	/// If set, only features in this layer are updated. Other layers pass through unchanged.
+	layer_name: Option<String>,
+}
+
+#[derive(Debug)]
+struct Runner {
+	field: String,
+	expr: String,
+	expression: Expression,
+	layer_name: Option<String>,
+}
+
+impl Runner {
+	pub fn from_args(args: Args) -> Result<Self> {
+		Ok(Self {
+			field: args.field,
+			expression: Expression::parse(&args.expr)?,
+			expr: args.expr,
+			layer_name: args.layer_name,
+		})
+	}
+
+	fn is_selected(&self, layer_name: &str) -> bool {
+		self.layer_name.as_ref().is_none_or(|name| name == layer_name)
+	}
+}
+
+impl RunnerTrait for Runner {
+	#[context("Failed to run vector compute property")]
+	fn run(&self, mut tile: VectorTile) -> Result<Option<VectorTile>> {
+		for layer in &mut tile.layers {
+			if !self.is_selected(&layer.name) {
+				continue;
+			}
+			layer.map_properties(|mut properties| {
+				match self.expression.evaluate(&properties) {
+					GeoValue::Null => properties.remove(&self.field),
+					value => properties.insert(self.field.clone(), value),
+				}
+				properties
+			})?;
+		}
+		Ok(Some(tile))
+	}
+
+	fn update_tilejson(&self, tilejson: &mut TileJSON) {
+		for (name, layer) in tilejson.vector_layers.iter_mut() {
+			if self.is_selected(name) {
+				layer
+					.fields
+					.entry(self.field.clone())
+					.or_insert_with(|| format!("computed from `{}`", self.expr));
+			}
+		}
+	}
+}
+
+pub struct Factory {}
+
+impl OperationFactoryTrait for Factory {
+	fn get_docs(&self) -> OperationDocs {
+		Args::get_docs()
+	}
+	fn get_tag_name(&self) -> &str {
+		"vector_compute_property"
+	}
+}
+
+#[async_trait]
+impl TransformOperationFactoryTrait for Factory {
+	async fn build<'a>(
+		&self,
+		vpl_node: VPLNode,
+		source: Box<dyn TileSource>,
+		_factory: &'a PipelineFactory,
+	) -> Result<Box<dyn TileSource>> {
+		let args = Args::from_vpl_node(&vpl_node)?;
+
+		build_transform::<Runner>(source, Runner::from_args(args)?).await
+	}
+}
+
+// ───────────────────────── TESTS ─────────────────────────
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pretty_assertions::assert_eq;
+	use versatiles_geometry::{geo::*, vector_tile::VectorTileLayer};
+
+	fn create_layer(name: &str) -> VectorTileLayer {
+		let features = [Some(4), None]
+			.into_iter()
+			.map(|rank| {
+				let mut feature = GeoFeature::new(Geometry::new_example());
+				feature.properties = GeoProperties::from(vec![("name", GeoValue::from("Berlin"))]);
+				if let Some(rank) = rank {
+					feature.properties.insert("rank".to_string(), GeoValue::UInt(rank));
+				}
+				feature
+			})
+			.collect();
+		VectorTileLayer::from_features(name.to_string(), features, 4096, 1).unwrap()
+	}
+
+	fn run(vpl: &str) -> Result<Vec<String>> {
+		let vpl_node = VPLNode::try_from_str(&format!("vector_compute_property {vpl}"))?;
+		let runner = Runner::from_args(Args::from_vpl_node(&vpl_node)?)?;
+		let tile = VectorTile::new(vec![create_layer("places"), create_layer("other")]);
+		let tile = runner.run(tile)?.unwrap();
+		let mut result = Vec::new();
+		for layer in &tile.layers {
+			for feature in &layer.features {
+				let properties = feature.decode_properties(layer)?;
+				let value = properties.get("result").map_or("-".to_string(), ToString::to_string);
+				result.push(format!("{}:{value}", layer.name));
+			}
+		}
+		Ok(result)
+	}
+
+	#[test]
+	fn test_numbers() -> Result<()> {
+		assert_eq!(
+			run(r#"field="result" expr="coalesce(rank, 10) / 2 + 4""#)?,
+			["places:6", "places:9", "other:6", "other:9"]
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn test_strings_in_one_layer() -> Result<()> {
+		assert_eq!(
+			run(r#"field="result" expr="name + ' (' + rank + ')'" layer_name="places""#)?,
+			["places:Berlin (4)", "places:-", "other:-", "other:-"]
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn test_replace_existing() -> Result<()> {
+		let vpl_node = VPLNode::try_from_str(r#"vector_compute_property field="rank" expr="rank * 2""#)?;
+		let runner = Runner::from_args(Args::from_vpl_node(&vpl_node)?)?;
+		let tile = runner.run(VectorTile::new(vec![create_layer("places")]))?.unwrap();
+		let layer = &tile.layers[0];
+		let ranks = layer
+			.features
+			.iter()
+			.map(|f| f.decode_properties(layer).unwrap().get("rank").cloned())
+			.collect::<Vec<_>>();
+		assert_eq!(ranks, [Some(GeoValue::UInt(8)), None]);
+		Ok(())
+	}
+
+	#[test]
+	fn test_tilejson() -> Result<()> {
+		let vpl_node = VPLNode::try_from_str(r#"vector_compute_property field="label" expr="name" layer_name="places""#)?;
+		let runner = Runner::from_args(Args::from_vpl_node(&vpl_node)?)?;
+		let mut tilejson = TileJSON::default();
+		tilejson
+			.vector_layers
+			.0
+			.insert("places".to_string(), Default::default());
+		tilejson.vector_layers.0.insert("other".to_string(), Default::default());
+		runner.update_tilejson(&mut tilejson);
+		assert_eq!(
+			tilejson.vector_layers.0["places"].fields["label"],
+			"computed from `name`"
+		);
+		assert!(tilejson.vector_layers.0["other"].fields.is_empty());
+		Ok(())
+	}
+
+	#[test]
+	fn test_invalid_expression() {
+		let err = format!("{:?}", run(r#"field="result" expr="rank +""#).unwrap_err());
+		assert!(err.contains("Failed to parse expression 'rank +'"), "{err}");
+	}
+}
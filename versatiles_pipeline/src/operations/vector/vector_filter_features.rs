@@ -0,0 +1,165 @@
+use crate::{
+	OperationDocs, PipelineFactory,
+	helpers::Expression,
+	operations::vector::traits::{RunnerTrait, build_transform},
+	traits::{OperationFactoryTrait, TransformOperationFactoryTrait},
+	vpl::VPLNode,
+};
+use anyhow::Result;
+use async_trait::async_trait;
+use versatiles_container::TileSource;
+use versatiles_core::TileJSON;
+use versatiles_derive::context;
+use versatiles_geometry::vector_tile::VectorTile;
+
+#[derive(versatiles_derive::VPLDecode, Clone, Debug)]
+/// Keeps only the features whose properties match an expression, e.g. `vector_filter_features expr="class == 'path' && surface != 'paved'"`.
+/// Layers without remaining features are removed.
+struct Args {
+	/// Expression that is evaluated for every feature; features for which it is `false` are removed.
+	/// Supported are numbers, strings in single or double quotes, `true`, `false`, `null`, property names like `name:de`,
+	/// the comparisons `==`, `!=`, `<`, `<=`, `>`, `>=`, the boolean operators `!`, `&&`, `||` and parentheses.
+	/// Properties that a feature doesn't have evaluate to `null`.
+	expr: String,
+
+	/// If set, only features in this layer are filtered. Other layers pass through unchanged.
+	layer_name: Option<String>,
+}
+
+#[derive(Debug)]
+struct Runner {
+	expression: Expression,
+	layer_name: Option<String>,
+}
+
+impl Runner {
+	pub fn from_args(args: Args) -> Result<Self> {
+		Ok(Self {
+			expression: Expression::parse(&args.expr)?,
+			layer_name: args.layer_name,
+		})
+	}
+}
+
+impl RunnerTrait for Runner {
+	fn update_tilejson(&self, _tilejson: &mut TileJSON) {}
+
+	#[context("Failed to run vector filter features")]
+	fn run(&self, mut tile: VectorTile) -> Result<Option<VectorTile>> {
+		for layer in &mut tile.layers {
+			if self.layer_name.as_ref().is_some_and(|name| name != &layer.name) {
+				continue;
+			}
+			let keep = layer
+				.features
+				.iter()
+				.map(|feature| Ok(self.expression.matches(&feature.decode_properties(layer)?)))
+				.collect::<Result<Vec<bool>>>()?;
+			let mut keep = keep.into_iter();
+			layer.features.retain(|_| keep.next().unwrap_or(true));
+		}
+
+		tile.remove_empty_layers(None);
+
+		Ok(Some(tile))
+	}
+}
+
+pub struct Factory {}
+
+impl OperationFactoryTrait for Factory {
+	fn get_docs(&self) -> OperationDocs {
+		Args::get_docs()
+	}
+	fn get_tag_name(&self) -> &str {
+		"vector_filter_features"
+	}
+}
+
+#[async_trait]
+impl TransformOperationFactoryTrait for Factory {
+	async fn build<'a>(
+		&self,
+		vpl_node: VPLNode,
+		source: Box<dyn TileSource>,
+		_factory: &'a PipelineFactory,
+	) -> Result<Box<dyn TileSource>> {
+		let args = Args::from_vpl_node(&vpl_node)?;
+
+		build_transform::<Runner>(source, Runner::from_args(args)?).await
+	}
+}
+
+// ───────────────────────── TESTS ─────────────────────────
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pretty_assertions::assert_eq;
+	use versatiles_geometry::{geo::*, vector_tile::VectorTileLayer};
+
+	fn create_layer(name: &str) -> VectorTileLayer {
+		let features = [("path", "gravel", 1), ("path", "paved", 2), ("road", "paved", 3)]
+			.into_iter()
+			.map(|(class, surface, width)| {
+				let mut feature = GeoFeature::new(Geometry::new_example());
+				feature.properties = GeoProperties::from(vec![
+					("class", GeoValue::from(class)),
+					("surface", GeoValue::from(surface)),
+					("width", GeoValue::UInt(width)),
+				]);
+				feature
+			})
+			.collect();
+		VectorTileLayer::from_features(name.to_string(), features, 4096, 1).unwrap()
+	}
+
+	fn run(vpl: &str) -> Result<Vec<String>> {
+		let vpl_node = VPLNode::try_from_str(&format!("vector_filter_features {vpl}"))?;
+		let runner = Runner::from_args(Args::from_vpl_node(&vpl_node)?)?;
+		let tile = VectorTile::new(vec![create_layer("ways"), create_layer("other")]);
+		let tile = runner.run(tile)?.unwrap();
+		Ok(tile
+			.layers
+			.iter()
+			.map(|layer| {
+				let widths = layer
+					.features
+					.iter()
+					.map(|f| f.decode_properties(layer).unwrap().get("width").unwrap().to_string())
+					.collect::<Vec<_>>();
+				format!("{}:{}", layer.name, widths.join(","))
+			})
+			.collect())
+	}
+
+	#[test]
+	fn test_filter_all_layers() -> Result<()> {
+		assert_eq!(
+			run(r#"expr="class == 'path' && surface != 'paved'""#)?,
+			["ways:1", "other:1"]
+		);
+		assert_eq!(run(r#"expr="width >= 2""#)?, ["ways:2,3", "other:2,3"]);
+		Ok(())
+	}
+
+	#[test]
+	fn test_filter_single_layer() -> Result<()> {
+		assert_eq!(
+			run(r#"expr="!(class == 'road')" layer_name="ways""#)?,
+			["ways:1,2", "other:1,2,3"]
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn test_empty_layers_are_removed() -> Result<()> {
+		assert_eq!(run(r#"expr="missing == 'x'" layer_name="ways""#)?, ["other:1,2,3"]);
+		Ok(())
+	}
+
+	#[test]
+	fn test_invalid_expression() {
+		let err = format!("{:?}", run(r#"expr="class = 'path'""#).unwrap_err());
+		assert!(err.contains("Failed to parse expression"), "{err}");
+	}
+}
@@ -1,5 +1,5 @@
 use crate::{
-	PipelineFactory,
+	OperationDocs, PipelineFactory,
 	operations::vector::traits::{RunnerTrait, build_transform},
 	traits::{OperationFactoryTrait, TransformOperationFactoryTrait},
 	vpl::VPLNode,
@@ -59,7 +59,7 @@ impl RunnerTrait for Runner {
 pub struct Factory {}
 
 impl OperationFactoryTrait for Factory {
-	fn get_docs(&self) -> String {
+	fn get_docs(&self) -> OperationDocs {
 		Args::get_docs()
 	}
 	fn get_tag_name(&self) -> &str {
@@ -153,7 +153,7 @@ mod tests {
 			.await?;
 
 		let mut stream = operation.get_tile_stream(TileBBox::new_full(0)?).await?;
-		let tile = stream.next().await.unwrap().1.into_vector()?;
+		let tile = stream.next().await?.unwrap().1.into_vector()?;
 		let layer_names = tile
 			.layers
 			.iter()
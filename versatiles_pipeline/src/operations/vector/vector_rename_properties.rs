@@ -0,0 +1,344 @@
+use crate::{
+	OperationDocs, PipelineFactory,
+	operations::vector::traits::{RunnerTrait, build_transform},
+	traits::{OperationFactoryTrait, TransformOperationFactoryTrait},
+	vpl::VPLNode,
+};
+use anyhow::{Result, anyhow, bail, ensure};
+use async_trait::async_trait;
+use versatiles_container::TileSource;
+use versatiles_core::TileJSON;
+use versatiles_derive::context;
+use versatiles_geometry::vector_tile::{VectorTile, VectorTileLayer};
+
+#[derive(versatiles_derive::VPLDecode, versatiles_derive::VPLValidate, Clone, Debug)]
+/// Renames properties of vector tile features, e.g. `rename=["name_de=name:de","pop=population"]`.
+struct Args {
+	/// List of renamings in the form `old=new`. They are applied in the given order.
+	rename: Vec<String>,
+
+	/// If set, only properties in this layer are renamed.
+	layer_name: Option<String>,
+
+	/// What to do if a layer already contains the new property name: `error` stops processing,
+	/// `skip` keeps both properties unchanged, `overwrite` replaces the existing value with the renamed one.
+	#[vpl_default("error")]
+	#[vpl(one_of = "error|skip|overwrite")]
+	on_conflict: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum OnConflict {
+	Error,
+	Skip,
+	Overwrite,
+}
+
+#[derive(Debug)]
+struct Runner {
+	renames: Vec<(String, String)>,
+	layer_name: Option<String>,
+	on_conflict: OnConflict,
+}
+
+impl Runner {
+	#[context("Failed to parse arguments of vector_rename_properties")]
+	pub fn from_args(args: Args) -> Result<Self> {
+		let renames = args
+			.rename
+			.iter()
+			.map(|entry| {
+				let (old, new) = entry
+					.split_once('=')
+					.ok_or_else(|| anyhow!("rename entry '{entry}' must have the form 'old=new'"))?;
+				ensure!(
+					!old.is_empty() && !new.is_empty(),
+					"rename entry '{entry}' must have the form 'old=new'"
+				);
+				Ok((old.to_string(), new.to_string()))
+			})
+			.collect::<Result<Vec<_>>>()?;
+
+		let on_conflict = match args.on_conflict.as_deref().unwrap_or("error") {
+			"error" => OnConflict::Error,
+			"skip" => OnConflict::Skip,
+			"overwrite" => OnConflict::Overwrite,
+			value => bail!("on_conflict must be 'error', 'skip' or 'overwrite', but is '{value}'"),
+		};
+
+		Ok(Self {
+			renames,
+			layer_name: args.layer_name,
+			on_conflict,
+		})
+	}
+
+	fn is_selected(&self, layer_name: &str) -> bool {
+		self.layer_name.as_ref().is_none_or(|name| name == layer_name)
+	}
+
+	/// Renames the keys in the key table of the layer, so the tag ids of the features stay valid.
+	/// Only `overwrite` conflicts need to touch the features.
+	fn rename_layer(&self, layer: &mut VectorTileLayer) -> Result<()> {
+		for (old, new) in &self.renames {
+			let keys = &mut layer.property_manager.key;
+			let Ok(old_id) = keys.find(old) else {
+				continue;
+			};
+			let Ok(new_id) = keys.find(new) else {
+				keys.replace(old_id, new.clone())?;
+				continue;
+			};
+			match self.on_conflict {
+				OnConflict::Error => bail!(
+					"can't rename property '{old}' to '{new}' in layer '{}', because '{new}' already exists",
+					layer.name
+				),
+				OnConflict::Skip => {}
+				OnConflict::Overwrite => {
+					for feature in &mut layer.features {
+						feature.tag_ids = overwrite_key(&feature.tag_ids, old_id, new_id);
+					}
+				}
+			}
+		}
+		Ok(())
+	}
+}
+
+/// Replaces the key `old_id` by `new_id` in the (key, value) pairs of `tag_ids`,
+/// removing an existing `new_id` pair if the feature has both.
+fn overwrite_key(tag_ids: &[u32], old_id: u32, new_id: u32) -> Vec<u32> {
+	if !tag_ids.chunks_exact(2).any(|tag| tag[0] == old_id) {
+		return tag_ids.to_vec();
+	}
+	tag_ids
+		.chunks_exact(2)
+		.filter(|tag| tag[0] != new_id)
+		.flat_map(|tag| [if tag[0] == old_id { new_id } else { tag[0] }, tag[1]])
+		.collect()
+}
+
+impl RunnerTrait for Runner {
+	#[context("Failed to run vector rename properties")]
+	fn run(&self, mut tile: VectorTile) -> Result<Option<VectorTile>> {
+		for layer in &mut tile.layers {
+			if self.is_selected(&layer.name) {
+				self.rename_layer(layer)?;
+			}
+		}
+		Ok(Some(tile))
+	}
+
+	fn update_tilejson(&self, tilejson: &mut TileJSON) {
+		for (name, layer) in tilejson.vector_layers.iter_mut() {
+			if !self.is_selected(name) {
+				continue;
+			}
+			for (old, new) in &self.renames {
+				if layer.fields.contains_key(new) && self.on_conflict != OnConflict::Overwrite {
+					continue;
+				}
+				if let Some(description) = layer.fields.remove(old) {
+					layer.fields.insert(new.clone(), description);
+				}
+			}
+		}
+	}
+}
+
+pub struct Factory {}
+
+impl OperationFactoryTrait for Factory {
+	fn get_docs(&self) -> OperationDocs {
+		Args::get_docs()
+	}
+	fn get_tag_name(&self) -> &str {
+		"vector_rename_properties"
+	}
+}
+
+#[async_trait]
+impl TransformOperationFactoryTrait for Factory {
+	async fn build<'a>(
+		&self,
+		vpl_node: VPLNode,
+		source: Box<dyn TileSource>,
+		_factory: &'a PipelineFactory,
+	) -> Result<Box<dyn TileSource>> {
+		let args = Args::from_vpl_node(&vpl_node)?;
+
+		build_transform::<Runner>(source, Runner::from_args(args)?).await
+	}
+}
+
+// ───────────────────────── TESTS ─────────────────────────
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pretty_assertions::assert_eq;
+	use versatiles_core::TileBBox;
+	use versatiles_geometry::geo::*;
+
+	fn extract_tile_properties(tile: &VectorTile) -> Vec<String> {
+		let mut properties: Vec<String> = tile
+			.layers
+			.iter()
+			.flat_map(|layer| {
+				let name = layer.name.clone();
+				layer.features.iter().flat_map(move |feature| {
+					let p = feature.decode_properties(layer).unwrap();
+					p.iter().map(|(k, _v)| format!("{name}/{k}")).collect::<Vec<_>>()
+				})
+			})
+			.collect();
+		properties.sort();
+		properties.dedup();
+		properties
+	}
+
+	fn extract_json_properties(tilejson: &TileJSON) -> Vec<String> {
+		let mut properties: Vec<String> = tilejson
+			.vector_layers
+			.iter()
+			.flat_map(|(name, layer)| {
+				layer
+					.fields
+					.keys()
+					.map(|key| format!("{name}/{key}"))
+					.collect::<Vec<_>>()
+			})
+			.collect();
+		properties.sort();
+		properties
+	}
+
+	async fn run_test(args: &str) -> Result<(Vec<String>, Vec<String>)> {
+		let factory = PipelineFactory::new_dummy();
+		let operation = factory
+			.operation_from_vpl(&format!("from_debug | vector_rename_properties {args}"))
+			.await?;
+
+		let mut stream = operation.get_tile_stream(TileBBox::new_full(0)?).await?;
+		let tile = stream.next().await?.unwrap().1.into_vector()?;
+
+		Ok((
+			extract_tile_properties(&tile),
+			extract_json_properties(operation.tilejson()),
+		))
+	}
+
+	#[tokio::test]
+	async fn test_rename_in_all_layers() -> Result<()> {
+		let (tile, json) = run_test(r#"rename=["index=tile_index","x=pos_x"]"#).await?;
+		let expected = [
+			"debug_x/char",
+			"debug_x/pos_x",
+			"debug_x/tile_index",
+			"debug_y/char",
+			"debug_y/pos_x",
+			"debug_y/tile_index",
+			"debug_z/char",
+			"debug_z/pos_x",
+			"debug_z/tile_index",
+		];
+		assert_eq!(tile, expected);
+		assert_eq!(json, expected);
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_rename_in_one_layer() -> Result<()> {
+		let (tile, json) = run_test(r#"rename=["index=tile_index"] layer_name=debug_y"#).await?;
+		let expected = [
+			"debug_x/char",
+			"debug_x/index",
+			"debug_x/x",
+			"debug_y/char",
+			"debug_y/tile_index",
+			"debug_y/x",
+			"debug_z/char",
+			"debug_z/index",
+			"debug_z/x",
+		];
+		assert_eq!(tile, expected);
+		assert_eq!(json, expected);
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_invalid_arguments() {
+		let err = run_test(r#"rename=["index"]"#).await.unwrap_err();
+		assert!(format!("{err:?}").contains("rename entry 'index' must have the form 'old=new'"));
+
+		let err = run_test(r#"rename=["index=x"] on_conflict=merge"#).await.unwrap_err();
+		assert!(
+			format!("{err:?}").contains("'on_conflict' must be one of 'error', 'skip', 'overwrite', but is 'merge'"),
+			"{err:?}"
+		);
+	}
+
+	fn conflict_tile() -> VectorTile {
+		let features = [("a1", Some("b1")), ("a2", None)]
+			.into_iter()
+			.map(|(a, b)| {
+				let mut feature = GeoFeature::new(Geometry::new_point([1.0, 2.0]));
+				feature.properties.insert("name_de".to_string(), GeoValue::from(a));
+				if let Some(b) = b {
+					feature.properties.insert("name:de".to_string(), GeoValue::from(b));
+				}
+				feature
+			})
+			.collect();
+		let layer = VectorTileLayer::from_features("place".to_string(), features, 4096, 1).unwrap();
+		VectorTile::new(vec![layer])
+	}
+
+	fn run_conflict(on_conflict: &str) -> Result<Vec<String>> {
+		let runner = Runner::from_args(Args {
+			rename: vec!["name_de=name:de".to_string()],
+			layer_name: None,
+			on_conflict: Some(on_conflict.to_string()),
+		})?;
+		let tile = runner.run(conflict_tile())?.unwrap();
+		let layer = &tile.layers[0];
+		Ok(layer
+			.features
+			.iter()
+			.map(|feature| {
+				let properties = feature.decode_properties(layer).unwrap();
+				let mut list = properties.iter().map(|(k, v)| format!("{k}={v}")).collect::<Vec<_>>();
+				list.sort();
+				list.join(",")
+			})
+			.collect())
+	}
+
+	#[test]
+	fn test_conflict_error() {
+		let err = run_conflict("error").unwrap_err();
+		assert!(
+			format!("{err:?}").contains(
+				"can't rename property 'name_de' to 'name:de' in layer 'place', because 'name:de' already exists"
+			)
+		);
+	}
+
+	#[test]
+	fn test_conflict_skip() -> Result<()> {
+		assert_eq!(run_conflict("skip")?, ["name:de=b1,name_de=a1", "name_de=a2"]);
+		Ok(())
+	}
+
+	#[test]
+	fn test_conflict_overwrite() -> Result<()> {
+		assert_eq!(run_conflict("overwrite")?, ["name:de=a1", "name:de=a2"]);
+		Ok(())
+	}
+
+	#[test]
+	fn test_overwrite_key() {
+		assert_eq!(overwrite_key(&[0, 10, 1, 11, 2, 12], 0, 1), [1, 10, 2, 12]);
+		assert_eq!(overwrite_key(&[1, 11, 2, 12], 0, 1), [1, 11, 2, 12]);
+	}
+}
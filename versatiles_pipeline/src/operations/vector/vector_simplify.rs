@@ -0,0 +1,244 @@
+use crate::{
+	OperationDocs, PipelineFactory,
+	operations::vector::traits::{RunnerTrait, build_transform},
+	traits::{OperationFactoryTrait, TransformOperationFactoryTrait},
+	vpl::VPLNode,
+};
+use anyhow::Result;
+use async_trait::async_trait;
+use std::{collections::BTreeMap, sync::Mutex};
+use versatiles_container::TileSource;
+use versatiles_core::{TileCoord, TileJSON};
+use versatiles_derive::context;
+use versatiles_geometry::vector_tile::VectorTile;
+
+#[derive(versatiles_derive::VPLDecode, versatiles_derive::VPLValidate, Clone, Debug)]
+/// Simplifies the geometries of vector tiles to make them smaller, e.g. `vector_simplify tolerance=4 level_max=8`.
+/// Lines and polygon rings are simplified with the Douglas-Peucker algorithm, features that collapse are removed.
+struct Args {
+	/// Maximum distance in tile units (of the resulting extent) a removed vertex may have from the simplified line.
+	/// `0` keeps all vertices.
+	#[vpl_default("2")]
+	#[vpl(min = 0)]
+	tolerance: Option<f32>,
+
+	/// Only simplify tiles on this zoom level and above.
+	level_min: Option<u8>,
+
+	/// Only simplify tiles on this zoom level and below.
+	level_max: Option<u8>,
+
+	/// If set, rescales all layers to this extent before simplifying, e.g. `extent=1024` for layers with the usual extent of 4096.
+	#[vpl(min = 1)]
+	extent: Option<u32>,
+
+	/// If set, only tiles whose estimated encoded size exceeds this number of bytes are simplified,
+	/// e.g. `max_tile_bytes=500000` for the usual recommendation of at most 500 KB per tile.
+	max_tile_bytes: Option<u32>,
+}
+
+/// Encoded sizes of all tiles of one zoom level, before and after simplification.
+#[derive(Debug, Default)]
+struct SizeStats {
+	tiles: u64,
+	before: u64,
+	after: u64,
+}
+
+#[derive(Debug)]
+struct Runner {
+	args: Args,
+	/// Sizes per zoom level, logged when the operation is dropped.
+	sizes: Mutex<BTreeMap<u8, SizeStats>>,
+}
+
+impl Runner {
+	pub fn from_args(args: Args) -> Self {
+		Self {
+			args,
+			sizes: Mutex::default(),
+		}
+	}
+
+	fn is_level_included(&self, level: u8) -> bool {
+		self.args.level_min.is_none_or(|min| level >= min) && self.args.level_max.is_none_or(|max| level <= max)
+	}
+}
+
+impl Drop for Runner {
+	fn drop(&mut self) {
+		for (level, stats) in self.sizes.get_mut().unwrap().iter() {
+			log::info!(
+				"vector_simplify: level {level}: {} tiles, {} bytes before, {} bytes after simplification",
+				stats.tiles,
+				stats.before,
+				stats.after
+			);
+		}
+	}
+}
+
+impl RunnerTrait for Runner {
+	fn update_tilejson(&self, _tilejson: &mut TileJSON) {}
+
+	#[context("Failed to run vector simplify")]
+	fn run(&self, mut tile: VectorTile) -> Result<Option<VectorTile>> {
+		if let Some(max_tile_bytes) = self.args.max_tile_bytes
+			&& tile.tile_size_bytes() <= max_tile_bytes as usize
+		{
+			return Ok(Some(tile));
+		}
+
+		tile.simplify_geometries(f64::from(self.args.tolerance.unwrap_or(2.0)), self.args.extent);
+
+		tile.remove_empty_layers(None);
+
+		Ok(Some(tile))
+	}
+
+	fn run_at(&self, coord: &TileCoord, tile: VectorTile) -> Result<Option<VectorTile>> {
+		if !self.is_level_included(coord.level) {
+			return Ok(Some(tile));
+		}
+
+		let before = tile.to_blob()?.len();
+		let tile = self.run(tile)?;
+		let after = match &tile {
+			Some(tile) => tile.to_blob()?.len(),
+			None => 0,
+		};
+
+		let mut sizes = self.sizes.lock().unwrap();
+		let stats = sizes.entry(coord.level).or_default();
+		stats.tiles += 1;
+		stats.before += before;
+		stats.after += after;
+
+		Ok(tile)
+	}
+}
+
+pub struct Factory {}
+
+impl OperationFactoryTrait for Factory {
+	fn get_docs(&self) -> OperationDocs {
+		Args::get_docs()
+	}
+	fn get_tag_name(&self) -> &str {
+		"vector_simplify"
+	}
+}
+
+#[async_trait]
+impl TransformOperationFactoryTrait for Factory {
+	async fn build<'a>(
+		&self,
+		vpl_node: VPLNode,
+		source: Box<dyn TileSource>,
+		_factory: &'a PipelineFactory,
+	) -> Result<Box<dyn TileSource>> {
+		let args = Args::from_vpl_node(&vpl_node)?;
+
+		build_transform::<Runner>(source, Runner::from_args(args)).await
+	}
+}
+
+// ───────────────────────── TESTS ─────────────────────────
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pretty_assertions::assert_eq;
+	use versatiles_geometry::{
+		geo::{GeoFeature, Geometry},
+		vector_tile::VectorTileLayer,
+	};
+
+	/// A line across the tile with 401 points that zigzag by 2 units.
+	fn create_dense_tile() -> VectorTile {
+		let line = (0..=400)
+			.map(|i| [f64::from(i) * 10.0, 2000.0 + f64::from(i % 2) * 2.0])
+			.collect::<Vec<_>>();
+		let feature = GeoFeature::new(Geometry::new_line_string(line));
+		let layer = VectorTileLayer::from_features(String::from("roads"), vec![feature], 4096, 1).unwrap();
+		VectorTile::new(vec![layer])
+	}
+
+	fn point_count(tile: &VectorTile) -> usize {
+		match tile.layers[0].features[0].to_geometry().unwrap() {
+			Geometry::MultiLineString(g) => g.0.iter().map(|line| line.0.len()).sum(),
+			geometry => panic!("unexpected geometry {geometry:?}"),
+		}
+	}
+
+	fn runner(vpl: &str) -> Runner {
+		let vpl_node = VPLNode::try_from_str(&format!("vector_simplify {vpl}")).unwrap();
+		Runner::from_args(Args::from_vpl_node(&vpl_node).unwrap())
+	}
+
+	#[test]
+	fn test_dense_line_loses_points() -> Result<()> {
+		let coord = TileCoord::new(5, 1, 1)?;
+		let runner = runner("tolerance=8");
+		let tile = runner.run_at(&coord, create_dense_tile())?.unwrap();
+		assert_eq!(point_count(&create_dense_tile()), 401);
+		assert_eq!(point_count(&tile), 2);
+
+		let sizes = runner.sizes.lock().unwrap();
+		let stats = &sizes[&5];
+		assert_eq!(stats.tiles, 1);
+		assert!(stats.after < stats.before);
+		Ok(())
+	}
+
+	#[test]
+	fn test_zero_tolerance_keeps_tile() -> Result<()> {
+		let coord = TileCoord::new(5, 1, 1)?;
+		let tile = runner("tolerance=0").run_at(&coord, create_dense_tile())?.unwrap();
+		assert_eq!(tile, create_dense_tile());
+		Ok(())
+	}
+
+	#[test]
+	fn test_levels_outside_range_are_untouched() -> Result<()> {
+		let runner = runner("tolerance=8 level_min=5 level_max=8");
+		for (level, expected) in [(4, 401), (5, 2), (8, 2), (9, 401)] {
+			let tile = runner
+				.run_at(&TileCoord::new(level, 0, 0)?, create_dense_tile())?
+				.unwrap();
+			assert_eq!(point_count(&tile), expected, "level {level}");
+		}
+		assert_eq!(runner.sizes.lock().unwrap().keys().collect::<Vec<_>>(), [&5, &8]);
+		Ok(())
+	}
+
+	#[test]
+	fn test_extent_is_reduced() -> Result<()> {
+		let tile = runner("tolerance=0 extent=1024").run(create_dense_tile())?.unwrap();
+		assert_eq!(tile.layers[0].extent, 1024);
+		// without a tolerance, rescaling alone keeps every vertex of the line
+		assert_eq!(point_count(&tile), 401);
+		Ok(())
+	}
+
+	#[test]
+	fn test_small_tiles_are_untouched() -> Result<()> {
+		let size = create_dense_tile().tile_size_bytes();
+		let tile = runner(&format!("tolerance=8 max_tile_bytes={size}"))
+			.run(create_dense_tile())?
+			.unwrap();
+		assert_eq!(point_count(&tile), 401);
+
+		let tile = runner(&format!("tolerance=8 max_tile_bytes={}", size - 1))
+			.run(create_dense_tile())?
+			.unwrap();
+		assert_eq!(point_count(&tile), 2);
+		Ok(())
+	}
+
+	#[test]
+	fn test_invalid_tolerance() {
+		let vpl_node = VPLNode::try_from_str("vector_simplify tolerance=-1").unwrap();
+		let err = format!("{:?}", Args::from_vpl_node(&vpl_node).unwrap_err());
+		assert!(err.contains("'tolerance' must be at least 0, but is -1"), "{err}");
+	}
+}
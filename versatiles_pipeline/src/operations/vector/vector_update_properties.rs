@@ -1,29 +1,41 @@
 use crate::{
-	PipelineFactory,
-	helpers::CsvReader,
+	OperationDocs, PipelineFactory,
+	helpers::{DataSourceOptions, normalize_key, read_data_source},
 	operations::vector::traits::{RunnerTrait, build_transform},
 	traits::{OperationFactoryTrait, TransformOperationFactoryTrait},
 	vpl::VPLNode,
 };
 use anyhow::{Context, Result, anyhow};
 use async_trait::async_trait;
-use std::collections::{HashMap, HashSet};
+use std::{
+	cell::{Cell, OnceCell},
+	collections::{BTreeMap, HashMap, HashSet},
+	sync::Mutex,
+};
 use versatiles_container::TileSource;
 use versatiles_core::TileJSON;
 use versatiles_derive::context;
-use versatiles_geometry::{geo::GeoProperties, vector_tile::VectorTile};
+use versatiles_geometry::{
+	geo::{GeoProperties, GeoValue, UpdateMode},
+	vector_tile::{GeometrySanitizeMode, SanitizeStats, VectorTile},
+};
 
 /// Arguments for the `vector_update_properties` operation.
 ///
-/// This operation joins vector tile features with external tabular data (CSV/TSV)
+/// This operation joins vector tile features with external tabular data (CSV/TSV, JSON, NDJSON or Parquet)
 /// based on matching ID fields, allowing you to enrich or update feature properties.
 #[derive(versatiles_derive::VPLDecode, Clone, Debug)]
 struct Args {
-	/// Path to the CSV/TSV data file:
-	/// The file must have a header row. Each subsequent row will be matched
-	/// to vector features using the ID fields.
+	/// Path to the data file:
+	/// A CSV/TSV file with a header row, a JSON array of objects, an NDJSON file with one object per line
+	/// or a Parquet file (only if built with the `parquet` feature).
+	/// Each row or object will be matched to vector features using the ID fields.
 	data_source_path: String,
 
+	/// Format of the data file: `csv`, `tsv`, `json`, `ndjson` or `parquet`.
+	/// Defaults to the format matching the file extension, or `csv` for unknown extensions.
+	data_format: Option<String>,
+
 	/// Name of the vector layer to update:
 	/// Only features in this layer will be modified. Other layers pass through unchanged.
 	layer_name: String,
@@ -37,15 +49,18 @@ struct Args {
 	id_field_data: String,
 
 	/// If `true`, replaces all existing properties with the data source values.
-	/// If `false` (default), merges new properties with existing ones.
+	/// If `false`, merges new properties with existing ones.
+	#[vpl_default("false")]
 	replace_properties: Option<bool>,
 
 	/// If `true`, removes features that don't have a matching row in the data source.
-	/// If `false` (default), non-matching features are kept unchanged.
+	/// If `false`, non-matching features are kept unchanged.
+	#[vpl_default("false")]
 	remove_non_matching: Option<bool>,
 
 	/// If `true`, includes the ID field from the data source in the output properties.
-	/// If `false` (default), the ID field is excluded from the merged properties.
+	/// If `false`, the ID field is excluded from the merged properties.
+	#[vpl_default("false")]
 	include_id: Option<bool>,
 
 	/// Field separator character for the data file:
@@ -54,9 +69,80 @@ struct Args {
 	field_separator: Option<String>,
 
 	/// Decimal separator character for parsing numbers:
-	/// Default is `.` (US/UK format).
-	/// Use `,` (comma) e.g. for German/European number format like `1.234,56`
+	/// `.` for US/UK format, or `,` (comma) e.g. for German/European number format like `1.234,56`
+	#[vpl_default(".")]
 	decimal_separator: Option<String>,
+
+	/// Quote character for fields that contain separators or line breaks, e.g. `'`.
+	/// A quote character inside a quoted field is escaped by doubling it.
+	#[vpl_default("\"")]
+	quote_char: Option<String>,
+
+	/// If `true`, values of the data file are stored as numbers or booleans where possible.
+	/// If `false`, all values are kept as strings, e.g. to preserve leading zeros like `01067`.
+	/// Style expressions treat `"5"` and `5` differently.
+	#[vpl_default("true")]
+	infer_types: Option<bool>,
+
+	/// If `true`, checks the geometries of the updated layer: duplicate points are removed,
+	/// unclosed rings are closed and features without a valid geometry are dropped.
+	/// A summary is logged at the end.
+	#[vpl_default("false")]
+	sanitize_geometries: Option<bool>,
+
+	/// If `true`, trims whitespace and lowercases the column names of the data file and `id_field_data`,
+	/// so a header like ` City_ID ` matches `id_field_data=city_id`.
+	#[vpl_default("false")]
+	normalize_keys: Option<bool>,
+
+	/// If `true`, trims whitespace around the ID values of both the data file and the tiles before matching.
+	#[vpl_default("false")]
+	trim_values: Option<bool>,
+
+	/// If `true`, lowercases the ID values of both the data file and the tiles before matching,
+	/// so a tile id `AB-12` matches `ab-12` in the data file.
+	#[vpl_default("false")]
+	ignore_case: Option<bool>,
+
+	/// If `true`, declares the new fields in the `vector_layers` entry of the layer in the TileJSON,
+	/// with a type (`String`, `Number`, `Boolean` or `Mixed`) inferred from the data file.
+	/// The entry is created if it doesn't exist. Already declared fields are kept.
+	#[vpl_default("false")]
+	update_meta: Option<bool>,
+
+	/// How joined values are merged with existing properties of the same name:
+	/// `overwrite` replaces them, `fill_missing` only adds properties that don't exist yet,
+	/// and `"prefix=<str>"` adds all joined properties with this prefix, e.g. `"prefix=census_"`.
+	/// Ignored if `replace_properties` is `true`.
+	#[vpl_default("overwrite")]
+	update_mode: Option<String>,
+}
+
+impl Args {
+	/// Applies `trim_values` and `ignore_case` to an ID value of the data file or a tile.
+	fn normalize_id(&self, id: String) -> String {
+		let id = if self.trim_values.unwrap_or(false) {
+			id.trim().to_string()
+		} else {
+			id
+		};
+		if self.ignore_case.unwrap_or(false) {
+			id.to_lowercase()
+		} else {
+			id
+		}
+	}
+}
+
+/// Number of tiles after which a join without any match is reported.
+const ZERO_MATCH_TILES: u64 = 16;
+
+/// Counts tiles and matches to detect joins that silently match nothing.
+#[derive(Debug, Default)]
+struct MatchStats {
+	tiles: u64,
+	matches: u64,
+	sample_tile_id: Option<String>,
 }
 
 #[derive(Debug)]
@@ -66,11 +152,25 @@ struct Runner {
 	/// Lookup table keyed by **feature‑ID** (`id_field_data`) holding the
 	/// new attribute sets parsed from the CSV.
 	properties_map: HashMap<String, GeoProperties>,
+	/// Geometry sanitizing statistics, summed up over all processed tiles.
+	sanitize_stats: Mutex<SanitizeStats>,
+	/// Match statistics of the first tiles, used to warn about a join without matches.
+	match_stats: Mutex<MatchStats>,
+	/// How joined properties are merged into existing ones.
+	update_mode: UpdateMode,
 }
 
 impl Runner {
 	#[context("Failed to build vector update properties runner")]
-	pub fn from_args(args: Args, data: Vec<GeoProperties>) -> Result<Self> {
+	pub fn from_args(mut args: Args, data: Vec<GeoProperties>) -> Result<Self> {
+		if args.normalize_keys.unwrap_or(false) {
+			args.id_field_data = normalize_key(&args.id_field_data);
+		}
+		let update_mode = match &args.update_mode {
+			Some(mode) => mode.parse::<UpdateMode>()?,
+			None => UpdateMode::default(),
+		};
+
 		// Convert each CSV row into a GeoProperties map.
 		// Transform Vec<GeoProperties> into HashMap keyed by the data‑ID column.
 		let properties_map = data
@@ -86,6 +186,7 @@ impl Runner {
 						)
 					})?
 					.to_string();
+				let key = args.normalize_id(key);
 				if !args.include_id.unwrap_or(false) {
 					properties.remove(&args.id_field_data)
 				}
@@ -94,13 +195,99 @@ impl Runner {
 			.collect::<Result<HashMap<String, GeoProperties>>>()
 			.context("Failed to build properties map from CSV data")?;
 
-		Ok(Self { args, properties_map })
+		Ok(Self {
+			args,
+			properties_map,
+			sanitize_stats: Mutex::new(SanitizeStats::default()),
+			match_stats: Mutex::new(MatchStats::default()),
+			update_mode,
+		})
+	}
+
+	/// Returns the ID used to look up a feature in the data file.
+	fn lookup_key(&self, id: &GeoValue) -> String {
+		self.args.normalize_id(id.to_string())
+	}
+
+	/// Returns the TileJSON type of every field in the data file, see [`field_type`].
+	fn field_types(&self) -> BTreeMap<String, &'static str> {
+		let mut types = BTreeMap::<String, &'static str>::new();
+		for (key, value) in self.properties_map.values().flat_map(GeoProperties::iter) {
+			let Some(value_type) = field_type(value) else {
+				continue;
+			};
+			types
+				.entry(key.clone())
+				.and_modify(|t| {
+					if *t != value_type {
+						*t = "Mixed";
+					}
+				})
+				.or_insert(value_type);
+		}
+		types
+	}
+
+	/// Adds the results of one tile to the match statistics. Returns a warning message exactly once,
+	/// when the first [`ZERO_MATCH_TILES`] tiles with IDs did not match any row of the data file.
+	fn count_matches(&self, matches: u64, sample_tile_id: Option<String>) -> Option<String> {
+		let mut stats = self.match_stats.lock().unwrap();
+		if stats.sample_tile_id.is_none() {
+			stats.sample_tile_id = sample_tile_id;
+		}
+		let Some(sample_tile_id) = stats.sample_tile_id.clone() else {
+			// Tiles without IDs say nothing about the join.
+			return None;
+		};
+		stats.tiles += 1;
+		stats.matches += matches;
+		if stats.tiles != ZERO_MATCH_TILES || stats.matches > 0 {
+			return None;
+		}
+		let sample_data_key = self.properties_map.keys().min().map_or("<no rows>", String::as_str);
+		Some(format!(
+			"vector_update_properties: none of the features in the first {ZERO_MATCH_TILES} tiles matched a row of the data file. \
+			Sample tile id: {sample_tile_id:?}, sample data key: {sample_data_key:?}. \
+			Maybe the keys or values differ in case or whitespace; try `normalize_keys=true`, `trim_values=true` and/or `ignore_case=true`."
+		))
+	}
+}
+
+/// Returns the type of a field as declared in TileJSON `vector_layers`, or `None` for null values.
+fn field_type(value: &GeoValue) -> Option<&'static str> {
+	match value {
+		GeoValue::Bool(_) => Some("Boolean"),
+		GeoValue::Double(_) | GeoValue::Float(_) | GeoValue::Int(_) | GeoValue::UInt(_) => Some("Number"),
+		GeoValue::String(_) => Some("String"),
+		GeoValue::Null => None,
+	}
+}
+
+impl Drop for Runner {
+	fn drop(&mut self) {
+		let stats = self.sanitize_stats.get_mut().unwrap();
+		if stats.checked > 0 {
+			log::info!("sanitized geometries in layer \"{}\": {stats}", self.args.layer_name);
+		}
 	}
 }
 
 impl RunnerTrait for Runner {
 	fn update_tilejson(&self, tilejson: &mut TileJSON) {
-		if let Some(layer) = tilejson.vector_layers.0.get_mut(&self.args.layer_name) {
+		if self.args.update_meta.unwrap_or(false) {
+			let layer = tilejson
+				.vector_layers
+				.0
+				.entry(self.args.layer_name.clone())
+				.or_default();
+			if self.args.replace_properties.unwrap_or(false) {
+				layer.fields.clear();
+			}
+			for (key, field_type) in self.field_types() {
+				let key = self.update_mode.target_key(&key);
+				layer.fields.entry(key).or_insert_with(|| field_type.to_string());
+			}
+		} else if let Some(layer) = tilejson.vector_layers.0.get_mut(&self.args.layer_name) {
 			if self.args.replace_properties.unwrap_or(false) {
 				layer.fields.clear();
 			}
@@ -112,7 +299,13 @@ impl RunnerTrait for Runner {
 				}
 			}
 			for key in all_keys.into_iter() {
-				layer.fields.insert(key, "automatically added field".to_string());
+				let key = self.update_mode.target_key(&key);
+				let description = "automatically added field".to_string();
+				if self.update_mode == UpdateMode::FillMissing {
+					layer.fields.entry(key).or_insert(description);
+				} else {
+					layer.fields.insert(key, description);
+				}
 			}
 		}
 	}
@@ -123,23 +316,25 @@ impl RunnerTrait for Runner {
 
 		// Iterate over all layers in the tile and *only* touch the requested one.
 		// Other layers pass through unchanged.
-		let layer = tile.find_layer_mut(layer_name);
-		if layer.is_none() {
+		let Some(layer) = tile.find_layer_mut(layer_name) else {
 			return Ok(Some(tile));
-		}
+		};
 
-		layer.unwrap().filter_map_properties(|mut prop| {
+		let matches = Cell::new(0);
+		let sample_tile_id = OnceCell::new();
+		layer.filter_map_properties(|mut prop| {
 			// For every feature grab its identifier; if absent, log a warning
 			// and keep the feature unchanged.
 			if let Some(id) = prop.get(&self.args.id_field_tiles) {
 				// Look up the ID in our CSV‑derived map.  When found, merge or replace
 				// the properties according to the flags.
-
-				if let Some(new_prop) = self.properties_map.get(&id.to_string()) {
+				let key = self.lookup_key(id);
+				if let Some(new_prop) = self.properties_map.get(&key) {
+					matches.set(matches.get() + 1);
 					if self.args.replace_properties.unwrap_or(false) {
 						prop = new_prop.clone();
 					} else {
-						prop.update(new_prop);
+						prop.update_with_mode(new_prop, &self.update_mode);
 					}
 				} else {
 					// Optionally drop features that failed the lookup.
@@ -148,12 +343,24 @@ impl RunnerTrait for Runner {
 					}
 					log::warn!("id \"{id}\" not found in data source");
 				}
+				let _ = sample_tile_id.set(key);
 			} else {
 				log::warn!("id field \"{}\" not found", &self.args.id_field_tiles);
 			}
 			Some(prop)
 		})?;
 
+		if let Some(warning) = self.count_matches(matches.get(), sample_tile_id.into_inner()) {
+			log::warn!("{warning}");
+		}
+
+		if self.args.sanitize_geometries.unwrap_or(false) {
+			let stats = layer.sanitize_geometries(GeometrySanitizeMode::Repair);
+			self.sanitize_stats.lock().unwrap().add(&stats);
+		}
+
+		tile.remove_empty_layers(Some(layer_name));
+
 		Ok(Some(tile))
 	}
 }
@@ -161,7 +368,7 @@ impl RunnerTrait for Runner {
 pub struct Factory {}
 
 impl OperationFactoryTrait for Factory {
-	fn get_docs(&self) -> String {
+	fn get_docs(&self) -> OperationDocs {
 		Args::get_docs()
 	}
 	fn get_tag_name(&self) -> &str {
@@ -179,21 +386,29 @@ impl TransformOperationFactoryTrait for Factory {
 	) -> Result<Box<dyn TileSource>> {
 		let args = Args::from_vpl_node(&vpl_node)?;
 
-		let mut csv_reader = CsvReader::new(&factory.resolve_path(&args.data_source_path), factory.runtime());
-		if let Some(ref sep) = args.field_separator {
-			let sep_char = parse_separator_char(sep).with_context(|| format!("Invalid field_separator: '{sep}'"))?;
-			csv_reader = csv_reader.with_field_separator(sep_char);
-		}
-		if let Some(ref sep) = args.decimal_separator {
-			let sep_char = parse_separator_char(sep).with_context(|| format!("Invalid decimal_separator: '{sep}'"))?;
-			csv_reader = csv_reader.with_decimal_separator(sep_char);
-		}
+		let parse_char = |name: &str, value: &Option<String>| -> Result<Option<char>> {
+			value
+				.as_deref()
+				.map(|v| parse_separator_char(v).with_context(|| format!("Invalid {name}: '{v}'")))
+				.transpose()
+		};
+		let options = DataSourceOptions {
+			field_separator: parse_char("field_separator", &args.field_separator)?,
+			decimal_separator: parse_char("decimal_separator", &args.decimal_separator)?,
+			quote_char: parse_char("quote_char", &args.quote_char)?,
+			infer_types: args.infer_types.unwrap_or(true),
+			normalize_keys: args.normalize_keys.unwrap_or(false),
+		};
 
-		// Load the CSV file referenced in the VPL.
-		let data = csv_reader
-			.read()
-			.await
-			.with_context(|| format!("Failed to read CSV file from '{}'", args.data_source_path))?;
+		// Load the data file referenced in the VPL.
+		let data = read_data_source(
+			&factory.resolve_path(&args.data_source_path),
+			args.data_format.as_deref(),
+			options,
+			factory.runtime(),
+		)
+		.await
+		.with_context(|| format!("Failed to read data file from '{}'", args.data_source_path))?;
 
 		build_transform::<Runner>(source, Runner::from_args(args, data)?).await
 	}
@@ -244,6 +459,7 @@ mod tests {
 		let runner = Runner {
 			args: Args {
 				data_source_path: "data.csv".to_string(),
+				data_format: None,
 				id_field_tiles: "id".to_string(),
 				id_field_data: "id".to_string(),
 				layer_name: "test_layer".to_string(),
@@ -252,8 +468,19 @@ mod tests {
 				include_id: None,
 				field_separator: None,
 				decimal_separator: None,
+				quote_char: None,
+				infer_types: None,
+				sanitize_geometries: None,
+				normalize_keys: None,
+				trim_values: None,
+				ignore_case: None,
+				update_meta: None,
+				update_mode: None,
 			},
 			properties_map,
+			sanitize_stats: Mutex::default(),
+			match_stats: Mutex::default(),
+			update_mode: UpdateMode::Overwrite,
 		};
 
 		let tile0 = create_sample_vector_tile();
@@ -264,6 +491,84 @@ mod tests {
 		assert_eq!(properties.get("property2").unwrap(), &GeoValue::from("new_value"));
 	}
 
+	#[test]
+	fn test_runner_sanitize_geometries() -> Result<()> {
+		let square = vec![[0.0, 0.0], [4.0, 0.0], [4.0, 4.0], [0.0, 4.0], [0.0, 0.0]];
+		let features = vec![
+			GeoFeature::new(Geometry::new_line_string(vec![[0.0, 0.0], [0.0, 0.0], [5.0, 5.0]])),
+			GeoFeature::new(Geometry::new_line_string(vec![[2.0, 2.0], [2.0, 2.0]])),
+			GeoFeature::new(Geometry::new_polygon(vec![square.clone()])),
+		];
+		let layer = VectorTileLayer::from_features(String::from("test_layer"), features, 4096, 1)?;
+
+		let vpl_node = VPLNode::try_from_str(
+			r##"vector_update_properties data_source_path="data.csv" id_field_tiles=id id_field_data=id layer_name=test_layer sanitize_geometries=true"##,
+		)?;
+		let runner = Runner::from_args(Args::from_vpl_node(&vpl_node)?, vec![])?;
+
+		let tile = runner.run(VectorTile::new(vec![layer]))?.unwrap();
+		let geometries = tile.layers[0]
+			.features
+			.iter()
+			.map(|f| f.to_geometry())
+			.collect::<Result<Vec<_>>>()?;
+		assert_eq!(
+			geometries,
+			[
+				Geometry::new_multi_line_string(vec![vec![[0.0, 0.0], [5.0, 5.0]]]),
+				Geometry::new_multi_polygon(vec![vec![square]]),
+			]
+		);
+		assert_eq!(
+			runner.sanitize_stats.lock().unwrap().to_string(),
+			"3 features checked, 2 invalid, 1 fixed, 1 dropped"
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn test_runner_mixed_case_ids() -> Result<()> {
+		let data = vec![GeoProperties::from(vec![
+			("id", GeoValue::from(" Feature_1 ")),
+			("property2", GeoValue::from("new_value")),
+		])];
+		let run = |options: &str| -> Result<Option<GeoValue>> {
+			let vpl_node = VPLNode::try_from_str(&format!(
+				r##"vector_update_properties data_source_path="data.csv" id_field_tiles=id id_field_data=id layer_name=test_layer {options}"##
+			))?;
+			let runner = Runner::from_args(Args::from_vpl_node(&vpl_node)?, data.clone())?;
+			let tile = runner.run(create_sample_vector_tile())?.unwrap();
+			let properties = tile.layers[0].features[0].decode_properties(&tile.layers[0])?;
+			Ok(properties.get("property2").cloned())
+		};
+
+		assert_eq!(run("trim_values=true")?, None);
+		assert_eq!(run("ignore_case=true")?, None);
+		assert_eq!(
+			run("trim_values=true ignore_case=true")?,
+			Some(GeoValue::from("new_value"))
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn test_runner_removes_empty_layer() -> Result<()> {
+		let vpl_node = VPLNode::try_from_str(
+			r##"vector_update_properties data_source_path="data.csv" id_field_tiles=id id_field_data=id layer_name=test_layer remove_non_matching=true"##,
+		)?;
+		let runner = Runner::from_args(Args::from_vpl_node(&vpl_node)?, vec![])?;
+
+		let mut tile = create_sample_vector_tile();
+		tile.layers.push(VectorTileLayer::new_standard("other_layer"));
+		assert_eq!(tile.layers[0].feature_count(), 1);
+
+		let tile = runner.run(tile)?.unwrap();
+		assert_eq!(tile.layers.len(), 1);
+		assert_eq!(tile.layers[0].name, "other_layer");
+		assert!(tile.is_empty());
+		Ok(())
+	}
+
 	#[test]
 	fn test_args_from_vpl_node() {
 		let vpl_node = VPLNode::try_from_str(
@@ -282,10 +587,22 @@ mod tests {
 	}
 
 	async fn run_test(replace_properties: bool, include_id: bool) -> Result<(String, String)> {
+		run_test_with_csv(
+			"data_id,value\n1,test",
+			&[
+				"id_field_data=data_id",
+				&format!("replace_properties={replace_properties}"),
+				&format!("include_id={include_id}"),
+			],
+		)
+		.await
+	}
+
+	async fn run_test_with_csv(csv: &str, args: &[&str]) -> Result<(String, String)> {
 		// ── prepare tiny CSV on disk ────────────────────────────────
 		let temp_file = NamedTempFile::new("test.csv")?;
 		let mut file = File::create(&temp_file)?;
-		writeln!(&mut file, "data_id,value\n1,test")?;
+		writeln!(&mut file, "{csv}")?;
 
 		// ── build pipeline ─────────────────────────────────────────
 		let factory = PipelineFactory::new_dummy();
@@ -299,10 +616,8 @@ mod tests {
 						temp_file.to_str().unwrap().replace('\\', "\\\\")
 					),
 					"id_field_tiles=index",
-					"id_field_data=data_id",
 					"layer_name=debug_y",
-					&format!("replace_properties={replace_properties}"),
-					&format!("include_id={include_id}"),
+					&args.join(" "),
 				]
 				.join(" "),
 			)
@@ -312,7 +627,7 @@ mod tests {
 		let mut stream = operation
 			.get_tile_stream(TileCoord::new(10, 1000, 100)?.to_tile_bbox())
 			.await?;
-		let tile = stream.next().await.unwrap().1.into_vector()?;
+		let tile = stream.next().await?.unwrap().1.into_vector()?;
 		let layer = tile.find_layer("debug_y").unwrap();
 
 		// ── stringify for easy substring assertions ────────────────
@@ -388,4 +703,196 @@ mod tests {
 			["data_id: automatically added field", "value: automatically added field",]
 		);
 	}
+
+	#[tokio::test]
+	async fn test_run_normalized_keys_and_values() -> Result<()> {
+		let csv = "\u{feff} Data_ID ,Value \n 1 ,test";
+
+		// Without normalization the padded header is not found.
+		let err = run_test_with_csv(csv, &["id_field_data=Data_ID"]).await.unwrap_err();
+		assert!(
+			format!("{err:?}").contains("Key 'Data_ID' not found in CSV data"),
+			"{err:?}"
+		);
+
+		// Normalized keys find the column, but the padded id doesn't match.
+		let (props, _) = run_test_with_csv(csv, &["id_field_data=Data_ID", "normalize_keys=true"]).await?;
+		assert_eq!(
+			props,
+			"{\"char\": String(\":\"), \"index\": UInt(1), \"x\": Float(132.7017)}"
+		);
+
+		let (props, json) = run_test_with_csv(
+			csv,
+			&["id_field_data=Data_ID", "normalize_keys=true", "trim_values=true"],
+		)
+		.await?;
+		assert_eq!(
+			props,
+			"{\"char\": String(\":\"), \"index\": UInt(1), \"value\": String(\"test\"), \"x\": Float(132.7017)}"
+		);
+		assert!(json.contains("value: automatically added field"));
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_run_update_modes() -> Result<()> {
+		// `x` exists in the tiles and in the data file
+		async fn run(mode: &str) -> Result<(String, String)> {
+			run_test_with_csv("data_id,value,x\n1,test,9", &["id_field_data=data_id", mode]).await
+		}
+
+		let (props, json) = run("update_mode=overwrite").await?;
+		assert_eq!(
+			props,
+			"{\"char\": String(\":\"), \"index\": UInt(1), \"value\": String(\"test\"), \"x\": UInt(9)}"
+		);
+		assert!(json.contains("x: automatically added field"), "{json}");
+
+		let (props, json) = run("update_mode=fill_missing").await?;
+		assert_eq!(
+			props,
+			"{\"char\": String(\":\"), \"index\": UInt(1), \"value\": String(\"test\"), \"x\": Float(132.7017)}"
+		);
+		assert!(json.contains("x: position"), "{json}");
+
+		let (props, json) = run("update_mode=\"prefix=csv_\"").await?;
+		assert_eq!(
+			props,
+			"{\"char\": String(\":\"), \"csv_value\": String(\"test\"), \"csv_x\": UInt(9), \"index\": UInt(1), \"x\": Float(132.7017)}"
+		);
+		assert_eq!(
+			json.split('\n').collect::<Vec<_>>(),
+			[
+				"char: which character",
+				"csv_value: automatically added field",
+				"csv_x: automatically added field",
+				"index: index of char",
+				"x: position",
+			]
+		);
+
+		assert!(run("update_mode=merge").await.is_err());
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_run_quote_char_and_infer_types() -> Result<()> {
+		let csv = "data_id;value;count\n1;'a;b';05";
+		let args = ["id_field_data=data_id", "field_separator=\";\"", "quote_char=\"'\""];
+
+		let (props, _) = run_test_with_csv(csv, &args).await?;
+		assert_eq!(
+			props,
+			"{\"char\": String(\":\"), \"count\": UInt(5), \"index\": UInt(1), \"value\": String(\"a;b\"), \"x\": Float(132.7017)}"
+		);
+
+		let (props, _) = run_test_with_csv(csv, &[&args[..], &["infer_types=false"]].concat()).await?;
+		assert_eq!(
+			props,
+			"{\"char\": String(\":\"), \"count\": String(\"05\"), \"index\": UInt(1), \"value\": String(\"a;b\"), \"x\": Float(132.7017)}"
+		);
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_run_data_format_override() -> Result<()> {
+		let ndjson = "{\"data_id\":1,\"value\":\"test\",\"missing\":null}\n{\"data_id\":2,\"value\":\"other\"}";
+		let (props, _) = run_test_with_csv(ndjson, &["id_field_data=data_id", "data_format=ndjson"]).await?;
+		assert_eq!(
+			props,
+			"{\"char\": String(\":\"), \"index\": UInt(1), \"value\": String(\"test\"), \"x\": Float(132.7017)}"
+		);
+
+		let err = run_test_with_csv(ndjson, &["id_field_data=data_id", "data_format=xml"])
+			.await
+			.unwrap_err();
+		assert!(format!("{err:?}").contains("unknown data format 'xml'"), "{err:?}");
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_run_update_meta() -> Result<()> {
+		let csv = "data_id,value,count,flag,mixed\n1,test,5,true,7\n2,other,1.5,false,x";
+		let (_, json) = run_test_with_csv(csv, &["id_field_data=data_id", "update_meta=true"]).await?;
+		assert_eq!(
+			json.split('\n').collect::<Vec<_>>(),
+			[
+				"char: which character",
+				"count: Number",
+				"flag: Boolean",
+				"index: index of char",
+				"mixed: Mixed",
+				"value: String",
+				"x: position",
+			]
+		);
+
+		let (_, json) = run_test_with_csv(
+			csv,
+			&["id_field_data=data_id", "update_meta=true", "replace_properties=true"],
+		)
+		.await?;
+		assert_eq!(
+			json.split('\n').collect::<Vec<_>>(),
+			["count: Number", "flag: Boolean", "mixed: Mixed", "value: String"]
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn test_update_meta_adds_missing_layer() -> Result<()> {
+		let vpl_node = VPLNode::try_from_str(
+			r##"vector_update_properties data_source_path="data.csv" id_field_tiles=id id_field_data=id layer_name=test_layer update_meta=true"##,
+		)?;
+		let data = vec![GeoProperties::from(vec![
+			("id", GeoValue::from(1)),
+			("name", GeoValue::from("Berlin")),
+			("empty", GeoValue::Null),
+		])];
+		let runner = Runner::from_args(Args::from_vpl_node(&vpl_node)?, data)?;
+
+		let mut tilejson = TileJSON::default();
+		runner.update_tilejson(&mut tilejson);
+		let layer = tilejson.vector_layers.find("test_layer").unwrap();
+		assert_eq!(layer.fields.len(), 1);
+		assert_eq!(layer.fields.get("name").unwrap(), "String");
+		Ok(())
+	}
+
+	fn zero_match_runner() -> Result<Runner> {
+		let vpl_node = VPLNode::try_from_str(
+			r##"vector_update_properties data_source_path="data.csv" id_field_tiles=id id_field_data=id layer_name=test_layer"##,
+		)?;
+		let data = vec![GeoProperties::from(vec![("id", GeoValue::from("Feature_1"))])];
+		Runner::from_args(Args::from_vpl_node(&vpl_node)?, data)
+	}
+
+	#[test]
+	fn test_zero_match_warning() -> Result<()> {
+		let runner = zero_match_runner()?;
+
+		// Tiles without ids are not counted.
+		assert_eq!(runner.count_matches(0, None), None);
+		for _ in 1..ZERO_MATCH_TILES {
+			assert_eq!(runner.count_matches(0, Some(String::from("feature_1"))), None);
+		}
+		let warning = runner.count_matches(0, Some(String::from("feature_2"))).unwrap();
+		assert!(
+			warning.contains(r#"Sample tile id: "feature_1", sample data key: "Feature_1""#),
+			"{warning}"
+		);
+		assert!(warning.contains("normalize_keys=true"), "{warning}");
+
+		// The warning is emitted only once.
+		assert_eq!(runner.count_matches(0, Some(String::from("feature_1"))), None);
+
+		// A single match is enough to stay silent.
+		let runner = zero_match_runner()?;
+		runner.count_matches(1, Some(String::from("Feature_1")));
+		for _ in 1..ZERO_MATCH_TILES {
+			assert_eq!(runner.count_matches(0, Some(String::from("feature_1"))), None);
+		}
+		Ok(())
+	}
 }
@@ -1,11 +1,13 @@
-use crate::{PipelineFactory, vpl::VPLNode};
+use crate::{OperationDocs, PipelineFactory, vpl::VPLNode};
 use anyhow::Result;
 use async_trait::async_trait;
 use versatiles_container::TileSource;
 
+/// Common part of read and transform operation factories: the VPL tag name and the documentation.
 pub trait OperationFactoryTrait: Send + Sync {
 	fn get_tag_name(&self) -> &str;
-	fn get_docs(&self) -> String;
+	/// Returns the documentation of the operation, usually generated by `#[derive(VPLDecode)]`.
+	fn get_docs(&self) -> OperationDocs;
 }
 
 /// Factory trait for read operations that create tile sources from VPL nodes.
@@ -38,3 +40,10 @@ pub trait TransformOperationFactoryTrait: OperationFactoryTrait {
 		factory: &'a PipelineFactory,
 	) -> Result<Box<dyn TileSource>>;
 }
+
+/// A read or transform operation factory, as registered with
+/// [`PipelineFactory::register_override`].
+pub enum OperationFactory {
+	Read(Box<dyn ReadOperationFactoryTrait>),
+	Transform(Box<dyn TransformOperationFactoryTrait>),
+}
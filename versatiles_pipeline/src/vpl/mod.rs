@@ -1,7 +1,9 @@
 mod parser;
 mod vpl_node;
 mod vpl_pipeline;
+mod yaml;
 
 pub use parser::parse_vpl;
 pub use vpl_node::VPLNode;
 pub use vpl_pipeline::VPLPipeline;
+pub use yaml::parse_yaml;
@@ -130,6 +130,12 @@ impl VPLNode {
 		self.required(field, self.get_property_number_option::<T>(field))
 	}
 
+	/// Required string array parameter accessor, e.g. `key=["a","b"]`; a single value is returned as one-element list.
+	#[context("Failed to get required property string array '{field}' from VPL node '{}'", self.name)]
+	pub fn get_property_string_array_required(&self, field: &str) -> Result<Vec<String>> {
+		self.required(field, Ok(self.get_property_vec(field).cloned()))
+	}
+
 	/// Optional fixed-size numeric array accessor; enforces exactly `N` elements.
 	#[context("Failed to get optional property number array '{field}' from VPL node '{}'", self.name)]
 	pub fn get_property_number_array_option<T, const N: usize>(&self, field: &str) -> Result<Option<[T; N]>>
@@ -165,6 +171,58 @@ impl VPLNode {
 		self.required(field, self.get_property_number_array_option::<T, N>(field))
 	}
 
+	/// Errors if the node has a property that is not in `names`, e.g. to catch typos in hand-written `Args`.
+	pub fn ensure_known_properties(&self, names: &[&str]) -> Result<()> {
+		for property_name in self.properties.keys() {
+			ensure!(
+				names.contains(&property_name.as_str()),
+				"The '{}' operation does not support the argument '{property_name}'.\nOnly the following arguments are supported:\n'{}'",
+				self.name,
+				names.join("', '")
+			);
+		}
+		Ok(())
+	}
+
+	/// Shorthand for [`get_property_string_required`](Self::get_property_string_required).
+	pub fn get_required_string(&self, key: &str) -> Result<String> {
+		self.get_property_string_required(key)
+	}
+
+	/// Returns the string value of `key`, or `None` if it is absent or has more than one value.
+	pub fn get_optional_string(&self, key: &str) -> Option<String> {
+		self.ok_or_warn(key, self.get_property_string_option(key))
+	}
+
+	/// Returns the value of `key` as `f64`; errors if it is missing or not a number.
+	pub fn get_required_f64(&self, key: &str) -> Result<f64> {
+		self.get_property_number_required::<f64>(key)
+	}
+
+	/// Returns the value of `key` as `u64`, or `None` if it is absent or not an unsigned integer.
+	pub fn get_optional_u64(&self, key: &str) -> Option<u64> {
+		self.ok_or_warn(key, self.get_property_number_option::<u64>(key))
+	}
+
+	/// Returns the value of `key` as boolean (see [`get_property_bool_option`](Self::get_property_bool_option)),
+	/// or `default` if it is absent.
+	pub fn get_bool(&self, key: &str, default: bool) -> bool {
+		self
+			.ok_or_warn(key, self.get_property_bool_option(key))
+			.unwrap_or(default)
+	}
+
+	/// Internal helper for the infallible accessors: invalid values are logged and treated as absent.
+	fn ok_or_warn<T>(&self, key: &str, result: Result<Option<T>>) -> Option<T> {
+		result.unwrap_or_else(|err| {
+			log::warn!(
+				"In operation '{}' the parameter '{key}' is ignored, because it is invalid: {err:#}",
+				self.name
+			);
+			None
+		})
+	}
+
 	/// Internal helper: converts `Ok(Some(_))` to the value or produces a standard "required" error.
 	fn required<T>(&self, field: &str, result: Result<Option<T>>) -> Result<T> {
 		result?.ok_or_else(|| anyhow!("In operation '{}' the parameter '{}' is required.", self.name, field))
@@ -296,6 +354,18 @@ mod tests {
 		assert!(node.get_property_string_required("key2").is_err());
 	}
 
+	#[test]
+	fn test_vplnode_get_property_string_array_req() {
+		let node = VPLNode {
+			name: "node".to_string(),
+			properties: make_properties(vec![("key1", vec!["a=b", "c=d"]), ("key2", vec!["e"])]),
+			sources: vec![],
+		};
+		assert_eq!(node.get_property_string_array_required("key1").unwrap(), ["a=b", "c=d"]);
+		assert_eq!(node.get_property_string_array_required("key2").unwrap(), ["e"]);
+		assert!(node.get_property_string_array_required("key3").is_err());
+	}
+
 	#[test]
 	fn test_vplnode_get_property_bool_req() {
 		let node = VPLNode {
@@ -366,6 +436,78 @@ mod tests {
 		assert!(node.get_property_number_array_required::<i32, 4>("key2").is_err())
 	}
 
+	#[test]
+	fn test_vplnode_typed_accessors() {
+		let node = VPLNode {
+			name: "node".to_string(),
+			properties: make_properties(vec![
+				("text", vec!["value"]),
+				("list", vec!["a", "b"]),
+				("float", vec!["1.5"]),
+				("int", vec!["42"]),
+				("negative", vec!["-1"]),
+				("flag", vec!["yes"]),
+			]),
+			sources: vec![],
+		};
+
+		assert_eq!(node.get_required_string("text").unwrap(), "value");
+		assert_eq!(node.get_optional_string("text").as_deref(), Some("value"));
+		assert_eq!(node.get_optional_string("list"), None);
+		assert_eq!(node.get_optional_string("missing"), None);
+
+		assert_eq!(node.get_required_f64("float").unwrap(), 1.5);
+		assert_eq!(node.get_required_f64("int").unwrap(), 42.0);
+
+		assert_eq!(node.get_optional_u64("int"), Some(42));
+		assert_eq!(node.get_optional_u64("negative"), None);
+		assert_eq!(node.get_optional_u64("missing"), None);
+
+		assert!(node.get_bool("flag", false));
+		assert!(!node.get_bool("text", true));
+		assert!(node.get_bool("missing", true));
+		assert!(!node.get_bool("missing", false));
+	}
+
+	#[test]
+	fn test_vplnode_typed_accessor_errors() {
+		let node = VPLNode {
+			name: "node".to_string(),
+			properties: make_property(vec![("text", "value")]),
+			sources: vec![],
+		};
+
+		let err = format!("{:?}", node.get_required_string("missing").unwrap_err());
+		assert!(
+			err.contains("In operation 'node' the parameter 'missing' is required."),
+			"{err}"
+		);
+
+		let err = format!("{:?}", node.get_required_f64("missing").unwrap_err());
+		assert!(
+			err.contains("In operation 'node' the parameter 'missing' is required."),
+			"{err}"
+		);
+
+		let err = format!("{:?}", node.get_required_f64("text").unwrap_err());
+		assert!(err.contains("property number 'text' from VPL node 'node'"), "{err}");
+	}
+
+	#[test]
+	fn test_vplnode_ensure_known_properties() {
+		let node = VPLNode {
+			name: "node".to_string(),
+			properties: make_property(vec![("key1", "value1")]),
+			sources: vec![],
+		};
+		assert!(node.ensure_known_properties(&["key1", "key2"]).is_ok());
+		let err = node.ensure_known_properties(&["key2"]).unwrap_err().to_string();
+		assert_eq!(
+			err,
+			"The 'node' operation does not support the argument 'key1'.\nOnly the following arguments are supported:\n'key2'"
+		);
+	}
+
 	#[test]
 	fn test_vplnode_required() -> Result<()> {
 		let node = VPLNode {
@@ -0,0 +1,159 @@
+//! YAML notation of VPL pipelines.
+//!
+//! A YAML sequence is a pipeline and each mapping in it is a node with a `name`, optional
+//! `properties` and optional `sources` (a sequence of pipelines). Property values are scalars
+//! or sequences of scalars, like `key=value` and `key=[a,b]` in VPL.
+//!
+//! ```yaml
+//! - name: from_container
+//!   properties:
+//!     filename: berlin.mbtiles
+//! - name: vector_update_properties
+//!   properties:
+//!     data_source_path: cities.csv
+//!     layer_name: place_labels
+//! ```
+
+use super::{VPLNode, VPLPipeline};
+use anyhow::{Result, anyhow, bail};
+use serde_yaml_ng::Value;
+use std::collections::BTreeMap;
+use versatiles_derive::context;
+
+/// Parses a YAML document into a [`VPLPipeline`].
+#[context("Failed to parse YAML pipeline")]
+pub fn parse_yaml(yaml: &str) -> Result<VPLPipeline> {
+	let value: Value = serde_yaml_ng::from_str(yaml)?;
+	pipeline_from_value(&value)
+}
+
+fn pipeline_from_value(value: &Value) -> Result<VPLPipeline> {
+	let Value::Sequence(nodes) = value else {
+		bail!("a pipeline must be a sequence of nodes");
+	};
+	Ok(VPLPipeline::new(
+		nodes.iter().map(node_from_value).collect::<Result<_>>()?,
+	))
+}
+
+fn node_from_value(value: &Value) -> Result<VPLNode> {
+	let Value::Mapping(mapping) = value else {
+		bail!("a node must be a mapping with 'name', 'properties' and 'sources'");
+	};
+
+	let mut name = None;
+	let mut properties = BTreeMap::new();
+	let mut sources = Vec::new();
+	for (key, value) in mapping {
+		match key.as_str() {
+			Some("name") => name = Some(scalar_to_string(value)?),
+			Some("properties") => properties = properties_from_value(value)?,
+			Some("sources") => {
+				let Value::Sequence(list) = value else {
+					bail!("'sources' must be a sequence of pipelines");
+				};
+				sources = list.iter().map(pipeline_from_value).collect::<Result<_>>()?;
+			}
+			_ => bail!("unknown node key {key:?}, expected 'name', 'properties' or 'sources'"),
+		}
+	}
+
+	let name = name.ok_or_else(|| anyhow!("node is missing a 'name'"))?;
+	Ok(VPLNode {
+		name,
+		properties,
+		sources,
+	})
+}
+
+fn properties_from_value(value: &Value) -> Result<BTreeMap<String, Vec<String>>> {
+	let mapping = match value {
+		Value::Null => return Ok(BTreeMap::new()),
+		Value::Mapping(mapping) => mapping,
+		_ => bail!("'properties' must be a mapping"),
+	};
+
+	let mut properties = BTreeMap::new();
+	for (key, value) in mapping {
+		let key = scalar_to_string(key)?;
+		let values = match value {
+			Value::Sequence(list) => list.iter().map(scalar_to_string).collect::<Result<_>>()?,
+			_ => vec![scalar_to_string(value)?],
+		};
+		properties.insert(key, values);
+	}
+	Ok(properties)
+}
+
+fn scalar_to_string(value: &Value) -> Result<String> {
+	Ok(match value {
+		Value::String(s) => s.clone(),
+		Value::Number(n) => n.to_string(),
+		Value::Bool(b) => b.to_string(),
+		_ => bail!("expected a string, number or boolean, but got {value:?}"),
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::vpl::parse_vpl;
+	use pretty_assertions::assert_eq;
+
+	#[test]
+	fn berlin_equals_vpl() {
+		let yaml = r#"
+- name: from_container
+  properties:
+    filename: berlin.mbtiles
+- name: vector_update_properties
+  properties:
+    data_source_path: cities.csv
+    layer_name: place_labels
+    id_field_tiles: name
+    id_field_data: city_name
+"#;
+		assert_eq!(
+			parse_yaml(yaml).unwrap(),
+			parse_vpl(include_str!("../../../testdata/berlin.vpl")).unwrap()
+		);
+	}
+
+	#[test]
+	fn sources_and_lists() {
+		let yaml = r#"
+- name: overlay_tiles
+  sources:
+    - - name: from_container
+        properties: { filename: a.pmtiles }
+      - name: raster_format
+        properties: { quality: [70, 50], lossless: false }
+    - - name: from_container
+        properties: { filename: b.pmtiles }
+- name: filter
+"#;
+		let vpl = "overlay_tiles [ from_container filename=a.pmtiles | raster_format quality=[70,50] lossless=false, from_container filename=b.pmtiles ] | filter";
+		assert_eq!(parse_yaml(yaml).unwrap(), parse_vpl(vpl).unwrap());
+	}
+
+	#[test]
+	fn invalid_documents() {
+		let check = |yaml: &str, message: &str| {
+			let err = format!("{:?}", parse_yaml(yaml).unwrap_err());
+			assert!(err.contains(message), "{err}");
+		};
+		check("name: foo", "a pipeline must be a sequence of nodes");
+		check("- foo", "a node must be a mapping");
+		check("- properties: {}", "node is missing a 'name'");
+		check("- { name: foo, size: 3 }", "unknown node key");
+		check("- { name: foo, properties: [a] }", "'properties' must be a mapping");
+		check(
+			"- { name: foo, properties: { a: { b: c } } }",
+			"expected a string, number or boolean",
+		);
+		check(
+			"- { name: foo, sources: a }",
+			"'sources' must be a sequence of pipelines",
+		);
+	}
+}